@@ -4,8 +4,14 @@ import { Toaster } from "@/components/ui/sonner";
 import { Toolbar } from "@/components/toolbar";
 import { ColorView } from "@/components/color-view";
 import { KeyAssignmentView } from "@/components/key-assignment-view";
+import { NowPlaying } from "@/components/now-playing";
+import { OnboardingGuide } from "@/components/onboarding-guide";
 import { SettingsView } from "@/components/settings-view";
 import { SoundView } from "@/components/sound-view";
+import { RgbRestoreDialog } from "@/components/rgb-restore-dialog";
+import { KeymapRestoreDialog } from "@/components/keymap-restore-dialog";
+import { DeviceChoiceDialog } from "@/components/device-choice-dialog";
+import { LockBanner } from "@/components/lock";
 import { useDeck8 } from "@/hooks/use-deck8";
 import { Unplug, RefreshCw } from "lucide-react";
 import { cn } from "@/lib/utils";
@@ -16,35 +22,123 @@ const isTauri = "__TAURI_INTERNALS__" in window;
 
 export default function App() {
   const [overlayDismissed, setOverlayDismissed] = useState(false);
+  const [tab, setTab] = useState("keys");
   const {
     state,
     connecting,
     selectedKey,
     setSelectedKey,
     connect,
+    disconnect,
     updateKeyColor,
     updateKeycode,
+    captureKeycode,
     toggleKeyOverride,
+    updateKeySource,
     toggleKeySlot,
+    addKeySlot,
+    removeKeySlot,
+    renameKeySlot,
+    selectKeySlot,
+    updateKeyBinding,
+    updateKeyLightScene,
+    updateKeyLabel,
+    updateKeyIcon,
+    updateKeySoundCooldown,
+    updateKeySoundMode,
+    updateKeyPressMode,
+    updateKeySoundGain,
+    copyKeyConfig,
+    pasteKeyConfig,
+    applyColorToAll,
+    toggleDnd,
+    addSwatch,
+    removeSwatch,
+    saveTheme,
+    applyTheme,
+    deleteTheme,
+    themePreview,
+    previewTheme,
+    endThemePreview,
+    saveKeyGroup,
+    deleteKeyGroup,
+    saveMode,
+    deleteMode,
+    setMode,
+    saveRule,
+    deleteRule,
     saveCustom,
     restoreDefaults,
     bootloaderJump,
     eepromReset,
     dynamicKeymapReset,
     macroReset,
+    reloadDefinitions,
     updateRgb,
     updateRgbColor,
     saveRgb,
+    restoreRgb,
+    keepDeviceRgb,
+    restoreKeymaps,
+    keepDeviceKeymaps,
+    chooseAudioDevice,
+    updateBrightnessCurve,
+    updateAccessibilityCues,
+    updateNowPlaying,
+    updateDiscordSettings,
+    updateLighting,
+    pairHueBridge,
+    updateAccentKeyColors,
+    systemTheme,
+    updateLanguage,
+    updateWindowSettings,
+    updateHidTiming,
+    updateCooperative,
+    lockConfig,
+    unlockConfig,
+    updateFocusSettings,
+    updateGameSettings,
+    startFocus,
+    stopFocus,
+    skipOnboardingStep,
+    finishOnboarding,
+    resetOnboarding,
+    updateLocalApi,
+    announcement,
     audioDevices,
     refreshAudioDevices,
     selectAudioInput,
     selectAudioOutput,
+    selectSecondInput,
     updateSoundVolume,
     updateMicVolume,
+    updateInputGain,
+    updateSecondInputVolume,
+    updateProcessingStage,
+    updateProcessingOrder,
+    updateAgc,
+    updateStopWord,
+    toggleSoundboard,
+    stopAllSounds,
+    toggleSoundsMuted,
+    updateAppHotkey,
+    updateQuietHours,
+    overrideQuiet,
     addToLibrary,
     addToLibraryTrimmed,
+    updateSoundFormat,
+    updatePreviewRoute,
+    compressLibrary,
+    cleanLibrary,
+    relinkLibrarySound,
+    updateLibraryQuota,
     removeFromLibrary,
     renameSound,
+    toggleSoundMonitorOnly,
+    savePlaylist,
+    deletePlaylist,
+    skipPlaylist,
+    stopPlaylist,
     setKeySound,
     previewLibrarySound,
     getFileDuration,
@@ -66,23 +160,57 @@ export default function App() {
           )}
         />
 
+        {/* Slot changes read out by screen readers (accessibility cues) */}
+        <div aria-live="assertive" className="sr-only">
+          {announcement && <p key={announcement.id}>{announcement.text}</p>}
+        </div>
+
         {/* Main content with tabs — Toolbar is inside Tabs so TabsList works */}
-        <Tabs defaultValue="keys" className="flex flex-col flex-1 min-h-0 !gap-0">
+        <Tabs value={tab} onValueChange={setTab} className="flex flex-col flex-1 min-h-0 !gap-0">
           {/* Unified toolbar: brand + tabs + connection */}
           <Toolbar
             connected={state.connected}
             connecting={connecting}
+            dnd={state.dnd}
             onReconnect={connect}
+            onDisconnect={() => {
+              // A deliberate disconnect shouldn't pop the "not connected" overlay
+              setOverlayDismissed(true);
+              disconnect();
+            }}
+            onToggleDnd={toggleDnd}
+          />
+
+          <OnboardingGuide
+            step={state.settings.onboarding}
+            connected={state.connected}
+            onConnect={connect}
+            onOpenTab={setTab}
+            onSkip={skipOnboardingStep}
+            onFinish={finishOnboarding}
           />
 
+          {state.locked && <LockBanner onUnlock={unlockConfig} />}
+
           <TabsContent value="keys" className="flex flex-col flex-1 min-h-0 overflow-hidden animate-fade-in">
+            {state.settings.now_playing && <NowPlaying />}
             <KeyAssignmentView
               keys={state.keys}
               keymaps={state.keymaps}
               connected={state.connected}
               onKeycodeChange={updateKeycode}
+              onCaptureKeycode={captureKeycode}
+              onKeySourceChange={updateKeySource}
+              onKeyLabelChange={updateKeyLabel}
+              onKeyIconChange={updateKeyIcon}
+              onKeySoundCooldownChange={updateKeySoundCooldown}
+              onKeySoundModeChange={updateKeySoundMode}
+              onKeyPressModeChange={updateKeyPressMode}
+              onKeySoundGainChange={updateKeySoundGain}
               soundLibrary={state.audio_config.sound_library}
+              playlists={state.audio_config.playlists}
               keySounds={state.audio_config.key_sounds}
+              keySoundGains={state.audio_config.key_sound_gain_db}
               onSetKeySound={setKeySound}
               onPreviewLibrarySound={previewLibrarySound}
               onGetDuration={getFileDuration}
@@ -95,10 +223,44 @@ export default function App() {
           <TabsContent value="color" className="flex flex-col flex-1 min-h-0 overflow-hidden animate-fade-in">
             <ColorView
               keys={state.keys}
+              perKeySupported={state.device_info?.capabilities.per_key_override ?? true}
+              palette={state.palette}
+              themes={state.themes}
+              groups={state.groups}
+              modes={state.modes}
+              activeMode={state.active_mode}
+              rules={state.rules}
+              activeRules={state.active_rules}
               selectedKey={selectedKey}
               onSelectKey={(i) => setSelectedKey(i === -1 ? null : i)}
               onColorChange={updateKeyColor}
               onToggleOverride={toggleKeyOverride}
+              onAddSlot={addKeySlot}
+              onRemoveSlot={removeKeySlot}
+              onRenameSlot={renameKeySlot}
+              onSelectSlot={selectKeySlot}
+              onBindingChange={updateKeyBinding}
+              lightTargets={state.settings.lighting.targets}
+              onLightSceneChange={updateKeyLightScene}
+              onAddSwatch={addSwatch}
+              onRemoveSwatch={removeSwatch}
+              onSaveTheme={saveTheme}
+              onApplyTheme={applyTheme}
+              onDeleteTheme={deleteTheme}
+              themePreview={themePreview}
+              onPreviewTheme={previewTheme}
+              onEndThemePreview={endThemePreview}
+              onSaveGroup={saveKeyGroup}
+              onDeleteGroup={deleteKeyGroup}
+              onSaveMode={saveMode}
+              onSetMode={setMode}
+              onDeleteMode={deleteMode}
+              onSaveRule={saveRule}
+              onDeleteRule={deleteRule}
+              keyClipboard={state.key_clipboard}
+              onCopyConfig={copyKeyConfig}
+              onPasteConfig={pasteKeyConfig}
+              onApplyToAll={applyColorToAll}
               onToggleKeySlot={toggleKeySlot}
               onSaveCustom={saveCustom}
             />
@@ -110,15 +272,41 @@ export default function App() {
               audioDevices={audioDevices}
               onSelectInput={selectAudioInput}
               onSelectOutput={selectAudioOutput}
+              onSelectSecondInput={selectSecondInput}
+              onSecondInputVolumeChange={updateSecondInputVolume}
               onSoundVolumeChange={updateSoundVolume}
               onMicVolumeChange={updateMicVolume}
+              onInputGainChange={updateInputGain}
+              onProcessingStageChange={updateProcessingStage}
+              onProcessingOrderChange={updateProcessingOrder}
+              onAgcChange={updateAgc}
+              onStopWordChange={updateStopWord}
+              onSoundboardToggle={toggleSoundboard}
+              onStopAllSounds={stopAllSounds}
+              onSoundsMutedChange={toggleSoundsMuted}
+              quietHours={state.settings.quiet_hours}
+              quietActive={state.quiet_active}
+              quietOverrideUntil={state.quiet_override_until}
+              onQuietHoursChange={updateQuietHours}
+              onOverrideQuiet={overrideQuiet}
               onRefreshDevices={refreshAudioDevices}
               onGetDuration={getFileDuration}
               onPreviewTrim={previewTrimmedAudio}
               onAddToLibrary={addToLibrary}
               onAddToLibraryTrimmed={addToLibraryTrimmed}
               onRemoveFromLibrary={removeFromLibrary}
+              onSoundFormatChange={updateSoundFormat}
+              onPreviewRouteChange={updatePreviewRoute}
+              onCompressLibrary={compressLibrary}
+              onCleanLibrary={cleanLibrary}
+              onRelinkSound={relinkLibrarySound}
+              onLibraryQuotaChange={updateLibraryQuota}
               onRenameSound={renameSound}
+              onSoundMonitorOnlyChange={toggleSoundMonitorOnly}
+              onSavePlaylist={savePlaylist}
+              onDeletePlaylist={deletePlaylist}
+              onSkipPlaylist={skipPlaylist}
+              onStopPlaylist={stopPlaylist}
               onSetKeySound={setKeySound}
               onPreviewLibrarySound={previewLibrarySound}
             />
@@ -127,19 +315,80 @@ export default function App() {
           <TabsContent value="settings" className="flex flex-col flex-1 min-h-0 overflow-hidden animate-fade-in">
             <SettingsView
               rgbMatrix={state.rgb_matrix}
+              brightnessCurve={state.settings.brightness_curve}
+              accessibilityCues={state.settings.accessibility_cues}
+              onAccessibilityCuesChange={updateAccessibilityCues}
+              nowPlaying={state.settings.now_playing}
+              onNowPlayingChange={updateNowPlaying}
+              discord={state.settings.discord}
+              onDiscordChange={updateDiscordSettings}
+              lighting={state.settings.lighting}
+              onLightingChange={updateLighting}
+              onPairHue={pairHueBridge}
+              game={state.settings.game}
+              onGameChange={updateGameSettings}
+              accentKeyColors={state.settings.accent_key_colors}
+              systemAccent={systemTheme?.accent ?? null}
+              onAccentKeyColorsChange={updateAccentKeyColors}
+              language={state.settings.language}
+              onLanguageChange={updateLanguage}
+              windowSettings={state.settings.window}
+              onWindowChange={updateWindowSettings}
+              hidTiming={state.settings.hid}
+              onHidTimingChange={updateHidTiming}
+              cooperative={state.settings.cooperative}
+              onCooperativeChange={updateCooperative}
+              onLock={lockConfig}
+              focus={state.settings.focus}
+              focusUntil={state.focus_until}
+              onFocusChange={updateFocusSettings}
+              onStartFocus={startFocus}
+              onStopFocus={stopFocus}
+              onShowOnboarding={resetOnboarding}
+              localApi={state.settings.local_api}
+              onLocalApiChange={updateLocalApi}
+              stopSoundsHotkey={state.settings.stop_sounds_hotkey}
+              muteSoundsHotkey={state.settings.mute_sounds_hotkey}
+              onAppHotkeyChange={updateAppHotkey}
+              keys={state.keys}
+              soundLibrary={state.audio_config.sound_library}
               connected={state.connected}
+              syncReport={state.sync_report}
+              deviceSync={state.device_sync}
+              onSaveCustom={saveCustom}
               onRgbChange={updateRgb}
               onRgbColorChange={updateRgbColor}
               onRgbSave={saveRgb}
+              onBrightnessCurveChange={updateBrightnessCurve}
               onRestoreDefaults={restoreDefaults}
               onBootloaderJump={bootloaderJump}
               onEepromReset={eepromReset}
               onDynamicKeymapReset={dynamicKeymapReset}
               onMacroReset={macroReset}
+              onReloadDefinitions={reloadDefinitions}
             />
           </TabsContent>
         </Tabs>
 
+        <KeymapRestoreDialog
+          saved={state.keymap_restore}
+          device={state.keymaps}
+          keys={state.keys}
+          onRestore={restoreKeymaps}
+          onKeepDevice={keepDeviceKeymaps}
+        />
+        {/* One prompt at a time: keymaps first, then lighting, then audio devices */}
+        <RgbRestoreDialog
+          saved={state.keymap_restore ? null : state.rgb_restore}
+          device={state.rgb_matrix}
+          onRestore={restoreRgb}
+          onKeepDevice={keepDeviceRgb}
+        />
+        <DeviceChoiceDialog
+          choice={state.keymap_restore || state.rgb_restore ? null : state.device_choice}
+          onChoose={chooseAudioDevice}
+        />
+
         {/* Connection overlay — only in Tauri, not in browser dev mode */}
         {isTauri && !state.connected && !overlayDismissed && (
           <div className="connection-overlay fixed inset-0 z-50 flex items-center justify-center bg-[#09090b]/80 backdrop-blur-sm animate-fade-in">
@@ -172,12 +421,24 @@ export default function App() {
                   </div>
                   <div className="space-y-1.5">
                     <h2 className="font-pixel text-sm text-white/70 font-bold uppercase tracking-wider">
-                      No Device
+                      {state.device_busy ? "Device In Use" : "No Device"}
                     </h2>
                     <p className="font-clean text-[11px] text-white/30 leading-relaxed">
-                      Connect your Deck-8 via USB and click reconnect, or the app will auto-detect on plug-in.
+                      {state.device_busy
+                        ? "Another app (VIA?) has the Deck-8 open. Close it and click reconnect."
+                        : "Connect your Deck-8 via USB and click reconnect, or the app will auto-detect on plug-in."}
                     </p>
                   </div>
+                  {state.device_busy && (
+                    <label className="flex items-center gap-1.5 font-clean text-[10px] text-white/40 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        checked={state.settings.cooperative}
+                        onChange={(e) => updateCooperative(e.target.checked)}
+                      />
+                      Connect when it's closed
+                    </label>
+                  )}
                   <button
                     type="button"
                     className="flex items-center gap-2 px-5 py-2.5 rounded-xl bg-white/[0.08] border border-white/15 text-white/70 hover:bg-white/[0.14] hover:text-white/90 hover:border-white/25 transition-all duration-150 font-clean text-[11px] font-bold"
@@ -0,0 +1,79 @@
+import { useEffect, useState } from "react";
+import { Activity } from "lucide-react";
+import { cn } from "@/lib/utils";
+import { getAudioMetrics, onAudioMetrics } from "@/lib/tauri";
+import type { AudioMetrics as Metrics } from "@/lib/tauri";
+
+function Fill({ label, value }: { label: string; value: number }) {
+  return (
+    <div className="flex items-center gap-2">
+      <span className="font-clean text-[10px] text-white/40 w-16">{label}</span>
+      <div className="flex-1 h-1.5 rounded-full bg-white/[0.04] overflow-hidden">
+        <div
+          className={cn("h-full", value > 0.9 ? "bg-amber-400/60" : "bg-emerald-400/40")}
+          style={{ width: `${Math.min(value, 1) * 100}%` }}
+        />
+      </div>
+      <span className="font-clean text-[10px] text-white/40 tabular-nums w-10 text-right">{Math.round(value * 100)}%</span>
+    </div>
+  );
+}
+
+/** Buffer levels, drops, rates and mic clipping of the running pipeline, for diagnosing crackles and dropouts. */
+export function AudioMetrics() {
+  const [metrics, setMetrics] = useState<Metrics | null>(null);
+
+  useEffect(() => {
+    getAudioMetrics().then(setMetrics).catch(() => {});
+    const unlisten = onAudioMetrics(setMetrics);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  if (!metrics) return null;
+
+  const stat = (label: string, value: string, warn = false) => (
+    <div className="flex items-center justify-between">
+      <span className="text-white/30">{label}</span>
+      <span className={cn("tabular-nums", warn ? "text-amber-400/70" : "text-white/50")}>{value}</span>
+    </div>
+  );
+
+  return (
+    <div className="flex flex-col gap-1.5">
+      <div className="flex items-center gap-1.5">
+        <Activity className="w-3 h-3 text-white/20" />
+        <span className="font-pixel text-[9px] text-white/40 uppercase tracking-wider">Pipeline</span>
+        {metrics.clipping && (
+          <span
+            className="px-1 rounded bg-red-500/20 font-pixel text-[8px] text-red-300/80 uppercase"
+            title="The mic clipped in the last few seconds — lower the input gain (or the mic's own level)"
+          >
+            Clip
+          </span>
+        )}
+        <span className="font-clean text-[10px] text-white/25 ml-auto">
+          {metrics.channels}ch @ {metrics.sample_rate} Hz
+        </span>
+      </div>
+      <Fill label="Input peak" value={metrics.input_peak} />
+      <Fill label="Mic buffer" value={metrics.mic_buffer_fill} />
+      <Fill label="Sound buffer" value={metrics.sound_buffer_fill} />
+      <div className="grid grid-cols-2 gap-x-4 gap-y-0.5 font-clean text-[10px]">
+        {stat("Input rate", `${Math.round(metrics.input_rate_hz)} Hz`)}
+        {stat("Output rate", `${Math.round(metrics.output_rate_hz)} Hz`)}
+        {stat("Dropped (mic)", String(metrics.dropped_mic_samples), metrics.dropped_mic_samples > 0)}
+        {stat("Dropped (sound)", String(metrics.dropped_sound_samples), metrics.dropped_sound_samples > 0)}
+        {stat("Underruns", String(metrics.underruns))}
+        {stat("Clipped", String(metrics.clipped_samples), metrics.clipping)}
+        {stat("Stream errors", String(metrics.stream_errors), metrics.stream_errors > 0)}
+      </div>
+      {metrics.last_stream_error && (
+        <span className="font-clean text-[9px] text-amber-400/60 truncate" title={metrics.last_stream_error}>
+          {metrics.last_stream_error}
+        </span>
+      )}
+    </div>
+  );
+}
@@ -9,54 +9,94 @@ import {
   DialogDescription,
 } from "@/components/ui/dialog";
 import { ColorPicker } from "@/components/color-picker";
+import { SlotBindingEditor } from "@/components/slot-binding-editor";
+import { LightSceneEditor } from "@/components/light-scene-editor";
 import { hsvToRgb, hsvToHex, hexToHsv } from "@/lib/hsv";
-import { X, Cpu, ToggleRight, Copy, Check, Power } from "lucide-react";
-import type { ActiveSlot, KeyConfig } from "@/lib/tauri";
+import {
+  X, Cpu, ToggleRight, Copy, Check, Power, Plus, Trash2, ClipboardCopy, ClipboardPaste, LayoutGrid,
+} from "lucide-react";
+import type { HsvColor, KeyConfig, LightScene, LightTarget, SlotBinding } from "@/lib/tauri";
 import { cn } from "@/lib/utils";
 
 interface ColorEditorDialogProps {
   open: boolean;
   keyIndex: number;
   config: KeyConfig;
-  onColorChange: (keyIndex: number, slot: ActiveSlot | "both", h: number, s: number, v: number) => void;
+  /** `slot` is a slot name, or null for every slot. */
+  onColorChange: (keyIndex: number, slot: string | null, h: number, s: number, v: number) => void;
   onToggleOverride: (keyIndex: number) => void;
+  onAddSlot: (keyIndex: number, name: string, color: HsvColor) => void;
+  onRemoveSlot: (keyIndex: number, name: string) => void;
+  onRenameSlot: (keyIndex: number, name: string, newName: string) => void;
+  onSelectSlot: (keyIndex: number, name: string) => void;
+  onBindingChange: (keyIndex: number, binding: SlotBinding | null) => void;
+  lightTargets: LightTarget[];
+  onLightSceneChange: (keyIndex: number, scene: LightScene | null) => void;
+  palette: HsvColor[];
+  onAddSwatch: (color: HsvColor) => void;
+  onRemoveSwatch: (index: number) => void;
+  /** LED index of the key last copied, if any. */
+  keyClipboard: number | null;
+  onCopyConfig: (keyIndex: number) => void;
+  onPasteConfig: (keyIndex: number) => void;
+  /** `slot` is a slot name, or null for every slot. */
+  onApplyToAll: (slot: string | null, color: HsvColor) => void;
   onSaveCustom: () => void;
   onClose: () => void;
 }
 
+const MAX_SLOTS = 16;
+
 export function ColorEditorDialog({
   open,
   keyIndex,
   config,
   onColorChange,
   onToggleOverride,
+  onAddSlot,
+  onRemoveSlot,
+  onRenameSlot,
+  onSelectSlot,
+  onBindingChange,
+  lightTargets,
+  onLightSceneChange,
+  palette,
+  onAddSwatch,
+  onRemoveSwatch,
+  keyClipboard,
+  onCopyConfig,
+  onPasteConfig,
+  onApplyToAll,
   onSaveCustom,
   onClose,
 }: ColorEditorDialogProps) {
-  const [slot, setSlot] = useState<ActiveSlot>(config.active_slot ?? "A");
+  const activeName = config.slots[config.active_slot]?.name ?? config.slots[0].name;
+  const [slot, setSlot] = useState(activeName);
   const [toggleMode, setToggleMode] = useState(false);
   const didAutoEnable = useRef(false);
   const prevOpen = useRef(false);
 
   // Refs to avoid stale closures in drag handlers (mousemove captures onChange at mousedown time)
   const slotRef = useRef(slot);
-  slotRef.current = slot;
   const toggleModeRef = useRef(toggleMode);
   toggleModeRef.current = toggleMode;
 
   // Reset state only when dialog OPENS (false→true), not on every active_slot change
   useEffect(() => {
     if (open && !prevOpen.current) {
-      setSlot(config.active_slot ?? "A");
-      // Auto-enable toggle mode if A and B have different colors
-      const { slot_a: a, slot_b: b } = config;
-      setToggleMode(a.h !== b.h || a.s !== b.s || a.v !== b.v);
+      setSlot(activeName);
+      // Auto-enable toggle mode if the slots don't all share one color
+      const [first, ...rest] = config.slots.map((sl) => sl.color);
+      setToggleMode(rest.some((c) => c.h !== first.h || c.s !== first.s || c.v !== first.v));
       didAutoEnable.current = false;
     }
     prevOpen.current = open;
-  }, [open, config.active_slot, config.slot_a, config.slot_b]);
+  }, [open, activeName, config.slots]);
 
-  const color = slot === "A" ? config.slot_a : config.slot_b;
+  // Fall back to the active slot if the edited one was just renamed or removed
+  const current = config.slots.find((sl) => sl.name === slot) ?? config.slots[config.active_slot] ?? config.slots[0];
+  const color = current.color;
+  slotRef.current = current.name;
   const previewColor = hsvToRgb(color.h, color.s, color.v);
   const hexColor = hsvToHex(color.h, color.s, color.v);
 
@@ -70,7 +110,7 @@ export function ColorEditorDialog({
       // Use refs to always read the latest slot/toggleMode (avoids stale closures during drag)
       const currentSlot = slotRef.current;
       const currentToggle = toggleModeRef.current;
-      onColorChange(keyIndex, currentToggle ? currentSlot : "both", h, s, v);
+      onColorChange(keyIndex, currentToggle ? currentSlot : null, h, s, v);
     },
     [config.override_enabled, keyIndex, onColorChange, onToggleOverride],
   );
@@ -83,7 +123,6 @@ export function ColorEditorDialog({
   };
 
   const handleClose = useCallback(() => {
-    setSlot("A");
     setToggleMode(false);
     // Persist per-key overrides to device EEPROM on dialog close
     onSaveCustom();
@@ -91,20 +130,43 @@ export function ColorEditorDialog({
   }, [onClose, onSaveCustom]);
 
   const handleToggleModeChange = () => {
-    const keySlot = config.active_slot ?? "A";
     if (!toggleMode) {
       // Turning ON: start on the key's own active slot
-      setSlot(keySlot);
+      setSlot(activeName);
       setToggleMode(true);
     } else {
-      // Turning OFF: sync both slots to match slot A
-      const a = config.slot_a;
-      onColorChange(keyIndex, "both", a.h, a.s, a.v);
-      setSlot(keySlot);
+      // Turning OFF: sync every slot to the first one
+      const first = config.slots[0].color;
+      onColorChange(keyIndex, null, first.h, first.s, first.v);
+      setSlot(activeName);
       setToggleMode(false);
     }
   };
 
+  const handleAddSlot = () => {
+    let n = config.slots.length + 1;
+    while (config.slots.some((sl) => sl.name === `Slot ${n}`)) n++;
+    const name = `Slot ${n}`;
+    onAddSlot(keyIndex, name, color);
+    setSlot(name);
+  };
+
+  // Editable slot name, committed on blur / Enter
+  const [nameInput, setNameInput] = useState(current.name);
+  useEffect(() => {
+    setNameInput(current.name);
+  }, [current.name]);
+
+  const handleRenameCommit = () => {
+    const newName = nameInput.trim();
+    if (!newName || newName === current.name) {
+      setNameInput(current.name);
+      return;
+    }
+    onRenameSlot(keyIndex, current.name, newName);
+    setSlot(newName);
+  };
+
   // Editable hex input state
   const [hexInput, setHexInput] = useState(hexColor);
   const [hexError, setHexError] = useState(false);
@@ -150,7 +212,7 @@ export function ColorEditorDialog({
             Key {keyIndex + 1}
           </DialogTitle>
           <DialogDescription className="text-xs text-white/40">
-            Pick a color to override device animation. Toggle mode lets you set independent A/B colors that swap on keypress.
+            Pick a color to override device animation. Toggle mode gives each named slot its own color; every keypress advances to the next slot.
           </DialogDescription>
         </DialogHeader>
 
@@ -158,16 +220,21 @@ export function ColorEditorDialog({
         <div className="flex items-center gap-3">
           <div className="flex rounded-lg overflow-hidden border border-white/15">
             {toggleMode ? (
-              /* Toggle mode: show both slots with labels */
-              <>
-                <div className="relative w-10 h-8" style={{ backgroundColor: hsvToRgb(config.slot_a.h, config.slot_a.s, config.slot_a.v) }}>
-                  <span className="absolute bottom-0.5 left-1 font-pixel text-[7px] text-white/60 drop-shadow-[0_1px_2px_rgba(0,0,0,1)]">A</span>
-                </div>
-                <div className="w-px bg-black/30" />
-                <div className="relative w-10 h-8" style={{ backgroundColor: hsvToRgb(config.slot_b.h, config.slot_b.s, config.slot_b.v) }}>
-                  <span className="absolute bottom-0.5 right-1 font-pixel text-[7px] text-white/60 drop-shadow-[0_1px_2px_rgba(0,0,0,1)]">B</span>
+              /* Toggle mode: one swatch per slot, labeled with its initial */
+              config.slots.map((sl, i) => (
+                <div
+                  key={sl.name}
+                  className={cn("relative h-8", i > 0 && "border-l border-black/30")}
+                  style={{
+                    width: Math.max(12, 80 / config.slots.length),
+                    backgroundColor: hsvToRgb(sl.color.h, sl.color.s, sl.color.v),
+                  }}
+                >
+                  <span className="absolute bottom-0.5 left-1 font-pixel text-[7px] text-white/60 drop-shadow-[0_1px_2px_rgba(0,0,0,1)]">
+                    {sl.name.charAt(0)}
+                  </span>
                 </div>
-              </>
+              ))
             ) : (
               /* Single mode: one swatch */
               <div className="w-10 h-8" style={{ backgroundColor: previewColor }} />
@@ -252,77 +319,144 @@ export function ColorEditorDialog({
           </div>
           {toggleMode ? (
             <span className="ml-auto text-[9px] text-violet-300/40">
-              A/B independent
+              {config.slots.length} independent slots
             </span>
           ) : (
             <span className="ml-auto text-[9px] text-white/15">
-              Same color for both
+              Same color for all slots
             </span>
           )}
         </button>
 
         {/* Slot switcher — only in toggle mode */}
         {toggleMode && (
-          <div className="flex gap-1.5 animate-fade-in">
-            {(["A", "B"] as const).map((s) => {
-              const slotColor = s === "A"
-                ? hsvToRgb(config.slot_a.h, config.slot_a.s, config.slot_a.v)
-                : hsvToRgb(config.slot_b.h, config.slot_b.s, config.slot_b.v);
-              const slotHex = s === "A"
-                ? hsvToHex(config.slot_a.h, config.slot_a.s, config.slot_a.v)
-                : hsvToHex(config.slot_b.h, config.slot_b.s, config.slot_b.v);
-              const isActive = slot === s;
-              return (
-                <button
-                  key={s}
-                  type="button"
-                  className={cn(
-                    "flex-1 flex items-center gap-2 px-3 py-2 rounded-lg border transition-all",
-                    isActive
-                      ? "border-violet-400/30 bg-violet-500/[0.08]"
-                      : "border-white/[0.06] bg-transparent hover:bg-white/[0.02] hover:border-white/10",
-                  )}
-                  onClick={() => {
-                    setSlot(s);
-                    // Push this slot's color to the device immediately
-                    const c = s === "A" ? config.slot_a : config.slot_b;
-                    onColorChange(keyIndex, s, c.h, c.s, c.v);
-                  }}
-                >
-                  <span
+          <div className="flex flex-col gap-1.5 animate-fade-in">
+            <div className="grid grid-cols-2 gap-1.5">
+              {config.slots.map((sl) => {
+                const slotColor = hsvToRgb(sl.color.h, sl.color.s, sl.color.v);
+                const slotHex = hsvToHex(sl.color.h, sl.color.s, sl.color.v);
+                const isActive = current.name === sl.name;
+                return (
+                  <button
+                    key={sl.name}
+                    type="button"
                     className={cn(
-                      "w-4 h-4 rounded-md border transition-all",
+                      "flex items-center gap-2 px-3 py-2 rounded-lg border transition-all min-w-0",
                       isActive
-                        ? "border-white/40 shadow-[0_0_8px_rgba(255,255,255,0.1)]"
-                        : "border-white/15",
+                        ? "border-violet-400/30 bg-violet-500/[0.08]"
+                        : "border-white/[0.06] bg-transparent hover:bg-white/[0.02] hover:border-white/10",
                     )}
-                    style={{ backgroundColor: slotColor }}
-                  />
-                  <div className="flex flex-col items-start">
-                    <span className={cn(
-                      "text-[10px] font-bold leading-none",
-                      isActive ? "text-white/80" : "text-white/30",
-                    )}>
-                      Slot {s}
-                    </span>
-                    <span className={cn(
-                      "text-[8px] tabular-nums leading-none mt-0.5",
-                      isActive ? "text-white/35" : "text-white/15",
-                    )}>
-                      {slotHex}
-                    </span>
-                  </div>
-                </button>
-              );
-            })}
+                    onClick={() => {
+                      setSlot(sl.name);
+                      // Show this slot on the device immediately
+                      onSelectSlot(keyIndex, sl.name);
+                    }}
+                  >
+                    <span
+                      className={cn(
+                        "w-4 h-4 rounded-md border transition-all",
+                        isActive
+                          ? "border-white/40 shadow-[0_0_8px_rgba(255,255,255,0.1)]"
+                          : "border-white/15",
+                      )}
+                      style={{ backgroundColor: slotColor }}
+                    />
+                    <div className="flex flex-col items-start">
+                      <span className={cn(
+                        "text-[10px] font-bold leading-none truncate max-w-[7rem]",
+                        isActive ? "text-white/80" : "text-white/30",
+                      )}>
+                        {sl.name}
+                      </span>
+                      <span className={cn(
+                        "text-[8px] tabular-nums leading-none mt-0.5",
+                        isActive ? "text-white/35" : "text-white/15",
+                      )}>
+                        {slotHex}
+                      </span>
+                    </div>
+                  </button>
+                );
+              })}
+            </div>
+            {/* Selected slot: rename / remove, plus add */}
+            <div className="flex items-center gap-1.5">
+              <input
+                type="text"
+                value={nameInput}
+                maxLength={32}
+                onChange={(e) => setNameInput(e.target.value)}
+                onBlur={handleRenameCommit}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") handleRenameCommit();
+                }}
+                className="flex-1 min-w-0 bg-transparent text-[10px] font-medium text-white/50 outline-none border-b border-transparent focus:border-white/20 transition-colors"
+                spellCheck={false}
+                title="Slot name"
+              />
+              <button
+                type="button"
+                className="p-1 rounded hover:bg-white/[0.06] transition-colors disabled:opacity-30 disabled:pointer-events-none"
+                onClick={() => onRemoveSlot(keyIndex, current.name)}
+                disabled={config.slots.length <= 1}
+                title="Remove slot"
+              >
+                <Trash2 className="w-3 h-3 text-white/30" />
+              </button>
+              <button
+                type="button"
+                className="p-1 rounded hover:bg-white/[0.06] transition-colors disabled:opacity-30 disabled:pointer-events-none"
+                onClick={handleAddSlot}
+                disabled={config.slots.length >= MAX_SLOTS}
+                title="Add slot"
+              >
+                <Plus className="w-3 h-3 text-white/30" />
+              </button>
+            </div>
+            <SlotBindingEditor
+              slotNames={config.slots.map((sl) => sl.name)}
+              binding={config.binding}
+              onChange={(binding) => onBindingChange(keyIndex, binding)}
+            />
+            <LightSceneEditor
+              targets={lightTargets}
+              scene={config.light_scene}
+              onChange={(scene) => onLightSceneChange(keyIndex, scene)}
+            />
           </div>
         )}
 
+        {/* Saved swatches: click to use, right-click to remove */}
+        <div className="flex flex-wrap items-center gap-1">
+          {palette.map((c, i) => (
+            <button
+              key={i}
+              type="button"
+              className="w-4 h-4 rounded-sm border border-white/15 hover:border-white/40 transition-colors"
+              style={{ backgroundColor: hsvToRgb(c.h, c.s, c.v) }}
+              title={hsvToHex(c.h, c.s, c.v)}
+              onClick={() => handleColorChange(c.h, c.s, c.v)}
+              onContextMenu={(e) => {
+                e.preventDefault();
+                onRemoveSwatch(i);
+              }}
+            />
+          ))}
+          <button
+            type="button"
+            className="w-4 h-4 flex items-center justify-center rounded-sm border border-dashed border-white/15 hover:border-white/40 transition-colors"
+            title="Save current color"
+            onClick={() => onAddSwatch(color)}
+          >
+            <Plus className="w-2.5 h-2.5 text-white/30" />
+          </button>
+        </div>
+
         {/* Color picker — always visible */}
         <ColorPicker
-          key={`${keyIndex}-${slot}`}
+          key={`${keyIndex}-${current.name}`}
           keyIndex={keyIndex}
-          slot={slot}
+          slot={current.name}
           color={color}
           onChange={handleColorChange}
           hideHeader
@@ -352,6 +486,35 @@ export function ColorEditorDialog({
                 Device
               </Button>
             )}
+            <Button
+              variant="ghost"
+              size="sm"
+              className="text-white/25 hover:text-white/50 hover:bg-white/[0.04] h-7 w-7 p-0 transition-smooth rounded-md"
+              onClick={() => onApplyToAll(toggleMode ? current.name : null, color)}
+              title={toggleMode ? `Use this color for "${current.name}" on every key` : "Use this color on every key"}
+            >
+              <LayoutGrid className="w-3 h-3" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              className="text-white/25 hover:text-white/50 hover:bg-white/[0.04] h-7 w-7 p-0 transition-smooth rounded-md"
+              onClick={() => onCopyConfig(keyIndex)}
+              title="Copy this key's setup"
+            >
+              <ClipboardCopy className="w-3 h-3" />
+            </Button>
+            {keyClipboard !== null && keyClipboard !== keyIndex && (
+              <Button
+                variant="ghost"
+                size="sm"
+                className="text-white/25 hover:text-white/50 hover:bg-white/[0.04] h-7 w-7 p-0 transition-smooth rounded-md"
+                onClick={() => onPasteConfig(keyIndex)}
+                title={`Paste setup from key ${keyClipboard + 1}`}
+              >
+                <ClipboardPaste className="w-3 h-3" />
+              </Button>
+            )}
           </div>
           <Button
             variant="ghost"
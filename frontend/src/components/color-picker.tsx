@@ -1,11 +1,11 @@
 import { useCallback, useRef } from "react";
 import { Slider } from "@/components/ui/slider";
 import { hsvToRgb, hsvToHex } from "@/lib/hsv";
-import type { ActiveSlot, HsvColor } from "@/lib/tauri";
+import type { HsvColor } from "@/lib/tauri";
 
 interface ColorPickerProps {
   keyIndex: number;
-  slot: ActiveSlot;
+  slot: string;
   color: HsvColor;
   onChange: (h: number, s: number, v: number) => void;
   hideHeader?: boolean;
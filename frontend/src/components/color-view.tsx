@@ -1,24 +1,97 @@
 import { useState } from "react";
 import { KeyGrid } from "@/components/key-grid";
 import { ColorEditorDialog } from "@/components/color-editor-dialog";
-import type { ActiveSlot, KeyConfig } from "@/lib/tauri";
+import { ThemeBar } from "@/components/theme-bar";
+import { GroupBar } from "@/components/group-bar";
+import { ModeBar } from "@/components/mode-bar";
+import { RuleBar } from "@/components/rule-bar";
+import type { HsvColor, KeyConfig, KeyGroup, LightScene, LightTarget, Mode, Rule, SlotBinding, Theme } from "@/lib/tauri";
 
 interface ColorViewProps {
   keys: KeyConfig[];
+  /** False when the connected firmware can't set per-key colors. */
+  perKeySupported: boolean;
+  palette: HsvColor[];
+  themes: Theme[];
+  groups: KeyGroup[];
+  modes: Mode[];
+  activeMode: string | null;
+  rules: Rule[];
+  activeRules: string[];
   selectedKey: number | null;
   onSelectKey: (index: number) => void;
-  onColorChange: (keyIndex: number, slot: ActiveSlot | "both", h: number, s: number, v: number) => void;
+  onColorChange: (keyIndex: number, slot: string | null, h: number, s: number, v: number) => void;
   onToggleOverride: (keyIndex: number) => void;
+  onAddSlot: (keyIndex: number, name: string, color: HsvColor) => void;
+  onRemoveSlot: (keyIndex: number, name: string) => void;
+  onRenameSlot: (keyIndex: number, name: string, newName: string) => void;
+  onSelectSlot: (keyIndex: number, name: string) => void;
+  onBindingChange: (keyIndex: number, binding: SlotBinding | null) => void;
+  lightTargets: LightTarget[];
+  onLightSceneChange: (keyIndex: number, scene: LightScene | null) => void;
+  onAddSwatch: (color: HsvColor) => void;
+  onRemoveSwatch: (index: number) => void;
+  onSaveTheme: (name: string) => void;
+  onApplyTheme: (name: string) => void;
+  onDeleteTheme: (name: string) => void;
+  themePreview: string | null;
+  onPreviewTheme: (name: string) => void;
+  onEndThemePreview: () => void;
+  onSaveGroup: (name: string, keys: number[]) => void;
+  onDeleteGroup: (name: string) => void;
+  onSaveMode: (name: string, soundsMuted: boolean | null, base: string | null) => void;
+  onSetMode: (name: string) => void;
+  onDeleteMode: (name: string) => void;
+  onSaveRule: (rule: Rule) => void;
+  onDeleteRule: (ruleId: string) => void;
+  keyClipboard: number | null;
+  onCopyConfig: (keyIndex: number) => void;
+  onPasteConfig: (keyIndex: number) => void;
+  onApplyToAll: (slot: string | null, color: HsvColor) => void;
   onToggleKeySlot: (keyIndex: number) => void;
   onSaveCustom: () => void;
 }
 
 export function ColorView({
   keys,
+  perKeySupported,
+  palette,
+  themes,
+  groups,
+  modes,
+  activeMode,
+  rules,
+  activeRules,
   selectedKey,
   onSelectKey,
   onColorChange,
   onToggleOverride,
+  onAddSlot,
+  onRemoveSlot,
+  onRenameSlot,
+  onSelectSlot,
+  onBindingChange,
+  lightTargets,
+  onLightSceneChange,
+  onAddSwatch,
+  onRemoveSwatch,
+  onSaveTheme,
+  onApplyTheme,
+  onDeleteTheme,
+  themePreview,
+  onPreviewTheme,
+  onEndThemePreview,
+  onSaveGroup,
+  onDeleteGroup,
+  onSaveMode,
+  onSetMode,
+  onDeleteMode,
+  onSaveRule,
+  onDeleteRule,
+  keyClipboard,
+  onCopyConfig,
+  onPasteConfig,
+  onApplyToAll,
   onToggleKeySlot,
   onSaveCustom,
 }: ColorViewProps) {
@@ -46,10 +119,28 @@ export function ColorView({
         </div>
         {/* Hint */}
         <div className="relative z-[1] px-5 pb-3 text-center">
-          <span className="font-clean text-[9px] text-white/15">
-            Click a key to edit its color
-          </span>
+          {perKeySupported ? (
+            <span className="font-clean text-[9px] text-white/15">
+              Click a key to edit its color
+            </span>
+          ) : (
+            <span className="font-clean text-[9px] text-amber-400/60">
+              This firmware doesn't support per-key colors — update it to light keys from here
+            </span>
+          )}
         </div>
+        <ThemeBar
+          themes={themes}
+          previewing={themePreview}
+          onSave={onSaveTheme}
+          onApply={onApplyTheme}
+          onPreview={onPreviewTheme}
+          onEndPreview={onEndThemePreview}
+          onDelete={onDeleteTheme}
+        />
+        <GroupBar groups={groups} onSave={onSaveGroup} onDelete={onDeleteGroup} />
+        <ModeBar modes={modes} activeMode={activeMode} onSave={onSaveMode} onSet={onSetMode} onDelete={onDeleteMode} />
+        <RuleBar keys={keys} rules={rules} activeRules={activeRules} onSave={onSaveRule} onDelete={onDeleteRule} />
       </div>
 
       {/* Color editor dialog */}
@@ -60,6 +151,20 @@ export function ColorView({
           config={keys[selectedKey]}
           onColorChange={onColorChange}
           onToggleOverride={onToggleOverride}
+          onAddSlot={onAddSlot}
+          onRemoveSlot={onRemoveSlot}
+          onRenameSlot={onRenameSlot}
+          onSelectSlot={onSelectSlot}
+          onBindingChange={onBindingChange}
+          lightTargets={lightTargets}
+          onLightSceneChange={onLightSceneChange}
+          palette={palette}
+          onAddSwatch={onAddSwatch}
+          onRemoveSwatch={onRemoveSwatch}
+          keyClipboard={keyClipboard}
+          onCopyConfig={onCopyConfig}
+          onPasteConfig={onPasteConfig}
+          onApplyToAll={onApplyToAll}
           onSaveCustom={onSaveCustom}
           onClose={() => setEditorOpen(false)}
         />
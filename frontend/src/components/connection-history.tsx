@@ -0,0 +1,70 @@
+import { useEffect, useState } from "react";
+import { Cable } from "lucide-react";
+import { getConnectionHistory, onStateUpdated, type ConnectionEventKind, type ConnectionHistory } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+/** How many of the latest events to list. */
+const RECENT = 6;
+
+const LABELS: Record<ConnectionEventKind, string> = {
+  Connected: "Connected",
+  Released: "Released",
+  Resync: "Reconnected",
+  Unresponsive: "Stopped responding",
+  Lost: "Connection lost",
+  NotFound: "Not found",
+  Busy: "In use by another app",
+  Failed: "Connect failed",
+};
+
+function formatDuration(ms: number): string {
+  const minutes = Math.floor(ms / 60_000);
+  const h = Math.floor(minutes / 60);
+  if (h >= 24) return `${Math.floor(h / 24)}d ${h % 24}h`;
+  return h > 0 ? `${h}h ${minutes % 60}m` : `${minutes}m`;
+}
+
+/** Connect/disconnect history: drops in the last day and week, and the latest events. */
+export function ConnectionHistoryPanel() {
+  const [history, setHistory] = useState<ConnectionHistory | null>(null);
+
+  useEffect(() => {
+    const refresh = () => getConnectionHistory().then(setHistory).catch(() => {});
+    refresh();
+    const unlisten = onStateUpdated(refresh);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  if (!history || history.events.length === 0) return null;
+  const { summary } = history;
+  const recent = history.events.slice(-RECENT).reverse();
+
+  return (
+    <div className="flex flex-col gap-1.5 px-3 py-2 rounded-lg border border-white/[0.06] bg-white/[0.02]">
+      <div className="flex items-center gap-2">
+        <Cable className="w-3 h-3 text-white/30" />
+        <span className="font-clean text-[10px] text-white/60">
+          {summary.connected_since_ms !== null
+            ? `Connected for ${formatDuration(Date.now() - summary.connected_since_ms)}`
+            : "Not connected"}
+        </span>
+        <span className={cn("ml-auto font-clean text-[9px]", summary.drops_day > 0 ? "text-amber-400/70" : "text-white/25")}>
+          {summary.drops_day} drops today · {summary.drops_week} this week
+          {summary.mean_time_to_drop_ms !== null && ` · every ${formatDuration(summary.mean_time_to_drop_ms)} on average`}
+        </span>
+      </div>
+      <div className="flex flex-col gap-0.5">
+        {recent.map((e) => (
+          <div key={`${e.at_ms}-${e.kind}`} className="flex items-center gap-2 font-clean text-[9px]" title={e.detail ?? undefined}>
+            <span className="text-white/25 tabular-nums">{new Date(e.at_ms).toLocaleString()}</span>
+            <span className={e.kind === "Unresponsive" || e.kind === "Lost" ? "text-amber-400/70" : "text-white/40"}>
+              {LABELS[e.kind]}
+            </span>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,55 @@
+import {
+  AlertDialog,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog";
+import type { DeviceChoice } from "@/lib/tauri";
+
+interface DeviceChoiceDialogProps {
+  /** The dialog is open while this is set. */
+  choice: DeviceChoice | null;
+  onChoose: (name: string | null) => void;
+}
+
+const ROLE_LABEL: Record<DeviceChoice["role"], string> = {
+  Input: "microphone",
+  Output: "output",
+  SecondInput: "second input",
+};
+
+/** Shown when a saved audio device is gone and several listed devices look like it. */
+export function DeviceChoiceDialog({ choice, onChoose }: DeviceChoiceDialogProps) {
+  return (
+    <AlertDialog open={choice !== null}>
+      <AlertDialogContent className="bg-[#111113] border-white/12">
+        <AlertDialogHeader>
+          <AlertDialogTitle className="text-sm">Which one is your {choice && ROLE_LABEL[choice.role]}?</AlertDialogTitle>
+          <AlertDialogDescription className="text-xs text-white/40">
+            &ldquo;{choice?.saved}&rdquo; isn't listed anymore, and more than one device looks like it.
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        <div className="flex flex-col gap-1 px-1">
+          {choice?.candidates.map((name) => (
+            <button
+              key={name}
+              type="button"
+              className="px-2.5 py-1.5 rounded-lg text-left font-clean text-[10px] text-white/60 bg-white/[0.02] border border-white/[0.06] hover:bg-violet-500/15 hover:text-violet-200 transition-colors"
+              onClick={() => onChoose(name)}
+            >
+              {name}
+            </button>
+          ))}
+        </div>
+        <AlertDialogFooter>
+          <AlertDialogCancel className="text-xs h-8" onClick={() => onChoose(null)}>
+            Decide later
+          </AlertDialogCancel>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
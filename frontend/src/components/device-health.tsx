@@ -0,0 +1,63 @@
+import { useEffect, useState } from "react";
+import { Activity } from "lucide-react";
+import { getDeviceHealth, onDeviceHealth, onDeviceRebooted, type DeviceHealth } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+function formatUptime(seconds: number): string {
+  const h = Math.floor(seconds / 3600);
+  const m = Math.floor((seconds % 3600) / 60);
+  return h > 0 ? `${h}h ${m}m` : `${m}m ${seconds % 60}s`;
+}
+
+/** Heartbeat history: current uptime, response time and reboots in the last hour. */
+export function DeviceHealthPanel() {
+  const [health, setHealth] = useState<DeviceHealth | null>(null);
+
+  useEffect(() => {
+    const refresh = () => getDeviceHealth().then(setHealth).catch(() => {});
+    refresh();
+    const unlistenSample = onDeviceHealth(refresh);
+    const unlistenReboot = onDeviceRebooted(refresh);
+    return () => {
+      unlistenSample.then((fn) => fn());
+      unlistenReboot.then((fn) => fn());
+    };
+  }, []);
+
+  const samples = health?.samples ?? [];
+  const last = samples[samples.length - 1];
+  if (!last) return null;
+  const latencies = samples.filter((s) => s.latency_ms !== null);
+  const slowest = Math.max(1, ...latencies.map((s) => s.latency_ms ?? 0));
+  const reboots = health?.reboots ?? [];
+
+  return (
+    <div className="flex flex-col gap-1.5 px-3 py-2 rounded-lg border border-white/[0.06] bg-white/[0.02]">
+      <div className="flex items-center gap-2">
+        <Activity className="w-3 h-3 text-white/30" />
+        <span className="font-clean text-[10px] text-white/60">
+          {last.uptime !== null ? `Up ${formatUptime(last.uptime)}` : last.error ?? "Not connected"}
+        </span>
+        {last.latency_ms !== null && (
+          <span className="font-clean text-[9px] text-white/25">{last.latency_ms} ms</span>
+        )}
+        <span className={cn("ml-auto font-clean text-[9px]", reboots.length > 0 ? "text-amber-400/70" : "text-white/25")}>
+          {reboots.length === 0
+            ? "No restarts in the last hour"
+            : `Restarted ${reboots.length}× · last ${new Date(reboots[reboots.length - 1].at_ms).toLocaleTimeString()}`}
+        </span>
+      </div>
+      {/* Response time per sample, oldest first; gaps are reads that failed or no device */}
+      <div className="flex items-end gap-px h-4">
+        {samples.map((s) => (
+          <span
+            key={s.at_ms}
+            className={cn("flex-1 rounded-[1px]", s.uptime !== null ? "bg-emerald-400/40" : "bg-white/[0.06]")}
+            style={{ height: s.latency_ms !== null ? `${Math.max(15, (s.latency_ms / slowest) * 100)}%` : "15%" }}
+            title={new Date(s.at_ms).toLocaleTimeString()}
+          />
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,79 @@
+import { useCallback, useEffect, useState } from "react";
+import { AlertTriangle, CircleCheck, CircleMinus, CircleX, Save } from "lucide-react";
+import { getState, type DeviceSync, type SyncStatus } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+const STATUS: Record<SyncStatus, { icon: typeof CircleCheck; className: string; label: string }> = {
+  Saved: { icon: CircleCheck, className: "text-emerald-400/60", label: "Saved on the device" },
+  Unsaved: { icon: AlertTriangle, className: "text-amber-400/60", label: "Shown, lost on unplug" },
+  OutOfSync: { icon: CircleX, className: "text-red-400/70", label: "Device shows something else" },
+  Unknown: { icon: CircleMinus, className: "text-white/20", label: "Not available" },
+};
+
+const ROWS: { key: keyof DeviceSync; name: string }[] = [
+  { key: "colors", name: "Key colors" },
+  { key: "keymaps", name: "Keymaps" },
+  { key: "rgb", name: "RGB Matrix" },
+];
+
+/**
+ * Whether the device matches the app, per subsystem, so "Save to device" is
+ * only pressed when something is actually unsaved. Re-read on open and after
+ * each save, since color and RGB writes don't refresh the app state.
+ */
+export function DeviceSyncPanel({ sync, onSaveColors, onSaveRgb }: {
+  sync: DeviceSync;
+  onSaveColors: () => void | Promise<void>;
+  onSaveRgb: () => void | Promise<void>;
+}) {
+  const [current, setCurrent] = useState(sync);
+
+  const refresh = useCallback(() => {
+    getState().then((s) => setCurrent(s.device_sync)).catch(() => {});
+  }, []);
+
+  useEffect(() => {
+    setCurrent(sync);
+    refresh();
+  }, [sync, refresh]);
+
+  const save = async () => {
+    if (current.colors === "Unsaved") await onSaveColors();
+    if (current.rgb === "Unsaved") await onSaveRgb();
+    refresh();
+  };
+
+  const unsaved = current.colors === "Unsaved" || current.rgb === "Unsaved";
+
+  return (
+    <div className="flex flex-col gap-1 px-3 py-2 rounded-lg border border-white/[0.06] bg-white/[0.02]">
+      {ROWS.map(({ key, name }) => {
+        const { icon: Icon, className, label } = STATUS[current[key]];
+        return (
+          <div key={key} className="flex items-center gap-2 min-w-0">
+            <Icon className={cn("w-3 h-3 shrink-0", className)} />
+            <span className="font-clean text-[10px] text-white/60 shrink-0">{name}</span>
+            <span className="font-clean text-[9px] text-white/25 truncate">{label}</span>
+          </div>
+        );
+      })}
+      {unsaved && (
+        <button
+          type="button"
+          className={cn(
+            "mt-1 flex items-center justify-center gap-1.5 px-2.5 py-1.5 rounded-lg",
+            "font-pixel text-[9px] uppercase tracking-wider font-bold",
+            "text-emerald-400/60 hover:text-emerald-400/90",
+            "bg-emerald-500/[0.06] hover:bg-emerald-500/[0.12]",
+            "border border-emerald-500/15 hover:border-emerald-500/30",
+            "transition-all duration-150",
+          )}
+          onClick={save}
+        >
+          <Save className="w-3 h-3" />
+          save to device
+        </button>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,129 @@
+import { useEffect, useState } from "react";
+import { Flame, Play, Square, Target } from "lucide-react";
+import { getFocusSummary, onFocusCompleted, type FocusSettings, type FocusSummary } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface FocusCardProps {
+  value: FocusSettings;
+  /** Unix time (ms) the running session ends. */
+  runningUntil: number | null;
+  onChange: (focus: FocusSettings) => void;
+  onStart: () => void;
+  onStop: () => void;
+}
+
+const WEEKDAY = new Intl.DateTimeFormat(undefined, { weekday: "narrow" });
+
+function formatLeft(ms: number) {
+  const s = Math.max(0, Math.ceil(ms / 1000));
+  return `${Math.floor(s / 60)}:${String(s % 60).padStart(2, "0")}`;
+}
+
+/**
+ * Focus timer: start or stop a session (or bind a key to "Focus running"),
+ * the last week's completed sessions, and the streak of days at the goal.
+ */
+export function FocusCard({ value, runningUntil, onChange, onStart, onStop }: FocusCardProps) {
+  const [summary, setSummary] = useState<FocusSummary | null>(null);
+  const [now, setNow] = useState(Date.now());
+  const [draft, setDraft] = useState(value);
+  useEffect(() => setDraft(value), [value]);
+
+  useEffect(() => {
+    getFocusSummary().then(setSummary).catch(() => {});
+    const unlisten = onFocusCompleted(setSummary);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [value.daily_goal]);
+
+  useEffect(() => {
+    if (runningUntil === null) return;
+    const id = setInterval(() => setNow(Date.now()), 1000);
+    return () => clearInterval(id);
+  }, [runningUntil]);
+
+  const commit = () => {
+    if (draft.focus_min !== value.focus_min || draft.daily_goal !== value.daily_goal) onChange(draft);
+  };
+  const running = runningUntil !== null && runningUntil > now;
+  const most = Math.max(1, value.daily_goal, ...(summary?.days.map((d) => d.sessions) ?? []));
+  const fields: { field: "focus_min" | "daily_goal"; label: string; unit: string; min: number; max: number }[] = [
+    { field: "focus_min", label: "Length", unit: "min", min: 1, max: 180 },
+    { field: "daily_goal", label: "Goal", unit: "a day", min: 0, max: 48 },
+  ];
+
+  return (
+    <div className="px-5 py-3 flex flex-col gap-1.5">
+      <div className="flex items-center gap-2 mb-1">
+        <Target className="w-3.5 h-3.5 text-rose-400/50" />
+        <span className="font-pixel text-[11px] text-white/70 font-bold uppercase tracking-wider">Focus</span>
+        {running && (
+          <span className="font-clean text-[10px] text-rose-300/70 tabular-nums">{formatLeft(runningUntil - now)} left</span>
+        )}
+        <button
+          type="button"
+          className="ml-auto p-1 rounded hover:bg-white/[0.06] transition-colors"
+          onClick={running ? onStop : onStart}
+          title={running ? "Stop (not counted)" : `Start ${value.focus_min} min`}
+        >
+          {running ? <Square className="w-3 h-3 text-white/40" /> : <Play className="w-3 h-3 text-white/40" />}
+        </button>
+      </div>
+      <div className="flex items-center gap-3">
+        {fields.map(({ field, label, unit, min, max }) => (
+          <label key={field} className="flex items-center gap-1 font-clean text-[9px] text-white/30">
+            {label}
+            <input
+              type="number"
+              min={min}
+              max={max}
+              value={draft[field]}
+              onChange={(e) => setDraft({ ...draft, [field]: Number(e.target.value) })}
+              onBlur={commit}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") commit();
+              }}
+              className="w-12 px-1.5 py-0.5 rounded bg-[#0d0d0f] border border-white/[0.08] font-clean text-[10px] text-white/60 outline-none"
+            />
+            {unit}
+          </label>
+        ))}
+        <label className="ml-auto flex items-center gap-1 font-clean text-[9px] text-white/30 cursor-pointer">
+          <input type="checkbox" checked={value.celebrate} onChange={(e) => onChange({ ...value, celebrate: e.target.checked })} />
+          Flash the keys at the goal
+        </label>
+      </div>
+      {summary && (
+        <>
+          <div className="flex items-end gap-1 h-10 mt-1">
+            {summary.days.map((d) => (
+              <div key={d.date} className="flex-1 flex flex-col items-center gap-0.5" title={`${d.date}: ${d.sessions} sessions, ${d.minutes} min`}>
+                <div className="w-full flex-1 flex items-end">
+                  <div
+                    className={cn(
+                      "w-full rounded-sm",
+                      summary.daily_goal > 0 && d.sessions >= summary.daily_goal ? "bg-rose-400/50" : "bg-white/[0.12]",
+                    )}
+                    style={{ height: `${(d.sessions / most) * 100}%` }}
+                  />
+                </div>
+                <span className="font-clean text-[8px] text-white/25">{WEEKDAY.format(new Date(`${d.date}T12:00`))}</span>
+              </div>
+            ))}
+          </div>
+          <div className="flex items-center gap-3 font-clean text-[9px] text-white/30">
+            <span>This week: {summary.week_sessions} sessions, {Math.round(summary.week_minutes / 6) / 10} h</span>
+            {summary.streak > 0 && (
+              <span className="ml-auto flex items-center gap-1 text-rose-300/60">
+                <Flame className="w-3 h-3" />
+                {summary.streak} day streak
+              </span>
+            )}
+          </div>
+        </>
+      )}
+      <div className="border-b border-white/[0.06] mt-1.5" />
+    </div>
+  );
+}
@@ -0,0 +1,273 @@
+import { useEffect, useState } from "react";
+import { ClipboardCopy, Crosshair, Plus, Save, X } from "lucide-react";
+import { toast } from "sonner";
+import { LED_TO_MATRIX } from "@/components/key-assignment-view";
+import { hexToHsv, hsvToHex, hsvToRgb } from "@/lib/hsv";
+import type { GameRule, GameSettings, GameTest, NotifyPattern } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface GameCardProps {
+  value: GameSettings;
+  onChange: (game: GameSettings) => void;
+}
+
+const RED = { h: 0, s: 255, v: 255 };
+
+/** Common rules; the paths are the games' own state integration fields. */
+const PRESETS: { label: string; rule: Omit<GameRule, "key"> }[] = [
+  { label: "CS2: low HP", rule: { name: "Low HP", path: "player.state.health", test: { kind: "Below", value: 30 }, color: RED, pattern: "Blink" } },
+  { label: "CS2: bomb planted", rule: { name: "Bomb planted", path: "round.bomb", test: { kind: "Equals", value: "planted" }, color: { h: 20, s: 255, v: 255 }, pattern: "Pulse" } },
+  { label: "CS2: flashed", rule: { name: "Flashed", path: "player.state.flashed", test: { kind: "Above", value: 0 }, color: { h: 0, s: 0, v: 255 }, pattern: "Solid" } },
+  { label: "Dota 2: low HP", rule: { name: "Low HP", path: "hero.health_percent", test: { kind: "Below", value: 30 }, color: RED, pattern: "Blink" } },
+  { label: "Dota 2: ultimate ready", rule: { name: "Ultimate ready", path: "abilities.ability5.can_cast", test: { kind: "Equals", value: "true" }, color: { h: 170, s: 255, v: 255 }, pattern: "Pulse" } },
+];
+
+/** LED indices in physical order, for the key picker. */
+const PICKER_ORDER = [0, 1, 2, 3, 4, 5, 6, 7].map((m) => LED_TO_MATRIX.indexOf(m));
+
+const fieldClass = "min-w-0 px-1.5 py-0.5 rounded bg-[#0d0d0f] border border-white/[0.08] font-clean text-[10px] text-white/60 outline-none focus:border-cyan-500/30 placeholder:text-white/15";
+
+function describeTest(test: GameTest) {
+  return test.kind === "Below" ? `< ${test.value}` : test.kind === "Above" ? `> ${test.value}` : `= ${test.value}`;
+}
+
+/** CS2's `game/csgo/cfg/gamestate_integration_deck8.cfg`. */
+function csConfig(port: number, token: string | null) {
+  const auth = token ? `\n  "auth" { "token" "${token}" }` : "";
+  return `"Deck-8 Hub"
+{
+  "uri" "http://127.0.0.1:${port}"
+  "timeout" "1.0"
+  "heartbeat" "30.0"${auth}
+  "data"
+  {
+    "provider" "1"
+    "round" "1"
+    "player_id" "1"
+    "player_state" "1"
+  }
+}`;
+}
+
+/**
+ * Game integration: games with a state integration (CS2, Dota 2) post their
+ * state here, and each rule lights a key while it holds.
+ */
+export function GameCard({ value, onChange }: GameCardProps) {
+  const [port, setPort] = useState(value.port);
+  const [token, setToken] = useState(value.token ?? "");
+  const [draft, setDraft] = useState<GameRule | null>(null);
+  useEffect(() => setPort(value.port), [value.port]);
+  useEffect(() => setToken(value.token ?? ""), [value.token]);
+
+  const commit = () => {
+    const next = { ...value, port, token: token.trim() || null };
+    if (next.port !== value.port || next.token !== value.token) onChange(next);
+  };
+  const copyConfig = async () => {
+    try {
+      await navigator.clipboard.writeText(csConfig(value.port, value.token));
+      toast.success("Copied; save it as gamestate_integration_deck8.cfg in game/csgo/cfg");
+    } catch {
+      toast.error("Couldn't copy to the clipboard");
+    }
+  };
+  const saveDraft = () => {
+    if (!draft || !draft.name.trim() || !draft.path.trim()) return;
+    onChange({ ...value, rules: [...value.rules, { ...draft, name: draft.name.trim(), path: draft.path.trim() }] });
+    setDraft(null);
+  };
+
+  return (
+    <div
+      className={cn(
+        "flex flex-col gap-2 px-3.5 py-3 rounded-xl border transition-all duration-150",
+        value.enabled ? "border-emerald-500/20 bg-emerald-500/[0.04]" : "border-white/[0.06] bg-white/[0.02]",
+      )}
+    >
+      <div className="flex items-center gap-3">
+        <div className={cn(
+          "flex items-center justify-center w-7 h-7 rounded-lg",
+          value.enabled ? "bg-emerald-500/15 text-emerald-400" : "bg-white/[0.06] text-white/25",
+        )}>
+          <Crosshair className="w-3.5 h-3.5" />
+        </div>
+        <div className="flex-1 min-w-0">
+          <div className="font-clean text-[11px] text-white/80 font-medium">Game events</div>
+          <div className="font-clean text-[9px] text-white/30 mt-0.5">
+            Light keys on low HP, a planted bomb or a ready ability. CS2 and Dota 2 send their state to http://127.0.0.1:{value.port}
+          </div>
+        </div>
+        <button
+          type="button"
+          role="switch"
+          aria-checked={value.enabled}
+          aria-label="Game events"
+          className="flex-shrink-0"
+          onClick={() => onChange({ ...value, enabled: !value.enabled })}
+        >
+          <div className={cn(
+            "w-9 h-5 rounded-full p-[2px] transition-all duration-150 cursor-pointer",
+            value.enabled ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+          )}>
+            <div className={cn(
+              "w-4 h-4 rounded-full transition-all duration-150",
+              value.enabled ? "translate-x-4 bg-white" : "translate-x-0 bg-white/30",
+            )} />
+          </div>
+        </button>
+      </div>
+      <div className="flex items-center gap-2 pl-10 font-clean text-[10px]">
+        <span className="text-white/40">Port</span>
+        <input
+          type="number"
+          min={1024}
+          max={65535}
+          value={port}
+          onChange={(e) => setPort(Number(e.target.value))}
+          onBlur={commit}
+          onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+          className={cn(fieldClass, "w-16 tabular-nums")}
+        />
+        <span className="text-white/40">Token</span>
+        <input
+          type="password"
+          value={token}
+          placeholder="Optional"
+          onChange={(e) => setToken(e.target.value)}
+          onBlur={commit}
+          onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+          className={cn(fieldClass, "flex-1")}
+        />
+        <button type="button" className="p-1 rounded hover:bg-white/[0.06] transition-colors" onClick={copyConfig} title="Copy the CS2 config file">
+          <ClipboardCopy className="w-3 h-3 text-white/30" />
+        </button>
+      </div>
+      {value.rules.length > 0 && (
+        <div className="flex flex-wrap gap-1.5 pl-10">
+          {value.rules.map((r, i) => (
+            <div
+              key={i}
+              className="group flex items-center gap-1 pl-1.5 pr-1 py-1 rounded-md border border-white/[0.06] hover:border-white/15 transition-colors"
+              title={`${r.path} ${describeTest(r.test)}: K${LED_TO_MATRIX[r.key] + 1} ${r.pattern.toLowerCase()}`}
+            >
+              <span className="w-2 h-2 rounded-full" style={{ background: hsvToRgb(r.color.h, r.color.s, r.color.v) }} />
+              <span className="font-clean text-[9px] text-white/40 max-w-[90px] truncate">{r.name}</span>
+              <button
+                type="button"
+                className="opacity-0 group-hover:opacity-100 transition-opacity"
+                onClick={() => onChange({ ...value, rules: value.rules.filter((_, j) => j !== i) })}
+                title="Delete rule"
+              >
+                <X className="w-2.5 h-2.5 text-white/25 hover:text-white/50" />
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+      {draft ? (
+        <div className="flex flex-col gap-1.5 pl-10">
+          <div className="flex items-center gap-1.5">
+            <input
+              value={draft.path}
+              placeholder="player.state.health"
+              onChange={(e) => setDraft({ ...draft, path: e.target.value })}
+              className={cn(fieldClass, "flex-1")}
+              spellCheck={false}
+            />
+            <select
+              className={cn(fieldClass, "appearance-none cursor-pointer")}
+              value={draft.test.kind}
+              onChange={(e) => {
+                const kind = e.target.value as GameTest["kind"];
+                setDraft({ ...draft, test: kind === "Equals" ? { kind, value: "" } : { kind, value: 0 } });
+              }}
+            >
+              <option value="Below">below</option>
+              <option value="Above">above</option>
+              <option value="Equals">is</option>
+            </select>
+            <input
+              value={draft.test.value}
+              type={draft.test.kind === "Equals" ? "text" : "number"}
+              onChange={(e) =>
+                setDraft({
+                  ...draft,
+                  test: draft.test.kind === "Equals"
+                    ? { kind: "Equals", value: e.target.value }
+                    : { kind: draft.test.kind, value: Number(e.target.value) },
+                })
+              }
+              className={cn(fieldClass, "w-20")}
+            />
+          </div>
+          <div className="flex items-center gap-1.5">
+            <select
+              className={cn(fieldClass, "appearance-none cursor-pointer")}
+              value={draft.key}
+              onChange={(e) => setDraft({ ...draft, key: Number(e.target.value) })}
+            >
+              {PICKER_ORDER.map((led) => <option key={led} value={led}>K{LED_TO_MATRIX[led] + 1}</option>)}
+            </select>
+            <input
+              type="color"
+              value={hsvToHex(draft.color.h, draft.color.s, draft.color.v)}
+              onChange={(e) => {
+                const color = hexToHsv(e.target.value);
+                if (color) setDraft({ ...draft, color });
+              }}
+              className="w-5 h-5 bg-transparent cursor-pointer"
+            />
+            <select
+              className={cn(fieldClass, "appearance-none cursor-pointer")}
+              value={draft.pattern}
+              onChange={(e) => setDraft({ ...draft, pattern: e.target.value as NotifyPattern })}
+            >
+              <option value="Solid">Solid</option>
+              <option value="Blink">Blink</option>
+              <option value="Pulse">Pulse</option>
+            </select>
+            <input
+              value={draft.name}
+              maxLength={32}
+              placeholder="Rule name…"
+              onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+              onKeyDown={(e) => e.key === "Enter" && saveDraft()}
+              className={cn(fieldClass, "flex-1")}
+              spellCheck={false}
+            />
+            <button type="button" className="p-1 rounded hover:bg-white/[0.06] transition-colors" onClick={() => setDraft(null)} title="Cancel">
+              <X className="w-3 h-3 text-white/30" />
+            </button>
+            <button
+              type="button"
+              className={cn("p-1 rounded hover:bg-white/[0.06] transition-colors", (!draft.name.trim() || !draft.path.trim()) && "opacity-30 pointer-events-none")}
+              onClick={saveDraft}
+              title="Save rule"
+            >
+              <Save className="w-3 h-3 text-white/30" />
+            </button>
+          </div>
+        </div>
+      ) : (
+        <div className="flex items-center gap-1.5 pl-10">
+          <Plus className="w-2.5 h-2.5 text-white/20" />
+          <select
+            className="appearance-none cursor-pointer bg-transparent font-clean text-[9px] text-white/20 hover:text-white/40 outline-none transition-colors"
+            value=""
+            onChange={(e) => {
+              const preset = PRESETS[Number(e.target.value)];
+              setDraft(preset
+                ? { ...preset.rule, key: 0 }
+                : { name: "", path: "", test: { kind: "Below", value: 0 }, key: 0, color: RED, pattern: "Solid" });
+            }}
+          >
+            <option value="" disabled>Add a rule…</option>
+            {PRESETS.map((p, i) => <option key={p.label} value={i}>{p.label}</option>)}
+            <option value="custom">Custom…</option>
+          </select>
+        </div>
+      )}
+    </div>
+  );
+}
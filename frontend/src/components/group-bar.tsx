@@ -0,0 +1,115 @@
+import { useState } from "react";
+import { Save, X } from "lucide-react";
+import { LED_TO_MATRIX } from "@/components/key-assignment-view";
+import type { KeyGroup } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface GroupBarProps {
+  groups: KeyGroup[];
+  onSave: (name: string, keys: number[]) => void;
+  onDelete: (name: string) => void;
+}
+
+/** Physical key number (K1…K8) of an LED index. */
+const keyNumber = (led: number) => LED_TO_MATRIX[led] + 1;
+
+/** LED indices in physical order, for the key picker. */
+const PICKER_ORDER = [0, 1, 2, 3, 4, 5, 6, 7].map((m) => LED_TO_MATRIX.indexOf(m));
+
+/**
+ * Key groups: keys in a group act like radio buttons (pressing one into its
+ * second slot sends the others back to their first). Click a group to edit it.
+ */
+export function GroupBar({ groups, onSave, onDelete }: GroupBarProps) {
+  const [name, setName] = useState("");
+  const [keys, setKeys] = useState<number[]>([]);
+
+  const canSave = name.trim() !== "" && keys.length >= 2;
+
+  const handleSave = () => {
+    if (!canSave) return;
+    onSave(name.trim(), keys);
+    setName("");
+    setKeys([]);
+  };
+
+  const toggleKey = (led: number) =>
+    setKeys((prev) => (prev.includes(led) ? prev.filter((k) => k !== led) : [...prev, led]));
+
+  return (
+    <div className="relative z-[1] flex flex-col gap-1.5 px-5 pb-3">
+      {groups.length > 0 && (
+        <div className="flex flex-wrap gap-1.5">
+          {groups.map((g) => (
+            <div
+              key={g.name}
+              className="group flex items-center gap-1.5 pl-1.5 pr-1 py-1 rounded-md border border-white/[0.06] hover:border-white/15 transition-colors"
+            >
+              <button
+                type="button"
+                className="flex items-center gap-1.5"
+                onClick={() => {
+                  setName(g.name);
+                  setKeys(g.keys);
+                }}
+                title={`Edit "${g.name}"`}
+              >
+                <span className="font-clean text-[9px] text-white/40 max-w-[80px] truncate">{g.name}</span>
+                <span className="font-mono text-[8px] text-white/20">
+                  {g.keys.map(keyNumber).sort((a, b) => a - b).map((n) => `K${n}`).join(" ")}
+                </span>
+              </button>
+              <button
+                type="button"
+                className="opacity-0 group-hover:opacity-100 transition-opacity"
+                onClick={() => onDelete(g.name)}
+                title="Delete group"
+              >
+                <X className="w-2.5 h-2.5 text-white/25 hover:text-white/50" />
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+      <div className="flex items-center gap-1.5">
+        <input
+          type="text"
+          value={name}
+          maxLength={32}
+          placeholder="Group keys (one lit at a time)…"
+          onChange={(e) => setName(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") handleSave();
+          }}
+          className="flex-1 min-w-0 bg-transparent font-clean text-[9px] text-white/40 placeholder:text-white/15 outline-none border-b border-transparent focus:border-white/15 transition-colors"
+          spellCheck={false}
+        />
+        <div className="flex gap-0.5">
+          {PICKER_ORDER.map((led) => (
+            <button
+              key={led}
+              type="button"
+              className={cn(
+                "w-4 h-4 rounded-sm font-mono text-[8px] transition-colors",
+                keys.includes(led) ? "bg-white/20 text-white/70" : "bg-white/[0.04] text-white/25 hover:bg-white/[0.08]",
+              )}
+              onClick={() => toggleKey(led)}
+              aria-pressed={keys.includes(led)}
+              title={`Key ${keyNumber(led)}`}
+            >
+              {keyNumber(led)}
+            </button>
+          ))}
+        </div>
+        <button
+          type="button"
+          className={cn("p-1 rounded hover:bg-white/[0.06] transition-colors", !canSave && "opacity-30 pointer-events-none")}
+          onClick={handleSave}
+          title="Save group"
+        >
+          <Save className="w-3 h-3 text-white/30" />
+        </button>
+      </div>
+    </div>
+  );
+}
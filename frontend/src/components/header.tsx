@@ -6,20 +6,21 @@ import {
   TooltipProvider,
   TooltipTrigger,
 } from "@/components/ui/tooltip";
-import type { ActiveSlot } from "@/lib/tauri";
 import { cn } from "@/lib/utils";
 
 interface HeaderProps {
   connected: boolean;
-  editSlot: ActiveSlot;
-  activeSlot: ActiveSlot;
-  onSlotChange: (slot: ActiveSlot) => void;
+  slotNames: string[];
+  editSlot: string;
+  activeSlot: string;
+  onSlotChange: (slot: string) => void;
   onToggle: () => void;
   onReconnect: () => void;
 }
 
 export function Header({
   connected,
+  slotNames,
   editSlot,
   activeSlot,
   onSlotChange,
@@ -43,7 +44,7 @@ export function Header({
               Edit
             </span>
             <div className="flex gap-0.5 bg-white/[0.06] rounded-lg p-0.5 border border-white/10">
-              {(["A", "B"] as const).map((s) => (
+              {slotNames.map((s) => (
                 <button
                   key={s}
                   type="button"
@@ -80,7 +81,7 @@ export function Header({
               </TooltipTrigger>
               <TooltipContent side="bottom" sideOffset={6}>
                 <p className="text-[11px] max-w-[200px] leading-relaxed">
-                  The device alternates between slot A and B colors for each key.
+                  Each keypress advances the key to its next color slot.
                 </p>
               </TooltipContent>
             </Tooltip>
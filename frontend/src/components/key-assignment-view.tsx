@@ -5,7 +5,7 @@ import { SoundUploadDialog } from "@/components/sound-upload-dialog";
 import { keycodeToLabel } from "@/lib/keycodes";
 import { Unplug } from "lucide-react";
 import { isInternalKeycode } from "@/lib/tauri";
-import type { KeyConfig, SoundEntry } from "@/lib/tauri";
+import type { KeyConfig, KeySource, Playlist, PressMode, SoundEntry, SoundMode, Trim } from "@/lib/tauri";
 
 /**
  * Maps LED index → matrix index.
@@ -13,21 +13,31 @@ import type { KeyConfig, SoundEntry } from "@/lib/tauri";
  * The key matrix is always L→R: top (0-3), bottom (4-7).
  * So LED 7 = physical bottom-left = matrix 4, LED 4 = bottom-right = matrix 7.
  */
-const LED_TO_MATRIX = [0, 1, 2, 3, 7, 6, 5, 4];
+export const LED_TO_MATRIX = [0, 1, 2, 3, 7, 6, 5, 4];
 
 interface KeyAssignmentViewProps {
   keys: KeyConfig[];
   keymaps: number[];
   connected: boolean;
   onKeycodeChange: (keyIndex: number, keycode: number) => void;
+  onCaptureKeycode: (keyIndex: number) => void;
+  onKeySourceChange: (keyIndex: number, source: KeySource) => void;
+  onKeyLabelChange: (keyIndex: number, label: string | null) => void;
+  onKeyIconChange: (keyIndex: number, icon: string | null) => void;
+  onKeySoundCooldownChange: (keyIndex: number, cooldownMs: number, dim: boolean) => void;
+  onKeySoundModeChange: (keyIndex: number, mode: SoundMode) => void;
+  onKeyPressModeChange: (keyIndex: number, mode: PressMode) => void;
+  onKeySoundGainChange: (keyIndex: number, gainDb: number) => void;
   soundLibrary: SoundEntry[];
+  playlists: Playlist[];
   keySounds: (string | null)[];
+  keySoundGains: number[];
   onSetKeySound: (keyIndex: number, soundId: string | null) => void;
   onPreviewLibrarySound: (soundId: string) => void;
   onGetDuration: (filePath: string) => Promise<number>;
-  onPreviewTrim: (sourcePath: string, startMs: number, endMs: number) => void;
+  onPreviewTrim: (sourcePath: string, trim: Trim) => void;
   onAddToLibrary: (filePath: string, displayName: string) => Promise<SoundEntry | null>;
-  onAddToLibraryTrimmed: (filePath: string, displayName: string, startMs: number, endMs: number) => Promise<SoundEntry | null>;
+  onAddToLibraryTrimmed: (filePath: string, displayName: string, trim: Trim) => Promise<SoundEntry | null>;
 }
 
 export function KeyAssignmentView({
@@ -35,8 +45,18 @@ export function KeyAssignmentView({
   keymaps,
   connected,
   onKeycodeChange,
+  onCaptureKeycode,
+  onKeySourceChange,
+  onKeyLabelChange,
+  onKeyIconChange,
+  onKeySoundCooldownChange,
+  onKeySoundModeChange,
+  onKeyPressModeChange,
+  onKeySoundGainChange,
   soundLibrary,
+  playlists,
   keySounds,
+  keySoundGains,
   onSetKeySound,
   onPreviewLibrarySound,
   onGetDuration,
@@ -61,7 +81,7 @@ export function KeyAssignmentView({
     const soundId = keySounds[hwIndex];
     if (!soundId) return null;
     const entry = soundLibrary.find((e) => e.id === soundId);
-    return entry?.display_name ?? null;
+    return entry?.display_name ?? playlists.find((p) => p.id === soundId)?.name ?? null;
   });
 
   const handleKeyClick = (ledIndex: number) => {
@@ -105,13 +125,30 @@ export function KeyAssignmentView({
           keyIndex={selectedKey}
           currentKeycode={keymaps[matrixIndex]}
           onSave={(keycode) => onKeycodeChange(matrixIndex, keycode)}
+          onCapture={() => onCaptureKeycode(matrixIndex)}
+          source={keys[selectedKey]?.source ?? "Deck"}
+          onSourceChange={(source) => onKeySourceChange(selectedKey, source)}
+          pressMode={keys[selectedKey]?.press_mode ?? "Both"}
+          onPressModeChange={(mode) => onKeyPressModeChange(selectedKey, mode)}
+          label={keys[selectedKey]?.label ?? null}
+          icon={keys[selectedKey]?.icon ?? null}
+          onLabelChange={(label) => onKeyLabelChange(selectedKey, label)}
+          onIconChange={(icon) => onKeyIconChange(selectedKey, icon)}
           onClose={() => {
             setEditorOpen(false);
             setSelectedKey(null);
           }}
           soundLibrary={soundLibrary}
+          playlists={playlists}
           currentSoundId={keySounds[selectedKey] ?? null}
           onSoundChange={(soundId) => onSetKeySound(selectedKey, soundId)}
+          soundCooldownMs={keys[selectedKey]?.sound_cooldown_ms ?? 0}
+          dimOnCooldown={keys[selectedKey]?.dim_on_cooldown ?? false}
+          onSoundCooldownChange={(ms, dim) => onKeySoundCooldownChange(selectedKey, ms, dim)}
+          soundMode={keys[selectedKey]?.sound_mode ?? "OneShot"}
+          onSoundModeChange={(mode) => onKeySoundModeChange(selectedKey, mode)}
+          soundGainDb={keySoundGains[selectedKey] ?? 0}
+          onSoundGainChange={(db) => onKeySoundGainChange(selectedKey, db)}
           onPreviewSound={onPreviewLibrarySound}
           onAddSound={handleAddSoundFromDialog}
         />
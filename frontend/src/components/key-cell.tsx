@@ -22,8 +22,7 @@ export const KeyCell = memo(function KeyCell({
   keycodeLabel,
   soundName,
 }: KeyCellProps) {
-  const keySlot = config.active_slot ?? "A";
-  const activeColor = keySlot === "A" ? config.slot_a : config.slot_b;
+  const activeColor = (config.slots[config.active_slot] ?? config.slots[0]).color;
   const bgColor = hsvToRgb(activeColor.h, activeColor.s, activeColor.v);
 
   const isColorMode = mode === "color";
@@ -48,7 +47,13 @@ export const KeyCell = memo(function KeyCell({
           boxShadow: "inset 0 1px 0 rgba(255,255,255,0.05), inset 0 -1px 2px rgba(0,0,0,0.3)",
         }}
         onClick={onClick}
+        title={config.label ?? undefined}
       >
+        {(config.icon || config.label) && (
+          <span className="absolute top-1 left-0 right-0 px-1.5 font-clean text-[8px] text-white/35 truncate text-center">
+            {config.icon} {config.label}
+          </span>
+        )}
         <span className="font-pixel text-[13px] text-white/80 leading-tight text-center px-1.5 truncate max-w-full">
           {keycodeLabel || "\u2014"}
         </span>
@@ -85,6 +90,13 @@ export const KeyCell = memo(function KeyCell({
           : {}),
       }}
       onClick={onClick}
-    />
+      title={config.label ?? undefined}
+    >
+      {config.icon && (
+        <span className="absolute top-1 left-1.5 text-[11px] drop-shadow-[0_1px_2px_rgba(0,0,0,0.8)]">
+          {config.icon}
+        </span>
+      )}
+    </button>
   );
 });
@@ -9,6 +9,7 @@ import {
   DialogDescription,
 } from "@/components/ui/dialog";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
+import { Slider } from "@/components/ui/slider";
 import {
   keycodeToLabel,
   getKeycodesByCategory,
@@ -20,19 +21,40 @@ import {
   type KeycodeDef,
 } from "@/lib/keycodes";
 import { cn } from "@/lib/utils";
-import { Trash2, Play, Music, Plus, Volume2 } from "lucide-react";
+import { Trash2, Play, Music, Plus, Volume2, Keyboard, ListMusic } from "lucide-react";
 import { isInternalKeycode } from "@/lib/tauri";
-import type { SoundEntry } from "@/lib/tauri";
+import type { KeySource, Playlist, PressMode, SoundEntry, SoundMode } from "@/lib/tauri";
 
 interface KeyEditorDialogProps {
   open: boolean;
   keyIndex: number;
   currentKeycode: number;
   onSave: (keycode: number) => void;
+  /** Bind the next chord pressed on any keyboard (backend capture). */
+  onCapture: () => void;
   onClose: () => void;
+  source: KeySource;
+  onSourceChange: (source: KeySource) => void;
+  pressMode: PressMode;
+  onPressModeChange: (mode: PressMode) => void;
+  label: string | null;
+  icon: string | null;
+  onLabelChange: (label: string | null) => void;
+  onIconChange: (icon: string | null) => void;
   soundLibrary: SoundEntry[];
+  playlists: Playlist[];
+  /** A sound or playlist id. */
   currentSoundId: string | null;
   onSoundChange: (soundId: string | null) => void;
+  /** The sound doesn't retrigger within this many ms (0 = off). */
+  soundCooldownMs: number;
+  dimOnCooldown: boolean;
+  onSoundCooldownChange: (cooldownMs: number, dim: boolean) => void;
+  soundMode: SoundMode;
+  onSoundModeChange: (mode: SoundMode) => void;
+  /** Volume offset for this key's sound, in dB. */
+  soundGainDb: number;
+  onSoundGainChange: (gainDb: number) => void;
   onPreviewSound: (soundId: string) => void;
   onAddSound: () => void;
 }
@@ -42,10 +64,27 @@ export function KeyEditorDialog({
   keyIndex,
   currentKeycode,
   onSave,
+  onCapture,
   onClose,
+  source,
+  onSourceChange,
+  pressMode,
+  onPressModeChange,
+  label,
+  icon,
+  onLabelChange,
+  onIconChange,
   soundLibrary,
+  playlists,
   currentSoundId,
   onSoundChange,
+  soundCooldownMs,
+  dimOnCooldown,
+  onSoundCooldownChange,
+  soundMode,
+  onSoundModeChange,
+  soundGainDb,
+  onSoundGainChange,
   onPreviewSound,
   onAddSound,
 }: KeyEditorDialogProps) {
@@ -66,6 +105,8 @@ export function KeyEditorDialog({
   useEffect(() => {
     if (!open || outerTab !== "shortcut") return;
     const handler = (e: KeyboardEvent) => {
+      // Let the label/icon fields receive their own typing
+      if (e.target instanceof HTMLInputElement) return;
       e.preventDefault();
       e.stopPropagation();
       const kc = keyEventToKeycode(e);
@@ -98,20 +139,73 @@ export function KeyEditorDialog({
     [newKeycode],
   );
 
-  const currentSoundName = soundLibrary.find((e) => e.id === currentSoundId)?.display_name ?? null;
+  // Label / icon drafts, committed on blur or Enter
+  const [labelInput, setLabelInput] = useState(label ?? "");
+  const [iconInput, setIconInput] = useState(icon ?? "");
+  useEffect(() => setLabelInput(label ?? ""), [label]);
+  useEffect(() => setIconInput(icon ?? ""), [icon]);
+
+  const commitLabel = () => {
+    if (labelInput.trim() !== (label ?? "")) onLabelChange(labelInput.trim() || null);
+  };
+  const commitIcon = () => {
+    if (iconInput.trim() !== (icon ?? "")) onIconChange(iconInput.trim() || null);
+  };
+
+  // Cooldown draft in seconds, committed on blur or Enter
+  const [gainDb, setGainDb] = useState(soundGainDb);
+  useEffect(() => setGainDb(soundGainDb), [soundGainDb]);
+  const [cooldownInput, setCooldownInput] = useState(String(soundCooldownMs / 1000));
+  useEffect(() => setCooldownInput(String(soundCooldownMs / 1000)), [soundCooldownMs]);
+  const commitCooldown = () => {
+    const ms = Math.max(0, Math.round(Number(cooldownInput) * 1000)) || 0;
+    if (ms !== soundCooldownMs) onSoundCooldownChange(ms, dimOnCooldown);
+    else setCooldownInput(String(soundCooldownMs / 1000));
+  };
+
+  const currentPlaylist = playlists.find((p) => p.id === currentSoundId);
+  const currentSoundName =
+    soundLibrary.find((e) => e.id === currentSoundId)?.display_name ?? currentPlaylist?.name ?? null;
 
   return (
     <Dialog open={open} onOpenChange={(v) => !v && onClose()}>
       <DialogContent className="max-w-md bg-[#111113] border-white/12 animate-scale-in">
         <DialogHeader>
           <DialogTitle className="text-sm font-bold">
-            Edit Key {keyIndex + 1}
+            Edit {label ? `${icon ? `${icon} ` : ""}${label}` : `Key ${keyIndex + 1}`}
           </DialogTitle>
           <DialogDescription className="text-xs text-white/40">
             Configure shortcut or sound for this key.
           </DialogDescription>
         </DialogHeader>
 
+        {/* Label + icon */}
+        <div className="flex gap-1.5">
+          <input
+            type="text"
+            value={iconInput}
+            maxLength={8}
+            placeholder="🙂"
+            onChange={(e) => setIconInput(e.target.value)}
+            onBlur={commitIcon}
+            onKeyDown={(e) => e.key === "Enter" && commitIcon()}
+            className="w-10 px-2 py-1.5 rounded-lg text-center text-[12px] bg-[#0d0d0f] border border-white/[0.08] outline-none focus:border-white/20"
+            title="Icon (emoji or symbol)"
+          />
+          <input
+            type="text"
+            value={labelInput}
+            maxLength={32}
+            placeholder={`Key ${keyIndex + 1}`}
+            onChange={(e) => setLabelInput(e.target.value)}
+            onBlur={commitLabel}
+            onKeyDown={(e) => e.key === "Enter" && commitLabel()}
+            className="flex-1 min-w-0 px-2 py-1.5 rounded-lg text-[11px] text-white/70 placeholder:text-white/20 bg-[#0d0d0f] border border-white/[0.08] outline-none focus:border-white/20"
+            spellCheck={false}
+            title="Label"
+          />
+        </div>
+
         {/* Outer tabs: Shortcut | Sound */}
         <div className="flex gap-1 p-1 rounded-lg bg-[#0d0d0f] border border-white/[0.06]">
           <button
@@ -172,6 +266,44 @@ export function KeyEditorDialog({
               ))}
             </div>
 
+            {/* Source: only the Deck-8, or any keyboard producing the same chord */}
+            <div className="flex gap-1.5 justify-center">
+              {([["Deck", "Deck-8 only"], ["Any", "Any keyboard"]] as const).map(([value, label]) => (
+                <button
+                  key={value}
+                  type="button"
+                  className={cn(
+                    "px-3 py-1.5 rounded-lg text-[10px] font-bold border transition-all duration-100",
+                    source === value
+                      ? "bg-white text-black border-white/40"
+                      : "bg-transparent text-white/35 border-white/10 hover:border-white/25 hover:text-white/60",
+                  )}
+                  onClick={() => onSourceChange(value)}
+                >
+                  {label}
+                </button>
+              ))}
+            </div>
+
+            {/* On press: flip the color, run the sound/scene/app toggle, or both */}
+            <div className="flex gap-1.5 justify-center">
+              {([["Both", "Color + action"], ["ActionOnly", "Action only"], ["LedOnly", "Color only"]] as const).map(([value, label]) => (
+                <button
+                  key={value}
+                  type="button"
+                  className={cn(
+                    "px-3 py-1.5 rounded-lg text-[10px] font-bold border transition-all duration-100",
+                    pressMode === value
+                      ? "bg-white text-black border-white/40"
+                      : "bg-transparent text-white/35 border-white/10 hover:border-white/25 hover:text-white/60",
+                  )}
+                  onClick={() => onPressModeChange(value)}
+                >
+                  {label}
+                </button>
+              ))}
+            </div>
+
             {/* Virtual keyboard */}
             <Tabs value={category} onValueChange={(v) => setCategory(v as KeycodeCategory)}>
               <TabsList className="w-full bg-[#1a1a1e] border border-white/10">
@@ -208,6 +340,16 @@ export function KeyEditorDialog({
                 Clear
               </Button>
               <div className="flex gap-2">
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="text-xs text-white/40 gap-1.5"
+                  title="Bind the next key combination pressed on any keyboard"
+                  onClick={() => { onCapture(); onClose(); }}
+                >
+                  <Keyboard className="w-3 h-3" />
+                  Capture
+                </Button>
                 <Button variant="ghost" size="sm" className="text-xs text-white/40" onClick={onClose}>
                   Cancel
                 </Button>
@@ -236,6 +378,73 @@ export function KeyEditorDialog({
                 </div>
               )}
 
+              {/* Cooldown: no retrigger within N seconds */}
+              {currentSoundId && (
+                <div className="flex items-center gap-2 px-3 py-1.5 rounded-lg border border-white/[0.06] font-clean text-[10px] text-white/40">
+                  <span>Cooldown</span>
+                  <input
+                    type="number"
+                    min={0}
+                    step={0.5}
+                    value={cooldownInput}
+                    onChange={(e) => setCooldownInput(e.target.value)}
+                    onBlur={commitCooldown}
+                    onKeyDown={(e) => e.key === "Enter" && commitCooldown()}
+                    className="w-14 px-1.5 py-0.5 rounded text-[10px] text-white/70 bg-[#0d0d0f] border border-white/[0.08] outline-none focus:border-white/20 tabular-nums"
+                    title="Seconds before the sound can play again (0 = off)"
+                  />
+                  <span>s</span>
+                  <label className="flex items-center gap-1.5 ml-auto cursor-pointer">
+                    <input
+                      type="checkbox"
+                      checked={dimOnCooldown}
+                      disabled={soundCooldownMs === 0}
+                      onChange={(e) => onSoundCooldownChange(soundCooldownMs, e.target.checked)}
+                      className="accent-cyan-400"
+                    />
+                    Dim LED while cooling down
+                  </label>
+                </div>
+              )}
+
+              {/* Volume offset: same clip, louder or quieter on this key */}
+              {currentSoundId && (
+                <div className="flex items-center gap-2 px-3 py-1.5 rounded-lg border border-white/[0.06] font-clean text-[10px] text-white/40">
+                  <Volume2 className="w-3 h-3 flex-shrink-0" />
+                  <span>Volume</span>
+                  <Slider
+                    min={-30}
+                    max={12}
+                    step={1}
+                    value={[gainDb]}
+                    onValueChange={([v]) => setGainDb(v)}
+                    onValueCommit={([v]) => onSoundGainChange(v)}
+                    onDoubleClick={() => onSoundGainChange(0)}
+                    className="flex-1"
+                    title="On top of the sound volume (double-click to reset)"
+                  />
+                  <span className="w-10 text-right tabular-nums text-white/60">
+                    {gainDb > 0 ? "+" : ""}{gainDb} dB
+                  </span>
+                </div>
+              )}
+
+              {/* Momentary: stops with a short fade when the key is released */}
+              {currentSoundId && !currentPlaylist && (
+                <label
+                  className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg border border-white/[0.06] font-clean text-[10px] text-white/40 cursor-pointer"
+                  title="Stops when the key is released (needs the soundboard running)"
+                >
+                  <input
+                    type="checkbox"
+                    checked={soundMode === "Momentary"}
+                    onChange={(e) => onSoundModeChange(e.target.checked ? "Momentary" : "OneShot")}
+                    className="accent-cyan-400"
+                  />
+                  Play while held
+                </label>
+              )}
+
               {/* None option */}
               <button
                 type="button"
@@ -286,6 +495,28 @@ export function KeyEditorDialog({
                 </div>
               ))}
 
+              {/* Playlists: pressing the key again skips ahead */}
+              {playlists.map((playlist) => (
+                <button
+                  key={playlist.id}
+                  type="button"
+                  className={cn(
+                    "flex items-center gap-2 px-3 py-2 rounded-lg border transition-all text-left",
+                    currentSoundId === playlist.id
+                      ? "border-cyan-500/20 bg-cyan-500/[0.06] text-cyan-300/80"
+                      : "border-white/[0.06] bg-white/[0.02] text-white/55 hover:bg-white/[0.04]",
+                  )}
+                  onClick={() => { onSoundChange(playlist.id); onClose(); }}
+                  title="Press again to skip to the next sound (needs the soundboard running)"
+                >
+                  <ListMusic className="w-3 h-3 flex-shrink-0 opacity-60" />
+                  <span className="font-clean text-[10px] truncate flex-1">{playlist.name}</span>
+                  <span className="font-clean text-[9px] text-white/25">
+                    {playlist.sounds.length} sound{playlist.sounds.length !== 1 ? "s" : ""}
+                  </span>
+                </button>
+              ))}
+
               {soundLibrary.length === 0 && (
                 <div className="flex flex-col items-center gap-2 py-4">
                   <Music className="w-5 h-5 text-white/10" />
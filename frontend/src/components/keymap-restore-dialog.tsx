@@ -0,0 +1,68 @@
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog";
+import { LED_TO_MATRIX } from "@/components/key-assignment-view";
+import { keycodeToLabel } from "@/lib/keycodes";
+import { isInternalKeycode } from "@/lib/tauri";
+import type { KeyConfig } from "@/lib/tauri";
+
+interface KeymapRestoreDialogProps {
+  /** Last-known keymaps (matrix order); the dialog is open while this is set. */
+  saved: number[] | null;
+  /** Current device keymaps (matrix order). */
+  device: number[];
+  keys: KeyConfig[];
+  onRestore: () => void;
+  onKeepDevice: () => void;
+}
+
+const label = (kc: number) => (isInternalKeycode(kc) ? "sound only" : keycodeToLabel(kc));
+
+/** Shown on connect when the device's keymap differs from the last-known one (keymap reset, reflash…). */
+export function KeymapRestoreDialog({ saved, device, keys, onRestore, onKeepDevice }: KeymapRestoreDialogProps) {
+  // One row per differing key, in LED (on-screen) order
+  const changed = saved
+    ? LED_TO_MATRIX.map((m, led) => ({ led, m })).filter(({ m }) => saved[m] !== device[m])
+    : [];
+
+  return (
+    <AlertDialog open={saved !== null}>
+      <AlertDialogContent className="bg-[#111113] border-white/12">
+        <AlertDialogHeader>
+          <AlertDialogTitle className="text-sm">Restore your key binds?</AlertDialogTitle>
+          <AlertDialogDescription className="text-xs text-white/40">
+            The device's keymap doesn't match the one this app last saw — it may have been reset or reflashed.
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        <div className="flex flex-col gap-1 px-1">
+          {saved && changed.map(({ led, m }) => (
+            <div key={led} className="flex items-center gap-2 font-clean text-[10px]">
+              <span className="text-white/30 w-20 truncate">{keys[led]?.label ?? `Key ${led + 1}`}</span>
+              <span className="text-white/30">{label(device[m])}</span>
+              <span className="text-white/20">&rarr;</span>
+              <span className="text-white/70">{label(saved[m])}</span>
+            </div>
+          ))}
+        </div>
+        <AlertDialogFooter>
+          <AlertDialogCancel className="text-xs h-8" onClick={onKeepDevice}>
+            Keep device
+          </AlertDialogCancel>
+          <AlertDialogAction
+            className="text-xs h-8 bg-violet-500/20 text-violet-200 hover:bg-violet-500/30 border border-violet-500/20"
+            onClick={onRestore}
+          >
+            Restore my binds
+          </AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
@@ -0,0 +1,118 @@
+import { useCallback, useEffect, useState } from "react";
+import { HardDrive, RefreshCw } from "lucide-react";
+import { cn } from "@/lib/utils";
+import { getLibraryStats } from "@/lib/tauri";
+import type { LibraryStats, SoundEntry } from "@/lib/tauri";
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog";
+
+interface LibraryStorageProps {
+  library: SoundEntry[];
+  keySounds: (string | null)[];
+  quotaMb: number | null;
+  onClean: (removeUnused: boolean) => Promise<void>;
+  onQuotaChange: (mb: number | null) => void;
+}
+
+const mb = (bytes: number) => `${(bytes / 1_000_000).toFixed(1)} MB`;
+
+/** Disk use of the sounds folder, with cleanup of orphaned files and unused sounds. */
+export function LibraryStorage({ library, keySounds, quotaMb, onClean, onQuotaChange }: LibraryStorageProps) {
+  const [stats, setStats] = useState<LibraryStats | null>(null);
+  const [confirmOpen, setConfirmOpen] = useState(false);
+
+  const refresh = useCallback(() => {
+    getLibraryStats().then(setStats).catch(() => {});
+  }, []);
+
+  useEffect(refresh, [refresh, library, keySounds]);
+
+  if (!stats) return null;
+
+  const over = stats.quota_bytes !== null && stats.library_bytes >= stats.quota_bytes;
+  const junk = stats.orphans.length + stats.unused.length;
+
+  const clean = async (removeUnused: boolean) => {
+    await onClean(removeUnused);
+    refresh();
+  };
+
+  return (
+    <div className="flex flex-col gap-1 font-clean text-[10px]">
+      <div className="flex items-center gap-1.5">
+        <HardDrive className="w-3 h-3 text-white/20" />
+        <span className={cn("tabular-nums", over ? "text-amber-400/70" : "text-white/40")}>
+          {mb(stats.library_bytes)}
+          {stats.quota_bytes !== null && ` of ${mb(stats.quota_bytes)}`}
+        </span>
+        <button
+          type="button"
+          className="p-0.5 rounded hover:bg-white/10 text-white/20 hover:text-white/40 transition-colors"
+          onClick={refresh}
+          title="Refresh"
+        >
+          <RefreshCw className="w-2.5 h-2.5" />
+        </button>
+        <label className="flex items-center gap-1 ml-auto text-white/25" title="Imports are refused above this (empty: no limit)">
+          Quota
+          <input
+            type="number"
+            min={1}
+            value={quotaMb ?? ""}
+            placeholder="—"
+            onChange={(e) => onQuotaChange(e.target.value === "" ? null : Math.max(1, Number(e.target.value)))}
+            className="w-12 bg-transparent text-white/50 text-right tabular-nums outline-none border-b border-white/[0.08] focus:border-white/20"
+          />
+          MB
+        </label>
+      </div>
+      {(junk > 0 || stats.missing.length > 0) && (
+        <div className="flex items-center gap-2 text-white/30">
+          {stats.unused.length > 0 && <span>{stats.unused.length} unused ({mb(stats.unused_bytes)})</span>}
+          {stats.orphans.length > 0 && <span>{stats.orphans.length} orphaned file{stats.orphans.length !== 1 ? "s" : ""} ({mb(stats.orphan_bytes)})</span>}
+          {stats.missing.length > 0 && <span className="text-amber-400/60">{stats.missing.length} missing</span>}
+          {junk > 0 && (
+            <button
+              type="button"
+              className="ml-auto px-2 py-0.5 rounded border border-white/[0.08] text-white/40 hover:text-white/60 hover:bg-white/[0.04] transition-colors"
+              onClick={() => (stats.unused.length > 0 ? setConfirmOpen(true) : clean(false))}
+            >
+              Clean up
+            </button>
+          )}
+        </div>
+      )}
+
+      <AlertDialog open={confirmOpen} onOpenChange={setConfirmOpen}>
+        <AlertDialogContent className="bg-[#111113] border-white/12">
+          <AlertDialogHeader>
+            <AlertDialogTitle className="text-sm">Also delete unused sounds?</AlertDialogTitle>
+            <AlertDialogDescription className="text-xs text-white/40">
+              {stats.unused.length} sound{stats.unused.length !== 1 ? "s aren't" : " isn't"} assigned to any key
+              ({mb(stats.unused_bytes)}). Orphaned files are removed either way.
+            </AlertDialogDescription>
+          </AlertDialogHeader>
+          <AlertDialogFooter>
+            <AlertDialogCancel className="text-xs h-8" onClick={() => stats.orphans.length > 0 && clean(false)}>
+              Keep Them
+            </AlertDialogCancel>
+            <AlertDialogAction
+              className="text-xs h-8 bg-red-500/20 text-red-300 hover:bg-red-500/30 border border-red-500/20"
+              onClick={() => clean(true)}
+            >
+              Delete Unused
+            </AlertDialogAction>
+          </AlertDialogFooter>
+        </AlertDialogContent>
+      </AlertDialog>
+    </div>
+  );
+}
@@ -0,0 +1,69 @@
+import { useEffect, useState } from "react";
+import { Lamp } from "lucide-react";
+import type { LightScene, LightTarget } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface LightSceneEditorProps {
+  targets: LightTarget[];
+  scene: LightScene | null;
+  onChange: (scene: LightScene | null) => void;
+}
+
+const fieldClass = cn(
+  "w-full px-2 py-1 rounded-md text-[10px] font-clean",
+  "bg-[#0d0d0f] border border-white/[0.08] text-white/60",
+  "focus:outline-none focus:border-violet-500/30",
+);
+
+/** Lighting scene a key recalls on each press. Edits apply on "Apply". */
+export function LightSceneEditor({ targets, scene, onChange }: LightSceneEditorProps) {
+  const [draft, setDraft] = useState<LightScene | null>(scene);
+
+  useEffect(() => {
+    setDraft(scene);
+  }, [scene]);
+
+  // Nothing to pick from until a light is set up in Settings
+  if (targets.length === 0 && !scene) return null;
+
+  const target = targets.find((t) => t.name === draft?.target);
+  const dirty = JSON.stringify(draft) !== JSON.stringify(scene);
+
+  return (
+    <div className="flex flex-col gap-1.5 rounded-lg border border-white/[0.06] px-3 py-2">
+      <div className="flex items-center gap-1.5">
+        <Lamp className="w-3 h-3 text-white/20" />
+        <span className="text-[10px] font-medium text-white/30">Light scene</span>
+        <select
+          className={cn(fieldClass, "ml-auto w-auto appearance-none cursor-pointer")}
+          value={draft?.target ?? ""}
+          onChange={(e) => setDraft(e.target.value ? { target: e.target.value, scene: draft?.scene ?? "" } : null)}
+        >
+          <option value="">Off</option>
+          {targets.map((t) => (
+            <option key={t.name} value={t.name}>{t.name}</option>
+          ))}
+        </select>
+      </div>
+
+      {draft && (
+        <input
+          className={fieldClass}
+          value={draft.scene}
+          placeholder={target?.kind === "Wled" ? "Preset number" : "Hue scene id"}
+          onChange={(e) => setDraft({ ...draft, scene: e.target.value })}
+        />
+      )}
+
+      {dirty && (
+        <button
+          type="button"
+          className="self-end px-2 py-0.5 rounded-md text-[10px] font-medium text-violet-300/70 hover:bg-violet-500/[0.08] transition-colors"
+          onClick={() => onChange(draft)}
+        >
+          Apply
+        </button>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,177 @@
+import { useEffect, useState } from "react";
+import { Lamp, Link2, Plus, Trash2 } from "lucide-react";
+import type { LightingSettings, LightSource, LightTarget } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface LightingCardProps {
+  value: LightingSettings;
+  onChange: (lighting: LightingSettings) => Promise<boolean>;
+  /** Resolves to the bridge's app key, or null if pairing failed. */
+  onPairHue: (bridge: string) => Promise<string | null>;
+}
+
+const fieldClass =
+  "min-w-0 px-2 py-1 rounded bg-[#0d0d0f] border border-white/[0.08] text-white/60 outline-none focus:border-cyan-500/30 placeholder:text-white/15";
+
+function sourceValue(source: LightSource | null): string {
+  if (!source) return "";
+  return source.kind === "Matrix" ? "matrix" : String(source.key_index);
+}
+
+function parseSource(value: string): LightSource | null {
+  if (value === "") return null;
+  if (value === "matrix") return { kind: "Matrix" };
+  return { kind: "Key", key_index: Number(value) };
+}
+
+/** Hue / WLED lights that mirror a key or the RGB matrix. Edits apply on "Apply". */
+export function LightingCard({ value, onChange, onPairHue }: LightingCardProps) {
+  const [draft, setDraft] = useState(value);
+  const [pairing, setPairing] = useState<number | null>(null);
+  useEffect(() => setDraft(value), [value]);
+
+  const dirty = JSON.stringify(draft) !== JSON.stringify(value);
+  const active = draft.mirror !== null && draft.targets.length > 0;
+
+  const updateTarget = (index: number, patch: Partial<LightTarget>) => {
+    setDraft({
+      ...draft,
+      targets: draft.targets.map((t, i) => (i === index ? ({ ...t, ...patch } as LightTarget) : t)),
+    });
+  };
+
+  const addTarget = (kind: LightTarget["kind"]) => {
+    const name = `${kind === "Hue" ? "Hue" : "WLED"} ${draft.targets.length + 1}`;
+    const target: LightTarget = kind === "Hue"
+      ? { kind, name, bridge: "", user: "", lights: [] }
+      : { kind, name, host: "" };
+    setDraft({ ...draft, targets: [...draft.targets, target] });
+  };
+
+  const pair = async (index: number, bridge: string) => {
+    setPairing(index);
+    const user = await onPairHue(bridge);
+    setPairing(null);
+    if (user) updateTarget(index, { user });
+  };
+
+  return (
+    <div
+      className={cn(
+        "flex flex-col gap-2 px-3.5 py-3 rounded-xl border transition-all duration-150",
+        active ? "border-emerald-500/20 bg-emerald-500/[0.04]" : "border-white/[0.06] bg-white/[0.02]",
+      )}
+    >
+      <div className="flex items-center gap-3">
+        <div className={cn(
+          "flex items-center justify-center w-7 h-7 rounded-lg",
+          active ? "bg-emerald-500/15 text-emerald-400" : "bg-white/[0.06] text-white/25",
+        )}>
+          <Lamp className="w-3.5 h-3.5" />
+        </div>
+        <div className="flex-1 min-w-0">
+          <div className="font-clean text-[11px] text-white/80 font-medium">Desk lights</div>
+          <div className="font-clean text-[9px] text-white/30 mt-0.5">
+            Philips Hue or WLED lights follow a key's color; keys can also recall their scenes (set in the color editor)
+          </div>
+        </div>
+        <select
+          className={cn(fieldClass, "font-clean text-[10px] appearance-none cursor-pointer")}
+          value={sourceValue(draft.mirror)}
+          onChange={(e) => setDraft({ ...draft, mirror: parseSource(e.target.value) })}
+          aria-label="Mirror"
+        >
+          <option value="">Don't mirror</option>
+          {Array.from({ length: 8 }, (_, i) => (
+            <option key={i} value={i}>Key {i + 1}</option>
+          ))}
+          <option value="matrix">RGB matrix</option>
+        </select>
+      </div>
+
+      {draft.targets.map((t, i) => (
+        <div key={i} className="flex items-center gap-1.5 pl-10 font-clean text-[10px]">
+          <span className="w-10 text-white/40">{t.kind === "Hue" ? "Hue" : "WLED"}</span>
+          <input
+            className={cn(fieldClass, "w-20")}
+            value={t.name}
+            placeholder="Name"
+            onChange={(e) => updateTarget(i, { name: e.target.value })}
+          />
+          {t.kind === "Hue" ? (
+            <>
+              <input
+                className={cn(fieldClass, "flex-1")}
+                value={t.bridge}
+                placeholder="Bridge IP"
+                onChange={(e) => updateTarget(i, { bridge: e.target.value })}
+              />
+              <input
+                className={cn(fieldClass, "w-20")}
+                value={t.lights.join(",")}
+                placeholder="Lights 1,2"
+                title="Light ids that mirror the color"
+                onChange={(e) => updateTarget(i, {
+                  lights: e.target.value.split(",").map((l) => l.trim()).filter(Boolean),
+                })}
+              />
+              <button
+                type="button"
+                className={cn(
+                  "flex items-center gap-1 px-2 py-1 rounded-md border transition-colors disabled:opacity-30",
+                  t.user
+                    ? "border-emerald-500/20 text-emerald-300/70"
+                    : "border-white/[0.08] text-white/50 hover:bg-white/[0.04]",
+                )}
+                disabled={!t.bridge.trim() || pairing !== null}
+                title="Press the bridge's link button, then pair"
+                onClick={() => pair(i, t.bridge)}
+              >
+                <Link2 className="w-3 h-3" />
+                {pairing === i ? "Pairing…" : t.user ? "Paired" : "Pair"}
+              </button>
+            </>
+          ) : (
+            <input
+              className={cn(fieldClass, "flex-1")}
+              value={t.host}
+              placeholder="WLED address"
+              onChange={(e) => updateTarget(i, { host: e.target.value })}
+            />
+          )}
+          <button
+            type="button"
+            className="p-1 rounded hover:bg-white/[0.06] transition-colors"
+            title="Remove light"
+            onClick={() => setDraft({ ...draft, targets: draft.targets.filter((_, j) => j !== i) })}
+          >
+            <Trash2 className="w-3 h-3 text-white/30" />
+          </button>
+        </div>
+      ))}
+
+      <div className="flex items-center gap-1.5 pl-10 font-clean text-[10px]">
+        {(["Hue", "Wled"] as const).map((kind) => (
+          <button
+            key={kind}
+            type="button"
+            className="flex items-center gap-1 px-2 py-1 rounded-md border border-white/[0.08] text-white/50 hover:bg-white/[0.04] hover:text-white/70 transition-colors"
+            onClick={() => addTarget(kind)}
+          >
+            <Plus className="w-3 h-3" />
+            {kind === "Hue" ? "Hue bridge" : "WLED"}
+          </button>
+        ))}
+        {dirty && (
+          <button
+            type="button"
+            className="ml-auto px-2 py-0.5 rounded-md font-medium text-violet-300/70 hover:bg-violet-500/[0.08] transition-colors"
+            onClick={() => onChange(draft)}
+          >
+            Apply
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
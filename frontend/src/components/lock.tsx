@@ -0,0 +1,75 @@
+import { useState } from "react";
+import { Lock, LockOpen } from "lucide-react";
+import { cn } from "@/lib/utils";
+
+const fieldClass = "min-w-0 px-1.5 py-0.5 rounded bg-[#0d0d0f] border border-white/[0.08] font-clean text-[10px] text-white/60 outline-none focus:border-cyan-500/30 placeholder:text-white/15";
+const buttonClass = "px-2 py-1 rounded-md font-clean text-[10px] text-white/50 border border-white/[0.08] hover:bg-white/[0.04] hover:text-white/70 transition-colors disabled:opacity-40";
+
+function PinField({ value, onChange, onSubmit }: {
+  value: string;
+  onChange: (pin: string) => void;
+  onSubmit: () => void;
+}) {
+  return (
+    <input
+      type="password"
+      inputMode="numeric"
+      autoComplete="off"
+      maxLength={8}
+      value={value}
+      placeholder="PIN"
+      onChange={(e) => onChange(e.target.value.replace(/\D/g, ""))}
+      onKeyDown={(e) => e.key === "Enter" && onSubmit()}
+      className={cn(fieldClass, "w-20 tabular-nums tracking-widest")}
+    />
+  );
+}
+
+/** Shown while the setup is locked: what still works, and a PIN to unlock. */
+export function LockBanner({ onUnlock }: { onUnlock: (pin: string) => Promise<boolean> }) {
+  const [pin, setPin] = useState("");
+  const unlock = () => {
+    if (pin.length < 4) return;
+    onUnlock(pin).then(() => setPin(""));
+  };
+
+  return (
+    <div className="flex items-center gap-2 px-4 py-1.5 border-b border-amber-500/15 bg-amber-500/[0.04]">
+      <Lock className="w-3 h-3 text-amber-400/70" />
+      <span className="flex-1 font-clean text-[10px] text-white/50">
+        Settings are locked. Keys, modes and sounds still work.
+      </span>
+      <PinField value={pin} onChange={setPin} onSubmit={unlock} />
+      <button type="button" className={buttonClass} disabled={pin.length < 4} onClick={unlock}>
+        Unlock
+      </button>
+    </div>
+  );
+}
+
+/** Lock colors, keymaps, sounds and settings behind a PIN (kiosks, shared streaming PCs). */
+export function LockCard({ onLock }: { onLock: (pin: string) => Promise<boolean> }) {
+  const [pin, setPin] = useState("");
+  const lock = () => {
+    if (pin.length < 4) return;
+    onLock(pin).then((locked) => locked && setPin(""));
+  };
+
+  return (
+    <div className="flex items-center gap-3 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
+      <div className="flex items-center justify-center w-7 h-7 rounded-lg bg-white/[0.06] text-white/25">
+        <LockOpen className="w-3.5 h-3.5" />
+      </div>
+      <div className="flex-1 min-w-0">
+        <div className="font-clean text-[11px] text-white/80 font-medium">Lock settings</div>
+        <div className="font-clean text-[9px] text-white/30 mt-0.5">
+          Operators can use the keys but not rebind them. Unlocking needs the PIN (4–8 digits)
+        </div>
+      </div>
+      <PinField value={pin} onChange={setPin} onSubmit={lock} />
+      <button type="button" className={buttonClass} disabled={pin.length < 4} onClick={lock}>
+        Lock
+      </button>
+    </div>
+  );
+}
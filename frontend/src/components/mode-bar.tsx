@@ -0,0 +1,125 @@
+import { useState } from "react";
+import { Save, Volume2, VolumeX, Volume, X } from "lucide-react";
+import type { Mode } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface ModeBarProps {
+  modes: Mode[];
+  activeMode: string | null;
+  onSave: (name: string, soundsMuted: boolean | null, base: string | null) => void;
+  onSet: (name: string) => void;
+  onDelete: (name: string) => void;
+}
+
+/** Key sounds setting saved with a mode: leave alone → mute → unmute. */
+const NEXT_SOUNDS = new Map<boolean | null, boolean | null>([
+  [null, true],
+  [true, false],
+  [false, null],
+]);
+
+const SOUNDS_TITLE = (muted: boolean | null) =>
+  muted === null ? "Leaves key sounds as they are" : muted ? "Mutes key sounds" : "Unmutes key sounds";
+
+/**
+ * Named modes: save every key's current slot under a name, then switch all of
+ * them at once from here, the tray or a key following "Mode active". Keys
+ * following another condition aren't saved. A mode saved "on" another only
+ * keeps the keys that differ from it and follows the rest.
+ */
+export function ModeBar({ modes, activeMode, onSave, onSet, onDelete }: ModeBarProps) {
+  const [name, setName] = useState("");
+  const [soundsMuted, setSoundsMuted] = useState<boolean | null>(null);
+  const [base, setBase] = useState<string | null>(null);
+
+  const handleSave = () => {
+    if (!name.trim()) return;
+    onSave(name.trim(), soundsMuted, base);
+    setName("");
+    setSoundsMuted(null);
+    setBase(null);
+  };
+
+  const SoundsIcon = soundsMuted === null ? Volume : soundsMuted ? VolumeX : Volume2;
+
+  return (
+    <div className="relative z-[1] flex flex-col gap-1.5 px-5 pb-3">
+      {modes.length > 0 && (
+        <div className="flex flex-wrap gap-1.5">
+          {modes.map((m) => (
+            <div
+              key={m.name}
+              className={cn(
+                "group flex items-center gap-1 pl-1.5 pr-1 py-1 rounded-md border transition-colors",
+                m.name === activeMode ? "border-violet-400/30 bg-violet-500/[0.06]" : "border-white/[0.06] hover:border-white/15",
+              )}
+            >
+              <button
+                type="button"
+                onClick={() => onSet(m.name)}
+                aria-pressed={m.name === activeMode}
+                title={m.base ? `Switch to "${m.name}" (built on "${m.base}")` : `Switch to "${m.name}"`}
+              >
+                <span className={cn("font-clean text-[9px] max-w-[80px] truncate", m.name === activeMode ? "text-violet-200/70" : "text-white/40")}>
+                  {m.name}
+                </span>
+                {m.base && <span className="font-clean text-[8px] text-white/20"> ← {m.base}</span>}
+              </button>
+              <button
+                type="button"
+                className="opacity-0 group-hover:opacity-100 transition-opacity"
+                onClick={() => onDelete(m.name)}
+                title="Delete mode"
+              >
+                <X className="w-2.5 h-2.5 text-white/25 hover:text-white/50" />
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+      <div className="flex items-center gap-1.5">
+        <input
+          type="text"
+          value={name}
+          maxLength={32}
+          placeholder="Save current slots as a mode…"
+          onChange={(e) => setName(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") handleSave();
+          }}
+          className="flex-1 min-w-0 bg-transparent font-clean text-[9px] text-white/40 placeholder:text-white/15 outline-none border-b border-transparent focus:border-white/15 transition-colors"
+          spellCheck={false}
+        />
+        {modes.length > 0 && (
+          <select
+            value={base ?? ""}
+            onChange={(e) => setBase(e.target.value || null)}
+            className="max-w-[90px] bg-transparent font-clean text-[9px] text-white/30 outline-none cursor-pointer"
+            title="Build on another mode: only the keys that differ from it are saved"
+          >
+            <option value="">No base</option>
+            {modes.map((m) => (
+              <option key={m.name} value={m.name}>On {m.name}</option>
+            ))}
+          </select>
+        )}
+        <button
+          type="button"
+          className="p-1 rounded hover:bg-white/[0.06] transition-colors"
+          onClick={() => setSoundsMuted((prev) => NEXT_SOUNDS.get(prev) ?? null)}
+          title={SOUNDS_TITLE(soundsMuted)}
+        >
+          <SoundsIcon className={cn("w-3 h-3", soundsMuted === null ? "text-white/20" : "text-white/50")} />
+        </button>
+        <button
+          type="button"
+          className={cn("p-1 rounded hover:bg-white/[0.06] transition-colors", !name.trim() && "opacity-30 pointer-events-none")}
+          onClick={handleSave}
+          title="Save mode"
+        >
+          <Save className="w-3 h-3 text-white/30" />
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,41 @@
+import { useEffect, useState } from "react";
+import { Music, Pause, Play } from "lucide-react";
+import { getNowPlaying, onNowPlaying } from "@/lib/tauri";
+import type { NowPlaying as Track } from "@/lib/tauri";
+
+/** Track and play state of the OS media session; hidden when nothing is playing or paused. */
+export function NowPlaying() {
+  const [track, setTrack] = useState<Track | null>(null);
+
+  useEffect(() => {
+    getNowPlaying().then(setTrack).catch(() => {});
+    const unlisten = onNowPlaying(setTrack);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  if (!track) return null;
+
+  const StateIcon = track.playing ? Play : Pause;
+  return (
+    <div
+      className="flex items-center gap-2 mx-5 mt-3 px-3 py-2 rounded-lg bg-white/[0.02] border border-white/[0.06]"
+      title={track.player}
+    >
+      <Music className="w-3.5 h-3.5 text-violet-400/60 shrink-0" />
+      <div className="flex-1 min-w-0">
+        <div className="font-clean text-[11px] text-white/70 truncate">{track.title || "Unknown track"}</div>
+        {(track.artist || track.album) && (
+          <div className="font-clean text-[9px] text-white/30 truncate">
+            {[track.artist, track.album].filter(Boolean).join(" — ")}
+          </div>
+        )}
+      </div>
+      <StateIcon
+        className="w-3 h-3 text-white/40 shrink-0"
+        aria-label={track.playing ? "Playing" : "Paused"}
+      />
+    </div>
+  );
+}
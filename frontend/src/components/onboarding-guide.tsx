@@ -0,0 +1,94 @@
+import { Check, Keyboard, Music, Plug, Volume2, X } from "lucide-react";
+import type { OnboardingStep } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface OnboardingGuideProps {
+  step: OnboardingStep;
+  connected: boolean;
+  onConnect: () => void;
+  /** Switch to the tab where the step is done. */
+  onOpenTab: (tab: string) => void;
+  onSkip: () => void;
+  onFinish: () => void;
+}
+
+const STEPS: { step: Exclude<OnboardingStep, "Done">; icon: typeof Plug; title: string; hint: string; tab?: string }[] = [
+  { step: "Device", icon: Plug, title: "Connect your Deck-8", hint: "Plug it in over USB, then connect" },
+  { step: "TestKey", icon: Keyboard, title: "Press a key", hint: "Press any key on the Deck-8 and watch it change color" },
+  { step: "Audio", icon: Volume2, title: "Pick your audio devices", hint: "Choose your mic and the virtual cable the soundboard plays into", tab: "sound" },
+  { step: "FirstSound", icon: Music, title: "Add a first sound", hint: "Import a sound into the library, then assign it to a key", tab: "sound" },
+];
+
+/** First-run checklist; the backend moves it on as each step gets done. */
+export function OnboardingGuide({ step, connected, onConnect, onOpenTab, onSkip, onFinish }: OnboardingGuideProps) {
+  if (step === "Done") return null;
+  const current = STEPS.findIndex((s) => s.step === step);
+
+  return (
+    <div className="flex flex-col gap-2 mx-5 mt-3 px-3.5 py-3 rounded-xl border border-violet-500/20 bg-violet-500/[0.04]">
+      <div className="flex items-center gap-2">
+        <span className="font-pixel text-[11px] text-white/70 font-bold uppercase tracking-wider">Getting started</span>
+        <span className="font-clean text-[9px] text-white/30">{current + 1} of {STEPS.length}</span>
+        <button
+          type="button"
+          className="ml-auto p-1 rounded hover:bg-white/[0.06] transition-colors"
+          title="Skip setup (Settings can show it again)"
+          onClick={onFinish}
+        >
+          <X className="w-3 h-3 text-white/30" />
+        </button>
+      </div>
+
+      <ol className="flex flex-col gap-1.5">
+        {STEPS.map(({ step: s, icon: Icon, title, hint, tab }, i) => {
+          const done = i < current;
+          const active = i === current;
+          return (
+            <li key={s} className="flex items-center gap-2.5">
+              <div className={cn(
+                "flex items-center justify-center w-6 h-6 rounded-md shrink-0",
+                done ? "bg-emerald-500/15 text-emerald-400"
+                  : active ? "bg-violet-500/15 text-violet-300" : "bg-white/[0.04] text-white/20",
+              )}>
+                {done ? <Check className="w-3 h-3" /> : <Icon className="w-3 h-3" />}
+              </div>
+              <div className="flex-1 min-w-0">
+                <div className={cn("font-clean text-[11px]", active ? "text-white/80 font-medium" : "text-white/35")}>{title}</div>
+                {active && <div className="font-clean text-[9px] text-white/30 mt-0.5">{hint}</div>}
+              </div>
+              {active && (
+                <div className="flex items-center gap-1.5 font-clean text-[10px]">
+                  {s === "Device" && !connected && (
+                    <button
+                      type="button"
+                      className="px-2 py-1 rounded-md border border-violet-500/20 text-violet-300/80 hover:bg-violet-500/[0.08] transition-colors"
+                      onClick={onConnect}
+                    >
+                      Connect
+                    </button>
+                  )}
+                  {tab && (
+                    <button
+                      type="button"
+                      className="px-2 py-1 rounded-md border border-violet-500/20 text-violet-300/80 hover:bg-violet-500/[0.08] transition-colors"
+                      onClick={() => onOpenTab(tab)}
+                    >
+                      Open Sound tab
+                    </button>
+                  )}
+                  <button
+                    type="button"
+                    className="px-2 py-1 rounded-md text-white/35 hover:bg-white/[0.04] hover:text-white/60 transition-colors"
+                    onClick={onSkip}
+                  >
+                    Skip
+                  </button>
+                </div>
+              )}
+            </li>
+          );
+        })}
+      </ol>
+    </div>
+  );
+}
@@ -0,0 +1,170 @@
+import { useState } from "react";
+import { ChevronDown, ChevronUp, ListMusic, Plus, Repeat, SkipForward, Square, Trash2, X } from "lucide-react";
+import { cn } from "@/lib/utils";
+import type { Playlist, SoundEntry } from "@/lib/tauri";
+
+interface PlaylistEditorProps {
+  library: SoundEntry[];
+  playlists: Playlist[];
+  onSave: (playlist: Playlist) => Promise<boolean>;
+  onDelete: (playlistId: string) => void;
+  onSkip: () => void;
+  onStop: () => void;
+}
+
+const EMPTY: Playlist = { id: "", name: "", sounds: [], loop_last: false };
+
+/** Playlists of library sounds, played back to back by a key. */
+export function PlaylistEditor({ library, playlists, onSave, onDelete, onSkip, onStop }: PlaylistEditorProps) {
+  const [draft, setDraft] = useState<Playlist | null>(null);
+
+  const soundName = (id: string) => library.find((e) => e.id === id)?.display_name ?? "?";
+
+  const move = (from: number, to: number) => {
+    if (!draft || to < 0 || to >= draft.sounds.length) return;
+    const sounds = [...draft.sounds];
+    [sounds[from], sounds[to]] = [sounds[to], sounds[from]];
+    setDraft({ ...draft, sounds });
+  };
+
+  const save = async () => {
+    if (draft && (await onSave(draft))) setDraft(null);
+  };
+
+  return (
+    <div className="flex flex-col gap-1.5 font-clean text-[10px]">
+      <div className="flex items-center gap-1.5">
+        <ListMusic className="w-3 h-3 text-white/20" />
+        <span className="text-white/40">Playlists</span>
+        <button
+          type="button"
+          className="ml-auto p-1 rounded hover:bg-white/10 text-white/25 hover:text-white/50 transition-colors"
+          onClick={onSkip}
+          title="Skip to the playlist's next sound"
+        >
+          <SkipForward className="w-3 h-3" />
+        </button>
+        <button
+          type="button"
+          className="p-1 rounded hover:bg-white/10 text-white/25 hover:text-white/50 transition-colors"
+          onClick={onStop}
+          title="Stop the playlist"
+        >
+          <Square className="w-3 h-3" />
+        </button>
+        <button
+          type="button"
+          className="p-1 rounded hover:bg-white/10 text-white/25 hover:text-white/50 transition-colors"
+          onClick={() => setDraft(EMPTY)}
+          title="New playlist"
+        >
+          <Plus className="w-3 h-3" />
+        </button>
+      </div>
+
+      {playlists.map((playlist) => (
+        <div
+          key={playlist.id}
+          className="flex items-center gap-2 px-2.5 py-1.5 rounded-lg bg-white/[0.02] border border-white/[0.06] hover:bg-white/[0.04] transition-colors group"
+        >
+          <button
+            type="button"
+            className="text-white/60 truncate flex-1 text-left"
+            onClick={() => setDraft(playlist)}
+            title="Edit"
+          >
+            {playlist.name}
+          </button>
+          {playlist.loop_last && <Repeat className="w-3 h-3 text-white/25" />}
+          <span className="text-[9px] text-white/25">
+            {playlist.sounds.length} sound{playlist.sounds.length !== 1 ? "s" : ""}
+          </span>
+          <button
+            type="button"
+            className="p-1 rounded hover:bg-white/10 text-white/15 hover:text-red-400/70 transition-colors opacity-0 group-hover:opacity-100"
+            onClick={() => onDelete(playlist.id)}
+            title="Delete"
+          >
+            <Trash2 className="w-3 h-3" />
+          </button>
+        </div>
+      ))}
+
+      {draft && (
+        <div className="flex flex-col gap-1.5 p-2 rounded-lg border border-white/[0.08] bg-white/[0.02]">
+          <input
+            value={draft.name}
+            placeholder="Playlist name"
+            onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+            className="bg-transparent text-white/60 outline-none border-b border-white/[0.08] focus:border-white/20"
+          />
+          {draft.sounds.map((id, i) => (
+            <div key={`${id}-${i}`} className="flex items-center gap-1 text-white/50">
+              <span className="w-3 text-white/20 tabular-nums">{i + 1}</span>
+              <span className="truncate flex-1">{soundName(id)}</span>
+              <button type="button" className="p-0.5 text-white/20 hover:text-white/50" onClick={() => move(i, i - 1)} title="Earlier">
+                <ChevronUp className="w-3 h-3" />
+              </button>
+              <button type="button" className="p-0.5 text-white/20 hover:text-white/50" onClick={() => move(i, i + 1)} title="Later">
+                <ChevronDown className="w-3 h-3" />
+              </button>
+              <button
+                type="button"
+                className="p-0.5 text-white/20 hover:text-red-400/70"
+                onClick={() => setDraft({ ...draft, sounds: draft.sounds.filter((_, j) => j !== i) })}
+                title="Remove"
+              >
+                <X className="w-3 h-3" />
+              </button>
+            </div>
+          ))}
+          <select
+            className={cn(
+              "w-full px-2 py-1 rounded text-[10px]",
+              "bg-[#0d0d0f] border border-white/[0.08] text-white/40",
+              "focus:outline-none focus:border-cyan-500/30",
+              "appearance-none cursor-pointer",
+            )}
+            value=""
+            onChange={(e) => setDraft({ ...draft, sounds: [...draft.sounds, e.target.value] })}
+          >
+            <option value="" disabled>
+              Add a sound...
+            </option>
+            {library.map((entry) => (
+              <option key={entry.id} value={entry.id}>
+                {entry.display_name}
+              </option>
+            ))}
+          </select>
+          <div className="flex items-center gap-2">
+            <label className="flex items-center gap-1.5 text-white/40 cursor-pointer" title="The last sound repeats until skipped or stopped">
+              <input
+                type="checkbox"
+                checked={draft.loop_last}
+                onChange={(e) => setDraft({ ...draft, loop_last: e.target.checked })}
+                className="accent-cyan-400"
+              />
+              Loop the last sound
+            </label>
+            <button
+              type="button"
+              className="ml-auto px-2 py-0.5 rounded text-white/30 hover:text-white/50 transition-colors"
+              onClick={() => setDraft(null)}
+            >
+              Cancel
+            </button>
+            <button
+              type="button"
+              className="px-2 py-0.5 rounded border border-white/[0.08] text-white/50 hover:text-white/70 hover:bg-white/[0.04] transition-colors disabled:opacity-40"
+              onClick={save}
+              disabled={!draft.name.trim() || draft.sounds.length === 0}
+            >
+              Save
+            </button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
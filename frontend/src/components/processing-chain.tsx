@@ -0,0 +1,118 @@
+import { Fragment, useEffect, useState } from "react";
+import { ChevronDown, ChevronUp, SlidersHorizontal } from "lucide-react";
+import { cn } from "@/lib/utils";
+import type { AgcSettings, ProcessingStage, StageKind } from "@/lib/tauri";
+
+const STAGE_LABELS: Record<StageKind, { name: string; hint: string }> = {
+  Gate: { name: "Gate", hint: "Mutes the mic below speaking level" },
+  Denoise: { name: "Denoise", hint: "Pushes down steady background noise" },
+  Eq: { name: "EQ", hint: "Cuts rumble, adds a little presence" },
+  Agc: { name: "Auto Gain", hint: "Keeps your speaking level steady as you move" },
+  Compressor: { name: "Compressor", hint: "Evens out loud and quiet speech" },
+};
+
+interface ProcessingChainProps {
+  stages: ProcessingStage[];
+  onToggle: (kind: StageKind, enabled: boolean) => void;
+  onReorder: (order: StageKind[]) => void;
+  agc: AgcSettings;
+  onAgcChange: (agc: AgcSettings) => void;
+}
+
+/** A dB field that commits on blur or Enter. */
+function DbInput({ value, onCommit, label }: { value: number; onCommit: (db: number) => void; label: string }) {
+  const [text, setText] = useState(String(value));
+  useEffect(() => setText(String(value)), [value]);
+  const commit = () => {
+    const db = Number(text);
+    if (text.trim() !== "" && Number.isFinite(db) && db !== value) onCommit(db);
+    else setText(String(value));
+  };
+  return (
+    <input
+      type="number"
+      step={1}
+      value={text}
+      aria-label={label}
+      onChange={(e) => setText(e.target.value)}
+      onBlur={commit}
+      onKeyDown={(e) => e.key === "Enter" && commit()}
+      className="w-10 bg-transparent text-white/50 text-right tabular-nums outline-none border-b border-white/[0.08] focus:border-white/20"
+    />
+  );
+}
+
+/** Mic processing stages in order; sounds are mixed in after the last one. */
+export function ProcessingChain({ stages, onToggle, onReorder, agc, onAgcChange }: ProcessingChainProps) {
+  const move = (from: number, to: number) => {
+    const order = stages.map((s) => s.kind);
+    [order[from], order[to]] = [order[to], order[from]];
+    onReorder(order);
+  };
+
+  const arrowClass = "p-0.5 rounded text-white/25 hover:text-white/60 disabled:opacity-30 disabled:pointer-events-none";
+
+  return (
+    <div className="flex flex-col gap-1.5">
+      <div className="flex items-center gap-1.5">
+        <SlidersHorizontal className="w-3 h-3 text-white/20" />
+        <span className="font-pixel text-[9px] text-white/40 uppercase tracking-wider">Mic Processing</span>
+        <span className="font-clean text-[10px] text-white/25 ml-auto">then mixed with sounds</span>
+      </div>
+      {stages.map((stage, i) => (
+        <Fragment key={stage.kind}>
+          <div className="flex items-center gap-2">
+            <div className="flex flex-col">
+              <button type="button" className={arrowClass} disabled={i === 0} onClick={() => move(i, i - 1)} aria-label="Move up">
+                <ChevronUp className="w-3 h-3" />
+              </button>
+              <button
+                type="button"
+                className={arrowClass}
+                disabled={i === stages.length - 1}
+                onClick={() => move(i, i + 1)}
+                aria-label="Move down"
+              >
+                <ChevronDown className="w-3 h-3" />
+              </button>
+            </div>
+            <div className="flex flex-col flex-1 min-w-0">
+              <span className={cn("font-clean text-[10px]", stage.enabled ? "text-white/60" : "text-white/30")}>
+                {STAGE_LABELS[stage.kind].name}
+              </span>
+              <span className="font-clean text-[9px] text-white/25 truncate">{STAGE_LABELS[stage.kind].hint}</span>
+            </div>
+            <button
+              type="button"
+              role="switch"
+              aria-checked={stage.enabled}
+              aria-label={STAGE_LABELS[stage.kind].name}
+              onClick={() => onToggle(stage.kind, !stage.enabled)}
+            >
+              <div className={cn(
+                "w-7 h-4 rounded-full p-[2px] transition-all duration-150",
+                stage.enabled ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+              )}>
+                <div className={cn(
+                  "w-3 h-3 rounded-full transition-all duration-150",
+                  stage.enabled ? "translate-x-3 bg-white" : "translate-x-0 bg-white/30",
+                )} />
+              </div>
+            </button>
+          </div>
+          {stage.kind === "Agc" && stage.enabled && (
+            <div className="flex items-center gap-1 pl-6 font-clean text-[9px] text-white/30">
+              <span>Target</span>
+              <DbInput label="Target low" value={agc.target_low_db} onCommit={(db) => onAgcChange({ ...agc, target_low_db: db })} />
+              <span>to</span>
+              <DbInput label="Target high" value={agc.target_high_db} onCommit={(db) => onAgcChange({ ...agc, target_high_db: db })} />
+              <span>dB, boost up to</span>
+              <DbInput label="Max gain" value={agc.max_gain_db} onCommit={(db) => onAgcChange({ ...agc, max_gain_db: db })} />
+              <span>dB</span>
+            </div>
+          )}
+        </Fragment>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,98 @@
+import { useState, type ReactNode } from "react";
+import { cn } from "@/lib/utils";
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+  AlertDialogTrigger,
+} from "@/components/ui/alert-dialog";
+import { errorMessage, previewReset } from "@/lib/tauri";
+import type { ResetKind, ResetPreview } from "@/lib/tauri";
+
+/** One line per thing the reset erases, from what the device holds now. */
+function losses(preview: ResetPreview): string[] {
+  const { layers, rgb, overrides } = preview.snapshot;
+  const lines: string[] = [];
+  if (layers.length > 0) {
+    const bound = layers.reduce((n, layer) => n + layer.filter((code) => code !== 0).length, 0);
+    lines.push(`${bound} key assignments on ${layers.length} layer${layers.length === 1 ? "" : "s"}`);
+  }
+  if (preview.kind !== "Keymaps") {
+    lines.push(preview.macro_count === 1 ? "1 macro" : `${preview.macro_count} macros`);
+  }
+  if (rgb) lines.push(`RGB settings (effect ${rgb.effect}, brightness ${rgb.brightness})`);
+  if (overrides.length > 0) lines.push(`${overrides.length} per-key color override${overrides.length === 1 ? "" : "s"}`);
+  return lines;
+}
+
+/**
+ * Confirm a destructive device reset: on open, reads what it would erase
+ * and gets the confirm token the reset needs. A backup is saved first.
+ */
+export function ResetDialog({ kind, title, description, action, danger, onConfirm, children }: {
+  kind: ResetKind;
+  title: string;
+  description: string;
+  action: string;
+  /** The full EEPROM reset gets the stronger red. */
+  danger?: boolean;
+  onConfirm: (token: string) => void;
+  children: ReactNode;
+}) {
+  const [preview, setPreview] = useState<ResetPreview | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const onOpenChange = (open: boolean) => {
+    setPreview(null);
+    setError(null);
+    if (!open) return;
+    previewReset(kind)
+      .then((p) => (p ? setPreview(p) : setError("Not available outside the app")))
+      .catch((e) => setError(errorMessage(e)));
+  };
+
+  return (
+    <AlertDialog onOpenChange={onOpenChange}>
+      <AlertDialogTrigger asChild>{children}</AlertDialogTrigger>
+      <AlertDialogContent className="bg-[#111113] border-white/12">
+        <AlertDialogHeader>
+          <AlertDialogTitle className={cn("text-sm", danger && "text-red-300")}>{title}</AlertDialogTitle>
+          <AlertDialogDescription className="text-xs text-white/40">{description}</AlertDialogDescription>
+        </AlertDialogHeader>
+        <div className="rounded-lg border border-white/[0.06] bg-white/[0.02] px-3 py-2">
+          <div className="font-clean text-[10px] text-white/50 mb-1">This erases:</div>
+          {error ? (
+            <div className="font-clean text-[10px] text-red-300/70">{error}</div>
+          ) : preview ? (
+            <ul className="font-clean text-[10px] text-white/40 list-disc pl-4 space-y-0.5">
+              {losses(preview).map((line) => <li key={line}>{line}</li>)}
+            </ul>
+          ) : (
+            <div className="font-clean text-[10px] text-white/25">Reading the device…</div>
+          )}
+          <div className="font-clean text-[9px] text-white/25 mt-1.5">A backup is saved to the config folder first.</div>
+        </div>
+        <AlertDialogFooter>
+          <AlertDialogCancel className="text-xs h-8">Cancel</AlertDialogCancel>
+          <AlertDialogAction
+            className={cn(
+              "text-xs h-8 border",
+              danger
+                ? "bg-red-500/30 text-red-200 hover:bg-red-500/40 border-red-500/30"
+                : "bg-red-500/20 text-red-300 hover:bg-red-500/30 border-red-500/20",
+            )}
+            disabled={!preview}
+            onClick={() => preview && onConfirm(preview.token)}
+          >
+            {action}
+          </AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
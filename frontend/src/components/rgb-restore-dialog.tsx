@@ -0,0 +1,70 @@
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog";
+import { RGB_EFFECTS } from "@/lib/rgb-effects";
+import { hsvToRgb } from "@/lib/hsv";
+import type { RgbMatrixState } from "@/lib/tauri";
+
+interface RgbRestoreDialogProps {
+  /** Saved settings; the dialog is open while this is set. */
+  saved: RgbMatrixState | null;
+  device: RgbMatrixState | null;
+  onRestore: () => void;
+  onKeepDevice: () => void;
+}
+
+function describe(rgb: RgbMatrixState) {
+  const effect = RGB_EFFECTS.find((e) => e.id === rgb.effect)?.name ?? `Effect ${rgb.effect}`;
+  return `${effect} · ${Math.round((rgb.brightness / 255) * 100)}% · speed ${Math.round((rgb.speed / 255) * 100)}%`;
+}
+
+function Row({ title, rgb }: { title: string; rgb: RgbMatrixState }) {
+  return (
+    <div className="flex items-center gap-2">
+      <span
+        className="w-3 h-3 rounded-sm border border-white/20 shrink-0"
+        style={{ backgroundColor: hsvToRgb(rgb.color_h, rgb.color_s, 255) }}
+      />
+      <span className="font-clean text-[10px] text-white/30 w-12">{title}</span>
+      <span className="font-clean text-[10px] text-white/60">{describe(rgb)}</span>
+    </div>
+  );
+}
+
+/** Shown on connect when the device's RGB settings differ from the saved ones (e.g. after an EEPROM reset). */
+export function RgbRestoreDialog({ saved, device, onRestore, onKeepDevice }: RgbRestoreDialogProps) {
+  return (
+    <AlertDialog open={saved !== null}>
+      <AlertDialogContent className="bg-[#111113] border-white/12">
+        <AlertDialogHeader>
+          <AlertDialogTitle className="text-sm">Restore your lighting?</AlertDialogTitle>
+          <AlertDialogDescription className="text-xs text-white/40">
+            The device's RGB settings don't match the ones you saved — it may have been reset or reflashed.
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        <div className="flex flex-col gap-1.5 px-1">
+          {saved && <Row title="Saved" rgb={saved} />}
+          {device && <Row title="Device" rgb={device} />}
+        </div>
+        <AlertDialogFooter>
+          <AlertDialogCancel className="text-xs h-8" onClick={onKeepDevice}>
+            Keep device
+          </AlertDialogCancel>
+          <AlertDialogAction
+            className="text-xs h-8 bg-violet-500/20 text-violet-200 hover:bg-violet-500/30 border border-violet-500/20"
+            onClick={onRestore}
+          >
+            Restore saved
+          </AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
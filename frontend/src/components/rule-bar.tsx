@@ -0,0 +1,233 @@
+import { useState } from "react";
+import { Plus, Power, Save, X } from "lucide-react";
+import { LED_TO_MATRIX } from "@/components/key-assignment-view";
+import type { KeyConfig, Rule, RuleAction, RuleWhen } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface RuleBarProps {
+  keys: KeyConfig[];
+  rules: Rule[];
+  activeRules: string[];
+  onSave: (rule: Rule) => void;
+  onDelete: (ruleId: string) => void;
+}
+
+const DAYS = ["M", "T", "W", "T", "F", "S", "S"];
+const WEEKDAYS = 0b11111;
+
+/** Physical key number (K1…K8) of an LED index. */
+const keyNumber = (led: number) => LED_TO_MATRIX[led] + 1;
+
+/** LED indices in physical order, for the key picker. */
+const PICKER_ORDER = [0, 1, 2, 3, 4, 5, 6, 7].map((m) => LED_TO_MATRIX.indexOf(m));
+
+const fieldClass = "bg-white/[0.03] border border-white/[0.08] rounded px-1 py-0.5 font-clean text-[9px] text-white/50 outline-none";
+
+function minutesToTime(min: number) {
+  return `${String(Math.floor(min / 60)).padStart(2, "0")}:${String(min % 60).padStart(2, "0")}`;
+}
+
+function timeToMinutes(time: string) {
+  const [h, m] = time.split(":").map(Number);
+  return h * 60 + m;
+}
+
+function describeWhen(when: RuleWhen) {
+  if (when.kind === "OnBattery") return "On battery";
+  const days = when.days === WEEKDAYS ? "Weekdays" : when.days === 0x7f ? "Every day" : DAYS.filter((_, i) => when.days & (1 << i)).join("");
+  return `${days} ${minutesToTime(when.start_min)}–${minutesToTime(when.end_min)}`;
+}
+
+function describeThen(then: RuleAction) {
+  return then.kind === "Dim" ? `dim to ${then.percent}%` : `K${keyNumber(then.key)} → ${then.slot}`;
+}
+
+/**
+ * LED rules: while a schedule or "on battery" holds, put a key on a slot or
+ * dim every key; undone when it stops. Checked every 30 s.
+ */
+export function RuleBar({ keys, rules, activeRules, onSave, onDelete }: RuleBarProps) {
+  const [adding, setAdding] = useState(false);
+  const [name, setName] = useState("");
+  const [when, setWhen] = useState<RuleWhen>({ kind: "Schedule", days: WEEKDAYS, start_min: 9 * 60, end_min: 17 * 60 });
+  const [then, setThen] = useState<RuleAction>({ kind: "KeySlot", key: 0, slot: keys[0]?.slots[0]?.name ?? "" });
+
+  const handleSave = () => {
+    if (!name.trim()) return;
+    onSave({ id: "", name: name.trim(), enabled: true, when, then });
+    setName("");
+    setAdding(false);
+  };
+
+  const slotNames = then.kind === "KeySlot" ? (keys[then.key]?.slots.map((s) => s.name) ?? []) : [];
+
+  return (
+    <div className="relative z-[1] flex flex-col gap-1.5 px-5 pb-3">
+      {rules.length > 0 && (
+        <div className="flex flex-wrap gap-1.5">
+          {rules.map((r) => {
+            const active = activeRules.includes(r.id);
+            return (
+              <div
+                key={r.id}
+                className={cn(
+                  "group flex items-center gap-1 pl-1.5 pr-1 py-1 rounded-md border transition-colors",
+                  active ? "border-sky-400/30 bg-sky-500/[0.06]" : "border-white/[0.06] hover:border-white/15",
+                )}
+                title={`${describeWhen(r.when)}: ${describeThen(r.then)}${active ? " (now)" : ""}`}
+              >
+                <button type="button" onClick={() => onSave({ ...r, enabled: !r.enabled })} title={r.enabled ? "Disable rule" : "Enable rule"}>
+                  <Power className={cn("w-2.5 h-2.5", r.enabled ? "text-sky-300/60" : "text-white/15")} />
+                </button>
+                <span className={cn("font-clean text-[9px] max-w-[80px] truncate", r.enabled ? "text-white/40" : "text-white/15 line-through")}>
+                  {r.name}
+                </span>
+                <button
+                  type="button"
+                  className="opacity-0 group-hover:opacity-100 transition-opacity"
+                  onClick={() => onDelete(r.id)}
+                  title="Delete rule"
+                >
+                  <X className="w-2.5 h-2.5 text-white/25 hover:text-white/50" />
+                </button>
+              </div>
+            );
+          })}
+        </div>
+      )}
+      {adding ? (
+        <div className="flex flex-col gap-1.5">
+          <div className="flex items-center gap-1.5">
+            <select
+              className={cn(fieldClass, "appearance-none cursor-pointer")}
+              value={when.kind}
+              onChange={(e) =>
+                setWhen(e.target.value === "OnBattery"
+                  ? { kind: "OnBattery" }
+                  : { kind: "Schedule", days: WEEKDAYS, start_min: 9 * 60, end_min: 17 * 60 })
+              }
+            >
+              <option value="Schedule">Between</option>
+              <option value="OnBattery">On battery</option>
+            </select>
+            {when.kind === "Schedule" && (
+              <>
+                <input
+                  type="time"
+                  value={minutesToTime(when.start_min)}
+                  onChange={(e) => e.target.value && setWhen({ ...when, start_min: timeToMinutes(e.target.value) })}
+                  className={fieldClass}
+                />
+                <span className="font-clean text-[9px] text-white/25">and</span>
+                <input
+                  type="time"
+                  value={minutesToTime(when.end_min)}
+                  onChange={(e) => e.target.value && setWhen({ ...when, end_min: timeToMinutes(e.target.value) })}
+                  className={fieldClass}
+                />
+                <div className="flex gap-0.5 ml-auto">
+                  {DAYS.map((d, i) => (
+                    <button
+                      key={i}
+                      type="button"
+                      onClick={() => setWhen({ ...when, days: when.days ^ (1 << i) })}
+                      aria-pressed={(when.days & (1 << i)) !== 0}
+                      className={cn(
+                        "w-4 h-4 rounded font-clean text-[8px] transition-colors",
+                        when.days & (1 << i) ? "bg-sky-500/20 text-sky-200/70" : "bg-white/[0.03] text-white/20",
+                      )}
+                    >
+                      {d}
+                    </button>
+                  ))}
+                </div>
+              </>
+            )}
+          </div>
+          <div className="flex items-center gap-1.5">
+            <select
+              className={cn(fieldClass, "appearance-none cursor-pointer")}
+              value={then.kind}
+              onChange={(e) =>
+                setThen(e.target.value === "Dim"
+                  ? { kind: "Dim", percent: 30 }
+                  : { kind: "KeySlot", key: 0, slot: keys[0]?.slots[0]?.name ?? "" })
+              }
+            >
+              <option value="KeySlot">Put key</option>
+              <option value="Dim">Dim all keys</option>
+            </select>
+            {then.kind === "KeySlot" ? (
+              <>
+                <select
+                  className={cn(fieldClass, "appearance-none cursor-pointer")}
+                  value={then.key}
+                  onChange={(e) => {
+                    const key = Number(e.target.value);
+                    setThen({ kind: "KeySlot", key, slot: keys[key]?.slots[0]?.name ?? "" });
+                  }}
+                >
+                  {PICKER_ORDER.map((led) => <option key={led} value={led}>K{keyNumber(led)}</option>)}
+                </select>
+                <span className="font-clean text-[9px] text-white/25">on</span>
+                <select
+                  className={cn(fieldClass, "appearance-none cursor-pointer")}
+                  value={then.slot}
+                  onChange={(e) => setThen({ ...then, slot: e.target.value })}
+                >
+                  {slotNames.map((n) => <option key={n} value={n}>{n}</option>)}
+                </select>
+              </>
+            ) : (
+              <>
+                <input
+                  type="number"
+                  min={1}
+                  max={100}
+                  value={then.percent}
+                  onChange={(e) => setThen({ kind: "Dim", percent: Math.min(100, Math.max(1, Number(e.target.value) || 1)) })}
+                  className={cn(fieldClass, "w-12 tabular-nums")}
+                />
+                <span className="font-clean text-[9px] text-white/25">%</span>
+              </>
+            )}
+          </div>
+          <div className="flex items-center gap-1.5">
+            <input
+              type="text"
+              value={name}
+              maxLength={32}
+              placeholder="Rule name…"
+              onChange={(e) => setName(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") handleSave();
+              }}
+              className="flex-1 min-w-0 bg-transparent font-clean text-[9px] text-white/40 placeholder:text-white/15 outline-none border-b border-transparent focus:border-white/15 transition-colors"
+              spellCheck={false}
+            />
+            <button type="button" className="p-1 rounded hover:bg-white/[0.06] transition-colors" onClick={() => setAdding(false)} title="Cancel">
+              <X className="w-3 h-3 text-white/30" />
+            </button>
+            <button
+              type="button"
+              className={cn("p-1 rounded hover:bg-white/[0.06] transition-colors", !name.trim() && "opacity-30 pointer-events-none")}
+              onClick={handleSave}
+              title="Save rule"
+            >
+              <Save className="w-3 h-3 text-white/30" />
+            </button>
+          </div>
+        </div>
+      ) : (
+        <button
+          type="button"
+          className="flex items-center gap-1 self-start font-clean text-[9px] text-white/20 hover:text-white/40 transition-colors"
+          onClick={() => setAdding(true)}
+        >
+          <Plus className="w-2.5 h-2.5" />
+          Add a rule…
+        </button>
+      )}
+    </div>
+  );
+}
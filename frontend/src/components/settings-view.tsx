@@ -1,7 +1,9 @@
 import { useState, useEffect, useCallback } from "react";
 import { cn } from "@/lib/utils";
 import { Slider } from "@/components/ui/slider";
-import { Power, Monitor, Info, Sparkles, Sun, Gauge, Palette, Save, RotateCcw, Keyboard, Cpu, Eraser, AlertTriangle } from "lucide-react";
+import {
+  Power, Monitor, Info, Sparkles, Sun, SunDim, Gauge, Palette, Save, RotateCcw, Keyboard, Cpu, Eraser, AlertTriangle, CircleX, CircleCheck, CircleMinus, FolderOpen, ClipboardCopy, Accessibility, Music, Plug, OctagonX, Gamepad2, Paintbrush, Languages, AppWindow, ListChecks, Timer, Cable,
+} from "lucide-react";
 import {
   AlertDialog,
   AlertDialogAction,
@@ -16,21 +18,83 @@ import {
 import { toast } from "sonner";
 import { RGB_EFFECTS } from "@/lib/rgb-effects";
 import { hsvToRgb } from "@/lib/hsv";
-import type { RgbMatrixState } from "@/lib/tauri";
+import { errorMessage, getAppInfo, getRecentErrors, listLanguages, LOCAL_API_PORT, openConfigDir, openSoundsDir } from "@/lib/tauri";
+import type { AppHotkey, AppInfo, BrightnessCurve, CheckStatus, DeviceSync, DiscordSettings, FocusSettings, GameSettings, HidTiming, HsvColor, HubError, Language, LightingSettings, KeyConfig, RgbMatrixState, SoundEntry, SyncReport, WindowSettings } from "@/lib/tauri";
+import { UsageStats } from "@/components/usage-stats";
+import { LightingCard } from "@/components/lighting-card";
+import { FocusCard } from "@/components/focus-card";
+import { GameCard } from "@/components/game-card";
+import { DeviceHealthPanel } from "@/components/device-health";
+import { ConnectionHistoryPanel } from "@/components/connection-history";
+import { TransferCard } from "@/components/transfer-card";
+import { LockCard } from "@/components/lock";
+import { ResetDialog } from "@/components/reset-dialog";
+import { DeviceSyncPanel } from "@/components/device-sync";
 
 interface SettingsViewProps {
   rgbMatrix: RgbMatrixState | null;
+  brightnessCurve: BrightnessCurve;
+  accessibilityCues: boolean;
+  onAccessibilityCuesChange: (enabled: boolean) => void;
+  nowPlaying: boolean;
+  onNowPlayingChange: (enabled: boolean) => void;
+  discord: DiscordSettings;
+  onDiscordChange: (discord: DiscordSettings) => void;
+  lighting: LightingSettings;
+  onLightingChange: (lighting: LightingSettings) => Promise<boolean>;
+  onPairHue: (bridge: string) => Promise<string | null>;
+  game: GameSettings;
+  onGameChange: (game: GameSettings) => void;
+  accentKeyColors: boolean;
+  /** The OS accent color, or null where there is none. */
+  systemAccent: HsvColor | null;
+  onAccentKeyColorsChange: (enabled: boolean) => void;
+  /** null follows the system language. */
+  language: string | null;
+  onLanguageChange: (language: string | null) => void;
+  windowSettings: WindowSettings;
+  onWindowChange: (window: WindowSettings) => void;
+  hidTiming: HidTiming;
+  onHidTimingChange: (timing: HidTiming) => void;
+  cooperative: boolean;
+  onCooperativeChange: (enabled: boolean) => void;
+  onLock: (pin: string) => Promise<boolean>;
+  focus: FocusSettings;
+  focusUntil: number | null;
+  onFocusChange: (focus: FocusSettings) => void;
+  onStartFocus: () => void;
+  onStopFocus: () => void;
+  onShowOnboarding: () => void;
+  localApi: boolean;
+  onLocalApiChange: (enabled: boolean) => void;
+  stopSoundsHotkey: string | null;
+  muteSoundsHotkey: string | null;
+  onAppHotkeyChange: (action: AppHotkey, hotkey: string | null) => Promise<boolean>;
+  keys: KeyConfig[];
+  soundLibrary: SoundEntry[];
   connected: boolean;
+  syncReport: SyncReport | null;
+  deviceSync: DeviceSync;
+  onSaveCustom: () => void | Promise<void>;
   onRgbChange: (field: keyof RgbMatrixState, value: number) => void;
   onRgbColorChange: (h: number, s: number) => void;
   onRgbSave: () => void;
+  onBrightnessCurveChange: (curve: BrightnessCurve) => void;
   onRestoreDefaults: () => void;
   onBootloaderJump: () => void;
-  onEepromReset: () => void;
-  onDynamicKeymapReset: () => void;
-  onMacroReset: () => void;
+  onEepromReset: (confirm: string) => void;
+  onDynamicKeymapReset: (confirm: string) => void;
+  onMacroReset: (confirm: string) => void;
+  onReloadDefinitions: () => void;
 }
 
+const CHECK_ICONS: Record<CheckStatus, { icon: typeof CircleCheck; className: string }> = {
+  Pass: { icon: CircleCheck, className: "text-emerald-400/60" },
+  Warn: { icon: AlertTriangle, className: "text-amber-400/60" },
+  Fail: { icon: CircleX, className: "text-red-400/70" },
+  Skipped: { icon: CircleMinus, className: "text-white/20" },
+};
+
 // Tauri autostart bindings — gracefully fail in browser
 let autostartApi: {
   enable: () => Promise<void>;
@@ -40,18 +104,78 @@ let autostartApi: {
 
 export function SettingsView({
   rgbMatrix,
+  brightnessCurve,
+  accessibilityCues,
+  onAccessibilityCuesChange,
+  nowPlaying,
+  onNowPlayingChange,
+  discord,
+  onDiscordChange,
+  lighting,
+  onLightingChange,
+  onPairHue,
+  game,
+  onGameChange,
+  accentKeyColors,
+  systemAccent,
+  onAccentKeyColorsChange,
+  language,
+  onLanguageChange,
+  windowSettings,
+  onWindowChange,
+  hidTiming,
+  onHidTimingChange,
+  cooperative,
+  onCooperativeChange,
+  onLock,
+  focus,
+  focusUntil,
+  onFocusChange,
+  onStartFocus,
+  onStopFocus,
+  onShowOnboarding,
+  localApi,
+  onLocalApiChange,
+  stopSoundsHotkey,
+  muteSoundsHotkey,
+  onAppHotkeyChange,
+  keys,
+  soundLibrary,
   connected,
+  syncReport,
+  deviceSync,
+  onSaveCustom,
   onRgbChange,
   onRgbColorChange,
   onRgbSave,
+  onBrightnessCurveChange,
   onRestoreDefaults,
   onBootloaderJump,
   onEepromReset,
   onDynamicKeymapReset,
   onMacroReset,
+  onReloadDefinitions,
 }: SettingsViewProps) {
   const [autostart, setAutostart] = useState(false);
   const [loading, setLoading] = useState(true);
+  const [recentErrors, setRecentErrors] = useState<HubError[]>([]);
+  const [appInfo, setAppInfo] = useState<AppInfo | null>(null);
+  const [languages, setLanguages] = useState<Language[]>([]);
+
+  useEffect(() => {
+    getRecentErrors().then(setRecentErrors).catch(() => {});
+    getAppInfo().then(setAppInfo).catch(() => {});
+    listLanguages().then(setLanguages).catch(() => {});
+  }, []);
+
+  const copyDiagnostics = useCallback(async () => {
+    try {
+      await navigator.clipboard.writeText(JSON.stringify({ ...appInfo, recent_errors: recentErrors }, null, 2));
+      toast.success("Diagnostics copied — paste them into your bug report");
+    } catch (e) {
+      toast.error(`Copy failed: ${errorMessage(e)}`);
+    }
+  }, [appInfo, recentErrors]);
 
   useEffect(() => {
     import("@tauri-apps/plugin-autostart")
@@ -82,7 +206,7 @@ export function SettingsView({
         toast.success("Autostart enabled — will launch on login");
       }
     } catch (e) {
-      toast.error(`Autostart failed: ${e}`);
+      toast.error(`Autostart failed: ${errorMessage(e)}`);
     }
   }, [autostart]);
 
@@ -169,6 +293,42 @@ export function SettingsView({
               />
             </div>
 
+            {/* Brightness curve: gamma + output range, applied to every V sent to the LEDs */}
+            <div className="flex flex-col gap-1.5">
+              <div className="flex items-center gap-1.5">
+                <SunDim className="w-3 h-3 text-white/20" />
+                <span className="font-pixel text-[9px] text-white/40 uppercase tracking-wider">Curve</span>
+                <span className="font-clean text-[10px] text-white/50 tabular-nums ml-auto">
+                  γ {brightnessCurve.gamma.toFixed(1)} · {brightnessCurve.min}–{brightnessCurve.max}
+                </span>
+              </div>
+              <div className="flex items-center gap-2">
+                <span className="font-clean text-[10px] text-white/25 w-4">γ</span>
+                <div className="flex-1">
+                  <Slider
+                    min={0.2}
+                    max={5}
+                    step={0.1}
+                    value={[brightnessCurve.gamma]}
+                    onValueChange={([gamma]) => onBrightnessCurveChange({ ...brightnessCurve, gamma })}
+                  />
+                </div>
+              </div>
+              <div className="flex items-center gap-2">
+                <span className="font-clean text-[10px] text-white/25 w-4">V</span>
+                <div className="flex-1">
+                  <Slider
+                    min={0}
+                    max={255}
+                    step={1}
+                    minStepsBetweenThumbs={1}
+                    value={[brightnessCurve.min, brightnessCurve.max]}
+                    onValueChange={([min, max]) => onBrightnessCurveChange({ ...brightnessCurve, min, max })}
+                  />
+                </div>
+              </div>
+            </div>
+
             {/* Speed */}
             <div className="flex flex-col gap-1.5">
               <div className="flex items-center gap-1.5">
@@ -273,6 +433,236 @@ export function SettingsView({
             </button>
           </div>
 
+          {/* Close / minimize */}
+          <WindowCard value={windowSettings} onChange={onWindowChange} />
+
+          {/* Language */}
+          <div className="flex items-center gap-3 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
+            <div className="flex items-center justify-center w-7 h-7 rounded-lg bg-white/[0.06] text-white/25">
+              <Languages className="w-3.5 h-3.5" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="font-clean text-[11px] text-white/80 font-medium">Language</div>
+              <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                Tray menu, screen-reader announcements and error messages
+              </div>
+            </div>
+            <select
+              className="px-2 py-1 rounded bg-[#0d0d0f] border border-white/[0.08] font-clean text-[10px] text-white/60 outline-none appearance-none cursor-pointer"
+              value={language ?? ""}
+              onChange={(e) => onLanguageChange(e.target.value || null)}
+              aria-label="Language"
+            >
+              <option value="">System</option>
+              {languages.map((l) => (
+                <option key={l.code} value={l.code}>{l.name}</option>
+              ))}
+            </select>
+          </div>
+
+          {/* Accessibility cues */}
+          <div
+            className={cn(
+              "flex items-center gap-3 px-3.5 py-3 rounded-xl",
+              "border transition-all duration-150",
+              accessibilityCues
+                ? "border-emerald-500/20 bg-emerald-500/[0.04]"
+                : "border-white/[0.06] bg-white/[0.02]",
+            )}
+          >
+            <div className={cn(
+              "flex items-center justify-center w-7 h-7 rounded-lg",
+              accessibilityCues ? "bg-emerald-500/15 text-emerald-400" : "bg-white/[0.06] text-white/25",
+            )}>
+              <Accessibility className="w-3.5 h-3.5" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="font-clean text-[11px] text-white/80 font-medium">Accessibility cues</div>
+              <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                Tone + screen-reader announcement when a key changes slot
+              </div>
+            </div>
+            <button
+              type="button"
+              role="switch"
+              aria-checked={accessibilityCues}
+              aria-label="Accessibility cues"
+              className="flex-shrink-0"
+              onClick={() => onAccessibilityCuesChange(!accessibilityCues)}
+            >
+              <div className={cn(
+                "w-9 h-5 rounded-full p-[2px] transition-all duration-150 cursor-pointer",
+                accessibilityCues ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+              )}>
+                <div className={cn(
+                  "w-4 h-4 rounded-full transition-all duration-150",
+                  accessibilityCues ? "translate-x-4 bg-white" : "translate-x-0 bg-white/30",
+                )} />
+              </div>
+            </button>
+          </div>
+
+          {/* Now playing */}
+          <div
+            className={cn(
+              "flex items-center gap-3 px-3.5 py-3 rounded-xl",
+              "border transition-all duration-150",
+              nowPlaying
+                ? "border-emerald-500/20 bg-emerald-500/[0.04]"
+                : "border-white/[0.06] bg-white/[0.02]",
+            )}
+          >
+            <div className={cn(
+              "flex items-center justify-center w-7 h-7 rounded-lg",
+              nowPlaying ? "bg-emerald-500/15 text-emerald-400" : "bg-white/[0.06] text-white/25",
+            )}>
+              <Music className="w-3.5 h-3.5" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="font-clean text-[11px] text-white/80 font-medium">Now playing</div>
+              <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                Show the current track from the system media session on the Keys tab
+              </div>
+            </div>
+            <button
+              type="button"
+              role="switch"
+              aria-checked={nowPlaying}
+              aria-label="Now playing"
+              className="flex-shrink-0"
+              onClick={() => onNowPlayingChange(!nowPlaying)}
+            >
+              <div className={cn(
+                "w-9 h-5 rounded-full p-[2px] transition-all duration-150 cursor-pointer",
+                nowPlaying ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+              )}>
+                <div className={cn(
+                  "w-4 h-4 rounded-full transition-all duration-150",
+                  nowPlaying ? "translate-x-4 bg-white" : "translate-x-0 bg-white/30",
+                )} />
+              </div>
+            </button>
+          </div>
+
+          {/* System accent */}
+          <div
+            className={cn(
+              "flex items-center gap-3 px-3.5 py-3 rounded-xl",
+              "border transition-all duration-150",
+              accentKeyColors
+                ? "border-emerald-500/20 bg-emerald-500/[0.04]"
+                : "border-white/[0.06] bg-white/[0.02]",
+            )}
+          >
+            <div className={cn(
+              "flex items-center justify-center w-7 h-7 rounded-lg",
+              accentKeyColors ? "bg-emerald-500/15 text-emerald-400" : "bg-white/[0.06] text-white/25",
+            )}>
+              <Paintbrush className="w-3.5 h-3.5" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="font-clean text-[11px] text-white/80 font-medium">System accent color</div>
+              <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                {systemAccent
+                  ? "Keys still on the default color follow your desktop's accent color"
+                  : "Your desktop doesn't report an accent color"}
+              </div>
+            </div>
+            {systemAccent && (
+              <div
+                className="w-4 h-4 rounded-full border border-white/10 flex-shrink-0"
+                style={{ backgroundColor: hsvToRgb(systemAccent.h, systemAccent.s, systemAccent.v) }}
+                title="Current accent color"
+              />
+            )}
+            <button
+              type="button"
+              role="switch"
+              aria-checked={accentKeyColors}
+              aria-label="System accent color"
+              className="flex-shrink-0"
+              onClick={() => onAccentKeyColorsChange(!accentKeyColors)}
+            >
+              <div className={cn(
+                "w-9 h-5 rounded-full p-[2px] transition-all duration-150 cursor-pointer",
+                accentKeyColors ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+              )}>
+                <div className={cn(
+                  "w-4 h-4 rounded-full transition-all duration-150",
+                  accentKeyColors ? "translate-x-4 bg-white" : "translate-x-0 bg-white/30",
+                )} />
+              </div>
+            </button>
+          </div>
+
+          {/* Local API */}
+          <div
+            className={cn(
+              "flex items-center gap-3 px-3.5 py-3 rounded-xl",
+              "border transition-all duration-150",
+              localApi
+                ? "border-emerald-500/20 bg-emerald-500/[0.04]"
+                : "border-white/[0.06] bg-white/[0.02]",
+            )}
+          >
+            <div className={cn(
+              "flex items-center justify-center w-7 h-7 rounded-lg",
+              localApi ? "bg-emerald-500/15 text-emerald-400" : "bg-white/[0.06] text-white/25",
+            )}>
+              <Plug className="w-3.5 h-3.5" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="font-clean text-[11px] text-white/80 font-medium">Local API</div>
+              <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                Let scripts drive the LEDs over ws://127.0.0.1:{LOCAL_API_PORT} (this computer only)
+              </div>
+            </div>
+            <button
+              type="button"
+              role="switch"
+              aria-checked={localApi}
+              aria-label="Local API"
+              className="flex-shrink-0"
+              onClick={() => onLocalApiChange(!localApi)}
+            >
+              <div className={cn(
+                "w-9 h-5 rounded-full p-[2px] transition-all duration-150 cursor-pointer",
+                localApi ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+              )}>
+                <div className={cn(
+                  "w-4 h-4 rounded-full transition-all duration-150",
+                  localApi ? "translate-x-4 bg-white" : "translate-x-0 bg-white/30",
+                )} />
+              </div>
+            </button>
+          </div>
+
+          {/* Discord */}
+          <DiscordCard value={discord} onChange={onDiscordChange} />
+
+          {/* Desk lights */}
+          <LightingCard value={lighting} onChange={onLightingChange} onPairHue={onPairHue} />
+
+          {/* Game events */}
+          <GameCard value={game} onChange={onGameChange} />
+
+          {/* Emergency hotkeys */}
+          <div className="flex flex-col gap-2 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
+            <div className="flex items-center gap-3">
+              <div className="flex items-center justify-center w-7 h-7 rounded-lg bg-white/[0.06] text-white/25">
+                <OctagonX className="w-3.5 h-3.5" />
+              </div>
+              <div className="flex-1 min-w-0">
+                <div className="font-clean text-[11px] text-white/80 font-medium">Emergency hotkeys</div>
+                <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                  Work with the window hidden; the tray menu has both too
+                </div>
+              </div>
+            </div>
+            <HotkeyField label="Stop sounds" value={stopSoundsHotkey} onCommit={(h) => onAppHotkeyChange("StopSounds", h)} />
+            <HotkeyField label="Mute soundboard" value={muteSoundsHotkey} onCommit={(h) => onAppHotkeyChange("MuteSounds", h)} />
+          </div>
+
           {/* Minimize to tray */}
           <div className="flex items-center gap-3 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
             <div className="flex items-center justify-center w-7 h-7 rounded-lg bg-white/[0.06] text-white/25">
@@ -286,6 +676,117 @@ export function SettingsView({
             </div>
             <span className="font-pixel text-[8px] text-emerald-400/60 uppercase tracking-wider">on</span>
           </div>
+
+          {/* Folders */}
+          <div className="flex items-center gap-3 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
+            <div className="flex items-center justify-center w-7 h-7 rounded-lg bg-white/[0.06] text-white/25">
+              <FolderOpen className="w-3.5 h-3.5" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="font-clean text-[11px] text-white/80 font-medium">Files</div>
+              <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                Settings, logs and imported sounds
+              </div>
+            </div>
+            {([["Config", openConfigDir], ["Sounds", openSoundsDir]] as const).map(([label, open]) => (
+              <button
+                key={label}
+                type="button"
+                className="px-2 py-1 rounded-md font-clean text-[10px] text-white/50 border border-white/[0.08] hover:bg-white/[0.04] hover:text-white/70 transition-colors"
+                onClick={() => open().catch((e) => toast.error(`Open folder failed: ${errorMessage(e)}`))}
+              >
+                {label}
+              </button>
+            ))}
+          </div>
+
+          <TransferCard />
+
+          <LockCard onLock={onLock} />
+
+          {/* First-run guide */}
+          <div className="flex items-center gap-3 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
+            <div className="flex items-center justify-center w-7 h-7 rounded-lg bg-white/[0.06] text-white/25">
+              <ListChecks className="w-3.5 h-3.5" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="font-clean text-[11px] text-white/80 font-medium">Setup guide</div>
+              <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                Walk through connecting, testing a key, audio devices and a first sound again
+              </div>
+            </div>
+            <button
+              type="button"
+              className="px-2 py-1 rounded-md font-clean text-[10px] text-white/50 border border-white/[0.08] hover:bg-white/[0.04] hover:text-white/70 transition-colors"
+              onClick={onShowOnboarding}
+            >
+              Show
+            </button>
+          </div>
+
+          <HidTimingCard value={hidTiming} onChange={onHidTimingChange} />
+
+          {/* Sharing the device with VIA */}
+          <div
+            className={cn(
+              "flex items-center gap-3 px-3.5 py-3 rounded-xl",
+              "border transition-all duration-150",
+              cooperative
+                ? "border-emerald-500/20 bg-emerald-500/[0.04]"
+                : "border-white/[0.06] bg-white/[0.02]",
+            )}
+          >
+            <div className={cn(
+              "flex items-center justify-center w-7 h-7 rounded-lg",
+              cooperative ? "bg-emerald-500/15 text-emerald-400" : "bg-white/[0.06] text-white/25",
+            )}>
+              <Cable className="w-3.5 h-3.5" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="font-clean text-[11px] text-white/80 font-medium">Wait for VIA</div>
+              <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                When another configurator has the Deck-8 open, connect as soon as it's closed
+              </div>
+            </div>
+            <button
+              type="button"
+              role="switch"
+              aria-checked={cooperative}
+              aria-label="Wait for VIA"
+              className="flex-shrink-0"
+              onClick={() => onCooperativeChange(!cooperative)}
+            >
+              <div className={cn(
+                "w-9 h-5 rounded-full p-[2px] transition-all duration-150 cursor-pointer",
+                cooperative ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+              )}>
+                <div className={cn(
+                  "w-4 h-4 rounded-full transition-all duration-150",
+                  cooperative ? "translate-x-4 bg-white" : "translate-x-0 bg-white/30",
+                )} />
+              </div>
+            </button>
+          </div>
+
+          {/* Device definition (custom firmware) */}
+          <div className="flex items-center gap-3 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
+            <div className="flex items-center justify-center w-7 h-7 rounded-lg bg-white/[0.06] text-white/25">
+              <Cpu className="w-3.5 h-3.5" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="font-clean text-[11px] text-white/80 font-medium">Device definition</div>
+              <div className="font-clean text-[9px] text-white/30 mt-0.5">
+                For custom QMK forks: device-definition.json in the config folder
+              </div>
+            </div>
+            <button
+              type="button"
+              className="px-2 py-1 rounded-md font-clean text-[10px] text-white/50 border border-white/[0.08] hover:bg-white/[0.04] hover:text-white/70 transition-colors"
+              onClick={onReloadDefinitions}
+            >
+              Reload
+            </button>
+          </div>
         </div>
 
         {/* ── Device Actions ────────────────────────────── */}
@@ -298,6 +799,26 @@ export function SettingsView({
               </span>
             </div>
 
+            <DeviceHealthPanel />
+            <ConnectionHistoryPanel />
+            {connected && <DeviceSyncPanel sync={deviceSync} onSaveColors={onSaveCustom} onSaveRgb={onRgbSave} />}
+
+            {/* Connect-time self-test */}
+            {syncReport && (
+              <div className="flex flex-col gap-1 px-3 py-2 rounded-lg border border-white/[0.06] bg-white/[0.02]">
+                {syncReport.checks.map((check) => {
+                  const { icon: Icon, className } = CHECK_ICONS[check.status];
+                  return (
+                    <div key={check.name} className="flex items-center gap-2 min-w-0" title={check.detail}>
+                      <Icon className={cn("w-3 h-3 shrink-0", className)} />
+                      <span className="font-clean text-[10px] text-white/60 shrink-0">{check.name}</span>
+                      <span className="font-clean text-[9px] text-white/25 truncate">{check.detail}</span>
+                    </div>
+                  );
+                })}
+              </div>
+            )}
+
             <div className="grid grid-cols-2 gap-1.5">
               {/* Restore defaults */}
               <AlertDialog>
@@ -330,94 +851,65 @@ export function SettingsView({
               </AlertDialog>
 
               {/* Reset keymaps */}
-              <AlertDialog>
-                <AlertDialogTrigger asChild>
-                  <button type="button" className={cn(
-                    "flex items-center gap-2 px-3 py-2.5 rounded-lg border transition-all",
-                    "border-white/[0.06] bg-white/[0.02] hover:bg-white/[0.05] hover:border-white/10",
-                  )}>
-                    <Keyboard className="w-3 h-3 text-white/25" />
-                    <div className="text-left">
-                      <div className="font-clean text-[10px] text-white/60">Reset Keymaps</div>
-                      <div className="font-clean text-[9px] text-white/20">Default key bindings</div>
-                    </div>
-                  </button>
-                </AlertDialogTrigger>
-                <AlertDialogContent className="bg-[#111113] border-white/12">
-                  <AlertDialogHeader>
-                    <AlertDialogTitle className="text-sm">Reset all keymaps?</AlertDialogTitle>
-                    <AlertDialogDescription className="text-xs text-white/40">
-                      This will reset all 8 key assignments to QMK factory defaults stored in firmware.
-                    </AlertDialogDescription>
-                  </AlertDialogHeader>
-                  <AlertDialogFooter>
-                    <AlertDialogCancel className="text-xs h-8">Cancel</AlertDialogCancel>
-                    <AlertDialogAction className="text-xs h-8 bg-red-500/20 text-red-300 hover:bg-red-500/30 border border-red-500/20" onClick={onDynamicKeymapReset}>
-                      Reset Keymaps
-                    </AlertDialogAction>
-                  </AlertDialogFooter>
-                </AlertDialogContent>
-              </AlertDialog>
+              <ResetDialog
+                kind="Keymaps"
+                title="Reset all keymaps?"
+                description="This will reset all 8 key assignments to QMK factory defaults stored in firmware."
+                action="Reset Keymaps"
+                onConfirm={onDynamicKeymapReset}
+              >
+                <button type="button" className={cn(
+                  "flex items-center gap-2 px-3 py-2.5 rounded-lg border transition-all",
+                  "border-white/[0.06] bg-white/[0.02] hover:bg-white/[0.05] hover:border-white/10",
+                )}>
+                  <Keyboard className="w-3 h-3 text-white/25" />
+                  <div className="text-left">
+                    <div className="font-clean text-[10px] text-white/60">Reset Keymaps</div>
+                    <div className="font-clean text-[9px] text-white/20">Default key bindings</div>
+                  </div>
+                </button>
+              </ResetDialog>
 
               {/* Reset macros */}
-              <AlertDialog>
-                <AlertDialogTrigger asChild>
-                  <button type="button" className={cn(
-                    "flex items-center gap-2 px-3 py-2.5 rounded-lg border transition-all",
-                    "border-white/[0.06] bg-white/[0.02] hover:bg-white/[0.05] hover:border-white/10",
-                  )}>
-                    <Eraser className="w-3 h-3 text-white/25" />
-                    <div className="text-left">
-                      <div className="font-clean text-[10px] text-white/60">Reset Macros</div>
-                      <div className="font-clean text-[9px] text-white/20">Clear macro buffer</div>
-                    </div>
-                  </button>
-                </AlertDialogTrigger>
-                <AlertDialogContent className="bg-[#111113] border-white/12">
-                  <AlertDialogHeader>
-                    <AlertDialogTitle className="text-sm">Reset all macros?</AlertDialogTitle>
-                    <AlertDialogDescription className="text-xs text-white/40">
-                      This will clear all stored macros from device EEPROM.
-                    </AlertDialogDescription>
-                  </AlertDialogHeader>
-                  <AlertDialogFooter>
-                    <AlertDialogCancel className="text-xs h-8">Cancel</AlertDialogCancel>
-                    <AlertDialogAction className="text-xs h-8 bg-red-500/20 text-red-300 hover:bg-red-500/30 border border-red-500/20" onClick={onMacroReset}>
-                      Reset Macros
-                    </AlertDialogAction>
-                  </AlertDialogFooter>
-                </AlertDialogContent>
-              </AlertDialog>
+              <ResetDialog
+                kind="Macros"
+                title="Reset all macros?"
+                description="This will clear all stored macros from device EEPROM."
+                action="Reset Macros"
+                onConfirm={onMacroReset}
+              >
+                <button type="button" className={cn(
+                  "flex items-center gap-2 px-3 py-2.5 rounded-lg border transition-all",
+                  "border-white/[0.06] bg-white/[0.02] hover:bg-white/[0.05] hover:border-white/10",
+                )}>
+                  <Eraser className="w-3 h-3 text-white/25" />
+                  <div className="text-left">
+                    <div className="font-clean text-[10px] text-white/60">Reset Macros</div>
+                    <div className="font-clean text-[9px] text-white/20">Clear macro buffer</div>
+                  </div>
+                </button>
+              </ResetDialog>
 
               {/* EEPROM reset */}
-              <AlertDialog>
-                <AlertDialogTrigger asChild>
-                  <button type="button" className={cn(
-                    "flex items-center gap-2 px-3 py-2.5 rounded-lg border transition-all",
-                    "border-red-500/10 bg-red-500/[0.02] hover:bg-red-500/[0.06] hover:border-red-500/20",
-                  )}>
-                    <Cpu className="w-3 h-3 text-red-400/30" />
-                    <div className="text-left">
-                      <div className="font-clean text-[10px] text-red-300/50">EEPROM Reset</div>
-                      <div className="font-clean text-[9px] text-red-300/20">Full factory reset</div>
-                    </div>
-                  </button>
-                </AlertDialogTrigger>
-                <AlertDialogContent className="bg-[#111113] border-white/12">
-                  <AlertDialogHeader>
-                    <AlertDialogTitle className="text-sm text-red-300">Full EEPROM reset?</AlertDialogTitle>
-                    <AlertDialogDescription className="text-xs text-white/40">
-                      This erases ALL device EEPROM data including keymaps, macros, RGB settings, and calibration. The device will need to be reconnected after reset.
-                    </AlertDialogDescription>
-                  </AlertDialogHeader>
-                  <AlertDialogFooter>
-                    <AlertDialogCancel className="text-xs h-8">Cancel</AlertDialogCancel>
-                    <AlertDialogAction className="text-xs h-8 bg-red-500/30 text-red-200 hover:bg-red-500/40 border border-red-500/30" onClick={onEepromReset}>
-                      Erase EEPROM
-                    </AlertDialogAction>
-                  </AlertDialogFooter>
-                </AlertDialogContent>
-              </AlertDialog>
+              <ResetDialog
+                kind="Eeprom"
+                title="Full EEPROM reset?"
+                description="This erases ALL device EEPROM data including keymaps, macros, RGB settings, and calibration. The device will need to be reconnected after reset."
+                action="Erase EEPROM"
+                danger
+                onConfirm={onEepromReset}
+              >
+                <button type="button" className={cn(
+                  "flex items-center gap-2 px-3 py-2.5 rounded-lg border transition-all",
+                  "border-red-500/10 bg-red-500/[0.02] hover:bg-red-500/[0.06] hover:border-red-500/20",
+                )}>
+                  <Cpu className="w-3 h-3 text-red-400/30" />
+                  <div className="text-left">
+                    <div className="font-clean text-[10px] text-red-300/50">EEPROM Reset</div>
+                    <div className="font-clean text-[9px] text-red-300/20">Full factory reset</div>
+                  </div>
+                </button>
+              </ResetDialog>
             </div>
 
             {/* Bootloader — separate, most dangerous */}
@@ -454,14 +946,281 @@ export function SettingsView({
           </div>
         )}
 
+        {/* ── Focus timer ───────────────────────────────── */}
+        <FocusCard
+          value={focus}
+          runningUntil={focusUntil}
+          onChange={onFocusChange}
+          onStart={onStartFocus}
+          onStop={onStopFocus}
+        />
+
+        {/* ── Usage ─────────────────────────────────────── */}
+        <UsageStats keys={keys} soundLibrary={soundLibrary} />
+
+        {/* ── Recent errors ─────────────────────────────── */}
+        {recentErrors.length > 0 && (
+          <div className="px-5 py-3 flex flex-col gap-1.5">
+            <div className="flex items-center gap-2 mb-1">
+              <CircleX className="w-3.5 h-3.5 text-red-400/50" />
+              <span className="font-pixel text-[11px] text-white/70 font-bold uppercase tracking-wider">
+                Recent errors
+              </span>
+            </div>
+            {recentErrors.slice(-8).reverse().map((err) => (
+              <div key={`${err.at_ms}-${err.code}`} className="flex items-baseline gap-2 min-w-0">
+                <span className="font-clean text-[9px] text-white/20 shrink-0">
+                  {new Date(err.at_ms).toLocaleTimeString()}
+                </span>
+                <span className="font-pixel text-[8px] text-red-400/50 uppercase shrink-0">{err.code}</span>
+                <span className="font-clean text-[9px] text-white/40 truncate" title={err.context ?? undefined}>
+                  {err.message}
+                </span>
+              </div>
+            ))}
+            <div className="border-b border-white/[0.06] mt-1.5" />
+          </div>
+        )}
+
         {/* ── Footer ────────────────────────────────────── */}
         <div className="flex items-center gap-2 px-5 py-2.5">
           <Info className="w-3 h-3 text-white/15" />
           <span className="font-pixel text-[8px] text-white/20 uppercase tracking-widest">
             churrosoft
           </span>
+          {appInfo && (
+            <>
+              <span
+                className="ml-auto font-clean text-[9px] text-white/20 truncate"
+                title={`Shortcuts: ${appInfo.shortcut_backends.join(", ")}${appInfo.keyboard_layout ? ` (layout ${appInfo.keyboard_layout})` : ""} · Audio: ${appInfo.audio_backend}${appInfo.soundboard_running ? " (soundboard on)" : ""}`}
+              >
+                v{appInfo.version}
+                {appInfo.build_hash && ` (${appInfo.build_hash})`} · {appInfo.platform}
+              </span>
+              <button
+                type="button"
+                className="p-1 rounded hover:bg-white/[0.06] transition-colors"
+                onClick={copyDiagnostics}
+                title="Copy diagnostics for a bug report"
+              >
+                <ClipboardCopy className="w-3 h-3 text-white/20" />
+              </button>
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
+
+/** A global hotkey typed as text ("Ctrl+Alt+Backspace"), saved on Enter or blur; empty clears it. */
+function HotkeyField({ label, value, onCommit }: {
+  label: string;
+  value: string | null;
+  onCommit: (hotkey: string | null) => Promise<boolean>;
+}) {
+  const [draft, setDraft] = useState(value ?? "");
+  useEffect(() => setDraft(value ?? ""), [value]);
+
+  const commit = async () => {
+    const hotkey = draft.trim() || null;
+    if (hotkey === value) return;
+    if (!(await onCommit(hotkey))) setDraft(value ?? "");
+  };
+
+  return (
+    <label className="flex items-center gap-2 pl-10 font-clean text-[10px]">
+      <span className="w-24 text-white/40">{label}</span>
+      <input
+        value={draft}
+        placeholder="e.g. Ctrl+Alt+Backspace"
+        onChange={(e) => setDraft(e.target.value)}
+        onBlur={commit}
+        onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+        className="flex-1 min-w-0 px-2 py-1 rounded bg-[#0d0d0f] border border-white/[0.08] text-white/60 outline-none focus:border-cyan-500/30 placeholder:text-white/15"
+      />
+    </label>
+  );
+}
+
+function DiscordCard({ value, onChange }: {
+  value: DiscordSettings;
+  onChange: (discord: DiscordSettings) => void;
+}) {
+  const [clientId, setClientId] = useState(value.client_id);
+  const [secret, setSecret] = useState(value.client_secret ?? "");
+  useEffect(() => setClientId(value.client_id), [value.client_id]);
+  useEffect(() => setSecret(value.client_secret ?? ""), [value.client_secret]);
+
+  const commit = () => {
+    const next = { ...value, client_id: clientId.trim(), client_secret: secret.trim() || null };
+    if (next.client_id !== value.client_id || next.client_secret !== value.client_secret) onChange(next);
+  };
+  const fieldClass = "flex-1 min-w-0 px-2 py-1 rounded bg-[#0d0d0f] border border-white/[0.08] text-white/60 outline-none focus:border-cyan-500/30 placeholder:text-white/15";
+
+  return (
+    <div
+      className={cn(
+        "flex flex-col gap-2 px-3.5 py-3 rounded-xl border transition-all duration-150",
+        value.presence
+          ? "border-emerald-500/20 bg-emerald-500/[0.04]"
+          : "border-white/[0.06] bg-white/[0.02]",
+      )}
+    >
+      <div className="flex items-center gap-3">
+        <div className={cn(
+          "flex items-center justify-center w-7 h-7 rounded-lg",
+          value.presence ? "bg-emerald-500/15 text-emerald-400" : "bg-white/[0.06] text-white/25",
+        )}>
+          <Gamepad2 className="w-3.5 h-3.5" />
+        </div>
+        <div className="flex-1 min-w-0">
+          <div className="font-clean text-[11px] text-white/80 font-medium">Discord presence</div>
+          <div className="font-clean text-[9px] text-white/30 mt-0.5">
+            Show the theme your keys are on as your Discord activity. Uses your own Discord application; voice state bindings also need its client secret
+          </div>
+        </div>
+        <button
+          type="button"
+          role="switch"
+          aria-checked={value.presence}
+          aria-label="Discord presence"
+          className="flex-shrink-0"
+          onClick={() => onChange({ ...value, presence: !value.presence })}
+        >
+          <div className={cn(
+            "w-9 h-5 rounded-full p-[2px] transition-all duration-150 cursor-pointer",
+            value.presence ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+          )}>
+            <div className={cn(
+              "w-4 h-4 rounded-full transition-all duration-150",
+              value.presence ? "translate-x-4 bg-white" : "translate-x-0 bg-white/30",
+            )} />
+          </div>
+        </button>
+      </div>
+      <label className="flex items-center gap-2 pl-10 font-clean text-[10px]">
+        <span className="w-24 text-white/40">Client id</span>
+        <input
+          value={clientId}
+          inputMode="numeric"
+          placeholder="From discord.com/developers"
+          onChange={(e) => setClientId(e.target.value)}
+          onBlur={commit}
+          onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+          className={fieldClass}
+        />
+      </label>
+      <label className="flex items-center gap-2 pl-10 font-clean text-[10px]">
+        <span className="w-24 text-white/40">Client secret</span>
+        <input
+          type="password"
+          value={secret}
+          placeholder="Only for voice state bindings"
+          onChange={(e) => setSecret(e.target.value)}
+          onBlur={commit}
+          onKeyDown={(e) => e.key === "Enter" && e.currentTarget.blur()}
+          className={fieldClass}
+        />
+      </label>
+    </div>
+  );
+}
+
+/** Read timeout and report spacing, for hubs or firmware builds that drop reports. */
+function HidTimingCard({ value, onChange }: {
+  value: HidTiming;
+  onChange: (timing: HidTiming) => void;
+}) {
+  const [draft, setDraft] = useState(value);
+  useEffect(() => setDraft(value), [value]);
+
+  const commit = () => {
+    if (draft.timeout_ms !== value.timeout_ms || draft.pacing_ms !== value.pacing_ms) onChange(draft);
+  };
+  const fields: { field: keyof HidTiming; label: string; min: number; max: number }[] = [
+    { field: "timeout_ms", label: "Timeout", min: 100, max: 1000 },
+    { field: "pacing_ms", label: "Gap", min: 0, max: 50 },
+  ];
+
+  return (
+    <div className="flex items-center gap-3 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
+      <div className="flex items-center justify-center w-7 h-7 rounded-lg bg-white/[0.06] text-white/25">
+        <Timer className="w-3.5 h-3.5" />
+      </div>
+      <div className="flex-1 min-w-0">
+        <div className="font-clean text-[11px] text-white/80 font-medium">Device timing</div>
+        <div className="font-clean text-[9px] text-white/30 mt-0.5">
+          Raise these if colors or keymaps go missing behind a USB hub
+        </div>
+      </div>
+      {fields.map(({ field, label, min, max }) => (
+        <label key={field} className="flex items-center gap-1 font-clean text-[9px] text-white/30">
+          {label}
+          <input
+            type="number"
+            min={min}
+            max={max}
+            step={field === "timeout_ms" ? 50 : 1}
+            value={draft[field]}
+            onChange={(e) => setDraft({ ...draft, [field]: Number(e.target.value) })}
+            onBlur={commit}
+            onKeyDown={(e) => {
+              if (e.key === "Enter") commit();
+            }}
+            className="w-12 px-1.5 py-0.5 rounded bg-[#0d0d0f] border border-white/[0.08] font-clean text-[10px] text-white/60 outline-none"
+          />
+          ms
+        </label>
+      ))}
+    </div>
+  );
+}
+
+function WindowCard({ value, onChange }: {
+  value: WindowSettings;
+  onChange: (window: WindowSettings) => void;
+}) {
+  const toggles: { field: "minimize_to_tray" | "confirm_quit"; label: string }[] = [
+    { field: "minimize_to_tray", label: "Minimizing hides to the tray" },
+    { field: "confirm_quit", label: "Ask before quitting while a sound plays" },
+  ];
+
+  return (
+    <div className="flex flex-col gap-2 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
+      <div className="flex items-center gap-3">
+        <div className="flex items-center justify-center w-7 h-7 rounded-lg bg-white/[0.06] text-white/25">
+          <AppWindow className="w-3.5 h-3.5" />
+        </div>
+        <div className="flex-1 min-w-0">
+          <div className="font-clean text-[11px] text-white/80 font-medium">Closing the window</div>
+          <div className="font-clean text-[9px] text-white/30 mt-0.5">
+            {value.close === "HideToTray"
+              ? "Keeps the keys and sounds working from the tray; quit from the tray menu"
+              : "Quits the app, stopping the keys' sounds and shortcuts"}
+          </div>
         </div>
+        <select
+          className="px-2 py-1 rounded bg-[#0d0d0f] border border-white/[0.08] font-clean text-[10px] text-white/60 outline-none appearance-none cursor-pointer"
+          value={value.close}
+          onChange={(e) => onChange({ ...value, close: e.target.value as WindowSettings["close"] })}
+          aria-label="Close button"
+        >
+          <option value="HideToTray">Hides to tray</option>
+          <option value="Exit">Quits</option>
+        </select>
       </div>
+      {toggles.map(({ field, label }) => (
+        <label key={field} className="flex items-center gap-2 pl-10 font-clean text-[10px] text-white/40 cursor-pointer">
+          <input
+            type="checkbox"
+            className="accent-emerald-400"
+            checked={value[field]}
+            onChange={(e) => onChange({ ...value, [field]: e.target.checked })}
+          />
+          {label}
+        </label>
+      ))}
     </div>
   );
 }
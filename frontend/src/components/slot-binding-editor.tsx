@@ -0,0 +1,359 @@
+import { useEffect, useState } from "react";
+import { Radio } from "lucide-react";
+import type { Condition, SlotBinding } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface SlotBindingEditorProps {
+  slotNames: string[];
+  binding: SlotBinding | null;
+  onChange: (binding: SlotBinding | null) => void;
+}
+
+type ConditionKind = Condition["kind"];
+
+const KIND_LABELS: Record<ConditionKind, string> = {
+  MicMuted: "Mic muted",
+  ObsRecording: "OBS recording",
+  ObsStreaming: "OBS streaming",
+  ObsScene: "OBS scene live",
+  FileExists: "File exists",
+  Mqtt: "MQTT topic",
+  HttpPoll: "HTTP poll",
+  TeamsMuted: "Teams muted",
+  ZoomMuted: "Zoom muted",
+  MediaPlaying: "Media playing",
+  MixerMuted: "Mixer channel muted",
+  SoundboardRunning: "Soundboard on",
+  DiscordMuted: "Discord muted",
+  DiscordSpeaking: "Discord speaking",
+  ModeActive: "Mode active",
+  CalendarMeeting: "Calendar meeting",
+  FocusRunning: "Focus running",
+};
+
+function defaultCondition(kind: ConditionKind): Condition {
+  switch (kind) {
+    case "MicMuted":
+    case "TeamsMuted":
+    case "ZoomMuted":
+    case "MediaPlaying":
+    case "SoundboardRunning":
+    case "DiscordMuted":
+    case "DiscordSpeaking":
+    case "FocusRunning":
+      return { kind };
+    case "ObsRecording":
+    case "ObsStreaming":
+      return { kind, host: "localhost", port: 4455, password: null };
+    case "ObsScene":
+      return { kind, host: "localhost", port: 4455, password: null, scene: "" };
+    case "FileExists":
+      return { kind, path: "" };
+    case "Mqtt":
+      return { kind, host: "localhost", port: 1883, topic: "", payload: "ON" };
+    case "HttpPoll":
+      return { kind, url: "http://", contains: null };
+    case "MixerMuted":
+      return { kind, mixer: "WaveLink", channel: "" };
+    case "ModeActive":
+      return { kind, mode: "" };
+    case "CalendarMeeting":
+      return { kind, url: "", lead_min: 5, open_link: true };
+  }
+}
+
+type ObsCondition = Extract<Condition, { kind: "ObsRecording" | "ObsStreaming" | "ObsScene" }>;
+
+function isObs(c: Condition): c is ObsCondition {
+  return c.kind === "ObsRecording" || c.kind === "ObsStreaming" || c.kind === "ObsScene";
+}
+
+const fieldClass = cn(
+  "w-full px-2 py-1 rounded-md text-[10px] font-clean",
+  "bg-[#0d0d0f] border border-white/[0.08] text-white/60",
+  "focus:outline-none focus:border-violet-500/30",
+);
+
+/** Drives a key's active slot from a watched condition. Edits apply on "Apply". */
+export function SlotBindingEditor({ slotNames, binding, onChange }: SlotBindingEditorProps) {
+  const [draft, setDraft] = useState<SlotBinding | null>(binding);
+
+  // Follow external changes (e.g. a slot rename updates the binding)
+  useEffect(() => {
+    setDraft(binding);
+  }, [binding]);
+
+  const setKind = (kind: ConditionKind | "") => {
+    if (kind === "") {
+      setDraft(null);
+      return;
+    }
+    setDraft({
+      condition: defaultCondition(kind),
+      when_true: draft?.when_true ?? slotNames[slotNames.length > 1 ? 1 : 0],
+      when_false: draft?.when_false ?? slotNames[0],
+      poll_ms: draft?.poll_ms ?? 1000,
+    });
+  };
+
+  const setCondition = (patch: Partial<Condition>) => {
+    if (!draft) return;
+    setDraft({ ...draft, condition: { ...draft.condition, ...patch } as Condition });
+  };
+
+  const dirty = JSON.stringify(draft) !== JSON.stringify(binding);
+  const c = draft?.condition;
+
+  return (
+    <div className="flex flex-col gap-1.5 rounded-lg border border-white/[0.06] px-3 py-2">
+      <div className="flex items-center gap-1.5">
+        <Radio className="w-3 h-3 text-white/20" />
+        <span className="text-[10px] font-medium text-white/30">Follow condition</span>
+        <select
+          className={cn(fieldClass, "ml-auto w-auto appearance-none cursor-pointer")}
+          value={c?.kind ?? ""}
+          onChange={(e) => setKind(e.target.value as ConditionKind | "")}
+        >
+          <option value="">Off</option>
+          {(Object.keys(KIND_LABELS) as ConditionKind[]).map((k) => (
+            <option key={k} value={k}>
+              {KIND_LABELS[k]}
+            </option>
+          ))}
+        </select>
+      </div>
+
+      {draft && c && (
+        <>
+          {(isObs(c) || c.kind === "Mqtt") && (
+            <div className="flex gap-1.5">
+              <input
+                className={fieldClass}
+                value={c.host}
+                placeholder="Host"
+                onChange={(e) => setCondition({ host: e.target.value })}
+              />
+              <input
+                className={cn(fieldClass, "w-16")}
+                type="number"
+                value={c.port}
+                onChange={(e) => setCondition({ port: Number(e.target.value) })}
+              />
+            </div>
+          )}
+          {isObs(c) && (
+            <input
+              className={fieldClass}
+              type="password"
+              value={c.password ?? ""}
+              placeholder="Password (if enabled)"
+              onChange={(e) => setCondition({ password: e.target.value || null })}
+            />
+          )}
+          {c.kind === "ObsScene" && (
+            <input
+              className={fieldClass}
+              value={c.scene}
+              placeholder="Scene name"
+              onChange={(e) => setCondition({ scene: e.target.value })}
+            />
+          )}
+          {isObs(c) && (
+            <span className="text-[9px] text-white/30">
+              {c.kind === "ObsScene"
+                ? "Lit while the scene is live in OBS. The key switches to it."
+                : `Follows OBS as it changes. The key toggles the ${c.kind === "ObsStreaming" ? "stream" : "recording"}.`}
+            </span>
+          )}
+          {c.kind === "Mqtt" && (
+            <div className="flex gap-1.5">
+              <input
+                className={fieldClass}
+                value={c.topic}
+                placeholder="Topic"
+                onChange={(e) => setCondition({ topic: e.target.value })}
+              />
+              <input
+                className={cn(fieldClass, "w-20")}
+                value={c.payload}
+                placeholder="Payload"
+                onChange={(e) => setCondition({ payload: e.target.value })}
+              />
+            </div>
+          )}
+          {c.kind === "FileExists" && (
+            <input
+              className={fieldClass}
+              value={c.path}
+              placeholder="File path"
+              onChange={(e) => setCondition({ path: e.target.value })}
+            />
+          )}
+          {c.kind === "HttpPoll" && (
+            <>
+              <input
+                className={fieldClass}
+                value={c.url}
+                placeholder="URL"
+                onChange={(e) => setCondition({ url: e.target.value })}
+              />
+              <input
+                className={fieldClass}
+                value={c.contains ?? ""}
+                placeholder="Body contains (optional)"
+                onChange={(e) => setCondition({ contains: e.target.value || null })}
+              />
+            </>
+          )}
+
+          {c.kind === "TeamsMuted" && (
+            <span className="text-[9px] text-white/30">
+              Turn on Teams → Settings → Privacy → Manage API, then allow Deck8 Hub the first time you press the key in a meeting. The key toggles mute.
+            </span>
+          )}
+          {c.kind === "ZoomMuted" && (
+            <span className="text-[9px] text-white/30">
+              The key toggles mute (Alt+A — enable Zoom's global shortcut for it). On macOS the LED follows Zoom (with the Accessibility permission); elsewhere it follows this key's toggles only.
+            </span>
+          )}
+
+          {c.kind === "MediaPlaying" && (
+            <span className="text-[9px] text-white/30">
+              Follows the system media session. The key toggles play/pause.
+            </span>
+          )}
+
+          {(c.kind === "DiscordMuted" || c.kind === "DiscordSpeaking") && (
+            <span className="text-[9px] text-white/30">
+              Needs your Discord application's client id and secret in Settings; Discord asks you to authorize Deck8 Hub once.
+              {c.kind === "DiscordMuted" ? " The key toggles mute." : " Lit while Discord hears you in a voice channel."}
+            </span>
+          )}
+
+          {c.kind === "SoundboardRunning" && (
+            <span className="text-[9px] text-white/30">
+              Follows whether the mic is routed through the soundboard. The key turns it on or off.
+            </span>
+          )}
+
+          {c.kind === "FocusRunning" && (
+            <span className="text-[9px] text-white/30">
+              Lit during a focus session (Settings → Focus). The key starts one, or stops the one running.
+            </span>
+          )}
+
+          {c.kind === "ModeActive" && (
+            <>
+              <input
+                className={fieldClass}
+                value={c.mode}
+                placeholder="Mode name"
+                onChange={(e) => setCondition({ mode: e.target.value })}
+              />
+              <span className="text-[9px] text-white/30">
+                Lit while the mode is active. The key cycles to the next mode.
+              </span>
+            </>
+          )}
+
+          {c.kind === "CalendarMeeting" && (
+            <>
+              <input
+                className={fieldClass}
+                value={c.url}
+                placeholder="Calendar ICS address (https:// or webcal://)"
+                onChange={(e) => setCondition({ url: e.target.value })}
+              />
+              <div className="flex items-center gap-1.5 text-[9px] text-white/30">
+                <input
+                  className={cn(fieldClass, "w-14")}
+                  type="number"
+                  min={0}
+                  max={120}
+                  value={c.lead_min}
+                  onChange={(e) => setCondition({ lead_min: Math.max(0, Number(e.target.value)) })}
+                />
+                <span>min before</span>
+                <label className="ml-auto flex items-center gap-1 cursor-pointer">
+                  <input
+                    type="checkbox"
+                    checked={c.open_link}
+                    onChange={(e) => setCondition({ open_link: e.target.checked })}
+                  />
+                  Key opens the link
+                </label>
+              </div>
+              <span className="text-[9px] text-white/30">
+                Outlook: Publish a calendar → ICS link. Google: Settings → Secret address in iCal format. Checked every 5 minutes.
+              </span>
+            </>
+          )}
+
+          {c.kind === "MixerMuted" && (
+            <>
+              <div className="flex gap-1.5">
+                <select
+                  className={cn(fieldClass, "w-auto appearance-none cursor-pointer")}
+                  value={c.mixer}
+                  onChange={(e) => setCondition({ mixer: e.target.value as "WaveLink" | "VoiceMeeter" })}
+                >
+                  <option value="WaveLink">Wave Link</option>
+                  <option value="VoiceMeeter">VoiceMeeter</option>
+                </select>
+                <input
+                  className={fieldClass}
+                  value={c.channel}
+                  placeholder={c.mixer === "WaveLink" ? "Input name" : "Strip[0] / Bus[0]"}
+                  onChange={(e) => setCondition({ channel: e.target.value })}
+                />
+              </div>
+              <span className="text-[9px] text-white/30">
+                {c.mixer === "WaveLink"
+                  ? "Follows the input's stream mix mute in Wave Link. The key toggles it."
+                  : "Follows the strip or bus mute in VoiceMeeter (Windows). The key toggles it."}
+              </span>
+            </>
+          )}
+
+          <div className="flex items-center gap-1.5 text-[9px] text-white/30">
+            <span>True</span>
+            <select
+              className={cn(fieldClass, "appearance-none cursor-pointer")}
+              value={draft.when_true}
+              onChange={(e) => setDraft({ ...draft, when_true: e.target.value })}
+            >
+              {slotNames.map((n) => <option key={n} value={n}>{n}</option>)}
+            </select>
+            <span>False</span>
+            <select
+              className={cn(fieldClass, "appearance-none cursor-pointer")}
+              value={draft.when_false}
+              onChange={(e) => setDraft({ ...draft, when_false: e.target.value })}
+            >
+              {slotNames.map((n) => <option key={n} value={n}>{n}</option>)}
+            </select>
+            <input
+              className={cn(fieldClass, "w-16")}
+              type="number"
+              min={100}
+              step={100}
+              value={draft.poll_ms}
+              title="Poll interval (ms)"
+              onChange={(e) => setDraft({ ...draft, poll_ms: Number(e.target.value) })}
+            />
+          </div>
+        </>
+      )}
+
+      {dirty && (
+        <button
+          type="button"
+          className="self-end px-2 py-0.5 rounded-md text-[10px] font-medium text-violet-300/70 hover:bg-violet-500/[0.08] transition-colors"
+          onClick={() => onChange(draft)}
+        >
+          Apply
+        </button>
+      )}
+    </div>
+  );
+}
@@ -6,15 +6,16 @@ import {
 import { Slider } from "@/components/ui/slider";
 import { cn } from "@/lib/utils";
 import { Play, Pause, Upload, Volume2 } from "lucide-react";
-import type { SoundEntry } from "@/lib/tauri";
+import { stopPreview } from "@/lib/tauri";
+import type { SoundEntry, Trim } from "@/lib/tauri";
 
 interface SoundUploadDialogProps {
   open: boolean;
   onClose: () => void;
   onGetDuration: (filePath: string) => Promise<number>;
-  onPreviewTrim: (sourcePath: string, startMs: number, endMs: number) => void;
+  onPreviewTrim: (sourcePath: string, trim: Trim) => void;
   onAddToLibrary: (filePath: string, displayName: string) => Promise<SoundEntry | null>;
-  onAddToLibraryTrimmed: (filePath: string, displayName: string, startMs: number, endMs: number) => Promise<SoundEntry | null>;
+  onAddToLibraryTrimmed: (filePath: string, displayName: string, trim: Trim) => Promise<SoundEntry | null>;
 }
 
 function formatTime(ms: number): string {
@@ -57,6 +58,9 @@ export function SoundUploadDialog({
   const [durationMs, setDurationMs] = useState(0);
   const [startMs, setStartMs] = useState(0);
   const [endMs, setEndMs] = useState(0);
+  const [fadeInMs, setFadeInMs] = useState(0);
+  const [fadeOutMs, setFadeOutMs] = useState(0);
+  const [gainDb, setGainDb] = useState(0);
   const [isPlaying, setIsPlaying] = useState(false);
   const [submitting, setSubmitting] = useState(false);
   const playTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
@@ -82,9 +86,13 @@ export function SoundUploadDialog({
       setDurationMs(0);
       setStartMs(0);
       setEndMs(0);
+      setFadeInMs(0);
+      setFadeOutMs(0);
+      setGainDb(0);
       setIsPlaying(false);
       setSubmitting(false);
       if (playTimeoutRef.current) clearTimeout(playTimeoutRef.current);
+      stopPreview().catch(() => {});
     }
   }, [open]);
 
@@ -115,32 +123,47 @@ export function SoundUploadDialog({
     }
   }, [onGetDuration]);
 
+  // Preview and upload send the same trim, so what plays is what's saved
+  const trim = useMemo<Trim>(
+    () => ({ start_ms: startMs, end_ms: endMs, fade_in_ms: fadeInMs, fade_out_ms: fadeOutMs, gain_db: gainDb }),
+    [startMs, endMs, fadeInMs, fadeOutMs, gainDb],
+  );
+  const clipMs = endMs - startMs;
+
   const handlePreview = useCallback(() => {
-    if (!filePath || isPlaying) return;
+    if (!filePath) return;
+    if (isPlaying) {
+      // Pause: the preview stops right away
+      if (playTimeoutRef.current) clearTimeout(playTimeoutRef.current);
+      setIsPlaying(false);
+      stopPreview().catch(() => {});
+      return;
+    }
     setIsPlaying(true);
-    onPreviewTrim(filePath, startMs, endMs);
+    onPreviewTrim(filePath, trim);
     // Auto-reset playing state after the clip duration
     const clipDuration = endMs - startMs;
     if (playTimeoutRef.current) clearTimeout(playTimeoutRef.current);
     playTimeoutRef.current = setTimeout(() => {
       setIsPlaying(false);
     }, clipDuration + 200);
-  }, [filePath, startMs, endMs, isPlaying, onPreviewTrim]);
+  }, [filePath, trim, startMs, endMs, isPlaying, onPreviewTrim]);
 
   const handleSubmit = useCallback(async () => {
     if (!filePath || !displayName.trim()) return;
     setSubmitting(true);
     try {
-      if (startMs === 0 && endMs === durationMs) {
+      const untouched = startMs === 0 && endMs === durationMs && fadeInMs === 0 && fadeOutMs === 0 && gainDb === 0;
+      if (untouched) {
         await onAddToLibrary(filePath, displayName.trim());
       } else {
-        await onAddToLibraryTrimmed(filePath, displayName.trim(), startMs, endMs);
+        await onAddToLibraryTrimmed(filePath, displayName.trim(), trim);
       }
       onClose();
     } finally {
       setSubmitting(false);
     }
-  }, [filePath, displayName, startMs, endMs, durationMs, onAddToLibrary, onAddToLibraryTrimmed, onClose]);
+  }, [filePath, displayName, trim, startMs, endMs, durationMs, fadeInMs, fadeOutMs, gainDb, onAddToLibrary, onAddToLibraryTrimmed, onClose]);
 
   // Calculate which waveform bars are in the selected range
   const startFrac = durationMs > 0 ? startMs / durationMs : 0;
@@ -185,7 +208,7 @@ export function SoundUploadDialog({
                     : "bg-white/[0.04] text-white/15 cursor-not-allowed",
                 )}
                 onClick={handlePreview}
-                disabled={!filePath || isPlaying}
+                disabled={!filePath}
               >
                 {isPlaying ? (
                   <Pause className="w-4 h-4" />
@@ -260,6 +283,34 @@ export function SoundUploadDialog({
                     setEndMs(e);
                   }}
                 />
+                <div className="grid grid-cols-2 gap-3 mt-1">
+                  <div className="flex flex-col gap-1.5">
+                    <div className="flex items-center justify-between">
+                      <span className="font-pixel text-[8px] text-white/30 uppercase tracking-widest">Fade in</span>
+                      <span className="font-clean text-[9px] text-white/25 tabular-nums">{formatTime(fadeInMs)}</span>
+                    </div>
+                    <Slider
+                      min={0}
+                      max={Math.max(50, Math.min(5000, clipMs))}
+                      step={50}
+                      value={[Math.min(fadeInMs, clipMs)]}
+                      onValueChange={([v]) => setFadeInMs(v)}
+                    />
+                  </div>
+                  <div className="flex flex-col gap-1.5">
+                    <div className="flex items-center justify-between">
+                      <span className="font-pixel text-[8px] text-white/30 uppercase tracking-widest">Fade out</span>
+                      <span className="font-clean text-[9px] text-white/25 tabular-nums">{formatTime(fadeOutMs)}</span>
+                    </div>
+                    <Slider
+                      min={0}
+                      max={Math.max(50, Math.min(5000, clipMs))}
+                      step={50}
+                      value={[Math.min(fadeOutMs, clipMs)]}
+                      onValueChange={([v]) => setFadeOutMs(v)}
+                    />
+                  </div>
+                </div>
               </div>
             )}
           </div>
@@ -318,21 +369,24 @@ export function SoundUploadDialog({
             />
           </div>
 
-          {/* Volume (preview) */}
+          {/* Gain, applied in the preview and the saved file */}
           {filePath && (
             <div className="flex flex-col gap-2">
               <div className="flex items-center gap-1.5">
                 <Volume2 className="w-3 h-3 text-white/25" />
-                <span className="font-clean text-[11px] text-white/70 font-semibold">
+                <span className="flex-1 font-clean text-[11px] text-white/70 font-semibold">
                   Volumen del sonido
                 </span>
+                <span className="font-clean text-[9px] text-white/25 tabular-nums">
+                  {gainDb > 0 ? "+" : ""}{gainDb.toFixed(1)} dB
+                </span>
               </div>
               <Slider
-                min={0}
-                max={1}
-                step={0.01}
-                value={[1]}
-                disabled
+                min={-24}
+                max={12}
+                step={0.5}
+                value={[gainDb]}
+                onValueChange={([v]) => setGainDb(v)}
               />
             </div>
           )}
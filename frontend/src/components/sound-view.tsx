@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { cn } from "@/lib/utils";
 import { Slider } from "@/components/ui/slider";
 import {
@@ -16,9 +16,22 @@ import {
   HelpCircle,
   CheckCircle2,
   Circle,
+  Moon,
+  Hand,
+  Archive,
+  Headphones,
+  SlidersHorizontal,
+  AudioLines,
+  Square,
+  VolumeX,
 } from "lucide-react";
-import type { AudioConfig, AudioDeviceList, SoundEntry } from "@/lib/tauri";
+import { getLibraryAudioInfo } from "@/lib/tauri";
+import type { AgcSettings, AudioConfig, AudioInfo, AudioDeviceList, Playlist, QuietHours, SoundEntry, SoundFormat, SoundRoute, StageKind, Trim } from "@/lib/tauri";
 import { SoundUploadDialog } from "@/components/sound-upload-dialog";
+import { AudioMetrics } from "@/components/audio-metrics";
+import { ProcessingChain } from "@/components/processing-chain";
+import { LibraryStorage } from "@/components/library-storage";
+import { PlaylistEditor } from "@/components/playlist-editor";
 
 /**
  * Maps visual grid position to hardware index.
@@ -26,21 +39,75 @@ import { SoundUploadDialog } from "@/components/sound-upload-dialog";
  * Bottom row: left-to-right = 7,6,5,4 (firmware scans right-to-left)
  */
 const DISPLAY_ORDER = [0, 1, 2, 3, 7, 6, 5, 4];
+/** Output the backend creates on demand on Linux (see virtual_sink.rs). */
+const LINUX_SINK = "Deck8 Soundboard";
+
+const PREVIEW_ROUTES: Record<SoundRoute, string> = { Monitor: "Headphones", Cable: "Call", Both: "Both" };
+
+/** Minutes after midnight ↔ "HH:MM" for `<input type="time">`. */
+function minutesToTime(min: number) {
+  return `${String(Math.floor(min / 60)).padStart(2, "0")}:${String(min % 60).padStart(2, "0")}`;
+}
+
+function timeToMinutes(time: string) {
+  const [h, m] = time.split(":").map(Number);
+  return h * 60 + m;
+}
+
+/** "0:04" / "12:30". */
+function formatDuration(ms: number) {
+  const sec = Math.round(ms / 1000);
+  return `${Math.floor(sec / 60)}:${String(sec % 60).padStart(2, "0")}`;
+}
+
+/** "MP3 · 44.1 kHz · stereo · 192 kbps" */
+function audioInfoDetails(info: AudioInfo) {
+  const channels = info.channels === 1 ? "mono" : info.channels === 2 ? "stereo" : `${info.channels} ch`;
+  const parts = [info.codec, `${info.sample_rate / 1000} kHz`, channels];
+  if (info.bitrate_kbps !== null) parts.push(`${info.bitrate_kbps} kbps`);
+  return parts.join(" · ");
+}
 
 interface SoundViewProps {
   audioConfig: AudioConfig;
   audioDevices: AudioDeviceList;
   onSelectInput: (name: string) => void;
   onSelectOutput: (name: string) => void;
+  onSelectSecondInput: (name: string | null) => void;
+  onSecondInputVolumeChange: (vol: number) => void;
   onSoundVolumeChange: (vol: number) => void;
   onMicVolumeChange: (vol: number) => void;
+  onInputGainChange: (gainDb: number) => void;
+  onProcessingStageChange: (kind: StageKind, enabled: boolean) => void;
+  onProcessingOrderChange: (order: StageKind[]) => void;
+  onAgcChange: (agc: AgcSettings) => void;
+  onStopWordChange: (enabled: boolean) => void;
+  onSoundboardToggle: (on: boolean) => void;
+  onStopAllSounds: () => void;
+  onSoundsMutedChange: (muted: boolean) => void;
+  quietHours: QuietHours;
+  quietActive: boolean;
+  quietOverrideUntil: number | null;
+  onQuietHoursChange: (quietHours: QuietHours) => void;
+  onOverrideQuiet: (minutes: number) => void;
   onRefreshDevices: () => void;
   onGetDuration: (filePath: string) => Promise<number>;
-  onPreviewTrim: (sourcePath: string, startMs: number, endMs: number) => void;
+  onPreviewTrim: (sourcePath: string, trim: Trim) => void;
   onAddToLibrary: (filePath: string, displayName: string) => Promise<SoundEntry | null>;
-  onAddToLibraryTrimmed: (filePath: string, displayName: string, startMs: number, endMs: number) => Promise<SoundEntry | null>;
+  onAddToLibraryTrimmed: (filePath: string, displayName: string, trim: Trim) => Promise<SoundEntry | null>;
   onRemoveFromLibrary: (soundId: string) => void;
   onRenameSound: (soundId: string, newName: string) => void;
+  onSoundFormatChange: (format: SoundFormat) => void;
+  onPreviewRouteChange: (route: SoundRoute) => void;
+  onSoundMonitorOnlyChange: (soundId: string, monitorOnly: boolean) => void;
+  onSavePlaylist: (playlist: Playlist) => Promise<boolean>;
+  onDeletePlaylist: (playlistId: string) => void;
+  onSkipPlaylist: () => void;
+  onStopPlaylist: () => void;
+  onCompressLibrary: () => void;
+  onCleanLibrary: (removeUnused: boolean) => Promise<void>;
+  onRelinkSound: (soundId: string) => void;
+  onLibraryQuotaChange: (mb: number | null) => void;
   onSetKeySound: (keyIndex: number, soundId: string | null) => void;
   onPreviewLibrarySound: (soundId: string) => void;
 }
@@ -50,38 +117,73 @@ export function SoundView({
   audioDevices,
   onSelectInput,
   onSelectOutput,
+  onSelectSecondInput,
+  onSecondInputVolumeChange,
   onSoundVolumeChange,
   onMicVolumeChange,
+  onInputGainChange,
+  onProcessingStageChange,
+  onProcessingOrderChange,
+  onAgcChange,
+  onStopWordChange,
+  onSoundboardToggle,
+  onStopAllSounds,
+  onSoundsMutedChange,
+  quietHours,
+  quietActive,
+  quietOverrideUntil,
+  onQuietHoursChange,
+  onOverrideQuiet,
   onRefreshDevices,
   onGetDuration,
   onPreviewTrim,
   onAddToLibrary,
   onAddToLibraryTrimmed,
   onRemoveFromLibrary,
+  onSoundFormatChange,
+  onPreviewRouteChange,
+  onSoundMonitorOnlyChange,
+  onSavePlaylist,
+  onDeletePlaylist,
+  onSkipPlaylist,
+  onStopPlaylist,
+  onCompressLibrary,
+  onCleanLibrary,
+  onRelinkSound,
+  onLibraryQuotaChange,
   onSetKeySound,
   onPreviewLibrarySound,
 }: SoundViewProps) {
   const [uploadOpen, setUploadOpen] = useState(false);
   const [keyDropdown, setKeyDropdown] = useState<number | null>(null);
+  const [audioInfo, setAudioInfo] = useState<Record<string, AudioInfo>>({});
+
+  useEffect(() => {
+    getLibraryAudioInfo().then(setAudioInfo).catch(() => {});
+  }, [audioConfig.sound_library]);
 
   const getSoundName = (soundId: string | null): string | null => {
     if (!soundId) return null;
     const entry = audioConfig.sound_library.find((e) => e.id === soundId);
-    return entry?.display_name ?? null;
+    return entry?.display_name ?? audioConfig.playlists.find((p) => p.id === soundId)?.name ?? null;
   };
 
   const hasVirtualCable = audioDevices.output_devices.some(
     (d) =>
       d.name.toLowerCase().includes("cable") ||
       d.name.toLowerCase().includes("blackhole") ||
-      d.name.toLowerCase().includes("virtual"),
+      d.name.toLowerCase().includes("virtual") ||
+      d.name.toLowerCase().includes("voicemeeter") ||
+      d.name.toLowerCase().includes("wave link") ||
+      d.name === LINUX_SINK,
   );
 
   const hasInputSelected = !!audioConfig.audio_input_device;
   const hasOutputSelected = !!audioConfig.audio_output_device;
   const isOutputVirtualCable = hasOutputSelected && (() => {
     const name = (audioConfig.audio_output_device ?? "").toLowerCase();
-    return name.includes("cable") || name.includes("blackhole") || name.includes("virtual");
+    return name.includes("cable") || name.includes("blackhole") || name.includes("virtual")
+      || name.includes("voicemeeter") || name.includes("wave link") || name === LINUX_SINK.toLowerCase();
   })();
   const pipelineReady = hasVirtualCable && hasInputSelected && isOutputVirtualCable;
 
@@ -91,7 +193,7 @@ export function SoundView({
     {
       done: hasVirtualCable,
       text: "Instalá un cable de audio virtual",
-      detail: "Windows: VB-Cable / macOS: BlackHole 2ch",
+      detail: "Windows: VB-Cable / macOS: BlackHole 2ch / Linux: no hace falta",
     },
     {
       done: hasInputSelected,
@@ -101,12 +203,12 @@ export function SoundView({
     {
       done: isOutputVirtualCable,
       text: "Seleccioná el cable virtual como Output",
-      detail: "Elegí \"CABLE Input\" (VB-Cable) o \"BlackHole 2ch\" (macOS)",
+      detail: "Elegí \"CABLE Input\" (VB-Cable), \"BlackHole 2ch\" (macOS) o \"Deck8 Soundboard\" (Linux)",
     },
     {
       done: false, // Can't auto-detect Discord settings
       text: "En Discord: Dispositivo de entrada → \"CABLE Output\"",
-      detail: "Ajustes → Voz y vídeo → Dispositivo de entrada",
+      detail: "Ajustes → Voz y vídeo → Dispositivo de entrada (Linux: \"Deck8 Soundboard Mic\")",
     },
     {
       done: false,
@@ -130,6 +232,45 @@ export function SoundView({
             {audioConfig.soundboard_enabled && (
               <span className="w-1.5 h-1.5 rounded-full bg-emerald-400/80 animate-pulse-subtle" />
             )}
+            <button
+              type="button"
+              className="ml-auto p-1 rounded hover:bg-white/10 text-white/25 hover:text-white/50 transition-colors"
+              onClick={onStopAllSounds}
+              title="Stop every sound playing (also in the tray)"
+            >
+              <Square className="w-3 h-3" />
+            </button>
+            <button
+              type="button"
+              aria-pressed={audioConfig.sounds_muted}
+              className={cn(
+                "p-1 rounded hover:bg-white/10 transition-colors",
+                audioConfig.sounds_muted ? "text-red-400/80" : "text-white/25 hover:text-white/50",
+              )}
+              onClick={() => onSoundsMutedChange(!audioConfig.sounds_muted)}
+              title={audioConfig.sounds_muted ? "Key sounds are muted — click to unmute" : "Mute key sounds (the mic keeps passing through)"}
+            >
+              <VolumeX className="w-3 h-3" />
+            </button>
+            <button
+              type="button"
+              role="switch"
+              aria-checked={!audioConfig.soundboard_off}
+              aria-label="Soundboard"
+              className="ml-1"
+              onClick={() => onSoundboardToggle(audioConfig.soundboard_off)}
+              title="Route the mic through the soundboard (a key can toggle this too)"
+            >
+              <div className={cn(
+                "w-7 h-4 rounded-full p-[2px] transition-all duration-150",
+                !audioConfig.soundboard_off ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+              )}>
+                <div className={cn(
+                  "w-3 h-3 rounded-full transition-all duration-150",
+                  !audioConfig.soundboard_off ? "translate-x-3 bg-white" : "translate-x-0 bg-white/30",
+                )} />
+              </div>
+            </button>
           </div>
         </div>
 
@@ -233,6 +374,50 @@ export function SoundView({
             </select>
           </div>
 
+          {/* Second input: another mic or a line-in, mixed in after the mic's processing */}
+          <div className="flex flex-col gap-1.5">
+            <div className="flex items-center gap-1.5">
+              <AudioLines className="w-3 h-3 text-white/20" />
+              <span className="font-pixel text-[9px] text-white/40 uppercase tracking-wider">
+                Second Input
+              </span>
+              {audioConfig.second_input_device && (
+                <span className="font-clean text-[10px] text-white/50 tabular-nums ml-auto">
+                  {Math.round(audioConfig.second_input_volume * 100)}%
+                </span>
+              )}
+            </div>
+            <select
+              className={cn(
+                "w-full px-2.5 py-1.5 rounded-lg text-[10px] font-clean",
+                "bg-[#0d0d0f] border border-white/[0.08] text-white/60",
+                "focus:outline-none focus:border-cyan-500/30",
+                "appearance-none cursor-pointer",
+              )}
+              value={audioConfig.second_input_device ?? ""}
+              onChange={(e) => onSelectSecondInput(e.target.value || null)}
+              title="A second mic or an instrument; skips the mic processing"
+            >
+              <option value="">None</option>
+              {audioDevices.input_devices
+                .filter((d) => d.name !== audioConfig.audio_input_device)
+                .map((d) => (
+                  <option key={d.name} value={d.name}>
+                    {d.name}
+                  </option>
+                ))}
+            </select>
+            {audioConfig.second_input_device && (
+              <Slider
+                min={0}
+                max={1}
+                step={0.01}
+                value={[audioConfig.second_input_volume]}
+                onValueChange={([v]) => onSecondInputVolumeChange(v)}
+              />
+            )}
+          </div>
+
           {/* Output device */}
           <div className="flex flex-col gap-1.5">
             <div className="flex items-center gap-1.5">
@@ -304,6 +489,141 @@ export function SoundView({
               onValueChange={([v]) => onMicVolumeChange(v)}
             />
           </div>
+
+          {/* Input gain: before processing, for mics that arrive too hot or too quiet */}
+          <div className="flex flex-col gap-1.5">
+            <div className="flex items-center gap-1.5">
+              <SlidersHorizontal className="w-3 h-3 text-white/20" />
+              <span className="font-pixel text-[9px] text-white/40 uppercase tracking-wider">
+                Input Gain
+              </span>
+              <span className="font-clean text-[10px] text-white/50 tabular-nums ml-auto">
+                {audioConfig.input_gain_db > 0 ? "+" : ""}
+                {audioConfig.input_gain_db.toFixed(1)} dB
+              </span>
+            </div>
+            <Slider
+              min={-20}
+              max={30}
+              step={0.5}
+              value={[audioConfig.input_gain_db]}
+              onValueChange={([v]) => onInputGainChange(v)}
+              onDoubleClick={() => onInputGainChange(0)}
+              title="Double-click to reset; watch the clip light below"
+            />
+          </div>
+
+          <ProcessingChain
+            stages={audioConfig.processing}
+            onToggle={onProcessingStageChange}
+            onReorder={onProcessingOrderChange}
+            agc={audioConfig.agc}
+            onAgcChange={onAgcChange}
+          />
+
+          {/* Stop word */}
+          <div className="flex items-center gap-1.5">
+            <Hand className="w-3 h-3 text-white/20" />
+            <span className="font-pixel text-[9px] text-white/40 uppercase tracking-wider">
+              Stop Word
+            </span>
+            <span className="font-clean text-[9px] text-white/20 truncate">
+              Say "stop stop stop" to stop all sounds
+            </span>
+            <button
+              type="button"
+              role="switch"
+              aria-checked={audioConfig.stop_word}
+              aria-label="Stop word"
+              className="ml-auto"
+              onClick={() => onStopWordChange(!audioConfig.stop_word)}
+              title="Listens to the mic for the phrase; uses a little CPU"
+            >
+              <div className={cn(
+                "w-7 h-4 rounded-full p-[2px] transition-all duration-150",
+                audioConfig.stop_word ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+              )}>
+                <div className={cn(
+                  "w-3 h-3 rounded-full transition-all duration-150",
+                  audioConfig.stop_word ? "translate-x-3 bg-white" : "translate-x-0 bg-white/30",
+                )} />
+              </div>
+            </button>
+          </div>
+
+          <AudioMetrics />
+        </div>
+
+        {/* ── Quiet Hours ─────────────────────────────────── */}
+        <div className="px-5 py-2 flex flex-col gap-2">
+          <div className="flex items-center gap-1.5">
+            <Moon className="w-3 h-3 text-white/20" />
+            <span className="font-pixel text-[9px] text-white/40 uppercase tracking-wider">
+              Quiet Hours
+            </span>
+            <button
+              type="button"
+              role="switch"
+              aria-checked={quietHours.enabled}
+              aria-label="Quiet hours"
+              className="ml-auto"
+              onClick={() => onQuietHoursChange({ ...quietHours, enabled: !quietHours.enabled })}
+            >
+              <div className={cn(
+                "w-7 h-4 rounded-full p-[2px] transition-all duration-150",
+                quietHours.enabled ? "bg-emerald-400/90" : "bg-white/12 hover:bg-white/18",
+              )}>
+                <div className={cn(
+                  "w-3 h-3 rounded-full transition-all duration-150",
+                  quietHours.enabled ? "translate-x-3 bg-white" : "translate-x-0 bg-white/30",
+                )} />
+              </div>
+            </button>
+          </div>
+          {quietHours.enabled && (
+            <>
+              <div className="flex items-center gap-2 font-clean text-[10px] text-white/40">
+                <input
+                  type="time"
+                  value={minutesToTime(quietHours.start_min)}
+                  onChange={(e) => e.target.value && onQuietHoursChange({ ...quietHours, start_min: timeToMinutes(e.target.value) })}
+                  className="bg-white/[0.03] border border-white/[0.08] rounded px-1.5 py-0.5 text-white/60 outline-none"
+                />
+                <span>to</span>
+                <input
+                  type="time"
+                  value={minutesToTime(quietHours.end_min)}
+                  onChange={(e) => e.target.value && onQuietHoursChange({ ...quietHours, end_min: timeToMinutes(e.target.value) })}
+                  className="bg-white/[0.03] border border-white/[0.08] rounded px-1.5 py-0.5 text-white/60 outline-none"
+                />
+                <span className="ml-auto tabular-nums text-white/50">
+                  {quietHours.max_volume === 0 ? "Muted" : `Max ${Math.round(quietHours.max_volume * 100)}%`}
+                </span>
+              </div>
+              <Slider
+                min={0}
+                max={1}
+                step={0.05}
+                value={[quietHours.max_volume]}
+                onValueChange={([v]) => onQuietHoursChange({ ...quietHours, max_volume: v })}
+              />
+              {quietOverrideUntil !== null ? (
+                <div className="flex items-center gap-2 font-clean text-[9px] text-white/30">
+                  Sounds on until {new Date(quietOverrideUntil).toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" })}
+                  <button type="button" className="ml-auto text-white/50 hover:text-white/70" onClick={() => onOverrideQuiet(0)}>
+                    End override
+                  </button>
+                </div>
+              ) : quietActive && (
+                <div className="flex items-center gap-2 font-clean text-[9px] text-amber-400/60">
+                  Quiet hours active — key sounds {quietHours.max_volume === 0 ? "muted" : "capped"}
+                  <button type="button" className="ml-auto text-white/50 hover:text-white/70" onClick={() => onOverrideQuiet(60)}>
+                    Sounds on for 1 h
+                  </button>
+                </div>
+              )}
+            </>
+          )}
         </div>
 
         <div className="px-5">
@@ -320,6 +640,53 @@ export function SoundView({
             <span className="font-clean text-[10px] text-white/25 ml-auto">
               {audioConfig.sound_library.length} sound{audioConfig.sound_library.length !== 1 ? "s" : ""}
             </span>
+            {/* Format for trimmed imports */}
+            <div className="flex rounded border border-white/[0.08] overflow-hidden" title="Format trimmed sounds are saved in">
+              {(["Wav", "Flac"] as SoundFormat[]).map((f) => (
+                <button
+                  key={f}
+                  type="button"
+                  className={cn(
+                    "px-1.5 py-0.5 font-mono text-[8px] uppercase transition-colors",
+                    audioConfig.sound_format === f ? "bg-white/15 text-white/70" : "text-white/25 hover:text-white/45",
+                  )}
+                  onClick={() => onSoundFormatChange(f)}
+                  aria-pressed={audioConfig.sound_format === f}
+                >
+                  {f}
+                </button>
+              ))}
+            </div>
+            <button
+              type="button"
+              className="p-1 rounded hover:bg-white/10 text-white/25 hover:text-white/50 transition-colors"
+              onClick={onCompressLibrary}
+              title="Compress library (re-encode WAVs as FLAC, lossless)"
+            >
+              <Archive className="w-3 h-3" />
+            </button>
+          </div>
+
+          {/* Where previews play while the pipeline runs */}
+          <div className="flex items-center gap-1.5 font-clean text-[10px] text-white/30">
+            <Headphones className="w-3 h-3 text-white/20" />
+            <span>Previews play on</span>
+            <div className="flex rounded border border-white/[0.08] overflow-hidden ml-auto">
+              {(Object.entries(PREVIEW_ROUTES) as [SoundRoute, string][]).map(([route, label]) => (
+                <button
+                  key={route}
+                  type="button"
+                  className={cn(
+                    "px-1.5 py-0.5 text-[9px] transition-colors",
+                    audioConfig.preview_route === route ? "bg-white/15 text-white/70" : "text-white/25 hover:text-white/45",
+                  )}
+                  onClick={() => onPreviewRouteChange(route)}
+                  aria-pressed={audioConfig.preview_route === route}
+                >
+                  {label}
+                </button>
+              ))}
+            </div>
           </div>
 
           {/* Library list */}
@@ -341,6 +708,38 @@ export function SoundView({
                   <span className="font-clean text-[10px] text-white/60 truncate flex-1">
                     {entry.display_name}
                   </span>
+                  {audioInfo[entry.id] && (
+                    <span
+                      className="font-clean text-[9px] text-white/20 tabular-nums flex-shrink-0"
+                      title={audioInfoDetails(audioInfo[entry.id])}
+                    >
+                      {formatDuration(audioInfo[entry.id].duration_ms)}
+                    </span>
+                  )}
+                  {entry.missing && (
+                    <button
+                      type="button"
+                      className="font-clean text-[9px] text-amber-400/60 hover:text-amber-300/80 transition-colors flex-shrink-0"
+                      onClick={() => onRelinkSound(entry.id)}
+                      title={`${entry.filename} is no longer in the sounds folder — click to pick a replacement`}
+                    >
+                      missing
+                    </button>
+                  )}
+                  <button
+                    type="button"
+                    className={cn(
+                      "p-1 rounded hover:bg-white/10 transition-colors flex-shrink-0",
+                      entry.monitor_only
+                        ? "text-cyan-400/70"
+                        : "text-white/15 hover:text-white/40 opacity-0 group-hover:opacity-100",
+                    )}
+                    onClick={() => onSoundMonitorOnlyChange(entry.id, !entry.monitor_only)}
+                    aria-pressed={entry.monitor_only}
+                    title={entry.monitor_only ? "Headphones only — never sent to the call" : "Keep this sound out of the call"}
+                  >
+                    <Headphones className="w-3 h-3" />
+                  </button>
                   <button
                     type="button"
                     className="p-1 rounded hover:bg-white/10 text-white/15 hover:text-red-400/70 transition-colors flex-shrink-0 opacity-0 group-hover:opacity-100"
@@ -354,6 +753,23 @@ export function SoundView({
             </div>
           )}
 
+          <PlaylistEditor
+            library={audioConfig.sound_library}
+            playlists={audioConfig.playlists}
+            onSave={onSavePlaylist}
+            onDelete={onDeletePlaylist}
+            onSkip={onSkipPlaylist}
+            onStop={onStopPlaylist}
+          />
+
+          <LibraryStorage
+            library={audioConfig.sound_library}
+            keySounds={audioConfig.key_sounds}
+            quotaMb={audioConfig.library_quota_mb}
+            onClean={onCleanLibrary}
+            onQuotaChange={onLibraryQuotaChange}
+          />
+
           {/* Add sound button → opens upload dialog */}
           <button
             type="button"
@@ -464,6 +880,24 @@ export function SoundView({
                           {entry.display_name}
                         </button>
                       ))}
+                      {audioConfig.playlists.map((playlist) => (
+                        <button
+                          key={playlist.id}
+                          type="button"
+                          className={cn(
+                            "w-full px-2.5 py-1.5 text-left font-clean text-[10px] transition-colors truncate",
+                            soundId === playlist.id
+                              ? "text-cyan-300/80 bg-cyan-500/[0.08]"
+                              : "text-white/50 hover:bg-white/[0.04] hover:text-white/70",
+                          )}
+                          onClick={() => {
+                            onSetKeySound(hwIndex, playlist.id);
+                            setKeyDropdown(null);
+                          }}
+                        >
+                          ▸ {playlist.name}
+                        </button>
+                      ))}
                       {audioConfig.sound_library.length === 0 && (
                         <div className="px-2.5 py-2 font-clean text-[10px] text-white/20 text-center">
                           No sounds in library
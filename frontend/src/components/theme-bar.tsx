@@ -0,0 +1,115 @@
+import { useState } from "react";
+import { Eye, EyeOff, Save, X } from "lucide-react";
+import { hsvToRgb } from "@/lib/hsv";
+import type { Theme } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+interface ThemeBarProps {
+  themes: Theme[];
+  /** Name of the theme showing on the device as a preview. */
+  previewing: string | null;
+  onSave: (name: string) => void;
+  onApply: (name: string) => void;
+  onPreview: (name: string) => void;
+  onEndPreview: () => void;
+  onDelete: (name: string) => void;
+}
+
+/** Saved themes (8 colors each): click to apply, preview one on the device,
+ *  save the current colors as a new one. */
+export function ThemeBar({ themes, previewing, onSave, onApply, onPreview, onEndPreview, onDelete }: ThemeBarProps) {
+  const [name, setName] = useState("");
+
+  const handleSave = () => {
+    const trimmed = name.trim();
+    if (!trimmed) return;
+    onSave(trimmed);
+    setName("");
+  };
+
+  return (
+    <div className="relative z-[1] flex flex-col gap-1.5 px-5 pb-3">
+      {themes.length > 0 && (
+        <div className="flex flex-wrap gap-1.5">
+          {themes.map((t) => (
+            <div
+              key={t.name}
+              className={cn(
+                "group flex items-center gap-1.5 pl-1.5 pr-1 py-1 rounded-md border transition-colors",
+                previewing === t.name ? "border-violet-500/40" : "border-white/[0.06] hover:border-white/15",
+              )}
+            >
+              <button
+                type="button"
+                className="flex items-center gap-1.5"
+                onClick={() => onApply(t.name)}
+                title={`Apply "${t.name}"`}
+              >
+                <span className="flex rounded-sm overflow-hidden">
+                  {t.colors.map((c, i) => (
+                    <span
+                      key={i}
+                      className="w-1.5 h-3"
+                      style={{ backgroundColor: hsvToRgb(c.h, c.s, c.v) }}
+                    />
+                  ))}
+                </span>
+                <span className="font-clean text-[9px] text-white/40 max-w-[80px] truncate">
+                  {t.name}
+                </span>
+              </button>
+              <button
+                type="button"
+                className={cn(
+                  "transition-opacity",
+                  previewing === t.name ? "opacity-100" : "opacity-0 group-hover:opacity-100",
+                )}
+                onClick={() => (previewing === t.name ? onEndPreview() : onPreview(t.name))}
+                title={previewing === t.name ? "End preview" : "Preview on the device for 5 seconds"}
+              >
+                {previewing === t.name ? (
+                  <EyeOff className="w-2.5 h-2.5 text-violet-300/70" />
+                ) : (
+                  <Eye className="w-2.5 h-2.5 text-white/25 hover:text-white/50" />
+                )}
+              </button>
+              <button
+                type="button"
+                className="opacity-0 group-hover:opacity-100 transition-opacity"
+                onClick={() => onDelete(t.name)}
+                title="Delete theme"
+              >
+                <X className="w-2.5 h-2.5 text-white/25 hover:text-white/50" />
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+      <div className="flex items-center gap-1.5">
+        <input
+          type="text"
+          value={name}
+          maxLength={32}
+          placeholder="Save current colors as theme…"
+          onChange={(e) => setName(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") handleSave();
+          }}
+          className="flex-1 min-w-0 bg-transparent font-clean text-[9px] text-white/40 placeholder:text-white/15 outline-none border-b border-transparent focus:border-white/15 transition-colors"
+          spellCheck={false}
+        />
+        <button
+          type="button"
+          className={cn(
+            "p-1 rounded hover:bg-white/[0.06] transition-colors",
+            !name.trim() && "opacity-30 pointer-events-none",
+          )}
+          onClick={handleSave}
+          title="Save theme"
+        >
+          <Save className="w-3 h-3 text-white/30" />
+        </button>
+      </div>
+    </div>
+  );
+}
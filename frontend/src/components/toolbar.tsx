@@ -6,8 +6,10 @@ import {
 } from "@/components/ui/tooltip";
 import {
   Keyboard,
+  Moon,
   Palette,
   Settings,
+  Unplug,
   Volume2,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
@@ -16,13 +18,19 @@ import packageJson from "../../package.json";
 interface ToolbarProps {
   connected: boolean;
   connecting: boolean;
+  dnd: boolean;
   onReconnect: () => void;
+  onDisconnect: () => void;
+  onToggleDnd: () => void;
 }
 
 export function Toolbar({
   connected,
   connecting,
+  dnd,
   onReconnect,
+  onDisconnect,
+  onToggleDnd,
 }: ToolbarProps) {
   return (
     <header className="toolbar flex items-center gap-2 px-3 h-10 border-b border-white/[0.08] bg-[#0c0c0e]">
@@ -81,11 +89,53 @@ export function Toolbar({
       {/* Spacer */}
       <div className="flex-1" />
 
+      {/* Do not disturb */}
+      <Tooltip>
+        <TooltipTrigger asChild>
+          <button
+            type="button"
+            onClick={onToggleDnd}
+            disabled={!connected}
+            className={cn(
+              "p-1.5 rounded-md transition-all duration-100 disabled:opacity-30",
+              dnd
+                ? "text-violet-300/80 bg-violet-500/[0.12]"
+                : "text-white/25 hover:text-white/55 hover:bg-white/[0.04]",
+            )}
+          >
+            <Moon className="w-3 h-3" />
+          </button>
+        </TooltipTrigger>
+        <TooltipContent side="bottom" sideOffset={6}>
+          <p className="text-[10px]">
+            {dnd ? "Do not disturb — click to restore LEDs" : "Do not disturb: turn all LEDs off"}
+          </p>
+        </TooltipContent>
+      </Tooltip>
+
       {/* Version */}
       <span className="font-clean text-[9px] text-white/15 mr-1">
         v{packageJson.version}
       </span>
 
+      {/* Release the device for other tools */}
+      {connected && !connecting && (
+        <Tooltip>
+          <TooltipTrigger asChild>
+            <button
+              type="button"
+              onClick={onDisconnect}
+              className="p-1.5 rounded-md text-white/25 hover:text-white/55 hover:bg-white/[0.04] transition-all duration-100"
+            >
+              <Unplug className="w-3 h-3" />
+            </button>
+          </TooltipTrigger>
+          <TooltipContent side="bottom" sideOffset={6}>
+            <p className="text-[10px]">Disconnect — release the device for VIA or other tools</p>
+          </TooltipContent>
+        </Tooltip>
+      )}
+
       {/* Connection status */}
       <Tooltip>
         <TooltipTrigger asChild>
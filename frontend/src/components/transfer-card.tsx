@@ -0,0 +1,117 @@
+import { useEffect, useState } from "react";
+import { ArrowRightLeft } from "lucide-react";
+import { toast } from "sonner";
+import { errorMessage, importSharedConfig, startConfigShare, stopConfigShare, type ConfigShare } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+const fieldClass = "min-w-0 px-1.5 py-0.5 rounded bg-[#0d0d0f] border border-white/[0.08] font-clean text-[10px] text-white/60 outline-none focus:border-cyan-500/30 placeholder:text-white/15";
+const buttonClass = "px-2 py-1 rounded-md font-clean text-[10px] text-white/50 border border-white/[0.08] hover:bg-white/[0.04] hover:text-white/70 transition-colors disabled:opacity-40";
+
+/** Copy the whole setup to another PC on the same network with a pairing code. */
+export function TransferCard() {
+  const [share, setShare] = useState<ConfigShare | null>(null);
+  const [importing, setImporting] = useState(false);
+  const [address, setAddress] = useState("");
+  const [code, setCode] = useState("");
+  const [busy, setBusy] = useState(false);
+
+  // The backend ends the share on its own; hide it once it would have
+  useEffect(() => {
+    if (!share) return;
+    const timer = setTimeout(() => setShare(null), share.expires_at_ms - Date.now());
+    return () => clearTimeout(timer);
+  }, [share]);
+  // Leaving the page stops sharing
+  useEffect(() => () => void stopConfigShare(), []);
+
+  const startSharing = () => {
+    setImporting(false);
+    startConfigShare()
+      .then(setShare)
+      .catch((e) => toast.error(`Sharing failed: ${errorMessage(e)}`));
+  };
+  const stopSharing = () => {
+    setShare(null);
+    stopConfigShare().catch(() => {});
+  };
+  const runImport = () => {
+    if (!address.trim() || !code.trim()) return;
+    setBusy(true);
+    // Restarts the app when it succeeds
+    importSharedConfig(address, code)
+      .catch((e) => toast.error(`Import failed: ${errorMessage(e)}`))
+      .finally(() => setBusy(false));
+  };
+
+  return (
+    <div className="flex flex-col gap-2 px-3.5 py-3 rounded-xl border border-white/[0.06] bg-white/[0.02]">
+      <div className="flex items-center gap-3">
+        <div className={cn(
+          "flex items-center justify-center w-7 h-7 rounded-lg",
+          share ? "bg-emerald-500/15 text-emerald-400" : "bg-white/[0.06] text-white/25",
+        )}>
+          <ArrowRightLeft className="w-3.5 h-3.5" />
+        </div>
+        <div className="flex-1 min-w-0">
+          <div className="font-clean text-[11px] text-white/80 font-medium">Transfer to another PC</div>
+          <div className="font-clean text-[9px] text-white/30 mt-0.5">
+            Keys, sounds and settings, over your local network
+          </div>
+        </div>
+        {share ? (
+          <button type="button" className={buttonClass} onClick={stopSharing}>
+            Stop
+          </button>
+        ) : (
+          <>
+            <button type="button" className={buttonClass} onClick={startSharing}>
+              Share
+            </button>
+            <button type="button" className={buttonClass} onClick={() => setImporting(!importing)}>
+              Import
+            </button>
+          </>
+        )}
+      </div>
+      {share && (
+        <div className="flex items-center gap-3 pl-10 font-clean text-[10px]">
+          <span className="text-white/40">Address</span>
+          <span className="text-white/80 tabular-nums select-all">{share.address}</span>
+          <span className="text-white/40">Code</span>
+          <span className="text-emerald-400/90 tabular-nums tracking-widest select-all">{share.code}</span>
+          <span className="ml-auto text-[9px] text-white/25">
+            until {new Date(share.expires_at_ms).toLocaleTimeString()}
+          </span>
+        </div>
+      )}
+      {importing && !share && (
+        <div className="flex flex-col gap-1.5 pl-10">
+          <div className="flex items-center gap-2 font-clean text-[10px]">
+            <input
+              value={address}
+              placeholder="192.168.1.20:18810"
+              onChange={(e) => setAddress(e.target.value)}
+              className={cn(fieldClass, "flex-1")}
+              spellCheck={false}
+            />
+            <input
+              value={code}
+              placeholder="Code"
+              inputMode="numeric"
+              maxLength={6}
+              onChange={(e) => setCode(e.target.value.replace(/\D/g, ""))}
+              onKeyDown={(e) => e.key === "Enter" && runImport()}
+              className={cn(fieldClass, "w-16 tabular-nums tracking-widest")}
+            />
+            <button type="button" className={buttonClass} disabled={busy || !address.trim() || code.length !== 6} onClick={runImport}>
+              {busy ? "Importing…" : "Import"}
+            </button>
+          </div>
+          <span className="font-clean text-[9px] text-amber-400/60">
+            Replaces this PC's keys, sounds of the same name and settings, then restarts
+          </span>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,97 @@
+import { useCallback, useEffect, useState } from "react";
+import { BarChart3, Download, RotateCcw } from "lucide-react";
+import { toast } from "sonner";
+import { errorMessage, exportUsageCsv, getUsageStats, resetUsageStats } from "@/lib/tauri";
+import type { KeyConfig, SoundEntry, UsageCounter, UsageStats as Stats } from "@/lib/tauri";
+
+interface UsageStatsProps {
+  keys: KeyConfig[];
+  soundLibrary: SoundEntry[];
+}
+
+function Row({ name, counter, max }: { name: string; counter: UsageCounter; max: number }) {
+  return (
+    <div className="flex items-center gap-2 min-w-0" title={counter.count ? `Last used ${new Date(counter.last_ms).toLocaleString()}` : "Never used"}>
+      <span className="font-clean text-[10px] text-white/50 w-24 truncate">{name}</span>
+      <div className="flex-1 h-1.5 rounded-full bg-white/[0.04] overflow-hidden">
+        <div className="h-full bg-emerald-400/40" style={{ width: `${max ? (counter.count / max) * 100 : 0}%` }} />
+      </div>
+      <span className="font-clean text-[10px] text-white/40 tabular-nums w-10 text-right">{counter.count}</span>
+    </div>
+  );
+}
+
+/** Per-key press counts plus the most played sounds and most run actions. */
+export function UsageStats({ keys, soundLibrary }: UsageStatsProps) {
+  const [stats, setStats] = useState<Stats | null>(null);
+
+  useEffect(() => {
+    getUsageStats().then(setStats).catch(() => {});
+  }, []);
+
+  const handleExport = useCallback(async () => {
+    try {
+      const { save } = await import("@tauri-apps/plugin-dialog");
+      const path = await save({ defaultPath: "deck8-usage.csv", filters: [{ name: "CSV", extensions: ["csv"] }] });
+      if (!path) return;
+      await exportUsageCsv(path);
+      toast.success("Usage exported");
+    } catch (e) {
+      toast.error(`Export failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const handleReset = useCallback(async () => {
+    try {
+      await resetUsageStats();
+      setStats(await getUsageStats());
+    } catch (e) {
+      toast.error(`Reset failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  if (!stats) return null;
+
+  const keyMax = Math.max(...stats.keys.map((c) => c.count));
+  const top = (entries: Record<string, UsageCounter>) =>
+    Object.entries(entries).sort(([, a], [, b]) => b.count - a.count).slice(0, 5);
+  const sounds = top(stats.sounds);
+  const actions = top(stats.actions);
+  const soundName = (id: string) => soundLibrary.find((s) => s.id === id)?.display_name ?? "(removed)";
+
+  return (
+    <div className="px-5 py-3 flex flex-col gap-1.5">
+      <div className="flex items-center gap-2 mb-1">
+        <BarChart3 className="w-3.5 h-3.5 text-emerald-400/50" />
+        <span className="font-pixel text-[11px] text-white/70 font-bold uppercase tracking-wider">Usage</span>
+        <span className="font-clean text-[9px] text-white/20">since {new Date(stats.since_ms).toLocaleDateString()}</span>
+        <button type="button" className="ml-auto p-1 rounded hover:bg-white/[0.06] transition-colors" onClick={handleExport} title="Export as CSV">
+          <Download className="w-3 h-3 text-white/30" />
+        </button>
+        <button type="button" className="p-1 rounded hover:bg-white/[0.06] transition-colors" onClick={handleReset} title="Reset counts">
+          <RotateCcw className="w-3 h-3 text-white/30" />
+        </button>
+      </div>
+      {stats.keys.map((c, i) => (
+        <Row key={i} name={keys[i]?.label ?? `Key ${i + 1}`} counter={c} max={keyMax} />
+      ))}
+      {sounds.length > 0 && (
+        <>
+          <span className="font-pixel text-[8px] text-white/25 uppercase tracking-wider mt-1.5">Top sounds</span>
+          {sounds.map(([id, c]) => (
+            <Row key={id} name={soundName(id)} counter={c} max={sounds[0][1].count} />
+          ))}
+        </>
+      )}
+      {actions.length > 0 && (
+        <>
+          <span className="font-pixel text-[8px] text-white/25 uppercase tracking-wider mt-1.5">Top actions</span>
+          {actions.map(([id, c]) => (
+            <Row key={id} name={id} counter={c} max={actions[0][1].count} />
+          ))}
+        </>
+      )}
+      <div className="border-b border-white/[0.06] mt-1.5" />
+    </div>
+  );
+}
@@ -1,17 +1,85 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import { toast } from "sonner";
-import type { ActiveSlot, AudioDeviceList, RgbMatrixState, SoundEntry, StateSnapshot } from "@/lib/tauri";
+import type {
+  AgcSettings, AppHotkey, AudioDeviceList, BrightnessCurve, DiscordSettings, HsvColor, KeySource, LightingSettings, LightScene, Playlist, PressMode, QuietHours, SlotBinding, RgbMatrixState, SoundEntry, SoundFormat, SoundMode, SoundRoute, StageKind,
+  StateSnapshot, SystemTheme, WindowSettings, HidTiming, Rule, FocusSettings, GameSettings, Trim,
+} from "@/lib/tauri";
 import {
+  errorMessage,
   connectDevice,
+  disconnectDevice,
   getState,
   setKeyColor,
-  onSlotToggled,
+  setAllKeyColors,
   onStateUpdated,
+  onAnnounce,
+  onSyncReport,
+  onDeviceRebooted,
+  onStopWord,
+  onSoundMissing,
+  onDeviceUnhealthy,
   toggleKeySlot as ipcToggleKeySlot,
+  addKeySlot as ipcAddKeySlot,
+  removeKeySlot as ipcRemoveKeySlot,
+  renameKeySlot as ipcRenameKeySlot,
+  selectKeySlot as ipcSelectKeySlot,
+  setKeyBinding as ipcSetKeyBinding,
+  setKeyLabel as ipcSetKeyLabel,
+  setKeyIcon as ipcSetKeyIcon,
+  setKeySoundCooldown as ipcSetKeySoundCooldown,
+  setKeySoundMode as ipcSetKeySoundMode,
+  setKeyPressMode as ipcSetKeyPressMode,
+  setKeyLightScene as ipcSetKeyLightScene,
+  setLighting as ipcSetLighting,
+  pairHueBridge as ipcPairHueBridge,
+  setAccentKeyColors as ipcSetAccentKeyColors,
+  setLanguage as ipcSetLanguage,
+  setWindowSettings as ipcSetWindowSettings,
+  setHidTiming as ipcSetHidTiming,
+  setCooperative as ipcSetCooperative,
+  lockConfig as ipcLockConfig,
+  unlockConfig as ipcUnlockConfig,
+  setFocusSettings as ipcSetFocusSettings,
+  setGameSettings as ipcSetGameSettings,
+  startFocus as ipcStartFocus,
+  stopFocus as ipcStopFocus,
+  skipOnboardingStep as ipcSkipOnboardingStep,
+  finishOnboarding as ipcFinishOnboarding,
+  resetOnboarding as ipcResetOnboarding,
+  getSystemTheme,
+  onSystemTheme,
+  onThemePreview,
+  setKeySoundGain as ipcSetKeySoundGain,
+  setSoundboard as ipcSetSoundboard,
+  stopAllSounds as ipcStopAllSounds,
+  setSoundsMuted as ipcSetSoundsMuted,
+  setAppHotkey as ipcSetAppHotkey,
+  copyKeyConfig as ipcCopyKeyConfig,
+  pasteKeyConfig as ipcPasteKeyConfig,
+  applyColorToAll as ipcApplyColorToAll,
+  toggleDnd as ipcToggleDnd,
+  addSwatch as ipcAddSwatch,
+  removeSwatch as ipcRemoveSwatch,
+  saveTheme as ipcSaveTheme,
+  applyTheme as ipcApplyTheme,
+  deleteTheme as ipcDeleteTheme,
+  previewTheme as ipcPreviewTheme,
+  endThemePreview as ipcEndThemePreview,
+  saveKeyGroup as ipcSaveKeyGroup,
+  deleteKeyGroup as ipcDeleteKeyGroup,
+  saveMode as ipcSaveMode,
+  deleteMode as ipcDeleteMode,
+  setMode as ipcSetMode,
+  saveRule as ipcSaveRule,
+  deleteRule as ipcDeleteRule,
   setKeycode as ipcSetKeycode,
+  startKeycodeCapture,
+  onKeycodeCaptured,
   setKeyOverride,
+  setKeySource as ipcSetKeySource,
   saveCustom,
   restoreDefaults,
+  reloadDefinitions as ipcReloadDefinitions,
   deviceIndication,
   bootloaderJump,
   eepromReset,
@@ -21,44 +89,151 @@ import {
   setRgbEffect,
   setRgbSpeed,
   setRgbColor,
+  setBrightnessCurve,
+  setAccessibilityCues,
+  setNowPlaying,
+  setDiscordSettings,
+  setLocalApi,
+  setQuietHours,
+  overrideQuietHours,
   saveRgbMatrix,
+  restoreRgbMatrix as ipcRestoreRgbMatrix,
+  restoreKeymaps as ipcRestoreKeymaps,
+  keepDeviceKeymaps as ipcKeepDeviceKeymaps,
+  keepDeviceRgbMatrix as ipcKeepDeviceRgbMatrix,
+  chooseAudioDevice as ipcChooseAudioDevice,
   listAudioDevices,
   setAudioInputDevice,
+  setSecondInputDevice,
+  setSecondInputVolume,
   setAudioOutputDevice,
   setSoundVolume,
   setMicVolume,
+  setInputGain,
+  setProcessingStage,
+  setProcessingOrder,
+  setAgc,
+  setStopWord,
   addToSoundLibrary,
   addToSoundLibraryTrimmed,
+  setSoundFormat,
+  setPreviewRoute,
+  compressSoundLibrary,
+  cleanSoundLibrary,
+  setLibraryQuota,
+  relinkSound,
   removeFromSoundLibrary,
   renameSound as ipcRenameSound,
+  setSoundMonitorOnly,
+  savePlaylist as ipcSavePlaylist,
+  deletePlaylist as ipcDeletePlaylist,
+  skipPlaylist as ipcSkipPlaylist,
+  stopPlaylist as ipcStopPlaylist,
   setKeySound,
   previewLibrarySound as ipcPreviewLibrarySound,
   getAudioDuration,
   previewTrim,
+  listKeycodes,
 } from "@/lib/tauri";
+import { keycodeToLabel, setKeycodeCatalog } from "@/lib/keycodes";
+import { hsvToRgb } from "@/lib/hsv";
 
 const DEFAULT_STATE: StateSnapshot = {
   connected: false,
   keys: Array.from({ length: 8 }, () => ({
-    slot_a: { h: 0x55, s: 0xff, v: 0x78 },
-    slot_b: { h: 0x00, s: 0xff, v: 0x78 },
+    slots: [
+      { name: "A", color: { h: 0x55, s: 0xff, v: 0x78 } },
+      { name: "B", color: { h: 0x00, s: 0xff, v: 0x78 } },
+    ],
     override_enabled: false,
-    active_slot: "A" as const,
+    active_slot: 0,
+    source: "Deck" as const,
+    cooldown_ms: 0,
+    sound_cooldown_ms: 0,
+    dim_on_cooldown: false,
+    sound_mode: "OneShot" as const,
+    press_mode: "Both" as const,
+    binding: null,
+    light_scene: null,
+    label: null,
+    icon: null,
   })),
-  active_slot: "A",
   keymaps: [0, 0, 0, 0, 0, 0, 0, 0],
+  keymap_restore: null,
   device_info: null,
   rgb_matrix: null,
+  rgb_restore: null,
   audio_config: {
     sound_files: [null, null, null, null, null, null, null, null],
     sound_library: [],
     key_sounds: [null, null, null, null, null, null, null, null],
+    playlists: [],
+    key_sound_gain_db: [0, 0, 0, 0, 0, 0, 0, 0],
     audio_input_device: null,
     audio_output_device: null,
+    second_input_device: null,
+    second_input_volume: 1.0,
     sound_volume: 1.0,
     mic_volume: 1.0,
+    input_gain_db: 0,
     soundboard_enabled: false,
+    soundboard_off: false,
+    sounds_muted: false,
+    processing: [
+      { kind: "Gate", enabled: false },
+      { kind: "Denoise", enabled: false },
+      { kind: "Eq", enabled: false },
+      { kind: "Agc", enabled: false },
+      { kind: "Compressor", enabled: false },
+    ],
+    agc: { target_low_db: -24, target_high_db: -16, max_gain_db: 12 },
+    stop_word: false,
+    sound_format: "Wav",
+    preview_route: "Monitor",
+    library_quota_mb: null,
+  },
+  settings: {
+    grab_device_input: false,
+    replay_method: "Native",
+    dedup_ms: 150,
+    fade_ms: 0,
+    brightness_curve: { gamma: 1, min: 0, max: 255 },
+    accessibility_cues: false,
+    quiet_hours: { enabled: false, start_min: 22 * 60, end_min: 8 * 60, max_volume: 0 },
+    now_playing: false,
+    local_api: false,
+    stop_sounds_hotkey: null,
+    mute_sounds_hotkey: null,
+    discord: { presence: false, client_id: "", client_secret: null },
+    lighting: { mirror: null, targets: [] },
+    accent_key_colors: false,
+    applied_accent: null,
+    language: null,
+    window: { close: "HideToTray", minimize_to_tray: false, confirm_quit: false },
+    hid: { timeout_ms: 500, pacing_ms: 0 },
+    cooperative: false,
+    focus: { focus_min: 25, daily_goal: 8, celebrate: true },
+    game: { enabled: false, port: 18809, token: null, rules: [] },
+    onboarding: "Done",
   },
+  palette: [],
+  themes: [],
+  groups: [],
+  modes: [],
+  active_mode: null,
+  rules: [],
+  active_rules: [],
+  key_clipboard: null,
+  dnd: false,
+  safe_mode: false,
+  quiet_active: false,
+  quiet_override_until: null,
+  focus_until: null,
+  sync_report: null,
+  device_sync: { colors: "Unknown", keymaps: "Unknown", rgb: "Unknown" },
+  device_busy: false,
+  locked: false,
+  device_choice: null,
 };
 
 const DEFAULT_DEVICES: AudioDeviceList = {
@@ -71,9 +246,18 @@ export function useDeck8() {
   const [connecting, setConnecting] = useState(false);
   const [selectedKey, setSelectedKey] = useState<number | null>(null);
   const [audioDevices, setAudioDevices] = useState<AudioDeviceList>(DEFAULT_DEVICES);
+  // Latest screen-reader announcement; `id` re-mounts the live region text so repeats are read too
+  const [announcement, setAnnouncement] = useState<{ id: number; text: string } | null>(null);
+  const [systemTheme, setSystemTheme] = useState<SystemTheme | null>(null);
+  // Theme showing on the device without being applied
+  const [themePreview, setThemePreview] = useState<string | null>(null);
   const colorTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+  // Latest state for callbacks that must not go stale mid-drag
+  const stateRef = useRef(state);
+  stateRef.current = state;
   const rgbTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
   const volumeTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const quietTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
 
   // ── Refresh helpers ─────────────────────────────────────
 
@@ -93,32 +277,49 @@ export function useDeck8() {
       try {
         setConnecting(true);
         const ok = await connectDevice();
+        const s = await getState();
+        setState(s);
         if (ok) {
           toast.success("Device connected");
         } else if (!silent) {
-          toast.error("Device not found");
+          toast.error(s.device_busy ? "Another app (VIA?) is using the Deck-8" : "Device not found");
         }
-        await refreshState();
       } catch (e) {
-        if (!silent) toast.error(`Connection error: ${e}`);
+        if (!silent) toast.error(`Connection error: ${errorMessage(e)}`);
       } finally {
         setConnecting(false);
       }
     },
-    [refreshState],
+    [],
   );
 
+  const disconnect = useCallback(async () => {
+    try {
+      setState(await disconnectDevice());
+      toast.success("Device released");
+    } catch (e) {
+      toast.error(`Disconnect failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  /** `slot` is a slot name, or null to set every slot of the key. */
   const updateKeyColor = useCallback(
-    (keyIndex: number, slot: ActiveSlot | "both", h: number, s: number, v: number) => {
-      console.log(`[color] key=${keyIndex} slot=${slot} h=${h} s=${s} v=${v}`);
+    (keyIndex: number, slot: string | null, h: number, s: number, v: number) => {
+      console.log(`[color] key=${keyIndex} slot=${slot ?? "all"} h=${h} s=${s} v=${v}`);
       const color = { h, s, v };
+      const key = stateRef.current.keys[keyIndex];
+      if (!key) return;
+      // Editing a slot makes it active; when setting all, the active one goes last
+      const active = key.slots[key.active_slot]?.name;
+      const names = slot !== null
+        ? [slot]
+        : [...key.slots.map((sl) => sl.name).filter((n) => n !== active), ...(active ? [active] : [])];
       setState((prev) => {
         const keys = prev.keys.map((k, i) => {
           if (i !== keyIndex) return k;
-          if (slot === "both") return { ...k, slot_a: color, slot_b: color };
-          return slot === "A"
-            ? { ...k, slot_a: color, active_slot: "A" as const }
-            : { ...k, slot_b: color, active_slot: "B" as const };
+          const slots = k.slots.map((sl) => (slot === null || sl.name === slot ? { ...sl, color } : sl));
+          const idx = slot === null ? k.active_slot : k.slots.findIndex((sl) => sl.name === slot);
+          return { ...k, slots, active_slot: idx >= 0 ? idx : k.active_slot };
         });
         return { ...prev, keys };
       });
@@ -126,10 +327,10 @@ export function useDeck8() {
       if (colorTimer.current) clearTimeout(colorTimer.current);
       colorTimer.current = setTimeout(async () => {
         try {
-          const activeSlot = slot === "both" ? "A" : slot;
-          await setKeyColor(keyIndex, activeSlot, h, s, v);
-          if (slot === "both") {
-            await setKeyColor(keyIndex, "B", h, s, v);
+          if (names.length === 1) {
+            await setKeyColor(keyIndex, names[0], h, s, v);
+          } else {
+            await setAllKeyColors(names.map((name) => [keyIndex, name, color]));
           }
           console.log(`[color] key=${keyIndex} IPC OK`);
         } catch (e) {
@@ -151,12 +352,21 @@ export function useDeck8() {
       try {
         await ipcSetKeycode(keyIndex, keycode);
       } catch (e) {
-        toast.error(`Set keycode failed: ${e}`);
+        toast.error(`Set keycode failed: ${errorMessage(e)}`);
       }
     },
     [],
   );
 
+  const captureKeycode = useCallback(async (keyIndex: number) => {
+    try {
+      await startKeycodeCapture(keyIndex);
+      toast.info("Press the key combination to bind (Esc cancels)");
+    } catch (e) {
+      toast.error(`Key capture failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
   const toggleKeyOverride = useCallback(
     async (keyIndex: number) => {
       const newEnabled = !state.keys[keyIndex].override_enabled;
@@ -180,18 +390,225 @@ export function useDeck8() {
     [state.keys, refreshState],
   );
 
+  // Slot / palette / theme edits: the backend validates, so take its snapshot as-is
+  const runStateEdit = useCallback(async (what: string, edit: () => Promise<StateSnapshot>) => {
+    try {
+      setState(await edit());
+      return true;
+    } catch (e) {
+      toast.error(`${what} failed: ${errorMessage(e)}`);
+      return false;
+    }
+  }, []);
+
+  const addKeySlot = useCallback(
+    (keyIndex: number, name: string, color: HsvColor) =>
+      runStateEdit("Add slot", () => ipcAddKeySlot(keyIndex, name, color)),
+    [runStateEdit],
+  );
+
+  const removeKeySlot = useCallback(
+    (keyIndex: number, name: string) =>
+      runStateEdit("Remove slot", () => ipcRemoveKeySlot(keyIndex, name)),
+    [runStateEdit],
+  );
+
+  const renameKeySlot = useCallback(
+    (keyIndex: number, name: string, newName: string) =>
+      runStateEdit("Rename slot", () => ipcRenameKeySlot(keyIndex, name, newName)),
+    [runStateEdit],
+  );
+
+  const selectKeySlot = useCallback(
+    (keyIndex: number, name: string) =>
+      runStateEdit("Select slot", () => ipcSelectKeySlot(keyIndex, name)),
+    [runStateEdit],
+  );
+
+  const updateKeyBinding = useCallback(
+    (keyIndex: number, binding: SlotBinding | null) =>
+      runStateEdit("Set binding", () => ipcSetKeyBinding(keyIndex, binding)),
+    [runStateEdit],
+  );
+
+  const updateKeyLightScene = useCallback(
+    (keyIndex: number, scene: LightScene | null) =>
+      runStateEdit("Set light scene", () => ipcSetKeyLightScene(keyIndex, scene)),
+    [runStateEdit],
+  );
+
+  const updateKeyLabel = useCallback(
+    (keyIndex: number, label: string | null) =>
+      runStateEdit("Set label", () => ipcSetKeyLabel(keyIndex, label)),
+    [runStateEdit],
+  );
+
+  const updateKeyIcon = useCallback(
+    (keyIndex: number, icon: string | null) =>
+      runStateEdit("Set icon", () => ipcSetKeyIcon(keyIndex, icon)),
+    [runStateEdit],
+  );
+
+  const updateKeySoundCooldown = useCallback(
+    (keyIndex: number, cooldownMs: number, dim: boolean) =>
+      runStateEdit("Set sound cooldown", () => ipcSetKeySoundCooldown(keyIndex, cooldownMs, dim)),
+    [runStateEdit],
+  );
+
+  const updateKeySoundMode = useCallback(
+    (keyIndex: number, mode: SoundMode) =>
+      runStateEdit("Set sound mode", () => ipcSetKeySoundMode(keyIndex, mode)),
+    [runStateEdit],
+  );
+
+  const updateKeyPressMode = useCallback(
+    (keyIndex: number, mode: PressMode) =>
+      runStateEdit("Set press mode", () => ipcSetKeyPressMode(keyIndex, mode)),
+    [runStateEdit],
+  );
+
+  const updateKeySoundGain = useCallback(
+    (keyIndex: number, gainDb: number) =>
+      runStateEdit("Set key volume", () => ipcSetKeySoundGain(keyIndex, gainDb)),
+    [runStateEdit],
+  );
+
+  const copyKeyConfig = useCallback(
+    async (keyIndex: number) => {
+      if (await runStateEdit("Copy key", () => ipcCopyKeyConfig(keyIndex))) {
+        toast.success(`Key ${keyIndex + 1} copied — open another key to paste`);
+      }
+    },
+    [runStateEdit],
+  );
+
+  const pasteKeyConfig = useCallback(
+    (keyIndex: number) => runStateEdit("Paste key", () => ipcPasteKeyConfig(keyIndex)),
+    [runStateEdit],
+  );
+
+  const applyColorToAll = useCallback(
+    (slot: string | null, color: HsvColor) =>
+      runStateEdit("Apply to all keys", () => ipcApplyColorToAll(slot, color)),
+    [runStateEdit],
+  );
+
+  const toggleDnd = useCallback(
+    () => runStateEdit("Do not disturb", ipcToggleDnd),
+    [runStateEdit],
+  );
+
+  // Palette & themes
+  const addSwatch = useCallback(
+    (color: HsvColor) => runStateEdit("Save swatch", () => ipcAddSwatch(color)),
+    [runStateEdit],
+  );
+
+  const removeSwatch = useCallback(
+    (index: number) => runStateEdit("Remove swatch", () => ipcRemoveSwatch(index)),
+    [runStateEdit],
+  );
+
+  const saveTheme = useCallback(
+    async (name: string) => {
+      if (await runStateEdit("Save theme", () => ipcSaveTheme(name))) {
+        toast.success(`Theme "${name}" saved`);
+      }
+    },
+    [runStateEdit],
+  );
+
+  const applyTheme = useCallback(
+    (name: string) => runStateEdit("Apply theme", () => ipcApplyTheme(name)),
+    [runStateEdit],
+  );
+
+  const deleteTheme = useCallback(
+    (name: string) => runStateEdit("Delete theme", () => ipcDeleteTheme(name)),
+    [runStateEdit],
+  );
+
+  const previewTheme = useCallback(async (name: string) => {
+    try {
+      await ipcPreviewTheme(name);
+    } catch (e) {
+      toast.error(`Preview failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const endThemePreview = useCallback(() => {
+    ipcEndThemePreview().catch(() => {});
+  }, []);
+
+  // Key groups
+  const saveKeyGroup = useCallback(
+    (name: string, keys: number[]) => runStateEdit("Save group", () => ipcSaveKeyGroup(name, keys)),
+    [runStateEdit],
+  );
+
+  const deleteKeyGroup = useCallback(
+    (name: string) => runStateEdit("Delete group", () => ipcDeleteKeyGroup(name)),
+    [runStateEdit],
+  );
+
+  // Modes
+  const saveMode = useCallback(
+    (name: string, soundsMuted: boolean | null, base: string | null) =>
+      runStateEdit("Save mode", () => ipcSaveMode(name, soundsMuted, base)),
+    [runStateEdit],
+  );
+
+  const deleteMode = useCallback(
+    (name: string) => runStateEdit("Delete mode", () => ipcDeleteMode(name)),
+    [runStateEdit],
+  );
+
+  const setMode = useCallback(
+    (name: string) => runStateEdit("Switch mode", () => ipcSetMode(name)),
+    [runStateEdit],
+  );
+
+  const saveRule = useCallback(
+    (rule: Rule) => runStateEdit("Save rule", () => ipcSaveRule(rule)),
+    [runStateEdit],
+  );
+
+  const deleteRule = useCallback(
+    (ruleId: string) => runStateEdit("Delete rule", () => ipcDeleteRule(ruleId)),
+    [runStateEdit],
+  );
+
+  const updateKeySource = useCallback(
+    async (keyIndex: number, source: KeySource) => {
+      setState((prev) => {
+        const keys = prev.keys.map((k, i) => (i === keyIndex ? { ...k, source } : k));
+        return { ...prev, keys };
+      });
+      try {
+        await ipcSetKeySource(keyIndex, source);
+      } catch (e) {
+        toast.error(`Set key source failed: ${errorMessage(e)}`);
+        await refreshState();
+      }
+    },
+    [refreshState],
+  );
+
   const doToggleKeySlot = useCallback(
     async (keyIndex: number) => {
       setState((prev) => {
-        const oldSlot = prev.keys[keyIndex]?.active_slot ?? "A";
-        const newSlot = oldSlot === "A" ? "B" : "A";
+        const key = prev.keys[keyIndex];
+        if (!key) return prev;
+        const next = (key.active_slot + 1) % key.slots.length;
+        const oldSlot = key.slots[key.active_slot]?.name;
+        const newSlot = key.slots[next].name;
         console.log(`[slot-toggle] key=${keyIndex} ${oldSlot}→${newSlot}`);
         toast.info(`Key ${keyIndex + 1}: ${oldSlot} → ${newSlot}`);
         return {
           ...prev,
           keys: prev.keys.map((k, i) => {
             if (i !== keyIndex) return k;
-            return { ...k, active_slot: newSlot as ActiveSlot };
+            return { ...k, active_slot: next };
           }),
         };
       });
@@ -220,17 +637,23 @@ export function useDeck8() {
       setState(snapshot);
       toast.success("Defaults restored");
     } catch (e) {
-      toast.error(`Restore failed: ${e}`);
+      toast.error(`Restore failed: ${errorMessage(e)}`);
     }
   }, []);
 
+  const reloadDefinitions = useCallback(async () => {
+    if (await runStateEdit("Reload definition", ipcReloadDefinitions)) {
+      toast.success("Device definition reloaded");
+    }
+  }, [runStateEdit]);
+
   // ── Device info & control actions ──────────────────────
 
   const doDeviceIndication = useCallback(async () => {
     try {
       await deviceIndication();
     } catch (e) {
-      toast.error(`Identify failed: ${e}`);
+      toast.error(`Identify failed: ${errorMessage(e)}`);
     }
   }, []);
 
@@ -240,35 +663,35 @@ export function useDeck8() {
       setState((prev) => ({ ...prev, connected: false, device_info: null, rgb_matrix: null }));
       toast.success("Entering bootloader — device will disconnect");
     } catch (e) {
-      toast.error(`Bootloader jump failed: ${e}`);
+      toast.error(`Bootloader jump failed: ${errorMessage(e)}`);
     }
   }, []);
 
-  const doEepromReset = useCallback(async () => {
+  const doEepromReset = useCallback(async (confirm: string) => {
     try {
-      await eepromReset();
-      toast.success("EEPROM reset — reconnect device");
+      const backup = await eepromReset(confirm);
+      toast.success("EEPROM reset — reconnect device", { description: `Backup: ${backup}` });
     } catch (e) {
-      toast.error(`EEPROM reset failed: ${e}`);
+      toast.error(`EEPROM reset failed: ${errorMessage(e)}`);
     }
   }, []);
 
-  const doDynamicKeymapReset = useCallback(async () => {
+  const doDynamicKeymapReset = useCallback(async (confirm: string) => {
     try {
-      await dynamicKeymapReset();
+      const backup = await dynamicKeymapReset(confirm);
       await refreshState();
-      toast.success("Keymap reset to defaults");
+      toast.success("Keymap reset to defaults", { description: `Backup: ${backup}` });
     } catch (e) {
-      toast.error(`Keymap reset failed: ${e}`);
+      toast.error(`Keymap reset failed: ${errorMessage(e)}`);
     }
   }, [refreshState]);
 
-  const doMacroReset = useCallback(async () => {
+  const doMacroReset = useCallback(async (confirm: string) => {
     try {
-      await macroReset();
-      toast.success("Macros reset");
+      const backup = await macroReset(confirm);
+      toast.success("Macros reset", { description: `Backup: ${backup}` });
     } catch (e) {
-      toast.error(`Macro reset failed: ${e}`);
+      toast.error(`Macro reset failed: ${errorMessage(e)}`);
     }
   }, []);
 
@@ -318,12 +741,186 @@ export function useDeck8() {
     [],
   );
 
+  const restoreKeymaps = useCallback(
+    async () => {
+      if (await runStateEdit("Restore key binds", ipcRestoreKeymaps)) {
+        toast.success("Key binds restored");
+      }
+    },
+    [runStateEdit],
+  );
+
+  const keepDeviceKeymaps = useCallback(
+    () => runStateEdit("Keep device keymap", ipcKeepDeviceKeymaps),
+    [runStateEdit],
+  );
+
+  const restoreRgb = useCallback(
+    () => runStateEdit("Restore RGB settings", ipcRestoreRgbMatrix),
+    [runStateEdit],
+  );
+
+  const keepDeviceRgb = useCallback(
+    () => runStateEdit("Keep device RGB settings", ipcKeepDeviceRgbMatrix),
+    [runStateEdit],
+  );
+
+  const chooseAudioDevice = useCallback(
+    (name: string | null) => runStateEdit("Choose audio device", () => ipcChooseAudioDevice(name)),
+    [runStateEdit],
+  );
+
+  const updateBrightnessCurve = useCallback(
+    (curve: BrightnessCurve) => {
+      setState((prev) => ({ ...prev, settings: { ...prev.settings, brightness_curve: curve } }));
+
+      if (rgbTimer.current) clearTimeout(rgbTimer.current);
+      rgbTimer.current = setTimeout(async () => {
+        try {
+          await setBrightnessCurve(curve);
+        } catch (e) {
+          toast.error(`Brightness curve: ${errorMessage(e)}`);
+        }
+      }, 50);
+    },
+    [],
+  );
+
+  const updateAccessibilityCues = useCallback(async (enabled: boolean) => {
+    setState((prev) => ({ ...prev, settings: { ...prev.settings, accessibility_cues: enabled } }));
+    try {
+      await setAccessibilityCues(enabled);
+    } catch (e) {
+      toast.error(`Accessibility cues: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const updateNowPlaying = useCallback(async (enabled: boolean) => {
+    setState((prev) => ({ ...prev, settings: { ...prev.settings, now_playing: enabled } }));
+    try {
+      await setNowPlaying(enabled);
+    } catch (e) {
+      toast.error(`Now playing: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const updateDiscordSettings = useCallback(async (discord: DiscordSettings) => {
+    let before: DiscordSettings | undefined;
+    setState((prev) => {
+      before = prev.settings.discord;
+      return { ...prev, settings: { ...prev.settings, discord } };
+    });
+    try {
+      await setDiscordSettings(discord);
+    } catch (e) {
+      toast.error(`Discord: ${errorMessage(e)}`);
+      if (before) {
+        const restore = before;
+        setState((prev) => ({ ...prev, settings: { ...prev.settings, discord: restore } }));
+      }
+    }
+  }, []);
+
+  const updateLighting = useCallback(
+    (lighting: LightingSettings) => runStateEdit("Lights", () => ipcSetLighting(lighting)),
+    [runStateEdit],
+  );
+
+  const updateAccentKeyColors = useCallback(
+    (enabled: boolean) => runStateEdit("Accent colors", () => ipcSetAccentKeyColors(enabled)),
+    [runStateEdit],
+  );
+
+  const updateLanguage = useCallback(
+    (language: string | null) => runStateEdit("Language", () => ipcSetLanguage(language)),
+    [runStateEdit],
+  );
+
+  const updateWindowSettings = useCallback(
+    (settings: WindowSettings) => runStateEdit("Window behavior", () => ipcSetWindowSettings(settings)),
+    [runStateEdit],
+  );
+
+  const updateHidTiming = useCallback(
+    (timing: HidTiming) => runStateEdit("HID timing", () => ipcSetHidTiming(timing)),
+    [runStateEdit],
+  );
+
+  const updateCooperative = useCallback(
+    (enabled: boolean) => runStateEdit("Cooperative mode", () => ipcSetCooperative(enabled)),
+    [runStateEdit],
+  );
+
+  const lockConfig = useCallback(
+    (pin: string) => runStateEdit("Lock", () => ipcLockConfig(pin)),
+    [runStateEdit],
+  );
+
+  const unlockConfig = useCallback(
+    (pin: string) => runStateEdit("Unlock", () => ipcUnlockConfig(pin)),
+    [runStateEdit],
+  );
+
+  const updateFocusSettings = useCallback(
+    (focus: FocusSettings) => runStateEdit("Focus timer", () => ipcSetFocusSettings(focus)),
+    [runStateEdit],
+  );
+
+  const updateGameSettings = useCallback(
+    (game: GameSettings) => runStateEdit("Game events", () => ipcSetGameSettings(game)),
+    [runStateEdit],
+  );
+
+  const startFocus = useCallback(
+    () => runStateEdit("Start focus", () => ipcStartFocus(null)),
+    [runStateEdit],
+  );
+
+  const stopFocus = useCallback(
+    () => runStateEdit("Stop focus", ipcStopFocus),
+    [runStateEdit],
+  );
+
+  const skipOnboardingStep = useCallback(
+    () => runStateEdit("Skip step", ipcSkipOnboardingStep),
+    [runStateEdit],
+  );
+
+  const finishOnboarding = useCallback(
+    () => runStateEdit("Close setup", ipcFinishOnboarding),
+    [runStateEdit],
+  );
+
+  const resetOnboarding = useCallback(
+    () => runStateEdit("Setup guide", ipcResetOnboarding),
+    [runStateEdit],
+  );
+
+  /** The bridge's app key, or null (with a toast) if pairing failed. */
+  const pairHueBridge = useCallback(async (bridge: string) => {
+    try {
+      return await ipcPairHueBridge(bridge);
+    } catch (e) {
+      toast.error(`Hue: ${errorMessage(e)}`);
+      return null;
+    }
+  }, []);
+
+  const updateLocalApi = useCallback(async (enabled: boolean) => {
+    setState((prev) => ({ ...prev, settings: { ...prev.settings, local_api: enabled } }));
+    try {
+      await setLocalApi(enabled);
+    } catch (e) {
+      toast.error(`Local API: ${errorMessage(e)}`);
+    }
+  }, []);
+
   const doSaveRgb = useCallback(async () => {
     try {
       await saveRgbMatrix();
       toast.success("RGB settings saved to EEPROM");
     } catch (e) {
-      toast.error(`Save failed: ${e}`);
+      toast.error(`Save failed: ${errorMessage(e)}`);
     }
   }, []);
 
@@ -344,7 +941,19 @@ export function useDeck8() {
     try {
       await setAudioInputDevice(name);
     } catch (e) {
-      toast.error(`Set input device failed: ${e}`);
+      toast.error(`Set input device failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const selectSecondInput = useCallback(async (name: string | null) => {
+    setState((prev) => ({
+      ...prev,
+      audio_config: { ...prev.audio_config, second_input_device: name },
+    }));
+    try {
+      await setSecondInputDevice(name);
+    } catch (e) {
+      toast.error(`Set second input failed: ${errorMessage(e)}`);
     }
   }, []);
 
@@ -356,7 +965,7 @@ export function useDeck8() {
     try {
       await setAudioOutputDevice(name);
     } catch (e) {
-      toast.error(`Set output device failed: ${e}`);
+      toast.error(`Set output device failed: ${errorMessage(e)}`);
     }
   }, []);
 
@@ -373,7 +982,7 @@ export function useDeck8() {
       toast.success(`"${displayName}" added to library`);
       return entry;
     } catch (e) {
-      toast.error(`Add to library failed: ${e}`);
+      toast.error(`Add to library failed: ${errorMessage(e)}`);
       return null;
     }
   }, []);
@@ -381,11 +990,10 @@ export function useDeck8() {
   const addToLibraryTrimmed = useCallback(async (
     filePath: string,
     displayName: string,
-    startMs: number,
-    endMs: number,
+    trim: Trim,
   ): Promise<SoundEntry | null> => {
     try {
-      const entry = await addToSoundLibraryTrimmed(filePath, displayName, startMs, endMs);
+      const entry = await addToSoundLibraryTrimmed(filePath, displayName, trim);
       setState((prev) => ({
         ...prev,
         audio_config: {
@@ -396,11 +1004,91 @@ export function useDeck8() {
       toast.success(`"${displayName}" added to library (trimmed)`);
       return entry;
     } catch (e) {
-      toast.error(`Add trimmed to library failed: ${e}`);
+      toast.error(`Add trimmed to library failed: ${errorMessage(e)}`);
       return null;
     }
   }, []);
 
+  const updatePreviewRoute = useCallback(async (route: SoundRoute) => {
+    setState((prev) => ({ ...prev, audio_config: { ...prev.audio_config, preview_route: route } }));
+    try {
+      await setPreviewRoute(route);
+    } catch (e) {
+      toast.error(`Preview routing: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const updateSoundFormat = useCallback(async (format: SoundFormat) => {
+    setState((prev) => ({ ...prev, audio_config: { ...prev.audio_config, sound_format: format } }));
+    try {
+      await setSoundFormat(format);
+    } catch (e) {
+      toast.error(`Sound format: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const compressLibrary = useCallback(async () => {
+    try {
+      const report = await compressSoundLibrary();
+      if (report.converted === 0) {
+        toast.info("Nothing to compress");
+      } else {
+        const mb = (report.bytes_saved / 1_000_000).toFixed(1);
+        toast.success(`Compressed ${report.converted} sound${report.converted !== 1 ? "s" : ""}, saved ${mb} MB`);
+      }
+    } catch (e) {
+      toast.error(`Compress library failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const cleanLibrary = useCallback(async (removeUnused: boolean) => {
+    try {
+      const report = await cleanSoundLibrary(removeUnused);
+      setState((prev) => ({
+        ...prev,
+        audio_config: {
+          ...prev.audio_config,
+          sound_library: prev.audio_config.sound_library.filter((e) => !report.removed_sounds.includes(e.id)),
+        },
+      }));
+      const mb = (report.bytes_freed / 1_000_000).toFixed(1);
+      toast.success(`Library cleaned up, freed ${mb} MB`);
+    } catch (e) {
+      toast.error(`Clean up failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const relinkLibrarySound = useCallback(async (soundId: string) => {
+    try {
+      const { open: openDialog } = await import("@tauri-apps/plugin-dialog");
+      const selected = await openDialog({
+        multiple: false,
+        filters: [{ name: "Audio", extensions: ["wav", "mp3", "ogg", "flac", "m4a", "mp4"] }],
+      });
+      if (!selected) return;
+      const entry = await relinkSound(soundId, selected);
+      setState((prev) => ({
+        ...prev,
+        audio_config: {
+          ...prev.audio_config,
+          sound_library: prev.audio_config.sound_library.map((e) => (e.id === soundId ? entry : e)),
+        },
+      }));
+      toast.success(`Relinked "${entry.display_name}"`);
+    } catch (e) {
+      toast.error(`Relink failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const updateLibraryQuota = useCallback(async (mb: number | null) => {
+    setState((prev) => ({ ...prev, audio_config: { ...prev.audio_config, library_quota_mb: mb } }));
+    try {
+      await setLibraryQuota(mb);
+    } catch (e) {
+      toast.error(`Library quota: ${errorMessage(e)}`);
+    }
+  }, []);
+
   const doRemoveFromLibrary = useCallback(async (soundId: string) => {
     try {
       await removeFromSoundLibrary(soundId);
@@ -409,13 +1097,17 @@ export function useDeck8() {
         audio_config: {
           ...prev.audio_config,
           sound_library: prev.audio_config.sound_library.filter((e) => e.id !== soundId),
+          playlists: prev.audio_config.playlists.map((p) => ({
+            ...p,
+            sounds: p.sounds.filter((id) => id !== soundId),
+          })),
           key_sounds: prev.audio_config.key_sounds.map((ks) =>
             ks === soundId ? null : ks,
           ) as (string | null)[],
         },
       }));
     } catch (e) {
-      toast.error(`Remove from library failed: ${e}`);
+      toast.error(`Remove from library failed: ${errorMessage(e)}`);
     }
   }, []);
 
@@ -432,10 +1124,56 @@ export function useDeck8() {
         },
       }));
     } catch (e) {
-      toast.error(`Rename failed: ${e}`);
+      toast.error(`Rename failed: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const toggleSoundMonitorOnly = useCallback(async (soundId: string, monitorOnly: boolean) => {
+    setState((prev) => ({
+      ...prev,
+      audio_config: {
+        ...prev.audio_config,
+        sound_library: prev.audio_config.sound_library.map((e) =>
+          e.id === soundId ? { ...e, monitor_only: monitorOnly } : e,
+        ),
+      },
+    }));
+    try {
+      await setSoundMonitorOnly(soundId, monitorOnly);
+    } catch (e) {
+      toast.error(`Monitor only: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const savePlaylist = useCallback(async (playlist: Playlist) => {
+    try {
+      const saved = await ipcSavePlaylist(playlist);
+      setState((prev) => {
+        const playlists = prev.audio_config.playlists.some((p) => p.id === saved.id)
+          ? prev.audio_config.playlists.map((p) => (p.id === saved.id ? saved : p))
+          : [...prev.audio_config.playlists, saved];
+        return { ...prev, audio_config: { ...prev.audio_config, playlists } };
+      });
+      return true;
+    } catch (e) {
+      toast.error(`Save playlist failed: ${errorMessage(e)}`);
+      return false;
     }
   }, []);
 
+  const deletePlaylist = useCallback(
+    (playlistId: string) => runStateEdit("Delete playlist", () => ipcDeletePlaylist(playlistId)),
+    [runStateEdit],
+  );
+
+  const skipPlaylist = useCallback(() => {
+    ipcSkipPlaylist().catch((e) => toast.error(`Skip failed: ${errorMessage(e)}`));
+  }, []);
+
+  const stopPlaylist = useCallback(() => {
+    ipcStopPlaylist().catch((e) => toast.error(`Stop failed: ${errorMessage(e)}`));
+  }, []);
+
   const doSetKeySound = useCallback(async (keyIndex: number, soundId: string | null) => {
     try {
       await setKeySound(keyIndex, soundId);
@@ -448,7 +1186,7 @@ export function useDeck8() {
         };
       });
     } catch (e) {
-      toast.error(`Set key sound failed: ${e}`);
+      toast.error(`Set key sound failed: ${errorMessage(e)}`);
     }
   }, []);
 
@@ -456,7 +1194,7 @@ export function useDeck8() {
     try {
       await ipcPreviewLibrarySound(soundId);
     } catch (e) {
-      toast.error(`Preview failed: ${e}`);
+      toast.error(`Preview failed: ${errorMessage(e)}`);
     }
   }, []);
 
@@ -473,6 +1211,26 @@ export function useDeck8() {
     }, 50);
   }, []);
 
+  const updateQuietHours = useCallback((quietHours: QuietHours) => {
+    setState((prev) => ({ ...prev, settings: { ...prev.settings, quiet_hours: quietHours } }));
+    if (quietTimer.current) clearTimeout(quietTimer.current);
+    quietTimer.current = setTimeout(async () => {
+      try {
+        setState(await setQuietHours(quietHours));
+      } catch (e) {
+        toast.error(`Quiet hours: ${errorMessage(e)}`);
+      }
+    }, 150);
+  }, []);
+
+  const overrideQuiet = useCallback(async (minutes: number) => {
+    try {
+      setState(await overrideQuietHours(minutes));
+    } catch (e) {
+      toast.error(`Quiet hours: ${errorMessage(e)}`);
+    }
+  }, []);
+
   const updateMicVolume = useCallback((volume: number) => {
     setState((prev) => ({
       ...prev,
@@ -486,55 +1244,230 @@ export function useDeck8() {
     }, 50);
   }, []);
 
+  const updateSecondInputVolume = useCallback((volume: number) => {
+    setState((prev) => ({
+      ...prev,
+      audio_config: { ...prev.audio_config, second_input_volume: volume },
+    }));
+    if (volumeTimer.current) clearTimeout(volumeTimer.current);
+    volumeTimer.current = setTimeout(async () => {
+      try {
+        await setSecondInputVolume(volume);
+      } catch { /* silent */ }
+    }, 50);
+  }, []);
+
+  const updateInputGain = useCallback((gainDb: number) => {
+    setState((prev) => ({
+      ...prev,
+      audio_config: { ...prev.audio_config, input_gain_db: gainDb },
+    }));
+    if (volumeTimer.current) clearTimeout(volumeTimer.current);
+    volumeTimer.current = setTimeout(async () => {
+      try {
+        await setInputGain(gainDb);
+      } catch { /* silent */ }
+    }, 50);
+  }, []);
+
+  const updateProcessingStage = useCallback(async (kind: StageKind, enabled: boolean) => {
+    setState((prev) => ({
+      ...prev,
+      audio_config: {
+        ...prev.audio_config,
+        processing: prev.audio_config.processing.map((s) => (s.kind === kind ? { ...s, enabled } : s)),
+      },
+    }));
+    try {
+      await setProcessingStage(kind, enabled);
+    } catch (e) {
+      toast.error(`Processing: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const updateAgc = useCallback(async (agc: AgcSettings) => {
+    try {
+      await setAgc(agc);
+      setState((prev) => ({ ...prev, audio_config: { ...prev.audio_config, agc } }));
+    } catch (e) {
+      toast.error(`Auto gain: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const updateProcessingOrder = useCallback(async (order: StageKind[]) => {
+    setState((prev) => ({
+      ...prev,
+      audio_config: {
+        ...prev.audio_config,
+        processing: order.map((kind) => ({
+          kind,
+          enabled: prev.audio_config.processing.some((s) => s.kind === kind && s.enabled),
+        })),
+      },
+    }));
+    try {
+      await setProcessingOrder(order);
+    } catch (e) {
+      toast.error(`Processing: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const toggleSoundboard = useCallback(
+    (on: boolean) =>
+      runStateEdit(`Turn ${on ? "on" : "off"} soundboard`, () => ipcSetSoundboard(on)),
+    [runStateEdit],
+  );
+
+  const stopAllSounds = useCallback(async () => {
+    try {
+      await ipcStopAllSounds();
+    } catch (e) {
+      toast.error(`Stop sounds: ${errorMessage(e)}`);
+    }
+  }, []);
+
+  const toggleSoundsMuted = useCallback(
+    (muted: boolean) =>
+      runStateEdit(`${muted ? "Mute" : "Unmute"} sounds`, () => ipcSetSoundsMuted(muted)),
+    [runStateEdit],
+  );
+
+  const updateAppHotkey = useCallback(
+    (action: AppHotkey, hotkey: string | null) =>
+      runStateEdit("Set hotkey", () => ipcSetAppHotkey(action, hotkey)),
+    [runStateEdit],
+  );
+
+  const updateStopWord = useCallback(async (enabled: boolean) => {
+    setState((prev) => ({ ...prev, audio_config: { ...prev.audio_config, stop_word: enabled } }));
+    try {
+      await setStopWord(enabled);
+    } catch (e) {
+      toast.error(`Stop word: ${errorMessage(e)}`);
+    }
+  }, []);
+
   // ── Audio trim actions ─────────────────────────────────────
 
   const getFileDuration = useCallback(async (filePath: string): Promise<number> => {
     try {
       return await getAudioDuration(filePath);
     } catch (e) {
-      toast.error(`Get duration failed: ${e}`);
+      toast.error(`Get duration failed: ${errorMessage(e)}`);
       return 0;
     }
   }, []);
 
-  const previewTrimmedAudio = useCallback(async (sourcePath: string, startMs: number, endMs: number) => {
+  const previewTrimmedAudio = useCallback(async (sourcePath: string, trim: Trim) => {
     try {
-      await previewTrim(sourcePath, startMs, endMs);
+      await previewTrim(sourcePath, trim);
     } catch (e) {
-      toast.error(`Preview failed: ${e}`);
+      toast.error(`Preview failed: ${errorMessage(e)}`);
     }
   }, []);
 
+  // Expose the desktop theme to CSS: [data-system-theme] and --system-accent
+  useEffect(() => {
+    const root = document.documentElement;
+    if (!systemTheme) return;
+    root.dataset.systemTheme = systemTheme.dark ? "dark" : "light";
+    const accent = systemTheme.accent;
+    if (accent) {
+      root.style.setProperty("--system-accent", hsvToRgb(accent.h, accent.s, accent.v));
+    } else {
+      root.style.removeProperty("--system-accent");
+    }
+  }, [systemTheme]);
+
   // ── Initialization + event listener ─────────────────────
 
   useEffect(() => {
-    // Silent auto-connect — no toast on failure
-    connect(true);
+    // Keymap picker catalog from the backend; re-render so labels pick it up
+    listKeycodes()
+      .then((catalog) => {
+        setKeycodeCatalog(catalog);
+        setState((prev) => ({ ...prev }));
+      })
+      .catch(() => {});
+    // Silent auto-connect — no toast on failure. Skipped in safe mode.
+    getState()
+      .then((s) => {
+        if (s.safe_mode) {
+          setState(s);
+          toast.warning("Safe mode: device, audio and shortcuts were not started");
+        } else {
+          connect(true);
+        }
+      })
+      .catch(() => connect(true));
     // Load audio devices
     refreshAudioDevices();
+    getSystemTheme().then(setSystemTheme).catch(() => {});
 
-    // Global toggle (tray menu "Toggle LEDs")
-    const unlistenGlobal = onSlotToggled((newSlot) => {
-      console.warn(`[GLOBAL EVENT] slot-toggled: all keys → ${newSlot}`);
-      setState((prev) => ({
-        ...prev,
-        active_slot: newSlot as ActiveSlot,
-        keys: prev.keys.map((k) => ({
-          ...k,
-          active_slot: newSlot as ActiveSlot,
-        })),
-      }));
-    });
-
-    // Per-key toggle (physical key press on Deck-8)
+    // Physical key press on Deck-8, or the tray's "Toggle LEDs"
     const unlistenState = onStateUpdated((snapshot) => {
-      console.log("[STATE EVENT] state-updated from physical key press");
+      console.log("[STATE EVENT] state-updated");
       setState(snapshot);
     });
 
+    // Result of start_keycode_capture (state arrives via state-updated)
+    const unlistenCapture = onKeycodeCaptured((result) => {
+      if (result.error) {
+        toast.error(`Set keycode failed: ${result.error}`);
+      } else if (result.keycode !== null) {
+        toast.success(`Bound ${keycodeToLabel(result.keycode)}`);
+      }
+    });
+
+    const unlistenAnnounce = onAnnounce((text) => {
+      setAnnouncement((prev) => ({ id: (prev?.id ?? 0) + 1, text }));
+    });
+
+    // The backend reconnects by itself; the result arrives as state-updated
+    const unlistenUnhealthy = onDeviceUnhealthy(() => {
+      toast.warning("Device stopped responding — reconnecting");
+    });
+
+    const unlistenSyncReport = onSyncReport((report) => {
+      const failed = report.checks.filter((c) => c.status === "Fail");
+      if (failed.length > 0) {
+        toast.warning(`Device self-test: ${failed.map((c) => c.name).join(", ")} failed`, {
+          description: "See Settings → Device for details",
+        });
+      }
+    });
+
+    const unlistenRebooted = onDeviceRebooted(() => {
+      toast.warning("Your Deck-8 restarted", {
+        description: "Its uptime reset — a loose cable or USB hub power is the usual cause",
+      });
+    });
+
+    const unlistenStopWord = onStopWord(() => {
+      toast.info("Heard \"stop\" — all sounds stopped");
+    });
+
+    const unlistenSoundMissing = onSoundMissing((entry) => {
+      toast.warning(`"${entry.display_name}" is missing its file`, {
+        action: { label: "Relink…", onClick: () => relinkLibrarySound(entry.id) },
+      });
+    });
+
+    // Accent-following keys arrive separately as state-updated
+    const unlistenSystemTheme = onSystemTheme(setSystemTheme);
+    const unlistenThemePreview = onThemePreview(setThemePreview);
+
     return () => {
-      unlistenGlobal.then((fn) => fn());
       unlistenState.then((fn) => fn());
+      unlistenCapture.then((fn) => fn());
+      unlistenAnnounce.then((fn) => fn());
+      unlistenUnhealthy.then((fn) => fn());
+      unlistenSyncReport.then((fn) => fn());
+      unlistenRebooted.then((fn) => fn());
+      unlistenStopWord.then((fn) => fn());
+      unlistenSoundMissing.then((fn) => fn());
+      unlistenSystemTheme.then((fn) => fn());
+      unlistenThemePreview.then((fn) => fn());
     };
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []);
@@ -545,12 +1478,47 @@ export function useDeck8() {
     selectedKey,
     setSelectedKey,
     connect: () => connect(false),
+    disconnect,
     updateKeyColor,
     updateKeycode,
+    captureKeycode,
     toggleKeyOverride,
+    updateKeySource,
     toggleKeySlot: doToggleKeySlot,
+    addKeySlot,
+    removeKeySlot,
+    renameKeySlot,
+    selectKeySlot,
+    updateKeyBinding,
+    updateKeyLightScene,
+    updateKeyLabel,
+    updateKeyIcon,
+    updateKeySoundCooldown,
+    updateKeySoundMode,
+    updateKeyPressMode,
+    updateKeySoundGain,
+    copyKeyConfig,
+    pasteKeyConfig,
+    applyColorToAll,
+    toggleDnd,
+    addSwatch,
+    removeSwatch,
+    saveTheme,
+    applyTheme,
+    deleteTheme,
+    themePreview,
+    previewTheme,
+    endThemePreview,
+    saveKeyGroup,
+    deleteKeyGroup,
+    saveMode,
+    deleteMode,
+    setMode,
+    saveRule,
+    deleteRule,
     saveCustom: doSaveCustom,
     restoreDefaults: doRestoreDefaults,
+    reloadDefinitions,
     deviceIndication: doDeviceIndication,
     bootloaderJump: doBootloaderJump,
     eepromReset: doEepromReset,
@@ -559,18 +1527,70 @@ export function useDeck8() {
     updateRgb,
     updateRgbColor,
     saveRgb: doSaveRgb,
+    restoreRgb,
+    keepDeviceRgb,
+    restoreKeymaps,
+    keepDeviceKeymaps,
+    chooseAudioDevice,
+    updateBrightnessCurve,
+    updateAccessibilityCues,
+    updateNowPlaying,
+    updateDiscordSettings,
+    updateLighting,
+    pairHueBridge,
+    updateAccentKeyColors,
+    systemTheme,
+    updateLanguage,
+    updateWindowSettings,
+    updateHidTiming,
+    updateCooperative,
+    lockConfig,
+    unlockConfig,
+    updateFocusSettings,
+    updateGameSettings,
+    startFocus,
+    stopFocus,
+    skipOnboardingStep,
+    finishOnboarding,
+    resetOnboarding,
+    updateLocalApi,
+    announcement,
     // Soundboard
     audioDevices,
     refreshAudioDevices,
     selectAudioInput,
     selectAudioOutput,
+    selectSecondInput,
     updateSoundVolume,
     updateMicVolume,
+    updateInputGain,
+    updateSecondInputVolume,
+    updateProcessingStage,
+    updateProcessingOrder,
+    updateAgc,
+    updateStopWord,
+    toggleSoundboard,
+    stopAllSounds,
+    toggleSoundsMuted,
+    updateAppHotkey,
+    updateQuietHours,
+    overrideQuiet,
     // Sound library
     addToLibrary,
     addToLibraryTrimmed,
+    updateSoundFormat,
+    updatePreviewRoute,
+    compressLibrary,
+    cleanLibrary,
+    relinkLibrarySound,
+    updateLibraryQuota,
     removeFromLibrary: doRemoveFromLibrary,
     renameSound: doRenameSound,
+    toggleSoundMonitorOnly,
+    savePlaylist,
+    deletePlaylist,
+    skipPlaylist,
+    stopPlaylist,
     setKeySound: doSetKeySound,
     previewLibrarySound: doPreviewLibrarySound,
     // Audio trim
@@ -1,5 +1,7 @@
 // ── QMK Keycode Database ────────────────────────────────────────────────
 
+import type { KeycodeInfo } from "@/lib/tauri";
+
 export type KeycodeCategory = "basic" | "multimedia" | "mouse" | "special" | "lighting";
 
 export interface KeycodeDef {
@@ -211,6 +213,35 @@ for (const kc of KEYCODES) {
   codeToDefMap.set(kc.code, kc);
 }
 
+/**
+ * Replace the built-in basic/multimedia keys with the backend's keycode
+ * catalog (`listKeycodes`), so the picker offers exactly what the shortcut
+ * backends understand. Modifier keys, mouse, lighting and special codes stay.
+ */
+export function setKeycodeCatalog(catalog: KeycodeInfo[]): void {
+  if (catalog.length === 0) return;
+  const fromBackend = new Set(catalog.map((kc) => kc.code));
+  const kept = KEYCODES.filter(
+    (kc) =>
+      kc.category !== "multimedia" &&
+      !(kc.category === "basic" && fromBackend.has(kc.code)),
+  );
+  const backend: KeycodeDef[] = catalog.map((kc) => ({
+    code: kc.code,
+    label: kc.label,
+    category: kc.category === "media" ? "multimedia" : "basic",
+    wide: kc.label.length > 7 || undefined,
+  }));
+  // Special codes (KC_NO, KC_TRNS) first, then the catalog, then the rest
+  const special = kept.filter((kc) => kc.code <= KC_TRANSPARENT);
+  const rest = kept.filter((kc) => kc.code > KC_TRANSPARENT);
+  KEYCODES.splice(0, KEYCODES.length, ...special, ...backend, ...rest);
+  codeToDefMap.clear();
+  for (const kc of KEYCODES) {
+    codeToDefMap.set(kc.code, kc);
+  }
+}
+
 // ── Modifier names for display ──────────────────────────────────────────
 
 const MOD_NAMES: [number, string][] = [
@@ -6,14 +6,104 @@ export interface HsvColor {
   v: number;
 }
 
+/** A named color a key can show; keypresses cycle through a key's slots. */
+export interface ColorSlot {
+  name: string;
+  color: HsvColor;
+}
+
 export interface KeyConfig {
-  slot_a: HsvColor;
-  slot_b: HsvColor;
+  slots: ColorSlot[];
   override_enabled: boolean;
-  active_slot: ActiveSlot;
+  /** Index into `slots`. */
+  active_slot: number;
+  source: KeySource;
+  /** Minimum ms between two actions of this key (0 = none). */
+  cooldown_ms: number;
+  /** Minimum ms between two plays of the key's sound (0 = none). */
+  sound_cooldown_ms: number;
+  /** Dim the LED until the sound can play again. */
+  dim_on_cooldown: boolean;
+  sound_mode: SoundMode;
+  press_mode: PressMode;
+  binding: SlotBinding | null;
+  /** Lighting scene recalled on each press. */
+  light_scene: LightScene | null;
+  /** User-facing name, e.g. "Mute mic". */
+  label: string | null;
+  /** Short emoji/symbol shown next to the label. */
+  icon: string | null;
+}
+
+/** Condition a slot binding watches (see watchers.rs). */
+export type Condition =
+  | { kind: "MicMuted" }
+  /** OBS output/scene (see obs.rs); pressing the key toggles it or switches to the scene. */
+  | { kind: "ObsRecording"; host: string; port: number; password: string | null }
+  | { kind: "ObsStreaming"; host: string; port: number; password: string | null }
+  | { kind: "ObsScene"; host: string; port: number; password: string | null; scene: string }
+  | { kind: "FileExists"; path: string }
+  | { kind: "Mqtt"; host: string; port: number; topic: string; payload: string }
+  | { kind: "HttpPoll"; url: string; contains: string | null }
+  /** Meeting mute (see meeting.rs); pressing the key toggles mute in the app. */
+  | { kind: "TeamsMuted" }
+  | { kind: "ZoomMuted" }
+  /** OS media session playing (see media.rs); pressing the key toggles play/pause. */
+  | { kind: "MediaPlaying" }
+  /** Virtual mixer channel mute (see mixer.rs); pressing the key toggles it. */
+  | { kind: "MixerMuted"; mixer: "WaveLink" | "VoiceMeeter"; channel: string }
+  /** The soundboard is routing the mic; pressing the key turns it on/off. */
+  | { kind: "SoundboardRunning" }
+  /** Discord voice state (see discord.rs); pressing a DiscordMuted key toggles mute. */
+  | { kind: "DiscordMuted" }
+  | { kind: "DiscordSpeaking" }
+  /** Lit while the named mode is active; pressing the key cycles to the next mode. */
+  | { kind: "ModeActive"; mode: string }
+  /** Lit while a focus session runs; pressing the key starts or stops one. */
+  | { kind: "FocusRunning" }
+  /** A meeting in the ICS calendar starts within `lead_min` minutes or is on (see calendar.rs). */
+  | { kind: "CalendarMeeting"; url: string; lead_min: number; open_link: boolean };
+
+/** A key shows `when_true` while the condition holds, else `when_false`. */
+export interface SlotBinding {
+  condition: Condition;
+  when_true: string;
+  when_false: string;
+  poll_ms: number;
+}
+
+/** Which keyboards may trigger a key's shortcut. */
+export type KeySource = "Deck" | "Any";
+
+/** `Momentary` plays the sound only while the key is held. */
+export type SoundMode = "OneShot" | "Momentary";
+
+/** Whether a press changes the key's color, runs its action (sound, scene, followed app), or both. */
+export type PressMode = "Both" | "ActionOnly" | "LedOnly";
+
+/** Backend keycode catalog entry (`list_keycodes`). */
+export interface KeycodeInfo {
+  code: number;
+  name: string;
+  category:
+    | "letter" | "number" | "control" | "punctuation" | "function"
+    | "navigation" | "numpad" | "media" | "international";
+  label: string;
 }
 
-export type ActiveSlot = "A" | "B";
+/** Result of a keycode capture; `keycode` is null when cancelled. */
+export interface KeycodeCaptured {
+  key_index: number;
+  keycode: number | null;
+  error: string | null;
+}
+
+/** Physical key press/release, emitted as `key-event`. */
+export interface KeyEvent {
+  key_index: number;
+  pressed: boolean;
+  held_ms: number | null;
+}
 
 export interface DeviceInfo {
   protocol_version: number;
@@ -22,6 +112,17 @@ export interface DeviceInfo {
   layer_count: number;
   macro_count: number;
   macro_buffer_size: number;
+  capabilities: Capabilities;
+}
+
+/** Firmware features from the connect handshake (see `protocol::Capabilities`). */
+export interface Capabilities {
+  /** False for firmware without the handshake; the rest is then assumed. */
+  reported: boolean;
+  per_key_override: boolean;
+  rgb_matrix: boolean;
+  /** One report per key color instead of three. */
+  set_hsv: boolean;
 }
 
 export interface RgbMatrixState {
@@ -36,17 +137,84 @@ export interface SoundEntry {
   id: string;
   filename: string;
   display_name: string;
+  /** The file was deleted outside the app. */
+  missing: boolean;
+  /** Plays in the user's headphones only, never into the mic. */
+  monitor_only: boolean;
 }
 
 export interface AudioConfig {
   sound_files: (string | null)[];
   sound_library: SoundEntry[];
+  /** A sound or playlist id per key. */
   key_sounds: (string | null)[];
+  playlists: Playlist[];
+  /** Per-key volume offset in dB for its sound. */
+  key_sound_gain_db: number[];
   audio_input_device: string | null;
   audio_output_device: string | null;
+  /** Second mic or line-in, mixed in after the mic's processing. */
+  second_input_device: string | null;
+  second_input_volume: number;
   sound_volume: number;
   mic_volume: number;
+  /** Mic gain in dB as it's captured, before processing. */
+  input_gain_db: number;
+  /** The pipeline is running. */
   soundboard_enabled: boolean;
+  /** Turned off by the user; stays off until turned back on. */
+  soundboard_off: boolean;
+  /** Key sounds don't play; the mic still passes through. */
+  sounds_muted: boolean;
+  /** Mic processing stages in order (see dsp.rs); sounds are mixed in after. */
+  processing: ProcessingStage[];
+  agc: AgcSettings;
+  /** Saying "stop stop stop" into the mic stops every sound (see stopword.rs). */
+  stop_word: boolean;
+  /** Format trimmed imports are saved in. */
+  sound_format: SoundFormat;
+  /** Where previews play while the pipeline runs. */
+  preview_route: SoundRoute;
+  /** Imports are refused while the library takes more than this. */
+  library_quota_mb: number | null;
+}
+
+/** Sounds a key plays back to back with no gap; pressing it again skips ahead. */
+export interface Playlist {
+  /** Empty for a playlist not saved yet. */
+  id: string;
+  name: string;
+  /** SoundEntry ids, in order. */
+  sounds: string[];
+  /** The last sound repeats until skipped or stopped. */
+  loop_last: boolean;
+}
+
+/** FLAC is lossless and much smaller than the 32-bit float WAV. */
+export type SoundFormat = "Wav" | "Flac";
+
+/** Headphones only, the virtual cable (the call) only, or both. */
+export type SoundRoute = "Monitor" | "Cable" | "Both";
+
+/** Result of `compress_sound_library`. */
+export interface CompressReport {
+  converted: number;
+  bytes_saved: number;
+}
+
+export type StageKind = "Gate" | "Denoise" | "Eq" | "Agc" | "Compressor";
+
+/** AGC keeps speech between the targets (dBFS RMS), boosting by at most `max_gain_db`. */
+export interface AgcSettings {
+  target_low_db: number;
+  target_high_db: number;
+  max_gain_db: number;
+}
+
+/** A disabled stage is bypassed. */
+export interface ProcessingStage {
+  kind: StageKind;
+  enabled: boolean;
 }
 
 export interface AudioDeviceInfo {
@@ -58,14 +226,350 @@ export interface AudioDeviceList {
   output_devices: AudioDeviceInfo[];
 }
 
+export interface AppSettings {
+  grab_device_input: boolean;
+  replay_method: ReplayMethod;
+  dedup_ms: number;
+  /** Slot change fade, in ms (0 = instant). */
+  fade_ms: number;
+  brightness_curve: BrightnessCurve;
+  /** Earcon + screen-reader announcement on slot changes. */
+  accessibility_cues: boolean;
+  quiet_hours: QuietHours;
+  /** Track the OS media session for the now-playing card. */
+  now_playing: boolean;
+  /** Serve the local WebSocket API on 127.0.0.1:LOCAL_API_PORT. */
+  local_api: boolean;
+  /** Global hotkey that stops every sound, e.g. "Ctrl+Alt+Backspace". */
+  stop_sounds_hotkey: string | null;
+  /** Global hotkey that mutes or unmutes key sounds. */
+  mute_sounds_hotkey: string | null;
+  discord: DiscordSettings;
+  lighting: LightingSettings;
+  /** Keys still on the default first color take the OS accent color. */
+  accent_key_colors: boolean;
+  /** Accent color last given to those keys. */
+  applied_accent: HsvColor | null;
+  /** Language of tray items, announcements and errors; null follows the OS. */
+  language: string | null;
+  window: WindowSettings;
+  hid: HidTiming;
+  /** While another app holds the device, keep retrying until it lets go. */
+  cooperative: boolean;
+  focus: FocusSettings;
+  game: GameSettings;
+  /** First-run guide step; "Done" once finished or skipped. */
+  onboarding: OnboardingStep;
+}
+
+export type OnboardingStep = "Device" | "TestKey" | "Audio" | "FirstSound" | "Done";
+
+/** What closing and minimizing the main window do. */
+export interface WindowSettings {
+  close: CloseAction;
+  /** Minimizing hides the window to the tray too. */
+  minimize_to_tray: boolean;
+  /** Ask before quitting while a sound or playlist is playing. */
+  confirm_quit: boolean;
+}
+
+export type CloseAction = "HideToTray" | "Exit";
+
+/** HID read timeout and spacing between reports (see `hid::HidTiming`). */
+export interface HidTiming {
+  /** 100–1000 ms. */
+  timeout_ms: number;
+  /** 0–50 ms; 0 sends reports back to back. */
+  pacing_ms: number;
+}
+
+/** Focus timer (see pomodoro.rs). */
+export interface FocusSettings {
+  /** Session length, 1–180 min. */
+  focus_min: number;
+  /** Sessions a day for the streak; 0 for no goal. */
+  daily_goal: number;
+  /** Flash the keys when the day's goal is reached. */
+  celebrate: boolean;
+}
+
+export interface FocusDay {
+  /** Local date, YYYY-MM-DD. */
+  date: string;
+  sessions: number;
+  minutes: number;
+}
+
+/** Completed focus sessions (stopped ones don't count). */
+export interface FocusSummary {
+  /** The last 7 days, oldest first; the last is today. */
+  days: FocusDay[];
+  /** Since Monday. */
+  week_sessions: number;
+  week_minutes: number;
+  /** Days in a row that met the goal. */
+  streak: number;
+  daily_goal: number;
+}
+
+/** Game state endpoint (see game.rs). */
+export interface GameSettings {
+  enabled: boolean;
+  port: number;
+  /** The "auth.token" the game's config sends; null accepts any. */
+  token: string | null;
+  rules: GameRule[];
+}
+
+/** While the value at `path` passes `test`, `key` shows `color`. */
+export interface GameRule {
+  name: string;
+  /** Dotted path into the game's JSON, e.g. "player.state.health". */
+  path: string;
+  test: GameTest;
+  /** LED index. */
+  key: number;
+  color: HsvColor;
+  pattern: NotifyPattern;
+}
+
+export type GameTest =
+  | { kind: "Below"; value: number }
+  | { kind: "Above"; value: number }
+  | { kind: "Equals"; value: string };
+
+/** The desktop's dark/light mode and accent color (see appearance.rs). */
+export interface SystemTheme {
+  dark: boolean;
+  /** null where the desktop has no accent color. */
+  accent: HsvColor | null;
+}
+
+/** Desk lights that follow the pad (see lighting.rs). */
+export interface LightingSettings {
+  /** Color every light mirrors; null leaves them to key scenes. */
+  mirror: LightSource | null;
+  targets: LightTarget[];
+}
+
+export type LightSource = { kind: "Key"; key_index: number } | { kind: "Matrix" };
+
+export type LightTarget =
+  /** `user` is the app key from pairing; `lights` the light ids that mirror the color. */
+  | { kind: "Hue"; name: string; bridge: string; user: string; lights: string[] }
+  | { kind: "Wled"; name: string; host: string };
+
+/** A Hue scene id, or a WLED preset number, on the light named `target`. */
+export interface LightScene {
+  target: string;
+  scene: string;
+}
+
+/** Discord Rich Presence and voice state, through the user's own Discord app. */
+export interface DiscordSettings {
+  /** Show the deck's current profile (the theme its keys show) as the activity. */
+  presence: boolean;
+  client_id: string;
+  /** Only needed for voice state bindings. */
+  client_secret: string | null;
+}
+
+/** The app's own global hotkeys. */
+export type AppHotkey = "StopSounds" | "MuteSounds";
+
+/** Daily window in which key sounds are muted or capped. */
+export interface QuietHours {
+  enabled: boolean;
+  /** Minutes after local midnight; start > end wraps past midnight. */
+  start_min: number;
+  end_min: number;
+  /** Key sound volume inside the window (0 = muted). */
+  max_volume: number;
+}
+
+/** `out = min + (max - min) * (v / 255)^gamma` for v > 0; 0 stays off. */
+export interface BrightnessCurve {
+  gamma: number;
+  min: number;
+  max: number;
+}
+
+/** Keystroke replay mechanism for the plugin shortcut path. */
+export type ReplayMethod = "Native" | "Enigo";
+
+export type PermissionStatus = "granted" | "missing" | "limited" | "unknown";
+
+export interface PermissionCheck {
+  id: string;
+  label: string;
+  status: PermissionStatus;
+  detail: string;
+  fix: string | null;
+  settings_url: string | null;
+}
+
+/** A named set of 8 key colors (LED order). */
+export interface Theme {
+  name: string;
+  colors: HsvColor[];
+}
+
+export type CheckStatus = "Pass" | "Warn" | "Fail" | "Skipped";
+
+/** A saved audio device that's gone, with several look-alikes listed in its place. */
+export interface DeviceChoice {
+  role: "Input" | "Output" | "SecondInput";
+  saved: string;
+  candidates: string[];
+}
+
+/** Connect-time self-test (selftest.rs), also sent as `sync-report`. */
+export interface SyncReport {
+  checks: { name: string; status: CheckStatus; detail: string }[];
+}
+
+/** Whether the device shows what the app has, and keeps it through a power cycle. */
+export type SyncStatus = "Saved" | "Unsaved" | "OutOfSync" | "Unknown";
+
+/** Per subsystem: "Unsaved" means "Save to device" is needed. */
+export interface DeviceSync {
+  colors: SyncStatus;
+  keymaps: SyncStatus;
+  rgb: SyncStatus;
+}
+
+/** One uptime read by the heartbeat (heartbeat.rs), also sent as `device-health`. */
+export interface HealthSample {
+  at_ms: number;
+  connected: boolean;
+  /** Seconds; null when disconnected or the read failed. */
+  uptime: number | null;
+  latency_ms: number | null;
+  error: string | null;
+}
+
+/** The uptime went backwards while connected; also sent as `device-rebooted`. */
+export interface DeviceReboot {
+  at_ms: number;
+  previous_uptime: number;
+  uptime: number;
+}
+
+/** The last hour of heartbeat samples, oldest first. */
+export interface DeviceHealth {
+  samples: HealthSample[];
+  reboots: DeviceReboot[];
+}
+
+/** Why the connection changed (connections.rs). Unresponsive and Lost are drops. */
+export type ConnectionEventKind =
+  | "Connected"
+  | "Released"
+  | "Resync"
+  | "Unresponsive"
+  | "Lost"
+  | "NotFound"
+  | "Busy"
+  | "Failed";
+
+export interface ConnectionEvent {
+  at_ms: number;
+  kind: ConnectionEventKind;
+  detail: string | null;
+}
+
+export interface ConnectionSummary {
+  drops_day: number;
+  drops_week: number;
+  mean_time_to_drop_ms: number | null;
+  connected_since_ms: number | null;
+}
+
+/** Recorded connects and disconnects, oldest first. */
+export interface ConnectionHistory {
+  events: ConnectionEvent[];
+  summary: ConnectionSummary;
+}
+
+/** Radio-button keys: when one leaves its first slot the others go back to theirs. */
+export interface KeyGroup {
+  name: string;
+  /** LED indices. */
+  keys: number[];
+}
+
+/** Named mode (see modes.rs): a slot name per LED, and optionally the key sounds mute. */
+export interface Mode {
+  name: string;
+  /** `null` leaves the key alone, or takes the base's slot. */
+  slots: (string | null)[];
+  sounds_muted: boolean | null;
+  /** Mode this one builds on; resolved when it's applied. */
+  base: string | null;
+}
+
+/** When an LED rule holds (see rules.rs). */
+export type RuleWhen =
+  /** `days` bit 0 is Monday; minutes after midnight, `start_min > end_min` wraps past it. */
+  | { kind: "Schedule"; days: number; start_min: number; end_min: number }
+  | { kind: "OnBattery" };
+
+/** What an LED rule does while it holds; undone when it stops. */
+export type RuleAction =
+  | { kind: "KeySlot"; key: number; slot: string }
+  /** Every key at `percent` of its brightness (1–100). */
+  | { kind: "Dim"; percent: number };
+
+export interface Rule {
+  /** Empty for a new rule. */
+  id: string;
+  name: string;
+  enabled: boolean;
+  when: RuleWhen;
+  then: RuleAction;
+}
+
 export interface StateSnapshot {
   connected: boolean;
   keys: KeyConfig[];
-  active_slot: ActiveSlot;
   keymaps: number[];
+  /** Last-known keymaps (matrix order), set when the connected device differs. */
+  keymap_restore: number[] | null;
   device_info: DeviceInfo | null;
   rgb_matrix: RgbMatrixState | null;
+  /** Saved RGB settings, set when the connected device differs from them. */
+  rgb_restore: RgbMatrixState | null;
   audio_config: AudioConfig;
+  settings: AppSettings;
+  palette: HsvColor[];
+  themes: Theme[];
+  groups: KeyGroup[];
+  modes: Mode[];
+  /** The mode last switched to. */
+  active_mode: string | null;
+  rules: Rule[];
+  /** Ids of the rules holding right now. */
+  active_rules: string[];
+  /** LED index of the key last copied with copyKeyConfig, if any. */
+  key_clipboard: number | null;
+  /** Do-not-disturb: every LED is off until toggled again. */
+  dnd: boolean;
+  safe_mode: boolean;
+  /** Key sounds are muted or capped by quiet hours right now. */
+  quiet_active: boolean;
+  /** Unix time (ms) a quiet hours override ends. */
+  quiet_override_until: number | null;
+  /** Unix time (ms) the running focus session ends. */
+  focus_until: number | null;
+  /** Self-test of the current connection. */
+  sync_report: SyncReport | null;
+  device_sync: DeviceSync;
+  /** Another app (VIA, …) holds the device; the last connect couldn't open it. */
+  device_busy: boolean;
+  /** Configuration changes are refused (LOCKED) until unlocked with the PIN. */
+  locked: boolean;
+  /** Set while the app asks which device a saved one has become. */
+  device_choice: DeviceChoice | null;
 }
 
 // ── Internal keycode detection ──────────────────────────────────────
@@ -81,6 +585,34 @@ export function isInternalKeycode(keycode: number): boolean {
 
 const isTauri = "__TAURI_INTERNALS__" in window;
 
+/** Error returned by a failed command (see `error.rs`). */
+export interface HubError {
+  code:
+    | "NOT_CONNECTED"
+    | "HID_IO"
+    | "DEVICE_TIMEOUT"
+    | "UNSUPPORTED"
+    | "AUDIO_DEVICE"
+    | "FILE_IO"
+    | "PLATFORM"
+    | "INVALID_INPUT"
+    | "KEYMAP_WRITE";
+  message: string;
+  /** e.g. the file path for FILE_IO. */
+  context: string | null;
+  /** Unix time in milliseconds. */
+  at_ms: number;
+}
+
+function isHubError(e: unknown): e is HubError {
+  return typeof e === "object" && e !== null && "code" in e && "message" in e;
+}
+
+/** Human-readable text for anything a rejected command (or plugin) throws. */
+export function errorMessage(e: unknown): string {
+  return isHubError(e) ? e.message : String(e);
+}
+
 // Lazy-load Tauri APIs only when inside Tauri runtime
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   const { invoke } = await import("@tauri-apps/api/core");
@@ -94,6 +626,11 @@ export function connectDevice(): Promise<boolean> {
   return tauriInvoke<boolean>("connect_device");
 }
 
+export function disconnectDevice(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("disconnect_device");
+}
+
 export function getState(): Promise<StateSnapshot> {
   if (!isTauri) return Promise.reject("Not in Tauri");
   return tauriInvoke<StateSnapshot>("get_state");
@@ -101,7 +638,7 @@ export function getState(): Promise<StateSnapshot> {
 
 export function setKeyColor(
   keyIndex: number,
-  slot: ActiveSlot,
+  slot: string,
   h: number,
   s: number,
   v: number,
@@ -110,9 +647,10 @@ export function setKeyColor(
   return tauriInvoke("set_key_color", { keyIndex, slot, h, s, v });
 }
 
-export function toggleSlot(): Promise<string> {
-  if (!isTauri) return Promise.resolve("B");
-  return tauriInvoke<string>("toggle_slot");
+/** Several slot colors in one call: `[keyIndex, slot, color]`, applied in order. */
+export function setAllKeyColors(colors: [number, string, HsvColor][]): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_all_key_colors", { colors });
 }
 
 export function toggleKeySlot(keyIndex: number): Promise<StateSnapshot> {
@@ -120,6 +658,57 @@ export function toggleKeySlot(keyIndex: number): Promise<StateSnapshot> {
   return tauriInvoke<StateSnapshot>("toggle_key_slot", { keyIndex });
 }
 
+export function addKeySlot(keyIndex: number, name: string, color: HsvColor): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("add_key_slot", { keyIndex, name, ...color });
+}
+
+export function removeKeySlot(keyIndex: number, name: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("remove_key_slot", { keyIndex, name });
+}
+
+export function renameKeySlot(keyIndex: number, name: string, newName: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("rename_key_slot", { keyIndex, name, newName });
+}
+
+export function setKeyBinding(keyIndex: number, binding: SlotBinding | null): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_binding", { keyIndex, binding });
+}
+
+export function selectKeySlot(keyIndex: number, name: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("select_key_slot", { keyIndex, name });
+}
+
+export function setKeyLabel(keyIndex: number, label: string | null): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_label", { keyIndex, label });
+}
+
+export function setKeyIcon(keyIndex: number, icon: string | null): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_icon", { keyIndex, icon });
+}
+
+export function copyKeyConfig(src: number): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("copy_key_config", { src });
+}
+
+export function pasteKeyConfig(dst: number): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("paste_key_config", { dst });
+}
+
+/** `slot` is a slot name, or null for every slot of every key. */
+export function applyColorToAll(slot: string | null, color: HsvColor): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("apply_color_to_all", { slot, ...color });
+}
+
 export function applyColors(): Promise<void> {
   if (!isTauri) return Promise.resolve();
   return tauriInvoke("apply_colors");
@@ -140,16 +729,153 @@ export function setKeycode(keyIndex: number, keycode: number): Promise<void> {
   return tauriInvoke("set_keycode", { keyIndex, keycode });
 }
 
+/** Bind the next chord pressed on any keyboard (Windows only). Result via `onKeycodeCaptured`. */
+export function startKeycodeCapture(keyIndex: number): Promise<void> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke("start_keycode_capture", { keyIndex });
+}
+
+export function cancelKeycodeCapture(): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("cancel_keycode_capture");
+}
+
 export function setKeyOverride(keyIndex: number, enabled: boolean): Promise<StateSnapshot> {
   if (!isTauri) return Promise.reject("Not in Tauri");
   return tauriInvoke<StateSnapshot>("set_key_override", { keyIndex, enabled });
 }
 
+export function setKeySource(keyIndex: number, source: KeySource): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_source", { keyIndex, source });
+}
+
+export function setKeyCooldown(keyIndex: number, cooldownMs: number): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_cooldown", { keyIndex, cooldownMs });
+}
+
+export function setKeySoundCooldown(keyIndex: number, cooldownMs: number, dim: boolean): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_sound_cooldown", { keyIndex, cooldownMs, dim });
+}
+
+/** `null` stops recalling a scene on press. */
+export function setKeyLightScene(keyIndex: number, scene: LightScene | null): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_light_scene", { keyIndex, scene });
+}
+
+export function setKeyPressMode(keyIndex: number, mode: PressMode): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_press_mode", { keyIndex, mode });
+}
+
+export function setKeySoundMode(keyIndex: number, mode: SoundMode): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_sound_mode", { keyIndex, mode });
+}
+
+/** Louder or quieter than the sound volume for this key, in dB (-30 to +12). */
+export function setKeySoundGain(keyIndex: number, gainDb: number): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_key_sound_gain", { keyIndex, gainDb });
+}
+
 export function restoreDefaults(): Promise<StateSnapshot> {
   if (!isTauri) return Promise.reject("Not in Tauri");
   return tauriInvoke<StateSnapshot>("restore_defaults");
 }
 
+// ── Palette & themes ────────────────────────────────────────────────
+
+export function addSwatch(color: HsvColor): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("add_swatch", { ...color });
+}
+
+export function removeSwatch(index: number): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("remove_swatch", { index });
+}
+
+export function saveTheme(name: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("save_theme", { name });
+}
+
+export function applyTheme(name: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("apply_theme", { name });
+}
+
+export function deleteTheme(name: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("delete_theme", { name });
+}
+
+/** Show a theme on the device for a few seconds without applying it. */
+export function previewTheme(name: string, seconds?: number): Promise<void> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke("preview_theme", { name, seconds: seconds ?? null });
+}
+
+export function endThemePreview(): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("end_theme_preview");
+}
+
+// ── Key groups ──────────────────────────────────────────────────────
+
+/** Create a group, or replace the keys of the one with the same name. */
+export function saveKeyGroup(name: string, keys: number[]): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("save_key_group", { name, keys });
+}
+
+export function deleteKeyGroup(name: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("delete_key_group", { name });
+}
+
+// ── Modes ───────────────────────────────────────────────────────────
+
+/** Save the keys' active slots as a mode (replacing one with the same name). */
+/** With a `base`, only the slots that differ from it are saved. */
+export function saveMode(name: string, soundsMuted: boolean | null, base: string | null): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("save_mode", { name, soundsMuted, base });
+}
+
+export function deleteMode(name: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("delete_mode", { name });
+}
+
+export function setMode(name: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_mode", { name });
+}
+
+/** Switch to the mode after the active one. */
+export function cycleMode(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("cycle_mode");
+}
+
+// ── LED rules ───────────────────────────────────────────────────────
+
+/** Create (empty id) or replace a rule; the rules are evaluated right away. */
+export function saveRule(rule: Rule): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("save_rule", { rule });
+}
+
+export function deleteRule(ruleId: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("delete_rule", { ruleId });
+}
+
 // ── Device info & control ───────────────────────────────────────────
 
 export function getDeviceInfo(): Promise<DeviceInfo> {
@@ -157,6 +883,16 @@ export function getDeviceInfo(): Promise<DeviceInfo> {
   return tauriInvoke<DeviceInfo>("get_device_info");
 }
 
+export function getDeviceHealth(): Promise<DeviceHealth | null> {
+  if (!isTauri) return Promise.resolve(null);
+  return tauriInvoke<DeviceHealth>("get_device_health");
+}
+
+export function getConnectionHistory(): Promise<ConnectionHistory | null> {
+  if (!isTauri) return Promise.resolve(null);
+  return tauriInvoke<ConnectionHistory>("get_connection_history");
+}
+
 export function deviceIndication(): Promise<void> {
   if (!isTauri) return Promise.resolve();
   return tauriInvoke("device_indication");
@@ -167,19 +903,44 @@ export function bootloaderJump(): Promise<void> {
   return tauriInvoke("bootloader_jump");
 }
 
-export function eepromReset(): Promise<void> {
-  if (!isTauri) return Promise.resolve();
-  return tauriInvoke("eeprom_reset");
+export type ResetKind = "Eeprom" | "Keymaps" | "Macros";
+
+/** What a reset wipes, read from the device; empty parts are kept. */
+export interface DeviceSnapshot {
+  layers: number[][];
+  macros: number[];
+  rgb: RgbMatrixState | null;
+  overrides: number[];
 }
 
-export function dynamicKeymapReset(): Promise<void> {
-  if (!isTauri) return Promise.resolve();
-  return tauriInvoke("dynamic_keymap_reset");
+export interface ResetPreview {
+  kind: ResetKind;
+  /** Pass to the reset to run it; single use. */
+  token: string;
+  expires_at_ms: number;
+  snapshot: DeviceSnapshot;
+  macro_count: number;
 }
 
-export function macroReset(): Promise<void> {
-  if (!isTauri) return Promise.resolve();
-  return tauriInvoke("macro_reset");
+export function previewReset(kind: ResetKind): Promise<ResetPreview | null> {
+  if (!isTauri) return Promise.resolve(null);
+  return tauriInvoke<ResetPreview>("preview_reset", { kind });
+}
+
+/** The resets take the token from `previewReset` and return the backup's path. */
+export function eepromReset(confirm: string): Promise<string> {
+  if (!isTauri) return Promise.resolve("");
+  return tauriInvoke<string>("eeprom_reset", { confirm });
+}
+
+export function dynamicKeymapReset(confirm: string): Promise<string> {
+  if (!isTauri) return Promise.resolve("");
+  return tauriInvoke<string>("dynamic_keymap_reset", { confirm });
+}
+
+export function macroReset(confirm: string): Promise<string> {
+  if (!isTauri) return Promise.resolve("");
+  return tauriInvoke<string>("macro_reset", { confirm });
 }
 
 // ── Per-key EEPROM persistence ──────────────────────────────────────
@@ -221,6 +982,32 @@ export function saveRgbMatrix(): Promise<void> {
   return tauriInvoke("save_rgb_matrix");
 }
 
+export function restoreKeymaps(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("restore_keymaps");
+}
+
+export function keepDeviceKeymaps(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("keep_device_keymaps");
+}
+
+export function restoreRgbMatrix(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("restore_rgb_matrix");
+}
+
+export function keepDeviceRgbMatrix(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("keep_device_rgb_matrix");
+}
+
+/** Turn every LED off, or restore exactly what was there. */
+export function toggleDnd(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("toggle_dnd");
+}
+
 // ── Soundboard ──────────────────────────────────────────────────────
 
 export function listAudioDevices(): Promise<AudioDeviceList> {
@@ -233,8 +1020,25 @@ export function setAudioInputDevice(name: string): Promise<void> {
   return tauriInvoke("set_audio_input_device", { name });
 }
 
-export function setAudioOutputDevice(name: string): Promise<void> {
-  if (!isTauri) return Promise.resolve();
+/** Answer the device choice prompt; `null` decides later. */
+export function chooseAudioDevice(name: string | null): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("choose_audio_device", { name });
+}
+
+/** `null` removes the second input. */
+export function setSecondInputDevice(name: string | null): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_second_input_device", { name });
+}
+
+export function setSecondInputVolume(volume: number): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_second_input_volume", { volume });
+}
+
+export function setAudioOutputDevice(name: string): Promise<void> {
+  if (!isTauri) return Promise.resolve();
   return tauriInvoke("set_audio_output_device", { name });
 }
 
@@ -248,6 +1052,57 @@ export function setMicVolume(volume: number): Promise<void> {
   return tauriInvoke("set_mic_volume", { volume });
 }
 
+/** Clamped to -20..=+30 dB by the backend. */
+export function setInputGain(gainDb: number): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_input_gain", { gainDb });
+}
+
+export function setProcessingStage(kind: StageKind, enabled: boolean): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_processing_stage", { kind, enabled });
+}
+
+export function setProcessingOrder(order: StageKind[]): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_processing_order", { order });
+}
+
+/** Errors if a target is outside -60..0 dB, the range is empty or the max gain outside 0..40 dB. */
+export function setAgc(agc: AgcSettings): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_agc", { agc });
+}
+
+/** Start or stop mic routing; errors if it can't start. */
+export function setSoundboard(on: boolean): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_soundboard", { on });
+}
+
+/** Fade out every sound playing, playlists included. */
+export function stopAllSounds(): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("stop_all_sounds");
+}
+
+/** Muting also stops what's playing. */
+export function setSoundsMuted(muted: boolean): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_sounds_muted", { muted });
+}
+
+/** `null` clears the hotkey; errors if it can't be registered. */
+export function setAppHotkey(action: AppHotkey, hotkey: string | null): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_app_hotkey", { action, hotkey });
+}
+
+export function setStopWord(enabled: boolean): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_stop_word", { enabled });
+}
+
 // ── Sound Library ───────────────────────────────────────────────────
 
 export function addToSoundLibrary(filePath: string, displayName: string): Promise<SoundEntry> {
@@ -255,18 +1110,103 @@ export function addToSoundLibrary(filePath: string, displayName: string): Promis
   return tauriInvoke<SoundEntry>("add_to_sound_library", { filePath, displayName });
 }
 
+/** What the trim editor keeps: a range, fades at its ends and a gain. */
+export interface Trim {
+  start_ms: number;
+  end_ms: number;
+  fade_in_ms: number;
+  fade_out_ms: number;
+  /** -24 to +12 dB. */
+  gain_db: number;
+}
+
 export function addToSoundLibraryTrimmed(
   filePath: string,
   displayName: string,
-  startMs: number,
-  endMs: number,
+  trim: Trim,
 ): Promise<SoundEntry> {
   if (!isTauri) return Promise.reject("Not in Tauri");
   return tauriInvoke<SoundEntry>("add_to_sound_library_trimmed", {
-    filePath, displayName, startMs, endMs,
+    filePath, displayName, trim,
   });
 }
 
+export function setPreviewRoute(route: SoundRoute): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_preview_route", { route });
+}
+
+export function setSoundFormat(format: SoundFormat): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_sound_format", { format });
+}
+
+/** Re-encode the library's WAVs as FLAC where that's lossless and smaller. */
+export function compressSoundLibrary(): Promise<CompressReport> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<CompressReport>("compress_sound_library");
+}
+
+/** Disk use of the sound library. */
+export interface LibraryStats {
+  library_bytes: number;
+  /** Entries no key plays, by id. */
+  unused: string[];
+  unused_bytes: number;
+  /** Entries whose file is gone, by id. */
+  missing: string[];
+  /** Files in the sounds folder no entry points at. */
+  orphans: string[];
+  orphan_bytes: number;
+  quota_bytes: number | null;
+}
+
+export interface CleanupReport {
+  removed_files: number;
+  /** Library entries removed, by id. */
+  removed_sounds: string[];
+  bytes_freed: number;
+}
+
+export function getLibraryStats(): Promise<LibraryStats> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<LibraryStats>("get_library_stats");
+}
+
+/** Read from the file's headers, not decoded. */
+export interface AudioInfo {
+  duration_ms: number;
+  sample_rate: number;
+  channels: number;
+  /** "PCM 16-bit", "FLAC 24-bit", "Vorbis", "MP3"… */
+  codec: string;
+  /** Average over the file. */
+  bitrate_kbps: number | null;
+}
+
+/** Info on the library's sounds by id; unreadable files are left out. */
+export function getLibraryAudioInfo(): Promise<Record<string, AudioInfo>> {
+  if (!isTauri) return Promise.resolve({});
+  return tauriInvoke<Record<string, AudioInfo>>("get_library_audio_info");
+}
+
+/** Delete orphaned files and, with `removeUnused`, the sounds no key plays. */
+export function cleanSoundLibrary(removeUnused: boolean): Promise<CleanupReport> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<CleanupReport>("clean_sound_library", { removeUnused });
+}
+
+/** Point a library sound at a new file; keys using it keep it. */
+export function relinkSound(soundId: string, newPath: string): Promise<SoundEntry> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<SoundEntry>("relink_sound", { soundId, newPath });
+}
+
+export function setLibraryQuota(mb: number | null): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_library_quota", { mb });
+}
+
 export function removeFromSoundLibrary(soundId: string): Promise<void> {
   if (!isTauri) return Promise.resolve();
   return tauriInvoke("remove_from_sound_library", { soundId });
@@ -277,6 +1217,33 @@ export function renameSound(soundId: string, newName: string): Promise<void> {
   return tauriInvoke("rename_sound", { soundId, newName });
 }
 
+export function setSoundMonitorOnly(soundId: string, monitorOnly: boolean): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_sound_monitor_only", { soundId, monitorOnly });
+}
+
+/** Create a playlist (empty id) or replace the one with its id. */
+export function savePlaylist(playlist: Playlist): Promise<Playlist> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<Playlist>("save_playlist", { playlist });
+}
+
+export function deletePlaylist(playlistId: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("delete_playlist", { playlistId });
+}
+
+/** Go on with the playing playlist's next sound. */
+export function skipPlaylist(): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("skip_playlist");
+}
+
+export function stopPlaylist(): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("stop_playlist");
+}
+
 export function setKeySound(keyIndex: number, soundId: string | null): Promise<void> {
   if (!isTauri) return Promise.resolve();
   return tauriInvoke("set_key_sound", { keyIndex, soundId });
@@ -294,24 +1261,550 @@ export function getAudioDuration(filePath: string): Promise<number> {
   return tauriInvoke<number>("get_audio_duration", { filePath });
 }
 
-export function previewTrim(sourcePath: string, startMs: number, endMs: number): Promise<void> {
+/** Plays exactly what `addToSoundLibraryTrimmed` would save. */
+export function previewTrim(sourcePath: string, trim: Trim): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("preview_trim", { sourcePath, trim });
+}
+
+/** Cut the trim (or library) preview playing. */
+export function stopPreview(): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("stop_preview");
+}
+
+// ── Settings ────────────────────────────────────────────────────────
+
+export function setInputGrab(enabled: boolean): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_input_grab", { enabled });
+}
+
+export function setReplayMethod(method: ReplayMethod): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_replay_method", { method });
+}
+
+export function setFadeDuration(ms: number): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_fade_duration", { ms });
+}
+
+export function setQuietHours(quietHours: QuietHours): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_quiet_hours", { quietHours });
+}
+
+/** Let key sounds play normally for `minutes` (0 ends the override). */
+export function overrideQuietHours(minutes: number): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("override_quiet_hours", { minutes });
+}
+
+export function setAccessibilityCues(enabled: boolean): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_accessibility_cues", { enabled });
+}
+
+export function setNowPlaying(enabled: boolean): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_now_playing", { enabled });
+}
+
+/** Errors if the client id isn't numeric. */
+export function setDiscordSettings(discord: DiscordSettings): Promise<void> {
   if (!isTauri) return Promise.resolve();
-  return tauriInvoke("preview_trim", { sourcePath, startMs, endMs });
+  return tauriInvoke("set_discord_settings", { discord });
+}
+
+export function setLighting(lighting: LightingSettings): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_lighting", { lighting });
+}
+
+/** Resolves to the bridge's app key; press the bridge's link button first. */
+export function pairHueBridge(bridge: string): Promise<string> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<string>("pair_hue_bridge", { bridge });
+}
+
+export function setAccentKeyColors(enabled: boolean): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_accent_key_colors", { enabled });
+}
+
+/** A backend translation (see i18n.rs). */
+export interface Language {
+  code: string;
+  /** The language's name in itself. */
+  name: string;
+}
+
+export function listLanguages(): Promise<Language[]> {
+  if (!isTauri) return Promise.resolve([]);
+  return tauriInvoke<Language[]>("list_languages");
+}
+
+export function setLanguage(language: string | null): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_language", { language });
+}
+
+export function skipOnboardingStep(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("skip_onboarding_step");
+}
+
+export function finishOnboarding(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("finish_onboarding");
+}
+
+export function resetOnboarding(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("reset_onboarding");
+}
+
+export function setWindowSettings(settings: WindowSettings): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_window_settings", { window: settings });
+}
+
+export function setHidTiming(timing: HidTiming): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_hid_timing", { timing });
+}
+
+export function setCooperative(enabled: boolean): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_cooperative", { enabled });
+}
+
+/** The PIN is 4 to 8 digits and is needed to unlock again. */
+export function lockConfig(pin: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("lock_config", { pin });
+}
+
+export function unlockConfig(pin: string): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("unlock_config", { pin });
+}
+
+export function getSystemTheme(): Promise<SystemTheme | null> {
+  if (!isTauri) return Promise.resolve(null);
+  return tauriInvoke<SystemTheme>("get_system_theme");
+}
+
+/** Port of the local WebSocket API (api.rs). */
+export const LOCAL_API_PORT = 18808;
+
+export function setLocalApi(enabled: boolean): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_local_api", { enabled });
+}
+
+/** Progress bar across the keys (0–100); key colors return on `clearProgress`. */
+export function setProgress(percent: number, color: HsvColor): Promise<void> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke("set_progress", { percent, color });
+}
+
+export function clearProgress(): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("clear_progress");
+}
+
+export type NotifyPattern = "Solid" | "Blink" | "Pulse";
+
+/** Flash a key over its own color; resolves to the id for `dismissNotification`. */
+export function notifyKey(
+  keyIndex: number,
+  color: HsvColor,
+  durationMs: number,
+  pattern: NotifyPattern = "Solid",
+  priority = 0,
+): Promise<number> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke("notify_key", { keyIndex, color, pattern, durationMs, priority });
+}
+
+export function dismissNotification(id: number): Promise<boolean> {
+  if (!isTauri) return Promise.resolve(false);
+  return tauriInvoke("dismiss_notification", { id });
+}
+
+/** The OS media session (see media.rs). */
+export interface NowPlaying {
+  playing: boolean;
+  title: string;
+  artist: string;
+  album: string;
+  /** The app playing it. */
+  player: string;
+}
+
+/** `null` when nothing is playing or paused, or tracking is off. */
+export function getNowPlaying(): Promise<NowPlaying | null> {
+  if (!isTauri) return Promise.resolve(null);
+  return tauriInvoke<NowPlaying | null>("get_now_playing");
+}
+
+export function setBrightnessCurve(curve: BrightnessCurve): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_brightness_curve", { curve });
+}
+
+export function setDedupWindow(ms: number): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("set_dedup_window", { ms });
+}
+
+// ── Keycodes ────────────────────────────────────────────────────────
+
+export function listKeycodes(): Promise<KeycodeInfo[]> {
+  if (!isTauri) return Promise.resolve([]);
+  return tauriInvoke<KeycodeInfo[]>("list_keycodes");
+}
+
+// ── Permissions ─────────────────────────────────────────────────────
+
+/** Returned by `get_app_info`. */
+export interface AppInfo {
+  version: string;
+  build_hash: string | null;
+  platform: string;
+  config_dir: string | null;
+  sounds_dir: string | null;
+  shortcut_backends: string[];
+  /** Keyboard layout keymaps are translated for; null on macOS or when unknown. */
+  keyboard_layout: string | null;
+  audio_backend: string;
+  soundboard_running: boolean;
+  safe_mode: boolean;
+}
+
+export function getAppInfo(): Promise<AppInfo> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<AppInfo>("get_app_info");
+}
+
+/** Reveal the config folder (state.json, debug.log) in the file manager. */
+/** Where the other PC fetches this setup from (pairing.rs). */
+export interface ConfigShare {
+  /** ip:port */
+  address: string;
+  code: string;
+  expires_at_ms: number;
+}
+
+export function startConfigShare(): Promise<ConfigShare> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<ConfigShare>("start_config_share");
+}
+
+export function stopConfigShare(): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("stop_config_share");
+}
+
+/** Replaces this setup with the shared one; the app restarts when it succeeds. */
+export function importSharedConfig(address: string, code: string): Promise<void> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke("import_shared_config", { address, code });
+}
+
+export function openConfigDir(): Promise<void> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke("open_config_dir");
+}
+
+/** Re-read device-definition.json from the config folder (custom QMK forks). */
+export function reloadDefinitions(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("reload_definitions");
+}
+
+/** Reveal the imported sounds folder in the file manager. */
+export function openSoundsDir(): Promise<void> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke("open_sounds_dir");
+}
+
+/** Uses of one key, sound or action. Times are Unix ms. */
+export interface UsageCounter {
+  count: number;
+  first_ms: number;
+  last_ms: number;
+}
+
+export interface UsageStats {
+  /** When counting started (first run or last reset). */
+  since_ms: number;
+  /** Presses per LED index. */
+  keys: UsageCounter[];
+  /** Plays per sound library id. */
+  sounds: Record<string, UsageCounter>;
+  /** Runs per action, e.g. `replay:Ctrl+Alt+KeyM`, `slot:3:Muted`, `dnd`. */
+  actions: Record<string, UsageCounter>;
+}
+
+export function getUsageStats(): Promise<UsageStats> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<UsageStats>("get_usage_stats");
+}
+
+export function resetUsageStats(): Promise<void> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke("reset_usage_stats");
+}
+
+/** Write the usage counts to `path` as CSV. */
+export function exportUsageCsv(path: string): Promise<void> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke("export_usage_csv", { path });
+}
+
+// ── Focus timer ─────────────────────────────────────────────────────
+
+/** Start a focus session (`minutes`, else the configured length). */
+export function startFocus(minutes: number | null): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("start_focus", { minutes });
+}
+
+/** Stop the running session without counting it. */
+export function stopFocus(): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("stop_focus");
+}
+
+export function setFocusSettings(focus: FocusSettings): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_focus_settings", { focus });
+}
+
+export function getFocusSummary(): Promise<FocusSummary> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<FocusSummary>("get_focus_summary");
+}
+
+// ── Game integration ────────────────────────────────────────────────
+
+export function setGameSettings(game: GameSettings): Promise<StateSnapshot> {
+  if (!isTauri) return Promise.reject("Not in Tauri");
+  return tauriInvoke<StateSnapshot>("set_game_settings", { game });
+}
+
+/** Soundboard pipeline health (`get_audio_metrics`, `audio-metrics` event). */
+export interface AudioMetrics {
+  channels: number;
+  /** Configured rate of the input device. */
+  sample_rate: number;
+  /** Ring buffer occupancy, 0..1. */
+  mic_buffer_fill: number;
+  sound_buffer_fill: number;
+  dropped_mic_samples: number;
+  dropped_sound_samples: number;
+  /** Times the output ran out of mic samples. */
+  underruns: number;
+  /** Measured frames/s since the previous read. */
+  input_rate_hz: number;
+  output_rate_hz: number;
+  stream_errors: number;
+  last_stream_error: string | null;
+  /** Loudest mic sample since the previous read, after the input gain (1 = full scale). */
+  input_peak: number;
+  clipped_samples: number;
+  /** Some clipped since the previous read. */
+  clipping: boolean;
+}
+
+/** `null` while the pipeline is stopped. */
+export function getAudioMetrics(): Promise<AudioMetrics | null> {
+  if (!isTauri) return Promise.resolve(null);
+  return tauriInvoke<AudioMetrics | null>("get_audio_metrics");
+}
+
+/** Errors returned by commands since launch, oldest first. */
+export function getRecentErrors(): Promise<HubError[]> {
+  if (!isTauri) return Promise.resolve([]);
+  return tauriInvoke<HubError[]>("get_recent_errors");
+}
+
+export function checkPermissions(): Promise<PermissionCheck[]> {
+  if (!isTauri) return Promise.resolve([]);
+  return tauriInvoke<PermissionCheck[]>("check_permissions");
+}
+
+export function openSettingsUrl(url: string): Promise<void> {
+  if (!isTauri) return Promise.resolve();
+  return tauriInvoke("open_settings_url", { url });
 }
 
 // ── Events ──────────────────────────────────────────────────────────
 
 type UnlistenFn = () => void;
 
-export function onSlotToggled(
-  callback: (newSlot: string) => void,
+/** Physical key press/release from any shortcut backend. */
+export function onKeyEvent(
+  callback: (event: KeyEvent) => void,
 ): Promise<UnlistenFn> {
   if (!isTauri) {
-    void callback; // unused in browser
+    void callback;
     return Promise.resolve(() => {});
   }
   return import("@tauri-apps/api/event").then(({ listen }) =>
-    listen<string>("slot-toggled", (event) => {
+    listen<KeyEvent>("key-event", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+export function onKeycodeCaptured(
+  callback: (result: KeycodeCaptured) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<KeycodeCaptured>("keycode-captured", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+/** Text for screen readers ("Key 3: Muted"), sent when accessibility cues are on. */
+export function onAnnounce(
+  callback: (text: string) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<string>("announce", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+/** The media session changed track or play state (`null`: nothing playing). */
+export function onNowPlaying(
+  callback: (nowPlaying: NowPlaying | null) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<NowPlaying | null>("now-playing", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+/** A heartbeat sample (every 30 s). */
+export function onDeviceHealth(
+  callback: (sample: HealthSample) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<HealthSample>("device-health", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+/** The device restarted while connected (its uptime went backwards). */
+export function onDeviceRebooted(
+  callback: (reboot: DeviceReboot) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<DeviceReboot>("device-rebooted", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+/** Self-test results after each connect. */
+export function onSyncReport(
+  callback: (report: SyncReport) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<SyncReport>("sync-report", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+/** The stop word was heard and every sound was stopped. */
+export function onStopWord(
+  callback: () => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen("stop-word", () => {
+      callback();
+    }),
+  );
+}
+
+/** A sound's file was gone when it was played. */
+export function onSoundMissing(
+  callback: (entry: SoundEntry) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<SoundEntry>("sound-missing", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+/** The device stopped acking and the backend is reconnecting it. */
+export function onDeviceUnhealthy(
+  callback: () => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen("device-unhealthy", () => {
+      callback();
+    }),
+  );
+}
+
+/** Pipeline metrics, every couple of seconds while the soundboard runs. */
+export function onAudioMetrics(
+  callback: (metrics: AudioMetrics) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<AudioMetrics>("audio-metrics", (event) => {
       callback(event.payload);
     }),
   );
@@ -330,3 +1823,48 @@ export function onStateUpdated(
     }),
   );
 }
+
+/** The desktop switched dark/light mode or accent color. */
+/** A theme preview started (its name) or ended (`null`). */
+export function onThemePreview(
+  callback: (name: string | null) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<string | null>("theme-preview", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+export function onSystemTheme(
+  callback: (theme: SystemTheme) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<SystemTheme>("system-theme", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
+
+/** A focus session ran to the end; carries the updated summary. */
+export function onFocusCompleted(
+  callback: (summary: FocusSummary) => void,
+): Promise<UnlistenFn> {
+  if (!isTauri) {
+    void callback;
+    return Promise.resolve(() => {});
+  }
+  return import("@tauri-apps/api/event").then(({ listen }) =>
+    listen<FocusSummary>("focus-completed", (event) => {
+      callback(event.payload);
+    }),
+  );
+}
@@ -1,3 +1,11 @@
 fn main() {
+    // Short commit hash for get_app_info; left unset outside a git checkout
+    if let Ok(out) = std::process::Command::new("git").args(["rev-parse", "--short", "HEAD"]).output() {
+        if out.status.success() {
+            println!("cargo:rustc-env=DECK8_BUILD_HASH={}", String::from_utf8_lossy(&out.stdout).trim());
+        }
+    }
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs/heads");
     tauri_build::build();
 }
@@ -0,0 +1,150 @@
+# English: the fallback for every other language, so every message lives here.
+language-name = English
+
+## Tray menu
+tray-show = Show
+tray-keys = Keys
+tray-modes = Modes
+tray-dnd = Do Not Disturb
+tray-stop-sounds = Stop Sounds
+tray-mute-soundboard = Mute Soundboard
+tray-quit = Quit
+
+## Quit confirmation (sounds still playing)
+quit-confirm-title = Quit Deck-8 Hub?
+quit-confirm-message = A sound is still playing. Quitting cuts it off and stops the soundboard.
+quit-confirm-quit = Quit
+quit-confirm-cancel = Keep running
+
+## Keys
+key-name = Key { $n }
+# Screen-reader announcement on a slot change
+slot-announcement = { $key }: { $slot }
+# Screen-reader announcement when a key press runs an action
+action-announcement = { $key }: { $action }
+action-toggle-recording = toggle recording
+action-toggle-stream = toggle stream
+action-scene = switch to scene { $scene }
+action-toggle-mute = toggle mute
+action-play-pause = play or pause
+action-toggle-focus = toggle focus session
+action-toggle-soundboard = toggle soundboard
+action-open-meeting = open meeting link
+action-next-mode = next mode
+action-light-scene = light scene { $scene }
+
+## Toasts
+toast-pipeline-stopped = Soundboard audio stopped
+toast-pipeline-stopped-body = The microphone stopped sending audio.
+toast-pipeline-stopped-because = The microphone stopped sending audio ({ $error }).
+toast-restart-pipeline = Restart pipeline
+toast-open-app = Open app
+
+## Errors
+error-not-connected = Not connected
+error-device-busy = Another app (VIA?) is using the Deck-8
+error-locked = Settings are locked; unlock them with the PIN to make changes
+error-key-index = key_index out of range
+error-no-translation = No translation for "{ $language }"
+error-no-answer = No answer after { $seconds } s
+error-hotkey-taken = Couldn't register "{ $hotkey }" — it may be invalid or in use
+error-capture-unsupported = Capturing keys from other keyboards is only supported on Windows
+error-dedup-too-long = Dedup window must be at most 2000 ms
+
+# Slots, themes, groups and modes
+error-no-slot = No slot named "{ $slot }"
+error-no-key-slot = No key has a slot named "{ $slot }"
+error-slot-exists = Slot "{ $slot }" already exists
+error-too-many-slots = A key can have at most { $max } slots
+error-last-slot = A key needs at least one slot
+error-slot-bound = Slot "{ $slot }" is used by the key's binding
+error-slot-name-empty = Slot name cannot be empty
+error-slot-name-too-long = Slot name must be at most 32 characters
+error-label-too-long = Label must be at most { $max } characters
+error-icon-too-long = Icon must be at most { $max } characters
+error-poll-too-short = Poll interval must be at least 100 ms
+error-mixer-channel-empty = Mixer channel is empty
+error-voicemeeter-channel = VoiceMeeter channel must look like Strip[0] or Bus[0], not "{ $channel }"
+error-calendar-address = Calendar address must be an ICS link (https:// or webcal://)
+error-nothing-copied = Nothing copied yet
+error-palette-full = The palette holds at most { $max } colors
+error-swatch-index = Swatch index out of range
+error-theme-name-empty = Theme name cannot be empty
+error-too-many-themes = At most { $max } themes can be saved
+error-no-theme = No theme named "{ $name }"
+error-preview-length = A preview lasts 1 to { $max } seconds
+error-dnd-on = Do not disturb is on
+error-progress-showing = A progress bar is showing on the keys
+error-progress-percent = percent must be between 0 and 100, got { $percent }
+error-notify-duration = duration_ms must be between 1 and { $max }
+error-group-name-empty = Group name cannot be empty
+error-group-too-small = A group needs at least two keys
+error-too-many-groups = At most { $max } groups can be saved
+error-no-group = No group named "{ $name }"
+error-mode-name-empty = Mode name cannot be empty
+error-no-mode = No mode named "{ $name }"
+error-no-modes = No modes saved
+error-too-many-modes = At most { $max } modes can be saved
+error-mode-loop = "{ $name }" builds on itself through "{ $base }"
+error-too-many-rules = At most { $max } rules can be saved
+error-no-rule = Rule not found
+error-rule-name-empty = Rule name is empty
+error-rule-no-days = Pick at least one day
+error-rule-schedule = The schedule's start and end must be different times of day
+error-rule-dim = Dim must be 1–100%
+
+# Device
+error-no-keymap-backup = No keymaps to restore
+error-reset-expired = The confirmation expired; review the reset again
+error-reset-unconfirmed = Review what the reset will erase and confirm it first
+error-no-saved-rgb = No saved RGB settings
+error-gamma = Gamma must be between 0.2 and 5.0
+error-brightness-range = Minimum brightness must be below the maximum
+
+# Lights
+error-scene-empty = Scene is empty
+error-no-light = No light named "{ $name }"
+error-light-name-empty = Every light needs a name
+error-light-name-taken = Two lights are called "{ $name }"
+error-hue-unpaired = Pair "{ $name }" with its Hue bridge first
+error-wled-address = "{ $name }" needs the WLED address
+
+# Audio and sounds
+error-soundboard-unset = Set a mic and a virtual cable output first
+error-no-device-choice = No device to choose
+error-device-not-a-choice = That device isn't one of the choices
+error-device-is-mic = That device is already the microphone
+error-gain-not-number = gain_db must be a number
+error-input-gain-not-number = Input gain must be a number
+error-chain-incomplete = The chain must list every stage once
+error-agc-target = AGC targets must be between -60 and 0 dB
+error-agc-range = AGC target range is empty
+error-agc-max-gain = AGC max gain must be between 0 and 40 dB
+error-fade-too-long = Fade duration must be at most 2000 ms
+error-quota-full = The sound won't fit in the library's { $quota } MB quota; clean it up or raise the quota
+error-no-sound = Sound not found in library
+error-trim-empty = Trimmed audio is empty
+error-playlist-name-empty = Playlist name is empty
+error-playlist-sound = Sound { $id } is not in the library
+error-no-playlist = Playlist not found
+error-quiet-hours-time = Quiet hours must be between 00:00 and 23:59
+error-quiet-hours-volume = Quiet hours volume must be between 0 and 1
+error-override-too-long = Override can last at most 24 hours
+
+# Integrations
+error-discord-client-id = The Discord client id is the application's numeric id
+error-focus-length = A focus session must be 1–180 minutes
+error-game-port = Pick a port from 1024 up, other than { $api_port }
+error-too-many-game-rules = At most { $max } game rules
+error-game-rule-name-empty = Game rule name is empty
+error-game-rule-path = "{ $path }" isn't a path like player.state.health
+
+# Lock
+error-pin-format = The PIN must be 4 to 8 digits
+error-pin-wrong = Wrong PIN
+error-pin-backoff = Too many wrong PINs, try again in { $seconds } s
+
+# Local API
+error-api-bad-request = Bad request: { $error }
+error-api-bad-params = Bad params: { $error }
+error-api-unknown-method = Unknown method "{ $method }"
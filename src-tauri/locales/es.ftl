@@ -0,0 +1,147 @@
+language-name = Español
+
+## Tray menu
+tray-show = Mostrar
+tray-keys = Teclas
+tray-modes = Modos
+tray-dnd = No molestar
+tray-stop-sounds = Detener sonidos
+tray-mute-soundboard = Silenciar soundboard
+tray-quit = Salir
+
+## Quit confirmation (sounds still playing)
+quit-confirm-title = ¿Salir de Deck-8 Hub?
+quit-confirm-message = Todavía se está reproduciendo un sonido. Al salir se corta y se detiene la soundboard.
+quit-confirm-quit = Salir
+quit-confirm-cancel = Seguir abierto
+
+## Keys
+key-name = Tecla { $n }
+slot-announcement = { $key }: { $slot }
+action-announcement = { $key }: { $action }
+action-toggle-recording = alternar grabación
+action-toggle-stream = alternar transmisión
+action-scene = cambiar a la escena { $scene }
+action-toggle-mute = alternar silencio
+action-play-pause = reproducir o pausar
+action-toggle-focus = alternar sesión de enfoque
+action-toggle-soundboard = alternar soundboard
+action-open-meeting = abrir enlace de la reunión
+action-next-mode = siguiente modo
+action-light-scene = escena de luces { $scene }
+
+## Toasts
+toast-pipeline-stopped = El audio de la soundboard se detuvo
+toast-pipeline-stopped-body = El micrófono dejó de enviar audio.
+toast-pipeline-stopped-because = El micrófono dejó de enviar audio ({ $error }).
+toast-restart-pipeline = Reiniciar pipeline
+toast-open-app = Abrir la app
+
+## Errors
+error-not-connected = No conectado
+error-device-busy = Otra aplicación (¿VIA?) está usando el Deck-8
+error-locked = La configuración está bloqueada; desbloquéala con el PIN para hacer cambios
+error-key-index = Índice de tecla fuera de rango
+error-no-translation = No hay traducción para "{ $language }"
+error-no-answer = Sin respuesta después de { $seconds } s
+error-hotkey-taken = No se pudo registrar "{ $hotkey }": puede ser inválido o estar en uso
+error-capture-unsupported = Capturar teclas de otros teclados solo funciona en Windows
+error-dedup-too-long = La ventana de deduplicación puede durar como mucho 2000 ms
+
+# Slots, themes, groups and modes
+error-no-slot = No hay ningún slot llamado "{ $slot }"
+error-no-key-slot = Ninguna tecla tiene un slot llamado "{ $slot }"
+error-slot-exists = El slot "{ $slot }" ya existe
+error-too-many-slots = Una tecla puede tener como mucho { $max } slots
+error-last-slot = Una tecla necesita al menos un slot
+error-slot-bound = El slot "{ $slot }" lo usa la vinculación de la tecla
+error-slot-name-empty = El nombre del slot no puede estar vacío
+error-slot-name-too-long = El nombre del slot puede tener como mucho 32 caracteres
+error-label-too-long = La etiqueta puede tener como mucho { $max } caracteres
+error-icon-too-long = El icono puede tener como mucho { $max } caracteres
+error-poll-too-short = El intervalo de consulta debe ser de al menos 100 ms
+error-mixer-channel-empty = El canal del mezclador está vacío
+error-voicemeeter-channel = El canal de VoiceMeeter debe tener la forma Strip[0] o Bus[0], no "{ $channel }"
+error-calendar-address = La dirección del calendario debe ser un enlace ICS (https:// o webcal://)
+error-nothing-copied = Todavía no copiaste nada
+error-palette-full = La paleta admite como mucho { $max } colores
+error-swatch-index = Índice de color fuera de rango
+error-theme-name-empty = El nombre del tema no puede estar vacío
+error-too-many-themes = Se pueden guardar como mucho { $max } temas
+error-no-theme = No hay ningún tema llamado "{ $name }"
+error-preview-length = Una vista previa dura entre 1 y { $max } segundos
+error-dnd-on = No molestar está activado
+error-progress-showing = Hay una barra de progreso en las teclas
+error-progress-percent = percent debe estar entre 0 y 100, se recibió { $percent }
+error-notify-duration = duration_ms debe estar entre 1 y { $max }
+error-group-name-empty = El nombre del grupo no puede estar vacío
+error-group-too-small = Un grupo necesita al menos dos teclas
+error-too-many-groups = Se pueden guardar como mucho { $max } grupos
+error-no-group = No hay ningún grupo llamado "{ $name }"
+error-mode-name-empty = El nombre del modo no puede estar vacío
+error-no-mode = No hay ningún modo llamado "{ $name }"
+error-no-modes = No hay modos guardados
+error-too-many-modes = Se pueden guardar como mucho { $max } modos
+error-mode-loop = "{ $name }" se basa en sí mismo a través de "{ $base }"
+error-too-many-rules = Se pueden guardar como mucho { $max } reglas
+error-no-rule = No se encontró la regla
+error-rule-name-empty = El nombre de la regla está vacío
+error-rule-no-days = Elige al menos un día
+error-rule-schedule = El inicio y el fin del horario deben ser horas distintas
+error-rule-dim = La atenuación debe estar entre 1 y 100%
+
+# Device
+error-no-keymap-backup = No hay keymaps para restaurar
+error-reset-expired = La confirmación venció; revisa el reinicio otra vez
+error-reset-unconfirmed = Revisa lo que el reinicio va a borrar y confírmalo primero
+error-no-saved-rgb = No hay ajustes RGB guardados
+error-gamma = La gamma debe estar entre 0,2 y 5,0
+error-brightness-range = El brillo mínimo debe ser menor que el máximo
+
+# Lights
+error-scene-empty = La escena está vacía
+error-no-light = No hay ninguna luz llamada "{ $name }"
+error-light-name-empty = Cada luz necesita un nombre
+error-light-name-taken = Hay dos luces llamadas "{ $name }"
+error-hue-unpaired = Primero vincula "{ $name }" con su bridge de Hue
+error-wled-address = "{ $name }" necesita la dirección de WLED
+
+# Audio and sounds
+error-soundboard-unset = Primero elige un micrófono y una salida de cable virtual
+error-no-device-choice = No hay ningún dispositivo para elegir
+error-device-not-a-choice = Ese dispositivo no está entre las opciones
+error-device-is-mic = Ese dispositivo ya es el micrófono
+error-gain-not-number = gain_db debe ser un número
+error-input-gain-not-number = La ganancia de entrada debe ser un número
+error-chain-incomplete = La cadena debe incluir cada etapa una vez
+error-agc-target = Los objetivos del AGC deben estar entre -60 y 0 dB
+error-agc-range = El rango objetivo del AGC está vacío
+error-agc-max-gain = La ganancia máxima del AGC debe estar entre 0 y 40 dB
+error-fade-too-long = El fundido puede durar como mucho 2000 ms
+error-quota-full = El sonido no entra en la cuota de { $quota } MB de la biblioteca; límpiala o aumenta la cuota
+error-no-sound = No se encontró el sonido en la biblioteca
+error-trim-empty = El audio recortado está vacío
+error-playlist-name-empty = El nombre de la playlist está vacío
+error-playlist-sound = El sonido { $id } no está en la biblioteca
+error-no-playlist = No se encontró la playlist
+error-quiet-hours-time = Las horas de silencio deben estar entre 00:00 y 23:59
+error-quiet-hours-volume = El volumen de las horas de silencio debe estar entre 0 y 1
+error-override-too-long = La excepción puede durar como mucho 24 horas
+
+# Integrations
+error-discord-client-id = El client id de Discord es el id numérico de la aplicación
+error-focus-length = Una sesión de foco debe durar entre 1 y 180 minutos
+error-game-port = Elige un puerto desde 1024, distinto de { $api_port }
+error-too-many-game-rules = Como mucho { $max } reglas de juego
+error-game-rule-name-empty = El nombre de la regla de juego está vacío
+error-game-rule-path = "{ $path }" no es una ruta como player.state.health
+
+# Lock
+error-pin-format = El PIN debe tener entre 4 y 8 dígitos
+error-pin-wrong = PIN incorrecto
+error-pin-backoff = Demasiados PIN incorrectos, prueba de nuevo en { $seconds } s
+
+# Local API
+error-api-bad-request = Solicitud inválida: { $error }
+error-api-bad-params = Parámetros inválidos: { $error }
+error-api-unknown-method = Método desconocido "{ $method }"
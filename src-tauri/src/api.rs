@@ -0,0 +1,198 @@
+// Local API: a WebSocket on 127.0.0.1 so scripts and other tools can drive
+// the pad. Off until `settings.local_api` is turned on; only loopback
+// connections are possible. Handshakes carrying an `Origin` are refused:
+// browsers always send one, so a web page can't reach the pad through it,
+// while scripts and tools don't.
+//
+// Messages are JSON-RPC style, one request per text frame:
+//   → {"id": 1, "method": "set_progress", "params": {"percent": 40, "color": {"h": 85, "s": 255, "v": 120}}}
+//   ← {"id": 1, "result": null}
+//   ← {"id": 1, "error": {"code": "NOT_CONNECTED", "message": "Not connected"}}
+// Error codes are `HubError`'s, like the commands'.
+//
+// Methods: set_progress {percent, color}, clear_progress,
+// notify {key, color, pattern?, duration_ms, priority?} → id, dismiss {id} → bool.
+
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tungstenite::handshake::server::{ErrorResponse, Request as Handshake, Response as HandshakeResponse};
+use tungstenite::Message;
+
+use crate::error::HubError;
+use crate::notify::Pattern;
+use crate::protocol::HsvColor;
+use crate::state::SharedState;
+
+pub const PORT: u16 = 18_808;
+/// How often the listener checks for connections and the on/off switch.
+const POLL: Duration = Duration::from_millis(200);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Start the listener thread. The port is only bound while enabled.
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut listener: Option<TcpListener> = None;
+        loop {
+            std::thread::sleep(POLL);
+            if !ENABLED.load(Ordering::SeqCst) {
+                if listener.take().is_some() {
+                    info!("[api] Stopped listening");
+                }
+                continue;
+            }
+            if listener.is_none() {
+                match bind() {
+                    Ok(l) => {
+                        info!("[api] Listening on ws://127.0.0.1:{PORT}");
+                        listener = Some(l);
+                    }
+                    Err(e) => {
+                        warn!("[api] Can't listen on port {PORT}: {e}");
+                        // Don't retry (and log) every poll
+                        ENABLED.store(false, Ordering::SeqCst);
+                        continue;
+                    }
+                }
+            }
+            let Some(ref l) = listener else { continue };
+            while let Ok((stream, _)) = l.accept() {
+                let app = app.clone();
+                std::thread::spawn(move || serve(&app, stream));
+            }
+        }
+    });
+}
+
+fn bind() -> std::io::Result<TcpListener> {
+    let listener = TcpListener::bind(("127.0.0.1", PORT))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+fn serve(app: &AppHandle, stream: TcpStream) {
+    if stream.set_nonblocking(false).is_err() {
+        return;
+    }
+    let mut ws = match tungstenite::accept_hdr(stream, refuse_browsers) {
+        Ok(ws) => ws,
+        Err(e) => {
+            warn!("[api] Handshake failed: {e}");
+            return;
+        }
+    };
+    loop {
+        let text = match ws.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) | Err(_) => return,
+            Ok(_) => continue,
+        };
+        // Turned off meanwhile
+        if !ENABLED.load(Ordering::SeqCst) {
+            let _ = ws.close(None);
+            return;
+        }
+        let reply = respond(app, &text);
+        if ws.send(Message::text(reply.to_string())).is_err() {
+            return;
+        }
+    }
+}
+
+/// Handshake check: no web page (any request with an `Origin`) gets in.
+pub fn refuse_browsers(request: &Handshake, response: HandshakeResponse) -> Result<HandshakeResponse, ErrorResponse> {
+    match request.headers().get("origin") {
+        None => Ok(response),
+        Some(origin) => {
+            warn!("[api] Refused a connection from {origin:?}");
+            let mut refusal = ErrorResponse::new(Some("Browser connections are not allowed".into()));
+            *refusal.status_mut() = tungstenite::http::StatusCode::FORBIDDEN;
+            Err(refusal)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+fn respond(app: &AppHandle, text: &str) -> Value {
+    let request: Request = match serde_json::from_str(text) {
+        Ok(r) => r,
+        Err(e) => return error_reply(Value::Null, HubError::InvalidInput(crate::i18n::t_with("error-api-bad-request", &[("error", &e)]))),
+    };
+    match call(app, &request.method, request.params) {
+        Ok(result) => json!({ "id": request.id, "result": result }),
+        Err(e) => {
+            warn!("[api] {}: {}", request.method, e);
+            error_reply(request.id, e)
+        }
+    }
+}
+
+fn error_reply(id: Value, e: HubError) -> Value {
+    json!({ "id": id, "error": { "code": e.code(), "message": e.to_string() } })
+}
+
+fn params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, HubError> {
+    serde_json::from_value(params).map_err(|e| HubError::InvalidInput(crate::i18n::t_with("error-api-bad-params", &[("error", &e)])))
+}
+
+#[derive(Deserialize)]
+struct ProgressParams {
+    percent: f32,
+    color: HsvColor,
+}
+
+#[derive(Deserialize)]
+struct NotifyParams {
+    key: usize,
+    color: HsvColor,
+    #[serde(default)]
+    pattern: Pattern,
+    duration_ms: u64,
+    #[serde(default)]
+    priority: u8,
+}
+
+#[derive(Deserialize)]
+struct DismissParams {
+    id: u64,
+}
+
+fn call(app: &AppHandle, method: &str, args: Value) -> Result<Value, HubError> {
+    let state = app.state::<SharedState>();
+    match method {
+        "set_progress" => {
+            let p: ProgressParams = params(args)?;
+            crate::progress::show(&state.lock().unwrap(), p.percent, p.color)?;
+        }
+        "clear_progress" => crate::progress::clear(&state.lock().unwrap()),
+        "notify" => {
+            let p: NotifyParams = params(args)?;
+            let id = crate::notify::notify(app, p.key, p.color, p.pattern, p.duration_ms, p.priority)?;
+            return Ok(json!(id));
+        }
+        "dismiss" => {
+            let p: DismissParams = params(args)?;
+            return Ok(json!(crate::notify::dismiss(p.id)));
+        }
+        _ => return Err(crate::i18n::t_with("error-api-unknown-method", &[("method", &method)]).into()),
+    }
+    Ok(Value::Null)
+}
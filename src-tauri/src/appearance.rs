@@ -0,0 +1,239 @@
+// The desktop's dark/light mode and accent color.
+//
+// A background thread reads both every `POLL` and emits `system-theme` when
+// they change, so the frontend can follow the desktop. With
+// `AppSettings.accent_key_colors` on, keys whose first slot still has the
+// default color take the accent instead (at the default's brightness), and
+// follow it when it changes.
+//
+// - Windows: HKCU DWM AccentColor and Personalize AppsUseLightTheme.
+// - macOS: AppleAccentColor / AppleInterfaceStyle from `defaults`.
+// - Linux: GNOME's accent-color / color-scheme from `gsettings`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::info;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::protocol::HsvColor;
+use crate::state::{AppState, KeyConfig, SharedState};
+
+const POLL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct SystemTheme {
+    pub dark: bool,
+    /// `None` where the desktop has no accent color (or it can't be read).
+    pub accent: Option<HsvColor>,
+}
+
+static CURRENT: Mutex<Option<SystemTheme>> = Mutex::new(None);
+
+/// The last theme read (read now if the thread hasn't yet).
+pub fn current() -> SystemTheme {
+    *CURRENT.lock().unwrap().get_or_insert_with(platform::read)
+}
+
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    std::thread::spawn(move || loop {
+        let theme = platform::read();
+        let changed = CURRENT.lock().unwrap().replace(theme) != Some(theme);
+        if changed {
+            info!("[appearance] dark={} accent={:?}", theme.dark, theme.accent);
+            let _ = app.emit("system-theme", theme);
+            follow_accent(&app);
+        }
+        std::thread::sleep(POLL);
+    });
+}
+
+/// Re-sync key colors with the accent and push them if any changed.
+pub fn follow_accent<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<SharedState>();
+    let mut st = state.lock().unwrap();
+    if !sync_accent(&mut st, current().accent) {
+        return;
+    }
+    if let Some(dev) = st.device.as_ref() {
+        crate::apply_all_to_device(dev, &st.keys);
+    }
+    crate::persist_state(&st);
+    let _ = app.emit("state-updated", st.snapshot());
+}
+
+/// The first slot color of a default key.
+pub fn stock_color() -> HsvColor {
+    KeyConfig::default().slots[0].color
+}
+
+/// The key color for an accent: its hue and saturation at the stock brightness.
+pub fn accent_key_color(accent: HsvColor) -> HsvColor {
+    HsvColor { v: stock_color().v, ..accent }
+}
+
+/// Move keys still showing the default first color to the accent's (or back
+/// to the stock color when following is off). True if anything changed.
+pub fn sync_accent(st: &mut AppState, accent: Option<HsvColor>) -> bool {
+    let previous = st.settings.applied_accent;
+    let target = match (st.settings.accent_key_colors, accent) {
+        (true, Some(accent)) => Some(accent_key_color(accent)),
+        (true, None) => return false,
+        (false, _) => None,
+    };
+    if target == previous {
+        return false;
+    }
+    let from = previous.unwrap_or_else(stock_color);
+    let to = target.unwrap_or_else(stock_color);
+    for key in st.keys.iter_mut() {
+        if key.slots[0].color == from {
+            key.slots[0].color = to;
+        }
+    }
+    st.settings.applied_accent = target;
+    true
+}
+
+/// 0–255 RGB to QMK-scale HSV.
+pub fn rgb_to_hsv(r: u8, g: u8, b: u8) -> HsvColor {
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    if delta == 0 {
+        return HsvColor { h: 0, s: 0, v: max as u8 };
+    }
+    let sector = if max == r {
+        (g - b) * 43 / delta
+    } else if max == g {
+        85 + (b - r) * 43 / delta
+    } else {
+        171 + (r - g) * 43 / delta
+    };
+    HsvColor { h: sector.rem_euclid(256) as u8, s: (delta * 255 / max) as u8, v: max as u8 }
+}
+
+/// Named accents (macOS, GNOME) as RGB.
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+pub fn named_accent(name: &str) -> Option<HsvColor> {
+    let (r, g, b) = match name {
+        "blue" => (0x35, 0x84, 0xe4),
+        "teal" => (0x21, 0x90, 0xa4),
+        "green" => (0x3a, 0x94, 0x4a),
+        "yellow" => (0xc8, 0x88, 0x00),
+        "orange" => (0xed, 0x5b, 0x00),
+        "red" => (0xe6, 0x2d, 0x42),
+        "pink" => (0xd5, 0x61, 0x99),
+        "purple" => (0x91, 0x41, 0xac),
+        "slate" | "graphite" => (0x6f, 0x83, 0x96),
+        _ => return None,
+    };
+    Some(rgb_to_hsv(r, g, b))
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::ffi::c_void;
+
+    use super::{rgb_to_hsv, SystemTheme};
+
+    const HKEY_CURRENT_USER: isize = 0x8000_0001u32 as i32 as isize;
+    const RRF_RT_REG_DWORD: u32 = 0x10;
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn RegGetValueW(
+            key: isize,
+            sub_key: *const u16,
+            value: *const u16,
+            flags: u32,
+            kind: *mut u32,
+            data: *mut c_void,
+            size: *mut u32,
+        ) -> i32;
+    }
+
+    fn dword(sub_key: &str, value: &str) -> Option<u32> {
+        let sub_key: Vec<u16> = sub_key.encode_utf16().chain([0]).collect();
+        let value: Vec<u16> = value.encode_utf16().chain([0]).collect();
+        let mut data = 0u32;
+        let mut size = 4u32;
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                sub_key.as_ptr(),
+                value.as_ptr(),
+                RRF_RT_REG_DWORD,
+                std::ptr::null_mut(),
+                &mut data as *mut u32 as *mut c_void,
+                &mut size,
+            )
+        };
+        (status == 0).then_some(data)
+    }
+
+    pub fn read() -> SystemTheme {
+        let light = dword(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize", "AppsUseLightTheme");
+        // 0xAABBGGRR
+        let accent = dword(r"Software\Microsoft\Windows\DWM", "AccentColor")
+            .map(|c| rgb_to_hsv(c as u8, (c >> 8) as u8, (c >> 16) as u8));
+        SystemTheme { dark: light == Some(0), accent }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{named_accent, SystemTheme};
+
+    fn default(key: &str) -> Option<String> {
+        let out = std::process::Command::new("defaults").args(["read", "-g", key]).output().ok()?;
+        out.status.success().then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+    }
+
+    pub fn read() -> SystemTheme {
+        let dark = default("AppleInterfaceStyle").is_some_and(|s| s == "Dark");
+        // Unset means the default blue
+        let name = match default("AppleAccentColor").as_deref() {
+            Some("-1") => "graphite",
+            Some("0") => "red",
+            Some("1") => "orange",
+            Some("2") => "yellow",
+            Some("3") => "green",
+            Some("5") => "purple",
+            Some("6") => "pink",
+            _ => "blue",
+        };
+        SystemTheme { dark, accent: named_accent(name) }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{named_accent, SystemTheme};
+
+    fn gsetting(key: &str) -> Option<String> {
+        let out = std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", key])
+            .output()
+            .ok()?;
+        out.status.success().then(|| String::from_utf8_lossy(&out.stdout).trim().trim_matches('\'').to_string())
+    }
+
+    pub fn read() -> SystemTheme {
+        let dark = gsetting("color-scheme").is_some_and(|s| s == "prefer-dark")
+            || gsetting("gtk-theme").is_some_and(|s| s.to_lowercase().contains("dark"));
+        let accent = gsetting("accent-color").and_then(|name| named_accent(&name));
+        SystemTheme { dark, accent }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    use super::SystemTheme;
+
+    pub fn read() -> SystemTheme {
+        SystemTheme { dark: false, accent: None }
+    }
+}
@@ -1,23 +1,31 @@
 use anyhow::{Context, Result};
-use log::{error, info};
+use log::{error, info, warn};
 use ringbuf::{
-    traits::{Consumer, Producer, Split},
+    traits::{Consumer, Observer, Producer, Split},
     HeapRb,
 };
 use rodio::cpal::{
     self,
     traits::{DeviceTrait, HostTrait, StreamTrait},
 };
-use rodio::{Decoder, OutputStream, Sink, Source};
+use rodio::{buffer::SamplesBuffer, Decoder, OutputStream, OutputStreamHandle, Sink, Source};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-
-use crate::state::SoundEntry;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::convert;
+use crate::dsp::{self, Graph, StageControl};
+use crate::flac;
+use crate::line_in::{self, LineIn, LineInControl};
+use crate::playlist::{self, PlaylistItem, PlaylistVoice};
+use crate::state::{AgcSettings, ProcessingStage, SoundEntry, SoundFormat, SoundRoute};
+use crate::stopword;
+use crate::virtual_sink;
 
 // ── Types ───────────────────────────────────────────────────────────
 
@@ -32,6 +40,67 @@ pub struct AudioDeviceList {
     pub output_devices: Vec<AudioDeviceInfo>,
 }
 
+/// Pipeline health, from `get_audio_metrics` and the `audio-metrics` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioMetrics {
+    pub channels: u16,
+    /// Configured rate of the input device.
+    pub sample_rate: u32,
+    /// Mic ring buffer occupancy, 0..1.
+    pub mic_buffer_fill: f32,
+    /// Sound injection ring buffer occupancy, 0..1.
+    pub sound_buffer_fill: f32,
+    /// Mic samples lost because the ring buffer was full.
+    pub dropped_mic_samples: u64,
+    /// Sound samples lost because the injection buffer was full.
+    pub dropped_sound_samples: u64,
+    /// Times the output ran out of mic samples.
+    pub underruns: u64,
+    /// Measured frames/s since the previous read.
+    pub input_rate_hz: f32,
+    pub output_rate_hz: f32,
+    pub stream_errors: u64,
+    pub last_stream_error: Option<String>,
+    /// Loudest mic sample since the previous read, after the input gain
+    /// (1.0 = full scale).
+    pub input_peak: f32,
+    /// Mic samples that clipped since the pipeline started.
+    pub clipped_samples: u64,
+    /// Some clipped since the previous read.
+    pub clipping: bool,
+}
+
+/// Counters bumped from the audio callbacks (lock-free, except the last error).
+#[derive(Default)]
+struct Counters {
+    mic_fill: AtomicU64,
+    captured: AtomicU64,
+    rendered: AtomicU64,
+    dropped_mic: AtomicU64,
+    dropped_sound: AtomicU64,
+    underruns: AtomicU64,
+    starved: AtomicBool,
+    stream_errors: AtomicU64,
+    last_error: Mutex<Option<String>>,
+    /// `f32` bits of the input peak since the last read.
+    input_peak: AtomicU32,
+    clipped: AtomicU64,
+}
+
+/// A mic sample at or above this (before the input gain) was clipped by the
+/// device already.
+const CLIP_LEVEL: f32 = 0.999;
+
+/// Peak after `gain` and how many samples clipped: already at full scale
+/// from the device, or pushed past it by the gain.
+pub(crate) fn measure_input(data: &[f32], gain: f32) -> (f32, u64) {
+    data.iter().fold((0.0f32, 0), |(peak, clipped), &s| {
+        let level = (s * gain).abs();
+        let clip = s.abs() >= CLIP_LEVEL || level >= 1.0;
+        (peak.max(level), clipped + clip as u64)
+    })
+}
+
 // ── Device enumeration ──────────────────────────────────────────────
 
 pub fn list_devices() -> AudioDeviceList {
@@ -47,7 +116,7 @@ pub fn list_devices() -> AudioDeviceList {
         })
         .unwrap_or_default();
 
-    let output_devices = host
+    let mut output_devices: Vec<AudioDeviceInfo> = host
         .output_devices()
         .map(|devs| {
             devs.filter_map(|d| {
@@ -56,6 +125,10 @@ pub fn list_devices() -> AudioDeviceList {
             .collect()
         })
         .unwrap_or_default();
+    // Created on demand, so it's listed before it exists
+    if virtual_sink::available() {
+        output_devices.insert(0, AudioDeviceInfo { name: virtual_sink::NAME.into() });
+    }
 
     AudioDeviceList {
         input_devices,
@@ -63,7 +136,7 @@ pub fn list_devices() -> AudioDeviceList {
     }
 }
 
-fn find_input_device(name: &str) -> Option<cpal::Device> {
+pub(crate) fn find_input_device(name: &str) -> Option<cpal::Device> {
     let host = cpal::default_host();
     host.input_devices().ok()?.find(|d| {
         d.name().map(|n| n == name).unwrap_or(false)
@@ -77,11 +150,92 @@ fn find_output_device(name: &str) -> Option<cpal::Device> {
     })
 }
 
+/// Sample formats input streams can open with, worst first.
+const INPUT_FORMATS: [cpal::SampleFormat; 4] =
+    [cpal::SampleFormat::U16, cpal::SampleFormat::I16, cpal::SampleFormat::I32, cpal::SampleFormat::F32];
+
+/// Input config to open: the device's default when its sample format is one
+/// we convert, else the best supported one. Mono or stereo first, then the
+/// default rate, 48 kHz or 44.1 kHz, then the sample format.
+pub(crate) fn pick_input_config(
+    default: Option<cpal::SupportedStreamConfig>,
+    supported: Vec<cpal::SupportedStreamConfigRange>,
+) -> Option<cpal::SupportedStreamConfig> {
+    if let Some(config) = default.as_ref().filter(|c| INPUT_FORMATS.contains(&c.sample_format())) {
+        return Some(config.clone());
+    }
+    let rates: Vec<u32> = default.map(|c| c.sample_rate().0).into_iter().chain([48_000, 44_100]).collect();
+    supported
+        .into_iter()
+        .filter_map(|range| {
+            let format = INPUT_FORMATS.iter().position(|&f| f == range.sample_format())?;
+            let (min, max) = (range.min_sample_rate().0, range.max_sample_rate().0);
+            let rate = rates.iter().position(|r| (min..=max).contains(r));
+            let config = range.with_sample_rate(cpal::SampleRate(rate.map_or(48_000.clamp(min, max), |i| rates[i])));
+            let key = (matches!(config.channels(), 1 | 2), std::cmp::Reverse(rate.unwrap_or(rates.len())), format);
+            Some((key, config))
+        })
+        .max_by_key(|(key, _)| *key)
+        .map(|(_, config)| config)
+}
+
+/// `pick_input_config` for `dev`.
+pub(crate) fn input_config(dev: &cpal::Device) -> Result<cpal::SupportedStreamConfig> {
+    let supported = dev.supported_input_configs().map(|c| c.collect()).unwrap_or_default();
+    pick_input_config(dev.default_input_config().ok(), supported)
+        .context("No input config with a usable sample format")
+}
+
+/// Build an input stream with `config`, handing its samples to `on_data` as
+/// f32 whatever the device's format.
+pub(crate) fn build_input_stream(
+    dev: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    mut on_data: impl FnMut(&[f32]) + Send + 'static,
+    on_error: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<cpal::Stream, cpal::BuildStreamError> {
+    let stream_config = config.config();
+    match config.sample_format() {
+        cpal::SampleFormat::F32 => dev.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| on_data(data),
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::I32 => build_converted::<i32>(dev, &stream_config, on_data, on_error),
+        cpal::SampleFormat::I16 => build_converted::<i16>(dev, &stream_config, on_data, on_error),
+        cpal::SampleFormat::U16 => build_converted::<u16>(dev, &stream_config, on_data, on_error),
+        _ => Err(cpal::BuildStreamError::StreamConfigNotSupported),
+    }
+}
+
+fn build_converted<T: cpal::SizedSample>(
+    dev: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut on_data: impl FnMut(&[f32]) + Send + 'static,
+    on_error: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    f32: cpal::FromSample<T>,
+{
+    let mut buf = Vec::new();
+    dev.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| on_data(convert::to_f32(data, &mut buf)),
+        on_error,
+        None,
+    )
+}
+
+/// Name of the cpal host (WASAPI, CoreAudio, ALSA, …).
+pub fn host_name() -> &'static str {
+    cpal::default_host().id().name()
+}
+
 // ── Sound file management ───────────────────────────────────────────
 
 pub fn sounds_dir() -> Result<PathBuf> {
-    let base = dirs::config_dir().context("Cannot determine config directory")?;
-    let dir = base.join("deck8-hub").join("sounds");
+    let dir = crate::profile::config_dir()?.join("sounds");
     if !dir.exists() {
         fs::create_dir_all(&dir).context("Failed to create sounds directory")?;
     }
@@ -97,6 +251,17 @@ pub fn delete_sound(filename: &str) -> Result<()> {
     Ok(())
 }
 
+/// `sounds_dir()/filename` without touching the disk once the folder is
+/// known, for the key press path (see trigger.rs).
+pub fn sound_path(filename: &str) -> Result<PathBuf> {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    if let Some(dir) = DIR.get() {
+        return Ok(dir.join(filename));
+    }
+    let dir = sounds_dir()?;
+    Ok(DIR.get_or_init(|| dir).join(filename))
+}
+
 pub fn resolve_sound_path(filename: &str) -> Result<PathBuf> {
     let path = sounds_dir()?.join(filename);
     if !path.exists() {
@@ -105,6 +270,63 @@ pub fn resolve_sound_path(filename: &str) -> Result<PathBuf> {
     Ok(path)
 }
 
+/// Disk use of the sound library, from `get_library_stats`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LibraryStats {
+    /// Bytes of the files library entries point at.
+    pub library_bytes: u64,
+    /// Entries no key plays, by id.
+    pub unused: Vec<String>,
+    pub unused_bytes: u64,
+    /// Entries whose file is gone, by id.
+    pub missing: Vec<String>,
+    /// Audio files in the sounds folder no entry points at, left there for
+    /// longer than an import or adoption takes.
+    pub orphans: Vec<String>,
+    pub orphan_bytes: u64,
+    pub quota_bytes: Option<u64>,
+}
+
+/// Result of `clean_sound_library`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupReport {
+    pub removed_files: usize,
+    /// Library entries removed, by id.
+    pub removed_sounds: Vec<String>,
+    pub bytes_freed: u64,
+}
+
+/// Compare the library against the files in `dir`. `used` are the ids of
+/// sounds on keys or in playlists.
+pub fn library_stats(dir: &Path, library: &[SoundEntry], used: &[&str]) -> Result<LibraryStats> {
+    let mut stats = LibraryStats::default();
+    for entry in library {
+        let Ok(meta) = fs::metadata(dir.join(&entry.filename)) else {
+            stats.missing.push(entry.id.clone());
+            continue;
+        };
+        stats.library_bytes += meta.len();
+        if !used.contains(&entry.id.as_str()) {
+            stats.unused.push(entry.id.clone());
+            stats.unused_bytes += meta.len();
+        }
+    }
+    for file in fs::read_dir(dir).context("Failed to list the sounds folder")? {
+        let file = file?;
+        let meta = file.metadata()?;
+        let name = file.file_name().to_string_lossy().into_owned();
+        let settled = meta.modified().ok().and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age >= crate::library_watch::SETTLED_AFTER);
+        if meta.is_file() && settled && crate::library_watch::is_audio(&name)
+            && !library.iter().any(|e| e.filename == name)
+        {
+            stats.orphans.push(name);
+            stats.orphan_bytes += meta.len();
+        }
+    }
+    Ok(stats)
+}
+
 /// Simple timestamp-based unique ID (no extra crate needed).
 pub fn uuid_simple() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -116,103 +338,197 @@ pub fn uuid_simple() -> String {
 
 // ── Sound Library imports ───────────────────────────────────────────
 
-/// Import a sound file into the library. Copies file to sounds_dir with a unique filename.
-pub fn import_to_library(source_path: &str, display_name: &str) -> Result<SoundEntry> {
+/// Copy a sound file into sounds_dir as `{id}.{ext}`; returns the filename.
+pub fn copy_into_library(source_path: &str, id: &str) -> Result<String> {
     let src = Path::new(source_path);
     let ext = src
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("wav");
-    let id = uuid_simple();
     let filename = format!("{}.{}", id, ext);
     let dest = sounds_dir()?.join(&filename);
     fs::copy(src, &dest).context("Failed to copy sound file")?;
     info!("[audio] Library import: {} → {}", source_path, dest.display());
+    Ok(filename)
+}
+
+/// Import a sound file into the library. Copies file to sounds_dir with a unique filename.
+pub fn import_to_library(source_path: &str, display_name: &str) -> Result<SoundEntry> {
+    let id = uuid_simple();
+    let filename = copy_into_library(source_path, &id)?;
     Ok(SoundEntry {
         id,
         filename,
         display_name: display_name.to_string(),
+        missing: false,
+        monitor_only: false,
     })
 }
 
-/// Import a sound file into the library with trimming. Saves as WAV.
+/// Import a sound file into the library with trimming, saved as `format`.
 pub fn import_to_library_trimmed(
     source_path: &str,
     display_name: &str,
-    start_ms: u64,
-    end_ms: u64,
+    trim: &Trim,
+    format: SoundFormat,
 ) -> Result<SoundEntry> {
-    let file = fs::File::open(source_path)
-        .context(format!("Cannot open: {}", source_path))?;
-    let reader = BufReader::new(file);
-    let source = Decoder::new(reader).context("Failed to decode audio")?;
-
-    let sample_rate = source.sample_rate();
-    let channels = source.channels();
-
-    let start_sample = (start_ms as usize) * (sample_rate as usize) * (channels as usize) / 1000;
-    let end_sample = (end_ms as usize) * (sample_rate as usize) * (channels as usize) / 1000;
-
-    let samples: Vec<f32> = source
-        .skip(start_sample)
-        .take(end_sample - start_sample)
-        .map(|s| s as f32 / 32768.0)
-        .collect();
-
-    if samples.is_empty() {
-        anyhow::bail!("Trimmed audio is empty");
-    }
+    let (channels, sample_rate, samples) = decode_trimmed(source_path, trim)?;
 
     let id = uuid_simple();
-    let filename = format!("{}.wav", id);
+    let filename = match format {
+        SoundFormat::Wav => format!("{}.wav", id),
+        SoundFormat::Flac => format!("{}.flac", id),
+    };
     let dest = sounds_dir()?.join(&filename);
 
-    let spec = hound::WavSpec {
-        channels,
-        sample_rate,
-        bits_per_sample: 32,
-        sample_format: hound::SampleFormat::Float,
-    };
-    let mut writer = hound::WavWriter::create(&dest, spec)
-        .context("Failed to create WAV file")?;
+    match format {
+        SoundFormat::Wav => {
+            let spec = hound::WavSpec {
+                channels,
+                sample_rate,
+                bits_per_sample: 32,
+                sample_format: hound::SampleFormat::Float,
+            };
+            let mut writer = hound::WavWriter::create(&dest, spec)
+                .context("Failed to create WAV file")?;
 
-    for sample in &samples {
-        writer.write_sample(*sample).context("Failed to write sample")?;
+            for sample in &samples {
+                writer.write_sample(*sample).context("Failed to write sample")?;
+            }
+            writer.finalize().context("Failed to finalize WAV")?;
+        }
+        SoundFormat::Flac => {
+            let samples: Vec<i16> = samples.iter().map(|&s| (s * 32768.0).round().clamp(-32768.0, 32767.0) as i16).collect();
+            fs::write(&dest, flac::encode(channels, sample_rate, &samples))
+                .context("Failed to write FLAC file")?;
+        }
     }
-    writer.finalize().context("Failed to finalize WAV")?;
 
     info!(
         "[audio] Library trim import {}ms-{}ms → {} ({} samples, {}ch @ {}Hz)",
-        start_ms, end_ms, filename, samples.len(), channels, sample_rate
+        trim.start_ms, trim.end_ms, filename, samples.len(), channels, sample_rate
     );
     Ok(SoundEntry {
         id,
         filename,
         display_name: display_name.to_string(),
+        missing: false,
+        monitor_only: false,
     })
 }
 
+/// Result of `compress_sound_library`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompressReport {
+    pub converted: usize,
+    pub bytes_saved: u64,
+}
+
+/// Re-encode a library WAV as FLAC when that loses nothing (16-bit samples,
+/// including float WAVs holding 16-bit values like old trimmed imports) and
+/// saves space. Returns the new filename and the bytes saved; the WAV is
+/// left for the caller to delete once nothing points at it.
+pub fn compress_sound(filename: &str) -> Result<Option<(String, u64)>> {
+    let path = resolve_sound_path(filename)?;
+    if !path.extension().is_some_and(|e| e.eq_ignore_ascii_case("wav")) {
+        return Ok(None);
+    }
+    let mut reader = hound::WavReader::open(&path).context(format!("Cannot read WAV: {}", filename))?;
+    let spec = reader.spec();
+    let samples: Vec<i16> = match (spec.sample_format, spec.bits_per_sample) {
+        (hound::SampleFormat::Int, 16) => reader.samples::<i16>().collect::<Result<_, _>>()?,
+        (hound::SampleFormat::Float, 32) => {
+            let floats = reader.samples::<f32>().collect::<Result<Vec<_>, _>>()?;
+            let exact = |s: f32| {
+                let scaled = s * 32768.0;
+                scaled.fract() == 0.0 && (-32768.0..=32767.0).contains(&scaled)
+            };
+            if !floats.iter().all(|&s| exact(s)) {
+                return Ok(None);
+            }
+            floats.iter().map(|&s| (s * 32768.0) as i16).collect()
+        }
+        _ => return Ok(None),
+    };
+    let encoded = flac::encode(spec.channels, spec.sample_rate, &samples);
+    let before = fs::metadata(&path)?.len();
+    if encoded.len() as u64 >= before {
+        return Ok(None);
+    }
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(filename);
+    let new_name = format!("{}.flac", stem);
+    fs::write(sounds_dir()?.join(&new_name), &encoded).context("Failed to write FLAC file")?;
+    info!("[audio] Compressed {} → {} ({} → {} bytes)", filename, new_name, before, encoded.len());
+    Ok(Some((new_name, before - encoded.len() as u64)))
+}
+
 // ── Audio trim & duration ───────────────────────────────────────────
 
-/// Get the duration of an audio file in milliseconds.
-pub fn get_audio_duration(file_path: &str) -> Result<u64> {
-    let file = fs::File::open(file_path)
-        .context(format!("Cannot open: {}", file_path))?;
-    let reader = BufReader::new(file);
-    let source = Decoder::new(reader).context("Failed to decode audio")?;
-    let sample_rate = source.sample_rate() as u64;
-    let channels = source.channels() as u64;
-    // Count total samples
-    let total_samples: u64 = source.count() as u64;
-    if sample_rate == 0 || channels == 0 {
-        return Ok(0);
+/// Gain the trim editor allows, in dB.
+pub const TRIM_GAIN_DB: std::ops::RangeInclusive<f32> = -24.0..=12.0;
+
+/// What the trim editor keeps of a file: a range, faded in and out at its
+/// ends, with a gain. Previews and imports both go through `apply`, so what
+/// is heard is what gets saved.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+pub struct Trim {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    #[serde(default)]
+    pub fade_in_ms: u64,
+    #[serde(default)]
+    pub fade_out_ms: u64,
+    /// Clamped to `TRIM_GAIN_DB`.
+    #[serde(default)]
+    pub gain_db: f32,
+}
+
+impl Trim {
+    /// `start_ms..end_ms` as it is.
+    pub fn range(start_ms: u64, end_ms: u64) -> Self {
+        Self { start_ms, end_ms, ..Self::default() }
+    }
+
+    /// The whole file as it is.
+    pub fn whole() -> Self {
+        Self::range(0, u64::MAX)
+    }
+
+    /// Cut interleaved `samples` to the range and shape them. Fades are
+    /// linear and shortened to fit; the result is clipped to [-1, 1].
+    pub fn apply(&self, samples: &[f32], channels: u16, sample_rate: u32) -> Vec<f32> {
+        let channels = channels.max(1) as usize;
+        let frames_in = |ms: u64| (ms as usize).saturating_mul(sample_rate as usize) / 1000;
+        let at = |ms: u64| (frames_in(ms) * channels).min(samples.len());
+        let kept = &samples[at(self.start_ms)..at(self.end_ms).max(at(self.start_ms))];
+        // A trailing partial frame (a truncated file) is dropped
+        let frames = kept.len() / channels;
+        let kept = &kept[..frames * channels];
+        let fade_in = frames_in(self.fade_in_ms).min(frames);
+        let fade_out = frames_in(self.fade_out_ms).min(frames);
+        let gain = if self.gain_db.is_finite() {
+            dsp::db_to_lin(self.gain_db.clamp(*TRIM_GAIN_DB.start(), *TRIM_GAIN_DB.end()))
+        } else {
+            1.0
+        };
+        kept.chunks(channels)
+            .enumerate()
+            .flat_map(|(frame, samples)| {
+                let mut scale = gain;
+                if frame < fade_in {
+                    scale *= frame as f32 / fade_in as f32;
+                }
+                if frames - frame <= fade_out {
+                    scale *= (frames - frame - 1) as f32 / fade_out as f32;
+                }
+                samples.iter().map(move |&s| (s * scale).clamp(-1.0, 1.0))
+            })
+            .collect()
     }
-    let frames = total_samples / channels;
-    Ok(frames * 1000 / sample_rate)
 }
 
-/// Preview a trimmed portion of an audio file through the default output device.
-pub fn preview_trim(source_path: &str, start_ms: u64, end_ms: u64) -> Result<()> {
+/// Decode a file and apply `trim`: (channels, sample rate, samples).
+pub fn decode_trimmed(source_path: &str, trim: &Trim) -> Result<(u16, u32, Vec<f32>)> {
     let file = fs::File::open(source_path)
         .context(format!("Cannot open: {}", source_path))?;
     let reader = BufReader::new(file);
@@ -220,64 +536,213 @@ pub fn preview_trim(source_path: &str, start_ms: u64, end_ms: u64) -> Result<()>
 
     let sample_rate = source.sample_rate();
     let channels = source.channels();
+    let raw: Vec<f32> = source.convert_samples::<f32>().collect();
+    let samples = trim.apply(&raw, channels, sample_rate);
+    if samples.is_empty() {
+        anyhow::bail!("Trimmed audio is empty");
+    }
+    Ok((channels, sample_rate, samples))
+}
 
-    let start_sample = (start_ms as usize) * (sample_rate as usize) * (channels as usize) / 1000;
-    let end_sample = (end_ms as usize) * (sample_rate as usize) * (channels as usize) / 1000;
+/// Decode a sound file into `channels` at `sample_rate` (a pipeline's format).
+pub fn decode_to(path: &Path, dst_channels: u16, dst_rate: u32) -> Result<Vec<f32>> {
+    let file = fs::File::open(path)
+        .context(format!("Cannot open sound: {}", path.display()))?;
+    let reader = BufReader::new(file);
+    let source = Decoder::new(reader)
+        .context("Failed to decode audio file")?;
 
-    // Collect trimmed samples into a buffer (Decoder yields i16, convert to f32)
-    let samples: Vec<f32> = source
-        .skip(start_sample)
-        .take(end_sample - start_sample)
-        .map(|s| s as f32 / 32768.0)
-        .collect();
+    let src_rate = source.sample_rate();
+    let src_channels = source.channels();
 
+    // Collect all samples as f32 (normalized to [-1, 1])
+    let raw: Vec<f32> = source.convert_samples::<f32>().collect();
+
+    // Channel conversion
+    let chan_converted: Vec<f32> = if src_channels == 2 && dst_channels == 1 {
+        raw.chunks(2)
+            .map(|c| (c[0] + c.get(1).copied().unwrap_or(0.0)) / 2.0)
+            .collect()
+    } else if src_channels == 1 && dst_channels == 2 {
+        raw.iter().flat_map(|&s| [s, s]).collect()
+    } else {
+        raw
+    };
+
+    // Sample rate conversion (linear interpolation)
+    let resampled = if src_rate != dst_rate {
+        let ratio = src_rate as f64 / dst_rate as f64;
+        let out_len = (chan_converted.len() as f64 / ratio) as usize;
+        let mut out = Vec::with_capacity(out_len);
+        for i in 0..out_len {
+            let src_pos = i as f64 * ratio;
+            let idx = src_pos as usize;
+            let frac = (src_pos - idx as f64) as f32;
+            let s0 = chan_converted.get(idx).copied().unwrap_or(0.0);
+            let s1 = chan_converted.get(idx + 1).copied().unwrap_or(s0);
+            out.push(s0 + (s1 - s0) * frac);
+        }
+        out
+    } else {
+        chan_converted
+    };
+    Ok(resampled)
+}
+
+/// Decode a file into a pipeline's format and apply `trim` (trim previews
+/// while the pipeline runs; decoded before it's locked).
+pub fn decode_trimmed_to(path: &Path, trim: &Trim, channels: u16, sample_rate: u32) -> Result<Vec<f32>> {
+    let samples = trim.apply(&decode_to(path, channels, sample_rate)?, channels, sample_rate);
     if samples.is_empty() {
         anyhow::bail!("Trimmed audio is empty");
     }
+    Ok(samples)
+}
+
+/// Get the duration of an audio file in milliseconds, from its headers
+/// where they have it (see probe.rs).
+pub fn get_audio_duration(file_path: &str) -> Result<u64> {
+    Ok(crate::probe::probe(Path::new(file_path))?.duration_ms)
+}
 
-    // Play through default output (speakers) for preview.
-    // OutputStream contains cpal::Stream which is !Send on Windows,
-    // so we spawn a new thread that owns both the stream and sink.
+/// Open the default output, not while the pipeline is claiming its stream
+/// for the Linux soundboard sink (see virtual_sink.rs).
+pub fn open_default_output() -> Result<(OutputStream, OutputStreamHandle), rodio::StreamError> {
+    let _opening = virtual_sink::OPENING.lock().unwrap();
+    OutputStream::try_default()
+}
+
+/// Short accessibility tone on the default output (never into the mic). Each
+/// slot gets its own pitch, a major third apart, so changes can be told apart.
+pub fn play_earcon(slot: usize) {
+    let freq = 523.25 * 2f32.powf((slot % 6) as f32 / 3.0);
     std::thread::spawn(move || {
-        let Ok((_stream, handle)) = OutputStream::try_default() else {
-            error!("[audio] Failed to open default output for preview");
-            return;
-        };
-        let Ok(sink) = Sink::try_new(&handle) else {
-            error!("[audio] Failed to create preview sink");
-            return;
-        };
-        let buffer = rodio::buffer::SamplesBuffer::new(channels, sample_rate, samples);
-        sink.append(buffer);
+        let Ok((_stream, handle)) = open_default_output() else { return; };
+        let Ok(sink) = Sink::try_new(&handle) else { return; };
+        sink.append(
+            rodio::source::SineWave::new(freq)
+                .take_duration(Duration::from_millis(90))
+                .fade_in(Duration::from_millis(5))
+                .amplify(0.15),
+        );
         sink.sleep_until_end();
     });
-
-    Ok(())
 }
 
 // ── MicSource (rodio::Source reading from ring buffer) ───────────────
 
+/// How long a held sound takes to fade out once its key is released.
+const RELEASE_FADE: Duration = Duration::from_millis(50);
+
+/// Where one sound sits in the injection ring, counted in samples pushed
+/// since the pipeline started.
+#[derive(Debug, Clone, Copy)]
+pub struct Span {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Producer side of the sound ring, counting what went in for `Span`s.
+struct SoundQueue {
+    producer: ringbuf::HeapProd<f32>,
+    pushed: u64,
+}
+
+/// A momentary sound whose key is still down, or the trim preview.
+struct Held {
+    /// Where it is in the mic mix, unless it went to the monitor only.
+    span: Option<Span>,
+    /// Stops the local (headphone) copy, if there is one.
+    stop: Option<Arc<AtomicBool>>,
+}
+
+/// Reading side of the sound ring: released held sounds fade out and the
+/// rest of them is skipped.
+pub struct SoundStream {
+    consumer: ringbuf::HeapCons<f32>,
+    /// Samples taken from the ring so far.
+    popped: u64,
+    /// Released held sounds, from `stop_held`.
+    cuts: ringbuf::HeapCons<Span>,
+    /// Cuts in progress: fading from `start`, dropped up to `end`.
+    fading: Vec<Span>,
+    fade_len: u64,
+}
+
+impl SoundStream {
+    /// `fade_len` in samples (all channels).
+    pub fn new(consumer: ringbuf::HeapCons<f32>, cuts: ringbuf::HeapCons<Span>, fade_len: u64) -> Self {
+        Self { consumer, popped: 0, cuts, fading: Vec::new(), fade_len: fade_len.max(1) }
+    }
+
+    /// Next sample, 0.0 when the ring is empty.
+    pub fn next_sample(&mut self) -> f32 {
+        while let Some(span) = self.cuts.try_pop() {
+            self.fading.push(Span { start: span.start.max(self.popped), end: span.end });
+        }
+        loop {
+            let pos = self.popped;
+            self.fading.retain(|f| f.end > pos);
+            let mut gain = 1.0;
+            if let Some(f) = self.fading.iter().find(|f| f.start <= pos) {
+                let faded = pos - f.start;
+                if faded >= self.fade_len {
+                    // Faded out: the rest of the sound is dropped
+                    self.popped += self.consumer.skip((f.end - pos) as usize) as u64;
+                    continue;
+                }
+                gain = 1.0 - faded as f32 / self.fade_len as f32;
+            }
+            return match self.consumer.try_pop() {
+                Some(sample) => {
+                    self.popped += 1;
+                    sample * gain
+                }
+                None => 0.0,
+            };
+        }
+    }
+}
+
 struct MicSource {
     consumer: ringbuf::HeapCons<f32>,
-    sound_consumer: ringbuf::HeapCons<f32>,
+    graph: Graph,
+    sounds: SoundStream,
+    playlist: PlaylistVoice,
+    /// Second input, mixed in after the processing (see line_in.rs).
+    line_in: LineIn,
     channels: u16,
     sample_rate: u32,
     volume: Arc<AtomicU32>,
     sound_volume: Arc<AtomicU32>,
+    counters: Arc<Counters>,
 }
 
 impl Iterator for MicSource {
     type Item = f32;
 
     fn next(&mut self) -> Option<f32> {
-        let mic_sample = self.consumer.try_pop().unwrap_or(0.0);
+        let mic_sample = match self.consumer.try_pop() {
+            Some(sample) => {
+                self.counters.starved.store(false, Ordering::Relaxed);
+                sample
+            }
+            None => {
+                // Count each run of empty pops once
+                if !self.counters.starved.swap(true, Ordering::Relaxed) {
+                    self.counters.underruns.fetch_add(1, Ordering::Relaxed);
+                }
+                0.0
+            }
+        };
+        self.counters.rendered.fetch_add(1, Ordering::Relaxed);
         let vol = f32::from_bits(self.volume.load(Ordering::Relaxed));
 
-        let sound_sample = self.sound_consumer.try_pop().unwrap_or(0.0);
+        let sound_sample = self.sounds.next_sample() + self.playlist.next_sample();
         let svol = f32::from_bits(self.sound_volume.load(Ordering::Relaxed));
 
-        // Mix mic + sound into a single stream so Discord sees sound as mic input
-        Some(mic_sample * vol + sound_sample * svol)
+        // Mix processed mic + sound into a single stream so Discord sees sound as mic input
+        Some(self.graph.process(mic_sample) * vol + self.line_in.next_sample() + sound_sample * svol)
     }
 }
 
@@ -299,18 +764,176 @@ impl Source for MicSource {
     }
 }
 
+// ── Monitor (local copies) ──────────────────────────────────────────
+
+/// A sound for the monitor thread; setting `stop` fades it out early.
+struct MonitorSound {
+    samples: Arc<Vec<f32>>,
+    volume: f32,
+    stop: Arc<AtomicBool>,
+}
+
+/// Fade steps once a local copy is stopped, `RELEASE_FADE` apart in total.
+const MONITOR_FADE_STEPS: u32 = 10;
+
+struct Voice {
+    sink: Sink,
+    volume: f32,
+    stop: Arc<AtomicBool>,
+    /// Fade steps left; counts down once stopped.
+    fade: u32,
+}
+
+impl Voice {
+    /// Advance one step; false once it's done.
+    fn tick(&mut self) -> bool {
+        if self.fade == MONITOR_FADE_STEPS && !self.sink.empty() && !self.stop.load(Ordering::Relaxed) {
+            return true;
+        }
+        if self.fade == 0 || self.sink.empty() {
+            self.sink.stop();
+            return false;
+        }
+        self.fade -= 1;
+        self.sink.set_volume(self.volume * self.fade as f32 / MONITOR_FADE_STEPS as f32);
+        true
+    }
+}
+
+/// One thread for the pipeline's local copies: it opens the default output
+/// once and gives each sound a sink on it, instead of a thread and an output
+/// stream per sound (tens of ms on some hosts). Ends with the pipeline, once
+/// what's playing has finished.
+fn spawn_monitor(channels: u16, sample_rate: u32) -> mpsc::Sender<MonitorSound> {
+    let (tx, rx) = mpsc::channel::<MonitorSound>();
+    let step = RELEASE_FADE / MONITOR_FADE_STEPS;
+    std::thread::spawn(move || {
+        let Ok((_stream, handle)) = open_default_output() else {
+            warn!("[audio] No default output, sounds won't play locally");
+            return;
+        };
+        let mut voices: Vec<Voice> = Vec::new();
+        let mut open = true;
+        loop {
+            let next = match (open, voices.is_empty()) {
+                (true, true) => rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+                (true, false) => rx.recv_timeout(step),
+                (false, true) => break,
+                (false, false) => {
+                    std::thread::sleep(step);
+                    Err(mpsc::RecvTimeoutError::Timeout)
+                }
+            };
+            match next {
+                Ok(sound) => match Sink::try_new(&handle) {
+                    Ok(sink) => {
+                        sink.set_volume(sound.volume);
+                        sink.append(SamplesBuffer::new(channels, sample_rate, sound.samples.to_vec()));
+                        voices.push(Voice { sink, volume: sound.volume, stop: sound.stop, fade: MONITOR_FADE_STEPS });
+                    }
+                    Err(e) => warn!("[audio] Local playback failed: {e}"),
+                },
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => open = false,
+            }
+            voices.retain_mut(Voice::tick);
+        }
+    });
+    tx
+}
+
 // ── AudioPipeline ───────────────────────────────────────────────────
 
+/// What the rest of the app needs from the soundboard. The real one is
+/// `AudioPipeline`; tests use a fake that records what was played.
+pub trait SoundOutput: Send + Sync {
+    /// Mix a sound into the mic stream and/or play it locally, as `route`
+    /// says, scaled by `gain` (quiet hours and the key's offset; 1.0 otherwise).
+    fn play_sound(&self, path: &Path, gain: f32, route: SoundRoute) -> Result<()>;
+    /// Channels and sample rate sounds are decoded to.
+    fn format(&self) -> (u16, u32);
+    /// Play a trim preview of `path`, already decoded into `format()` and
+    /// shaped by `trim` (`decode_trimmed_to`). Replaces the preview still
+    /// playing.
+    fn play_trimmed(&self, path: &Path, trim: &Trim, samples: Vec<f32>, route: SoundRoute);
+    /// Cut the trim preview, if it's still playing.
+    fn stop_preview(&self);
+    /// Like `play_sound`, for a momentary key: `stop_held` fades it out.
+    fn play_held(&self, key_index: usize, path: &Path, gain: f32, route: SoundRoute) -> Result<()>;
+    /// Release the key's held sound, if it's still playing.
+    fn stop_held(&self, key_index: usize);
+    /// Fade out everything playing, held or not.
+    fn stop_all(&self);
+    /// Play `sounds` back to back with no gap, the last looping if
+    /// `loop_last`, replacing the playlist playing (see playlist.rs). Sounds
+    /// routed to the monitor only are silent in the mic.
+    fn play_playlist(&self, id: &str, sounds: &[(PathBuf, SoundRoute)], loop_last: bool, gain: f32) -> Result<()>;
+    /// Go on with the playlist's next sound (ends it after the last).
+    fn skip_playlist(&self);
+    fn stop_playlist(&self);
+    /// Id of the playlist playing.
+    fn playing_playlist(&self) -> Option<String>;
+    /// Whether anything is still audible: in the mic mix, on the monitor or
+    /// a playlist.
+    fn is_playing(&self) -> bool;
+    /// Listen for the stop word on the mic (see stopword.rs).
+    fn set_stop_word(&self, enabled: bool);
+    /// Make the decode cache hold exactly `paths` (the sounds assigned to keys).
+    fn preload(&self, paths: &[PathBuf]);
+    fn set_mic_volume(&self, vol: f32);
+    /// Mix a second input device in (`None`: stop), after the mic's processing.
+    fn set_second_input(&self, device: Option<&str>) -> Result<()>;
+    fn set_second_input_volume(&self, vol: f32);
+    /// Gain applied to the mic as it's captured, before processing.
+    fn set_input_gain(&self, gain_db: f32);
+    fn set_sound_volume(&self, vol: f32);
+    /// Replace the mic processing chain (order and bypass).
+    fn set_processing(&self, stages: &[ProcessingStage]);
+    fn set_agc(&self, agc: &AgcSettings);
+    /// Buffer levels, drops and rates; `None` when there's nothing to measure.
+    fn metrics(&self) -> Option<AudioMetrics> {
+        None
+    }
+}
+
 pub struct AudioPipeline {
     _input_stream: cpal::Stream,
     _output_stream: OutputStream,
     mic_volume: Arc<AtomicU32>,
+    /// Linear, as `f32` bits.
+    input_gain: Arc<AtomicU32>,
+    line_in: Mutex<LineInControl>,
+    line_in_volume: Arc<AtomicU32>,
     sound_volume: Arc<AtomicU32>,
+    stage_control: Arc<StageControl>,
     _mic_sink: Sink,
     // Sound injection: decoded samples are pushed here and mixed into the mic stream
-    sound_producer: Mutex<ringbuf::HeapProd<f32>>,
+    sound_queue: Mutex<SoundQueue>,
+    cut_producer: Mutex<ringbuf::HeapProd<Span>>,
+    /// Momentary sounds by LED index, until released.
+    held: Mutex<[Option<Held>; 8]>,
+    /// The trim preview, until replaced or stopped.
+    preview: Mutex<Option<Held>>,
+    /// Stop flags of the local copies, for `stop_all`.
+    playing: Mutex<Vec<Arc<AtomicBool>>>,
+    /// To the monitor thread that plays the local copies.
+    monitor: Mutex<mpsc::Sender<MonitorSound>>,
+    /// To the mixer's playlist voice.
+    playlist_commands: Mutex<ringbuf::HeapProd<playlist::Command>>,
+    /// Id of the last playlist started and its local copy's control.
+    playlist: Mutex<Option<(String, mpsc::Sender<playlist::Local>)>>,
+    /// Set by the voice while a playlist plays.
+    playlist_playing: Arc<AtomicBool>,
+    /// Feeds the stop word spotter while set; dropping it ends the spotter.
+    stop_word: Arc<AtomicBool>,
     pipeline_channels: u16,
     pipeline_sample_rate: u32,
+    /// Decoded sounds in pipeline format, by path. A new pipeline (other
+    /// devices, other format) starts empty.
+    cache: Mutex<HashMap<PathBuf, Arc<Vec<f32>>>>,
+    counters: Arc<Counters>,
+    /// (time, captured, rendered, clipped) at the previous `metrics()` call.
+    last_read: Mutex<(Instant, u64, u64, u64)>,
 }
 
 // SAFETY: AudioPipeline is created and dropped on the main thread.
@@ -326,28 +949,39 @@ impl AudioPipeline {
         output_device_name: &str,
         mic_vol: f32,
         sound_vol: f32,
+        processing: &[ProcessingStage],
+        stop_word: bool,
+        on_stop_word: Box<dyn Fn() + Send>,
     ) -> Result<Self> {
         // Find devices
         let input_dev = find_input_device(input_device_name)
             .context(format!("Input device not found: {}", input_device_name))?;
-        let output_dev = find_output_device(output_device_name)
-            .context(format!("Output device not found: {}", output_device_name))?;
+        let to_sink = output_device_name == virtual_sink::NAME;
+        if to_sink {
+            virtual_sink::create()?;
+        } else {
+            virtual_sink::remove();
+        }
+        let output_dev = if to_sink {
+            virtual_sink::ALSA_DEVICES.iter().find_map(|name| find_output_device(name))
+                .context("No PulseAudio/PipeWire ALSA output to reach the soundboard sink")?
+        } else {
+            find_output_device(output_device_name)
+                .context(format!("Output device not found: {}", output_device_name))?
+        };
 
         info!(
             "[audio] Starting pipeline: input={} output={}",
             input_device_name, output_device_name
         );
 
-        // Get input config
-        let input_config = input_dev
-            .default_input_config()
-            .context("No default input config")?;
+        let input_config = input_config(&input_dev)?;
         let channels = input_config.channels();
         let sample_rate = input_config.sample_rate().0;
 
         info!(
-            "[audio] Input: {}ch @ {}Hz",
-            channels, sample_rate
+            "[audio] Input: {}ch @ {}Hz ({:?})",
+            channels, sample_rate, input_config.sample_format()
         );
 
         // Ring buffer: ~1 second of mic audio
@@ -359,142 +993,359 @@ impl AudioPipeline {
         let sound_buf_size = (sample_rate as usize) * (channels as usize) * 30;
         let sound_rb = HeapRb::<f32>::new(sound_buf_size);
         let (sound_producer, sound_consumer) = sound_rb.split();
+        let (cut_producer, cuts) = HeapRb::<Span>::new(16).split();
+        let fade_len = (RELEASE_FADE.as_secs_f32() * sample_rate as f32) as u64 * channels as u64;
+        let (playlist_commands, playlist_consumer) = HeapRb::<playlist::Command>::new(8).split();
+        let playlist_playing = Arc::new(AtomicBool::new(false));
+
+        // Mic tap for the stop word spotter: ~1 second, filled only while enabled
+        let (mut tap_producer, tap) = HeapRb::<f32>::new(buf_size).split();
+        let stop_word = Arc::new(AtomicBool::new(stop_word));
+        let tap_enabled = Arc::clone(&stop_word);
+        stopword::spawn(tap, channels, sample_rate, Arc::downgrade(&stop_word), on_stop_word);
 
         // Shared volumes (lock-free via AtomicU32)
         let mic_volume = Arc::new(AtomicU32::new(mic_vol.to_bits()));
+        let input_gain = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+        let callback_gain = Arc::clone(&input_gain);
+        let line_in_volume = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+        let (line_in_control, line_in) = line_in::new(channels, sample_rate, Arc::clone(&line_in_volume));
         let sound_volume = Arc::new(AtomicU32::new(sound_vol.to_bits()));
+        let stage_control = Arc::new(StageControl::new(processing));
+        let counters = Arc::new(Counters::default());
+        let (input_counters, error_counters) = (Arc::clone(&counters), Arc::clone(&counters));
 
         // cpal input stream → ring buffer
-        let input_stream = input_dev
-            .build_input_stream(
-                &input_config.into(),
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    for &sample in data {
-                        let _ = producer.try_push(sample);
-                    }
-                },
-                move |err| {
-                    error!("[audio] Input stream error: {}", err);
-                },
-                None,
-            )
-            .context("Failed to build input stream")?;
+        let input_stream = build_input_stream(
+            &input_dev,
+            &input_config,
+            move |data: &[f32]| {
+                let gain = f32::from_bits(callback_gain.load(Ordering::Relaxed));
+                let pushed = producer.push_iter(data.iter().map(|s| s * gain));
+                if tap_enabled.load(Ordering::Relaxed) {
+                    tap_producer.push_iter(data.iter().map(|s| s * gain));
+                }
+                let c = &input_counters;
+                let (peak, clipped) = measure_input(data, gain);
+                c.input_peak.fetch_max(peak.to_bits(), Ordering::Relaxed);
+                c.clipped.fetch_add(clipped, Ordering::Relaxed);
+                c.captured.fetch_add(data.len() as u64, Ordering::Relaxed);
+                c.dropped_mic.fetch_add((data.len() - pushed) as u64, Ordering::Relaxed);
+                c.mic_fill.store(producer.occupied_len() as u64, Ordering::Relaxed);
+            },
+            move |err| {
+                error!("[audio] Input stream error: {}", err);
+                error_counters.stream_errors.fetch_add(1, Ordering::Relaxed);
+                *error_counters.last_error.lock().unwrap() = Some(err.to_string());
+            },
+        )
+        .context("Failed to build input stream")?;
 
         input_stream.play().context("Failed to start input stream")?;
 
         // rodio output stream on the selected output device
+        let opening = to_sink.then(|| virtual_sink::OPENING.lock().unwrap());
+        let streams_before = to_sink.then(virtual_sink::own_streams);
         let (output_stream, output_handle) = OutputStream::try_from_device(&output_dev)
             .context("Failed to open output stream on selected device")?;
 
         // Create MicSource that mixes mic + sound and play through a Sink (infinite)
         let mic_source = MicSource {
             consumer,
-            sound_consumer,
+            graph: Graph::new(Arc::clone(&stage_control), channels, sample_rate),
+            sounds: SoundStream::new(sound_consumer, cuts, fade_len),
+            playlist: PlaylistVoice::new(playlist_consumer, channels, fade_len, Arc::clone(&playlist_playing)),
+            line_in,
             channels,
             sample_rate,
             volume: Arc::clone(&mic_volume),
             sound_volume: Arc::clone(&sound_volume),
+            counters: Arc::clone(&counters),
         };
 
         let mic_sink = Sink::try_new(&output_handle)
             .context("Failed to create mic sink")?;
         mic_sink.append(mic_source);
 
+        if let Some(before) = streams_before {
+            virtual_sink::claim_streams(&before)?;
+        }
+        drop(opening);
+
         info!("[audio] Pipeline started successfully");
 
         Ok(Self {
             _input_stream: input_stream,
             _output_stream: output_stream,
             mic_volume,
+            input_gain,
+            line_in: Mutex::new(line_in_control),
+            line_in_volume,
             sound_volume,
+            stage_control,
             _mic_sink: mic_sink,
-            sound_producer: Mutex::new(sound_producer),
+            sound_queue: Mutex::new(SoundQueue { producer: sound_producer, pushed: 0 }),
+            cut_producer: Mutex::new(cut_producer),
+            held: Mutex::new(Default::default()),
+            preview: Mutex::new(None),
+            playing: Mutex::new(Vec::new()),
+            monitor: Mutex::new(spawn_monitor(channels, sample_rate)),
+            playlist_commands: Mutex::new(playlist_commands),
+            playlist: Mutex::new(None),
+            playlist_playing,
+            stop_word,
             pipeline_channels: channels,
             pipeline_sample_rate: sample_rate,
+            cache: Mutex::new(HashMap::new()),
+            counters,
+            last_read: Mutex::new((Instant::now(), 0, 0, 0)),
         })
     }
 
-    pub fn play_sound(&self, path: &Path) -> Result<()> {
-        // Inject into mic stream (mixed with mic → virtual cable → Discord)
-        // Decode, convert to pipeline format, push to sound ring buffer
-        {
-            let file = fs::File::open(path)
-                .context(format!("Cannot open sound: {}", path.display()))?;
-            let reader = BufReader::new(file);
-            let source = Decoder::new(reader)
-                .context("Failed to decode audio file")?;
-
-            let src_rate = source.sample_rate();
-            let src_channels = source.channels();
-            let dst_rate = self.pipeline_sample_rate;
-            let dst_channels = self.pipeline_channels;
-
-            // Collect all samples as f32 (normalized to [-1, 1])
-            let raw: Vec<f32> = source.convert_samples::<f32>().collect();
-
-            // Channel conversion
-            let chan_converted: Vec<f32> = if src_channels == 2 && dst_channels == 1 {
-                raw.chunks(2)
-                    .map(|c| (c[0] + c.get(1).copied().unwrap_or(0.0)) / 2.0)
-                    .collect()
-            } else if src_channels == 1 && dst_channels == 2 {
-                raw.iter().flat_map(|&s| [s, s]).collect()
-            } else {
-                raw
-            };
+    /// Sounds assigned to keys are preloaded; anything else (previews) is decoded now.
+    fn samples(&self, path: &Path) -> Result<Arc<Vec<f32>>> {
+        let cached = self.cache.lock().unwrap().get(path).cloned();
+        match cached {
+            Some(samples) => Ok(samples),
+            None => self.decode(path),
+        }
+    }
 
-            // Sample rate conversion (linear interpolation)
-            let resampled = if src_rate != dst_rate {
-                let ratio = src_rate as f64 / dst_rate as f64;
-                let out_len = (chan_converted.len() as f64 / ratio) as usize;
-                let mut out = Vec::with_capacity(out_len);
-                for i in 0..out_len {
-                    let src_pos = i as f64 * ratio;
-                    let idx = src_pos as usize;
-                    let frac = (src_pos - idx as f64) as f32;
-                    let s0 = chan_converted.get(idx).copied().unwrap_or(0.0);
-                    let s1 = chan_converted.get(idx + 1).copied().unwrap_or(s0);
-                    out.push(s0 + (s1 - s0) * frac);
-                }
-                out
-            } else {
-                chan_converted
-            };
+    /// Queue a sound into the mic stream (mic → virtual cable → Discord).
+    fn inject(&self, samples: &[f32], gain: f32) -> Span {
+        let mut queue = self.sound_queue.lock().unwrap();
+        let start = queue.pushed;
+        let pushed = queue.producer.push_iter(samples.iter().map(|s| s * gain)) as u64;
+        queue.pushed += pushed;
+        self.counters.dropped_sound.fetch_add(samples.len() as u64 - pushed, Ordering::Relaxed);
+        info!(
+            "[audio] Injected {} samples into mic stream ({}ch @ {}Hz)",
+            pushed, self.pipeline_channels, self.pipeline_sample_rate
+        );
+        Span { start, end: queue.pushed }
+    }
 
-            // Push to sound ring buffer (MicSource will mix it with mic)
-            if let Ok(mut prod) = self.sound_producer.lock() {
-                for &sample in &resampled {
-                    let _ = prod.try_push(sample);
+    /// Send a sound where `route` says: its span in the mic mix and the stop
+    /// flag of its local copy, for whichever it went to.
+    fn route(&self, samples: Arc<Vec<f32>>, gain: f32, route: SoundRoute) -> (Option<Span>, Option<Arc<AtomicBool>>) {
+        let span = route.cable().then(|| self.inject(&samples, gain));
+        let stop = route.monitor().then(|| self.play_locally(samples, gain));
+        (span, stop)
+    }
+
+    /// Fade out a held sound or preview, locally and in the mic mix.
+    fn cut(&self, held: Held) {
+        if let Some(stop) = held.stop {
+            stop.store(true, Ordering::Relaxed);
+        }
+        let Some(span) = held.span else { return };
+        if self.cut_producer.lock().unwrap().try_push(span).is_err() {
+            warn!("[audio] Too many sounds cut at once, one plays out");
+        }
+    }
+
+    /// Also play through the default output (headphones) so the user hears it.
+    /// Setting the returned flag fades the sound out early.
+    fn play_locally(&self, samples: Arc<Vec<f32>>, gain: f32) -> Arc<AtomicBool> {
+        let volume = f32::from_bits(self.sound_volume.load(Ordering::Relaxed)) * gain;
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut playing = self.playing.lock().unwrap();
+        // Only finished sounds' flags have no other owner
+        playing.retain(|s| Arc::strong_count(s) > 1);
+        playing.push(Arc::clone(&stop));
+        let sound = MonitorSound { samples, volume, stop: Arc::clone(&stop) };
+        if self.monitor.lock().unwrap().send(sound).is_err() {
+            warn!("[audio] Monitor thread is gone, sound not played locally");
+        }
+        stop
+    }
+
+    /// Decode a sound file into pipeline format (channels + sample rate).
+    fn decode(&self, path: &Path) -> Result<Arc<Vec<f32>>> {
+        Ok(Arc::new(decode_to(path, self.pipeline_channels, self.pipeline_sample_rate)?))
+    }
+}
+
+impl SoundOutput for AudioPipeline {
+    /// Decodes the paths not already cached.
+    fn preload(&self, paths: &[PathBuf]) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.retain(|p, _| paths.contains(p));
+        for path in paths {
+            if cache.contains_key(path) {
+                continue;
+            }
+            match self.decode(path) {
+                Ok(samples) => {
+                    cache.insert(path.clone(), samples);
                 }
-                info!(
-                    "[audio] Injected {} samples into mic stream ({}ch @ {}Hz)",
-                    resampled.len(), dst_channels, dst_rate
-                );
+                Err(e) => warn!("[audio] Preload failed for {}: {e:#}", path.display()),
             }
         }
+        info!("[audio] {} sound(s) preloaded", cache.len());
+    }
 
-        // Also play through default output (headphones) so the user hears it
-        let vol = f32::from_bits(self.sound_volume.load(Ordering::Relaxed));
-        let path_clone = path.to_path_buf();
-        std::thread::spawn(move || {
-            let Ok((_stream, handle)) = OutputStream::try_default() else { return; };
-            let Ok(sink) = Sink::try_new(&handle) else { return; };
-            let Ok(file) = fs::File::open(&path_clone) else { return; };
-            let reader = BufReader::new(file);
-            let Ok(source) = Decoder::new(reader) else { return; };
-            sink.set_volume(vol);
-            sink.append(source);
-            sink.sleep_until_end();
-        });
+    fn play_sound(&self, path: &Path, gain: f32, route: SoundRoute) -> Result<()> {
+        let samples = self.samples(path)?;
+        self.route(samples, gain, route);
+        Ok(())
+    }
 
+    fn format(&self) -> (u16, u32) {
+        (self.pipeline_channels, self.pipeline_sample_rate)
+    }
+
+    fn play_trimmed(&self, path: &Path, _trim: &Trim, samples: Vec<f32>, route: SoundRoute) {
+        self.stop_preview();
+        let (span, stop) = self.route(Arc::new(samples), 1.0, route);
+        *self.preview.lock().unwrap() = Some(Held { span, stop });
+        info!("[audio] Previewing {}", path.display());
+    }
+
+    fn stop_preview(&self) {
+        if let Some(preview) = self.preview.lock().unwrap().take() {
+            self.cut(preview);
+        }
+    }
+
+    fn play_held(&self, key_index: usize, path: &Path, gain: f32, route: SoundRoute) -> Result<()> {
+        // A new press replaces a sound the key still holds
+        self.stop_held(key_index);
+        let samples = self.samples(path)?;
+        let (span, stop) = self.route(samples, gain, route);
+        self.held.lock().unwrap()[key_index] = Some(Held { span, stop });
         Ok(())
     }
 
-    pub fn set_mic_volume(&self, vol: f32) {
+    fn stop_held(&self, key_index: usize) {
+        if let Some(held) = self.held.lock().unwrap()[key_index].take() {
+            self.cut(held);
+        }
+    }
+
+    fn stop_all(&self) {
+        self.stop_playlist();
+        self.held.lock().unwrap().iter_mut().for_each(|h| *h = None);
+        *self.preview.lock().unwrap() = None;
+        for stop in self.playing.lock().unwrap().drain(..) {
+            stop.store(true, Ordering::Relaxed);
+        }
+        // One cut over everything queued so far
+        let end = self.sound_queue.lock().unwrap().pushed;
+        if self.cut_producer.lock().unwrap().try_push(Span { start: 0, end }).is_err() {
+            warn!("[audio] Too many cuts at once, queued sounds play out");
+        }
+    }
+
+    fn play_playlist(&self, id: &str, sounds: &[(PathBuf, SoundRoute)], loop_last: bool, gain: f32) -> Result<()> {
+        if sounds.is_empty() {
+            anyhow::bail!("Playlist {id} has no sounds to play");
+        }
+        let items = sounds.iter()
+            .map(|(path, route)| Ok(PlaylistItem { samples: self.samples(path)?, to_mic: route.cable() }))
+            .collect::<Result<Vec<_>>>()?;
+        let local: Vec<_> = items.iter().map(|item| Arc::clone(&item.samples)).collect();
+        if self.playlist_commands.lock().unwrap().try_push(playlist::Command::Start { items, loop_last, gain }).is_err() {
+            anyhow::bail!("Too many playlist commands at once");
+        }
+        let volume = f32::from_bits(self.sound_volume.load(Ordering::Relaxed)) * gain;
+        let control = playlist::play_locally(local, loop_last, self.pipeline_channels, self.pipeline_sample_rate, volume);
+        // Replacing the old control stops the old local copy
+        *self.playlist.lock().unwrap() = Some((id.to_string(), control));
+        info!("[audio] Playlist {} started ({} sounds)", id, sounds.len());
+        Ok(())
+    }
+
+    fn skip_playlist(&self) {
+        let _ = self.playlist_commands.lock().unwrap().try_push(playlist::Command::Skip);
+        if let Some((_, ref local)) = *self.playlist.lock().unwrap() {
+            let _ = local.send(playlist::Local::Skip);
+        }
+    }
+
+    fn stop_playlist(&self) {
+        let _ = self.playlist_commands.lock().unwrap().try_push(playlist::Command::Stop);
+        if let Some((_, local)) = self.playlist.lock().unwrap().take() {
+            let _ = local.send(playlist::Local::Stop);
+        }
+    }
+
+    fn playing_playlist(&self) -> Option<String> {
+        let playlist = self.playlist.lock().unwrap();
+        playlist.as_ref().filter(|_| self.playlist_playing.load(Ordering::Relaxed)).map(|(id, _)| id.clone())
+    }
+
+    fn is_playing(&self) -> bool {
+        // Local copies drop their clone of the flag when they finish
+        self.playlist_playing.load(Ordering::Relaxed)
+            || self.sound_queue.lock().unwrap().producer.occupied_len() > 0
+            || self.playing.lock().unwrap().iter().any(|s| Arc::strong_count(s) > 1 && !s.load(Ordering::Relaxed))
+    }
+
+    fn set_stop_word(&self, enabled: bool) {
+        self.stop_word.store(enabled, Ordering::Relaxed);
+    }
+
+    fn set_mic_volume(&self, vol: f32) {
         self.mic_volume.store(vol.to_bits(), Ordering::Relaxed);
     }
 
-    pub fn set_sound_volume(&self, vol: f32) {
+    fn set_second_input(&self, device: Option<&str>) -> Result<()> {
+        self.line_in.lock().unwrap().open(device)
+    }
+
+    fn set_second_input_volume(&self, vol: f32) {
+        self.line_in_volume.store(vol.to_bits(), Ordering::Relaxed);
+    }
+
+    fn set_input_gain(&self, gain_db: f32) {
+        self.input_gain.store(dsp::db_to_lin(gain_db).to_bits(), Ordering::Relaxed);
+    }
+
+    fn set_sound_volume(&self, vol: f32) {
         self.sound_volume.store(vol.to_bits(), Ordering::Relaxed);
     }
+
+    fn set_processing(&self, stages: &[ProcessingStage]) {
+        self.stage_control.set(stages);
+    }
+
+    fn set_agc(&self, agc: &AgcSettings) {
+        self.stage_control.set_agc(agc);
+    }
+
+    fn metrics(&self) -> Option<AudioMetrics> {
+        let c = &self.counters;
+        let (captured, rendered) = (c.captured.load(Ordering::Relaxed), c.rendered.load(Ordering::Relaxed));
+        let mut last = self.last_read.lock().unwrap();
+        let secs = last.0.elapsed().as_secs_f32().max(f32::EPSILON);
+        let rate = |now: u64, before: u64| (now - before) as f32 / self.pipeline_channels as f32 / secs;
+        let (input_rate_hz, output_rate_hz) = (rate(captured, last.1), rate(rendered, last.2));
+        let clipped = c.clipped.load(Ordering::Relaxed);
+        let clipping = clipped > last.3;
+        *last = (Instant::now(), captured, rendered, clipped);
+
+        // Same size as the ring built in `start`
+        let mic_capacity = self.pipeline_sample_rate as f32 * self.pipeline_channels as f32;
+        let sound_buffer_fill = {
+            let queue = self.sound_queue.lock().unwrap();
+            queue.producer.occupied_len() as f32 / queue.producer.capacity().get() as f32
+        };
+        Some(AudioMetrics {
+            channels: self.pipeline_channels,
+            sample_rate: self.pipeline_sample_rate,
+            mic_buffer_fill: c.mic_fill.load(Ordering::Relaxed) as f32 / mic_capacity,
+            sound_buffer_fill,
+            dropped_mic_samples: c.dropped_mic.load(Ordering::Relaxed),
+            dropped_sound_samples: c.dropped_sound.load(Ordering::Relaxed),
+            underruns: c.underruns.load(Ordering::Relaxed),
+            input_rate_hz,
+            output_rate_hz,
+            stream_errors: c.stream_errors.load(Ordering::Relaxed),
+            last_stream_error: c.last_error.lock().unwrap().clone(),
+            input_peak: f32::from_bits(c.input_peak.swap(0, Ordering::Relaxed)),
+            clipped_samples: clipped,
+            clipping,
+        })
+    }
 }
@@ -0,0 +1,54 @@
+// Brightness calibration curve.
+//
+// The Deck-8's LEDs are far from linear: the bottom of the V range barely
+// lights up and the top is glaring. Every V we send (per-key colors and the
+// RGB matrix brightness) goes through `settings.brightness_curve` so the UI
+// sliders map to perceptually even steps. The curve lives in a static because
+// the device helpers that need it don't have the app state at hand. So does
+// the dim level of the LED rules (rules.rs), which scales per-key colors only:
+// the matrix brightness is read back from the device and saved as the user's.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+use crate::protocol::HsvColor;
+use crate::state::BrightnessCurve;
+
+static CURVE: RwLock<BrightnessCurve> = RwLock::new(BrightnessCurve::LINEAR);
+static DIM: AtomicU8 = AtomicU8::new(100);
+
+/// Install the curve used by every later device write.
+pub fn set(curve: BrightnessCurve) {
+    *CURVE.write().unwrap() = curve;
+}
+
+/// Map a UI value (0–255) to the value sent to the device. 0 stays off.
+pub fn apply(v: u8) -> u8 {
+    if v == 0 {
+        return 0;
+    }
+    let c = *CURVE.read().unwrap();
+    let t = (v as f32 / 255.0).powf(c.gamma);
+    (c.min as f32 + (c.max as f32 - c.min as f32) * t).round() as u8
+}
+
+/// Scale later key colors to `percent` (100 = as set). True if it changed.
+pub fn set_dim(percent: u8) -> bool {
+    DIM.swap(percent, Ordering::Relaxed) != percent
+}
+
+/// `color` dimmed, with its V run through the curve.
+pub fn key_color(color: &HsvColor) -> HsvColor {
+    let v = color.v as u32 * DIM.load(Ordering::Relaxed) as u32 / 100;
+    // Dimmed, not off
+    let v = if color.v > 0 { (v as u8).max(1) } else { 0 };
+    HsvColor { v: apply(v), ..*color }
+}
+
+/// Inverse of `apply`, for values read back from the device.
+pub fn invert(raw: u8) -> u8 {
+    if raw == 0 {
+        return 0;
+    }
+    (1..=255).find(|&v| apply(v) >= raw).unwrap_or(255)
+}
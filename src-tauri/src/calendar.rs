@@ -0,0 +1,529 @@
+// Calendar meetings for the `CalendarMeeting` slot binding: the key lights
+// `lead_min` minutes before a meeting and stays lit until it ends, and
+// pressing it can open the meeting's link (Teams, Zoom, Meet, Webex).
+//
+// Calendars come in as an ICS address: Outlook ("Publish a calendar") and
+// Google Calendar ("Secret address in iCal format") both give one, so no
+// account sign-in is needed. Each address is fetched on a thread of its own
+// every `REFRESH`; the watcher only reads the last copy, a day either side of
+// now. Recurring meetings are expanded for DAILY and WEEKLY rules (INTERVAL,
+// BYDAY, COUNT, UNTIL, EXDATE, moved occurrences); other rules only count
+// their first occurrence. Recurrences are expanded on the event's own clock
+// (its TZID, an IANA or a Windows zone name) and each is converted to local
+// time, so a meeting set in another zone follows that zone's DST; an unknown
+// TZID is taken as local time. All-day, cancelled and "free" events aren't
+// meetings.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use log::{info, warn};
+
+const REFRESH: Duration = Duration::from_secs(5 * 60);
+/// Retry a failed fetch sooner than a good one.
+const RETRY: Duration = Duration::from_secs(60);
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Recurrences expanded per event, to bound a years-old daily standup.
+const MAX_OCCURRENCES: usize = 20_000;
+/// Hosts whose links are meeting links, for events that only mention one in
+/// their location or description.
+const MEETING_HOSTS: [&str; 5] = ["teams.microsoft.com", "zoom.us", "meet.google.com", "webex.com", "whereby.com"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub summary: String,
+    /// Local time.
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub link: Option<String>,
+}
+
+#[derive(Default)]
+struct Feed {
+    occurrences: Vec<Occurrence>,
+    error: Option<String>,
+    /// A fetch has succeeded since the binding was set.
+    loaded: bool,
+    /// When the last fetch finished.
+    attempted: Option<Instant>,
+    fetching: bool,
+}
+
+static FEEDS: Mutex<Option<HashMap<String, Feed>>> = Mutex::new(None);
+
+/// Whether a meeting starts within `lead_min` minutes or is on now. Starts a
+/// fetch when the last copy is stale; false until the first one arrives.
+pub fn in_meeting(url: &str, lead_min: u16) -> Result<bool> {
+    let mut feeds = FEEDS.lock().unwrap();
+    let feed = feeds.get_or_insert_with(HashMap::new).entry(url.to_string()).or_default();
+    let stale_after = if feed.error.is_some() { RETRY } else { REFRESH };
+    if !feed.fetching && feed.attempted.is_none_or(|t| t.elapsed() >= stale_after) {
+        feed.fetching = true;
+        let url = url.to_string();
+        std::thread::spawn(move || fetch(url));
+    }
+    if let (Some(e), false) = (&feed.error, feed.loaded) {
+        return Err(anyhow!("{e}"));
+    }
+    Ok(current(&feed.occurrences, Local::now().naive_local(), lead_min).is_some())
+}
+
+/// Link of the meeting `in_meeting` is lit for (the one on now, else the
+/// next), if it has one.
+pub fn meeting_link(url: &str, lead_min: u16) -> Option<String> {
+    let feeds = FEEDS.lock().unwrap();
+    let feed = feeds.as_ref()?.get(url)?;
+    current(&feed.occurrences, Local::now().naive_local(), lead_min)?.link.clone()
+}
+
+/// Forget the calendars no binding uses anymore.
+pub fn retain(urls: &[&str]) {
+    if let Some(feeds) = FEEDS.lock().unwrap().as_mut() {
+        feeds.retain(|url, _| urls.contains(&url.as_str()));
+    }
+}
+
+/// Open the current meeting's link in the browser (or the meeting app).
+pub fn open_link(url: &str, lead_min: u16) {
+    let Some(link) = meeting_link(url, lead_min) else {
+        info!("[calendar] No meeting link to open");
+        return;
+    };
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    if let Err(e) = std::process::Command::new(opener).arg(&link).spawn() {
+        warn!("[calendar] Failed to open {link}: {e}");
+    }
+}
+
+fn fetch(url: String) {
+    let result = download(&url).map(|ics| {
+        let now = Local::now().naive_local();
+        parse(&ics, now - chrono::Duration::days(1), now + chrono::Duration::days(1))
+    });
+    let mut feeds = FEEDS.lock().unwrap();
+    // Dropped by `retain` while fetching
+    let Some(feed) = feeds.as_mut().and_then(|f| f.get_mut(&url)) else { return };
+    feed.fetching = false;
+    feed.attempted = Some(Instant::now());
+    match result {
+        Ok(occurrences) => {
+            info!("[calendar] {} meetings around now", occurrences.len());
+            feed.occurrences = occurrences;
+            feed.error = None;
+            feed.loaded = true;
+        }
+        // Keep the last good copy and retry sooner
+        Err(e) => {
+            let msg = format!("{e:#}");
+            if feed.error.as_ref() != Some(&msg) {
+                warn!("[calendar] {msg}");
+            }
+            feed.error = Some(msg);
+        }
+    }
+}
+
+fn download(url: &str) -> Result<String> {
+    // webcal:// is https:// that calendar apps open
+    let url = match url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{rest}"),
+        None => url.to_string(),
+    };
+    let agent = ureq::AgentBuilder::new().timeout(FETCH_TIMEOUT).build();
+    let resp = agent.get(&url).call().map_err(|e| anyhow!("GET calendar failed: {e}"))?;
+    resp.into_string().context("Failed to read the calendar")
+}
+
+/// The meeting on at `now`, else the first starting within `lead_min`.
+pub fn current(occurrences: &[Occurrence], now: NaiveDateTime, lead_min: u16) -> Option<&Occurrence> {
+    let lead = chrono::Duration::minutes(lead_min as i64);
+    occurrences.iter()
+        .filter(|o| o.start - lead <= now && now < o.end)
+        .min_by_key(|o| (o.start > now, o.start))
+}
+
+// ── ICS ─────────────────────────────────────────────────────────────────
+
+/// A VEVENT's properties: name → (parameters, value).
+type Properties = Vec<(String, String, String)>;
+
+/// Meetings in an ICS calendar overlapping `from..to`, in local time, by start.
+pub fn parse(ics: &str, from: NaiveDateTime, to: NaiveDateTime) -> Vec<Occurrence> {
+    let events = events(ics);
+    // Moved or edited occurrences of a recurring meeting: (UID, original start)
+    let moved: Vec<(String, NaiveDateTime)> = events.iter()
+        .filter_map(|e| Some((prop(e, "UID")?.1.to_string(), time(prop(e, "RECURRENCE-ID")?)?)))
+        .collect();
+    let mut out = Vec::new();
+    for event in &events {
+        if prop(event, "STATUS").is_some_and(|p| p.1 == "CANCELLED")
+            || prop(event, "TRANSP").is_some_and(|p| p.1 == "TRANSPARENT")
+        {
+            continue;
+        }
+        // All-day events carry a date only
+        let Some((wall_start, zone)) = prop(event, "DTSTART").and_then(wall_time) else { continue };
+        let start = zone.to_local(wall_start);
+        let end = match (prop(event, "DTEND").and_then(time), prop(event, "DURATION").and_then(|p| duration(p.1))) {
+            (Some(end), _) => end,
+            (None, Some(d)) => start + d,
+            (None, None) => start,
+        };
+        let length = end - start;
+        let uid = prop(event, "UID").map_or("", |p| p.1);
+        let exdates: Vec<NaiveDateTime> = event.iter()
+            .filter(|(name, ..)| name == "EXDATE")
+            .flat_map(|(_, params, value)| value.split(',').filter_map(move |v| time((params.as_str(), v))))
+            .collect();
+        let summary = prop(event, "SUMMARY").map_or_else(String::new, |p| unescape(p.1));
+        let link = link(event);
+        let is_override = prop(event, "RECURRENCE-ID").is_some();
+        let starts = match prop(event, "RRULE").filter(|_| !is_override) {
+            Some(rule) => recurrences(wall_start, zone, rule.1, to),
+            None => vec![start],
+        };
+        for s in starts {
+            if s + length <= from || s >= to || exdates.contains(&s) {
+                continue;
+            }
+            if !is_override && moved.iter().any(|(u, at)| u == uid && *at == s) {
+                continue;
+            }
+            out.push(Occurrence { summary: summary.clone(), start: s, end: s + length, link: link.clone() });
+        }
+    }
+    out.sort_by_key(|o| o.start);
+    out
+}
+
+/// Unfold the lines and split the VEVENTs into properties.
+fn events(ics: &str) -> Vec<Properties> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    let mut events = Vec::new();
+    let mut current: Option<Properties> = None;
+    // VALARMs nested in an event have their own DTSTART-less properties
+    let mut depth = 0;
+    for line in lines {
+        match line.as_str() {
+            "BEGIN:VEVENT" => current = Some(Vec::new()),
+            "END:VEVENT" => events.extend(current.take()),
+            l if l.starts_with("BEGIN:") && current.is_some() => depth += 1,
+            l if l.starts_with("END:") && current.is_some() => depth -= 1,
+            l if depth == 0 => {
+                let Some(props) = current.as_mut() else { continue };
+                if let Some(p) = property(l) {
+                    props.push(p);
+                }
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+/// `NAME;PARAM=x:value` → (NAME, PARAM=x, value). The first colon outside
+/// quotes ends the parameters.
+fn property(line: &str) -> Option<(String, String, String)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find(|&(_, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ':' && !quoted
+    })?.0;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.to_ascii_uppercase(), params.to_string(), value.to_string()))
+}
+
+fn prop<'a>(event: &'a Properties, name: &str) -> Option<(&'a str, &'a str)> {
+    event.iter().find(|(n, ..)| n == name).map(|(_, params, value)| (params.as_str(), value.as_str()))
+}
+
+/// The clock a DATE-TIME is written in.
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+    /// Floating, or a TZID we don't know.
+    Local,
+    Utc,
+    Named(Tz),
+}
+
+impl Zone {
+    /// The zone a `TZID` parameter names: an IANA name (also at the end of a
+    /// path, as Mozilla writes them) or a Windows one, as Outlook writes them.
+    fn from_tzid(tzid: &str) -> Self {
+        let tzid = tzid.trim_matches('"');
+        let iana = WINDOWS_ZONES.iter().find(|(windows, _)| *windows == tzid).map_or(tzid, |(_, iana)| *iana);
+        let named = std::iter::once(iana)
+            .chain(iana.match_indices('/').map(|(at, _)| &iana[at + 1..]))
+            .find_map(|name| name.parse::<Tz>().ok());
+        match named {
+            Some(tz) => Self::Named(tz),
+            None => Self::Local,
+        }
+    }
+
+    /// A wall-clock time in this zone as local time. Times skipped by a DST
+    /// change are moved past it, as calendar apps do.
+    fn to_local(self, t: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Self::Local => t,
+            Self::Utc => Utc.from_utc_datetime(&t).with_timezone(&Local).naive_local(),
+            Self::Named(tz) => tz.from_local_datetime(&t).earliest()
+                .or_else(|| tz.from_local_datetime(&(t + chrono::Duration::hours(1))).earliest())
+                .map_or(t, |t| t.with_timezone(&Local).naive_local()),
+        }
+    }
+}
+
+/// A DATE-TIME in local time. `None` for dates (all-day).
+fn time(p: (&str, &str)) -> Option<NaiveDateTime> {
+    wall_time(p).map(|(t, zone)| zone.to_local(t))
+}
+
+/// A DATE-TIME as written, with the zone its clock is in.
+fn wall_time((params, value): (&str, &str)) -> Option<(NaiveDateTime, Zone)> {
+    if params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME") {
+        return None;
+    }
+    let value = value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        return Some((NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?, Zone::Utc));
+    }
+    let t = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let zone = params.split(';').find_map(|p| p.strip_prefix("TZID=")).map_or(Zone::Local, Zone::from_tzid);
+    Some((t, zone))
+}
+
+/// `P1DT2H30M`, `PT45M`, `P1W`.
+fn duration(value: &str) -> Option<chrono::Duration> {
+    let rest = value.trim().strip_prefix('P')?;
+    let mut total = chrono::Duration::zero();
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match unit {
+                    'W' => chrono::Duration::weeks(n),
+                    'D' => chrono::Duration::days(n),
+                    'H' => chrono::Duration::hours(n),
+                    'M' => chrono::Duration::minutes(n),
+                    'S' => chrono::Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(total)
+}
+
+/// Local starts of a recurring event up to `to`, stepped on `zone`'s clock
+/// from `start` in it.
+fn recurrences(start: NaiveDateTime, zone: Zone, rule: &str, to: NaiveDateTime) -> Vec<NaiveDateTime> {
+    let part = |key: &str| rule.split(';').find_map(|p| p.strip_prefix(key)?.strip_prefix('='));
+    let interval: i64 = part("INTERVAL").and_then(|v| v.parse().ok()).filter(|&n| n > 0).unwrap_or(1);
+    let count: Option<usize> = part("COUNT").and_then(|v| v.parse().ok());
+    let until = part("UNTIL").and_then(|v| {
+        time(("", v)).or_else(|| Some(zone.to_local(NaiveDate::parse_from_str(v, "%Y%m%d").ok()?.and_hms_opt(23, 59, 59)?)))
+    });
+    let last = until.map_or(to, |u| u.min(to));
+    let limit = count.unwrap_or(MAX_OCCURRENCES).min(MAX_OCCURRENCES);
+    let mut out = Vec::new();
+    match part("FREQ") {
+        Some("DAILY") => {
+            let mut s = start;
+            while zone.to_local(s) <= last && out.len() < limit {
+                out.push(zone.to_local(s));
+                s += chrono::Duration::days(interval);
+            }
+        }
+        Some("WEEKLY") => {
+            const DAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+            let mut days: Vec<i64> = part("BYDAY").map_or_else(Vec::new, |v| {
+                // "1MO"-style prefixes only mean something for monthly rules
+                v.split(',').filter_map(|d| DAYS.iter().position(|n| d.ends_with(n))).map(|i| i as i64).collect()
+            });
+            if days.is_empty() {
+                days.push(start.weekday().num_days_from_monday() as i64);
+            }
+            days.sort_unstable();
+            let monday = start - chrono::Duration::days(start.weekday().num_days_from_monday() as i64);
+            let mut week = 0;
+            'weeks: loop {
+                for &d in &days {
+                    let s = monday + chrono::Duration::days(week * 7 + d);
+                    if s < start {
+                        continue;
+                    }
+                    let s = zone.to_local(s);
+                    if s > last || out.len() >= limit {
+                        break 'weeks;
+                    }
+                    out.push(s);
+                }
+                week += interval;
+            }
+        }
+        _ => out.push(zone.to_local(start)),
+    }
+    out
+}
+
+/// Windows time zone names (Outlook's TZIDs) and their IANA zones, after
+/// CLDR's windowsZones.
+const WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("Dateline Standard Time", "Etc/GMT+12"),
+    ("UTC-11", "Etc/GMT+11"),
+    ("Aleutian Standard Time", "America/Adak"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("Pacific Standard Time (Mexico)", "America/Tijuana"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Mountain Standard Time (Mexico)", "America/Mazatlan"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("Central America Standard Time", "America/Guatemala"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Central Standard Time (Mexico)", "America/Mexico_City"),
+    ("Canada Central Standard Time", "America/Regina"),
+    ("SA Pacific Standard Time", "America/Bogota"),
+    ("Eastern Standard Time (Mexico)", "America/Cancun"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("US Eastern Standard Time", "America/Indianapolis"),
+    ("Venezuela Standard Time", "America/Caracas"),
+    ("Paraguay Standard Time", "America/Asuncion"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("Central Brazilian Standard Time", "America/Cuiaba"),
+    ("SA Western Standard Time", "America/La_Paz"),
+    ("Pacific SA Standard Time", "America/Santiago"),
+    ("Newfoundland Standard Time", "America/St_Johns"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("SA Eastern Standard Time", "America/Cayenne"),
+    ("Argentina Standard Time", "America/Buenos_Aires"),
+    ("Montevideo Standard Time", "America/Montevideo"),
+    ("UTC-02", "Etc/GMT+2"),
+    ("Azores Standard Time", "Atlantic/Azores"),
+    ("Cape Verde Standard Time", "Atlantic/Cape_Verde"),
+    ("UTC", "Etc/UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik"),
+    ("Morocco Standard Time", "Africa/Casablanca"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("W. Central Africa Standard Time", "Africa/Lagos"),
+    ("Jordan Standard Time", "Asia/Amman"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Middle East Standard Time", "Asia/Beirut"),
+    ("Egypt Standard Time", "Africa/Cairo"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("Syria Standard Time", "Asia/Damascus"),
+    ("South Africa Standard Time", "Africa/Johannesburg"),
+    ("FLE Standard Time", "Europe/Kiev"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("Kaliningrad Standard Time", "Europe/Kaliningrad"),
+    ("Arabic Standard Time", "Asia/Baghdad"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Arab Standard Time", "Asia/Riyadh"),
+    ("Belarus Standard Time", "Europe/Minsk"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("E. Africa Standard Time", "Africa/Nairobi"),
+    ("Iran Standard Time", "Asia/Tehran"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("Azerbaijan Standard Time", "Asia/Baku"),
+    ("Georgian Standard Time", "Asia/Tbilisi"),
+    ("Afghanistan Standard Time", "Asia/Kabul"),
+    ("West Asia Standard Time", "Asia/Tashkent"),
+    ("Ekaterinburg Standard Time", "Asia/Yekaterinburg"),
+    ("Pakistan Standard Time", "Asia/Karachi"),
+    ("India Standard Time", "Asia/Calcutta"),
+    ("Sri Lanka Standard Time", "Asia/Colombo"),
+    ("Nepal Standard Time", "Asia/Katmandu"),
+    ("Central Asia Standard Time", "Asia/Almaty"),
+    ("Bangladesh Standard Time", "Asia/Dhaka"),
+    ("Myanmar Standard Time", "Asia/Rangoon"),
+    ("SE Asia Standard Time", "Asia/Bangkok"),
+    ("N. Central Asia Standard Time", "Asia/Novosibirsk"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("North Asia Standard Time", "Asia/Krasnoyarsk"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("W. Australia Standard Time", "Australia/Perth"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("Cen. Australia Standard Time", "Australia/Adelaide"),
+    ("AUS Central Standard Time", "Australia/Darwin"),
+    ("E. Australia Standard Time", "Australia/Brisbane"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("West Pacific Standard Time", "Pacific/Port_Moresby"),
+    ("Tasmania Standard Time", "Australia/Hobart"),
+    ("Vladivostok Standard Time", "Asia/Vladivostok"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+    ("UTC+12", "Etc/GMT-12"),
+    ("Fiji Standard Time", "Pacific/Fiji"),
+    ("Tonga Standard Time", "Pacific/Tongatapu"),
+    ("Samoa Standard Time", "Pacific/Apia"),
+];
+
+/// The meeting link: the conference property, the URL, else the first
+/// meeting-host link in the location or description. Only https:// links
+/// count: open_link hands it to the OS opener, so an invite's file:// URL or
+/// program path must never get there.
+fn link(event: &Properties) -> Option<String> {
+    let conference = ["X-GOOGLE-CONFERENCE", "X-MICROSOFT-SKYPETEAMSMEETINGURL"].iter()
+        .filter_map(|name| prop(event, name))
+        .map(|p| p.1.trim())
+        .find(|u| u.starts_with("https://"));
+    if let Some(url) = conference {
+        return Some(url.to_string());
+    }
+    let texts: Vec<String> = ["LOCATION", "DESCRIPTION", "URL"].iter()
+        .filter_map(|name| prop(event, name).map(|p| unescape(p.1)))
+        .collect();
+    let links: Vec<&str> = texts.iter()
+        .flat_map(|t| t.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '(' | ')')))
+        .filter(|w| w.starts_with("https://"))
+        .collect();
+    let meeting = links.iter().find(|l| MEETING_HOSTS.iter().any(|h| l.contains(h))).map(|l| l.to_string());
+    meeting.or_else(|| prop(event, "URL").map(|p| p.1.to_string()).filter(|u| u.starts_with("https://")))
+}
+
+/// ICS text escapes: `\n`, `\,`, `\;`, `\\`.
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
@@ -0,0 +1,32 @@
+// Coexisting with VIA and other configurators.
+//
+// Only one program can hold the Deck-8's raw HID interface on some systems
+// (Windows without sharing, macOS exclusive opens). When `connect_device`
+// finds the device but can't open it, `hid::Busy` sets `device_busy` and the
+// UI says another app is using it instead of "not found". With
+// `settings.cooperative` on, this thread keeps trying while it's busy and
+// connects once the other app lets go. `disconnect_device` is the other half:
+// it releases the interface for VIA.
+
+use std::time::Duration;
+
+use log::info;
+use tauri::{AppHandle, Manager};
+
+use crate::state::SharedState;
+
+const RETRY: Duration = Duration::from_secs(3);
+
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(RETRY);
+        let waiting = {
+            let state = app.state::<SharedState>();
+            let st = state.lock().unwrap();
+            st.settings.cooperative && st.device_busy && st.device.is_none()
+        };
+        if waiting && crate::connect_device(app.clone(), app.state::<SharedState>()) {
+            info!("[coexist] Device released by the other app, connected");
+        }
+    });
+}
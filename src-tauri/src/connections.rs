@@ -0,0 +1,159 @@
+// Connection history: when the Deck-8 connected, when it went away and why,
+// so a flaky cable or hub shows up as a pattern instead of a guess.
+//
+// Events are recorded where the connection changes: `connect_device`
+// (connected, a failed attempt, or the previous handle replaced),
+// `disconnect_device` (released) and the heartbeat (lost: a read failed with
+// an I/O error, e.g. the cable was pulled). A handle given up because it
+// stopped acking counts as unresponsive, wherever it was let go. The last
+// `CAPACITY` events are kept in connections.json next to state.json.
+// `get_connection_history` returns them with a summary.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::now_ms;
+
+const CAPACITY: usize = 500;
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EventKind {
+    Connected,
+    /// Let go on request (`disconnect_device`), e.g. for VIA.
+    Released,
+    /// Replaced by a new connect (reconnect button, definitions reload).
+    Resync,
+    /// Given up on after it stopped acking (see watchdog.rs).
+    Unresponsive,
+    /// A read failed with an I/O error: unplugged, or the USB link dropped.
+    Lost,
+    /// A connect found no Deck-8.
+    NotFound,
+    /// A connect found it held by another app.
+    Busy,
+    /// A connect failed otherwise.
+    Failed,
+}
+
+impl EventKind {
+    /// Disconnects nobody asked for.
+    pub fn is_drop(self) -> bool {
+        matches!(self, Self::Unresponsive | Self::Lost)
+    }
+
+    fn is_failure(self) -> bool {
+        matches!(self, Self::NotFound | Self::Busy | Self::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConnectionEvent {
+    /// Unix time (ms).
+    pub at_ms: u64,
+    pub kind: EventKind,
+    /// The error, for failures and lost connections.
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConnectionSummary {
+    /// Unexpected disconnects in the last 24 hours and 7 days.
+    pub drops_day: u32,
+    pub drops_week: u32,
+    /// Average length of the connections that ended in a drop.
+    pub mean_time_to_drop_ms: Option<u64>,
+    /// Start of the current connection, if the last event is a connect.
+    pub connected_since_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionHistory {
+    /// Oldest first.
+    pub events: Vec<ConnectionEvent>,
+    pub summary: ConnectionSummary,
+}
+
+static EVENTS: Mutex<Option<Vec<ConnectionEvent>>> = Mutex::new(None);
+
+fn file() -> Result<PathBuf> {
+    Ok(crate::profile::config_dir()?.join("connections.json"))
+}
+
+fn with_events<T>(f: impl FnOnce(&mut Vec<ConnectionEvent>) -> T) -> T {
+    let mut events = EVENTS.lock().unwrap();
+    let events = events.get_or_insert_with(|| {
+        file().ok()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    });
+    f(events)
+}
+
+/// Append an event and save the history.
+pub fn record(kind: EventKind, detail: Option<String>) {
+    with_events(|events| {
+        if !push(events, ConnectionEvent { at_ms: now_ms(), kind, detail }) {
+            return;
+        }
+        let result = file().and_then(|path| {
+            let json = serde_json::to_string(events).context("Failed to serialize the connection history")?;
+            fs::write(path, json).context("Failed to write the connection history")
+        });
+        if let Err(e) = result {
+            error!("[connections] {e:#}");
+        }
+    });
+}
+
+/// Add an event, dropping the oldest past `CAPACITY`. A failed connect like
+/// the last event isn't added again (cooperative mode retries every few
+/// seconds). False if nothing was added.
+pub fn push(events: &mut Vec<ConnectionEvent>, event: ConnectionEvent) -> bool {
+    if event.kind.is_failure() && events.last().is_some_and(|last| last.kind == event.kind) {
+        return false;
+    }
+    if events.len() == CAPACITY {
+        events.remove(0);
+    }
+    events.push(event);
+    true
+}
+
+pub fn summarize(events: &[ConnectionEvent], now_ms: u64) -> ConnectionSummary {
+    let drops_since = |ms: u64| {
+        events.iter().filter(|e| e.kind.is_drop() && now_ms.saturating_sub(e.at_ms) < ms).count() as u32
+    };
+    let mut connected_at = None;
+    let mut drop_lengths = Vec::new();
+    for event in events {
+        match event.kind {
+            EventKind::Connected => connected_at = Some(event.at_ms),
+            kind if kind.is_failure() => {}
+            kind => {
+                if let (true, Some(at)) = (kind.is_drop(), connected_at) {
+                    drop_lengths.push(event.at_ms.saturating_sub(at));
+                }
+                connected_at = None;
+            }
+        }
+    }
+    ConnectionSummary {
+        drops_day: drops_since(DAY_MS),
+        drops_week: drops_since(7 * DAY_MS),
+        mean_time_to_drop_ms: (!drop_lengths.is_empty())
+            .then(|| drop_lengths.iter().sum::<u64>() / drop_lengths.len() as u64),
+        connected_since_ms: connected_at,
+    }
+}
+
+pub fn history() -> ConnectionHistory {
+    with_events(|events| ConnectionHistory { events: events.clone(), summary: summarize(events, now_ms()) })
+}
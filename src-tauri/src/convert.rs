@@ -0,0 +1,72 @@
+// Streaming format conversion for input callbacks: integer samples to f32,
+// then channel mapping and linear resampling into the pipeline's format, one
+// callback buffer at a time. The position between input frames carries over
+// from one buffer to the next, so buffer boundaries don't click.
+
+use rodio::cpal::{FromSample, Sample};
+
+/// `data` as f32 in `buf`, which is reused so a callback stops allocating
+/// once it has seen its largest buffer.
+pub fn to_f32<'a, T: Sample>(data: &[T], buf: &'a mut Vec<f32>) -> &'a [f32]
+where
+    f32: FromSample<T>,
+{
+    buf.clear();
+    buf.extend(data.iter().map(|&s| s.to_sample::<f32>()));
+    buf
+}
+
+pub struct Converter {
+    in_channels: usize,
+    out_channels: usize,
+    /// Input frames per output frame.
+    step: f64,
+    /// Where the next output frame falls between `prev` (0) and the frame
+    /// being read (1).
+    pos: f64,
+    prev: Vec<f32>,
+    cur: Vec<f32>,
+}
+
+impl Converter {
+    pub fn new(in_channels: u16, in_rate: u32, out_channels: u16, out_rate: u32) -> Self {
+        let out_channels = out_channels.max(1) as usize;
+        Self {
+            in_channels: in_channels.max(1) as usize,
+            out_channels,
+            step: in_rate.max(1) as f64 / out_rate.max(1) as f64,
+            pos: 0.0,
+            prev: vec![0.0; out_channels],
+            cur: vec![0.0; out_channels],
+        }
+    }
+
+    /// Most samples `push` can produce from `len` input samples.
+    pub fn max_output(&self, len: usize) -> usize {
+        ((len / self.in_channels) as f64 / self.step).ceil() as usize * self.out_channels + self.out_channels
+    }
+
+    /// Convert interleaved `data`, handing each output sample to `out`.
+    /// Allocation-free, for the audio thread.
+    pub fn push(&mut self, data: &[f32], mut out: impl FnMut(f32)) {
+        for frame in data.chunks_exact(self.in_channels) {
+            // Down to mono averages; otherwise channels repeat round-robin
+            for (c, sample) in self.cur.iter_mut().enumerate() {
+                *sample = if self.out_channels == 1 {
+                    frame.iter().sum::<f32>() / frame.len() as f32
+                } else {
+                    frame[c % frame.len()]
+                };
+            }
+            while self.pos <= 1.0 {
+                let t = self.pos as f32;
+                for (p, c) in self.prev.iter().zip(&self.cur) {
+                    out(p + (c - p) * t);
+                }
+                self.pos += self.step;
+            }
+            self.pos -= 1.0;
+            std::mem::swap(&mut self.prev, &mut self.cur);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// Per-key sound cooldown.
+//
+// A key's sound doesn't retrigger until `KeyConfig::sound_cooldown_ms` has
+// passed since it last played; the key's slot change and other actions still
+// run (`cooldown_ms` is the one that drops the whole press). With
+// `dim_on_cooldown` the LED shows at reduced brightness until the sound is
+// ready again: `shade` is applied wherever a key color is written, and a
+// thread rewrites the key once the cooldown ends.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::protocol::HsvColor;
+use crate::state::{AppState, KeyConfig, SharedState};
+
+/// Brightness (V) divisor while cooling down.
+const DIM_DIVISOR: u8 = 4;
+
+/// When each key's sound may play again.
+static READY_AT: Mutex<[Option<Instant>; 8]> = Mutex::new([None; 8]);
+
+/// Whether the key's sound may play at `now`; if so its cooldown starts.
+pub fn try_play(key_index: usize, cooldown_ms: u64, now: Instant) -> bool {
+    let mut ready_at = READY_AT.lock().unwrap();
+    if ready_at[key_index].is_some_and(|t| now < t) {
+        return false;
+    }
+    ready_at[key_index] = (cooldown_ms > 0).then(|| now + Duration::from_millis(cooldown_ms));
+    true
+}
+
+fn cooling(key_index: usize, now: Instant) -> bool {
+    READY_AT.lock().unwrap()[key_index].is_some_and(|t| now < t)
+}
+
+/// The color to show for a key: dimmed while its sound cools down.
+pub fn shade(key_index: usize, key: &KeyConfig, color: HsvColor) -> HsvColor {
+    if key.dim_on_cooldown && cooling(key_index, Instant::now()) {
+        HsvColor { v: color.v / DIM_DIVISOR, ..color }
+    } else {
+        color
+    }
+}
+
+/// Show the dim and undo it when the cooldown ends. `fading` when the key just
+/// started a fade, which shades its own frames. Called with the state lock
+/// held, right after `try_play`.
+pub fn dim<R: Runtime>(app: &AppHandle<R>, st: &AppState, key_index: usize, fading: bool) {
+    let key = &st.keys[key_index];
+    if !key.dim_on_cooldown || key.sound_cooldown_ms == 0 {
+        return;
+    }
+    let Some(ref dev) = st.device else { return };
+    if !fading {
+        crate::apply_key_to_device(dev, key_index as u8, key);
+    }
+    let (app, cooldown) = (app.clone(), Duration::from_millis(key.sound_cooldown_ms));
+    std::thread::spawn(move || {
+        std::thread::sleep(cooldown);
+        let state = app.state::<SharedState>();
+        let st = state.lock().unwrap();
+        if let Some(ref dev) = st.device {
+            crate::apply_key_to_device(dev, key_index as u8, &st.keys[key_index]);
+        }
+    });
+}
@@ -0,0 +1,121 @@
+// Device definition: the parts of the Deck-8's firmware interface that a
+// custom QMK fork may change (matrix size, LED wiring, custom channel IDs).
+//
+// The stock firmware's definition is built in. `device-definition.json` in the
+// config dir overrides any of its fields; it's read at startup and again by
+// the `reload_definitions` command, so no rebuild is needed. Like the
+// brightness curve it lives in a static, because the report builders don't
+// have the app state at hand.
+//
+// The app still drives exactly 8 keys: the matrix must have room for them
+// (the first 8 positions in row-major order) and `led_order` must be a
+// permutation of the 8 LED indices.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use anyhow::{bail, Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::RGB_MATRIX_CHANNEL;
+
+const FILE_NAME: &str = "device-definition.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeviceDefinition {
+    pub rows: u8,
+    pub cols: u8,
+    /// LED index of each key, in matrix order.
+    pub led_order: [u8; 8],
+    /// VIA channel of the per-key LED commands (`id_custom_channel`).
+    pub per_key_channel: u8,
+    pub cmd_enable_override: u8,
+    pub cmd_set_brightness: u8,
+    pub cmd_set_color: u8,
+    /// Color and override in one report, on firmware reporting `set_hsv`.
+    pub cmd_set_hsv: u8,
+    /// Per-key channel value answering the features handshake.
+    pub val_features: u8,
+    pub rgb_matrix_channel: u8,
+}
+
+impl DeviceDefinition {
+    /// Stock Deck-8 firmware: 2×4 matrix, bottom row's LEDs snake back.
+    pub const DECK8: Self = Self {
+        rows: 2,
+        cols: 4,
+        led_order: [0, 1, 2, 3, 7, 6, 5, 4],
+        per_key_channel: 0x00,
+        cmd_enable_override: 0x01,
+        cmd_set_brightness: 0x02,
+        cmd_set_color: 0x03,
+        cmd_set_hsv: 0x04,
+        val_features: 0x10,
+        rgb_matrix_channel: RGB_MATRIX_CHANNEL,
+    };
+
+    /// Matrix position (row, col) of a key in matrix order.
+    pub fn matrix_position(&self, keymap_idx: u8) -> (u8, u8) {
+        (keymap_idx / self.cols, keymap_idx % self.cols)
+    }
+
+    /// Matrix-order index of the key wired to `led_idx`.
+    pub fn keymap_index(&self, led_idx: usize) -> usize {
+        self.led_order.iter().position(|&l| l as usize == led_idx).unwrap_or(led_idx)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.cols == 0 || (self.rows as usize * self.cols as usize) < 8 {
+            bail!("A {}x{} matrix can't hold the 8 keys", self.rows, self.cols);
+        }
+        let mut order = self.led_order;
+        order.sort_unstable();
+        if order != [0, 1, 2, 3, 4, 5, 6, 7] {
+            bail!("led_order must list each LED index 0-7 once, got {:?}", self.led_order);
+        }
+        Ok(())
+    }
+}
+
+impl Default for DeviceDefinition {
+    fn default() -> Self {
+        Self::DECK8
+    }
+}
+
+static ACTIVE: RwLock<DeviceDefinition> = RwLock::new(DeviceDefinition::DECK8);
+
+/// The definition every device report is built with.
+pub fn current() -> DeviceDefinition {
+    *ACTIVE.read().unwrap()
+}
+
+/// Parse a definition file; missing fields keep the stock values.
+pub fn parse(json: &str) -> Result<DeviceDefinition> {
+    let def: DeviceDefinition = serde_json::from_str(json)?;
+    def.validate()?;
+    Ok(def)
+}
+
+pub fn path() -> Result<PathBuf> {
+    Ok(crate::profile::config_dir()?.join(FILE_NAME))
+}
+
+/// Re-read the definition file (the built-in one when there is none) and
+/// install it. On error the active definition is left as it was.
+pub fn reload() -> Result<DeviceDefinition> {
+    let path = path()?;
+    let def = if path.exists() {
+        let json = fs::read_to_string(&path).context("Failed to read the device definition")?;
+        let def = parse(&json).context("Invalid device definition")?;
+        info!("[definition] Loaded {}: {:?}", path.display(), def);
+        def
+    } else {
+        DeviceDefinition::DECK8
+    };
+    *ACTIVE.write().unwrap() = def;
+    Ok(def)
+}
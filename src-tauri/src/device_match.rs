@@ -0,0 +1,101 @@
+// Finding saved audio devices again when their names drift between sessions.
+//
+// cpal has no stable device id, so a device is known by its name. Windows
+// numbers duplicate endpoints ("Microphone (2- USB Audio)"), and the number
+// comes and goes with plugging order; drivers add or drop a word. A saved name
+// that's no longer listed is compared again by its key (the name without the
+// numbering, case and punctuation); one device with the same key is taken
+// over. Names that only share most of their words ("Jabra Evolve 65" and
+// "Jabra Evolve 75") can be different devices, so those are offered to the
+// user rather than switched to, and so is anything for a virtual cable, where
+// "CABLE Input" and "CABLE-A Input" route audio to different places.
+
+use std::collections::HashSet;
+
+/// Words two names must share, as a fraction of all their words, to be offered
+/// as the same device. Above half, so one differing word in a short name isn't
+/// enough.
+const MIN_OVERLAP: f32 = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceMatch {
+    /// The saved name is listed as is.
+    Exact,
+    /// One device differs only by numbering; it goes by this name now.
+    Renamed(String),
+    /// Devices that may be it; the user picks.
+    Ambiguous(Vec<String>),
+    Missing,
+}
+
+/// Check if a device name looks like a virtual audio cable.
+pub fn is_virtual_cable(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    name == crate::virtual_sink::NAME || lower.contains("cable") || lower.contains("blackhole") || lower.contains("virtual")
+        || lower.contains("voicemeeter") || lower.contains("wave link")
+}
+
+/// Name with the OS numbering ("2- "), case and punctuation taken out.
+pub fn device_key(name: &str) -> String {
+    words(name).join(" ")
+}
+
+fn words(name: &str) -> Vec<String> {
+    let lower = name.to_lowercase();
+    let mut words = Vec::new();
+    for token in lower.split(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        // "2-" right before the device name is the endpoint number
+        if token.len() > 1 && token.ends_with('-') && token[..token.len() - 1].chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        words.extend(
+            token.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).map(str::to_string),
+        );
+    }
+    words
+}
+
+/// Look for `saved` among the `available` device names.
+pub fn find(saved: &str, available: &[String]) -> DeviceMatch {
+    if available.iter().any(|n| n == saved) {
+        return DeviceMatch::Exact;
+    }
+    let key = device_key(saved);
+    let same_key: Vec<String> = available.iter().filter(|n| device_key(n) == key).cloned().collect();
+    if !same_key.is_empty() {
+        return if is_virtual_cable(saved) { DeviceMatch::Ambiguous(same_key) } else { pick(same_key) };
+    }
+
+    let saved_words: HashSet<String> = words(saved).into_iter().collect();
+    let mut best = 0.0;
+    let mut matches = Vec::new();
+    for name in available {
+        let name_words: HashSet<String> = words(name).into_iter().collect();
+        let union = saved_words.union(&name_words).count();
+        if union == 0 {
+            continue;
+        }
+        let overlap = saved_words.intersection(&name_words).count() as f32 / union as f32;
+        if overlap <= MIN_OVERLAP || overlap < best {
+            continue;
+        }
+        if overlap > best {
+            best = overlap;
+            matches.clear();
+        }
+        matches.push(name.clone());
+    }
+    if matches.is_empty() {
+        DeviceMatch::Missing
+    } else {
+        DeviceMatch::Ambiguous(matches)
+    }
+}
+
+fn pick(mut names: Vec<String>) -> DeviceMatch {
+    match names.len() {
+        0 => DeviceMatch::Missing,
+        1 => DeviceMatch::Renamed(names.remove(0)),
+        _ => DeviceMatch::Ambiguous(names),
+    }
+}
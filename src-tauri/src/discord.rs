@@ -0,0 +1,521 @@
+// Discord over its local RPC (the IPC socket the desktop client opens).
+//
+// - Rich Presence (opt-in, `DiscordSettings.presence`): the deck's current
+//   profile — the saved theme its keys are showing — as the user's activity.
+//   Only needs the handshake.
+// - Voice state for the `DiscordMuted` / `DiscordSpeaking` slot bindings:
+//   needs the `rpc` scopes, so the client authorizes once (Discord asks the
+//   user), trades the code for a token with the app's client secret and keeps
+//   it in discord-token.txt. Then it follows VOICE_SETTINGS_UPDATE, and the
+//   speaking events of whatever voice channel the user is in. Pressing a
+//   `DiscordMuted` key flips self-mute.
+//
+// Both use the user's own Discord application (client id in Settings); one
+// background client (reconnect.rs) serves them while either is in use, like
+// Teams in meeting.rs.
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+use serde_json::{json, Value};
+
+use crate::reconnect::{self, Session};
+use crate::state::{Condition, DiscordSettings};
+
+/// How long a read waits before queued actions and the presence are sent.
+const POLL: Duration = Duration::from_millis(200);
+const RETRY: Duration = Duration::from_secs(5);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+/// The user answers Discord's authorize prompt within this.
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(120);
+const SCOPES: [&str; 3] = ["rpc", "rpc.voice.read", "rpc.voice.write"];
+
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+const OP_PING: u32 = 3;
+const OP_PONG: u32 = 4;
+
+#[derive(Debug, Default)]
+pub struct DiscordStatus {
+    /// Voice state is readable (authenticated with the rpc scopes).
+    pub voice: bool,
+    pub user_id: Option<String>,
+    /// Self-muted (or deafened).
+    pub muted: bool,
+    pub speaking: bool,
+    /// Voice channel the user is in.
+    pub channel: Option<String>,
+    pub error: Option<String>,
+}
+
+/// What the client runs with; a change restarts it.
+#[derive(Debug, Clone, PartialEq)]
+struct Config {
+    client_id: String,
+    client_secret: Option<String>,
+    voice: bool,
+}
+
+impl reconnect::Status for DiscordStatus {
+    fn failed(&mut self, error: String) {
+        *self = Self { error: Some(error), ..Default::default() };
+    }
+}
+
+struct Client {
+    config: Config,
+    /// Profile to show, or `None` for no activity.
+    presence: Arc<Mutex<Option<Option<String>>>>,
+    /// Commands are mute toggles.
+    conn: reconnect::Client<DiscordStatus, ()>,
+}
+
+static CLIENT: Mutex<Option<Client>> = Mutex::new(None);
+
+/// Whether a binding condition follows Discord.
+pub fn follows(condition: &Condition) -> bool {
+    matches!(condition, Condition::DiscordMuted | Condition::DiscordSpeaking)
+}
+
+/// Start, restart or stop the client for the current settings (called every
+/// watcher tick). `voice`: a binding follows Discord. `profile`: the deck's
+/// current profile, shown if presence is on.
+pub fn update(settings: &DiscordSettings, voice: bool, profile: Option<String>) {
+    let mut client = CLIENT.lock().unwrap();
+    if !(settings.presence || voice) || settings.client_id.is_empty() {
+        client.take();
+        return;
+    }
+    let config = Config {
+        client_id: settings.client_id.clone(),
+        client_secret: settings.client_secret.clone(),
+        voice,
+    };
+    if client.as_ref().is_none_or(|c| c.config != config) {
+        *client = Some(Client::start(config));
+    }
+    let client = client.as_ref().unwrap();
+    *client.presence.lock().unwrap() = settings.presence.then_some(profile);
+}
+
+/// Whether the condition holds in Discord (false while still connecting).
+pub fn state(condition: &Condition) -> Result<bool> {
+    let client = CLIENT.lock().unwrap();
+    let client = client.as_ref().context("Set your Discord application's client id in Settings")?;
+    let status = client.conn.status.lock().unwrap();
+    match (status.voice, &status.error) {
+        (true, _) => Ok(holds(&status, condition)),
+        (false, Some(e)) => Err(anyhow!("Discord: {e}")),
+        (false, None) => Ok(false),
+    }
+}
+
+/// Whether `condition` holds for a Discord in `status`.
+pub fn holds(status: &DiscordStatus, condition: &Condition) -> bool {
+    match condition {
+        Condition::DiscordMuted => status.muted,
+        Condition::DiscordSpeaking => status.speaking,
+        _ => false,
+    }
+}
+
+/// Ask Discord to flip self-mute. Doesn't block.
+pub fn toggle_mute() {
+    let client = CLIENT.lock().unwrap();
+    match client.as_ref().filter(|c| c.conn.status.lock().unwrap().voice) {
+        Some(c) => c.conn.send(()),
+        None => warn!("[discord] Voice state isn't connected, can't toggle mute"),
+    }
+}
+
+/// Rich Presence activity for the deck's current profile.
+pub fn activity(profile: Option<&str>) -> Value {
+    json!({
+        "details": match profile {
+            Some(name) => format!("Profile: {name}"),
+            None => "Custom colors".to_string(),
+        },
+        "state": "Deck-8 macro pad",
+    })
+}
+
+impl Client {
+    fn start(config: Config) -> Self {
+        let presence = Arc::new(Mutex::new(None));
+        let (session_config, session_presence) = (config.clone(), presence.clone());
+        let conn = reconnect::Client::start(RETRY, move |s| session(&session_config, &session_presence, s));
+        Self { config, presence, conn }
+    }
+}
+
+fn token_file() -> Result<PathBuf> {
+    Ok(crate::profile::config_dir()?.join("discord-token.txt"))
+}
+
+// ── IPC framing ─────────────────────────────────────────────────────────
+
+/// One frame: op and payload length (little endian), then the JSON payload.
+pub fn encode_frame(op: u32, payload: &Value) -> Vec<u8> {
+    let body = payload.to_string();
+    let mut frame = Vec::with_capacity(8 + body.len());
+    frame.extend_from_slice(&op.to_le_bytes());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(body.as_bytes());
+    frame
+}
+
+/// Take the first whole frame off `buf`, if one has arrived.
+pub fn take_frame(buf: &mut Vec<u8>) -> Result<Option<(u32, Value)>> {
+    if buf.len() < 8 {
+        return Ok(None);
+    }
+    let op = u32::from_le_bytes(buf[0..4].try_into().unwrap());
+    let len = u32::from_le_bytes(buf[4..8].try_into().unwrap()) as usize;
+    if buf.len() < 8 + len {
+        return Ok(None);
+    }
+    let payload = serde_json::from_slice(&buf[8..8 + len]).context("Bad frame from Discord")?;
+    buf.drain(..8 + len);
+    Ok(Some((op, payload)))
+}
+
+struct Conn {
+    pipe: platform::Pipe,
+    buf: Vec<u8>,
+    nonce: u64,
+}
+
+impl Conn {
+    fn send(&mut self, op: u32, payload: &Value) -> Result<()> {
+        self.pipe.write_all(&encode_frame(op, payload)).context("Discord closed the connection")
+    }
+
+    /// Send a command; returns its nonce.
+    fn command(&mut self, cmd: &str, args: Value, evt: Option<&str>) -> Result<String> {
+        self.nonce += 1;
+        let nonce = self.nonce.to_string();
+        let mut payload = json!({ "cmd": cmd, "args": args, "nonce": nonce });
+        if let Some(evt) = evt {
+            payload["evt"] = evt.into();
+        }
+        self.send(OP_FRAME, &payload)?;
+        Ok(nonce)
+    }
+
+    /// Next message, or `None` if nothing arrived within `POLL`.
+    fn recv(&mut self) -> Result<Option<Value>> {
+        loop {
+            if let Some((op, payload)) = take_frame(&mut self.buf)? {
+                match op {
+                    OP_FRAME => return Ok(Some(payload)),
+                    OP_PING => self.send(OP_PONG, &payload)?,
+                    OP_CLOSE => bail!("Discord closed the connection: {}", payload["message"]),
+                    _ => {}
+                }
+                continue;
+            }
+            let mut chunk = [0u8; 4096];
+            match self.pipe.read_some(&mut chunk)? {
+                Some(n) => self.buf.extend_from_slice(&chunk[..n]),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Send a command and wait for its reply (other messages go to `status`).
+    fn request(&mut self, session: &Session<DiscordStatus, ()>, cmd: &str, args: Value, timeout: Duration) -> Result<Value> {
+        let nonce = self.command(cmd, args, None)?;
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline && !session.stopped() {
+            let Some(msg) = self.recv()? else { continue };
+            if msg["nonce"] != nonce.as_str() {
+                apply(&mut session.status.lock().unwrap(), &msg);
+                continue;
+            }
+            if msg["evt"] == "ERROR" {
+                bail!("{cmd} failed: {}", msg["data"]["message"]);
+            }
+            apply(&mut session.status.lock().unwrap(), &msg);
+            return Ok(msg["data"].clone());
+        }
+        bail!("Discord didn't answer {cmd}")
+    }
+}
+
+// ── Session ─────────────────────────────────────────────────────────────
+
+/// One connection to Discord, until it closes or the client is dropped.
+fn session(
+    config: &Config,
+    presence: &Mutex<Option<Option<String>>>,
+    session: &Session<DiscordStatus, ()>,
+) -> Result<()> {
+    let status = session.status;
+    let mut conn = Conn { pipe: platform::Pipe::connect()?, buf: Vec::new(), nonce: 0 };
+    conn.send(OP_HANDSHAKE, &json!({ "v": 1, "client_id": config.client_id }))?;
+    let deadline = Instant::now() + REPLY_TIMEOUT;
+    loop {
+        match conn.recv()? {
+            Some(msg) if msg["evt"] == "READY" => break,
+            _ if Instant::now() > deadline => bail!("Discord didn't accept the handshake (wrong client id?)"),
+            _ => {}
+        }
+    }
+    info!("[discord] Connected");
+    *status.lock().unwrap() = DiscordStatus::default();
+
+    if config.voice {
+        let secret = config.client_secret.as_deref().filter(|s| !s.is_empty())
+            .context("Voice state needs your Discord application's client secret")?;
+        authenticate(&mut conn, config, secret, session)?;
+        conn.request(session, "GET_VOICE_SETTINGS", json!({}), REPLY_TIMEOUT)?;
+        conn.request(session, "GET_SELECTED_VOICE_CHANNEL", json!({}), REPLY_TIMEOUT)?;
+        conn.command("SUBSCRIBE", json!({}), Some("VOICE_SETTINGS_UPDATE"))?;
+        conn.command("SUBSCRIBE", json!({}), Some("VOICE_CHANNEL_SELECT"))?;
+        status.lock().unwrap().voice = true;
+    }
+    session.connected();
+
+    let mut shown: Option<Option<String>> = None;
+    let mut speaking_channel: Option<String> = None;
+    while !session.stopped() {
+        let wanted = presence.lock().unwrap().clone();
+        if wanted != shown {
+            let mut args = json!({ "pid": std::process::id() });
+            if let Some(profile) = &wanted {
+                args["activity"] = activity(profile.as_deref());
+            }
+            conn.command("SET_ACTIVITY", args, None)?;
+            shown = wanted;
+        }
+        for () in session.commands() {
+            let mute = !status.lock().unwrap().muted;
+            conn.command("SET_VOICE_SETTINGS", json!({ "mute": mute }), None)?;
+        }
+        // Speaking events are per channel: follow the user between channels
+        let channel = status.lock().unwrap().channel.clone();
+        if config.voice && channel != speaking_channel {
+            for evt in ["SPEAKING_START", "SPEAKING_STOP"] {
+                if let Some(old) = &speaking_channel {
+                    conn.command("UNSUBSCRIBE", json!({ "channel_id": old }), Some(evt))?;
+                }
+                if let Some(new) = &channel {
+                    conn.command("SUBSCRIBE", json!({ "channel_id": new }), Some(evt))?;
+                }
+            }
+            speaking_channel = channel;
+        }
+
+        let Some(msg) = conn.recv()? else { continue };
+        if msg["evt"] == "ERROR" {
+            warn!("[discord] {} failed: {}", msg["cmd"], msg["data"]["message"]);
+            continue;
+        }
+        apply(&mut status.lock().unwrap(), &msg);
+    }
+    Ok(())
+}
+
+/// AUTHENTICATE with the saved token, or authorize again (Discord asks the
+/// user) when there's none or it stopped working.
+fn authenticate(
+    conn: &mut Conn,
+    config: &Config,
+    secret: &str,
+    session: &Session<DiscordStatus, ()>,
+) -> Result<()> {
+    let saved = token_file().ok().and_then(|p| fs::read_to_string(p).ok());
+    if let Some(token) = saved.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        match conn.request(session, "AUTHENTICATE", json!({ "access_token": token }), REPLY_TIMEOUT) {
+            Ok(_) => return Ok(()),
+            Err(e) => info!("[discord] Saved token rejected, authorizing again: {e:#}"),
+        }
+    }
+
+    info!("[discord] Asking the user to authorize Deck8 Hub in Discord");
+    let data = conn.request(
+        session,
+        "AUTHORIZE",
+        json!({ "client_id": config.client_id, "scopes": SCOPES }),
+        AUTHORIZE_TIMEOUT,
+    )?;
+    let code = data["code"].as_str().context("Discord sent no authorization code")?;
+    let response = ureq::post("https://discord.com/api/oauth2/token")
+        .timeout(REPLY_TIMEOUT)
+        .send_form(&[
+            ("client_id", config.client_id.as_str()),
+            ("client_secret", secret),
+            ("grant_type", "authorization_code"),
+            ("code", code),
+        ])
+        .map_err(|e| anyhow!("Token exchange failed (check the client secret): {e}"))?
+        .into_string()?;
+    let response: Value = serde_json::from_str(&response)?;
+    let token = response["access_token"].as_str().context("Discord sent no access token")?;
+    if let Err(e) = token_file().and_then(|p| fs::write(p, token).context("Failed to save Discord token")) {
+        warn!("[discord] {e:#}");
+    }
+    conn.request(session, "AUTHENTICATE", json!({ "access_token": token }), REPLY_TIMEOUT)?;
+    Ok(())
+}
+
+/// Update `status` from a command reply or an event.
+pub fn apply(status: &mut DiscordStatus, msg: &Value) {
+    let data = &msg["data"];
+    let kind = match msg["cmd"].as_str() {
+        Some("DISPATCH") => msg["evt"].as_str(),
+        cmd => cmd,
+    };
+    match kind.unwrap_or_default() {
+        "AUTHENTICATE" => status.user_id = data["user"]["id"].as_str().map(str::to_string),
+        "GET_VOICE_SETTINGS" | "SET_VOICE_SETTINGS" | "VOICE_SETTINGS_UPDATE" => {
+            status.muted = data["mute"] == true || data["deaf"] == true;
+        }
+        "GET_SELECTED_VOICE_CHANNEL" => {
+            status.channel = data["id"].as_str().map(str::to_string);
+            status.speaking = false;
+        }
+        "VOICE_CHANNEL_SELECT" => {
+            status.channel = data["channel_id"].as_str().map(str::to_string);
+            status.speaking = false;
+        }
+        evt @ ("SPEAKING_START" | "SPEAKING_STOP")
+            if status.user_id.is_some() && data["user_id"].as_str() == status.user_id.as_deref() =>
+        {
+            status.speaking = evt == "SPEAKING_START";
+        }
+        _ => {}
+    }
+}
+
+// ── Platform pipes ──────────────────────────────────────────────────────
+
+#[cfg(unix)]
+mod platform {
+    use std::io::{self, Read, Write};
+    use std::os::unix::net::UnixStream;
+    use std::path::PathBuf;
+
+    use anyhow::{bail, Result};
+
+    pub struct Pipe(UnixStream);
+
+    impl Pipe {
+        /// The first of discord-ipc-0..9 in the runtime or temp dir.
+        pub fn connect() -> Result<Self> {
+            let dir = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+                .iter()
+                .find_map(std::env::var_os)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("/tmp"));
+            // Flatpak and Snap builds of Discord put the socket in a subdirectory
+            for sub in ["", "app/com.discordapp.Discord", "snap.discord"] {
+                for i in 0..10 {
+                    if let Ok(stream) = UnixStream::connect(dir.join(sub).join(format!("discord-ipc-{i}"))) {
+                        stream.set_read_timeout(Some(super::POLL))?;
+                        return Ok(Self(stream));
+                    }
+                }
+            }
+            bail!("Discord isn't running")
+        }
+
+        /// Read what's there; `None` if nothing arrived within `POLL`.
+        pub fn read_some(&mut self, buf: &mut [u8]) -> Result<Option<usize>> {
+            match self.0.read(buf) {
+                Ok(0) => bail!("Discord closed the connection"),
+                Ok(n) => Ok(Some(n)),
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        }
+    }
+
+    impl Write for Pipe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.0.flush()
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::c_void;
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, Read, Write};
+    use std::os::windows::io::AsRawHandle;
+
+    use anyhow::{bail, Result};
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn PeekNamedPipe(
+            pipe: *mut c_void,
+            buffer: *mut c_void,
+            size: u32,
+            read: *mut u32,
+            available: *mut u32,
+            left: *mut u32,
+        ) -> i32;
+    }
+
+    pub struct Pipe(File);
+
+    impl Pipe {
+        /// The first of \\.\pipe\discord-ipc-0..9.
+        pub fn connect() -> Result<Self> {
+            for i in 0..10 {
+                if let Ok(file) = OpenOptions::new().read(true).write(true).open(format!(r"\\.\pipe\discord-ipc-{i}")) {
+                    return Ok(Self(file));
+                }
+            }
+            bail!("Discord isn't running")
+        }
+
+        /// Read what's there; `None` if nothing arrived within `POLL`. A
+        /// blocking read would hold up writes on the same handle, so this
+        /// peeks first.
+        pub fn read_some(&mut self, buf: &mut [u8]) -> Result<Option<usize>> {
+            let mut available = 0u32;
+            let ok = unsafe {
+                PeekNamedPipe(
+                    self.0.as_raw_handle(),
+                    std::ptr::null_mut(),
+                    0,
+                    std::ptr::null_mut(),
+                    &mut available,
+                    std::ptr::null_mut(),
+                )
+            };
+            if ok == 0 {
+                bail!("Discord closed the connection");
+            }
+            if available == 0 {
+                std::thread::sleep(super::POLL);
+                return Ok(None);
+            }
+            let len = buf.len().min(available as usize);
+            Ok(Some(self.0.read(&mut buf[..len])?))
+        }
+    }
+
+    impl Write for Pipe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.0.flush()
+        }
+    }
+}
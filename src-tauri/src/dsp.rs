@@ -0,0 +1,363 @@
+// Mic processing graph: mic → stages → mixer.
+//
+// `MicSource` runs every mic sample through a `Graph` before mixing sounds
+// in. The stages (gate, denoise, EQ, AGC, compressor) keep their state whether
+// enabled or not, so bypassing or reordering one at runtime doesn't click.
+// Commands change the chain through `StageControl`; the audio thread only
+// takes its lock after the version counter moves.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::state::{AgcSettings, ProcessingStage, StageKind};
+
+/// Gate opens above this level.
+const GATE_OPEN_DB: f32 = -45.0;
+const GATE_HOLD_MS: f32 = 50.0;
+const GATE_ATTACK_MS: f32 = 1.0;
+const GATE_RELEASE_MS: f32 = 100.0;
+/// Denoise leaves signals this far above the tracked noise floor alone.
+const DENOISE_MARGIN_DB: f32 = 10.0;
+/// How fast the noise floor estimate may rise, per second.
+const DENOISE_FLOOR_RISE_DB: f32 = 3.0;
+const DENOISE_SMOOTH_MS: f32 = 10.0;
+const EQ_HIGH_PASS_HZ: f32 = 80.0;
+const EQ_PRESENCE_HZ: f32 = 3000.0;
+const EQ_PRESENCE_DB: f32 = 3.0;
+const COMP_THRESHOLD_DB: f32 = -20.0;
+const COMP_RATIO: f32 = 4.0;
+const COMP_MAKEUP_DB: f32 = 6.0;
+const COMP_ATTACK_MS: f32 = 5.0;
+const COMP_RELEASE_MS: f32 = 100.0;
+/// AGC measures the speaking level over about this long.
+const AGC_LEVEL_MS: f32 = 300.0;
+/// Below this the AGC holds its gain, so pauses and room noise don't get
+/// boosted.
+const AGC_SILENCE_DB: f32 = -50.0;
+const AGC_MIN_GAIN_DB: f32 = -20.0;
+/// Gain comes down fast when speech gets loud and goes up slowly.
+const AGC_FALL_MS: f32 = 100.0;
+const AGC_RISE_MS: f32 = 1500.0;
+
+/// Whether `stages` has every stage exactly once.
+pub fn is_complete(stages: &[ProcessingStage]) -> bool {
+    stages.len() == StageKind::ALL.len()
+        && StageKind::ALL.iter().all(|k| stages.iter().filter(|s| s.kind == *k).count() == 1)
+}
+
+/// The chain and the AGC settings as commands set them, shared with the
+/// audio thread.
+pub struct StageControl {
+    version: AtomicU64,
+    stages: Mutex<Vec<ProcessingStage>>,
+    agc: Mutex<AgcSettings>,
+}
+
+impl StageControl {
+    pub fn new(stages: &[ProcessingStage]) -> Self {
+        Self {
+            version: AtomicU64::new(0),
+            stages: Mutex::new(stages.to_vec()),
+            agc: Mutex::new(AgcSettings::default()),
+        }
+    }
+
+    pub fn set(&self, stages: &[ProcessingStage]) {
+        *self.stages.lock().unwrap() = stages.to_vec();
+        self.version.fetch_add(1, Ordering::Release);
+    }
+
+    pub fn set_agc(&self, agc: &AgcSettings) {
+        *self.agc.lock().unwrap() = *agc;
+        self.version.fetch_add(1, Ordering::Release);
+    }
+}
+
+pub struct Graph {
+    control: Arc<StageControl>,
+    version: u64,
+    stages: Vec<ProcessingStage>,
+    channels: usize,
+    /// Channel of the next interleaved sample.
+    channel: usize,
+    gate: Gate,
+    denoise: Denoise,
+    eq: Eq,
+    agc: Agc,
+    compressor: Compressor,
+}
+
+impl Graph {
+    pub fn new(control: Arc<StageControl>, channels: u16, sample_rate: u32) -> Self {
+        let stages = control.stages.lock().unwrap().clone();
+        let agc = *control.agc.lock().unwrap();
+        let version = control.version.load(Ordering::Acquire);
+        let (channels, rate) = (channels.max(1) as usize, sample_rate as f32);
+        Self {
+            control,
+            version,
+            stages,
+            channels,
+            channel: 0,
+            gate: Gate::new(channels, rate),
+            denoise: Denoise::new(channels, rate),
+            eq: Eq::new(channels, rate),
+            agc: Agc::new(channels, rate, agc),
+            compressor: Compressor::new(channels, rate),
+        }
+    }
+
+    /// Run one interleaved sample through the enabled stages.
+    pub fn process(&mut self, mut x: f32) -> f32 {
+        let version = self.control.version.load(Ordering::Acquire);
+        if version != self.version {
+            // Never wait on a command; pick the change up next sample
+            if let (Ok(stages), Ok(agc)) = (self.control.stages.try_lock(), self.control.agc.try_lock()) {
+                self.stages.clone_from(&stages);
+                self.agc.settings = *agc;
+                self.version = version;
+            }
+        }
+        let ch = self.channel;
+        self.channel = (ch + 1) % self.channels;
+        for stage in &self.stages {
+            // Disabled stages still track the signal so enabling one is seamless
+            let y = match stage.kind {
+                StageKind::Gate => self.gate.process(x, ch),
+                StageKind::Denoise => self.denoise.process(x, ch),
+                StageKind::Eq => self.eq.process(x, ch),
+                StageKind::Agc => self.agc.process(x, ch),
+                StageKind::Compressor => self.compressor.process(x, ch),
+            };
+            if stage.enabled {
+                x = y;
+            }
+        }
+        x
+    }
+}
+
+pub(crate) fn db_to_lin(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+fn lin_to_db(lin: f32) -> f32 {
+    20.0 * lin.max(1e-6).log10()
+}
+
+/// One-pole smoothing coefficient for a time constant.
+fn coef(ms: f32, rate: f32) -> f32 {
+    (-1.0 / (ms * 0.001 * rate)).exp()
+}
+
+/// Mutes the mic below a fixed level, with hold to ride out gaps in speech.
+struct Gate {
+    threshold: f32,
+    hold: u32,
+    attack: f32,
+    release: f32,
+    /// Samples left before closing, and the current gain, per channel.
+    held: Vec<u32>,
+    gain: Vec<f32>,
+}
+
+impl Gate {
+    fn new(channels: usize, rate: f32) -> Self {
+        Self {
+            threshold: db_to_lin(GATE_OPEN_DB),
+            hold: (GATE_HOLD_MS * 0.001 * rate) as u32,
+            attack: coef(GATE_ATTACK_MS, rate),
+            release: coef(GATE_RELEASE_MS, rate),
+            held: vec![0; channels],
+            gain: vec![0.0; channels],
+        }
+    }
+
+    fn process(&mut self, x: f32, ch: usize) -> f32 {
+        if x.abs() >= self.threshold {
+            self.held[ch] = self.hold;
+        } else {
+            self.held[ch] = self.held[ch].saturating_sub(1);
+        }
+        let (target, c) = if self.held[ch] > 0 { (1.0, self.attack) } else { (0.0, self.release) };
+        self.gain[ch] = target + c * (self.gain[ch] - target);
+        x * self.gain[ch]
+    }
+}
+
+/// Downward expander around an adaptive noise floor: the floor follows the
+/// quietest level and creeps up slowly, so steady noise (fans, hum) gets
+/// pushed down while speech passes.
+struct Denoise {
+    smooth: f32,
+    rise: f32,
+    margin: f32,
+    env: Vec<f32>,
+    floor: Vec<f32>,
+}
+
+impl Denoise {
+    fn new(channels: usize, rate: f32) -> Self {
+        Self {
+            smooth: coef(DENOISE_SMOOTH_MS, rate),
+            rise: db_to_lin(DENOISE_FLOOR_RISE_DB / rate),
+            margin: db_to_lin(DENOISE_MARGIN_DB),
+            env: vec![0.0; channels],
+            floor: vec![1e-3; channels],
+        }
+    }
+
+    fn process(&mut self, x: f32, ch: usize) -> f32 {
+        let env = x.abs() + self.smooth * (self.env[ch] - x.abs());
+        self.env[ch] = env;
+        self.floor[ch] = if env < self.floor[ch] { env.max(1e-5) } else { self.floor[ch] * self.rise };
+        // 1:3 expansion below floor + margin
+        let ratio = (env / (self.floor[ch] * self.margin)).min(1.0);
+        x * ratio * ratio
+    }
+}
+
+/// RBJ cookbook biquad, direct form I, with per-channel history.
+pub(crate) struct Biquad {
+    b: [f32; 3],
+    a: [f32; 2],
+    /// x1, x2, y1, y2 per channel.
+    history: Vec<[f32; 4]>,
+}
+
+impl Biquad {
+    fn new(channels: usize, b: [f32; 3], a: [f32; 3]) -> Self {
+        Self {
+            b: b.map(|v| v / a[0]),
+            a: [a[1] / a[0], a[2] / a[0]],
+            history: vec![[0.0; 4]; channels],
+        }
+    }
+
+    pub(crate) fn high_pass(channels: usize, rate: f32, freq: f32, q: f32) -> Self {
+        let w = std::f32::consts::TAU * freq / rate;
+        let (cos, alpha) = (w.cos(), w.sin() / (2.0 * q));
+        Self::new(
+            channels,
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    pub(crate) fn low_pass(channels: usize, rate: f32, freq: f32, q: f32) -> Self {
+        let w = std::f32::consts::TAU * freq / rate;
+        let (cos, alpha) = (w.cos(), w.sin() / (2.0 * q));
+        Self::new(
+            channels,
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    fn peaking(channels: usize, rate: f32, freq: f32, q: f32, gain_db: f32) -> Self {
+        let w = std::f32::consts::TAU * freq.min(rate * 0.45) / rate;
+        let (cos, alpha, a) = (w.cos(), w.sin() / (2.0 * q), 10f32.powf(gain_db / 40.0));
+        Self::new(
+            channels,
+            [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+            [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+        )
+    }
+
+    pub(crate) fn process(&mut self, x: f32, ch: usize) -> f32 {
+        let [x1, x2, y1, y2] = self.history[ch];
+        let y = self.b[0] * x + self.b[1] * x1 + self.b[2] * x2 - self.a[0] * y1 - self.a[1] * y2;
+        self.history[ch] = [x, x1, y, y1];
+        y
+    }
+}
+
+/// Rumble cut plus a little presence for voice.
+struct Eq {
+    high_pass: Biquad,
+    presence: Biquad,
+}
+
+impl Eq {
+    fn new(channels: usize, rate: f32) -> Self {
+        Self {
+            high_pass: Biquad::high_pass(channels, rate, EQ_HIGH_PASS_HZ, std::f32::consts::FRAC_1_SQRT_2),
+            presence: Biquad::peaking(channels, rate, EQ_PRESENCE_HZ, 1.0, EQ_PRESENCE_DB),
+        }
+    }
+
+    fn process(&mut self, x: f32, ch: usize) -> f32 {
+        self.presence.process(self.high_pass.process(x, ch), ch)
+    }
+}
+
+/// Rides the gain so speech stays in the target range: the level is a
+/// mean square over `AGC_LEVEL_MS`; outside the range the gain heads for its
+/// middle, inside it holds.
+struct Agc {
+    settings: AgcSettings,
+    smooth: f32,
+    fall: f32,
+    rise: f32,
+    /// Mean square level and gain in dB, per channel.
+    level: Vec<f32>,
+    gain: Vec<f32>,
+}
+
+impl Agc {
+    fn new(channels: usize, rate: f32, settings: AgcSettings) -> Self {
+        Self {
+            settings,
+            smooth: coef(AGC_LEVEL_MS, rate),
+            fall: coef(AGC_FALL_MS, rate),
+            rise: coef(AGC_RISE_MS, rate),
+            level: vec![0.0; channels],
+            gain: vec![0.0; channels],
+        }
+    }
+
+    fn process(&mut self, x: f32, ch: usize) -> f32 {
+        let level = x * x + self.smooth * (self.level[ch] - x * x);
+        self.level[ch] = level;
+        // Mean square to dB RMS
+        let level_db = 10.0 * level.max(1e-12).log10();
+        let gain = self.gain[ch];
+        let s = &self.settings;
+        let out_db = level_db + gain;
+        let target = if level_db < AGC_SILENCE_DB || (s.target_low_db..=s.target_high_db).contains(&out_db) {
+            gain
+        } else {
+            (s.target_low_db + s.target_high_db) / 2.0 - level_db
+        }
+        .clamp(AGC_MIN_GAIN_DB, s.max_gain_db.max(AGC_MIN_GAIN_DB));
+        let c = if target < gain { self.fall } else { self.rise };
+        self.gain[ch] = target + c * (gain - target);
+        x * db_to_lin(self.gain[ch])
+    }
+}
+
+/// Feed-forward compressor with makeup gain, evens out loud and quiet speech.
+struct Compressor {
+    attack: f32,
+    release: f32,
+    /// Gain reduction in dB per channel.
+    reduction: Vec<f32>,
+}
+
+impl Compressor {
+    fn new(channels: usize, rate: f32) -> Self {
+        Self {
+            attack: coef(COMP_ATTACK_MS, rate),
+            release: coef(COMP_RELEASE_MS, rate),
+            reduction: vec![0.0; channels],
+        }
+    }
+
+    fn process(&mut self, x: f32, ch: usize) -> f32 {
+        let over = lin_to_db(x.abs()) - COMP_THRESHOLD_DB;
+        let target = over.max(0.0) * (1.0 - 1.0 / COMP_RATIO);
+        let c = if target > self.reduction[ch] { self.attack } else { self.release };
+        self.reduction[ch] = target + c * (self.reduction[ch] - target);
+        x * db_to_lin(COMP_MAKEUP_DB - self.reduction[ch])
+    }
+}
@@ -0,0 +1,164 @@
+// Structured command errors.
+//
+// Commands return `HubError` instead of a flattened string, so the UI can
+// branch on a stable `code` (e.g. offer "Reconnect" for NOT_CONNECTED) and
+// still show the message. Every error that crosses the IPC boundary is also
+// kept in a small ring buffer that `get_recent_errors` returns.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::ipc::InvokeError;
+
+/// How many errors `get_recent_errors` keeps.
+const RECENT_CAPACITY: usize = 50;
+
+static RECENT: Mutex<VecDeque<ErrorReport>> = Mutex::new(VecDeque::new());
+
+#[derive(Debug)]
+pub enum HubError {
+    /// No Deck-8 handle is open.
+    NotConnected,
+    /// Another app (VIA, …) holds the Deck-8's raw HID interface.
+    DeviceBusy,
+    /// The setup is locked (see lock.rs).
+    Locked,
+    /// A HID report couldn't be written or read.
+    HidIo(String),
+    /// The device didn't answer a request in time.
+    DeviceTimeout(String),
+    /// The firmware didn't report the feature the command needs.
+    Unsupported(String),
+    /// The audio pipeline or an audio device failed.
+    AudioDevice(String),
+    /// A file couldn't be read, decoded or written.
+    FileIo { path: String, message: String },
+    /// An OS integration (settings pane, shortcuts) failed.
+    Platform(String),
+    /// The request itself was invalid (bad index, unknown slot, …).
+    InvalidInput(String),
+    /// A keymap write didn't read back (or failed mid-batch) and was rolled
+    /// back. `key_index` is the keymap (matrix) index that failed.
+    KeymapWrite { key_index: u8, message: String, stranded: Vec<u8> },
+}
+
+impl HubError {
+    /// Stable identifier the frontend can match on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotConnected => "NOT_CONNECTED",
+            Self::DeviceBusy => "DEVICE_BUSY",
+            Self::Locked => "LOCKED",
+            Self::HidIo(_) => "HID_IO",
+            Self::DeviceTimeout(_) => "DEVICE_TIMEOUT",
+            Self::Unsupported(_) => "UNSUPPORTED",
+            Self::AudioDevice(_) => "AUDIO_DEVICE",
+            Self::FileIo { .. } => "FILE_IO",
+            Self::Platform(_) => "PLATFORM",
+            Self::InvalidInput(_) => "INVALID_INPUT",
+            Self::KeymapWrite { .. } => "KEYMAP_WRITE",
+        }
+    }
+
+    fn context(&self) -> Option<String> {
+        match self {
+            Self::FileIo { path, .. } => Some(path.clone()),
+            Self::KeymapWrite { key_index, .. } => Some(format!("key {}", key_index + 1)),
+            _ => None,
+        }
+    }
+
+    pub fn audio(e: anyhow::Error) -> Self {
+        Self::AudioDevice(format!("{e:#}"))
+    }
+
+    pub fn file(path: impl AsRef<Path>, e: anyhow::Error) -> Self {
+        Self::FileIo { path: path.as_ref().display().to_string(), message: format!("{e:#}") }
+    }
+
+    pub fn platform(e: anyhow::Error) -> Self {
+        Self::Platform(format!("{e:#}"))
+    }
+}
+
+impl fmt::Display for HubError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotConnected => f.write_str(&crate::i18n::t("error-not-connected")),
+            Self::DeviceBusy => f.write_str(&crate::i18n::t("error-device-busy")),
+            Self::Locked => f.write_str(&crate::i18n::t("error-locked")),
+            Self::HidIo(m) | Self::DeviceTimeout(m) | Self::Unsupported(m) | Self::AudioDevice(m) | Self::Platform(m) | Self::InvalidInput(m) => {
+                f.write_str(m)
+            }
+            Self::FileIo { path, message } => write!(f, "{message} ({path})"),
+            Self::KeymapWrite { message, .. } => f.write_str(message),
+        }
+    }
+}
+
+/// Device errors: everything `hid::Deck8Device` returns.
+impl From<anyhow::Error> for HubError {
+    fn from(e: anyhow::Error) -> Self {
+        if let Some(failed) = e.downcast_ref::<crate::hid::KeymapWriteFailed>() {
+            return Self::KeymapWrite { key_index: failed.key_index, message: failed.to_string(), stranded: failed.stranded.clone() };
+        }
+        if e.downcast_ref::<crate::hid::Busy>().is_some() {
+            return Self::DeviceBusy;
+        }
+        if e.downcast_ref::<crate::hid::Timeout>().is_some() || e.downcast_ref::<crate::hid::Unhealthy>().is_some() {
+            Self::DeviceTimeout(format!("{e:#}"))
+        } else if e.downcast_ref::<crate::hid::Unsupported>().is_some() {
+            Self::Unsupported(format!("{e:#}"))
+        } else {
+            Self::HidIo(format!("{e:#}"))
+        }
+    }
+}
+
+/// Validation messages from `state` (and plain `Err("…".into())`).
+impl From<String> for HubError {
+    fn from(message: String) -> Self {
+        Self::InvalidInput(message)
+    }
+}
+
+impl From<&str> for HubError {
+    fn from(message: &str) -> Self {
+        Self::InvalidInput(message.to_owned())
+    }
+}
+
+/// What the frontend receives (and `get_recent_errors` lists).
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorReport {
+    pub code: &'static str,
+    pub message: String,
+    pub context: Option<String>,
+    /// Unix time in milliseconds.
+    pub at_ms: u64,
+}
+
+impl From<HubError> for InvokeError {
+    fn from(e: HubError) -> Self {
+        let report = ErrorReport {
+            code: e.code(),
+            message: e.to_string(),
+            context: e.context(),
+            at_ms: crate::now_ms(),
+        };
+        let mut recent = RECENT.lock().unwrap();
+        if recent.len() == RECENT_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(report.clone());
+        InvokeError::from(report)
+    }
+}
+
+/// Errors returned to the frontend, oldest first.
+pub fn recent() -> Vec<ErrorReport> {
+    RECENT.lock().unwrap().iter().cloned().collect()
+}
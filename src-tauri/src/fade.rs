@@ -0,0 +1,85 @@
+// Host-driven color fades between slots.
+//
+// The firmware only knows "set this key to this color", so a fade is a short
+// thread that streams eased intermediate HSV colors to the device. Each frame
+// re-reads the key's target color, so edits during a fade still land, and a
+// per-key generation counter lets a newer transition (or a direct color write)
+// cancel one in flight.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::protocol::HsvColor;
+use crate::state::{AppState, SharedState};
+
+const FRAME: Duration = Duration::from_millis(20);
+
+static GENERATION: [AtomicU64; 8] = [
+    AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0),
+    AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0),
+];
+
+/// Stop any fade running on `key_index`.
+pub fn cancel(key_index: usize) {
+    GENERATION[key_index].fetch_add(1, Ordering::SeqCst);
+}
+
+/// Show a key's (new) active slot, fading from `from` when `settings.fade_ms`
+/// is set. Called with the state lock held; the fade itself runs unlocked.
+pub fn transition<R: Runtime>(app: &AppHandle<R>, st: &AppState, key_index: usize, from: HsvColor) {
+    cancel(key_index);
+    if crate::dnd_active() || crate::progress::active() || crate::preview::active() || crate::notify::covers(key_index) {
+        return;
+    }
+    let key = &st.keys[key_index];
+    let Some(ref dev) = st.device else { return };
+    let duration = Duration::from_millis(st.settings.fade_ms);
+    if duration.is_zero() || !key.override_enabled || key.active().color == from {
+        crate::apply_key_to_device(dev, key_index as u8, key);
+        return;
+    }
+
+    let generation = GENERATION[key_index].load(Ordering::SeqCst);
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let start = Instant::now();
+        loop {
+            std::thread::sleep(FRAME);
+            if GENERATION[key_index].load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let t = (start.elapsed().as_secs_f32() / duration.as_secs_f32()).min(1.0);
+            let state = app.state::<SharedState>();
+            let st = state.lock().unwrap();
+            let key = &st.keys[key_index];
+            let Some(ref dev) = st.device else { return };
+            if t >= 1.0 || !key.override_enabled {
+                crate::apply_key_to_device(dev, key_index as u8, key);
+                return;
+            }
+            let color = crate::cooldown::shade(key_index, key, lerp(&from, &key.active().color, ease(t)));
+            let _ = dev.set_key_color(key_index as u8, &crate::brightness::key_color(&color));
+        }
+    });
+}
+
+/// Ease-in-out cubic.
+fn ease(t: f32) -> f32 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+/// Interpolate in HSV; hue takes the short way around the wheel.
+fn lerp(a: &HsvColor, b: &HsvColor, t: f32) -> HsvColor {
+    let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    let dh = (b.h.wrapping_sub(a.h) as i8) as f32;
+    HsvColor {
+        h: (a.h as f32 + dh * t).round().rem_euclid(256.0) as u8,
+        s: mix(a.s, b.s),
+        v: mix(a.v, b.v),
+    }
+}
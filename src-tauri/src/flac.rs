@@ -0,0 +1,256 @@
+// Minimal FLAC encoder for the sound library: 16-bit samples, fixed
+// predictors (orders 0–4) and partitioned Rice residuals, channels coded
+// independently. rodio decodes the result like any other FLAC.
+//
+// rodio hands decoded audio over as 16-bit, so trimmed imports lose nothing;
+// files come out around half the size of 16-bit WAV and a quarter of the
+// 32-bit float WAV trimming wrote before. Opus isn't offered: rodio can't
+// play it back and encoding it needs the native libopus.
+
+const BLOCK_SIZE: usize = 4096;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_FIXED_ORDER: usize = 4;
+const MAX_PARTITION_ORDER: u32 = 8;
+/// Rice parameters are 4 bits and 15 is the escape code.
+const MAX_RICE_PARAM: u32 = 14;
+
+/// Encode interleaved samples as a complete FLAC file.
+pub fn encode(channels: u16, sample_rate: u32, samples: &[i16]) -> Vec<u8> {
+    let channels = channels as usize;
+    let frames = samples.len() / channels;
+    let mut w = BitWriter::default();
+    w.bytes(b"fLaC");
+    // STREAMINFO, the only (last) metadata block
+    w.put(1, 1);
+    w.put(7, 0);
+    w.put(24, 34);
+    w.put(16, BLOCK_SIZE as u64);
+    w.put(16, BLOCK_SIZE as u64);
+    // Frame sizes unknown
+    w.put(24, 0);
+    w.put(24, 0);
+    w.put(20, sample_rate as u64);
+    w.put(3, channels as u64 - 1);
+    w.put(5, BITS_PER_SAMPLE as u64 - 1);
+    w.put(36, frames as u64);
+    // MD5 unset
+    w.bytes(&[0; 16]);
+
+    let mut channel = Vec::with_capacity(BLOCK_SIZE);
+    for (number, start) in (0..frames).step_by(BLOCK_SIZE).enumerate() {
+        let len = BLOCK_SIZE.min(frames - start);
+        let frame_start = w.out.len();
+        frame_header(&mut w, number as u64, len, channels);
+        for ch in 0..channels {
+            channel.clear();
+            channel.extend((start..start + len).map(|i| samples[i * channels + ch] as i64));
+            subframe(&mut w, &channel);
+        }
+        w.align();
+        let crc = crc16(&w.out[frame_start..]);
+        w.put(16, crc as u64);
+    }
+    w.out
+}
+
+fn frame_header(w: &mut BitWriter, number: u64, len: usize, channels: usize) {
+    let start = w.out.len();
+    // Sync code, fixed block size
+    w.put(14, 0b11_1111_1111_1110);
+    w.put(2, 0);
+    // 4096, or a 16-bit size after the frame number (the short last block)
+    w.put(4, if len == BLOCK_SIZE { 0b1100 } else { 0b0111 });
+    // Sample rate from STREAMINFO
+    w.put(4, 0);
+    // Independent channels, 16-bit
+    w.put(4, channels as u64 - 1);
+    w.put(3, 0b100);
+    w.put(1, 0);
+    w.utf8(number);
+    if len != BLOCK_SIZE {
+        w.put(16, len as u64 - 1);
+    }
+    let crc = crc8(&w.out[start..]);
+    w.put(8, crc as u64);
+}
+
+/// The cheapest of constant, fixed-predictor and verbatim coding.
+fn subframe(w: &mut BitWriter, x: &[i64]) {
+    let bps = BITS_PER_SAMPLE;
+    if x.iter().all(|&s| s == x[0]) {
+        w.put(8, 0);
+        w.put_signed(bps, x[0]);
+        return;
+    }
+    let verbatim = x.len() as u64 * bps as u64;
+    let best = (0..=MAX_FIXED_ORDER.min(x.len() - 1))
+        .map(|order| {
+            let residual = fixed_residual(x, order);
+            let (cost, partition_order) = best_partition_order(&residual, x.len(), order);
+            (cost + (order as u64) * bps as u64, order, residual, partition_order)
+        })
+        .min_by_key(|&(cost, ..)| cost);
+    match best {
+        Some((cost, order, residual, partition_order)) if cost < verbatim => {
+            w.put(8, (0b00_1000 | order as u64) << 1);
+            for &s in &x[..order] {
+                w.put_signed(bps, s);
+            }
+            write_residual(w, &residual, x.len(), order, partition_order);
+        }
+        _ => {
+            w.put(8, 0b0000_0010);
+            for &s in x {
+                w.put_signed(bps, s);
+            }
+        }
+    }
+}
+
+/// Zigzagged prediction errors for samples `order..`.
+fn fixed_residual(x: &[i64], order: usize) -> Vec<u64> {
+    (order..x.len())
+        .map(|i| {
+            let e = match order {
+                0 => x[i],
+                1 => x[i] - x[i - 1],
+                2 => x[i] - 2 * x[i - 1] + x[i - 2],
+                3 => x[i] - 3 * x[i - 1] + 3 * x[i - 2] - x[i - 3],
+                _ => x[i] - 4 * x[i - 1] + 6 * x[i - 2] - 4 * x[i - 3] + x[i - 4],
+            };
+            ((e << 1) ^ (e >> 63)) as u64
+        })
+        .collect()
+}
+
+/// Sample ranges of the residual partitions (the first is short by `order`).
+fn partitions(block_len: usize, order: usize, partition_order: u32) -> impl Iterator<Item = std::ops::Range<usize>> {
+    let size = block_len >> partition_order;
+    (0..1usize << partition_order).map(move |p| {
+        let start = if p == 0 { 0 } else { p * size - order };
+        start..(p + 1) * size - order
+    })
+}
+
+/// Rice parameter for a partition and its cost in bits: the best is within
+/// one of log2 of the mean.
+fn rice_param(values: &[u64]) -> (u32, u64) {
+    let mean = values.iter().sum::<u64>() / values.len().max(1) as u64;
+    let k = mean.checked_ilog2().unwrap_or(0).min(MAX_RICE_PARAM);
+    let cost = |k: u32| values.iter().map(|&u| (u >> k) + 1 + k as u64).sum::<u64>();
+    [k, (k + 1).min(MAX_RICE_PARAM)].into_iter().map(|k| (k, cost(k))).min_by_key(|&(_, c)| c).unwrap()
+}
+
+/// (bits, partition order) of the cheapest valid partitioning.
+fn best_partition_order(residual: &[u64], block_len: usize, order: usize) -> (u64, u32) {
+    (0..=MAX_PARTITION_ORDER)
+        .take_while(|&p| block_len.is_multiple_of(1 << p) && block_len >> p > order)
+        .map(|p| {
+            let bits: u64 = partitions(block_len, order, p)
+                .map(|r| 4 + rice_param(&residual[r]).1)
+                .sum();
+            (6 + bits, p)
+        })
+        .min()
+        .unwrap_or((u64::MAX, 0))
+}
+
+fn write_residual(w: &mut BitWriter, residual: &[u64], block_len: usize, order: usize, partition_order: u32) {
+    // Rice coding with 4-bit parameters
+    w.put(2, 0);
+    w.put(4, partition_order as u64);
+    for r in partitions(block_len, order, partition_order) {
+        let values = &residual[r];
+        let (k, _) = rice_param(values);
+        w.put(4, k as u64);
+        for &u in values {
+            w.unary(u >> k);
+            w.put(k, u & ((1 << k) - 1));
+        }
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u64,
+    /// Bits waiting in `acc`, always < 8 between calls.
+    len: u32,
+}
+
+impl BitWriter {
+    /// Append the low `bits` (≤ 32) of `value`.
+    fn put(&mut self, bits: u32, value: u64) {
+        if bits > 32 {
+            self.put(bits - 32, value >> 32);
+            self.put(32, value & 0xFFFF_FFFF);
+            return;
+        }
+        self.acc = (self.acc << bits) | (value & ((1u64 << bits) - 1));
+        self.len += bits;
+        while self.len >= 8 {
+            self.len -= 8;
+            self.out.push((self.acc >> self.len) as u8);
+        }
+        self.acc &= (1 << self.len) - 1;
+    }
+
+    fn put_signed(&mut self, bits: u32, value: i64) {
+        self.put(bits, value as u64 & ((1 << bits) - 1));
+    }
+
+    /// `q` zeros, then a one.
+    fn unary(&mut self, mut q: u64) {
+        while q >= 32 {
+            self.put(32, 0);
+            q -= 32;
+        }
+        self.put(q as u32 + 1, 1);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.put(8, b as u64);
+        }
+    }
+
+    /// FLAC's UTF-8-style variable-length number (frame numbers up to 31 bits).
+    fn utf8(&mut self, n: u64) {
+        if n < 0x80 {
+            return self.put(8, n);
+        }
+        let extra = (1..=5).find(|&e| n < 1 << (6 + 5 * e)).unwrap_or(5);
+        let lead = (0xFF00u64 >> (extra + 1)) & 0xFF;
+        self.put(8, lead | (n >> (6 * extra)));
+        for i in (0..extra).rev() {
+            self.put(8, 0x80 | ((n >> (6 * i)) & 0x3F));
+        }
+    }
+
+    /// Pad with zeros to a byte boundary.
+    fn align(&mut self) {
+        if self.len > 0 {
+            self.put(8 - self.len, 0);
+        }
+    }
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |mut crc, &b| {
+        crc ^= b;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+        }
+        crc
+    })
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |mut crc, &b| {
+        crc ^= (b as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x8005 } else { crc << 1 };
+        }
+        crc
+    })
+}
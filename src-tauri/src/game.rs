@@ -0,0 +1,253 @@
+// Game integration: key LEDs react to game events (low HP, bomb planted,
+// ability on cooldown).
+//
+// Games with a state integration POST their state as JSON to a local HTTP
+// endpoint: CS2/CS:GO and Dota 2 through a `gamestate_integration_*.cfg`
+// whose "uri" is http://127.0.0.1:<port>. Each `GameRule` looks up a dotted
+// path in that JSON ("player.state.health", "round.bomb") and, while its
+// test holds, shows a notification on its key. Those ride the notify.rs
+// stack, so the key's own color comes back when the rule stops holding, and
+// they only last `STALE_MS` past the last update: when the game closes the
+// keys go back on their own. With a token set, payloads whose "auth.token"
+// doesn't match are refused.
+//
+// Off until `settings.game.enabled`; only loopback connections are possible,
+// and requests from web pages (they carry an `Origin`) are refused.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::notify::Pattern;
+use crate::protocol::HsvColor;
+use crate::state::SharedState;
+
+pub const DEFAULT_PORT: u16 = 18_809;
+pub const MAX_RULES: usize = 16;
+/// How often the listener checks for connections and the settings.
+const POLL: Duration = Duration::from_millis(200);
+/// Rule notifications end this long after the last update that held them.
+const STALE_MS: u64 = 60_000;
+/// Above API notifications sent without a priority.
+const PRIORITY: u8 = 1;
+const MAX_BODY: usize = 1 << 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// The "auth.token" the game's config sends; `None` accepts any payload.
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<GameRule>,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self { enabled: false, port: DEFAULT_PORT, token: None, rules: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameRule {
+    pub name: String,
+    /// Dotted path into the game's JSON, e.g. "player.state.health".
+    pub path: String,
+    pub test: GameTest,
+    /// LED index.
+    pub key: usize,
+    pub color: HsvColor,
+    #[serde(default)]
+    pub pattern: Pattern,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum GameTest {
+    /// A number under `value` (e.g. health below 30).
+    Below { value: f64 },
+    Above { value: f64 },
+    /// A string, number or bool that reads as `value` (e.g. "planted").
+    Equals { value: String },
+}
+
+impl GameSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port < 1024 || self.port == crate::api::PORT {
+            return Err(crate::i18n::t_with("error-game-port", &[("api_port", &crate::api::PORT)]));
+        }
+        if self.rules.len() > MAX_RULES {
+            return Err(crate::i18n::t_with("error-too-many-game-rules", &[("max", &MAX_RULES)]));
+        }
+        for rule in &self.rules {
+            if rule.name.trim().is_empty() {
+                return Err(crate::i18n::t("error-game-rule-name-empty"));
+            }
+            if rule.path.split('.').any(str::is_empty) {
+                return Err(crate::i18n::t_with("error-game-rule-path", &[("path", &rule.path)]));
+            }
+            if rule.key >= 8 {
+                return Err(crate::i18n::t("error-key-index"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The value at a dotted path; `None` if the payload doesn't have it.
+pub fn lookup<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(payload, |value, part| value.get(part))
+}
+
+impl GameTest {
+    pub fn matches(&self, value: Option<&Value>) -> bool {
+        let Some(value) = value else { return false };
+        match self {
+            Self::Below { value: limit } => value.as_f64().is_some_and(|v| v < *limit),
+            Self::Above { value: limit } => value.as_f64().is_some_and(|v| v > *limit),
+            Self::Equals { value: expected } => match value {
+                Value::String(s) => s == expected,
+                Value::Number(n) => expected.parse::<f64>().ok() == n.as_f64(),
+                Value::Bool(b) => expected.parse::<bool>() == Ok(*b),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Whether a payload may update the keys.
+pub fn authorized(settings: &GameSettings, payload: &Value) -> bool {
+    match settings.token.as_deref().filter(|t| !t.is_empty()) {
+        Some(token) => lookup(payload, "auth.token").and_then(Value::as_str) == Some(token),
+        None => true,
+    }
+}
+
+/// The port to listen on; 0 while off.
+static PORT: AtomicU16 = AtomicU16::new(0);
+/// Notification ids of the rules holding now, by rule index.
+static SHOWN: Mutex<Option<HashMap<usize, u64>>> = Mutex::new(None);
+
+/// Follow new settings: listen (or stop) and take down what the old rules
+/// showed.
+pub fn configure(settings: &GameSettings) {
+    PORT.store(if settings.enabled { settings.port } else { 0 }, Ordering::SeqCst);
+    if let Some(shown) = SHOWN.lock().unwrap().take() {
+        for id in shown.into_values() {
+            crate::notify::dismiss(id);
+        }
+    }
+}
+
+/// Start the listener thread. The port is only bound while enabled.
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut listener: Option<(TcpListener, u16)> = None;
+        loop {
+            std::thread::sleep(POLL);
+            let port = PORT.load(Ordering::SeqCst);
+            if listener.as_ref().is_some_and(|(_, bound)| *bound != port) {
+                listener = None;
+                info!("[game] Stopped listening");
+            }
+            if port == 0 {
+                continue;
+            }
+            if listener.is_none() {
+                match bind(port) {
+                    Ok(l) => {
+                        info!("[game] Listening on http://127.0.0.1:{port}");
+                        listener = Some((l, port));
+                    }
+                    Err(e) => {
+                        warn!("[game] Can't listen on port {port}: {e}");
+                        // Don't retry (and log) every poll
+                        let _ = PORT.compare_exchange(port, 0, Ordering::SeqCst, Ordering::SeqCst);
+                        continue;
+                    }
+                }
+            }
+            let Some((ref l, _)) = listener else { continue };
+            while let Ok((stream, _)) = l.accept() {
+                let app = app.clone();
+                std::thread::spawn(move || serve(&app, stream));
+            }
+        }
+    });
+}
+
+fn bind(port: u16) -> std::io::Result<TcpListener> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// One HTTP request: the game POSTs its state and only wants a 200 back.
+fn serve(app: &AppHandle, stream: TcpStream) {
+    if stream.set_nonblocking(false).is_err() || stream.set_read_timeout(Some(Duration::from_secs(5))).is_err() {
+        return;
+    }
+    let status = match crate::http::read_request(&stream, MAX_BODY).and_then(|request| payload(&request)) {
+        Ok(payload) => receive(app, &payload),
+        Err(status) => status,
+    };
+    let reply = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    let _ = (&stream).write_all(reply.as_bytes());
+}
+
+/// The game state a request carries. Web pages (any request with an `Origin`)
+/// are refused before the body is parsed: a page can POST text/plain here
+/// without a preflight, and with no token set nothing else would stop it.
+pub fn payload(request: &crate::http::Request) -> Result<Value, &'static str> {
+    if let Some(origin) = request.header("origin") {
+        warn!("[game] Refused a request from {origin:?}");
+        return Err("403 Forbidden");
+    }
+    if request.method != "POST" {
+        return Err("405 Method Not Allowed");
+    }
+    serde_json::from_slice(&request.body).map_err(|_| "400 Bad Request")
+}
+
+fn receive<R: Runtime>(app: &AppHandle<R>, payload: &Value) -> &'static str {
+    let state = app.state::<SharedState>();
+    let settings = state.lock().unwrap().settings.game.clone();
+    if !settings.enabled {
+        return "503 Service Unavailable";
+    }
+    if !authorized(&settings, payload) {
+        return "401 Unauthorized";
+    }
+    let mut shown = SHOWN.lock().unwrap();
+    let shown = shown.get_or_insert_with(HashMap::new);
+    for (i, rule) in settings.rules.iter().enumerate() {
+        let holds = rule.test.matches(lookup(payload, &rule.path));
+        let up = shown.get(&i).copied();
+        match (holds, up) {
+            (true, Some(id)) if crate::notify::extend(id, STALE_MS) => {}
+            (true, _) => match crate::notify::notify(app, rule.key, rule.color, rule.pattern, STALE_MS, PRIORITY) {
+                Ok(id) => {
+                    info!("[game] {} started", rule.name);
+                    shown.insert(i, id);
+                }
+                Err(e) => warn!("[game] {}: {e}", rule.name),
+            },
+            (false, Some(id)) => {
+                info!("[game] {} ended", rule.name);
+                crate::notify::dismiss(id);
+                shown.remove(&i);
+            }
+            (false, None) => {}
+        }
+    }
+    "200 OK"
+}
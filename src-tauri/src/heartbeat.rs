@@ -0,0 +1,133 @@
+// Device heartbeat: reads the uptime every 30 s and keeps the last hour of
+// samples, so a reboot (uptime going backwards, e.g. a brown-out or a flaky
+// hub) shows up in Settings → Device instead of as "my overrides vanished".
+//
+// Each sample is emitted as `device-health`; a reboot also as
+// `device-rebooted`. `get_device_health` returns the history. The poll holds
+// the state lock for one HID round trip, like `get_device_info`. A read that
+// fails with an I/O error (not a timeout) releases the handle and is recorded
+// in connections.rs as lost.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::warn;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::connections::EventKind;
+use crate::state::SharedState;
+
+const POLL: Duration = Duration::from_secs(30);
+/// An hour at `POLL`.
+const CAPACITY: usize = 120;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthSample {
+    /// Unix time (ms).
+    pub at_ms: u64,
+    pub connected: bool,
+    /// Device uptime (s); `None` when disconnected or the read failed.
+    pub uptime: Option<u32>,
+    /// Round trip of the uptime read.
+    pub latency_ms: Option<u32>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Reboot {
+    pub at_ms: u64,
+    /// Uptime (s) at the last sample before the reboot.
+    pub previous_uptime: u32,
+    pub uptime: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeviceHealth {
+    /// Oldest first.
+    pub samples: VecDeque<HealthSample>,
+    pub reboots: VecDeque<Reboot>,
+}
+
+impl DeviceHealth {
+    /// Add a sample; returns the reboot it reveals, if any.
+    pub fn push(&mut self, sample: HealthSample) -> Option<Reboot> {
+        // Since the last disconnect: replugging restarts the count too
+        let previous = self.samples.iter().rev().take_while(|s| s.connected).find_map(|s| s.uptime);
+        let reboot = match (previous, sample.uptime) {
+            (Some(previous_uptime), Some(uptime)) if uptime < previous_uptime => {
+                Some(Reboot { at_ms: sample.at_ms, previous_uptime, uptime })
+            }
+            _ => None,
+        };
+        if let Some(ref reboot) = reboot {
+            if self.reboots.len() == CAPACITY {
+                self.reboots.pop_front();
+            }
+            self.reboots.push_back(reboot.clone());
+        }
+        if self.samples.len() == CAPACITY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        reboot
+    }
+}
+
+static HISTORY: Mutex<DeviceHealth> = Mutex::new(DeviceHealth { samples: VecDeque::new(), reboots: VecDeque::new() });
+
+pub fn history() -> DeviceHealth {
+    HISTORY.lock().unwrap().clone()
+}
+
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL);
+        poll(&app);
+    });
+}
+
+fn poll(app: &AppHandle) {
+    let mut lost = false;
+    let sample = {
+        let state = app.state::<SharedState>();
+        let mut st = state.lock().unwrap();
+        let at_ms = crate::now_ms();
+        match st.device {
+            Some(ref dev) => {
+                let t0 = Instant::now();
+                let read = dev.get_uptime();
+                let latency_ms = Some(t0.elapsed().as_millis() as u32);
+                match read {
+                    Ok(uptime) => {
+                        if let Some(ref mut info) = st.device_info {
+                            info.uptime = uptime;
+                        }
+                        HealthSample { at_ms, connected: true, uptime: Some(uptime), latency_ms, error: None }
+                    }
+                    Err(e) => {
+                        let error = format!("{e:#}");
+                        // Not a slow or hung device (the watchdog's job): the link is gone
+                        if e.downcast_ref::<crate::hid::Timeout>().is_none() && e.downcast_ref::<crate::hid::Unhealthy>().is_none() {
+                            warn!("[heartbeat] Device lost: {error}");
+                            crate::release_device(&mut st);
+                            crate::connections::record(EventKind::Lost, Some(error.clone()));
+                            lost = true;
+                        }
+                        HealthSample { at_ms, connected: true, uptime: None, latency_ms, error: Some(error) }
+                    }
+                }
+            }
+            None => HealthSample { at_ms, connected: false, uptime: None, latency_ms: None, error: None },
+        }
+    };
+    let _ = app.emit("device-health", &sample);
+    if let Some(reboot) = HISTORY.lock().unwrap().push(sample) {
+        warn!("[heartbeat] Device rebooted (uptime {}s → {}s)", reboot.previous_uptime, reboot.uptime);
+        let _ = app.emit("device-rebooted", &reboot);
+    }
+    if lost {
+        let _ = app.emit("state-updated", app.state::<SharedState>().lock().unwrap().snapshot());
+    }
+}
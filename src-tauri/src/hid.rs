@@ -1,17 +1,265 @@
-use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
 use hidapi::{HidApi, HidDevice};
-use log::{info, debug, warn};
+use log::{info, debug, error, warn};
+use serde::{Deserialize, Serialize};
 
 use crate::protocol::{
-    self, DeviceInfo, HsvColor, RgbMatrixState, PID, USAGE_ID, USAGE_PAGE, VID,
+    self, Capabilities, DeviceInfo, HsvColor, RgbMatrixState, PID, USAGE_ID, USAGE_PAGE, VID,
     KB_VALUE_UPTIME, KB_VALUE_FIRMWARE_VERSION, KB_VALUE_DEVICE_INDICATION,
     RGB_VAL_BRIGHTNESS, RGB_VAL_EFFECT, RGB_VAL_EFFECT_SPEED, RGB_VAL_COLOR,
 };
 
 pub struct Deck8Device {
-    device: HidDevice,
+    device: Box<dyn Transport>,
+    /// Gates the per-key and RGB Matrix commands; set from the device info.
+    capabilities: Capabilities,
+    health: Arc<Health>,
+    timing: HidTiming,
+    /// When the last report went out, for `HidTiming::pacing_ms`.
+    last_sent: Mutex<Option<Instant>>,
+    writes: Mutex<Writes>,
+}
+
+/// What this connection wrote that the device may not keep or show, for the
+/// sync status in `StateSnapshot`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Writes {
+    /// Per-key colors the user edited since the last `custom_save`
+    /// (`note_color_edit`); notifications, fades and previews don't count.
+    pub colors_unsaved: bool,
+    /// RGB Matrix settings written since the last `rgb_save`.
+    pub rgb_unsaved: bool,
+    /// LEDs (bits) whose last color write failed.
+    pub colors_stale: u8,
+    /// An RGB Matrix write failed since the settings were last read back or
+    /// written in full (`rgb_synced`).
+    pub rgb_stale: bool,
+    /// Keys (bits) a failed keymap write left changed.
+    pub keymaps_stale: u8,
+}
+
+/// How long the device may go without acking before it's considered hung.
+/// Spans at least two reads at the longest `HidTiming::timeout_ms`, so one
+/// slow reply doesn't trip it.
+pub const DEADLINE: Duration = Duration::from_secs(2);
+
+/// Read timeout and spacing between reports, for hubs and firmware builds
+/// that drop reports sent back to back. Persisted in `AppSettings.hid`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct HidTiming {
+    /// How long to wait for each ack.
+    pub timeout_ms: u32,
+    /// Minimum gap between two reports (0 = back to back).
+    pub pacing_ms: u32,
+}
+
+impl HidTiming {
+    pub const TIMEOUT_RANGE: std::ops::RangeInclusive<u32> = 100..=1000;
+    pub const MAX_PACING_MS: u32 = 50;
+
+    pub fn validate(&self) -> Result<()> {
+        if !Self::TIMEOUT_RANGE.contains(&self.timeout_ms) {
+            bail!("The HID timeout must be {} to {} ms", Self::TIMEOUT_RANGE.start(), Self::TIMEOUT_RANGE.end());
+        }
+        if self.pacing_ms > Self::MAX_PACING_MS {
+            bail!("Report pacing can be at most {} ms", Self::MAX_PACING_MS);
+        }
+        Ok(())
+    }
+}
+
+impl Default for HidTiming {
+    fn default() -> Self {
+        Self { timeout_ms: 500, pacing_ms: 0 }
+    }
+}
+
+/// A request starting within this long of a failed one continues its stall.
+const STREAK_GAP: Duration = Duration::from_secs(1);
+
+/// In-flight request tracking, shared with the watchdog (which can't take the
+/// state lock while a hung call holds it).
+#[derive(Debug, Default)]
+pub struct Health {
+    stall: Mutex<Option<Stall>>,
+    unhealthy: AtomicBool,
+}
+
+/// Requests sent since the last ack.
+#[derive(Debug)]
+struct Stall {
+    since: Instant,
+    last: Instant,
+    in_flight: bool,
+}
+
+impl Health {
+    pub fn is_unhealthy(&self) -> bool {
+        self.unhealthy.load(Ordering::SeqCst)
+    }
+
+    /// A request was sent at `now`.
+    pub fn begin(&self, now: Instant) {
+        let mut stall = self.stall.lock().unwrap();
+        match stall.as_mut() {
+            Some(s) if now.saturating_duration_since(s.last) < STREAK_GAP => {
+                s.in_flight = true;
+                s.last = now;
+            }
+            _ => *stall = Some(Stall { since: now, last: now, in_flight: true }),
+        }
+    }
+
+    /// The request finished at `now`, `acked` or not.
+    pub fn end(&self, acked: bool, now: Instant) {
+        let mut stall = self.stall.lock().unwrap();
+        match stall.as_mut() {
+            Some(s) if !acked => {
+                s.in_flight = false;
+                s.last = now;
+            }
+            _ => *stall = None,
+        }
+    }
+
+    /// Mark the device unhealthy if a request is in flight and nothing has
+    /// been acked for `DEADLINE` at `now`. Returns whether it's unhealthy.
+    pub fn check_at(&self, now: Instant) -> bool {
+        let stalled = self.stall.lock().unwrap().as_ref()
+            .is_some_and(|s| s.in_flight && now.saturating_duration_since(s.since) >= DEADLINE);
+        if stalled && !self.unhealthy.swap(true, Ordering::SeqCst) {
+            warn!("[HID] No ack for {}ms, marking device unhealthy", DEADLINE.as_millis());
+        }
+        self.is_unhealthy()
+    }
+}
+
+/// The raw HID link `Deck8Device` speaks VIA over. The real one is a hidapi
+/// handle; tests plug in an in-memory firmware.
+pub trait Transport: Send {
+    /// Write one report (report ID 0x00 + 32 bytes).
+    fn write(&self, report: &[u8; 33]) -> Result<()>;
+    /// Read one 32-byte report. Returns 0 when nothing arrived in time.
+    fn read_timeout(&self, buf: &mut [u8; 32], timeout_ms: i32) -> Result<usize>;
+}
+
+impl Transport for HidDevice {
+    fn write(&self, report: &[u8; 33]) -> Result<()> {
+        HidDevice::write(self, report)?;
+        Ok(())
+    }
+
+    fn read_timeout(&self, buf: &mut [u8; 32], timeout_ms: i32) -> Result<usize> {
+        Ok(HidDevice::read_timeout(self, buf, timeout_ms)?)
+    }
+}
+
+/// The device didn't answer within the read timeout.
+#[derive(Debug)]
+pub struct Timeout;
+
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HID read timed out")
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+/// The firmware didn't report the feature a command needs.
+#[derive(Debug)]
+pub struct Unsupported(pub &'static str);
+
+impl std::fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "This firmware doesn't support {}", self.0)
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+/// The watchdog gave up on the device; requests fail fast until it reconnects.
+#[derive(Debug)]
+pub struct Unhealthy;
+
+impl std::fmt::Display for Unhealthy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Device stopped responding, reconnecting")
+    }
+}
+
+impl std::error::Error for Unhealthy {}
+
+/// No Deck-8 is plugged in (or it enumerates with another VID/PID/usage).
+#[derive(Debug)]
+pub struct NotFound;
+
+impl std::fmt::Display for NotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Deck-8 not found (VID/PID/Usage mismatch)")
+    }
+}
+
+impl std::error::Error for NotFound {}
+
+/// The Deck-8 is there but another program (VIA, a vendor configurator) holds
+/// its raw HID interface.
+#[derive(Debug)]
+pub struct Busy;
+
+impl std::fmt::Display for Busy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Another app is using the Deck-8")
+    }
+}
+
+impl std::error::Error for Busy {}
+
+/// Whether an open error means the interface is held elsewhere, as opposed to
+/// gone or off-limits (Linux permissions are a udev rule away, not busy).
+pub fn is_busy(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    [
+        "used by another process",  // Windows ERROR_SHARING_VIOLATION
+        "access is denied",         // Windows, opened without sharing
+        "exclusive access",         // macOS kIOReturnExclusiveAccess
+        "0xe00002c5",
+        "resource busy",            // Linux EBUSY
+    ]
+    .iter()
+    .any(|m| message.contains(m))
+}
+
+/// A keymap write that didn't stick (`write_keymaps`). Keys are matrix
+/// indices.
+#[derive(Debug)]
+pub struct KeymapWriteFailed {
+    pub key_index: u8,
+    /// The keycode read back, or the HID error.
+    pub reason: String,
+    /// Keys put back to their previous keycode.
+    pub rolled_back: Vec<u8>,
+    /// Keys that couldn't be put back; the device now differs from the hub.
+    pub stranded: Vec<u8>,
+}
+
+impl std::fmt::Display for KeymapWriteFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Keymap write to key {} failed ({}); {} key(s) put back", self.key_index + 1, self.reason, self.rolled_back.len())?;
+        if !self.stranded.is_empty() {
+            let keys: Vec<String> = self.stranded.iter().map(|k| (k + 1).to_string()).collect();
+            write!(f, "; keys {} couldn't be rolled back", keys.join(", "))?;
+        }
+        Ok(())
+    }
 }
 
+impl std::error::Error for KeymapWriteFailed {}
+
 impl Deck8Device {
     /// Enumerate USB HID devices and open the Deck-8 raw HID interface.
     pub fn open() -> Result<Self> {
@@ -24,42 +272,152 @@ impl Deck8Device {
                     && d.usage_page() == USAGE_PAGE
                     && d.usage() == USAGE_ID
             })
-            .context("Deck-8 not found (VID/PID/Usage mismatch)")?;
+            .ok_or(NotFound)?;
 
         info!(
             "Found Deck-8 at path: {:?}",
             dev_info.path().to_str().unwrap_or("?")
         );
 
-        let device = dev_info
-            .open_device(&api)
-            .context("Failed to open Deck-8 HID device")?;
-        Ok(Self { device })
+        let device = match dev_info.open_device(&api) {
+            Ok(device) => device,
+            Err(e) if is_busy(&e.to_string()) => {
+                warn!("[HID] Deck-8 is busy: {e}");
+                return Err(Busy.into());
+            }
+            Err(e) => return Err(anyhow::Error::new(e).context("Failed to open Deck-8 HID device")),
+        };
+        Ok(Self::with_transport(Box::new(device)))
+    }
+
+    pub fn with_transport(device: Box<dyn Transport>) -> Self {
+        Self {
+            device,
+            capabilities: Capabilities::LEGACY,
+            health: Arc::default(),
+            timing: HidTiming::default(),
+            last_sent: Mutex::new(None),
+            writes: Mutex::default(),
+        }
+    }
+
+    pub fn writes(&self) -> Writes {
+        *self.writes.lock().unwrap()
+    }
+
+    /// Record a per-key color write to `key_id`.
+    fn note_color(&self, key_id: u8, result: &Result<()>) {
+        let mut writes = self.writes.lock().unwrap();
+        let bit = 1u8.checked_shl(key_id as u32).unwrap_or(0);
+        if result.is_ok() {
+            writes.colors_stale &= !bit;
+        } else {
+            writes.colors_stale |= bit;
+        }
+    }
+
+    /// The user changed key colors: they're lost on unplug until `custom_save`.
+    pub fn note_color_edit(&self) {
+        self.writes.lock().unwrap().colors_unsaved = true;
+    }
+
+    /// The device's RGB Matrix settings are what the app has: read back, or
+    /// all written and verified.
+    pub fn rgb_synced(&self) {
+        self.writes.lock().unwrap().rgb_stale = false;
+    }
+
+    /// Send an RGB Matrix setting; it's live until `rgb_save`.
+    fn rgb_write(&self, cmd: &[u8; 32]) -> Result<()> {
+        let result = self.send_and_receive(cmd).map(|_| ());
+        let mut writes = self.writes.lock().unwrap();
+        writes.rgb_stale |= result.is_err();
+        writes.rgb_unsaved |= result.is_ok();
+        result
+    }
+
+    pub fn health(&self) -> Arc<Health> {
+        Arc::clone(&self.health)
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
+    pub fn set_timing(&mut self, timing: HidTiming) {
+        self.timing = timing;
+    }
+
+    fn require_per_key(&self) -> Result<()> {
+        if !self.capabilities.per_key_override {
+            return Err(Unsupported("per-key colors").into());
+        }
+        Ok(())
+    }
+
+    fn require_rgb_matrix(&self) -> Result<()> {
+        if !self.capabilities.rgb_matrix {
+            return Err(Unsupported("RGB Matrix settings").into());
+        }
+        Ok(())
     }
 
     // ── Per-key LED commands ────────────────────────────────────────────
 
-    /// Set a key's LED color by sending the 3-message sequence:
-    /// enable override, set color (H+S), set brightness (V).
-    /// Each report waits for firmware acknowledgment to prevent USB buffer overflow.
+    /// Set a key's LED color: one combined report on firmware with `set_hsv`,
+    /// else the 3-message sequence enable override, set color (H+S), set
+    /// brightness (V). Each report waits for firmware acknowledgment to
+    /// prevent USB buffer overflow.
     pub fn set_key_color(&self, key_id: u8, color: &HsvColor) -> Result<()> {
+        self.require_per_key()?;
+        let result = self.write_key_color(key_id, color);
+        self.note_color(key_id, &result);
+        result
+    }
+
+    fn write_key_color(&self, key_id: u8, color: &HsvColor) -> Result<()> {
         debug!("[HID] set_key_color led={} h={} s={} v={}", key_id, color.h, color.s, color.v);
-        let resp = self.send_and_receive(&protocol::build_enable_override(key_id), 500)?;
+        if self.capabilities.set_hsv {
+            let resp = self.send_and_receive(&protocol::build_set_hsv(key_id, color))?;
+            if resp[0] == 0xFF { warn!("[HID] set_hsv led={} → UNHANDLED", key_id); }
+            return Ok(());
+        }
+        let resp = self.send_and_receive(&protocol::build_enable_override(key_id))?;
         if resp[0] == 0xFF { warn!("[HID] enable_override led={} → UNHANDLED", key_id); }
-        let resp = self.send_and_receive(&protocol::build_set_color(key_id, color), 500)?;
+        let resp = self.send_and_receive(&protocol::build_set_color(key_id, color))?;
         if resp[0] == 0xFF { warn!("[HID] set_color led={} → UNHANDLED", key_id); }
-        let resp = self.send_and_receive(&protocol::build_set_brightness(key_id, color.v), 500)?;
+        let resp = self.send_and_receive(&protocol::build_set_brightness(key_id, color.v))?;
         if resp[0] == 0xFF { warn!("[HID] set_brightness led={} → UNHANDLED", key_id); }
         Ok(())
     }
 
+    /// Set several keys in one call (e.g. applying a theme). The firmware has
+    /// no multi-key report, so this is still one acked report (or sequence)
+    /// per key, but nothing else can interleave while the caller holds the
+    /// state lock.
+    pub fn set_key_colors(&self, colors: &[(u8, HsvColor)]) -> Result<()> {
+        for (key_id, color) in colors {
+            self.set_key_color(*key_id, color)?;
+        }
+        Ok(())
+    }
+
     /// Disable per-key override, restoring the original color/animation.
     /// Waits for firmware acknowledgment.
     pub fn disable_override(&self, key_id: u8) -> Result<()> {
+        self.require_per_key()?;
         debug!("[HID] disable_override led={}", key_id);
-        let resp = self.send_and_receive(&protocol::build_disable_override(key_id), 500)?;
-        if resp[0] == 0xFF { warn!("[HID] disable_override led={} → UNHANDLED", key_id); }
-        Ok(())
+        let result = self.send_and_receive(&protocol::build_disable_override(key_id));
+        if let Ok(resp) = result {
+            if resp[0] == 0xFF { warn!("[HID] disable_override led={} → UNHANDLED", key_id); }
+        }
+        let result = result.map(|_| ());
+        self.note_color(key_id, &result);
+        result
     }
 
     // ── Keymap commands ─────────────────────────────────────────────────
@@ -67,7 +425,7 @@ impl Deck8Device {
     /// Read the keycode for a specific key position from the device.
     pub fn get_keycode(&self, layer: u8, row: u8, col: u8) -> Result<u16> {
         let cmd = protocol::build_get_keycode(layer, row, col);
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         let keycode = ((resp[4] as u16) << 8) | (resp[5] as u16);
         Ok(keycode)
     }
@@ -75,7 +433,52 @@ impl Deck8Device {
     /// Write a keycode to a specific key position on the device.
     pub fn set_keycode(&self, layer: u8, row: u8, col: u8, keycode: u16) -> Result<()> {
         let cmd = protocol::build_set_keycode(layer, row, col, keycode);
-        let _resp = self.send_and_receive(&cmd, 500)?;
+        let _resp = self.send_and_receive(&cmd)?;
+        Ok(())
+    }
+
+    /// Write a layer-0 keycode and read it back. Errors with the value read if
+    /// the device didn't keep it.
+    fn write_keycode_verified(&self, key_index: u8, keycode: u16) -> Result<()> {
+        let (row, col) = protocol::key_index_to_matrix(key_index);
+        self.set_keycode(0, row, col, keycode)?;
+        let read = self.get_keycode(0, row, col)?;
+        if read != keycode {
+            anyhow::bail!("wrote 0x{keycode:04X}, reads back 0x{read:04X}");
+        }
+        Ok(())
+    }
+
+    /// Write layer-0 keycodes as `(key_index, previous, new)`, verifying each.
+    /// On the first failure every key written so far (and the failing one) is
+    /// put back to `previous`, and a `KeymapWriteFailed` is returned.
+    pub fn write_keymaps(&self, writes: &[(u8, u16, u16)]) -> Result<()> {
+        let bit = |key: u8| 1u8.checked_shl(key as u32).unwrap_or(0);
+        for (n, &(key_index, _, keycode)) in writes.iter().enumerate() {
+            let Err(e) = self.write_keycode_verified(key_index, keycode) else {
+                self.writes.lock().unwrap().keymaps_stale &= !bit(key_index);
+                continue;
+            };
+            warn!("[HID] keymap write key={} 0x{:04X} failed: {:#}; rolling back {} key(s)", key_index, keycode, e, n + 1);
+            let (mut rolled_back, mut stranded) = (Vec::new(), Vec::new());
+            for &(key, previous, _) in writes[..=n].iter().rev() {
+                match self.write_keycode_verified(key, previous) {
+                    Ok(()) => rolled_back.push(key),
+                    Err(e) => {
+                        error!("[HID] keymap rollback key={} FAILED: {:#}", key, e);
+                        stranded.push(key);
+                    }
+                }
+            }
+            let mut tracked = self.writes.lock().unwrap();
+            for &key in &rolled_back {
+                tracked.keymaps_stale &= !bit(key);
+            }
+            for &key in &stranded {
+                tracked.keymaps_stale |= bit(key);
+            }
+            return Err(KeymapWriteFailed { key_index, reason: format!("{e:#}"), rolled_back, stranded }.into());
+        }
         Ok(())
     }
 
@@ -89,17 +492,33 @@ impl Deck8Device {
         Ok(keymaps)
     }
 
+    /// The 8 keycodes of every layer, layer 0 first.
+    pub fn read_all_layers(&self) -> Result<Vec<[u16; 8]>> {
+        let mut layers = Vec::new();
+        for layer in 0..self.get_layer_count()? {
+            let mut keymaps = [0u16; 8];
+            for i in 0..8u8 {
+                let (row, col) = protocol::key_index_to_matrix(i);
+                keymaps[i as usize] = self.get_keycode(layer, row, col)?;
+            }
+            layers.push(keymaps);
+        }
+        Ok(layers)
+    }
+
     /// Reset dynamic keymap to firmware defaults.
     pub fn dynamic_keymap_reset(&self) -> Result<()> {
         let cmd = protocol::build_dynamic_keymap_reset();
-        let _resp = self.send_and_receive(&cmd, 500)?;
+        let _resp = self.send_and_receive(&cmd)?;
+        // Every key is back to its default, which the app reads back
+        self.writes.lock().unwrap().keymaps_stale = 0;
         Ok(())
     }
 
     /// Get the number of layers supported by the keyboard.
     pub fn get_layer_count(&self) -> Result<u8> {
         let cmd = protocol::build_get_layer_count();
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         Ok(resp[1])
     }
 
@@ -108,7 +527,7 @@ impl Deck8Device {
     /// Get the VIA protocol version (e.g. 12 = 0x000C).
     pub fn get_protocol_version(&self) -> Result<u16> {
         let cmd = protocol::build_get_protocol_version();
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         let version = ((resp[1] as u16) << 8) | (resp[2] as u16);
         Ok(version)
     }
@@ -116,7 +535,7 @@ impl Deck8Device {
     /// Get the device uptime in seconds.
     pub fn get_uptime(&self) -> Result<u32> {
         let cmd = protocol::build_get_keyboard_value(KB_VALUE_UPTIME);
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         let uptime = ((resp[2] as u32) << 24)
             | ((resp[3] as u32) << 16)
             | ((resp[4] as u32) << 8)
@@ -127,7 +546,7 @@ impl Deck8Device {
     /// Get the firmware version as a packed u32.
     pub fn get_firmware_version(&self) -> Result<u32> {
         let cmd = protocol::build_get_keyboard_value(KB_VALUE_FIRMWARE_VERSION);
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         let version = ((resp[2] as u32) << 24)
             | ((resp[3] as u32) << 16)
             | ((resp[4] as u32) << 8)
@@ -138,7 +557,7 @@ impl Deck8Device {
     /// Trigger the device indication LED pattern (identify device).
     pub fn device_indication(&self) -> Result<()> {
         let cmd = protocol::build_set_keyboard_value(KB_VALUE_DEVICE_INDICATION, 1);
-        let _resp = self.send_and_receive(&cmd, 500)?;
+        let _resp = self.send_and_receive(&cmd)?;
         Ok(())
     }
 
@@ -153,18 +572,28 @@ impl Deck8Device {
     /// Reset EEPROM to factory defaults.
     pub fn eeprom_reset(&self) -> Result<()> {
         let cmd = protocol::build_eeprom_reset();
-        let _resp = self.send_and_receive(&cmd, 500)?;
+        let _resp = self.send_and_receive(&cmd)?;
+        // The device is back to defaults until the app writes its setup again
+        let mut writes = self.writes.lock().unwrap();
+        writes.colors_stale = u8::MAX;
+        writes.rgb_stale = true;
         Ok(())
     }
 
-    /// Get aggregate device info.
-    pub fn get_device_info(&self) -> Result<DeviceInfo> {
-        let protocol_version = self.get_protocol_version()?;
+    /// Read the device info. Everything but uptime is fixed by the firmware, so
+    /// when `cached` has the same firmware version its other fields are reused
+    /// (2 round-trips instead of 7).
+    pub fn get_device_info(&self, cached: Option<&DeviceInfo>) -> Result<DeviceInfo> {
         let firmware_version = self.get_firmware_version()?;
         let uptime = self.get_uptime()?;
+        if let Some(c) = cached.filter(|c| c.firmware_version == firmware_version) {
+            return Ok(DeviceInfo { uptime, ..c.clone() });
+        }
+        let protocol_version = self.get_protocol_version()?;
         let layer_count = self.get_layer_count()?;
         let macro_count = self.get_macro_count()?;
         let macro_buffer_size = self.get_macro_buffer_size()?;
+        let capabilities = self.get_capabilities()?;
         Ok(DeviceInfo {
             protocol_version,
             firmware_version,
@@ -172,94 +601,123 @@ impl Deck8Device {
             layer_count,
             macro_count,
             macro_buffer_size,
+            capabilities,
         })
     }
 
+    /// Ask the firmware which custom-channel features it has. Builds without
+    /// the handshake answer "unhandled" and get `Capabilities::LEGACY`.
+    pub fn get_capabilities(&self) -> Result<Capabilities> {
+        let resp = self.send_and_receive(&protocol::build_get_features())?;
+        if resp[0] == 0xFF {
+            info!("[HID] features query unhandled → legacy firmware");
+            return Ok(Capabilities::LEGACY);
+        }
+        Ok(Capabilities::from_bits(u16::from_be_bytes([resp[3], resp[4]])))
+    }
+
     // ── Macro commands ──────────────────────────────────────────────────
 
     /// Get the number of macros supported by the keyboard.
     pub fn get_macro_count(&self) -> Result<u8> {
         let cmd = protocol::build_macro_get_count();
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         Ok(resp[1])
     }
 
     /// Get the macro buffer size in bytes.
     pub fn get_macro_buffer_size(&self) -> Result<u16> {
         let cmd = protocol::build_macro_get_buffer_size();
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         let size = ((resp[1] as u16) << 8) | (resp[2] as u16);
         Ok(size)
     }
 
+    /// Read the whole macro buffer (NUL-separated macros).
+    pub fn read_macro_buffer(&self) -> Result<Vec<u8>> {
+        let size = self.get_macro_buffer_size()?;
+        let mut buffer = Vec::with_capacity(size as usize);
+        while buffer.len() < size as usize {
+            let chunk = (size as usize - buffer.len()).min(protocol::MACRO_CHUNK as usize) as u8;
+            let cmd = protocol::build_macro_get_buffer(buffer.len() as u16, chunk);
+            let resp = self.send_and_receive(&cmd)?;
+            buffer.extend_from_slice(&resp[4..4 + chunk as usize]);
+        }
+        Ok(buffer)
+    }
+
     /// Reset all macros to empty.
     pub fn macro_reset(&self) -> Result<()> {
         let cmd = protocol::build_macro_reset();
-        let _resp = self.send_and_receive(&cmd, 500)?;
+        let _resp = self.send_and_receive(&cmd)?;
         Ok(())
     }
 
     // ── RGB Matrix commands ─────────────────────────────────────────────
 
     pub fn rgb_get_brightness(&self) -> Result<u8> {
+        self.require_rgb_matrix()?;
         let cmd = protocol::build_rgb_get_value(RGB_VAL_BRIGHTNESS);
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         Ok(resp[3])
     }
 
     pub fn rgb_set_brightness(&self, val: u8) -> Result<()> {
-        let cmd = protocol::build_rgb_set_value_u8(RGB_VAL_BRIGHTNESS, val);
-        let _resp = self.send_and_receive(&cmd, 500)?;
-        Ok(())
+        self.require_rgb_matrix()?;
+        self.rgb_write(&protocol::build_rgb_set_value_u8(RGB_VAL_BRIGHTNESS, val))
     }
 
     pub fn rgb_get_effect(&self) -> Result<u8> {
+        self.require_rgb_matrix()?;
         let cmd = protocol::build_rgb_get_value(RGB_VAL_EFFECT);
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         Ok(resp[3])
     }
 
     pub fn rgb_set_effect(&self, val: u8) -> Result<()> {
-        let cmd = protocol::build_rgb_set_value_u8(RGB_VAL_EFFECT, val);
-        let _resp = self.send_and_receive(&cmd, 500)?;
-        Ok(())
+        self.require_rgb_matrix()?;
+        self.rgb_write(&protocol::build_rgb_set_value_u8(RGB_VAL_EFFECT, val))
     }
 
     pub fn rgb_get_speed(&self) -> Result<u8> {
+        self.require_rgb_matrix()?;
         let cmd = protocol::build_rgb_get_value(RGB_VAL_EFFECT_SPEED);
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         Ok(resp[3])
     }
 
     pub fn rgb_set_speed(&self, val: u8) -> Result<()> {
-        let cmd = protocol::build_rgb_set_value_u8(RGB_VAL_EFFECT_SPEED, val);
-        let _resp = self.send_and_receive(&cmd, 500)?;
-        Ok(())
+        self.require_rgb_matrix()?;
+        self.rgb_write(&protocol::build_rgb_set_value_u8(RGB_VAL_EFFECT_SPEED, val))
     }
 
     pub fn rgb_get_color(&self) -> Result<(u8, u8)> {
+        self.require_rgb_matrix()?;
         let cmd = protocol::build_rgb_get_value(RGB_VAL_COLOR);
-        let resp = self.send_and_receive(&cmd, 500)?;
+        let resp = self.send_and_receive(&cmd)?;
         Ok((resp[3], resp[4]))
     }
 
     pub fn rgb_set_color(&self, h: u8, s: u8) -> Result<()> {
-        let cmd = protocol::build_rgb_set_color(h, s);
-        let _resp = self.send_and_receive(&cmd, 500)?;
-        Ok(())
+        self.require_rgb_matrix()?;
+        self.rgb_write(&protocol::build_rgb_set_color(h, s))
     }
 
     /// Save current RGB Matrix settings to EEPROM.
     pub fn rgb_save(&self) -> Result<()> {
+        self.require_rgb_matrix()?;
         let cmd = protocol::build_rgb_save();
-        let _resp = self.send_and_receive(&cmd, 500)?;
+        let _resp = self.send_and_receive(&cmd)?;
+        self.writes.lock().unwrap().rgb_unsaved = false;
         Ok(())
     }
 
     /// Save per-key LED overrides to EEPROM.
     pub fn custom_save(&self) -> Result<()> {
+        self.require_per_key()?;
         let cmd = protocol::build_custom_save();
-        let _resp = self.send_and_receive(&cmd, 500)?;
+        let _resp = self.send_and_receive(&cmd)?;
+        self.writes.lock().unwrap().colors_unsaved = false;
         Ok(())
     }
 
@@ -288,15 +746,38 @@ impl Deck8Device {
             .read_timeout(&mut buf, timeout_ms)
             .context("Failed to read HID response")?;
         if n == 0 {
-            anyhow::bail!("HID read timed out");
+            return Err(Timeout.into());
         }
         Ok(buf)
     }
 
-    /// Send a report and read back the response.
-    fn send_and_receive(&self, report: &[u8; 32], timeout_ms: i32) -> Result<[u8; 32]> {
-        self.send_report(report)?;
-        self.read_response(timeout_ms)
+    /// Send a report and read back the response. The wait counts toward the
+    /// watchdog deadline until some request is acked.
+    fn send_and_receive(&self, report: &[u8; 32]) -> Result<[u8; 32]> {
+        if self.health.is_unhealthy() {
+            return Err(Unhealthy.into());
+        }
+        self.pace();
+        self.health.begin(Instant::now());
+        let result = self.send_report(report).and_then(|()| self.read_response(self.timing.timeout_ms as i32));
+        // Checked while still in flight: stop here rather than after the rest
+        // of the sequence times out too
+        let unhealthy = result.is_err() && self.health.check_at(Instant::now());
+        self.health.end(result.is_ok(), Instant::now());
+        if unhealthy {
+            return Err(Unhealthy.into());
+        }
+        result
+    }
+
+    /// Wait out what's left of `pacing_ms` since the last report.
+    fn pace(&self) {
+        let gap = Duration::from_millis(self.timing.pacing_ms as u64);
+        let mut last_sent = self.last_sent.lock().unwrap();
+        if let Some(wait) = last_sent.and_then(|t| gap.checked_sub(t.elapsed())) {
+            std::thread::sleep(wait);
+        }
+        *last_sent = Some(Instant::now());
     }
 
     /// Send a 32-byte report prepended with Report ID 0x00 (33 bytes total).
@@ -0,0 +1,72 @@
+// Request reading for the app's small local HTTP endpoints (game.rs,
+// pairing.rs): the request line, headers and a Content-Length body, each
+// bounded so a client that never ends its line or lies about its length
+// can't grow memory. Errors are the status line to answer with.
+
+use std::io::{BufRead, BufReader, Read};
+
+/// Request line plus headers.
+const MAX_HEAD: u64 = 16 * 1024;
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// First header called `name` (any case), trimmed.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+}
+
+/// Read one request whose body is at most `max_body` bytes.
+pub fn read_request(stream: impl Read, max_body: usize) -> Result<Request, &'static str> {
+    let mut reader = BufReader::new(stream.take(MAX_HEAD));
+    let line = read_line(&mut reader)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err("400 Bad Request");
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(&mut reader)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    let mut request = Request { method, path, headers, body: Vec::new() };
+    let length: usize = match request.header("content-length") {
+        Some(value) => value.parse().map_err(|_| "400 Bad Request")?,
+        None => 0,
+    };
+    if length > max_body {
+        return Err("413 Payload Too Large");
+    }
+    // The head's limit no longer applies; allow exactly the body past what's buffered
+    let buffered = reader.buffer().len() as u64;
+    reader.get_mut().set_limit((length as u64).saturating_sub(buffered));
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body).map_err(|_| "400 Bad Request")?;
+    Ok(request)
+}
+
+/// One line of the head; running into `MAX_HEAD` before its end is refused.
+fn read_line(reader: &mut impl BufRead) -> Result<String, &'static str> {
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|_| "400 Bad Request")?;
+    if line.is_empty() {
+        return Err("400 Bad Request");
+    }
+    if !line.ends_with('\n') {
+        return Err("431 Request Header Fields Too Large");
+    }
+    Ok(line)
+}
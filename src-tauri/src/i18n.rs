@@ -0,0 +1,153 @@
+// Translations for backend strings (tray menu, announcements, errors).
+//
+// Catalogs are Fluent-style files in `locales/`, compiled in: one
+// `id = text` message per line, `{ $name }` placeholders and `#` comments
+// (no selectors or multi-line values). A message missing from the current
+// language falls back to English, then to its id, so a partial translation
+// never breaks the UI.
+//
+// The language is `AppSettings.language`, or the OS locale when that is unset
+// (`set_language`). Strings already handed out (the tray menu) are rebuilt by
+// the caller after a change.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{OnceLock, RwLock};
+
+use serde::Serialize;
+
+const SOURCES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+];
+const FALLBACK: &str = "en";
+
+static CURRENT: RwLock<&str> = RwLock::new(FALLBACK);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Language {
+    pub code: &'static str,
+    /// The language's name in itself ("Español").
+    pub name: &'static str,
+}
+
+type Catalog = HashMap<&'static str, &'static str>;
+
+fn catalogs() -> &'static HashMap<&'static str, Catalog> {
+    static CATALOGS: OnceLock<HashMap<&'static str, Catalog>> = OnceLock::new();
+    CATALOGS.get_or_init(|| SOURCES.iter().map(|&(code, source)| (code, parse(source))).collect())
+}
+
+/// `id = text` lines; blank lines and `#` comments are skipped.
+pub fn parse(source: &str) -> HashMap<&str, &str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(id, text)| (id.trim(), text.trim()))
+        .collect()
+}
+
+pub fn languages() -> Vec<Language> {
+    SOURCES
+        .iter()
+        .map(|&(code, _)| Language { code, name: catalogs()[code].get("language-name").copied().unwrap_or(code) })
+        .collect()
+}
+
+/// Switch to `language`, or the OS locale's language (English if there is no
+/// catalog for it) when `None`.
+pub fn set(language: Option<&str>) -> Result<(), String> {
+    let code = match language {
+        Some(code) => SOURCES
+            .iter()
+            .map(|&(c, _)| c)
+            .find(|&c| c == code)
+            .ok_or_else(|| t_with("error-no-translation", &[("language", &code)]))?,
+        None => {
+            let detected = platform::locale();
+            let code = detected.as_deref().map(language_code).unwrap_or(FALLBACK);
+            SOURCES.iter().map(|&(c, _)| c).find(|&c| c == code).unwrap_or(FALLBACK)
+        }
+    };
+    *CURRENT.write().unwrap() = code;
+    Ok(())
+}
+
+pub fn current() -> &'static str {
+    *CURRENT.read().unwrap()
+}
+
+/// The language part of a locale: "es_AR.UTF-8", "es-AR" → "es".
+pub fn language_code(locale: &str) -> &str {
+    locale.split(['_', '-', '.', '@']).next().unwrap_or(locale)
+}
+
+/// A message in the current language.
+pub fn t(id: &str) -> String {
+    t_with(id, &[])
+}
+
+/// A message with its `{ $name }` placeholders filled in.
+pub fn t_with(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let catalogs = catalogs();
+    let text = [current(), FALLBACK].iter().find_map(|code| catalogs[code].get(id).copied()).unwrap_or(id);
+    format(text, args)
+}
+
+/// Fill `{ $name }` placeholders; unknown ones are left as they are.
+pub fn format(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else { break };
+        let placeable = &rest[start..start + len + 1];
+        let name = placeable[1..len].trim().trim_start_matches('$');
+        match args.iter().find(|(n, _)| *n == name) {
+            Some((_, value)) => out.push_str(&value.to_string()),
+            None => out.push_str(placeable),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    const LOCALE_NAME_MAX_LENGTH: usize = 85;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetUserDefaultLocaleName(name: *mut u16, len: i32) -> i32;
+    }
+
+    pub fn locale() -> Option<String> {
+        let mut buf = [0u16; LOCALE_NAME_MAX_LENGTH];
+        let len = unsafe { GetUserDefaultLocaleName(buf.as_mut_ptr(), buf.len() as i32) };
+        // The length includes the terminating NUL
+        (len > 1).then(|| String::from_utf16_lossy(&buf[..len as usize - 1]))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    pub fn locale() -> Option<String> {
+        // Apps started from Finder don't get LANG
+        let out = std::process::Command::new("defaults").args(["read", "-g", "AppleLocale"]).output().ok()?;
+        let locale = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        (out.status.success() && !locale.is_empty()).then_some(locale)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    pub fn locale() -> Option<String> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|v| !v.is_empty() && v != "C" && v != "POSIX")
+    }
+}
@@ -9,14 +9,66 @@
 //    physically interacts with the desktop. Can BLOCK keystrokes (return 1)
 //    for internal shortcuts. Coexists with other apps using hooks (e.g. Wispr Flow).
 //
-// Both mechanisms always run. A per-key timestamp dedup (DEDUP_MS) prevents
-// double-firing when both detect the same keystroke.
+// Both mechanisms always run. A per-key timestamp dedup (DEDUP_MS, the
+// dedup_ms setting) prevents double-firing when both detect the same
+// keystroke. Key-ups are dispatched too (crate::on_key_event): HELD records
+// which VK holds each key, and the first mechanism to see its release
+// clears it. Every backend hands its events to one worker thread (send_key_event)
+// so a quick tap's release can never be handled before its press.
+//
+// Capture mode (start_capture): the LL hook swallows the next chord from any
+// keyboard and hands it to a callback as a QMK keycode; shortcuts are paused
+// meanwhile. Windows only — the Linux reader only sees the Deck-8.
+//
+// Keys set to "Deck-8 only" (KeySource::Deck) are matched by Raw Input alone:
+// its device handle tells the Deck-8 apart from the main keyboard, while the
+// LL hook can't see where a keystroke came from.
+//
+// Linux: the Deck-8's evdev nodes (/dev/input/eventN) are read directly, which
+// works the same under X11 and Wayland. Needs read access to the nodes (see
+// UDEV_RULES). Optionally the device is grabbed (EVIOCGRAB) so sound-only keys
+// never reach other apps; everything else is re-emitted through a uinput
+// passthrough device. When no node can be opened, lib.rs falls back to the
+// GlobalShortcuts portal (wayland_shortcuts.rs) or tauri_plugin_global_shortcut.
+
+use std::sync::{mpsc, OnceLock};
+
+/// Start a thread that hands each (target, key, pressed) event to `handler`, in
+/// the order they were sent.
+pub(crate) fn spawn_key_worker<T: Send + 'static>(
+    handler: impl Fn(T, usize, bool) + Send + 'static,
+) -> mpsc::Sender<(T, usize, bool)> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for (target, led_idx, pressed) in rx {
+            handler(target, led_idx, pressed);
+        }
+    });
+    tx
+}
+
+/// Queue a key press or release for crate::on_key_event. Hook and reader
+/// threads must return fast, and a thread per event would let a release
+/// overtake its press, leaving the key held forever.
+#[allow(dead_code)] // Windows and Linux only
+pub fn send_key_event(app: &tauri::AppHandle, led_idx: usize, pressed: bool) {
+    static WORKER: OnceLock<mpsc::Sender<(tauri::AppHandle, usize, bool)>> = OnceLock::new();
+    let tx = WORKER.get_or_init(|| {
+        spawn_key_worker(|app: tauri::AppHandle, led_idx, pressed| {
+            crate::on_key_event(&app, led_idx, pressed);
+        })
+    });
+    let _ = tx.send((app.clone(), led_idx, pressed));
+}
 
 #[cfg(target_os = "windows")]
 mod windows_impl {
     use log::{error, info};
-    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
     use std::sync::{Mutex, OnceLock};
+    use tauri::Manager;
 
     // Tracked modifier state for the LL hook (main thread).
     // Updated from hook_proc on every modifier key event.
@@ -30,12 +82,31 @@ mod windows_impl {
     // Dedup: per-key timestamp of the last toggle, to avoid double-firing
     // when both LL hook and Raw Input detect the same keystroke.
     // Value is GetTickCount64() in milliseconds.
-    const DEDUP_MS: u64 = 150;
+    static DEDUP_MS: AtomicU64 = AtomicU64::new(crate::state::DEFAULT_DEDUP_MS);
     static LAST_TOGGLE: [AtomicU64; 8] = [
         AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0),
         AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0),
     ];
 
+    // VK currently holding each key down (0 = released). Filters OS autorepeat
+    // and pairs each press with exactly one release across both mechanisms.
+    static HELD: [AtomicU32; 8] = [
+        AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0),
+        AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0),
+    ];
+
+    // Capture mode: the LL hook hands the next chord to CAPTURE's callback.
+    // CAPTURING is the lock-free check for the hot path.
+    type CaptureCallback = Box<dyn FnOnce(Option<u16>) + Send>;
+    // Whether each mechanism came up, for backends()
+    static LL_HOOK_OK: AtomicBool = AtomicBool::new(false);
+    static RAW_INPUT_OK: AtomicBool = AtomicBool::new(false);
+
+    static CAPTURING: AtomicBool = AtomicBool::new(false);
+    static CAPTURE: Mutex<Option<(u64, CaptureCallback)>> = Mutex::new(None);
+    static CAPTURE_ID: AtomicU64 = AtomicU64::new(0);
+    const VK_ESCAPE: u32 = 0x1B;
+
     // Raw Input modifier tracking — separate from LL hook atomics because
     // raw input arrives on a different thread.
     static RAW_MOD_CTRL: AtomicBool = AtomicBool::new(false);
@@ -43,6 +114,10 @@ mod windows_impl {
     static RAW_MOD_ALT: AtomicBool = AtomicBool::new(false);
     static RAW_MOD_GUI: AtomicBool = AtomicBool::new(false);
 
+    // Set when any registered shortcut has no modifiers, so plain keystrokes
+    // only pay for the state lookup when they could actually match.
+    static HAS_BARE: AtomicBool = AtomicBool::new(false);
+
     // ── Win32 constants ──────────────────────────────────────────────
     const WH_KEYBOARD_LL: i32 = 13;
     const WM_KEYDOWN: u32 = 0x0100;
@@ -68,17 +143,20 @@ mod windows_impl {
     const RID_INPUT: u32 = 0x10000003;
     const RIM_TYPEKEYBOARD: u32 = 1;
     const RIDEV_INPUTSINK: u32 = 0x00000100;
+    const RIDI_DEVICENAME: u32 = 0x20000007;
     const RI_KEY_BREAK: u16 = 1;
+    const RI_KEY_E0: u16 = 2;
+    const LLKHF_EXTENDED: u32 = 0x01;
     const HWND_MESSAGE_PARENT: isize = -3;
 
     // ── Win32 types ────────────────────────────────────────────────
     #[repr(C)]
     struct KBDLLHOOKSTRUCT {
         vk_code: u32,
-        _scan_code: u32,
-        _flags: u32,
+        scan_code: u32,
+        flags: u32,
         _time: u32,
-        _dw_extra_info: usize,
+        dw_extra_info: usize,
     }
 
     #[repr(C)]
@@ -104,7 +182,7 @@ mod windows_impl {
         reserved: u16,
         vkey: u16,
         message: u32,
-        extra_info: usize,
+        extra_info: u32, // ULONG, unlike KBDLLHOOKSTRUCT
     }
 
     #[repr(C)]
@@ -146,6 +224,12 @@ mod windows_impl {
             size: *mut u32,
             header_size: u32,
         ) -> u32;
+        fn GetRawInputDeviceInfoW(
+            device: isize,
+            command: u32,
+            data: *mut u16,
+            size: *mut u32,
+        ) -> u32;
         fn CreateWindowExW(
             ex_style: u32,
             class: *const u16,
@@ -173,12 +257,17 @@ mod windows_impl {
     // ── Shortcut matching data ───────────────────────────────────────
     struct ShortcutEntry {
         vk_code: u32,
+        /// Numpad keys match by scancode: their VK follows NumLock, and Num
+        /// Enter's is Enter's.
+        scan_code: Option<u32>,
         need_ctrl: bool,
         need_shift: bool,
         need_alt: bool,
         need_gui: bool,
         led_idx: usize,
         is_internal: bool,
+        /// Only react when Raw Input attributes the keystroke to the Deck-8.
+        deck_only: bool,
     }
 
     struct HookState {
@@ -215,6 +304,40 @@ mod windows_impl {
         )
     }
 
+    /// Whether a Raw Input device handle belongs to the Deck-8, by matching
+    /// VID/PID in its device path. Cached per handle (handles are stable until
+    /// the device is replugged). Handle 0 is injected input (SendInput).
+    fn is_deck8_device(device: isize) -> bool {
+        thread_local! {
+            static CACHE: RefCell<HashMap<isize, bool>> = RefCell::new(HashMap::new());
+        }
+        if device == 0 {
+            return false;
+        }
+        CACHE.with(|cache| {
+            *cache.borrow_mut().entry(device).or_insert_with(|| {
+                let needle = format!("VID_{:04X}&PID_{:04X}", crate::protocol::VID, crate::protocol::PID);
+                device_name(device).is_some_and(|name| name.to_uppercase().contains(&needle))
+            })
+        })
+    }
+
+    fn device_name(device: isize) -> Option<String> {
+        unsafe {
+            let mut len: u32 = 0;
+            GetRawInputDeviceInfoW(device, RIDI_DEVICENAME, std::ptr::null_mut(), &mut len);
+            if len == 0 {
+                return None;
+            }
+            let mut buf = vec![0u16; len as usize];
+            if GetRawInputDeviceInfoW(device, RIDI_DEVICENAME, buf.as_mut_ptr(), &mut len) == u32::MAX {
+                return None;
+            }
+            let end = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+            Some(String::from_utf16_lossy(&buf[..end]))
+        }
+    }
+
     /// Returns true if this key should be toggled (not a duplicate within DEDUP_MS).
     fn should_toggle(led_idx: usize) -> bool {
         if led_idx >= 8 {
@@ -222,7 +345,89 @@ mod windows_impl {
         }
         let now = unsafe { GetTickCount64() };
         let prev = LAST_TOGGLE[led_idx].swap(now, Ordering::Relaxed);
-        now.wrapping_sub(prev) > DEDUP_MS
+        now.wrapping_sub(prev) > DEDUP_MS.load(Ordering::Relaxed)
+    }
+
+    /// Set-1 scancode with 0xE0-prefixed ones as 0xE0xx, as
+    /// `KeycodeDef::numpad_scancode` has them.
+    fn full_scancode(scan: u32, extended: bool) -> u32 {
+        if extended { 0xE000 | scan } else { scan }
+    }
+
+    /// Whether a keydown (VK + full scancode) is the entry's key.
+    fn is_entry_key(entry: &ShortcutEntry, vk: u32, scan: u32) -> bool {
+        match entry.scan_code {
+            Some(s) => s == scan,
+            None => entry.vk_code == vk,
+        }
+    }
+
+    /// What a key is held by: its VK, or for keys without one (Num =) its
+    /// scancode, kept clear of VK values.
+    fn hold_id(vk: u32, scan: u32) -> u32 {
+        if vk != 0 { vk } else { 0x1_0000 | scan }
+    }
+
+    /// Mark a key as held by `id` (`hold_id`). False for autorepeat, for the second of
+    /// the LL hook / Raw Input pair, and for duplicates within DEDUP_MS.
+    fn begin_hold(led_idx: usize, id: u32) -> bool {
+        if led_idx >= 8 || HELD[led_idx].swap(id, Ordering::Relaxed) == id {
+            return false;
+        }
+        if !should_toggle(led_idx) {
+            HELD[led_idx].store(0, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+
+    /// Release every key held by `id` (modifiers may already be up, so only
+    /// the key is matched). Returns a bitmask of the released LED indices.
+    fn end_hold(id: u32) -> u8 {
+        let mut released = 0u8;
+        for (led_idx, held) in HELD.iter().enumerate() {
+            if held.compare_exchange(id, 0, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+                released |= 1 << led_idx;
+            }
+        }
+        released
+    }
+
+    /// Dispatch key events off the calling thread (hook/raw input must return fast).
+    fn dispatch(app: &Option<tauri::AppHandle>, led_mask: u8, pressed: bool) {
+        let Some(app) = app else { return };
+        for led_idx in (0..8).filter(|i| led_mask & (1 << i) != 0) {
+            super::send_key_event(app, led_idx, pressed);
+        }
+    }
+
+    /// Finish a pending capture with a chord (None = cancelled), off the hook
+    /// thread. `id` limits it to one capture; None finishes whichever is pending.
+    fn finish_capture(
+        pending: &mut Option<(u64, CaptureCallback)>,
+        id: Option<u64>,
+        keycode: Option<u16>,
+    ) -> bool {
+        if id.is_some_and(|id| pending.as_ref().is_some_and(|(p, _)| *p != id)) {
+            return false;
+        }
+        let Some((_, callback)) = pending.take() else { return false };
+        CAPTURING.store(false, Ordering::SeqCst);
+        std::thread::spawn(move || callback(keycode));
+        true
+    }
+
+    /// QMK keycode for a key (numpad keys by scancode) plus the tracked LL
+    /// hook modifiers.
+    fn chord_keycode(vk: u32, scan: u32) -> Option<u16> {
+        let def = crate::keycodes::from_numpad_scancode(scan as u16)
+            .or_else(|| crate::layout::from_vk(vk as u16))?;
+        let mut mods = 0u16;
+        if MOD_CTRL.load(Ordering::Relaxed) { mods |= 0x01; }
+        if MOD_SHIFT.load(Ordering::Relaxed) { mods |= 0x02; }
+        if MOD_ALT.load(Ordering::Relaxed) { mods |= 0x04; }
+        if MOD_GUI.load(Ordering::Relaxed) { mods |= 0x08; }
+        Some(mods << 8 | def.basic as u16)
     }
 
     // ── LL Hook callback ───────────────────────────────────────────
@@ -235,9 +440,12 @@ mod windows_impl {
             let is_down = msg_type == WM_KEYDOWN || msg_type == WM_SYSKEYDOWN;
             let is_up = msg_type == WM_KEYUP || msg_type == WM_SYSKEYUP;
 
-            if is_down || is_up {
-                let kb = &*(lparam as *const KBDLLHOOKSTRUCT);
+            let kb = &*(lparam as *const KBDLLHOOKSTRUCT);
+            // Our own replayed keystrokes (REPLAY_MARKER) are never shortcuts
+            if (is_down || is_up) && kb.dw_extra_info != crate::replay::REPLAY_MARKER {
                 let vk = kb.vk_code as i32;
+                let scan = full_scancode(kb.scan_code, kb.flags & LLKHF_EXTENDED != 0);
+                let held = hold_id(kb.vk_code, scan);
 
                 // Track modifier state from the hook itself
                 match vk {
@@ -248,18 +456,37 @@ mod windows_impl {
                     _ => {}
                 }
 
+                // Capture mode: swallow the next chord (Escape cancels)
+                if is_down && !is_modifier_vk(kb.vk_code) && CAPTURING.load(Ordering::Relaxed) {
+                    let no_mods = !(MOD_CTRL.load(Ordering::Relaxed)
+                        || MOD_SHIFT.load(Ordering::Relaxed)
+                        || MOD_ALT.load(Ordering::Relaxed)
+                        || MOD_GUI.load(Ordering::Relaxed));
+                    let keycode = if kb.vk_code == VK_ESCAPE && no_mods {
+                        Some(None)
+                    } else {
+                        chord_keycode(kb.vk_code, scan).map(Some)
+                    };
+                    // Unknown keys pass through; capture keeps waiting
+                    if let (Some(keycode), Ok(mut pending)) = (keycode, CAPTURE.try_lock()) {
+                        if finish_capture(&mut pending, None, keycode) {
+                            return 1;
+                        }
+                    }
+                }
+
                 // For non-modifier keydowns, check if a shortcut matches
-                if is_down && !is_modifier_vk(kb.vk_code) {
+                if is_down && !is_modifier_vk(kb.vk_code) && !CAPTURING.load(Ordering::Relaxed) {
                     let ctrl = MOD_CTRL.load(Ordering::Relaxed);
                     let shift = MOD_SHIFT.load(Ordering::Relaxed);
                     let alt = MOD_ALT.load(Ordering::Relaxed);
                     let gui = MOD_GUI.load(Ordering::Relaxed);
 
-                    if ctrl || shift || alt || gui {
+                    if ctrl || shift || alt || gui || HAS_BARE.load(Ordering::Relaxed) {
                         match state().try_lock() {
                             Ok(st) => {
                                 for entry in &st.shortcuts {
-                                    if entry.vk_code == kb.vk_code
+                                    if is_entry_key(entry, kb.vk_code, scan)
                                         && entry.need_ctrl == ctrl
                                         && entry.need_shift == shift
                                         && entry.need_alt == alt
@@ -267,13 +494,9 @@ mod windows_impl {
                                     {
                                         let led_idx = entry.led_idx;
                                         let is_internal = entry.is_internal;
-                                        if should_toggle(led_idx) {
-                                            if let Some(ref app) = st.app_handle {
-                                                let app_clone = app.clone();
-                                                std::thread::spawn(move || {
-                                                    crate::do_toggle_key(&app_clone, led_idx);
-                                                });
-                                            }
+                                        // Deck-only keys are left to Raw Input, which knows the source
+                                        if !entry.deck_only && begin_hold(led_idx, held) {
+                                            dispatch(&st.app_handle, 1 << led_idx, true);
                                         }
                                         if is_internal {
                                             return 1;
@@ -286,6 +509,15 @@ mod windows_impl {
                         }
                     }
                 }
+
+                if is_up && !is_modifier_vk(kb.vk_code) {
+                    let released = end_hold(held);
+                    if released != 0 {
+                        if let Ok(st) = state().try_lock() {
+                            dispatch(&st.app_handle, released, false);
+                        }
+                    }
+                }
             }
         }
         CallNextHookEx(0, code, wparam, lparam)
@@ -336,6 +568,7 @@ mod windows_impl {
                     error!("[raw-input] RegisterRawInputDevices failed");
                     return;
                 }
+                RAW_INPUT_OK.store(true, Ordering::SeqCst);
 
                 info!("[raw-input] Listening for keyboard input");
 
@@ -376,11 +609,14 @@ mod windows_impl {
         }
 
         let raw = &*(buf.as_ptr() as *const RAWINPUT_KB);
-        if raw.header.type_ != RIM_TYPEKEYBOARD {
+        if raw.header.type_ != RIM_TYPEKEYBOARD || raw.keyboard.extra_info == crate::replay::REPLAY_MARKER as u32 {
             return;
         }
 
         let vk = raw.keyboard.vkey as u32;
+        let scan = full_scancode(raw.keyboard.make_code as u32, raw.keyboard.flags & RI_KEY_E0 != 0);
+        let held = hold_id(vk, scan);
+        let device = raw.header.device;
         let is_up = raw.keyboard.flags & RI_KEY_BREAK != 0;
         let is_down = !is_up;
 
@@ -401,31 +637,27 @@ mod windows_impl {
             _ => {}
         }
 
-        // For non-modifier keydowns, check if a shortcut matches
-        if is_down && !is_modifier_vk(vk) {
+        // For non-modifier keydowns, check if a shortcut matches (paused while capturing)
+        if is_down && !is_modifier_vk(vk) && !CAPTURING.load(Ordering::Relaxed) {
             let ctrl = RAW_MOD_CTRL.load(Ordering::Relaxed);
             let shift = RAW_MOD_SHIFT.load(Ordering::Relaxed);
             let alt = RAW_MOD_ALT.load(Ordering::Relaxed);
             let gui = RAW_MOD_GUI.load(Ordering::Relaxed);
 
-            if ctrl || shift || alt || gui {
+            if ctrl || shift || alt || gui || HAS_BARE.load(Ordering::Relaxed) {
                 match state().try_lock() {
                     Ok(st) => {
                         for entry in &st.shortcuts {
-                            if entry.vk_code == vk
+                            if is_entry_key(entry, vk, scan)
                                 && entry.need_ctrl == ctrl
                                 && entry.need_shift == shift
                                 && entry.need_alt == alt
                                 && entry.need_gui == gui
+                                && (!entry.deck_only || is_deck8_device(device))
                             {
                                 let led_idx = entry.led_idx;
-                                if should_toggle(led_idx) {
-                                    if let Some(ref app) = st.app_handle {
-                                        let app_clone = app.clone();
-                                        std::thread::spawn(move || {
-                                            crate::do_toggle_key(&app_clone, led_idx);
-                                        });
-                                    }
+                                if begin_hold(led_idx, held) {
+                                    dispatch(&st.app_handle, 1 << led_idx, true);
                                 }
                                 break;
                             }
@@ -435,21 +667,20 @@ mod windows_impl {
                 }
             }
         }
+
+        if is_up && !is_modifier_vk(vk) {
+            let released = end_hold(held);
+            if released != 0 {
+                if let Ok(st) = state().try_lock() {
+                    dispatch(&st.app_handle, released, false);
+                }
+            }
+        }
     }
 
-    // ── QMK → Windows VK mapping ────────────────────────────────────
+    // ── QMK → Windows VK mapping (active layout) ────────────────────
     fn qmk_basic_to_vk(basic: u8) -> Option<u32> {
-        match basic {
-            0x04..=0x1D => Some(0x41 + (basic - 0x04) as u32), // A-Z
-            0x1E..=0x26 => Some(0x31 + (basic - 0x1E) as u32), // 1-9
-            0x27 => Some(0x30),                                  // 0
-            0x28 => Some(0x0D),                                  // Enter
-            0x29 => Some(0x1B),                                  // Escape
-            0x2C => Some(0x20),                                  // Space
-            0x3A..=0x45 => Some(0x70 + (basic - 0x3A) as u32),  // F1-F12
-            0x68..=0x6F => Some(0x7C + (basic - 0x68) as u32),  // F13-F20
-            _ => None,
-        }
+        crate::layout::vk(crate::keycodes::lookup(basic)?).map(u32::from)
     }
 
     // ── Public API ──────────────────────────────────────────────────
@@ -466,6 +697,7 @@ mod windows_impl {
                 if hook == 0 {
                     error!("[hook] Failed to install keyboard hook");
                 } else {
+                    LL_HOOK_OK.store(true, Ordering::SeqCst);
                     info!("[hook] Keyboard LL hook installed (main thread)");
                 }
             }
@@ -475,48 +707,634 @@ mod windows_impl {
         });
     }
 
+    /// The mechanisms that are running.
+    pub fn backends() -> Vec<&'static str> {
+        let mut active = Vec::new();
+        if LL_HOOK_OK.load(Ordering::SeqCst) {
+            active.push("ll-hook");
+        }
+        if RAW_INPUT_OK.load(Ordering::SeqCst) {
+            active.push("raw-input");
+        }
+        active
+    }
+
+    /// Set the LL hook / Raw Input dedup window.
+    pub fn set_dedup_ms(ms: u64) {
+        DEDUP_MS.store(ms, Ordering::Relaxed);
+    }
+
+    /// Hand the next chord pressed on any keyboard to `callback` (None when
+    /// cancelled with Escape or `cancel_capture`). Replaces a pending capture,
+    /// which gets cancelled. Returns the capture id for `cancel_capture`.
+    pub fn start_capture(callback: CaptureCallback) -> Option<u64> {
+        let id = CAPTURE_ID.fetch_add(1, Ordering::SeqCst) + 1;
+        let previous = CAPTURE.lock().unwrap().replace((id, callback));
+        CAPTURING.store(true, Ordering::SeqCst);
+        if let Some((_, previous)) = previous {
+            std::thread::spawn(move || previous(None));
+        }
+        info!("[hook] Capturing next chord");
+        Some(id)
+    }
+
+    /// Cancel capture `id` (or whichever is pending when None).
+    pub fn cancel_capture(id: Option<u64>) {
+        finish_capture(&mut CAPTURE.lock().unwrap(), id, None);
+    }
+
     /// Update the shortcut entries (called when device connects or keymaps change).
     pub fn register_shortcuts(app: &tauri::AppHandle, keymaps: &[u16; 8]) {
+        let sources: [crate::state::KeySource; 8] = {
+            let state = app.state::<crate::state::SharedState>();
+            let st = state.lock().unwrap();
+            std::array::from_fn(|led| st.keys[led].source)
+        };
         let mut entries = Vec::new();
 
         for (i, &keycode) in keymaps.iter().enumerate() {
             let mods = (keycode >> 8) as u8;
             let basic = (keycode & 0xFF) as u8;
-            if mods == 0 || basic == 0 {
+            let led_idx = crate::keymap_to_led_index(i);
+            let deck_only = sources[led_idx] == crate::state::KeySource::Deck;
+            // "Any keyboard" keys would hijack typing if bound bare
+            if !crate::keycodes::is_bindable(keycode, deck_only) {
                 continue;
             }
 
-            if let Some(vk) = qmk_basic_to_vk(basic) {
-                let led_idx = crate::keymap_to_led_index(i);
+            let scan_code = crate::keycodes::lookup(basic)
+                .and_then(|def| def.numpad_scancode())
+                .map(u32::from);
+            let vk = qmk_basic_to_vk(basic);
+            if vk.is_some() || scan_code.is_some() {
                 let is_internal = crate::is_internal_keycode(keycode);
                 entries.push(ShortcutEntry {
-                    vk_code: vk,
+                    vk_code: vk.unwrap_or(0),
+                    scan_code,
                     need_ctrl: mods & 0x11 != 0,
                     need_shift: mods & 0x22 != 0,
                     need_alt: mods & 0x44 != 0,
                     need_gui: mods & 0x88 != 0,
                     led_idx,
                     is_internal,
+                    deck_only,
                 });
             }
         }
 
         let count = entries.len();
+        let has_bare = entries
+            .iter()
+            .any(|e| !(e.need_ctrl || e.need_shift || e.need_alt || e.need_gui));
         let mut st = state().lock().unwrap();
         st.shortcuts = entries;
         st.app_handle = Some(app.clone());
         drop(st);
+        HAS_BARE.store(has_bare, Ordering::Relaxed);
 
         info!("[hook] {} shortcuts registered", count);
     }
 }
 
+#[cfg(target_os = "linux")]
+mod linux_impl {
+    use log::{error, info, warn};
+    use std::collections::{HashMap, HashSet};
+    use std::ffi::c_ulong;
+    use std::fs::{self, File, OpenOptions};
+    use std::io::{Read, Write};
+    use std::os::unix::io::{AsRawFd, RawFd};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::Duration;
+
+    use crate::protocol::{PID, VID};
+
+    // Tracked modifier state, updated from every Deck-8 key event.
+    static MOD_CTRL: AtomicBool = AtomicBool::new(false);
+    static MOD_SHIFT: AtomicBool = AtomicBool::new(false);
+    static MOD_ALT: AtomicBool = AtomicBool::new(false);
+    static MOD_GUI: AtomicBool = AtomicBool::new(false);
+
+    // evdev code currently holding each key down (0 = released), so each
+    // press gets exactly one release.
+    static HELD: [AtomicU16; 8] = [
+        AtomicU16::new(0), AtomicU16::new(0), AtomicU16::new(0), AtomicU16::new(0),
+        AtomicU16::new(0), AtomicU16::new(0), AtomicU16::new(0), AtomicU16::new(0),
+    ];
+
+    // Whether the device is currently grabbed (set_grab + uinput available).
+    static GRAB_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+    // Only warn once about missing permissions (the rescan runs every 2s).
+    static PERMISSION_WARNED: AtomicBool = AtomicBool::new(false);
+
+    const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+    // ── Linux input constants ────────────────────────────────────────
+    const EV_SYN: u16 = 0x00;
+    const EV_KEY: u16 = 0x01;
+    const SYN_REPORT: u16 = 0x00;
+    const KEY_MAX: u16 = 0x2FF;
+    const BUS_USB: u16 = 0x03;
+
+    const KEY_LEFTCTRL: u16 = 29;
+    const KEY_LEFTSHIFT: u16 = 42;
+    const KEY_RIGHTSHIFT: u16 = 54;
+    const KEY_LEFTALT: u16 = 56;
+    const KEY_RIGHTCTRL: u16 = 97;
+    const KEY_RIGHTALT: u16 = 100;
+    const KEY_LEFTMETA: u16 = 125;
+    const KEY_RIGHTMETA: u16 = 126;
+
+    // ioctl request numbers (_IOW('E', 0x90, int), _IOW('U', 100/101, int), _IO('U', 1))
+    const EVIOCGRAB: c_ulong = 0x4004_4590;
+    const UI_SET_EVBIT: c_ulong = 0x4004_5564;
+    const UI_SET_KEYBIT: c_ulong = 0x4004_5565;
+    const UI_DEV_CREATE: c_ulong = 0x5501;
+
+    // ── Linux types ──────────────────────────────────────────────────
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct InputEvent {
+        tv_sec: isize,
+        tv_usec: isize,
+        type_: u16,
+        code: u16,
+        value: i32,
+    }
+
+    /// Legacy uinput setup struct (written to /dev/uinput before UI_DEV_CREATE).
+    #[repr(C)]
+    struct UinputUserDev {
+        name: [u8; 80],
+        bustype: u16,
+        vendor: u16,
+        product: u16,
+        version: u16,
+        ff_effects_max: u32,
+        absmax: [i32; 64],
+        absmin: [i32; 64],
+        absfuzz: [i32; 64],
+        absflat: [i32; 64],
+    }
+
+    extern "C" {
+        fn ioctl(fd: RawFd, request: c_ulong, ...) -> i32;
+    }
+
+    /// udev rules that give the logged-in user access to the Deck-8's
+    /// hidraw + evdev nodes and to /dev/uinput (for the grab passthrough).
+    pub const UDEV_RULES: &str = "\
+# /etc/udev/rules.d/70-deck8.rules
+SUBSYSTEM==\"hidraw\", ATTRS{idVendor}==\"cbbc\", ATTRS{idProduct}==\"c101\", TAG+=\"uaccess\"
+SUBSYSTEM==\"input\", ATTRS{idVendor}==\"cbbc\", ATTRS{idProduct}==\"c101\", TAG+=\"uaccess\"
+KERNEL==\"uinput\", SUBSYSTEM==\"misc\", TAG+=\"uaccess\", OPTIONS+=\"static_node=uinput\"
+";
+
+    // ── Shortcut matching data ───────────────────────────────────────
+    struct ShortcutEntry {
+        code: u16,
+        need_ctrl: bool,
+        need_shift: bool,
+        need_alt: bool,
+        need_gui: bool,
+        led_idx: usize,
+        is_internal: bool,
+    }
+
+    struct HookState {
+        shortcuts: Vec<ShortcutEntry>,
+        app_handle: Option<tauri::AppHandle>,
+    }
+
+    static HOOK_STATE: OnceLock<Mutex<HookState>> = OnceLock::new();
+
+    fn state() -> &'static Mutex<HookState> {
+        HOOK_STATE.get_or_init(|| {
+            Mutex::new(HookState {
+                shortcuts: Vec::new(),
+                app_handle: None,
+            })
+        })
+    }
+
+    /// Event nodes currently being read → their raw fd (for grab/ungrab).
+    static OPEN_NODES: OnceLock<Mutex<HashMap<PathBuf, RawFd>>> = OnceLock::new();
+
+    fn open_nodes() -> &'static Mutex<HashMap<PathBuf, RawFd>> {
+        OPEN_NODES.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    /// uinput device used to re-emit non-consumed keys while grabbed.
+    /// None if /dev/uinput isn't writable.
+    static PASSTHROUGH: OnceLock<Option<Mutex<File>>> = OnceLock::new();
+
+    fn passthrough() -> Option<&'static Mutex<File>> {
+        PASSTHROUGH
+            .get_or_init(|| match create_passthrough() {
+                Ok(f) => {
+                    info!("[evdev] uinput passthrough device created");
+                    Some(Mutex::new(f))
+                }
+                Err(e) => {
+                    warn!("[evdev] uinput unavailable, grab disabled: {}", e);
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    // ── Helpers ────────────────────────────────────────────────────
+    fn is_modifier_code(code: u16) -> bool {
+        matches!(
+            code,
+            KEY_LEFTCTRL
+                | KEY_RIGHTCTRL
+                | KEY_LEFTSHIFT
+                | KEY_RIGHTSHIFT
+                | KEY_LEFTALT
+                | KEY_RIGHTALT
+                | KEY_LEFTMETA
+                | KEY_RIGHTMETA
+        )
+    }
+
+    fn read_sysfs_hex(path: &Path) -> Option<u16> {
+        let s = fs::read_to_string(path).ok()?;
+        u16::from_str_radix(s.trim(), 16).ok()
+    }
+
+    /// List /dev/input/eventN nodes that belong to the Deck-8.
+    pub fn find_deck8_nodes() -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir("/sys/class/input") else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("event"))
+            .filter(|e| {
+                let id = e.path().join("device").join("id");
+                read_sysfs_hex(&id.join("vendor")) == Some(VID)
+                    && read_sysfs_hex(&id.join("product")) == Some(PID)
+            })
+            .map(|e| Path::new("/dev/input").join(e.file_name()))
+            .collect()
+    }
+
+    fn set_grab_fd(fd: RawFd, grab: bool) -> bool {
+        unsafe { ioctl(fd, EVIOCGRAB, grab as i32) == 0 }
+    }
+
+    fn create_passthrough() -> std::io::Result<File> {
+        let mut file = OpenOptions::new().write(true).open("/dev/uinput")?;
+        let fd = file.as_raw_fd();
+        unsafe {
+            if ioctl(fd, UI_SET_EVBIT, EV_KEY as i32) < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            for code in 1..=KEY_MAX {
+                ioctl(fd, UI_SET_KEYBIT, code as i32);
+            }
+        }
+        let mut dev = UinputUserDev {
+            name: [0; 80],
+            bustype: BUS_USB,
+            vendor: 0,
+            product: 0,
+            version: 1,
+            ff_effects_max: 0,
+            absmax: [0; 64],
+            absmin: [0; 64],
+            absfuzz: [0; 64],
+            absflat: [0; 64],
+        };
+        let name = b"Deck-8 Hub passthrough";
+        dev.name[..name.len()].copy_from_slice(name);
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                &dev as *const UinputUserDev as *const u8,
+                std::mem::size_of::<UinputUserDev>(),
+            )
+        };
+        file.write_all(bytes)?;
+        if unsafe { ioctl(fd, UI_DEV_CREATE) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(file)
+    }
+
+    fn forward(events: &[InputEvent]) {
+        let Some(pt) = passthrough() else { return };
+        let Ok(mut f) = pt.lock() else { return };
+        for ev in events {
+            let bytes = unsafe {
+                std::slice::from_raw_parts(
+                    ev as *const InputEvent as *const u8,
+                    std::mem::size_of::<InputEvent>(),
+                )
+            };
+            let _ = f.write_all(bytes);
+        }
+    }
+
+    // ── Event handling ─────────────────────────────────────────────
+
+    /// Track modifiers and dispatch a shortcut for a key press.
+    /// Returns true if the press matched an internal (sound-only) shortcut.
+    fn handle_key_press(code: u16) -> bool {
+        let ctrl = MOD_CTRL.load(Ordering::Relaxed);
+        let shift = MOD_SHIFT.load(Ordering::Relaxed);
+        let alt = MOD_ALT.load(Ordering::Relaxed);
+        let gui = MOD_GUI.load(Ordering::Relaxed);
+
+        let st = state().lock().unwrap();
+        let Some(entry) = st.shortcuts.iter().find(|e| {
+            e.code == code
+                && e.need_ctrl == ctrl
+                && e.need_shift == shift
+                && e.need_alt == alt
+                && e.need_gui == gui
+        }) else {
+            return false;
+        };
+        let led_idx = entry.led_idx;
+        HELD[led_idx].store(code, Ordering::Relaxed);
+        if let Some(ref app) = st.app_handle {
+            super::send_key_event(app, led_idx, true);
+        }
+        entry.is_internal
+    }
+
+    /// Dispatch a release for every key held by this code (modifiers may
+    /// already be up, so only the code is matched).
+    fn handle_key_release(code: u16) {
+        let released: Vec<usize> = (0..8)
+            .filter(|&i| HELD[i].compare_exchange(code, 0, Ordering::Relaxed, Ordering::Relaxed).is_ok())
+            .collect();
+        if released.is_empty() {
+            return;
+        }
+        let Some(app) = state().lock().unwrap().app_handle.clone() else { return };
+        for led_idx in released {
+            super::send_key_event(&app, led_idx, false);
+        }
+    }
+
+    fn track_modifier(code: u16, is_down: bool) {
+        match code {
+            KEY_LEFTCTRL | KEY_RIGHTCTRL => MOD_CTRL.store(is_down, Ordering::Relaxed),
+            KEY_LEFTSHIFT | KEY_RIGHTSHIFT => MOD_SHIFT.store(is_down, Ordering::Relaxed),
+            KEY_LEFTALT | KEY_RIGHTALT => MOD_ALT.store(is_down, Ordering::Relaxed),
+            KEY_LEFTMETA | KEY_RIGHTMETA => MOD_GUI.store(is_down, Ordering::Relaxed),
+            _ => {}
+        }
+    }
+
+    /// Process one SYN_REPORT frame. When grabbed, everything except the
+    /// internal chord (modifiers + key, and their later releases) is forwarded.
+    fn handle_frame(frame: &[InputEvent], suppressed: &mut HashSet<u16>) {
+        let mut consumed = false;
+        for ev in frame.iter().filter(|e| e.type_ == EV_KEY) {
+            track_modifier(ev.code, ev.value != 0);
+            // value: 1 = press, 2 = autorepeat, 0 = release
+            if ev.value == 1 && !is_modifier_code(ev.code) && handle_key_press(ev.code) {
+                consumed = true;
+            }
+            if ev.value == 0 && !is_modifier_code(ev.code) {
+                handle_key_release(ev.code);
+            }
+        }
+
+        if !GRAB_ACTIVE.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let mut out = Vec::with_capacity(frame.len());
+        for ev in frame {
+            if ev.type_ != EV_KEY {
+                out.push(*ev);
+                continue;
+            }
+            let drop = match ev.value {
+                0 => suppressed.remove(&ev.code),
+                _ if consumed => {
+                    suppressed.insert(ev.code);
+                    true
+                }
+                _ => suppressed.contains(&ev.code),
+            };
+            if !drop {
+                out.push(*ev);
+            }
+        }
+        // Only the SYN left — nothing worth forwarding
+        if out.iter().any(|e| e.type_ == EV_KEY) {
+            forward(&out);
+        }
+    }
+
+    fn spawn_reader(path: PathBuf, mut file: File) {
+        std::thread::spawn(move || {
+            let size = std::mem::size_of::<InputEvent>();
+            let mut buf = vec![0u8; size];
+            let mut frame: Vec<InputEvent> = Vec::new();
+            let mut suppressed: HashSet<u16> = HashSet::new();
+            info!("[evdev] Reading {}", path.display());
+
+            while file.read_exact(&mut buf).is_ok() {
+                let ev = unsafe { std::ptr::read_unaligned(buf.as_ptr() as *const InputEvent) };
+                frame.push(ev);
+                if ev.type_ == EV_SYN && ev.code == SYN_REPORT {
+                    handle_frame(&frame, &mut suppressed);
+                    frame.clear();
+                }
+            }
+
+            info!("[evdev] {} closed (device unplugged?)", path.display());
+            open_nodes().lock().unwrap().remove(&path);
+        });
+    }
+
+    /// Open any Deck-8 event nodes that aren't being read yet.
+    fn scan_devices() {
+        for path in find_deck8_nodes() {
+            if open_nodes().lock().unwrap().contains_key(&path) {
+                continue;
+            }
+            match File::open(&path) {
+                Ok(file) => {
+                    let fd = file.as_raw_fd();
+                    if GRAB_ACTIVE.load(Ordering::Relaxed) && !set_grab_fd(fd, true) {
+                        warn!("[evdev] Failed to grab {}", path.display());
+                    }
+                    open_nodes().lock().unwrap().insert(path.clone(), fd);
+                    spawn_reader(path, file);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                    if !PERMISSION_WARNED.swap(true, Ordering::Relaxed) {
+                        warn!(
+                            "[evdev] No permission to read {} — falling back to global shortcuts. \
+                             Install these udev rules and replug the Deck-8:\n{}",
+                            path.display(),
+                            UDEV_RULES
+                        );
+                    }
+                }
+                Err(e) => error!("[evdev] Failed to open {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    // ── QMK → Linux key code mapping ────────────────────────────────
+    fn qmk_basic_to_evdev(basic: u8) -> Option<u16> {
+        crate::keycodes::lookup(basic)?.evdev()
+    }
+
+    // ── Public API ──────────────────────────────────────────────────
+
+    /// Open the Deck-8's event nodes (if accessible) and start a rescan
+    /// thread that picks the device up again after a replug.
+    pub fn init() {
+        static STARTED: OnceLock<()> = OnceLock::new();
+        STARTED.get_or_init(|| {
+            scan_devices();
+            std::thread::spawn(|| loop {
+                std::thread::sleep(RESCAN_INTERVAL);
+                scan_devices();
+            });
+        });
+    }
+
+    /// True when at least one Deck-8 event node is being read, i.e. the
+    /// evdev backend can replace the global shortcut plugin.
+    pub fn is_active() -> bool {
+        scan_devices();
+        !open_nodes().lock().unwrap().is_empty()
+    }
+
+    /// Enable/disable grabbing the Deck-8's input (requires /dev/uinput access).
+    pub fn set_grab(enabled: bool) {
+        let active = enabled && passthrough().is_some();
+        GRAB_ACTIVE.store(active, Ordering::Relaxed);
+        for (path, &fd) in open_nodes().lock().unwrap().iter() {
+            if !set_grab_fd(fd, active) && active {
+                warn!("[evdev] Failed to grab {}", path.display());
+            }
+        }
+        info!("[evdev] Grab {}", if active { "enabled" } else { "disabled" });
+    }
+
+    /// Emit key presses/releases (evdev codes) through the uinput passthrough
+    /// device, one SYN_REPORT per event. Used for keystroke replay. Returns
+    /// false when /dev/uinput isn't writable.
+    pub fn emit_keys(keys: &[(u16, bool)]) -> bool {
+        let fresh = PASSTHROUGH.get().is_none();
+        if passthrough().is_none() {
+            return false;
+        }
+        if fresh {
+            // Give libinput/the X server a moment to pick up the new device
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        let event = |type_, code, value| InputEvent { tv_sec: 0, tv_usec: 0, type_, code, value };
+        let events: Vec<InputEvent> = keys
+            .iter()
+            .flat_map(|&(code, pressed)| {
+                [event(EV_KEY, code, pressed as i32), event(EV_SYN, SYN_REPORT, 0)]
+            })
+            .collect();
+        forward(&events);
+        true
+    }
+
+    /// Update the shortcut entries (called when device connects or keymaps change).
+    pub fn register_shortcuts(app: &tauri::AppHandle, keymaps: &[u16; 8]) {
+        let mut entries = Vec::new();
+
+        for (i, &keycode) in keymaps.iter().enumerate() {
+            let mods = (keycode >> 8) as u8;
+            let basic = (keycode & 0xFF) as u8;
+            // Events only come from the Deck-8's own nodes: any bare key is fine
+            if !crate::keycodes::is_bindable(keycode, true) {
+                continue;
+            }
+
+            if let Some(code) = qmk_basic_to_evdev(basic) {
+                entries.push(ShortcutEntry {
+                    code,
+                    need_ctrl: mods & 0x11 != 0,
+                    need_shift: mods & 0x22 != 0,
+                    need_alt: mods & 0x44 != 0,
+                    need_gui: mods & 0x88 != 0,
+                    led_idx: crate::keymap_to_led_index(i),
+                    is_internal: crate::is_internal_keycode(keycode),
+                });
+            }
+        }
+
+        let count = entries.len();
+        let mut st = state().lock().unwrap();
+        st.shortcuts = entries;
+        st.app_handle = Some(app.clone());
+        drop(st);
+
+        info!("[evdev] {} shortcuts registered", count);
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub use windows_impl::{backends, cancel_capture, init, register_shortcuts, set_dedup_ms, start_capture};
+
+/// The Windows hook is always available.
+#[cfg(target_os = "windows")]
+pub fn is_active() -> bool {
+    true
+}
+
 #[cfg(target_os = "windows")]
-pub use windows_impl::{init, register_shortcuts};
+pub fn set_grab(_enabled: bool) {}
 
-// Non-Windows stubs — shortcuts handled by tauri_plugin_global_shortcut in lib.rs
+/// Capturing chords from other keyboards needs the Windows hook; the Linux
+/// reader only sees the Deck-8 and macOS has no hook.
 #[cfg(not(target_os = "windows"))]
-pub fn init() {}
+pub fn start_capture(_callback: Box<dyn FnOnce(Option<u16>) + Send>) -> Option<u64> {
+    None
+}
 
 #[cfg(not(target_os = "windows"))]
+pub fn cancel_capture(_id: Option<u64>) {}
+
+/// Only the Windows hook has two sources to dedup.
+#[cfg(not(target_os = "windows"))]
+pub fn set_dedup_ms(_ms: u64) {}
+
+#[cfg(target_os = "linux")]
+pub use linux_impl::{emit_keys, find_deck8_nodes, init, is_active, register_shortcuts, set_grab};
+
+/// The hook backends that are running (empty when the plugin is in use).
+#[cfg(target_os = "linux")]
+pub fn backends() -> Vec<&'static str> {
+    if is_active() { vec!["evdev"] } else { Vec::new() }
+}
+
+// macOS stubs — shortcuts handled by tauri_plugin_global_shortcut in lib.rs
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn init() {}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn backends() -> Vec<&'static str> {
+    Vec::new()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn is_active() -> bool {
+    false
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn set_grab(_enabled: bool) {}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 pub fn register_shortcuts(_app: &tauri::AppHandle, _keymaps: &[u16; 8]) {}
@@ -0,0 +1,333 @@
+// Shared QMK basic keycode table.
+//
+// One row per HID usage the app understands, with the names/codes every
+// backend needs so they stay in sync:
+//   - `code`: W3C code name used by global_hotkey, both to register
+//     ("Ctrl+Alt+PageDown") and in its Display format ("control+alt+PageDown")
+//   - `vk`: Windows virtual-key code (LL hook / Raw Input, enigo replay)
+//   - `evdev`: Linux input event code (evdev reader)
+//   - `mac`: macOS kVK virtual keycode (enigo replay)
+//   - `keysym` / `xkb`: X keysym value (enigo replay on Linux) and name
+//     (XDG GlobalShortcuts portal triggers)
+// Zero / empty means the platform has no equivalent for that key. For
+// letters, digits and punctuation `vk`, `keysym` and `xkb` are the US layout's;
+// layout.rs translates them for the active one.
+//
+// The frontend's keymap picker gets the table through `list_keycodes`.
+
+use serde::Serialize;
+
+/// Grouping for UI pickers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyCategory {
+    Letter,
+    Number,
+    Control,
+    Punctuation,
+    Function,
+    Navigation,
+    Numpad,
+    Media,
+    International,
+}
+
+#[derive(Debug)]
+pub struct KeycodeDef {
+    /// HID usage = QMK basic keycode (low byte of a modified keycode).
+    pub basic: u8,
+    /// QMK name, e.g. "KC_PGDN".
+    pub qmk: &'static str,
+    /// Human label for the UI, e.g. "Page Down".
+    pub label: &'static str,
+    pub category: KeyCategory,
+    code: &'static str,
+    vk: u16,
+    evdev: u16,
+    mac: u16,
+    keysym: u32,
+    xkb: &'static str,
+}
+
+/// No macOS virtual keycode (kVK_ANSI_A is 0, so 0 can't be the sentinel).
+const NO_MAC: u16 = 0xFFFF;
+
+impl KeycodeDef {
+    /// W3C code name accepted by global_hotkey, if it supports the key.
+    pub fn hotkey_code(&self) -> Option<&'static str> {
+        (!self.code.is_empty()).then_some(self.code)
+    }
+
+    /// Letters, digits and punctuation: what they type, and their Windows
+    /// VK or X keysym, depend on the keyboard layout (see layout.rs).
+    pub fn positional(&self) -> bool {
+        matches!(self.category, Letter | Number | Punctuation) || self.basic == 0x64
+    }
+
+    /// PC set-1 scancode of a positional key (the same number as its evdev
+    /// code for these keys).
+    #[allow(dead_code)] // Windows and Linux only
+    pub fn scancode(&self) -> Option<u16> {
+        if self.positional() { self.evdev() } else { None }
+    }
+
+    /// PC set-1 scancode of a numpad key, with 0xE0-prefixed ones as 0xE0xx.
+    /// Windows reports numpad keys by their navigation VK while NumLock is
+    /// off, Num Enter shares VK_RETURN and Num = has no VK: the scancode tells
+    /// them apart.
+    #[allow(dead_code)] // Windows only
+    pub fn numpad_scancode(&self) -> Option<u16> {
+        match self.basic {
+            0x54 => Some(0xE035), // Num /
+            0x58 => Some(0xE01C), // Num Enter
+            0x67 => Some(0x59),   // Num =
+            // Num Lock is VK_NUMLOCK however it's reported
+            0x53 => None,
+            _ if self.category == Numpad => self.evdev(),
+            _ => None,
+        }
+    }
+
+    /// Windows virtual-key code on a US layout; `layout::vk` for the active one.
+    #[allow(dead_code)] // Windows only
+    pub fn vk(&self) -> Option<u16> {
+        (self.vk != 0).then_some(self.vk)
+    }
+
+    /// Linux evdev key code.
+    #[allow(dead_code)] // Linux only
+    pub fn evdev(&self) -> Option<u16> {
+        (self.evdev != 0).then_some(self.evdev)
+    }
+
+    /// macOS kVK virtual keycode.
+    #[allow(dead_code)] // macOS only
+    pub fn mac(&self) -> Option<u16> {
+        (self.mac != NO_MAC).then_some(self.mac)
+    }
+
+    /// X keysym value.
+    #[allow(dead_code)] // Linux only
+    pub fn keysym(&self) -> Option<u32> {
+        (self.keysym != 0).then_some(self.keysym)
+    }
+
+    /// X keysym name (XDG shortcut trigger format).
+    #[allow(dead_code)] // Linux only
+    pub fn xkb(&self) -> Option<&'static str> {
+        (!self.xkb.is_empty()).then_some(self.xkb)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+const fn k(
+    basic: u8,
+    qmk: &'static str,
+    label: &'static str,
+    category: KeyCategory,
+    code: &'static str,
+    vk: u16,
+    evdev: u16,
+    mac: u16,
+    keysym: u32,
+    xkb: &'static str,
+) -> KeycodeDef {
+    KeycodeDef { basic, qmk, label, category, code, vk, evdev, mac, keysym, xkb }
+}
+
+use KeyCategory::*;
+
+#[rustfmt::skip]
+pub static KEYCODES: &[KeycodeDef] = &[
+    // basic  qmk            label           category      global_hotkey       vk    evdev mac     keysym      xkb
+    k(0x04, "KC_A",          "A",            Letter,       "KeyA",             0x41, 30,   0x00,   0x0061,     "a"),
+    k(0x05, "KC_B",          "B",            Letter,       "KeyB",             0x42, 48,   0x0B,   0x0062,     "b"),
+    k(0x06, "KC_C",          "C",            Letter,       "KeyC",             0x43, 46,   0x08,   0x0063,     "c"),
+    k(0x07, "KC_D",          "D",            Letter,       "KeyD",             0x44, 32,   0x02,   0x0064,     "d"),
+    k(0x08, "KC_E",          "E",            Letter,       "KeyE",             0x45, 18,   0x0E,   0x0065,     "e"),
+    k(0x09, "KC_F",          "F",            Letter,       "KeyF",             0x46, 33,   0x03,   0x0066,     "f"),
+    k(0x0A, "KC_G",          "G",            Letter,       "KeyG",             0x47, 34,   0x05,   0x0067,     "g"),
+    k(0x0B, "KC_H",          "H",            Letter,       "KeyH",             0x48, 35,   0x04,   0x0068,     "h"),
+    k(0x0C, "KC_I",          "I",            Letter,       "KeyI",             0x49, 23,   0x22,   0x0069,     "i"),
+    k(0x0D, "KC_J",          "J",            Letter,       "KeyJ",             0x4A, 36,   0x26,   0x006A,     "j"),
+    k(0x0E, "KC_K",          "K",            Letter,       "KeyK",             0x4B, 37,   0x28,   0x006B,     "k"),
+    k(0x0F, "KC_L",          "L",            Letter,       "KeyL",             0x4C, 38,   0x25,   0x006C,     "l"),
+    k(0x10, "KC_M",          "M",            Letter,       "KeyM",             0x4D, 50,   0x2E,   0x006D,     "m"),
+    k(0x11, "KC_N",          "N",            Letter,       "KeyN",             0x4E, 49,   0x2D,   0x006E,     "n"),
+    k(0x12, "KC_O",          "O",            Letter,       "KeyO",             0x4F, 24,   0x1F,   0x006F,     "o"),
+    k(0x13, "KC_P",          "P",            Letter,       "KeyP",             0x50, 25,   0x23,   0x0070,     "p"),
+    k(0x14, "KC_Q",          "Q",            Letter,       "KeyQ",             0x51, 16,   0x0C,   0x0071,     "q"),
+    k(0x15, "KC_R",          "R",            Letter,       "KeyR",             0x52, 19,   0x0F,   0x0072,     "r"),
+    k(0x16, "KC_S",          "S",            Letter,       "KeyS",             0x53, 31,   0x01,   0x0073,     "s"),
+    k(0x17, "KC_T",          "T",            Letter,       "KeyT",             0x54, 20,   0x11,   0x0074,     "t"),
+    k(0x18, "KC_U",          "U",            Letter,       "KeyU",             0x55, 22,   0x20,   0x0075,     "u"),
+    k(0x19, "KC_V",          "V",            Letter,       "KeyV",             0x56, 47,   0x09,   0x0076,     "v"),
+    k(0x1A, "KC_W",          "W",            Letter,       "KeyW",             0x57, 17,   0x0D,   0x0077,     "w"),
+    k(0x1B, "KC_X",          "X",            Letter,       "KeyX",             0x58, 45,   0x07,   0x0078,     "x"),
+    k(0x1C, "KC_Y",          "Y",            Letter,       "KeyY",             0x59, 21,   0x10,   0x0079,     "y"),
+    k(0x1D, "KC_Z",          "Z",            Letter,       "KeyZ",             0x5A, 44,   0x06,   0x007A,     "z"),
+    k(0x1E, "KC_1",          "1",            Number,       "Digit1",           0x31, 2,    0x12,   0x0031,     "1"),
+    k(0x1F, "KC_2",          "2",            Number,       "Digit2",           0x32, 3,    0x13,   0x0032,     "2"),
+    k(0x20, "KC_3",          "3",            Number,       "Digit3",           0x33, 4,    0x14,   0x0033,     "3"),
+    k(0x21, "KC_4",          "4",            Number,       "Digit4",           0x34, 5,    0x15,   0x0034,     "4"),
+    k(0x22, "KC_5",          "5",            Number,       "Digit5",           0x35, 6,    0x17,   0x0035,     "5"),
+    k(0x23, "KC_6",          "6",            Number,       "Digit6",           0x36, 7,    0x16,   0x0036,     "6"),
+    k(0x24, "KC_7",          "7",            Number,       "Digit7",           0x37, 8,    0x1A,   0x0037,     "7"),
+    k(0x25, "KC_8",          "8",            Number,       "Digit8",           0x38, 9,    0x1C,   0x0038,     "8"),
+    k(0x26, "KC_9",          "9",            Number,       "Digit9",           0x39, 10,   0x19,   0x0039,     "9"),
+    k(0x27, "KC_0",          "0",            Number,       "Digit0",           0x30, 11,   0x1D,   0x0030,     "0"),
+    k(0x28, "KC_ENT",        "Enter",        Control,      "Enter",            0x0D, 28,   0x24,   0xFF0D,     "Return"),
+    k(0x29, "KC_ESC",        "Esc",          Control,      "Escape",           0x1B, 1,    0x35,   0xFF1B,     "Escape"),
+    k(0x2A, "KC_BSPC",       "Backspace",    Control,      "Backspace",        0x08, 14,   0x33,   0xFF08,     "BackSpace"),
+    k(0x2B, "KC_TAB",        "Tab",          Control,      "Tab",              0x09, 15,   0x30,   0xFF09,     "Tab"),
+    k(0x2C, "KC_SPC",        "Space",        Control,      "Space",            0x20, 57,   0x31,   0x0020,     "space"),
+    k(0x2D, "KC_MINS",       "-",            Punctuation,  "Minus",            0xBD, 12,   0x1B,   0x002D,     "minus"),
+    k(0x2E, "KC_EQL",        "=",            Punctuation,  "Equal",            0xBB, 13,   0x18,   0x003D,     "equal"),
+    k(0x2F, "KC_LBRC",       "[",            Punctuation,  "BracketLeft",      0xDB, 26,   0x21,   0x005B,     "bracketleft"),
+    k(0x30, "KC_RBRC",       "]",            Punctuation,  "BracketRight",     0xDD, 27,   0x1E,   0x005D,     "bracketright"),
+    k(0x31, "KC_BSLS",       "\\",           Punctuation,  "Backslash",        0xDC, 43,   0x2A,   0x005C,     "backslash"),
+    k(0x33, "KC_SCLN",       ";",            Punctuation,  "Semicolon",        0xBA, 39,   0x29,   0x003B,     "semicolon"),
+    k(0x34, "KC_QUOT",       "'",            Punctuation,  "Quote",            0xDE, 40,   0x27,   0x0027,     "apostrophe"),
+    k(0x35, "KC_GRV",        "`",            Punctuation,  "Backquote",        0xC0, 41,   0x32,   0x0060,     "grave"),
+    k(0x36, "KC_COMM",       ",",            Punctuation,  "Comma",            0xBC, 51,   0x2B,   0x002C,     "comma"),
+    k(0x37, "KC_DOT",        ".",            Punctuation,  "Period",           0xBE, 52,   0x2F,   0x002E,     "period"),
+    k(0x38, "KC_SLSH",       "/",            Punctuation,  "Slash",            0xBF, 53,   0x2C,   0x002F,     "slash"),
+    k(0x39, "KC_CAPS",       "Caps Lock",    Control,      "CapsLock",         0x14, 58,   0x39,   0xFFE5,     "Caps_Lock"),
+    k(0x3A, "KC_F1",         "F1",           Function,     "F1",               0x70, 59,   0x7A,   0xFFBE,     "F1"),
+    k(0x3B, "KC_F2",         "F2",           Function,     "F2",               0x71, 60,   0x78,   0xFFBF,     "F2"),
+    k(0x3C, "KC_F3",         "F3",           Function,     "F3",               0x72, 61,   0x63,   0xFFC0,     "F3"),
+    k(0x3D, "KC_F4",         "F4",           Function,     "F4",               0x73, 62,   0x76,   0xFFC1,     "F4"),
+    k(0x3E, "KC_F5",         "F5",           Function,     "F5",               0x74, 63,   0x60,   0xFFC2,     "F5"),
+    k(0x3F, "KC_F6",         "F6",           Function,     "F6",               0x75, 64,   0x61,   0xFFC3,     "F6"),
+    k(0x40, "KC_F7",         "F7",           Function,     "F7",               0x76, 65,   0x62,   0xFFC4,     "F7"),
+    k(0x41, "KC_F8",         "F8",           Function,     "F8",               0x77, 66,   0x64,   0xFFC5,     "F8"),
+    k(0x42, "KC_F9",         "F9",           Function,     "F9",               0x78, 67,   0x65,   0xFFC6,     "F9"),
+    k(0x43, "KC_F10",        "F10",          Function,     "F10",              0x79, 68,   0x6D,   0xFFC7,     "F10"),
+    k(0x44, "KC_F11",        "F11",          Function,     "F11",              0x7A, 87,   0x67,   0xFFC8,     "F11"),
+    k(0x45, "KC_F12",        "F12",          Function,     "F12",              0x7B, 88,   0x6F,   0xFFC9,     "F12"),
+    k(0x46, "KC_PSCR",       "Print Screen", Navigation,   "PrintScreen",      0x2C, 99,   NO_MAC, 0xFF61,     "Print"),
+    k(0x47, "KC_SCRL",       "Scroll Lock",  Navigation,   "ScrollLock",       0x91, 70,   NO_MAC, 0xFF14,     "Scroll_Lock"),
+    k(0x48, "KC_PAUS",       "Pause",        Navigation,   "Pause",            0x13, 119,  NO_MAC, 0xFF13,     "Pause"),
+    k(0x49, "KC_INS",        "Insert",       Navigation,   "Insert",           0x2D, 110,  0x72,   0xFF63,     "Insert"),
+    k(0x4A, "KC_HOME",       "Home",         Navigation,   "Home",             0x24, 102,  0x73,   0xFF50,     "Home"),
+    k(0x4B, "KC_PGUP",       "Page Up",      Navigation,   "PageUp",           0x21, 104,  0x74,   0xFF55,     "Prior"),
+    k(0x4C, "KC_DEL",        "Delete",       Navigation,   "Delete",           0x2E, 111,  0x75,   0xFFFF,     "Delete"),
+    k(0x4D, "KC_END",        "End",          Navigation,   "End",              0x23, 107,  0x77,   0xFF57,     "End"),
+    k(0x4E, "KC_PGDN",       "Page Down",    Navigation,   "PageDown",         0x22, 109,  0x79,   0xFF56,     "Next"),
+    k(0x4F, "KC_RGHT",       "Right",        Navigation,   "ArrowRight",       0x27, 106,  0x7C,   0xFF53,     "Right"),
+    k(0x50, "KC_LEFT",       "Left",         Navigation,   "ArrowLeft",        0x25, 105,  0x7B,   0xFF51,     "Left"),
+    k(0x51, "KC_DOWN",       "Down",         Navigation,   "ArrowDown",        0x28, 108,  0x7D,   0xFF54,     "Down"),
+    k(0x52, "KC_UP",         "Up",           Navigation,   "ArrowUp",          0x26, 103,  0x7E,   0xFF52,     "Up"),
+    k(0x53, "KC_NUM",        "Num Lock",     Numpad,       "NumLock",          0x90, 69,   0x47,   0xFF7F,     "Num_Lock"),
+    k(0x54, "KC_PSLS",       "Num /",        Numpad,       "NumpadDivide",     0x6F, 98,   0x4B,   0xFFAF,     "KP_Divide"),
+    k(0x55, "KC_PAST",       "Num *",        Numpad,       "NumpadMultiply",   0x6A, 55,   0x43,   0xFFAA,     "KP_Multiply"),
+    k(0x56, "KC_PMNS",       "Num -",        Numpad,       "NumpadSubtract",   0x6D, 74,   0x4E,   0xFFAD,     "KP_Subtract"),
+    k(0x57, "KC_PPLS",       "Num +",        Numpad,       "NumpadAdd",        0x6B, 78,   0x45,   0xFFAB,     "KP_Add"),
+    k(0x58, "KC_PENT",       "Num Enter",    Numpad,       "NumpadEnter",      0x0D, 96,   0x4C,   0xFF8D,     "KP_Enter"),
+    k(0x59, "KC_P1",         "Num 1",        Numpad,       "Numpad1",          0x61, 79,   0x53,   0xFFB1,     "KP_1"),
+    k(0x5A, "KC_P2",         "Num 2",        Numpad,       "Numpad2",          0x62, 80,   0x54,   0xFFB2,     "KP_2"),
+    k(0x5B, "KC_P3",         "Num 3",        Numpad,       "Numpad3",          0x63, 81,   0x55,   0xFFB3,     "KP_3"),
+    k(0x5C, "KC_P4",         "Num 4",        Numpad,       "Numpad4",          0x64, 75,   0x56,   0xFFB4,     "KP_4"),
+    k(0x5D, "KC_P5",         "Num 5",        Numpad,       "Numpad5",          0x65, 76,   0x57,   0xFFB5,     "KP_5"),
+    k(0x5E, "KC_P6",         "Num 6",        Numpad,       "Numpad6",          0x66, 77,   0x58,   0xFFB6,     "KP_6"),
+    k(0x5F, "KC_P7",         "Num 7",        Numpad,       "Numpad7",          0x67, 71,   0x59,   0xFFB7,     "KP_7"),
+    k(0x60, "KC_P8",         "Num 8",        Numpad,       "Numpad8",          0x68, 72,   0x5B,   0xFFB8,     "KP_8"),
+    k(0x61, "KC_P9",         "Num 9",        Numpad,       "Numpad9",          0x69, 73,   0x5C,   0xFFB9,     "KP_9"),
+    k(0x62, "KC_P0",         "Num 0",        Numpad,       "Numpad0",          0x60, 82,   0x52,   0xFFB0,     "KP_0"),
+    k(0x63, "KC_PDOT",       "Num .",        Numpad,       "NumpadDecimal",    0x6E, 83,   0x41,   0xFFAE,     "KP_Decimal"),
+    k(0x64, "KC_NUBS",       "ISO \\",       International,"",                 0xE2, 86,   0x0A,   0x003C,     "less"),
+    k(0x65, "KC_APP",        "Menu",         Control,      "",                 0x5D, 127,  NO_MAC, 0xFF67,     "Menu"),
+    k(0x67, "KC_PEQL",       "Num =",        Numpad,       "NumpadEqual",      0x00, 117,  0x51,   0xFFBD,     "KP_Equal"),
+    k(0x68, "KC_F13",        "F13",          Function,     "F13",              0x7C, 183,  0x69,   0xFFCA,     "F13"),
+    k(0x69, "KC_F14",        "F14",          Function,     "F14",              0x7D, 184,  0x6B,   0xFFCB,     "F14"),
+    k(0x6A, "KC_F15",        "F15",          Function,     "F15",              0x7E, 185,  0x71,   0xFFCC,     "F15"),
+    k(0x6B, "KC_F16",        "F16",          Function,     "F16",              0x7F, 186,  0x6A,   0xFFCD,     "F16"),
+    k(0x6C, "KC_F17",        "F17",          Function,     "F17",              0x80, 187,  0x40,   0xFFCE,     "F17"),
+    k(0x6D, "KC_F18",        "F18",          Function,     "F18",              0x81, 188,  0x4F,   0xFFCF,     "F18"),
+    k(0x6E, "KC_F19",        "F19",          Function,     "F19",              0x82, 189,  0x50,   0xFFD0,     "F19"),
+    k(0x6F, "KC_F20",        "F20",          Function,     "F20",              0x83, 190,  0x5A,   0xFFD1,     "F20"),
+    k(0x70, "KC_F21",        "F21",          Function,     "F21",              0x84, 191,  NO_MAC, 0xFFD2,     "F21"),
+    k(0x71, "KC_F22",        "F22",          Function,     "F22",              0x85, 192,  NO_MAC, 0xFFD3,     "F22"),
+    k(0x72, "KC_F23",        "F23",          Function,     "F23",              0x86, 193,  NO_MAC, 0xFFD4,     "F23"),
+    k(0x73, "KC_F24",        "F24",          Function,     "F24",              0x87, 194,  NO_MAC, 0xFFD5,     "F24"),
+    k(0x87, "KC_INT1",       "Ro",           International,"",                 0xE2, 89,   0x5E,   0x0000,     ""),
+    k(0x88, "KC_INT2",       "Kana",         International,"",                 0x15, 93,   NO_MAC, 0xFF27,     "Hiragana_Katakana"),
+    k(0x89, "KC_INT3",       "Yen",          International,"",                 0xDC, 124,  0x5D,   0x00A5,     "yen"),
+    k(0x8A, "KC_INT4",       "Henkan",       International,"",                 0x1C, 92,   NO_MAC, 0xFF23,     "Henkan"),
+    k(0x8B, "KC_INT5",       "Muhenkan",     International,"",                 0x1D, 94,   NO_MAC, 0xFF22,     "Muhenkan"),
+    k(0x90, "KC_LNG1",       "Lang 1",       International,"",                 0x15, 122,  0x68,   0xFF31,     "Hangul"),
+    k(0x91, "KC_LNG2",       "Lang 2",       International,"",                 0x19, 123,  0x66,   0xFF34,     "Hangul_Hanja"),
+    k(0xA8, "KC_MUTE",       "Mute",         Media,        "AudioVolumeMute",  0xAD, 113,  0x4A,   0x1008FF12, "XF86AudioMute"),
+    k(0xA9, "KC_VOLU",       "Volume Up",    Media,        "AudioVolumeUp",    0xAF, 115,  0x48,   0x1008FF13, "XF86AudioRaiseVolume"),
+    k(0xAA, "KC_VOLD",       "Volume Down",  Media,        "AudioVolumeDown",  0xAE, 114,  0x49,   0x1008FF11, "XF86AudioLowerVolume"),
+    k(0xAB, "KC_MNXT",       "Next Track",   Media,        "MediaTrackNext",   0xB0, 163,  NO_MAC, 0x1008FF17, "XF86AudioNext"),
+    k(0xAC, "KC_MPRV",       "Prev Track",   Media,        "MediaTrackPrevious", 0xB1, 165, NO_MAC, 0x1008FF16, "XF86AudioPrev"),
+    k(0xAD, "KC_MSTP",       "Stop",         Media,        "MediaStop",        0xB2, 166,  NO_MAC, 0x1008FF15, "XF86AudioStop"),
+    k(0xAE, "KC_MPLY",       "Play/Pause",   Media,        "MediaPlayPause",   0xB3, 164,  NO_MAC, 0x1008FF14, "XF86AudioPlay"),
+    k(0xAF, "KC_MSEL",       "Media Select", Media,        "",                 0xB5, 226,  NO_MAC, 0x1008FF32, "XF86AudioMedia"),
+    k(0xB0, "KC_EJCT",       "Eject",        Media,        "",                 0x00, 161,  NO_MAC, 0x1008FF2C, "XF86Eject"),
+    k(0xB1, "KC_MAIL",       "Mail",         Media,        "",                 0xB4, 155,  NO_MAC, 0x1008FF19, "XF86Mail"),
+    k(0xB2, "KC_CALC",       "Calculator",   Media,        "",                 0xB7, 140,  NO_MAC, 0x1008FF1D, "XF86Calculator"),
+    k(0xB3, "KC_MYCM",       "My Computer",  Media,        "",                 0xB6, 157,  NO_MAC, 0x1008FF33, "XF86MyComputer"),
+    k(0xB4, "KC_WSCH",       "Web Search",   Media,        "",                 0xAA, 217,  NO_MAC, 0x1008FF1B, "XF86Search"),
+    k(0xB5, "KC_WHOM",       "Web Home",     Media,        "",                 0xAC, 172,  NO_MAC, 0x1008FF18, "XF86HomePage"),
+    k(0xB6, "KC_WBAK",       "Web Back",     Media,        "",                 0xA6, 158,  NO_MAC, 0x1008FF26, "XF86Back"),
+    k(0xB7, "KC_WFWD",       "Web Forward",  Media,        "",                 0xA7, 159,  NO_MAC, 0x1008FF27, "XF86Forward"),
+    k(0xB8, "KC_WSTP",       "Web Stop",     Media,        "",                 0xA9, 128,  NO_MAC, 0x1008FF28, "XF86Stop"),
+    k(0xB9, "KC_WREF",       "Web Refresh",  Media,        "",                 0xA8, 173,  NO_MAC, 0x1008FF29, "XF86Refresh"),
+    k(0xBA, "KC_WFAV",       "Favorites",    Media,        "",                 0xAB, 156,  NO_MAC, 0x1008FF30, "XF86Favorites"),
+    k(0xBB, "KC_MFFD",       "Fast Forward", Media,        "",                 0x00, 208,  NO_MAC, 0x1008FF97, "XF86AudioForward"),
+    k(0xBC, "KC_MRWD",       "Rewind",       Media,        "",                 0x00, 168,  NO_MAC, 0x1008FF3E, "XF86AudioRewind"),
+    k(0xBD, "KC_BRIU",       "Brightness Up",   Media,     "",                 0x00, 225,  NO_MAC, 0x1008FF02, "XF86MonBrightnessUp"),
+    k(0xBE, "KC_BRID",       "Brightness Down", Media,     "",                 0x00, 224,  NO_MAC, 0x1008FF03, "XF86MonBrightnessDown"),
+];
+
+/// Catalog entry sent to the frontend (`list_keycodes`).
+#[derive(Debug, Clone, Serialize)]
+pub struct KeycodeInfo {
+    /// QMK basic keycode (HID usage).
+    pub code: u16,
+    /// QMK name, e.g. "KC_PGDN".
+    pub name: &'static str,
+    pub category: KeyCategory,
+    pub label: &'static str,
+}
+
+/// The whole table in UI form, in table order.
+pub fn catalog() -> Vec<KeycodeInfo> {
+    KEYCODES
+        .iter()
+        .map(|d| KeycodeInfo { code: d.basic as u16, name: d.qmk, category: d.category, label: d.label })
+        .collect()
+}
+
+/// Look up a basic keycode (HID usage).
+pub fn lookup(basic: u8) -> Option<&'static KeycodeDef> {
+    KEYCODES.iter().find(|d| d.basic == basic)
+}
+
+/// Reverse lookup by Windows virtual-key code (first match, so Enter wins
+/// over Numpad Enter).
+#[allow(dead_code)] // Windows only
+pub fn from_vk(vk: u16) -> Option<&'static KeycodeDef> {
+    if vk == 0 {
+        return None;
+    }
+    KEYCODES.iter().find(|d| d.vk == vk)
+}
+
+/// Reverse lookup of `KeycodeDef::numpad_scancode`.
+#[allow(dead_code)] // Windows only
+pub fn from_numpad_scancode(scan: u16) -> Option<&'static KeycodeDef> {
+    KEYCODES.iter().find(|d| d.numpad_scancode() == Some(scan))
+}
+
+/// Whether a full QMK keycode can be registered as a per-key shortcut.
+/// Keys with modifiers always can. Bare keys are limited to F13–F24, which no
+/// regular keyboard has, unless the backend only sees the Deck-8's own events
+/// (`deck_only`) — then a bare "A" or F5 can't hijack the main keyboard.
+pub fn is_bindable(keycode: u16, deck_only: bool) -> bool {
+    let mods = (keycode >> 8) as u8;
+    let basic = (keycode & 0xFF) as u8;
+    basic != 0 && (mods != 0 || deck_only || (0x68..=0x73).contains(&basic))
+}
@@ -0,0 +1,220 @@
+// Keyboard layout awareness for keymaps.
+//
+// QMK keycodes name physical keys (HID usages), but for letters, digits and
+// punctuation what the OS reports and expects depends on the active layout:
+// on AZERTY the key QMK calls KC_A types "q", and Windows reports it as VK_Q.
+// Those keys (`KeycodeDef::positional`) are translated through their scancode
+// and the active layout wherever a virtual key or keysym stands for a key:
+//   - Windows: the LL hook / Raw Input shortcuts and keycode capture use the
+//     VK the foreground window's layout gives the scancode; replay sends the
+//     scancode itself.
+//   - Linux: plugin shortcuts (X11), portal triggers and enigo (xdo) replay
+//     use the keysym the X keymap (`xmodmap -pk`) puts on the keycode. The
+//     evdev reader and uinput replay use keycodes, which are positional.
+//   - macOS: kVK codes are positional already; nothing changes.
+// A thread notices layout switches and registers the shortcuts again.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use log::info;
+use tauri::{AppHandle, Manager};
+
+use crate::keycodes::{KeycodeDef, KEYCODES};
+use crate::state::SharedState;
+
+const POLL: Duration = Duration::from_secs(5);
+
+/// The active layout, e.g. "040C040C" (Windows HKL) or "fr" (X11); `None`
+/// where it isn't known or doesn't matter (macOS).
+pub fn current() -> Option<String> {
+    platform::current()
+}
+
+/// Windows VK for a key under the active layout.
+#[cfg(target_os = "windows")]
+pub fn vk(def: &KeycodeDef) -> Option<u16> {
+    def.scancode().and_then(platform::vk_for_scancode).or(def.vk())
+}
+
+/// The key a VK from the active layout comes from.
+#[cfg(target_os = "windows")]
+pub fn from_vk(vk: u16) -> Option<&'static KeycodeDef> {
+    KEYCODES.iter()
+        .filter(|d| d.positional())
+        .find(|d| self::vk(d) == Some(vk))
+        .or_else(|| crate::keycodes::from_vk(vk).filter(|d| !d.positional()))
+}
+
+/// X keysym a key types under the active layout.
+#[cfg(target_os = "linux")]
+pub fn keysym(def: &KeycodeDef) -> Option<u32> {
+    platform::with_keymap(|keymap| keymap.keysym(def).map(|(keysym, _)| keysym)).or(def.keysym())
+}
+
+/// X keysym name (XDG shortcut trigger) of a key under the active layout.
+#[cfg(target_os = "linux")]
+pub fn xkb(def: &KeycodeDef) -> Option<String> {
+    platform::with_keymap(|keymap| keymap.keysym(def).map(|(_, name)| name.to_string()))
+        .or(def.xkb().map(String::from))
+}
+
+/// W3C code to register a key with global_hotkey under the active layout.
+/// global_hotkey maps codes as if on a US layout, so this is the code whose
+/// US meaning is what the key means now.
+pub fn hotkey_code(def: &KeycodeDef) -> Option<&'static str> {
+    #[cfg(target_os = "windows")]
+    let translated = vk(def).and_then(crate::keycodes::from_vk).and_then(KeycodeDef::hotkey_code);
+    #[cfg(target_os = "linux")]
+    let translated = platform::with_keymap(|keymap| keymap.hotkey_code(def));
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    let translated = None;
+    if def.positional() { translated.or(def.hotkey_code()) } else { def.hotkey_code() }
+}
+
+/// Watch for layout switches; shortcuts are registered again after one.
+pub fn start(app: AppHandle) {
+    if cfg!(not(any(target_os = "windows", target_os = "linux"))) {
+        return;
+    }
+    std::thread::spawn(move || {
+        let mut last: Option<String> = None;
+        loop {
+            let layout = current();
+            if layout.is_some() && layout != last {
+                info!("[layout] Keyboard layout: {}", layout.as_deref().unwrap_or_default());
+                #[cfg(target_os = "linux")]
+                platform::reload();
+                // The first look only confirms what startup registered with
+                if last.is_some() {
+                    let keymaps = app.state::<SharedState>().lock().unwrap().keymaps;
+                    crate::register_key_shortcuts(&app, &keymaps);
+                }
+                last = layout;
+            }
+            std::thread::sleep(POLL);
+        }
+    });
+}
+
+/// An X keymap as `xmodmap -pk` prints it: each keycode's first keysym.
+#[allow(dead_code)] // Linux only
+#[derive(Debug, Default)]
+pub struct XKeymap(HashMap<u16, (u32, String)>);
+
+#[allow(dead_code)] // Linux only
+impl XKeymap {
+    pub fn parse(text: &str) -> Self {
+        let keysyms = text.lines().filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let keycode = fields.next()?.parse().ok()?;
+            let keysym = u32::from_str_radix(fields.next()?.strip_prefix("0x")?, 16).ok()?;
+            let name = fields.next()?.strip_prefix('(')?.strip_suffix(')')?;
+            (keysym != 0).then(|| (keycode, (keysym, name.to_string())))
+        });
+        Self(keysyms.collect())
+    }
+
+    /// Keysym and name a positional key types (X keycodes are evdev + 8).
+    pub fn keysym(&self, def: &KeycodeDef) -> Option<(u32, &str)> {
+        let (keysym, name) = self.0.get(&(def.scancode()? + 8))?;
+        Some((*keysym, name))
+    }
+
+    /// The code whose US keysym is what `def` types here, so global_hotkey's
+    /// keysym lookup lands on the same physical key.
+    pub fn hotkey_code(&self, def: &KeycodeDef) -> Option<&'static str> {
+        let (keysym, _) = self.keysym(def)?;
+        KEYCODES.iter()
+            .filter(|d| d.keysym() == Some(keysym))
+            .find_map(KeycodeDef::hotkey_code)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    extern "system" {
+        fn GetForegroundWindow() -> isize;
+        fn GetWindowThreadProcessId(hwnd: isize, process_id: *mut u32) -> u32;
+        fn GetKeyboardLayout(thread_id: u32) -> isize;
+        fn MapVirtualKeyExW(code: u32, map_type: u32, hkl: isize) -> u32;
+    }
+
+    const MAPVK_VSC_TO_VK: u32 = 1;
+
+    /// Layouts are per thread: the one keystrokes go to is the foreground
+    /// window's.
+    fn hkl() -> isize {
+        // SAFETY: plain Win32 calls; a null window gives this thread's layout
+        unsafe { GetKeyboardLayout(GetWindowThreadProcessId(GetForegroundWindow(), std::ptr::null_mut())) }
+    }
+
+    pub fn current() -> Option<String> {
+        Some(format!("{:08X}", hkl() as usize as u32))
+    }
+
+    pub fn vk_for_scancode(scan: u16) -> Option<u16> {
+        // SAFETY: MapVirtualKeyExW only reads its arguments
+        let vk = unsafe { MapVirtualKeyExW(scan as u32, MAPVK_VSC_TO_VK, hkl()) };
+        (vk != 0).then_some(vk as u16)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::Command;
+    use std::sync::Mutex;
+
+    use log::warn;
+
+    use super::XKeymap;
+
+    static KEYMAP: Mutex<Option<XKeymap>> = Mutex::new(None);
+
+    /// Layout and variant from `setxkbmap -query`, e.g. "fr" or "de(nodeadkeys)".
+    pub fn current() -> Option<String> {
+        let out = Command::new("setxkbmap").arg("-query").output().ok()?;
+        let text = String::from_utf8_lossy(&out.stdout);
+        let field = |name: &str| {
+            text.lines()
+                .find_map(|l| l.strip_prefix(name)?.trim_start().strip_prefix(':'))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let layout = field("layout")?;
+        Some(match field("variant") {
+            Some(variant) => format!("{layout}({variant})"),
+            None => layout,
+        })
+    }
+
+    fn load() -> XKeymap {
+        match Command::new("xmodmap").arg("-pk").output() {
+            Ok(out) if out.status.success() => XKeymap::parse(&String::from_utf8_lossy(&out.stdout)),
+            Ok(out) => {
+                warn!("[layout] xmodmap failed ({}); assuming a US layout", out.status);
+                XKeymap::default()
+            }
+            Err(e) => {
+                warn!("[layout] Can't run xmodmap ({e}); assuming a US layout");
+                XKeymap::default()
+            }
+        }
+    }
+
+    /// Read the X keymap again (after a layout switch).
+    pub fn reload() {
+        *KEYMAP.lock().unwrap() = Some(load());
+    }
+
+    pub fn with_keymap<T>(f: impl FnOnce(&XKeymap) -> Option<T>) -> Option<T> {
+        f(KEYMAP.lock().unwrap().get_or_insert_with(load))
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub fn current() -> Option<String> {
+        None
+    }
+}
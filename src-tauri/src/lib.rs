@@ -1,37 +1,101 @@
+mod api;
+mod appearance;
 mod audio;
+mod brightness;
+mod calendar;
+mod coexist;
+mod connections;
+mod convert;
+mod cooldown;
+mod definition;
+mod device_match;
+mod discord;
+mod dsp;
+mod error;
+mod fade;
+mod flac;
+mod game;
+mod heartbeat;
 mod hid;
+mod http;
+mod i18n;
 mod keyboard_hook;
+mod keycodes;
+mod layout;
+mod library_watch;
+mod lighting;
+mod line_in;
+mod lock;
+mod media;
+mod meeting;
+mod mixer;
+mod modes;
+mod notify;
+mod obs;
+mod onboarding;
+mod pairing;
+mod permissions;
+mod playlist;
+mod pomodoro;
+mod preview;
+mod probe;
 mod profile;
+mod progress;
 mod protocol;
+mod quiet;
+mod reconnect;
+mod replay;
+mod reset;
+mod rules;
+mod selftest;
+mod sound_preview;
+mod speech;
 mod state;
+mod transaction;
+mod trigger;
+mod stopword;
+mod toast;
+mod usage;
+mod virtual_sink;
+mod watchdog;
+mod watchers;
+#[cfg(target_os = "linux")]
+mod wayland_shortcuts;
+#[cfg(test)]
+mod tests;
 
+use connections::EventKind;
+use device_match::DeviceMatch;
+use error::HubError;
 use log::{error, info, warn};
 use protocol::{DeviceInfo, RgbMatrixState};
 use state::{
-    ActiveSlot, AppState, AudioConfig, KeyConfig, ManagedAudioPipeline, SharedState,
-    SoundEntry, StateSnapshot,
+    AppInfo, AppState, ColorSlot, DeviceChoice, DeviceRole, KeyConfig, KeyEvent, KeySource, KeycodeCaptured, ManagedAudioPipeline, ReplayMethod, SharedState, SlotBinding,
+    SoundEntry, StateSnapshot, Theme, KeyGroup, DndState, BrightnessCurve, QuietHours, DiscordSettings,
 };
 use tauri::{
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder},
     tray::TrayIconBuilder,
-    AppHandle, Emitter, Manager, State,
+    AppHandle, Emitter, Manager, Runtime, State,
 };
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 
 // ── QMK keycode → Tauri shortcut string ─────────────────────────────────
 
 /// Convert a QMK keycode (modifier+basic) to a Tauri global shortcut string.
 /// Returns None if the keycode can't be represented as a shortcut.
-/// Uses the Tauri/global_hotkey Display format: "Ctrl+Alt+M" for registration.
-#[allow(dead_code)]
+/// Uses W3C code names for the key: "Ctrl+Alt+KeyM", "Ctrl+Alt+PageDown",
+/// translated for the active keyboard layout (see layout.rs).
 fn qmk_keycode_to_shortcut(keycode: u16) -> Option<String> {
     let mods = (keycode >> 8) as u8;
     let basic = (keycode & 0xFF) as u8;
 
-    // Only handle keycodes with modifiers
-    if mods == 0 || basic == 0 {
+    // Bare keys only when they can't hijack typing (see keycodes::is_bindable)
+    if !keycodes::is_bindable(keycode, false) {
         return None;
     }
+    let key_name = layout::hotkey_code(keycodes::lookup(basic)?)?;
 
     let mut parts = Vec::new();
     // Left or right Ctrl
@@ -43,173 +107,197 @@ fn qmk_keycode_to_shortcut(keycode: u16) -> Option<String> {
     // Left or right GUI
     if mods & 0x88 != 0 { parts.push("Super"); }
 
-    let key_name = match basic {
-        0x04..=0x1D => String::from((b'A' + (basic - 0x04)) as char),
-        0x1E..=0x26 => String::from((b'1' + (basic - 0x1E)) as char),
-        0x27 => "0".into(),
-        0x28 => "Enter".into(),
-        0x29 => "Escape".into(),
-        0x2C => "Space".into(),
-        0x3A..=0x45 => format!("F{}", basic - 0x3A + 1),
-        _ => return None,
-    };
-
-    parts.push(&key_name);
+    parts.push(key_name);
     Some(parts.join("+"))
 }
 
 /// Convert a QMK keycode to the Display format used by Tauri's Shortcut type.
 /// This is the format returned by `format!("{}", shortcut)` in the handler.
-/// Example: "control+alt+KeyM" (lowercase modifiers, "Key" prefix for letters)
-#[allow(dead_code)]
+/// Example: "control+alt+KeyM" (lowercase modifiers, W3C code name for the key)
 fn qmk_keycode_to_display(keycode: u16) -> Option<String> {
     let mods = (keycode >> 8) as u8;
     let basic = (keycode & 0xFF) as u8;
 
-    if mods == 0 || basic == 0 {
+    if !keycodes::is_bindable(keycode, false) {
         return None;
     }
+    let key_name = layout::hotkey_code(keycodes::lookup(basic)?)?;
 
     // Order must match Tauri's global_hotkey Display format:
     // shift (bit 0), control (bit 1), alt (bit 2), super (bit 3)
     let mut parts = Vec::new();
-    if mods & 0x22 != 0 { parts.push("shift".to_string()); }
-    if mods & 0x11 != 0 { parts.push("control".to_string()); }
-    if mods & 0x44 != 0 { parts.push("alt".to_string()); }
-    if mods & 0x88 != 0 { parts.push("super".to_string()); }
-
-    let key_name = match basic {
-        0x04..=0x1D => format!("Key{}", (b'A' + (basic - 0x04)) as char),
-        0x1E..=0x26 => format!("Digit{}", (b'1' + (basic - 0x1E)) as char),
-        0x27 => "Digit0".into(),
-        0x28 => "Enter".into(),
-        0x29 => "Escape".into(),
-        0x2C => "Space".into(),
-        0x3A..=0x45 => format!("F{}", basic - 0x3A + 1),
-        _ => return None,
-    };
+    if mods & 0x22 != 0 { parts.push("shift"); }
+    if mods & 0x11 != 0 { parts.push("control"); }
+    if mods & 0x44 != 0 { parts.push("alt"); }
+    if mods & 0x88 != 0 { parts.push("super"); }
 
     parts.push(key_name);
     Some(parts.join("+"))
 }
 
-/// Simulate a QMK keycode as a real keystroke via enigo.
-/// This replays the shortcut to the OS so the focused application receives it.
-/// Only used on macOS — on Windows the low-level hook lets keystrokes propagate naturally.
-#[allow(dead_code)]
-fn simulate_qmk_keystroke(keycode: u16) {
-    use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+/// Set while a replay is in flight; the plugin handler ignores presses then,
+/// since they can only be our own synthetic keystroke.
+static REPLAYING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
 
-    let mods = (keycode >> 8) as u8;
-    let basic = (keycode & 0xFF) as u8;
+/// Serializes replays so quick successive presses can't interleave their
+/// unregister/re-register steps.
+static REPLAY_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 
-    let mut enigo = match Enigo::new(&Settings::default()) {
-        Ok(e) => e,
-        Err(e) => {
-            error!("[replay] Failed to create Enigo: {}", e);
-            return;
-        }
-    };
+/// Mirrors `AppState.dnd.is_some()` so device writes can be skipped without
+/// threading the state through every helper.
+static DND_ACTIVE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
 
-    // Press modifiers
-    if mods & 0x11 != 0 { let _ = enigo.key(Key::Control, Direction::Press); }
-    if mods & 0x22 != 0 { let _ = enigo.key(Key::Shift, Direction::Press); }
-    if mods & 0x44 != 0 { let _ = enigo.key(Key::Alt, Direction::Press); }
-    if mods & 0x88 != 0 { let _ = enigo.key(Key::Meta, Direction::Press); }
-
-    // Press+release the base key
-    let key = match basic {
-        0x04..=0x1D => Some(Key::Unicode((b'a' + (basic - 0x04)) as char)),
-        0x1E..=0x26 => Some(Key::Unicode((b'1' + (basic - 0x1E)) as char)),
-        0x27 => Some(Key::Unicode('0')),
-        0x28 => Some(Key::Return),
-        0x29 => Some(Key::Escape),
-        0x2C => Some(Key::Space),
-        0x3A => Some(Key::F1),
-        0x3B => Some(Key::F2),
-        0x3C => Some(Key::F3),
-        0x3D => Some(Key::F4),
-        0x3E => Some(Key::F5),
-        0x3F => Some(Key::F6),
-        0x40 => Some(Key::F7),
-        0x41 => Some(Key::F8),
-        0x42 => Some(Key::F9),
-        0x43 => Some(Key::F10),
-        0x44 => Some(Key::F11),
-        0x45 => Some(Key::F12),
-        _ => None,
-    };
-    if let Some(k) = key {
-        let _ = enigo.key(k, Direction::Click);
-    }
+fn dnd_active() -> bool {
+    DND_ACTIVE.load(std::sync::atomic::Ordering::SeqCst)
+}
 
-    // Release modifiers (reverse order)
-    if mods & 0x88 != 0 { let _ = enigo.key(Key::Meta, Direction::Release); }
-    if mods & 0x44 != 0 { let _ = enigo.key(Key::Alt, Direction::Release); }
-    if mods & 0x22 != 0 { let _ = enigo.key(Key::Shift, Direction::Release); }
-    if mods & 0x11 != 0 { let _ = enigo.key(Key::Control, Direction::Release); }
+/// Unix time in milliseconds.
+fn now_ms() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
 }
 
-/// Convert keymap index (matrix-order) to LED index (snake-wired).
-/// Top row: key 0-3 → LED 0-3 (direct)
-/// Bottom row: key 4-7 → LED 7,6,5,4 (reversed due to snake wiring)
+/// Convert keymap index (matrix-order) to LED index, per the device definition.
+/// Stock (snake-wired): top row key 0-3 → LED 0-3, bottom row key 4-7 → LED 7,6,5,4.
 fn keymap_to_led_index(keymap_idx: usize) -> usize {
-    if keymap_idx < 4 {
-        keymap_idx
-    } else {
-        11 - keymap_idx // 4→7, 5→6, 6→5, 7→4
-    }
+    definition::current().led_order[keymap_idx] as usize
 }
 
 /// Register per-key global shortcuts based on actual device keymaps.
 /// On Windows: uses a low-level keyboard hook (coexists with apps like Wispr Flow).
+/// On Linux: reads the Deck-8's evdev nodes when accessible, else falls back to the plugin.
 /// On macOS: uses tauri_plugin_global_shortcut (RegisterHotKey equivalent).
 fn register_key_shortcuts(app: &AppHandle, keymaps: &[u16; 8]) {
-    // Windows: low-level keyboard hook — keystroke propagates naturally, no replay needed
-    #[cfg(target_os = "windows")]
-    {
-        use tauri_plugin_global_shortcut::GlobalShortcutExt;
+    register_deck_shortcuts(app, keymaps);
+    // Every path above unregisters all plugin shortcuts
+    register_app_hotkeys(app);
+}
+
+fn register_deck_shortcuts(app: &AppHandle, keymaps: &[u16; 8]) {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    if app.state::<SharedState>().lock().unwrap().safe_mode {
+        info!("[shortcuts] Safe mode — not registering per-key shortcuts");
+        return;
+    }
+
+    // Hook backends: keystroke propagates naturally, no replay needed
+    if keyboard_hook::is_active() {
         // Ensure no plugin-based shortcuts are registered (hook handles everything)
         let _ = app.global_shortcut().unregister_all();
         keyboard_hook::register_shortcuts(app, keymaps);
+        // Release any portal binding made before the evdev reader took over
+        #[cfg(target_os = "linux")]
+        if wayland_shortcuts::is_active() {
+            wayland_shortcuts::register_shortcuts(&[0u16; 8]);
+        }
+        return;
     }
 
-    // macOS: use tauri_plugin_global_shortcut with unregister→replay→re-register dance
-    #[cfg(not(target_os = "windows"))]
-    {
-        use tauri_plugin_global_shortcut::GlobalShortcutExt;
+    // Wayland: bind through the GlobalShortcuts portal (compositor consumes the keystroke)
+    #[cfg(target_os = "linux")]
+    if wayland_shortcuts::is_active() {
+        let _ = app.global_shortcut().unregister_all();
+        app.state::<SharedState>().lock().unwrap().shortcut_map.clear();
+        if wayland_shortcuts::register_shortcuts(keymaps) {
+            return;
+        }
+    }
+
+    // Plugin fallback: unregister→replay→re-register dance
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        warn!("[shortcuts] Failed to unregister old shortcuts: {}", e);
+    }
 
-        if let Err(e) = app.global_shortcut().unregister_all() {
-            warn!("[shortcuts] Failed to unregister old shortcuts: {}", e);
+    let state = app.state::<SharedState>();
+    let mut st = state.lock().unwrap();
+    st.shortcut_map.clear();
+
+    for (display_str, (led_idx, keycode, shortcut_str)) in plugin_shortcuts(keymaps) {
+        info!("[shortcuts] led={} keycode=0x{:04X} → \"{}\"", led_idx, keycode, shortcut_str);
+        match app.global_shortcut().register(shortcut_str.as_str()) {
+            Ok(_) => {
+                st.shortcut_map.insert(display_str, (led_idx, keycode, shortcut_str));
+            }
+            Err(e) => {
+                error!("[shortcuts] led={} register failed: {}", led_idx, e);
+            }
         }
+    }
+    info!("[shortcuts] Registered {} per-key shortcuts", st.shortcut_map.len());
+}
 
-        let state = app.state::<SharedState>();
-        let mut st = state.lock().unwrap();
-        st.shortcut_map.clear();
-
-        for (i, &keycode) in keymaps.iter().enumerate() {
-            if let Some(shortcut_str) = qmk_keycode_to_shortcut(keycode) {
-                let display_str = qmk_keycode_to_display(keycode).unwrap_or_default();
-                let led_idx = keymap_to_led_index(i);
-                info!("[shortcuts] keymap={} → led={} keycode=0x{:04X} → \"{}\"",
-                      i, led_idx, keycode, shortcut_str);
-                match app.global_shortcut().register(shortcut_str.as_str()) {
-                    Ok(_) => {
-                        st.shortcut_map.insert(
-                            display_str,
-                            (led_idx, keycode, shortcut_str.clone()),
-                        );
-                    }
-                    Err(e) => {
-                        error!("[shortcuts] keymap={} register failed: {}", i, e);
-                    }
-                }
-            } else {
-                info!("[shortcuts] keymap={} keycode=0x{:04X} → not mappable", i, keycode);
+/// (Re-)register the app's own hotkeys from the settings with the plugin.
+/// Returns the ones that failed (bad hotkey, or taken by another app or key).
+fn register_app_hotkeys(app: &AppHandle) -> Vec<state::AppHotkey> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+    let state = app.state::<SharedState>();
+    let mut st = state.lock().unwrap();
+    for (_, hotkey) in std::mem::take(&mut st.app_hotkeys).into_values() {
+        let _ = app.global_shortcut().unregister(hotkey.as_str());
+    }
+    if st.safe_mode {
+        return Vec::new();
+    }
+    let mut failed = Vec::new();
+    for action in [state::AppHotkey::StopSounds, state::AppHotkey::MuteSounds] {
+        let Some(hotkey) = st.settings.hotkey_mut(action).clone() else { continue };
+        let registered = hotkey.parse::<Shortcut>().map_err(|e| e.to_string())
+            .and_then(|shortcut| {
+                app.global_shortcut().register(shortcut).map_err(|e| e.to_string())?;
+                Ok(shortcut)
+            });
+        match registered {
+            Ok(shortcut) => {
+                info!("[shortcuts] {:?} → \"{}\"", action, hotkey);
+                st.app_hotkeys.insert(shortcut.to_string(), (action, hotkey));
+            }
+            Err(e) => {
+                warn!("[shortcuts] {:?} hotkey \"{}\" not registered: {}", action, hotkey, e);
+                failed.push(action);
             }
         }
-        info!("[shortcuts] Registered {} per-key shortcuts", st.shortcut_map.len());
     }
+    failed
+}
+
+/// Set or clear (`None`) one of the app's global hotkeys. A hotkey that can't
+/// be registered is not kept.
+#[tauri::command]
+fn set_app_hotkey(
+    app: AppHandle,
+    state: State<SharedState>,
+    action: state::AppHotkey,
+    hotkey: Option<String>,
+) -> Result<StateSnapshot, HubError> {
+    let hotkey = hotkey.map(|h| h.trim().to_string()).filter(|h| !h.is_empty());
+    let previous = {
+        let mut st = state.lock().unwrap();
+        std::mem::replace(st.settings.hotkey_mut(action), hotkey.clone())
+    };
+    if register_app_hotkeys(&app).contains(&action) {
+        *state.lock().unwrap().settings.hotkey_mut(action) = previous;
+        register_app_hotkeys(&app);
+        return Err(i18n::t_with("error-hotkey-taken", &[("hotkey", &hotkey.unwrap_or_default())]).into());
+    }
+    let st = state.lock().unwrap();
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// What the plugin fallback registers for each mappable keymap entry, keyed
+/// like `shortcut_map`: handler display string → (LED index, keycode, shortcut).
+fn plugin_shortcuts(keymaps: &[u16; 8]) -> Vec<(String, (usize, u16, String))> {
+    keymaps.iter().enumerate()
+        .filter_map(|(i, &keycode)| {
+            let Some(shortcut_str) = qmk_keycode_to_shortcut(keycode) else {
+                info!("[shortcuts] keymap={} keycode=0x{:04X} → not mappable", i, keycode);
+                return None;
+            };
+            let display_str = qmk_keycode_to_display(keycode).unwrap_or_default();
+            Some((display_str, (keymap_to_led_index(i), keycode, shortcut_str)))
+        })
+        .collect()
 }
 
 // ── Internal keycodes for sound-only keys ───────────────────────────────
@@ -236,143 +324,352 @@ fn is_old_internal_keycode(keycode: u16) -> bool {
 }
 
 /// Convert LED index to keymap/matrix index (inverse of keymap_to_led_index).
-/// The mapping is symmetric: top row direct, bottom row reversed.
 fn led_to_keymap_index(led_idx: usize) -> usize {
-    if led_idx < 4 { led_idx } else { 11 - led_idx }
+    definition::current().keymap_index(led_idx)
 }
 
 // ── Helpers ─────────────────────────────────────────────────────────────
 
 /// Apply color for a single key to the device, using the key's own active_slot.
+/// No-op while do-not-disturb is on; `toggle_dnd` reapplies every key on exit.
 fn apply_key_to_device(dev: &hid::Deck8Device, key_index: u8, key: &KeyConfig) {
+    if !key_writable(dev, key_index as usize) {
+        return;
+    }
+    if let Err(e) = write_key_to_device(dev, key_index, key) {
+        error!("[apply] key={} FAILED: {:#}", key_index, e);
+    }
+}
+
+/// Whether DND, a progress bar, a theme preview or a notification is showing
+/// on the key's LED; whatever ends it puts the key's own color back.
+fn key_covered(key_index: usize) -> bool {
+    dnd_active() || progress::active() || preview::active() || notify::covers(key_index)
+}
+
+/// Whether the key's own color may be written now: nothing covers its LED
+/// and the firmware takes per-key overrides.
+fn key_writable(dev: &hid::Deck8Device, key_index: usize) -> bool {
+    !key_covered(key_index) && dev.capabilities().per_key_override
+}
+
+/// The key's active slot color, dimmed while the key cools down.
+fn own_color(key_index: usize, key: &KeyConfig) -> protocol::HsvColor {
+    cooldown::shade(key_index, key, key.active().color)
+}
+
+/// Write a key's active color (or turn its override off), whatever else is
+/// covering the LEDs.
+fn write_key_to_device(dev: &hid::Deck8Device, key_index: u8, key: &KeyConfig) -> anyhow::Result<()> {
     if key.override_enabled {
-        let color = match key.active_slot {
-            ActiveSlot::A => &key.slot_a,
-            ActiveSlot::B => &key.slot_b,
-        };
+        let slot = key.active();
+        let color = own_color(key_index as usize, key);
         info!("[apply] key={} slot={:?} override=ON h={} s={} v={}",
-              key_index, key.active_slot, color.h, color.s, color.v);
-        if let Err(e) = dev.set_key_color(key_index, color) {
-            error!("[apply] key={} set_key_color FAILED: {:#}", key_index, e);
-        }
+              key_index, slot.name, color.h, color.s, color.v);
+        dev.set_key_color(key_index, &brightness::key_color(&color))
     } else {
         info!("[apply] key={} override=OFF → disable", key_index);
-        if let Err(e) = dev.disable_override(key_index) {
-            error!("[apply] key={} disable_override FAILED: {:#}", key_index, e);
-        }
+        dev.disable_override(key_index)
+    }
+}
+
+/// Take over a saved setup (state.json at startup, or an imported bundle).
+fn apply_restored(state: &mut AppState, restored: profile::RestoredState) {
+    state.keys = restored.keys;
+    if let Some(cfg) = restored.audio_config {
+        state.audio_config = cfg;
+    }
+    if let Some(km) = restored.keymaps {
+        state.keymaps = km;
+    }
+    if let Some(settings) = restored.settings {
+        state.settings = settings;
     }
+    lock::set_locked(state.settings.lock_pin.is_some());
+    state.palette = restored.palette;
+    state.themes = restored.themes;
+    state.groups = restored.groups;
+    state.modes = restored.modes;
+    state.active_mode = restored.active_mode;
+    state.rules = restored.rules;
+    state.saved_rgb = restored.rgb_matrix;
+    state.echo_checked_firmware = restored.echo_checked_firmware;
 }
 
-/// Persist key + audio state + keymaps to disk (fire-and-forget).
-fn persist_state(keys: &[KeyConfig; 8], audio_config: &AudioConfig, keymaps: &[u16; 8]) {
-    if let Err(e) = profile::save_state(keys, audio_config, keymaps) {
+/// Persist key + audio state + keymaps + settings to disk (fire-and-forget).
+fn persist_state(st: &AppState) {
+    if let Err(e) = profile::save_state(st) {
         error!("Failed to persist state: {e:#}");
     }
 }
 
-/// Apply all 8 keys to device, using each key's own active_slot.
+/// Apply all 8 keys to device, using each key's own active_slot (or redraw
+/// the progress bar or theme preview covering them). Notifications redraw on
+/// their next frame.
 fn apply_all_to_device(dev: &hid::Deck8Device, keys: &[KeyConfig; 8]) {
+    notify::invalidate();
+    match progress::redraw(dev) {
+        Ok(false) => {}
+        Ok(true) => return,
+        Err(e) => {
+            error!("[progress] redraw FAILED: {:#}", e);
+            return;
+        }
+    }
+    match preview::redraw(dev) {
+        Ok(false) => {}
+        Ok(true) => return,
+        Err(e) => {
+            error!("[preview] redraw FAILED: {:#}", e);
+            return;
+        }
+    }
     for i in 0..8 {
         apply_key_to_device(dev, i as u8, &keys[i]);
     }
 }
 
-// ── Tauri Commands ──────────────────────────────────────────────────────
-
-#[tauri::command]
-fn connect_device(app: AppHandle, state: State<SharedState>) -> bool {
+/// Take over a freshly opened device: read its keymaps, info and RGB state,
+/// push every key's color and fix up internal keycodes.
+fn sync_device(s: &mut AppState, mut dev: hid::Deck8Device) {
     let t0 = std::time::Instant::now();
-    let mut s = state.lock().unwrap();
-    match hid::Deck8Device::open() {
-        Ok(dev) => {
-            info!("[connect] HID open: {}ms", t0.elapsed().as_millis());
-            let mut keymaps_copy = [0u16; 8];
-            match dev.read_all_keycodes() {
-                Ok(keymaps) => {
-                    s.keymaps = keymaps;
-                    keymaps_copy = keymaps;
-                    info!("[connect] Keymaps read: {}ms {:?}",
-                          t0.elapsed().as_millis(),
-                          keymaps.iter().map(|k| format!("0x{:04X}", k)).collect::<Vec<_>>());
+    dev.set_timing(s.settings.hid);
+    watchdog::watch(&dev.health());
+    match dev.read_all_keycodes() {
+        Ok(keymaps) => {
+            // Last-known binds (from state.json or the previous connection)
+            let known = s.keymap_restore.unwrap_or(s.keymaps);
+            if known != keymaps && known.iter().any(|&k| k != 0) {
+                info!("[connect] Keymaps differ from last known {:?}",
+                      known.iter().map(|k| format!("0x{:04X}", k)).collect::<Vec<_>>());
+                s.keymap_restore = Some(known);
+            } else {
+                s.keymap_restore = None;
+            }
+            s.keymaps = keymaps;
+            info!("[connect] Keymaps read: {}ms {:?}",
+                  t0.elapsed().as_millis(),
+                  keymaps.iter().map(|k| format!("0x{:04X}", k)).collect::<Vec<_>>());
+        }
+        Err(e) => error!("Failed to read keymaps: {e:#}"),
+    }
+    let info = match dev.get_device_info(s.device_info_cache.as_ref()) {
+        Ok(info) => {
+            info!("[connect] Device info: {}ms {:?}", t0.elapsed().as_millis(), info.capabilities);
+            dev.set_capabilities(info.capabilities);
+            s.device_info_cache = Some(info.clone());
+            s.device_info = Some(info.clone());
+            Some(info)
+        }
+        Err(e) => {
+            error!("Failed to read device info: {e:#}");
+            None
+        }
+    };
+    let report = selftest::run(&dev, info.as_ref(), s.echo_checked_firmware);
+    if report.failed() {
+        warn!("[connect] Self-test found firmware/app mismatches, see above");
+    }
+    if let Some(firmware) = info.as_ref().map(|i| i.firmware_version) {
+        if report.writes_passed() && s.echo_checked_firmware != Some(firmware) {
+            s.echo_checked_firmware = Some(firmware);
+            persist_state(s);
+        }
+    }
+    info!("[connect] Self-test: {}ms", t0.elapsed().as_millis());
+    s.sync_report = Some(report);
+    match dev.rgb_get_state() {
+        Err(e) if !dev.capabilities().rgb_matrix => info!("[connect] {e:#}, skipping RGB state"),
+        Ok(mut rgb) => {
+            // Compare on the wire values (the curve's inverse is lossy).
+            // During DND the device reads dark, so skip the check.
+            match s.saved_rgb {
+                Some(saved) if s.dnd.is_none() => {
+                    let wire = RgbMatrixState { brightness: brightness::apply(saved.brightness), ..saved };
+                    s.rgb_mismatch = wire != rgb;
+                    if s.rgb_mismatch {
+                        info!("[connect] RGB differs from saved: device={:?} saved={:?}", rgb, saved);
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    // First connect: adopt the device's settings as the saved ones
+                    s.saved_rgb = Some(RgbMatrixState { brightness: brightness::invert(rgb.brightness), ..rgb });
+                    persist_state(s);
                 }
-                Err(e) => error!("Failed to read keymaps: {e:#}"),
             }
-            match dev.get_device_info() {
-                Ok(info) => { s.device_info = Some(info); info!("[connect] Device info: {}ms", t0.elapsed().as_millis()); }
-                Err(e) => error!("Failed to read device info: {e:#}"),
+            rgb.brightness = brightness::invert(rgb.brightness);
+            if let Some(dnd) = s.dnd {
+                rgb.brightness = dnd.brightness;
             }
-            match dev.rgb_get_state() {
-                Ok(rgb) => { s.rgb_matrix = Some(rgb); info!("[connect] RGB state: {}ms", t0.elapsed().as_millis()); }
-                Err(e) => error!("Failed to read RGB state: {e:#}"),
+            s.rgb_matrix = Some(rgb);
+            dev.rgb_synced();
+            info!("[connect] RGB state: {}ms", t0.elapsed().as_millis());
+        }
+        Err(e) => error!("Failed to read RGB state: {e:#}"),
+    }
+    s.device = Some(dev);
+    // Sync ALL 8 keys on connect: enable overrides we want, disable the rest.
+    if let Some(dev) = s.device.as_ref().filter(|d| d.capabilities().per_key_override) {
+        info!("[connect] Syncing all 8 keys to device...");
+        for (i, k) in s.keys.iter().enumerate() {
+            info!("[connect]   key={} override={} slot={:?}", i, k.override_enabled, k.active().name);
+        }
+        apply_all_to_device(dev, &s.keys);
+        if s.dnd.is_some() {
+            // Reconnected during do-not-disturb: keep the new handle dark too
+            for i in 0..8u8 {
+                let _ = dev.disable_override(i);
             }
-            s.device = Some(dev);
-            // Sync ALL 8 keys on connect: enable overrides we want, disable the rest.
+            let _ = dev.rgb_set_brightness(0);
+        }
+        info!("[connect] Keys synced: {}ms", t0.elapsed().as_millis());
+        info!("[connect] Saving clean state to EEPROM...");
+        if let Err(e) = dev.custom_save() {
+            error!("[connect] custom_save FAILED: {:#}", e);
+        }
+        info!("[connect] EEPROM saved: {}ms", t0.elapsed().as_millis());
+    }
+    // Migrate old internal keycodes (0x071E range) to new range (0x0F68)
+    for km_idx in 0..8 {
+        let kc = s.keymaps[km_idx];
+        if is_old_internal_keycode(kc) {
+            let led_idx = keymap_to_led_index(km_idx);
+            let new_kc = internal_keycode_for_key(led_idx);
             if let Some(ref dev) = s.device {
-                info!("[connect] Syncing all 8 keys to device...");
-                for (i, k) in s.keys.iter().enumerate() {
-                    info!("[connect]   key={} override={} slot={:?}", i, k.override_enabled, k.active_slot);
-                }
-                apply_all_to_device(dev, &s.keys);
-                info!("[connect] Keys synced: {}ms", t0.elapsed().as_millis());
-                info!("[connect] Saving clean state to EEPROM...");
-                if let Err(e) = dev.custom_save() {
-                    error!("[connect] custom_save FAILED: {:#}", e);
+                let (row, col) = protocol::key_index_to_matrix(km_idx as u8);
+                if let Err(e) = dev.set_keycode(0, row, col, new_kc) {
+                    error!("[sound] Failed to migrate internal keycode: {}", e);
                 }
-                info!("[connect] EEPROM saved: {}ms", t0.elapsed().as_millis());
             }
-            // Migrate old internal keycodes (0x071E range) to new range (0x0F68)
-            for km_idx in 0..8 {
-                let kc = s.keymaps[km_idx];
-                if is_old_internal_keycode(kc) {
-                    let led_idx = keymap_to_led_index(km_idx);
-                    let new_kc = internal_keycode_for_key(led_idx);
-                    if let Some(ref dev) = s.device {
-                        let (row, col) = protocol::key_index_to_matrix(km_idx as u8);
-                        if let Err(e) = dev.set_keycode(0, row, col, new_kc) {
-                            error!("[sound] Failed to migrate internal keycode: {}", e);
-                        }
+            s.keymaps[km_idx] = new_kc;
+            info!("[sound] Migrated old internal keycode 0x{:04X} → 0x{:04X} for LED {} (keymap {})",
+                  kc, new_kc, led_idx, km_idx);
+        }
+    }
+    // Auto-assign internal keycodes for keys with sounds but no keycode
+    for led_idx in 0..8 {
+        if s.audio_config.key_sounds[led_idx].is_some() {
+            let km_idx = led_to_keymap_index(led_idx);
+            if s.keymaps[km_idx] == 0x0000 {
+                let internal_kc = internal_keycode_for_key(led_idx);
+                if let Some(ref dev) = s.device {
+                    let (row, col) = protocol::key_index_to_matrix(km_idx as u8);
+                    if let Err(e) = dev.set_keycode(0, row, col, internal_kc) {
+                        error!("[sound] Failed to auto-assign keycode on connect: {}", e);
                     }
-                    s.keymaps[km_idx] = new_kc;
-                    info!("[sound] Migrated old internal keycode 0x{:04X} → 0x{:04X} for LED {} (keymap {})",
-                          kc, new_kc, led_idx, km_idx);
                 }
+                s.keymaps[km_idx] = internal_kc;
+                info!("[sound] Auto-assigned internal keycode 0x{:04X} to LED {} on connect", internal_kc, led_idx);
             }
-            // Auto-assign internal keycodes for keys with sounds but no keycode
-            for led_idx in 0..8 {
-                if s.audio_config.key_sounds[led_idx].is_some() {
-                    let km_idx = led_to_keymap_index(led_idx);
-                    if s.keymaps[km_idx] == 0x0000 {
-                        let internal_kc = internal_keycode_for_key(led_idx);
-                        if let Some(ref dev) = s.device {
-                            let (row, col) = protocol::key_index_to_matrix(km_idx as u8);
-                            if let Err(e) = dev.set_keycode(0, row, col, internal_kc) {
-                                error!("[sound] Failed to auto-assign keycode on connect: {}", e);
-                            }
-                        }
-                        s.keymaps[km_idx] = internal_kc;
-                        info!("[sound] Auto-assigned internal keycode 0x{:04X} to LED {} on connect", internal_kc, led_idx);
-                    }
-                }
+        }
+    }
+}
+
+// ── Tauri Commands ──────────────────────────────────────────────────────
+
+/// Open the Deck-8 and sync it. Safe to call while connected: the old handle
+/// is released first and everything is re-read. Emits `state-updated`.
+#[tauri::command]
+fn connect_device(app: AppHandle, state: State<SharedState>) -> bool {
+    let t0 = std::time::Instant::now();
+    let mut s = state.lock().unwrap();
+    // Never hold two handles to the interface
+    if let Some(old) = s.device.take() {
+        info!("[connect] Releasing previous handle");
+        let kind = if old.health().is_unhealthy() { EventKind::Unresponsive } else { EventKind::Resync };
+        connections::record(kind, None);
+    }
+    match hid::Deck8Device::open() {
+        Ok(dev) => {
+            info!("[connect] HID open: {}ms", t0.elapsed().as_millis());
+            s.device_busy = false;
+            connections::record(EventKind::Connected, None);
+            sync_device(&mut s, dev);
+            let keymaps_copy = s.keymaps;
+            if let Some(ref report) = s.sync_report {
+                let _ = app.emit("sync-report", report);
             }
-            keymaps_copy = s.keymaps;
 
             // Release lock before registering shortcuts (which also locks state)
             drop(s);
             // Register per-key shortcuts based on actual device keymaps
             register_key_shortcuts(&app, &keymaps_copy);
             info!("[connect] TOTAL: {}ms", t0.elapsed().as_millis());
+            onboarding::sync(&app);
+            let _ = app.emit("state-updated", state.lock().unwrap().snapshot());
             true
         }
         Err(e) => {
-            error!("Failed to connect: {e:#}");
-            s.device = None;
+            let busy = e.downcast_ref::<hid::Busy>().is_some();
+            // Cooperative mode retries every few seconds; log the first one
+            if !(busy && s.device_busy) {
+                error!("Failed to connect: {e:#}");
+            }
+            if busy {
+                connections::record(EventKind::Busy, None);
+            } else if e.downcast_ref::<hid::NotFound>().is_some() {
+                connections::record(EventKind::NotFound, None);
+            } else {
+                connections::record(EventKind::Failed, Some(format!("{e:#}")));
+            }
+            s.device_busy = busy;
             s.device_info = None;
             s.rgb_matrix = None;
+            s.sync_report = None;
+            let _ = app.emit("state-updated", s.snapshot());
             false
         }
     }
 }
 
+/// Re-read `device-definition.json` from the config dir and, when connected,
+/// re-sync the device with it (keymaps, colors, shortcuts).
+#[tauri::command]
+fn reload_definitions(app: AppHandle, state: State<SharedState>) -> Result<StateSnapshot, HubError> {
+    let path = definition::path().map_err(HubError::platform)?;
+    definition::reload().map_err(|e| HubError::file(&path, e))?;
+    let (connected, keymaps) = {
+        let st = state.lock().unwrap();
+        (st.device.is_some(), st.keymaps)
+    };
+    if connected {
+        connect_device(app, state.clone());
+    } else {
+        // The LED order may have changed
+        register_key_shortcuts(&app, &keymaps);
+    }
+    Ok(state.lock().unwrap().snapshot())
+}
+
+/// Release the HID handle so VIA, QMK Toolbox, etc. can use the interface.
+/// Key colors stay on the device; a no-op when already disconnected.
+#[tauri::command]
+fn disconnect_device(app: AppHandle, state: State<SharedState>) -> StateSnapshot {
+    let mut st = state.lock().unwrap();
+    if let Some(dev) = release_device(&mut st) {
+        let kind = if dev.health().is_unhealthy() { EventKind::Unresponsive } else { EventKind::Released };
+        connections::record(kind, None);
+        info!("[connect] Device released");
+    }
+    st.device_busy = false;
+    let snapshot = st.snapshot();
+    let _ = app.emit("state-updated", &snapshot);
+    snapshot
+}
+
+/// Drop the HID handle and what was read from it; returns the handle.
+fn release_device(st: &mut AppState) -> Option<hid::Deck8Device> {
+    let dev = st.device.take()?;
+    for i in 0..8 {
+        fade::cancel(i);
+    }
+    st.device_info = None;
+    st.rgb_matrix = None;
+    st.sync_report = None;
+    Some(dev)
+}
+
 #[tauri::command]
 fn get_state(state: State<SharedState>) -> StateSnapshot {
     state.lock().unwrap().snapshot()
@@ -386,725 +683,3396 @@ fn set_key_color(
     h: u8,
     s: u8,
     v: u8,
-) -> Result<(), String> {
+) -> Result<(), HubError> {
     let mut st = state.lock().unwrap();
     if key_index >= 8 {
-        return Err("key_index out of range".into());
+        return Err(i18n::t("error-key-index").into());
     }
     let color = protocol::HsvColor { h, s, v };
-    let parsed_slot = match slot.as_str() {
-        "A" => { st.keys[key_index].slot_a = color; ActiveSlot::A },
-        "B" => { st.keys[key_index].slot_b = color; ActiveSlot::B },
-        _ => return Err("slot must be A or B".into()),
-    };
+    let key = &mut st.keys[key_index];
+    let idx = key.slot_index(&slot).ok_or_else(|| i18n::t_with("error-no-slot", &[("slot", &slot)]))?;
+    key.slots[idx].color = color;
     // Update the key's active slot to match whichever slot was just edited
-    st.keys[key_index].active_slot = parsed_slot;
+    key.active_slot = idx;
     // Always send to device when override is enabled
-    if st.keys[key_index].override_enabled {
+    fade::cancel(key_index);
+    if st.keys[key_index].override_enabled && !key_covered(key_index) {
         if let Some(ref dev) = st.device {
-            dev.set_key_color(key_index as u8, &color)
-                .map_err(|e| e.to_string())?;
+            dev.set_key_color(key_index as u8, &brightness::key_color(&color))
+                .map_err(HubError::from)?;
+            dev.note_color_edit();
         }
     }
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
+    persist_state(&st);
     Ok(())
 }
 
+/// Several `set_key_color`s in one go: `(key_index, slot, color)` entries are
+/// applied in order (the last slot edited on a key becomes active), the keys
+/// that changed are written in one batch and state is persisted once.
 #[tauri::command]
-fn toggle_slot(state: State<SharedState>) -> Result<String, String> {
-    info!("⚠️ [GLOBAL IPC] toggle_slot command called!");
-    let mut st = state.lock().unwrap();
-    // Toggle global indicator
-    st.active_slot = match st.active_slot {
-        ActiveSlot::A => ActiveSlot::B,
-        ActiveSlot::B => ActiveSlot::A,
-    };
-    let new_slot = st.active_slot;
-    // Toggle each key's individual slot
-    for key in st.keys.iter_mut() {
-        key.active_slot = match key.active_slot {
-            ActiveSlot::A => ActiveSlot::B,
-            ActiveSlot::B => ActiveSlot::A,
-        };
-    }
-    if let Some(ref dev) = st.device {
-        apply_all_to_device(dev, &st.keys);
-    }
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
-    Ok(new_slot.to_string())
-}
-
-#[tauri::command]
-fn toggle_key_slot(
+fn set_all_key_colors(
     state: State<SharedState>,
-    key_index: usize,
-) -> Result<StateSnapshot, String> {
+    colors: Vec<(usize, String, protocol::HsvColor)>,
+) -> Result<(), HubError> {
     let mut st = state.lock().unwrap();
-    if key_index >= 8 {
-        return Err("key_index out of range".into());
+    // Validate everything first so a bad entry leaves state untouched
+    let mut targets = Vec::with_capacity(colors.len());
+    for (key_index, slot, color) in colors {
+        let key = st.keys.get(key_index).ok_or_else(|| i18n::t("error-key-index"))?;
+        let idx = key.slot_index(&slot).ok_or_else(|| i18n::t_with("error-no-slot", &[("slot", &slot)]))?;
+        targets.push((key_index, idx, color));
+    }
+    let mut keys = st.keys.clone();
+    let mut touched = [false; 8];
+    for (key_index, idx, color) in targets {
+        let key = &mut keys[key_index];
+        key.slots[idx].color = color;
+        key.active_slot = idx;
+        touched[key_index] = true;
     }
-    let old = st.keys[key_index].active_slot;
-    st.keys[key_index].active_slot = match old {
-        ActiveSlot::A => ActiveSlot::B,
-        ActiveSlot::B => ActiveSlot::A,
-    };
-    let new = st.keys[key_index].active_slot;
-    info!("[PER-KEY TOGGLE] key={} {:?}→{:?} override={}",
-          key_index, old, new, st.keys[key_index].override_enabled);
     if let Some(ref dev) = st.device {
-        apply_key_to_device(dev, key_index as u8, &st.keys[key_index]);
+        let batch: Vec<_> = (0..8)
+            .filter(|&i| touched[i] && keys[i].override_enabled && key_writable(dev, i))
+            .map(|i| (i as u8, brightness::key_color(&own_color(i, &keys[i]))))
+            .collect();
+        if !batch.is_empty() {
+            dev.set_key_colors(&batch)?;
+            dev.note_color_edit();
+        }
     }
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
-    Ok(st.snapshot())
+    (0..8).filter(|&i| touched[i]).for_each(fade::cancel);
+    st.keys = keys;
+    persist_state(&st);
+    Ok(())
 }
 
-#[tauri::command]
-fn apply_colors(state: State<SharedState>) -> Result<(), String> {
-    let st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        apply_all_to_device(dev, &st.keys);
+/// Move a key to its next slot and show it (fading when `fade_ms` is set).
+/// Returns the slot it left.
+fn advance_key_slot<R: Runtime>(app: &AppHandle<R>, st: &mut AppState, key_index: usize) -> ColorSlot {
+    let old = st.keys[key_index].active().clone();
+    st.keys[key_index].cycle_slot();
+    fade::transition(app, st, key_index, old.color);
+    if st.keys[key_index].active_slot != 0 {
+        reset_group_peers(app, st, key_index);
     }
-    Ok(())
+    old
 }
 
-#[tauri::command]
-fn disable_all_overrides(state: State<SharedState>) -> Result<(), String> {
-    let st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        for i in 0..8u8 {
-            dev.disable_override(i).map_err(|e| e.to_string())?;
+/// Send the keys grouped with `key_index` back to their first slot. Keys
+/// bound to a condition are left to it.
+fn reset_group_peers<R: Runtime>(app: &AppHandle<R>, st: &mut AppState, key_index: usize) {
+    for peer in st.group_peers(key_index) {
+        let key = &mut st.keys[peer];
+        if key.active_slot == 0 || key.binding.is_some() {
+            continue;
         }
+        let from = key.active().color;
+        key.active_slot = 0;
+        info!("[group] key={} → {:?} (key {} selected)", peer, st.keys[peer].active().name, key_index);
+        fade::transition(app, st, peer, from);
     }
-    Ok(())
 }
 
-#[tauri::command]
-fn get_keymap(state: State<SharedState>) -> Result<Vec<u16>, String> {
-    let mut st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        match dev.read_all_keycodes() {
-            Ok(keymaps) => {
-                st.keymaps = keymaps;
-                Ok(keymaps.to_vec())
-            }
-            Err(e) => Err(e.to_string()),
-        }
+/// Read `text` out if accessibility cues are on: an `announce` event the
+/// frontend reads out through its live region, or the OS speech synthesizer
+/// while the window is in the tray (speech.rs).
+fn announce(app: &AppHandle, st: &AppState, text: String) {
+    if !st.settings.accessibility_cues {
+        return;
+    }
+    let visible = app.get_webview_window("main").is_some_and(|w| w.is_visible().unwrap_or(false));
+    if visible {
+        let _ = app.emit("announce", text);
     } else {
-        Ok(st.keymaps.to_vec())
+        speech::say(text);
     }
 }
 
-#[tauri::command]
-fn set_keycode(
-    app: AppHandle,
-    state: State<SharedState>,
-    key_index: usize,
-    keycode: u16,
-) -> Result<(), String> {
-    let keymaps_copy;
-    {
-        let mut st = state.lock().unwrap();
-        if key_index >= 8 {
-            return Err("key_index out of range".into());
-        }
-        let (row, col) = protocol::key_index_to_matrix(key_index as u8);
-        if let Some(ref dev) = st.device {
-            dev.set_keycode(0, row, col, keycode)
-                .map_err(|e| e.to_string())?;
-        }
-        st.keymaps[key_index] = keycode;
-        keymaps_copy = st.keymaps;
+/// The key's name as announced: the label without its icon (emoji read
+/// aloud are noise), else its number.
+fn spoken_key_name(st: &AppState, key_index: usize) -> String {
+    st.keys[key_index].label.clone().unwrap_or_else(|| i18n::t_with("key-name", &[("n", &(key_index + 1))]))
+}
+
+/// Accessibility feedback for a key that just changed slot: an earcon and
+/// the new slot read out.
+fn announce_slot(app: &AppHandle, st: &AppState, key_index: usize) {
+    if !st.settings.accessibility_cues {
+        return;
     }
-    // Re-register shortcuts with updated keymaps
-    register_key_shortcuts(&app, &keymaps_copy);
-    Ok(())
+    let key = &st.keys[key_index];
+    audio::play_earcon(key.active_slot);
+    let name = spoken_key_name(st, key_index);
+    announce(app, st, i18n::t_with("slot-announcement", &[("key", &name), ("slot", &key.active().name)]));
+}
+
+/// Read out an action a key press ran, such as a followed app's toggle.
+fn announce_action(app: &AppHandle, st: &AppState, key_index: usize, words: &str) {
+    let name = spoken_key_name(st, key_index);
+    announce(app, st, i18n::t_with("action-announcement", &[("key", &name), ("action", &words)]));
 }
 
+/// Words for a `toggle_followed_app` action id.
+fn action_words(action: &str) -> String {
+    if let Some(scene) = action.strip_prefix("obs:scene:") {
+        return i18n::t_with("action-scene", &[("scene", &scene)]);
+    }
+    match action.split(':').collect::<Vec<_>>().as_slice() {
+        ["obs", "toggle-record"] => i18n::t("action-toggle-recording"),
+        ["obs", "toggle-stream"] => i18n::t("action-toggle-stream"),
+        [.., "toggle-mute"] => i18n::t("action-toggle-mute"),
+        ["media", "play-pause"] => i18n::t("action-play-pause"),
+        ["focus", "toggle"] => i18n::t("action-toggle-focus"),
+        ["soundboard", "toggle"] => i18n::t("action-toggle-soundboard"),
+        ["calendar", "open-link"] => i18n::t("action-open-meeting"),
+        ["mode", "cycle"] => i18n::t("action-next-mode"),
+        _ => action.to_string(),
+    }
+}
+
+/// Advance one key to its next slot.
 #[tauri::command]
-fn set_key_override(
+fn toggle_key_slot(
+    app: AppHandle,
     state: State<SharedState>,
     key_index: usize,
-    enabled: bool,
-) -> Result<StateSnapshot, String> {
+) -> Result<StateSnapshot, HubError> {
     let mut st = state.lock().unwrap();
     if key_index >= 8 {
-        return Err("key_index out of range".into());
+        return Err(i18n::t("error-key-index").into());
     }
-    st.keys[key_index].override_enabled = enabled;
-    if let Some(ref dev) = st.device {
-        apply_key_to_device(dev, key_index as u8, &st.keys[key_index]);
-        // Persist per-key overrides to device EEPROM
-        let _ = dev.custom_save();
-    }
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
+    let old = advance_key_slot(&app, &mut st, key_index);
+    info!("[PER-KEY TOGGLE] key={} {:?}→{:?} override={}",
+          key_index, old.name, st.keys[key_index].active().name, st.keys[key_index].override_enabled);
+    announce_slot(&app, &st, key_index);
+    persist_state(&st);
     Ok(st.snapshot())
 }
 
-#[tauri::command]
-fn restore_defaults(state: State<SharedState>) -> Result<StateSnapshot, String> {
+/// Run `edit` on one key's slots, then push the key to the device and persist.
+fn edit_key_slots(
+    app: &AppHandle,
+    state: &SharedState,
+    key_index: usize,
+    edit: impl FnOnce(&mut KeyConfig) -> Result<(), String>,
+) -> Result<StateSnapshot, HubError> {
     let mut st = state.lock().unwrap();
-    st.keys = std::array::from_fn(|_| KeyConfig::default());
-    if let Some(ref dev) = st.device {
-        apply_all_to_device(dev, &st.keys);
-        let _ = dev.custom_save();
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
+    }
+    let from = st.keys[key_index].active().color;
+    edit(&mut st.keys[key_index])?;
+    fade::transition(app, &st, key_index, from);
+    if let Some(dev) = st.device.as_ref().filter(|_| st.keys[key_index].active().color != from) {
+        dev.note_color_edit();
     }
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
+    persist_state(&st);
     Ok(st.snapshot())
 }
 
-// ── Device info & control commands ───────────────────────────────────────
-
 #[tauri::command]
-fn get_device_info(state: State<SharedState>) -> Result<DeviceInfo, String> {
-    let mut st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        let info = dev.get_device_info().map_err(|e| e.to_string())?;
-        st.device_info = Some(info.clone());
-        Ok(info)
-    } else {
-        st.device_info.clone().ok_or_else(|| "Not connected".into())
-    }
+fn add_key_slot(
+    app: AppHandle,
+    state: State<SharedState>,
+    key_index: usize,
+    name: String,
+    h: u8,
+    s: u8,
+    v: u8,
+) -> Result<StateSnapshot, HubError> {
+    edit_key_slots(&app, &state, key_index, |key| key.add_slot(&name, protocol::HsvColor { h, s, v }))
 }
 
 #[tauri::command]
-fn device_indication(state: State<SharedState>) -> Result<(), String> {
-    let st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        dev.device_indication().map_err(|e| e.to_string())
-    } else {
-        Err("Not connected".into())
-    }
+fn remove_key_slot(
+    app: AppHandle,
+    state: State<SharedState>,
+    key_index: usize,
+    name: String,
+) -> Result<StateSnapshot, HubError> {
+    edit_key_slots(&app, &state, key_index, |key| key.remove_slot(&name))
 }
 
 #[tauri::command]
-fn bootloader_jump(state: State<SharedState>) -> Result<(), String> {
-    let mut st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        let _ = dev.bootloader_jump();
-    }
-    st.device = None;
-    st.device_info = None;
-    st.rgb_matrix = None;
-    Ok(())
+fn rename_key_slot(
+    app: AppHandle,
+    state: State<SharedState>,
+    key_index: usize,
+    name: String,
+    new_name: String,
+) -> Result<StateSnapshot, HubError> {
+    edit_key_slots(&app, &state, key_index, |key| key.rename_slot(&name, &new_name))
 }
 
+/// Bind a key's active slot to a watched condition (None to unbind).
 #[tauri::command]
-fn eeprom_reset(state: State<SharedState>) -> Result<(), String> {
-    let st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        dev.eeprom_reset().map_err(|e| e.to_string())
-    } else {
-        Err("Not connected".into())
-    }
+fn set_key_binding(
+    app: AppHandle,
+    state: State<SharedState>,
+    key_index: usize,
+    binding: Option<SlotBinding>,
+) -> Result<StateSnapshot, HubError> {
+    edit_key_slots(&app, &state, key_index, |key| key.set_binding(binding))
 }
 
+/// Make the slot called `name` active on one key.
 #[tauri::command]
-fn dynamic_keymap_reset(state: State<SharedState>) -> Result<(), String> {
-    let mut st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        dev.dynamic_keymap_reset().map_err(|e| e.to_string())?;
-        match dev.read_all_keycodes() {
-            Ok(keymaps) => st.keymaps = keymaps,
-            Err(e) => error!("Failed to re-read keymaps after reset: {e:#}"),
-        }
-        Ok(())
-    } else {
-        Err("Not connected".into())
-    }
+fn select_key_slot(
+    app: AppHandle,
+    state: State<SharedState>,
+    key_index: usize,
+    name: String,
+) -> Result<StateSnapshot, HubError> {
+    edit_key_slots(&app, &state, key_index, |key| key.select_slot(&name))
 }
 
+/// Copy a key's configuration (slots, override, binding, source, cooldown).
 #[tauri::command]
-fn macro_reset(state: State<SharedState>) -> Result<(), String> {
-    let st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        dev.macro_reset().map_err(|e| e.to_string())
-    } else {
-        Err("Not connected".into())
+fn copy_key_config(state: State<SharedState>, src: usize) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    if src >= 8 {
+        return Err(i18n::t("error-key-index").into());
     }
+    st.key_clipboard = Some((src, st.keys[src].clone()));
+    Ok(st.snapshot())
 }
 
-// ── RGB Matrix commands ─────────────────────────────────────────────────
-
+/// Overwrite a key with the configuration last copied by `copy_key_config`.
 #[tauri::command]
-fn get_rgb_matrix(state: State<SharedState>) -> Result<RgbMatrixState, String> {
-    let mut st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        let rgb = dev.rgb_get_state().map_err(|e| e.to_string())?;
-        st.rgb_matrix = Some(rgb);
-        Ok(rgb)
-    } else {
-        st.rgb_matrix.ok_or_else(|| "Not connected".into())
+fn paste_key_config(app: AppHandle, state: State<SharedState>, dst: usize) -> Result<StateSnapshot, HubError> {
+    let (config, old_source) = {
+        let st = state.lock().unwrap();
+        let (_, config) = st.key_clipboard.clone().ok_or_else(|| i18n::t("error-nothing-copied"))?;
+        (config, st.keys.get(dst).map(|k| k.source))
+    };
+    let new_source = config.source;
+    // Labels and icons name the physical key, so the target keeps its own
+    let snapshot = edit_key_slots(&app, &state, dst, |key| {
+        *key = KeyConfig { label: key.label.take(), icon: key.icon.take(), ..config };
+        Ok(())
+    })?;
+    if old_source != Some(new_source) {
+        let keymaps = state.lock().unwrap().keymaps;
+        register_key_shortcuts(&app, &keymaps);
     }
+    Ok(snapshot)
 }
 
+/// Set one color on every key: the slot called `slot` where a key has one, or
+/// all of its slots when `slot` is None. Turns override on and pushes all keys
+/// in one batch; if that fails nothing changes.
 #[tauri::command]
-fn set_rgb_brightness(state: State<SharedState>, value: u8) -> Result<(), String> {
+fn apply_color_to_all(
+    state: State<SharedState>,
+    slot: Option<String>,
+    h: u8,
+    s: u8,
+    v: u8,
+) -> Result<StateSnapshot, HubError> {
     let mut st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        dev.rgb_set_brightness(value).map_err(|e| e.to_string())?;
-        if let Some(ref mut rgb) = st.rgb_matrix {
-            rgb.brightness = value;
+    let color = protocol::HsvColor { h, s, v };
+    let mut keys = st.keys.clone();
+    let mut touched = Vec::new();
+    for (i, key) in keys.iter_mut().enumerate() {
+        match slot {
+            Some(ref name) => {
+                let Some(idx) = key.slot_index(name) else { continue };
+                key.slots[idx].color = color;
+                key.active_slot = idx;
+            }
+            None => key.slots.iter_mut().for_each(|sl| sl.color = color),
         }
-        Ok(())
-    } else {
-        Err("Not connected".into())
+        key.override_enabled = true;
+        touched.push(i);
+    }
+    if touched.is_empty() {
+        return Err(i18n::t_with("error-no-key-slot", &[("slot", &slot.unwrap_or_default())]).into());
     }
+    let batch: Vec<_> = touched
+        .iter()
+        .filter(|&&i| !key_covered(i))
+        .map(|&i| (i as u8, brightness::key_color(&color)))
+        .collect();
+    if let Some(dev) = st.device.as_ref().filter(|_| !batch.is_empty()) {
+        dev.set_key_colors(&batch).map_err(HubError::from)?;
+        let _ = dev.custom_save();
+    }
+    touched.into_iter().for_each(fade::cancel);
+    st.keys = keys;
+    persist_state(&st);
+    Ok(st.snapshot())
 }
 
 #[tauri::command]
-fn set_rgb_effect(state: State<SharedState>, value: u8) -> Result<(), String> {
-    let mut st = state.lock().unwrap();
+fn apply_colors(state: State<SharedState>) -> Result<(), HubError> {
+    let st = state.lock().unwrap();
     if let Some(ref dev) = st.device {
-        dev.rgb_set_effect(value).map_err(|e| e.to_string())?;
-        if let Some(ref mut rgb) = st.rgb_matrix {
-            rgb.effect = value;
-        }
-        Ok(())
-    } else {
-        Err("Not connected".into())
+        apply_all_to_device(dev, &st.keys);
     }
+    Ok(())
 }
 
 #[tauri::command]
-fn set_rgb_speed(state: State<SharedState>, value: u8) -> Result<(), String> {
-    let mut st = state.lock().unwrap();
+fn disable_all_overrides(state: State<SharedState>) -> Result<(), HubError> {
+    let st = state.lock().unwrap();
     if let Some(ref dev) = st.device {
-        dev.rgb_set_speed(value).map_err(|e| e.to_string())?;
-        if let Some(ref mut rgb) = st.rgb_matrix {
-            rgb.speed = value;
+        for i in 0..8u8 {
+            dev.disable_override(i).map_err(HubError::from)?;
         }
-        Ok(())
-    } else {
-        Err("Not connected".into())
     }
+    Ok(())
 }
 
 #[tauri::command]
-fn set_rgb_color(state: State<SharedState>, h: u8, s: u8) -> Result<(), String> {
+fn get_keymap(state: State<SharedState>) -> Result<Vec<u16>, HubError> {
     let mut st = state.lock().unwrap();
     if let Some(ref dev) = st.device {
-        dev.rgb_set_color(h, s).map_err(|e| e.to_string())?;
-        if let Some(ref mut rgb) = st.rgb_matrix {
-            rgb.color_h = h;
-            rgb.color_s = s;
+        match dev.read_all_keycodes() {
+            Ok(keymaps) => {
+                st.keymaps = keymaps;
+                Ok(keymaps.to_vec())
+            }
+            Err(e) => Err(e.into()),
         }
-        Ok(())
     } else {
-        Err("Not connected".into())
+        Ok(st.keymaps.to_vec())
     }
 }
 
 #[tauri::command]
-fn save_custom(state: State<SharedState>) -> Result<(), String> {
-    let st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        dev.custom_save().map_err(|e| e.to_string())
-    } else {
-        Err("Not connected".into())
-    }
+fn set_keycode(
+    app: AppHandle,
+    key_index: usize,
+    keycode: u16,
+) -> Result<(), HubError> {
+    write_keycode(&app, key_index, keycode)
 }
 
-#[tauri::command]
-fn save_rgb_matrix(state: State<SharedState>) -> Result<(), String> {
-    let st = state.lock().unwrap();
-    if let Some(ref dev) = st.device {
-        dev.rgb_save().map_err(|e| e.to_string())
-    } else {
-        Err("Not connected".into())
+/// Write a keycode to the device and the local keymap, then re-register shortcuts.
+fn write_keycode(app: &AppHandle, key_index: usize, keycode: u16) -> Result<(), HubError> {
+    let state = app.state::<SharedState>();
+    let keymaps_copy;
+    {
+        let mut st = state.lock().unwrap();
+        if key_index >= 8 {
+            return Err(i18n::t("error-key-index").into());
+        }
+        let previous = st.keymaps[key_index];
+        write_keymaps(&mut st, &[(key_index as u8, previous, keycode)])?;
+        st.keymaps[key_index] = keycode;
+        // An explicit bind also replaces the pending restore value for that key
+        if let Some(ref mut known) = st.keymap_restore {
+            known[key_index] = keycode;
+        }
+        persist_state(&st);
+        keymaps_copy = st.keymaps;
     }
+    // Re-register shortcuts with updated keymaps
+    register_key_shortcuts(app, &keymaps_copy);
+    Ok(())
 }
 
-// ── Soundboard commands ──────────────────────────────────────────────────
+/// Write `(keymap index, previous, new)` keycodes to the device, verified and
+/// rolled back as a batch (`Deck8Device::write_keymaps`). No-op when
+/// disconnected. Keys that couldn't be rolled back are re-read into
+/// `st.keymaps` so it matches the device again.
+fn write_keymaps(st: &mut AppState, writes: &[(u8, u16, u16)]) -> Result<(), HubError> {
+    let Some(ref dev) = st.device else { return Ok(()) };
+    let err = match dev.write_keymaps(writes) {
+        Ok(()) => return Ok(()),
+        Err(e) => HubError::from(e),
+    };
+    if let HubError::KeymapWrite { ref stranded, .. } = err {
+        for &key in stranded {
+            let (row, col) = protocol::key_index_to_matrix(key);
+            match dev.get_keycode(0, row, col) {
+                Ok(kc) => st.keymaps[key as usize] = kc,
+                Err(e) => error!("[keymap] re-read key={} FAILED: {:#}", key, e),
+            }
+        }
+    }
+    error!("[keymap] {}", err);
+    Err(err)
+}
 
+/// Replay the last-known keymaps onto the device ("restore my binds").
 #[tauri::command]
-fn list_audio_devices() -> audio::AudioDeviceList {
-    audio::list_devices()
+fn restore_keymaps(app: AppHandle, state: State<SharedState>) -> Result<StateSnapshot, HubError> {
+    let (snapshot, keymaps) = {
+        let mut st = state.lock().unwrap();
+        let known = st.keymap_restore.ok_or_else(|| i18n::t("error-no-keymap-backup"))?;
+        if st.device.is_none() {
+            return Err(HubError::NotConnected);
+        }
+        let writes: Vec<_> = (0..8)
+            .filter(|&i| st.keymaps[i] != known[i])
+            .map(|i| (i as u8, st.keymaps[i], known[i]))
+            .collect();
+        write_keymaps(&mut st, &writes)?;
+        info!("[keymap] restored {:?}", known.iter().map(|k| format!("0x{:04X}", k)).collect::<Vec<_>>());
+        st.keymaps = known;
+        st.keymap_restore = None;
+        persist_state(&st);
+        (st.snapshot(), st.keymaps)
+    };
+    register_key_shortcuts(&app, &keymaps);
+    Ok(snapshot)
 }
 
-/// Check if a device name looks like a virtual audio cable.
-fn is_virtual_cable(name: &str) -> bool {
-    let lower = name.to_lowercase();
-    lower.contains("cable") || lower.contains("blackhole") || lower.contains("virtual")
+/// Keep the device's keymaps and forget the last-known ones.
+#[tauri::command]
+fn keep_device_keymaps(state: State<SharedState>) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    st.keymap_restore = None;
+    persist_state(&st);
+    Ok(st.snapshot())
 }
 
-/// Try to (re)start the audio pipeline if both input and output devices are configured.
-/// Only starts if the output device looks like a virtual cable (to avoid echo).
-/// Stops any existing pipeline first. Silently does nothing if devices aren't set.
-fn try_auto_start_pipeline(
-    state: &State<SharedState>,
-    pipeline_state: &State<ManagedAudioPipeline>,
-) {
-    // Stop existing pipeline
-    {
-        let mut pl = pipeline_state.0.lock().unwrap();
-        if pl.is_some() {
-            *pl = None;
-            info!("[audio] Pipeline stopped (restart)");
-        }
-    }
-
-    let st = state.lock().unwrap();
-    let input = match st.audio_config.audio_input_device.as_deref() {
-        Some(s) => s.to_string(),
-        None => return,
-    };
-    let output = match st.audio_config.audio_output_device.as_deref() {
-        Some(s) => s.to_string(),
-        None => return,
-    };
+/// How long `start_keycode_capture` waits for a chord.
+const CAPTURE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
-    // Only start pipeline if output is a virtual cable — otherwise mic audio
-    // would loop back to the user's own speakers/headphones causing echo.
-    if !is_virtual_cable(&output) {
-        info!("[audio] Skipping pipeline auto-start: output \"{}\" is not a virtual cable", output);
-        return;
+/// Bind the next chord pressed on any keyboard to a key (keymap index).
+/// The result arrives as a `keycode-captured` event; Escape or the timeout
+/// cancel. Windows only (needs the LL hook).
+#[tauri::command]
+fn start_keycode_capture(app: AppHandle, key_index: usize) -> Result<(), HubError> {
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
     }
+    let app_clone = app.clone();
+    let id = keyboard_hook::start_capture(Box::new(move |keycode| {
+        let mut error = None;
+        if let Some(kc) = keycode {
+            info!("[capture] key={} ← 0x{:04X}", key_index, kc);
+            match write_keycode(&app_clone, key_index, kc) {
+                Ok(()) => {
+                    let snapshot = app_clone.state::<SharedState>().lock().unwrap().snapshot();
+                    let _ = app_clone.emit("state-updated", &snapshot);
+                }
+                Err(e) => error = Some(e.to_string()),
+            }
+        }
+        let _ = app_clone.emit("keycode-captured", KeycodeCaptured { key_index, keycode, error });
+    }))
+    .ok_or_else(|| i18n::t("error-capture-unsupported"))?;
 
-    let mic_vol = st.audio_config.mic_volume;
-    let sound_vol = st.audio_config.sound_volume;
-    drop(st);
+    std::thread::spawn(move || {
+        std::thread::sleep(CAPTURE_TIMEOUT);
+        keyboard_hook::cancel_capture(Some(id));
+    });
+    Ok(())
+}
 
-    match audio::AudioPipeline::start(&input, &output, mic_vol, sound_vol) {
-        Ok(pipeline) => {
-            let mut pl = pipeline_state.0.lock().unwrap();
-            *pl = Some(pipeline);
-            let mut st = state.lock().unwrap();
-            st.audio_config.soundboard_enabled = true;
-            persist_state(&st.keys, &st.audio_config, &st.keymaps);
-        }
-        Err(e) => {
-            warn!("[audio] Auto-start pipeline failed: {}", e);
-        }
-    }
+#[tauri::command]
+fn cancel_keycode_capture() {
+    keyboard_hook::cancel_capture(None);
 }
 
 #[tauri::command]
-fn set_audio_input_device(
+fn set_key_override(
     state: State<SharedState>,
-    pipeline_state: State<ManagedAudioPipeline>,
-    name: String,
-) -> Result<(), String> {
-    {
-        let mut st = state.lock().unwrap();
-        st.audio_config.audio_input_device = Some(name);
-        persist_state(&st.keys, &st.audio_config, &st.keymaps);
+    key_index: usize,
+    enabled: bool,
+) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
     }
-    try_auto_start_pipeline(&state, &pipeline_state);
-    Ok(())
+    st.keys[key_index].override_enabled = enabled;
+    if let Some(ref dev) = st.device {
+        apply_key_to_device(dev, key_index as u8, &st.keys[key_index]);
+        // Persist per-key overrides to device EEPROM
+        let _ = dev.custom_save();
+    }
+    persist_state(&st);
+    Ok(st.snapshot())
 }
 
+/// Choose whether a key's shortcut reacts to any keyboard or only the Deck-8.
 #[tauri::command]
-fn set_audio_output_device(
+fn set_key_source(
+    app: AppHandle,
     state: State<SharedState>,
-    pipeline_state: State<ManagedAudioPipeline>,
-    name: String,
-) -> Result<(), String> {
-    {
+    key_index: usize,
+    source: KeySource,
+) -> Result<StateSnapshot, HubError> {
+    let (snapshot, keymaps) = {
         let mut st = state.lock().unwrap();
-        st.audio_config.audio_output_device = Some(name);
-        persist_state(&st.keys, &st.audio_config, &st.keymaps);
-    }
-    try_auto_start_pipeline(&state, &pipeline_state);
-    Ok(())
+        if key_index >= 8 {
+            return Err(i18n::t("error-key-index").into());
+        }
+        st.keys[key_index].source = source;
+        persist_state(&st);
+        (st.snapshot(), st.keymaps)
+    };
+    register_key_shortcuts(&app, &keymaps);
+    Ok(snapshot)
 }
 
+/// Minimum time between two actions of a key (0 disables the cooldown).
 #[tauri::command]
-fn add_to_sound_library(
+fn set_key_cooldown(
     state: State<SharedState>,
-    file_path: String,
-    display_name: String,
-) -> Result<SoundEntry, String> {
-    let entry = audio::import_to_library(&file_path, &display_name)
-        .map_err(|e| e.to_string())?;
+    key_index: usize,
+    cooldown_ms: u64,
+) -> Result<StateSnapshot, HubError> {
     let mut st = state.lock().unwrap();
-    st.audio_config.sound_library.push(entry.clone());
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
-    Ok(entry)
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
+    }
+    st.keys[key_index].cooldown_ms = cooldown_ms;
+    persist_state(&st);
+    Ok(st.snapshot())
 }
 
+/// Minimum time between two plays of a key's sound (0 disables it), and
+/// whether the LED dims until the sound is ready again.
 #[tauri::command]
-fn add_to_sound_library_trimmed(
+fn set_key_sound_cooldown(
     state: State<SharedState>,
-    file_path: String,
-    display_name: String,
-    start_ms: u64,
-    end_ms: u64,
-) -> Result<SoundEntry, String> {
-    let entry = audio::import_to_library_trimmed(&file_path, &display_name, start_ms, end_ms)
-        .map_err(|e| e.to_string())?;
-    let mut st = state.lock().unwrap();
-    st.audio_config.sound_library.push(entry.clone());
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
-    Ok(entry)
+    key_index: usize,
+    cooldown_ms: u64,
+    dim: bool,
+) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
+    }
+    let key = &mut st.keys[key_index];
+    key.sound_cooldown_ms = cooldown_ms;
+    key.dim_on_cooldown = dim;
+    persist_state(&st);
+    Ok(st.snapshot())
 }
 
+/// Play the key's sound once per press, or only while the key is held.
 #[tauri::command]
-fn remove_from_sound_library(
+fn set_key_sound_mode(
     state: State<SharedState>,
-    sound_id: String,
-) -> Result<(), String> {
+    key_index: usize,
+    mode: state::SoundMode,
+) -> Result<StateSnapshot, HubError> {
     let mut st = state.lock().unwrap();
-    // Find and remove the entry
-    if let Some(pos) = st.audio_config.sound_library.iter().position(|e| e.id == sound_id) {
-        let entry = st.audio_config.sound_library.remove(pos);
-        let _ = audio::delete_sound(&entry.filename);
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
     }
-    // Clear any key_sounds referencing this id
-    for slot in st.audio_config.key_sounds.iter_mut() {
-        if slot.as_deref() == Some(sound_id.as_str()) {
-            *slot = None;
+    st.keys[key_index].sound_mode = mode;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Whether a press changes the key's color, runs its action, or both.
+#[tauri::command]
+fn set_key_press_mode(
+    state: State<SharedState>,
+    key_index: usize,
+    mode: state::PressMode,
+) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
+    }
+    st.keys[key_index].press_mode = mode;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Recall a lighting scene on every press of the key (None clears it).
+#[tauri::command]
+fn set_key_light_scene(
+    state: State<SharedState>,
+    key_index: usize,
+    scene: Option<state::LightScene>,
+) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
+    }
+    if let Some(scene) = &scene {
+        if scene.scene.trim().is_empty() {
+            return Err(i18n::t("error-scene-empty").into());
+        }
+        if lighting::scene_target(&st, scene).is_none() {
+            return Err(i18n::t_with("error-no-light", &[("name", &scene.target)]).into());
+        }
+    }
+    st.keys[key_index].light_scene = scene;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Make the key's sound louder or quieter than the sound volume, in dB
+/// (clamped to -30..=+12).
+#[tauri::command]
+fn set_key_sound_gain(
+    state: State<SharedState>,
+    key_index: usize,
+    gain_db: f32,
+) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
+    }
+    if !gain_db.is_finite() {
+        return Err(i18n::t("error-gain-not-number").into());
+    }
+    st.audio_config.key_sound_gain_db[key_index] = gain_db.clamp(-30.0, 12.0);
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Name a key (None or blank clears it). Shown in the tray's Keys submenu.
+#[tauri::command]
+fn set_key_label(
+    app: AppHandle,
+    state: State<SharedState>,
+    key_index: usize,
+    label: Option<String>,
+) -> Result<StateSnapshot, HubError> {
+    edit_key_meta(&app, &state, key_index, |key| key.set_label(label))
+}
+
+/// Give a key a short icon (emoji/symbol; None or blank clears it).
+#[tauri::command]
+fn set_key_icon(
+    app: AppHandle,
+    state: State<SharedState>,
+    key_index: usize,
+    icon: Option<String>,
+) -> Result<StateSnapshot, HubError> {
+    edit_key_meta(&app, &state, key_index, |key| key.set_icon(icon))
+}
+
+/// Edit a key's label/icon, persist, and rebuild the tray menu that shows them.
+fn edit_key_meta(
+    app: &AppHandle,
+    state: &SharedState,
+    key_index: usize,
+    edit: impl FnOnce(&mut KeyConfig) -> Result<(), String>,
+) -> Result<StateSnapshot, HubError> {
+    let snapshot = {
+        let mut st = state.lock().unwrap();
+        if key_index >= 8 {
+            return Err(i18n::t("error-key-index").into());
+        }
+        edit(&mut st.keys[key_index])?;
+        persist_state(&st);
+        st.snapshot()
+    };
+    refresh_tray_menu(app);
+    Ok(snapshot)
+}
+
+#[tauri::command]
+fn restore_defaults(app: AppHandle, state: State<SharedState>) -> Result<StateSnapshot, HubError> {
+    let snapshot = {
+        let mut st = state.lock().unwrap();
+        st.keys = std::array::from_fn(|_| KeyConfig::default());
+        st.settings.applied_accent = None;
+        appearance::sync_accent(&mut st, appearance::current().accent);
+        if let Some(ref dev) = st.device {
+            apply_all_to_device(dev, &st.keys);
+            let _ = dev.custom_save();
+        }
+        persist_state(&st);
+        st.snapshot()
+    };
+    refresh_tray_menu(&app);
+    Ok(snapshot)
+}
+
+// ── Palette & theme commands ─────────────────────────────────────────────
+
+/// Upper bounds so the swatch strip and theme list stay manageable.
+const MAX_SWATCHES: usize = 32;
+const MAX_THEMES: usize = 32;
+
+#[tauri::command]
+fn add_swatch(state: State<SharedState>, h: u8, s: u8, v: u8) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    let color = protocol::HsvColor { h, s, v };
+    if !st.palette.contains(&color) {
+        if st.palette.len() >= MAX_SWATCHES {
+            return Err(i18n::t_with("error-palette-full", &[("max", &MAX_SWATCHES)]).into());
+        }
+        st.palette.push(color);
+        persist_state(&st);
+    }
+    Ok(st.snapshot())
+}
+
+#[tauri::command]
+fn remove_swatch(state: State<SharedState>, index: usize) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    if index >= st.palette.len() {
+        return Err(i18n::t("error-swatch-index").into());
+    }
+    st.palette.remove(index);
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Save every key's active color as a theme (replacing one with the same name).
+#[tauri::command]
+fn save_theme(state: State<SharedState>, name: String) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(i18n::t("error-theme-name-empty").into());
+    }
+    let colors = std::array::from_fn(|i| st.keys[i].active().color);
+    if let Some(theme) = st.themes.iter_mut().find(|t| t.name == name) {
+        theme.colors = colors;
+    } else if st.themes.len() >= MAX_THEMES {
+        return Err(i18n::t_with("error-too-many-themes", &[("max", &MAX_THEMES)]).into());
+    } else {
+        st.themes.push(Theme { name, colors });
+    }
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Write a theme into every key's active slot. The device is written first
+/// (see transaction.rs); if that fails it's put back and nothing changes.
+#[tauri::command]
+fn apply_theme(app: AppHandle, state: State<SharedState>, name: String) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    let colors = st.themes.iter().find(|t| t.name == name)
+        .ok_or_else(|| i18n::t_with("error-no-theme", &[("name", &name)]))?
+        .colors;
+    preview::end(&app, &st);
+    let mut keys = st.keys.clone();
+    for (i, key) in keys.iter_mut().enumerate() {
+        let active = key.active_slot;
+        key.slots[active].color = colors[i];
+        key.override_enabled = true;
+    }
+    if let Some(dev) = st.device.as_ref().filter(|d| !dnd_active() && d.capabilities().per_key_override) {
+        transaction::DeviceTransaction::new().keys(&st.keys, &keys).commit(dev).map_err(HubError::from)?;
+        let _ = dev.custom_save();
+    }
+    (0..keys.len()).for_each(fade::cancel);
+    st.keys = keys;
+    info!("[theme] applied {:?}", name);
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Show a saved theme on the device for `seconds` (default 5, at most 60)
+/// without applying it; the keys' own colors come back after. Nothing is
+/// saved and keymaps aren't touched. Emits `theme-preview`.
+#[tauri::command]
+fn preview_theme(
+    app: AppHandle,
+    state: State<SharedState>,
+    name: String,
+    seconds: Option<u64>,
+) -> Result<(), HubError> {
+    let st = state.lock().unwrap();
+    let colors = st.themes.iter().find(|t| t.name == name)
+        .ok_or_else(|| i18n::t_with("error-no-theme", &[("name", &name)]))?
+        .colors;
+    preview::show(&app, &st, &name, colors, seconds.unwrap_or(preview::DEFAULT_SECS))
+}
+
+/// End a theme preview early. No-op without one.
+#[tauri::command]
+fn end_theme_preview(app: AppHandle, state: State<SharedState>) {
+    preview::end(&app, &state.lock().unwrap());
+}
+
+#[tauri::command]
+fn delete_theme(state: State<SharedState>, name: String) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    let before = st.themes.len();
+    st.themes.retain(|t| t.name != name);
+    if st.themes.len() == before {
+        return Err(i18n::t_with("error-no-theme", &[("name", &name)]).into());
+    }
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+// ── Key group commands ───────────────────────────────────────────────────
+
+const MAX_GROUPS: usize = 8;
+
+/// Create a key group, or replace the keys of the one with the same name.
+#[tauri::command]
+fn save_key_group(state: State<SharedState>, name: String, keys: Vec<usize>) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(i18n::t("error-group-name-empty").into());
+    }
+    let mut keys = keys;
+    keys.sort_unstable();
+    keys.dedup();
+    if keys.iter().any(|&k| k >= 8) {
+        return Err(i18n::t("error-key-index").into());
+    }
+    if keys.len() < 2 {
+        return Err(i18n::t("error-group-too-small").into());
+    }
+    if let Some(group) = st.groups.iter_mut().find(|g| g.name == name) {
+        group.keys = keys;
+    } else if st.groups.len() >= MAX_GROUPS {
+        return Err(i18n::t_with("error-too-many-groups", &[("max", &MAX_GROUPS)]).into());
+    } else {
+        st.groups.push(KeyGroup { name, keys });
+    }
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+#[tauri::command]
+fn delete_key_group(state: State<SharedState>, name: String) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    let before = st.groups.len();
+    st.groups.retain(|g| g.name != name);
+    if st.groups.len() == before {
+        return Err(i18n::t_with("error-no-group", &[("name", &name)]).into());
+    }
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+// ── Mode commands ────────────────────────────────────────────────────────
+
+const MAX_MODES: usize = 16;
+
+/// Save the keys' active slots as a mode, or replace the one with the same
+/// name. Keys bound to a condition aren't part of it. With a `base`, only the
+/// slots that differ from it are saved.
+#[tauri::command]
+fn save_mode(state: State<SharedState>, name: String, sounds_muted: Option<bool>, base: Option<String>) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(i18n::t("error-mode-name-empty").into());
+    }
+    let current = std::array::from_fn(|i| {
+        let key = &st.keys[i];
+        key.binding.is_none().then(|| key.active().name.clone())
+    });
+    // Only what differs from the base is the mode's own
+    let slots = match base {
+        Some(ref base) => modes::overrides(&st.modes, base, &current)?,
+        None => current,
+    };
+    modes::save(&mut st.modes, modes::Mode { name: name.clone(), slots, sounds_muted, base }, MAX_MODES)?;
+    st.active_mode = Some(name);
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+#[tauri::command]
+fn delete_mode(app: AppHandle, state: State<SharedState>, name: String) -> Result<StateSnapshot, HubError> {
+    let snapshot = {
+        let mut st = state.lock().unwrap();
+        if !modes::remove(&mut st.modes, &name) {
+            return Err(i18n::t_with("error-no-mode", &[("name", &name)]).into());
+        }
+        if st.active_mode.as_ref() == Some(&name) {
+            st.active_mode = None;
+        }
+        persist_state(&st);
+        st.snapshot()
+    };
+    refresh_tray_menu(&app);
+    Ok(snapshot)
+}
+
+/// Switch to a mode by name (UI, tray and mode keys), then tell the UI and
+/// refresh the tray's check marks.
+fn switch_mode(app: &AppHandle, name: &str) -> Result<StateSnapshot, HubError> {
+    let state = app.state::<SharedState>();
+    let (mut snapshot, sounds_muted) = {
+        let mut st = state.lock().unwrap();
+        let sounds_muted = modes::apply(app, &mut st, name)?;
+        info!("[mode] {:?}", name);
+        persist_state(&st);
+        (st.snapshot(), sounds_muted.filter(|&muted| muted != st.audio_config.sounds_muted))
+    };
+    if let Some(muted) = sounds_muted {
+        snapshot = set_sounds_muted_on(app, muted);
+    }
+    refresh_tray_menu(app);
+    let _ = app.emit("state-updated", &snapshot);
+    Ok(snapshot)
+}
+
+#[tauri::command]
+fn set_mode(app: AppHandle, name: String) -> Result<StateSnapshot, HubError> {
+    switch_mode(&app, &name)
+}
+
+/// Switch to the mode after the active one.
+#[tauri::command]
+fn cycle_mode(app: AppHandle) -> Result<StateSnapshot, HubError> {
+    let next = modes::next(&app.state::<SharedState>().lock().unwrap()).map(str::to_string);
+    let next = next.ok_or_else(|| i18n::t("error-no-modes"))?;
+    switch_mode(&app, &next)
+}
+
+const MAX_RULES: usize = 32;
+
+/// Create (empty id) or replace an LED rule, then evaluate the rules. An
+/// edited rule that was holding is undone first, so its new action applies.
+#[tauri::command]
+fn save_rule(app: AppHandle, mut rule: rules::Rule) -> Result<StateSnapshot, HubError> {
+    rule.name = rule.name.trim().to_string();
+    {
+        let state = app.state::<SharedState>();
+        let mut st = state.lock().unwrap();
+        rules::validate(&st, &rule)?;
+        if rule.id.is_empty() {
+            if st.rules.len() >= MAX_RULES {
+                return Err(i18n::t_with("error-too-many-rules", &[("max", &MAX_RULES)]).into());
+            }
+            rule.id = audio::uuid_simple();
+            st.rules.push(rule);
+        } else if let Some(i) = st.rules.iter().position(|r| r.id == rule.id) {
+            rules::end(&app, &mut st, &rule.id);
+            st.rules[i] = rule;
+        } else {
+            return Err(i18n::t("error-no-rule").into());
+        }
+        persist_state(&st);
+    }
+    Ok(rules::refresh(&app))
+}
+
+/// Delete an LED rule, undoing it if it was holding.
+#[tauri::command]
+fn delete_rule(app: AppHandle, rule_id: String) -> Result<StateSnapshot, HubError> {
+    {
+        let state = app.state::<SharedState>();
+        let mut st = state.lock().unwrap();
+        let before = st.rules.len();
+        st.rules.retain(|r| r.id != rule_id);
+        if st.rules.len() == before {
+            return Err(i18n::t("error-no-rule").into());
+        }
+        persist_state(&st);
+    }
+    Ok(rules::refresh(&app))
+}
+
+// ── Device info & control commands ───────────────────────────────────────
+
+/// Device info with a fresh uptime; the static fields come from the cache.
+#[tauri::command]
+fn get_device_info(state: State<SharedState>) -> Result<DeviceInfo, HubError> {
+    let mut st = state.lock().unwrap();
+    let st = &mut *st;
+    if let Some(ref dev) = st.device {
+        let info = match st.device_info {
+            Some(ref mut info) => {
+                info.uptime = dev.get_uptime()?;
+                info.clone()
+            }
+            None => dev.get_device_info(st.device_info_cache.as_ref())?,
+        };
+        st.device_info = Some(info.clone());
+        st.device_info_cache = Some(info.clone());
+        Ok(info)
+    } else {
+        st.device_info.clone().ok_or(HubError::NotConnected)
+    }
+}
+
+/// Uptime samples and reboots seen over the last hour.
+#[tauri::command]
+fn get_device_health() -> heartbeat::DeviceHealth {
+    heartbeat::history()
+}
+
+/// Connects and disconnects with their reasons, and how often it drops.
+#[tauri::command]
+fn get_connection_history() -> connections::ConnectionHistory {
+    connections::history()
+}
+
+#[tauri::command]
+fn device_indication(state: State<SharedState>) -> Result<(), HubError> {
+    let st = state.lock().unwrap();
+    if let Some(ref dev) = st.device {
+        dev.device_indication().map_err(HubError::from)
+    } else {
+        Err(HubError::NotConnected)
+    }
+}
+
+#[tauri::command]
+fn bootloader_jump(state: State<SharedState>) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    if let Some(ref dev) = st.device {
+        let _ = dev.bootloader_jump();
+    }
+    st.device = None;
+    st.device_info = None;
+    // Whatever gets flashed next may report different static info
+    st.device_info_cache = None;
+    st.rgb_matrix = None;
+    st.sync_report = None;
+    Ok(())
+}
+
+/// Read what a reset would erase and get the token that confirms it (see
+/// reset.rs).
+#[tauri::command]
+fn preview_reset(state: State<SharedState>, kind: reset::ResetKind) -> Result<reset::ResetPreview, HubError> {
+    let st = state.lock().unwrap();
+    let dev = st.device.as_ref().ok_or(HubError::NotConnected)?;
+    let snapshot = reset::DeviceSnapshot::read(dev, kind, override_keys(&st))?;
+    Ok(reset::preview(kind, snapshot, now_ms()))
+}
+
+/// LED indices of the keys showing their own color.
+fn override_keys(st: &AppState) -> Vec<usize> {
+    st.keys.iter().enumerate().filter(|(_, k)| k.override_enabled).map(|(i, _)| i).collect()
+}
+
+/// Use up the confirm token, then back up what `kind` is about to erase.
+/// Returns the backup's path.
+fn before_reset(st: &AppState, kind: reset::ResetKind, confirm: &str) -> Result<String, HubError> {
+    let dev = st.device.as_ref().ok_or(HubError::NotConnected)?;
+    reset::confirm(kind, confirm, now_ms())?;
+    let snapshot = reset::DeviceSnapshot::read(dev, kind, override_keys(st))?;
+    let path = reset::backup(kind, &snapshot).map_err(|e| HubError::file("backups", e))?;
+    info!("[reset] {kind:?}: backed up to {}", path.display());
+    Ok(path.display().to_string())
+}
+
+#[tauri::command]
+fn eeprom_reset(state: State<SharedState>, confirm: String) -> Result<String, HubError> {
+    let st = state.lock().unwrap();
+    let backup = before_reset(&st, reset::ResetKind::Eeprom, &confirm)?;
+    if let Some(ref dev) = st.device {
+        dev.eeprom_reset()?;
+    }
+    Ok(backup)
+}
+
+#[tauri::command]
+fn dynamic_keymap_reset(state: State<SharedState>, confirm: String) -> Result<String, HubError> {
+    let mut st = state.lock().unwrap();
+    let backup = before_reset(&st, reset::ResetKind::Keymaps, &confirm)?;
+    if let Some(ref dev) = st.device {
+        dev.dynamic_keymap_reset()?;
+        match dev.read_all_keycodes() {
+            Ok(keymaps) => st.keymaps = keymaps,
+            Err(e) => error!("Failed to re-read keymaps after reset: {e:#}"),
+        }
+    }
+    // The user asked for defaults, so there's nothing to restore
+    st.keymap_restore = None;
+    persist_state(&st);
+    Ok(backup)
+}
+
+#[tauri::command]
+fn macro_reset(state: State<SharedState>, confirm: String) -> Result<String, HubError> {
+    let st = state.lock().unwrap();
+    let backup = before_reset(&st, reset::ResetKind::Macros, &confirm)?;
+    if let Some(ref dev) = st.device {
+        dev.macro_reset()?;
+    }
+    Ok(backup)
+}
+
+// ── RGB Matrix commands ─────────────────────────────────────────────────
+
+#[tauri::command]
+fn get_rgb_matrix(state: State<SharedState>) -> Result<RgbMatrixState, HubError> {
+    let mut st = state.lock().unwrap();
+    if let Some(ref dev) = st.device {
+        let mut rgb = dev.rgb_get_state().map_err(HubError::from)?;
+        rgb.brightness = brightness::invert(rgb.brightness);
+        // The device reads 0 during DND; report the brightness DND will restore
+        if let Some(dnd) = st.dnd {
+            rgb.brightness = dnd.brightness;
+        }
+        st.rgb_matrix = Some(rgb);
+        Ok(rgb)
+    } else {
+        st.rgb_matrix.ok_or(HubError::NotConnected)
+    }
+}
+
+#[tauri::command]
+fn set_rgb_brightness(state: State<SharedState>, value: u8) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    let st = &mut *st;
+    if let Some(ref dev) = st.device {
+        if let Some(ref mut dnd) = st.dnd {
+            // Keep the LEDs dark; take effect when DND ends
+            dnd.brightness = value;
+        } else {
+            dev.rgb_set_brightness(brightness::apply(value)).map_err(HubError::from)?;
+        }
+        if let Some(ref mut rgb) = st.rgb_matrix {
+            rgb.brightness = value;
+        }
+        Ok(())
+    } else {
+        Err(HubError::NotConnected)
+    }
+}
+
+#[tauri::command]
+fn set_rgb_effect(state: State<SharedState>, value: u8) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    if let Some(ref dev) = st.device {
+        dev.rgb_set_effect(value).map_err(HubError::from)?;
+        if let Some(ref mut rgb) = st.rgb_matrix {
+            rgb.effect = value;
+        }
+        Ok(())
+    } else {
+        Err(HubError::NotConnected)
+    }
+}
+
+#[tauri::command]
+fn set_rgb_speed(state: State<SharedState>, value: u8) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    if let Some(ref dev) = st.device {
+        dev.rgb_set_speed(value).map_err(HubError::from)?;
+        if let Some(ref mut rgb) = st.rgb_matrix {
+            rgb.speed = value;
+        }
+        Ok(())
+    } else {
+        Err(HubError::NotConnected)
+    }
+}
+
+#[tauri::command]
+fn set_rgb_color(state: State<SharedState>, h: u8, s: u8) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    if let Some(ref dev) = st.device {
+        dev.rgb_set_color(h, s).map_err(HubError::from)?;
+        if let Some(ref mut rgb) = st.rgb_matrix {
+            rgb.color_h = h;
+            rgb.color_s = s;
+        }
+        Ok(())
+    } else {
+        Err(HubError::NotConnected)
+    }
+}
+
+#[tauri::command]
+fn save_custom(state: State<SharedState>) -> Result<(), HubError> {
+    let st = state.lock().unwrap();
+    if st.dnd.is_some() {
+        // The device's overrides are all off right now; toggle_dnd saves on exit
+        return Ok(());
+    }
+    if let Some(ref dev) = st.device {
+        dev.custom_save().map_err(HubError::from)
+    } else {
+        Err(HubError::NotConnected)
+    }
+}
+
+#[tauri::command]
+fn save_rgb_matrix(state: State<SharedState>) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    if st.device.is_none() {
+        return Err(HubError::NotConnected);
+    }
+    st.saved_rgb = st.rgb_matrix;
+    st.rgb_mismatch = false;
+    persist_state(&st);
+    if let Some(ref mut dnd) = st.dnd {
+        // Saving now would store brightness 0; defer until DND ends
+        dnd.rgb_save_pending = true;
+        return Ok(());
+    }
+    if let Some(ref dev) = st.device {
+        dev.rgb_save().map_err(HubError::from)?;
+    }
+    Ok(())
+}
+
+/// Write the saved RGB settings back to the device (after an EEPROM reset or
+/// a flash) and store them in its EEPROM.
+#[tauri::command]
+fn restore_rgb_matrix(state: State<SharedState>) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    let st = &mut *st;
+    let saved = st.saved_rgb.ok_or_else(|| i18n::t("error-no-saved-rgb"))?;
+    let Some(ref dev) = st.device else {
+        return Err(HubError::NotConnected);
+    };
+    let before = match st.rgb_matrix {
+        Some(rgb) => rgb,
+        None => dev.rgb_get_state().map_err(HubError::from)?,
+    };
+    transaction::DeviceTransaction::new().rgb(before, saved).commit(dev).map_err(HubError::from)?;
+    if let Some(ref mut dnd) = st.dnd {
+        dnd.brightness = saved.brightness;
+        dnd.rgb_save_pending = true;
+    } else {
+        dev.rgb_set_brightness(brightness::apply(saved.brightness)).map_err(HubError::from)?;
+        dev.rgb_save().map_err(HubError::from)?;
+    }
+    info!("[rgb] restored saved settings {:?}", saved);
+    st.rgb_matrix = Some(saved);
+    st.rgb_mismatch = false;
+    Ok(st.snapshot())
+}
+
+/// Keep the device's current RGB settings and make them the saved ones.
+#[tauri::command]
+fn keep_device_rgb_matrix(state: State<SharedState>) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    st.saved_rgb = st.rgb_matrix;
+    st.rgb_mismatch = false;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+// ── Do-not-disturb ───────────────────────────────────────────────────────
+
+/// Turn every LED off (brightness 0, overrides disabled), or put back exactly
+/// what was there. Key configs are left untouched, so edits made during DND
+/// show up on exit.
+#[tauri::command]
+fn toggle_dnd(state: State<SharedState>) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    let st = &mut *st;
+    let Some(ref dev) = st.device else {
+        return Err(HubError::NotConnected);
+    };
+    // The brightness write goes first: if it fails, DND stays as it was
+    match st.dnd {
+        None => {
+            let brightness = match st.rgb_matrix {
+                Some(rgb) => rgb.brightness,
+                None => brightness::invert(dev.rgb_get_state().map_err(HubError::from)?.brightness),
+            };
+            dev.rgb_set_brightness(0).map_err(HubError::from)?;
+            for i in 0..8 {
+                fade::cancel(i);
+                if let Err(e) = dev.disable_override(i as u8) {
+                    error!("[dnd] key={} disable_override FAILED: {:#}", i, e);
+                }
+            }
+            st.dnd = Some(DndState { brightness, rgb_save_pending: false });
+            usage::record_action("dnd");
+            DND_ACTIVE.store(true, std::sync::atomic::Ordering::SeqCst);
+            info!("[dnd] on (brightness {} saved)", brightness);
+        }
+        Some(dnd) => {
+            dev.rgb_set_brightness(brightness::apply(dnd.brightness)).map_err(HubError::from)?;
+            st.dnd = None;
+            DND_ACTIVE.store(false, std::sync::atomic::Ordering::SeqCst);
+            if let Some(ref mut rgb) = st.rgb_matrix {
+                rgb.brightness = dnd.brightness;
+            }
+            if dnd.rgb_save_pending {
+                let _ = dev.rgb_save();
+            }
+            apply_all_to_device(dev, &st.keys);
+            let _ = dev.custom_save();
+            info!("[dnd] off (brightness {} restored)", dnd.brightness);
+        }
+    }
+    Ok(st.snapshot())
+}
+
+// ── Soundboard commands ──────────────────────────────────────────────────
+
+#[tauri::command]
+fn list_audio_devices() -> audio::AudioDeviceList {
+    audio::list_devices()
+}
+
+/// Serializes pipeline restarts and soundboard switches, so two presses of a
+/// soundboard key can't both read "off" and race to start it.
+static SOUNDBOARD_SWITCH: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Try to (re)start the audio pipeline if both input and output devices are configured.
+/// Only starts if the output device looks like a virtual cable (to avoid echo).
+/// Stops any existing pipeline first. Silently does nothing if devices aren't set.
+fn try_auto_start_pipeline<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &State<SharedState>,
+    pipeline_state: &State<ManagedAudioPipeline>,
+) {
+    let _switch = SOUNDBOARD_SWITCH.lock().unwrap();
+    restart_pipeline(app, state, pipeline_state);
+}
+
+/// `try_auto_start_pipeline` with `SOUNDBOARD_SWITCH` already held.
+fn restart_pipeline<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &State<SharedState>,
+    pipeline_state: &State<ManagedAudioPipeline>,
+) {
+    // Stop existing pipeline
+    {
+        let mut pl = pipeline_state.0.lock().unwrap();
+        if pl.is_some() {
+            *pl = None;
+            info!("[audio] Pipeline stopped (restart)");
+        }
+    }
+
+    // Saved now, so every way out below leaves it off on disk too
+    let mut st = state.lock().unwrap();
+    st.audio_config.soundboard_enabled = false;
+    persist_state(&st);
+    if st.audio_config.soundboard_off {
+        info!("[audio] Soundboard is turned off, not starting the pipeline");
+        return;
+    }
+    drop(st);
+    let devices = audio::list_devices();
+    let mut st = state.lock().unwrap();
+    if rematch_saved_devices(&mut st, &devices) {
+        persist_state(&st);
+        let _ = app.emit("state-updated", st.snapshot());
+    }
+    let input = match st.audio_config.audio_input_device.as_deref() {
+        Some(s) => s.to_string(),
+        None => return,
+    };
+    // On Linux the soundboard sink needs no setup, so it's the default output
+    if st.audio_config.audio_output_device.is_none() && virtual_sink::available() {
+        st.audio_config.audio_output_device = Some(virtual_sink::NAME.into());
+        persist_state(&st);
+    }
+    let output = match st.audio_config.audio_output_device.as_deref() {
+        Some(s) => s.to_string(),
+        None => return,
+    };
+
+    // Only start pipeline if output is a virtual cable — otherwise mic audio
+    // would loop back to the user's own speakers/headphones causing echo.
+    if !device_match::is_virtual_cable(&output) {
+        info!("[audio] Skipping pipeline auto-start: output \"{}\" is not a virtual cable", output);
+        return;
+    }
+
+    let mic_vol = st.audio_config.mic_volume;
+    let input_gain_db = st.audio_config.input_gain_db;
+    let sound_vol = st.audio_config.sound_volume;
+    let processing = st.audio_config.processing.clone();
+    let agc = st.audio_config.agc;
+    let second_input = st.audio_config.second_input_device.clone();
+    let second_input_volume = st.audio_config.second_input_volume;
+    let stop_word = st.audio_config.stop_word;
+    drop(st);
+
+    let handle = app.clone();
+    let on_stop_word = Box::new(move || stop_word_heard(&handle));
+    match audio::AudioPipeline::start(&input, &output, mic_vol, sound_vol, &processing, stop_word, on_stop_word) {
+        Ok(pipeline) => {
+            audio::SoundOutput::set_input_gain(&pipeline, input_gain_db);
+            audio::SoundOutput::set_agc(&pipeline, &agc);
+            audio::SoundOutput::set_second_input_volume(&pipeline, second_input_volume);
+            if let Err(e) = audio::SoundOutput::set_second_input(&pipeline, second_input.as_deref()) {
+                warn!("[audio] Second input unavailable: {:#}", e);
+            }
+            let mut pl = pipeline_state.0.lock().unwrap();
+            *pl = Some(Box::new(pipeline));
+            drop(pl);
+            let mut st = state.lock().unwrap();
+            st.audio_config.soundboard_enabled = true;
+            persist_state(&st);
+            drop(st);
+            preload_key_sounds(state, pipeline_state);
+        }
+        Err(e) => {
+            warn!("[audio] Auto-start pipeline failed: {}", e);
+        }
+    }
+}
+
+/// Point saved devices that aren't listed under their name at the one device
+/// that differs only by numbering (see device_match.rs), or ask the user about
+/// the ones that may be it. True if
+/// anything changed.
+fn rematch_saved_devices(st: &mut AppState, devices: &audio::AudioDeviceList) -> bool {
+    let names = |list: &[audio::AudioDeviceInfo]| list.iter().map(|d| d.name.clone()).collect::<Vec<_>>();
+    let inputs = names(&devices.input_devices);
+    let outputs = names(&devices.output_devices);
+    let mut changed = false;
+    for role in [DeviceRole::Input, DeviceRole::Output, DeviceRole::SecondInput] {
+        let available = if role == DeviceRole::Output { &outputs } else { &inputs };
+        let slot = st.audio_config.device_mut(role);
+        let Some(saved) = slot.clone() else { continue };
+        match device_match::find(&saved, available) {
+            DeviceMatch::Renamed(name) => {
+                info!("[audio] Saved device \"{}\" is now \"{}\"", saved, name);
+                *slot = Some(name);
+                changed = true;
+            }
+            DeviceMatch::Ambiguous(candidates) if st.device_choice.is_none() => {
+                info!("[audio] Saved device \"{}\" could be any of {:?}", saved, candidates);
+                st.device_choice = Some(DeviceChoice { role, saved, candidates });
+                changed = true;
+            }
+            _ => {}
+        }
+    }
+    changed
+}
+
+fn soundboard_running<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.state::<ManagedAudioPipeline>().0.lock().unwrap().is_some()
+}
+
+/// Turn the soundboard on (start the pipeline if the devices allow) or off,
+/// remember the choice and tell the UI. Errors if it couldn't be turned on.
+fn set_soundboard_on<R: Runtime>(app: &AppHandle<R>, on: bool) -> Result<StateSnapshot, HubError> {
+    let _switch = SOUNDBOARD_SWITCH.lock().unwrap();
+    switch_soundboard(app, on)
+}
+
+/// Flip the soundboard; the state is read under the same lock as the switch.
+fn toggle_soundboard<R: Runtime>(app: &AppHandle<R>) -> Result<StateSnapshot, HubError> {
+    let _switch = SOUNDBOARD_SWITCH.lock().unwrap();
+    switch_soundboard(app, !soundboard_running(app))
+}
+
+/// `set_soundboard_on` with `SOUNDBOARD_SWITCH` already held.
+fn switch_soundboard<R: Runtime>(app: &AppHandle<R>, on: bool) -> Result<StateSnapshot, HubError> {
+    let state = app.state::<SharedState>();
+    let pipeline_state = app.state::<ManagedAudioPipeline>();
+    {
+        let mut st = state.lock().unwrap();
+        st.audio_config.soundboard_off = !on;
+        persist_state(&st);
+    }
+    restart_pipeline(app, &state, &pipeline_state);
+    info!("[audio] Soundboard turned {}", if on { "on" } else { "off" });
+    let snapshot = state.lock().unwrap().snapshot();
+    let _ = app.emit("state-updated", &snapshot);
+    if on && !soundboard_running(app) {
+        return Err(i18n::t("error-soundboard-unset").into());
+    }
+    Ok(snapshot)
+}
+
+/// The spotter heard "stop stop stop": cut every sound and tell the UI.
+fn stop_word_heard<R: Runtime>(app: &AppHandle<R>) {
+    stop_sounds(app);
+    let _ = app.emit("stop-word", ());
+}
+
+/// Fade out every sound the pipeline is playing, playlists included.
+fn stop_sounds<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(ref pipeline) = *app.state::<ManagedAudioPipeline>().0.lock().unwrap() {
+        pipeline.stop_all();
+        pipeline.stop_playlist();
+    }
+}
+
+/// Mute or unmute key sounds (muting also stops what's playing) and tell the
+/// UI. The caller refreshes the tray's check mark.
+fn set_sounds_muted_on<R: Runtime>(app: &AppHandle<R>, muted: bool) -> StateSnapshot {
+    if muted {
+        stop_sounds(app);
+    }
+    let state = app.state::<SharedState>();
+    let snapshot = {
+        let mut st = state.lock().unwrap();
+        st.audio_config.sounds_muted = muted;
+        persist_state(&st);
+        st.snapshot()
+    };
+    info!("[audio] Key sounds {}", if muted { "muted" } else { "unmuted" });
+    let _ = app.emit("state-updated", &snapshot);
+    snapshot
+}
+
+/// Stop every sound now (tray, hotkey or UI).
+#[tauri::command]
+fn stop_all_sounds(app: AppHandle) {
+    info!("[audio] Stopping all sounds");
+    stop_sounds(&app);
+}
+
+#[tauri::command]
+fn set_sounds_muted(app: AppHandle, muted: bool) -> StateSnapshot {
+    let snapshot = set_sounds_muted_on(&app, muted);
+    refresh_tray_menu(&app);
+    snapshot
+}
+
+/// Decode the sounds assigned to keys into the running pipeline's cache, so a
+/// key press doesn't wait on disk and resampling.
+fn preload_key_sounds(state: &SharedState, pipeline_state: &ManagedAudioPipeline) {
+    let paths: Vec<std::path::PathBuf> = {
+        let st = state.lock().unwrap();
+        st.audio_config.key_sound_ids().into_iter()
+            .filter_map(|id| st.audio_config.sound_library.iter().find(|e| e.id == id))
+            .filter_map(|e| audio::resolve_sound_path(&e.filename).ok())
+            .collect()
+    };
+    if let Some(ref pipeline) = *pipeline_state.0.lock().unwrap() {
+        pipeline.preload(&paths);
+    }
+}
+
+/// Turn mic routing on or off (stays off across restarts until turned on).
+#[tauri::command]
+fn set_soundboard(app: AppHandle, on: bool) -> Result<StateSnapshot, HubError> {
+    set_soundboard_on(&app, on)
+}
+
+#[tauri::command]
+fn set_audio_input_device(
+    app: AppHandle,
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    name: String,
+) -> Result<(), HubError> {
+    {
+        let mut st = state.lock().unwrap();
+        st.audio_config.audio_input_device = Some(name);
+        persist_state(&st);
+    }
+    try_auto_start_pipeline(&app, &state, &pipeline_state);
+    onboarding::sync(&app);
+    Ok(())
+}
+
+/// Answer the "which device is it?" prompt with one of its candidates, or
+/// `None` to leave the saved device as is for now.
+#[tauri::command]
+fn choose_audio_device(
+    app: AppHandle,
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    name: Option<String>,
+) -> Result<StateSnapshot, HubError> {
+    {
+        let mut st = state.lock().unwrap();
+        let choice = st.device_choice.as_ref().ok_or_else(|| i18n::t("error-no-device-choice"))?;
+        if name.as_ref().is_some_and(|n| !choice.candidates.contains(n)) {
+            return Err(i18n::t("error-device-not-a-choice").into());
+        }
+        let choice = st.device_choice.take().unwrap();
+        let Some(name) = name else {
+            return Ok(st.snapshot());
+        };
+        info!("[audio] \"{}\" picked for saved device \"{}\"", name, choice.saved);
+        *st.audio_config.device_mut(choice.role) = Some(name);
+        persist_state(&st);
+    }
+    try_auto_start_pipeline(&app, &state, &pipeline_state);
+    onboarding::sync(&app);
+    let snapshot = state.lock().unwrap().snapshot();
+    Ok(snapshot)
+}
+
+/// Second mic or line-in (`None` to remove); applied live if the pipeline runs.
+#[tauri::command]
+fn set_second_input_device(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    name: Option<String>,
+) -> Result<(), HubError> {
+    {
+        let mut st = state.lock().unwrap();
+        if name.is_some() && name == st.audio_config.audio_input_device {
+            return Err(i18n::t("error-device-is-mic").into());
+        }
+        st.audio_config.second_input_device = name.clone();
+        persist_state(&st);
+    }
+    if let Some(ref pipeline) = *pipeline_state.0.lock().unwrap() {
+        pipeline.set_second_input(name.as_deref()).map_err(HubError::audio)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn set_second_input_volume(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    volume: f32,
+) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.audio_config.second_input_volume = volume;
+    persist_state(&st);
+    drop(st);
+
+    if let Some(ref pipeline) = *pipeline_state.0.lock().unwrap() {
+        pipeline.set_second_input_volume(volume);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn set_audio_output_device(
+    app: AppHandle,
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    name: String,
+) -> Result<(), HubError> {
+    {
+        let mut st = state.lock().unwrap();
+        st.audio_config.audio_output_device = Some(name);
+        persist_state(&st);
+    }
+    try_auto_start_pipeline(&app, &state, &pipeline_state);
+    onboarding::sync(&app);
+    Ok(())
+}
+
+fn library_stats(st: &AppState) -> Result<audio::LibraryStats, HubError> {
+    let dir = audio::sounds_dir().map_err(HubError::platform)?;
+    let config = &st.audio_config;
+    let used: Vec<&str> = config.key_sounds.iter().flatten().map(String::as_str)
+        .chain(config.playlists.iter().flat_map(|p| p.sounds.iter().map(String::as_str)))
+        .collect();
+    let mut stats = audio::library_stats(&dir, &config.sound_library, &used)
+        .map_err(|e| HubError::file(&dir, e))?;
+    stats.quota_bytes = st.audio_config.library_quota_mb.map(|mb| mb * 1_000_000);
+    Ok(stats)
+}
+
+/// Refuse importing `file_path` if the library would go over its quota.
+/// Trimmed imports are measured by their source file.
+fn check_library_quota(state: &SharedState, file_path: &str) -> Result<(), HubError> {
+    let st = state.lock().unwrap();
+    let Some(quota_mb) = st.audio_config.library_quota_mb else { return Ok(()) };
+    let incoming = std::fs::metadata(file_path).map_err(|e| HubError::file(file_path, e.into()))?.len();
+    if library_stats(&st)?.library_bytes + incoming > quota_mb * 1_000_000 {
+        return Err(i18n::t_with("error-quota-full", &[("quota", &quota_mb)]).into());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn add_to_sound_library(
+    app: AppHandle,
+    state: State<SharedState>,
+    file_path: String,
+    display_name: String,
+) -> Result<SoundEntry, HubError> {
+    check_library_quota(&state, &file_path)?;
+    let entry = audio::import_to_library(&file_path, &display_name)
+        .map_err(|e| HubError::file(&file_path, e))?;
+    {
+        let mut st = state.lock().unwrap();
+        st.audio_config.sound_library.push(entry.clone());
+        persist_state(&st);
+    }
+    onboarding::sync(&app);
+    Ok(entry)
+}
+
+#[tauri::command]
+fn add_to_sound_library_trimmed(
+    app: AppHandle,
+    state: State<SharedState>,
+    file_path: String,
+    display_name: String,
+    trim: audio::Trim,
+) -> Result<SoundEntry, HubError> {
+    check_library_quota(&state, &file_path)?;
+    let format = state.lock().unwrap().audio_config.sound_format;
+    let entry = audio::import_to_library_trimmed(&file_path, &display_name, &trim, format)
+        .map_err(|e| HubError::file(&file_path, e))?;
+    {
+        let mut st = state.lock().unwrap();
+        st.audio_config.sound_library.push(entry.clone());
+        persist_state(&st);
+    }
+    onboarding::sync(&app);
+    Ok(entry)
+}
+
+/// Where previews play while the pipeline runs.
+#[tauri::command]
+fn set_preview_route(state: State<SharedState>, route: state::SoundRoute) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.audio_config.preview_route = route;
+    persist_state(&st);
+    Ok(())
+}
+
+/// Format trimmed imports are saved in.
+#[tauri::command]
+fn set_sound_format(state: State<SharedState>, format: state::SoundFormat) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.audio_config.sound_format = format;
+    persist_state(&st);
+    Ok(())
+}
+
+/// Re-encode the library's WAVs as FLAC where that's lossless and smaller.
+/// Files that can't be read are skipped and logged.
+#[tauri::command]
+fn compress_sound_library(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+) -> Result<audio::CompressReport, HubError> {
+    let library = state.lock().unwrap().audio_config.sound_library.clone();
+    let mut report = audio::CompressReport::default();
+    for entry in library {
+        let (filename, saved) = match audio::compress_sound(&entry.filename) {
+            Ok(Some(done)) => done,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("[audio] Couldn't compress {}: {e:#}", entry.filename);
+                continue;
+            }
+        };
+        let mut st = state.lock().unwrap();
+        let Some(current) = st.audio_config.sound_library.iter_mut()
+            .find(|e| e.id == entry.id && e.filename == entry.filename)
+        else {
+            // Removed while we were encoding
+            drop(st);
+            let _ = audio::delete_sound(&filename);
+            continue;
+        };
+        current.filename = filename;
+        persist_state(&st);
+        drop(st);
+        let _ = audio::delete_sound(&entry.filename);
+        report.converted += 1;
+        report.bytes_saved += saved;
+    }
+    preload_key_sounds(&state, &pipeline_state);
+    Ok(report)
+}
+
+/// Point a library entry at a new file, copied into the sounds folder (e.g.
+/// after its file went missing). Keys keep the sound, since they use its id.
+#[tauri::command]
+fn relink_sound(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    sound_id: String,
+    new_path: String,
+) -> Result<SoundEntry, HubError> {
+    let old = state.lock().unwrap().audio_config.sound_library.iter()
+        .find(|e| e.id == sound_id)
+        .map(|e| e.filename.clone())
+        .ok_or_else(|| i18n::t("error-no-sound"))?;
+    let filename = audio::copy_into_library(&new_path, &sound_id).map_err(|e| HubError::file(&new_path, e))?;
+    let mut st = state.lock().unwrap();
+    let Some(entry) = st.audio_config.sound_library.iter_mut().find(|e| e.id == sound_id) else {
+        // Removed while copying
+        let _ = audio::delete_sound(&filename);
+        return Err(i18n::t("error-no-sound").into());
+    };
+    entry.filename = filename.clone();
+    entry.missing = false;
+    let entry = entry.clone();
+    persist_state(&st);
+    drop(st);
+    if old != filename {
+        let _ = audio::delete_sound(&old);
+    }
+    preload_key_sounds(&state, &pipeline_state);
+    Ok(entry)
+}
+
+/// Duration, format and bitrate of the library's sounds, by id, read from
+/// their headers. Sounds whose file can't be read are left out.
+#[tauri::command]
+fn get_library_audio_info(state: State<SharedState>) -> std::collections::HashMap<String, probe::AudioInfo> {
+    let files: Vec<(String, String)> = state.lock().unwrap().audio_config.sound_library.iter()
+        .filter(|e| !e.missing)
+        .map(|e| (e.id.clone(), e.filename.clone()))
+        .collect();
+    files.into_iter()
+        .filter_map(|(id, filename)| {
+            let info = audio::sound_path(&filename).and_then(|path| probe::probe(&path));
+            info.map_err(|e| warn!("[audio] No info for {filename}: {e:#}")).ok().map(|info| (id, info))
+        })
+        .collect()
+}
+
+/// Library disk use, unused entries and orphaned files.
+#[tauri::command]
+fn get_library_stats(state: State<SharedState>) -> Result<audio::LibraryStats, HubError> {
+    library_stats(&state.lock().unwrap())
+}
+
+/// Delete leftover audio files in the sounds folder no entry points at (see
+/// `LibraryStats::orphans`) and, with `remove_unused`, the sounds no key plays.
+#[tauri::command]
+fn clean_sound_library(state: State<SharedState>, remove_unused: bool) -> Result<audio::CleanupReport, HubError> {
+    let mut st = state.lock().unwrap();
+    let stats = library_stats(&st)?;
+    let mut report = audio::CleanupReport::default();
+    // Sized before deleting, and only counted once the delete went through
+    let dir = audio::sounds_dir().map_err(HubError::platform)?;
+    let size = |file: &str| std::fs::metadata(dir.join(file)).map_or(0, |m| m.len());
+    for file in &stats.orphans {
+        let bytes = size(file);
+        match audio::delete_sound(file) {
+            Ok(()) => {
+                report.removed_files += 1;
+                report.bytes_freed += bytes;
+            }
+            Err(e) => warn!("[audio] Couldn't delete orphan {}: {e:#}", file),
+        }
+    }
+    if remove_unused {
+        let (unused, kept) = std::mem::take(&mut st.audio_config.sound_library)
+            .into_iter()
+            .partition(|e| stats.unused.contains(&e.id));
+        st.audio_config.sound_library = kept;
+        for entry in unused {
+            let bytes = size(&entry.filename);
+            match audio::delete_sound(&entry.filename) {
+                Ok(()) => report.bytes_freed += bytes,
+                Err(e) => warn!("[audio] Couldn't delete {}: {e:#}", entry.filename),
+            }
+            report.removed_sounds.push(entry.id);
+        }
+        persist_state(&st);
+    }
+    info!(
+        "[audio] Library cleanup: {} orphan file(s), {} unused sound(s), {} bytes",
+        report.removed_files, report.removed_sounds.len(), report.bytes_freed
+    );
+    Ok(report)
+}
+
+/// Imports are refused that would take the library past `mb` (`None`: no limit).
+#[tauri::command]
+fn set_library_quota(state: State<SharedState>, mb: Option<u64>) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.audio_config.library_quota_mb = mb.filter(|&mb| mb > 0);
+    persist_state(&st);
+    Ok(())
+}
+
+#[tauri::command]
+fn remove_from_sound_library(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    sound_id: String,
+) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    // Find and remove the entry
+    if let Some(pos) = st.audio_config.sound_library.iter().position(|e| e.id == sound_id) {
+        let entry = st.audio_config.sound_library.remove(pos);
+        let _ = audio::delete_sound(&entry.filename);
+    }
+    // Clear any key_sounds referencing this id
+    for slot in st.audio_config.key_sounds.iter_mut() {
+        if slot.as_deref() == Some(sound_id.as_str()) {
+            *slot = None;
+        }
+    }
+    for playlist in &mut st.audio_config.playlists {
+        playlist.sounds.retain(|id| *id != sound_id);
+    }
+    persist_state(&st);
+    drop(st);
+    preload_key_sounds(&state, &pipeline_state);
+    Ok(())
+}
+
+/// Create (empty id) or replace a playlist; returns it with its id.
+#[tauri::command]
+fn save_playlist(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    mut playlist: state::Playlist,
+) -> Result<state::Playlist, HubError> {
+    playlist.name = playlist.name.trim().to_string();
+    if playlist.name.is_empty() {
+        return Err(i18n::t("error-playlist-name-empty").into());
+    }
+    let mut st = state.lock().unwrap();
+    if let Some(id) = playlist.sounds.iter().find(|id| !st.audio_config.sound_library.iter().any(|e| &e.id == *id)) {
+        return Err(i18n::t_with("error-playlist-sound", &[("id", &id)]).into());
+    }
+    let playlists = &mut st.audio_config.playlists;
+    if playlist.id.is_empty() {
+        playlist.id = audio::uuid_simple();
+        playlists.push(playlist.clone());
+    } else if let Some(existing) = playlists.iter_mut().find(|p| p.id == playlist.id) {
+        *existing = playlist.clone();
+    } else {
+        return Err(i18n::t("error-no-playlist").into());
+    }
+    persist_state(&st);
+    drop(st);
+    preload_key_sounds(&state, &pipeline_state);
+    Ok(playlist)
+}
+
+/// Delete a playlist; keys that played it get no sound.
+#[tauri::command]
+fn delete_playlist(state: State<SharedState>, playlist_id: String) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    st.audio_config.playlists.retain(|p| p.id != playlist_id);
+    for slot in st.audio_config.key_sounds.iter_mut() {
+        if slot.as_deref() == Some(playlist_id.as_str()) {
+            *slot = None;
+        }
+    }
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Go on with the playing playlist's next sound.
+#[tauri::command]
+fn skip_playlist(pipeline_state: State<ManagedAudioPipeline>) {
+    if let Some(ref pipeline) = *pipeline_state.0.lock().unwrap() {
+        pipeline.skip_playlist();
+    }
+}
+
+#[tauri::command]
+fn stop_playlist(pipeline_state: State<ManagedAudioPipeline>) {
+    if let Some(ref pipeline) = *pipeline_state.0.lock().unwrap() {
+        pipeline.stop_playlist();
+    }
+}
+
+#[tauri::command]
+fn rename_sound(
+    state: State<SharedState>,
+    sound_id: String,
+    new_name: String,
+) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    if let Some(entry) = st.audio_config.sound_library.iter_mut().find(|e| e.id == sound_id) {
+        entry.display_name = new_name;
+    }
+    persist_state(&st);
+    Ok(())
+}
+
+/// Keep a sound out of the mic mix: it only plays in the user's headphones.
+#[tauri::command]
+fn set_sound_monitor_only(
+    state: State<SharedState>,
+    sound_id: String,
+    monitor_only: bool,
+) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    let entry = st.audio_config.sound_library.iter_mut()
+        .find(|e| e.id == sound_id)
+        .ok_or_else(|| i18n::t("error-no-sound"))?;
+    entry.monitor_only = monitor_only;
+    persist_state(&st);
+    Ok(())
+}
+
+/// Set a key's sound (LED index). A key with a sound but no keycode gets an
+/// internal keycode so presses can be detected; removing the sound clears it.
+fn assign_key_sound(st: &mut AppState, key_index: usize, sound_id: Option<String>) {
+    st.audio_config.key_sounds[key_index] = sound_id.clone();
+
+    let keymap_idx = led_to_keymap_index(key_index);
+    let current_keycode = st.keymaps[keymap_idx];
+
+    if sound_id.is_some() && current_keycode == 0x0000 {
+        // Auto-assign internal keycode so the shortcut handler can detect key presses
+        let internal_kc = internal_keycode_for_key(key_index);
+        if let Some(ref dev) = st.device {
+            let (row, col) = protocol::key_index_to_matrix(keymap_idx as u8);
+            if let Err(e) = dev.set_keycode(0, row, col, internal_kc) {
+                error!("[sound] Failed to auto-assign keycode: {}", e);
+            }
+        }
+        st.keymaps[keymap_idx] = internal_kc;
+        info!("[sound] Auto-assigned internal keycode 0x{:04X} to LED {} (keymap {})",
+              internal_kc, key_index, keymap_idx);
+    } else if sound_id.is_none() && is_internal_keycode(current_keycode) {
+        // Clear internal keycode when sound is removed
+        if let Some(ref dev) = st.device {
+            let (row, col) = protocol::key_index_to_matrix(keymap_idx as u8);
+            if let Err(e) = dev.set_keycode(0, row, col, 0x0000) {
+                error!("[sound] Failed to clear internal keycode: {}", e);
+            }
+        }
+        st.keymaps[keymap_idx] = 0x0000;
+        info!("[sound] Cleared internal keycode from LED {} (keymap {})", key_index, keymap_idx);
+    }
+}
+
+#[tauri::command]
+fn set_key_sound(
+    app: AppHandle,
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    key_index: usize,
+    sound_id: Option<String>,
+) -> Result<(), HubError> {
+    if key_index >= 8 {
+        return Err(i18n::t("error-key-index").into());
+    }
+    let keymaps_copy;
+    {
+        let mut st = state.lock().unwrap();
+        assign_key_sound(&mut st, key_index, sound_id);
+        keymaps_copy = st.keymaps;
+        persist_state(&st);
+    }
+    preload_key_sounds(&state, &pipeline_state);
+    // Re-register shortcuts with updated keymaps
+    register_key_shortcuts(&app, &keymaps_copy);
+    Ok(())
+}
+
+#[tauri::command]
+fn preview_library_sound(
+    app: AppHandle,
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    sound_id: String,
+) -> Result<(), HubError> {
+    let st = state.lock().unwrap();
+    let entry = st.audio_config.sound_library.iter()
+        .find(|e| e.id == sound_id)
+        .ok_or_else(|| i18n::t("error-no-sound"))?;
+    let filename = entry.filename.clone();
+    let route = entry.route(st.audio_config.preview_route);
+    drop(st);
+
+    let path = audio::resolve_sound_path(&filename).map_err(|e| {
+        mark_sound_missing(&app, &sound_id);
+        HubError::file(&filename, e)
+    })?;
+    let pl = pipeline_state.0.lock().unwrap();
+    if let Some(ref pipeline) = *pl {
+        pipeline.play_sound(&path, 1.0, route).map_err(HubError::audio)
+    } else {
+        // Fallback: play through default output when soundboard is not running
+        sound_preview::play(sound_preview::Request { path, trim: audio::Trim::whole(), route });
+        Ok(())
+    }
+}
+
+#[tauri::command]
+fn set_sound_volume(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    volume: f32,
+) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.audio_config.sound_volume = volume;
+    persist_state(&st);
+    drop(st);
+
+    let pl = pipeline_state.0.lock().unwrap();
+    if let Some(ref pipeline) = *pl {
+        pipeline.set_sound_volume(volume);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn set_mic_volume(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    volume: f32,
+) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.audio_config.mic_volume = volume;
+    persist_state(&st);
+    drop(st);
+
+    let pl = pipeline_state.0.lock().unwrap();
+    if let Some(ref pipeline) = *pl {
+        pipeline.set_mic_volume(volume);
+    }
+    Ok(())
+}
+
+/// Mic input gain in dB, clamped to -20..=+30.
+#[tauri::command]
+fn set_input_gain(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    gain_db: f32,
+) -> Result<(), HubError> {
+    if !gain_db.is_finite() {
+        return Err(i18n::t("error-input-gain-not-number").into());
+    }
+    let gain_db = gain_db.clamp(-20.0, 30.0);
+    let mut st = state.lock().unwrap();
+    st.audio_config.input_gain_db = gain_db;
+    persist_state(&st);
+    drop(st);
+
+    if let Some(ref pipeline) = *pipeline_state.0.lock().unwrap() {
+        pipeline.set_input_gain(gain_db);
+    }
+    Ok(())
+}
+
+/// Apply a processing chain to the config and the running pipeline.
+fn apply_processing(
+    state: &SharedState,
+    pipeline_state: &ManagedAudioPipeline,
+    stages: Vec<state::ProcessingStage>,
+) -> Result<(), HubError> {
+    if !dsp::is_complete(&stages) {
+        return Err(i18n::t("error-chain-incomplete").into());
+    }
+    let mut st = state.lock().unwrap();
+    st.audio_config.processing = stages;
+    persist_state(&st);
+    if let Some(ref pipeline) = *pipeline_state.0.lock().unwrap() {
+        pipeline.set_processing(&st.audio_config.processing);
+    }
+    Ok(())
+}
+
+/// AGC target range and max gain (the stage is enabled like any other).
+#[tauri::command]
+fn set_agc(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    agc: state::AgcSettings,
+) -> Result<(), HubError> {
+    let levels = [agc.target_low_db, agc.target_high_db];
+    if !levels.iter().all(|db| (-60.0..=0.0).contains(db)) {
+        return Err(i18n::t("error-agc-target").into());
+    }
+    if agc.target_low_db > agc.target_high_db {
+        return Err(i18n::t("error-agc-range").into());
+    }
+    if !(0.0..=40.0).contains(&agc.max_gain_db) {
+        return Err(i18n::t("error-agc-max-gain").into());
+    }
+    let mut st = state.lock().unwrap();
+    st.audio_config.agc = agc;
+    persist_state(&st);
+    drop(st);
+
+    if let Some(ref pipeline) = *pipeline_state.0.lock().unwrap() {
+        pipeline.set_agc(&agc);
+    }
+    Ok(())
+}
+
+/// Enable or bypass one mic processing stage.
+#[tauri::command]
+fn set_processing_stage(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    kind: state::StageKind,
+    enabled: bool,
+) -> Result<(), HubError> {
+    let mut stages = state.lock().unwrap().audio_config.processing.clone();
+    stages.iter_mut().filter(|s| s.kind == kind).for_each(|s| s.enabled = enabled);
+    apply_processing(&state, &pipeline_state, stages)
+}
+
+/// Reorder the mic processing stages, keeping each one's bypass.
+#[tauri::command]
+fn set_processing_order(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    order: Vec<state::StageKind>,
+) -> Result<(), HubError> {
+    let current = state.lock().unwrap().audio_config.processing.clone();
+    let stages = order.iter()
+        .map(|&kind| state::ProcessingStage {
+            kind,
+            enabled: current.iter().any(|s| s.kind == kind && s.enabled),
+        })
+        .collect();
+    apply_processing(&state, &pipeline_state, stages)
+}
+
+/// Listen for the stop word on the mic while the soundboard runs.
+#[tauri::command]
+fn set_stop_word(
+    state: State<SharedState>,
+    pipeline_state: State<ManagedAudioPipeline>,
+    enabled: bool,
+) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.audio_config.stop_word = enabled;
+    persist_state(&st);
+    drop(st);
+
+    if let Some(ref pipeline) = *pipeline_state.0.lock().unwrap() {
+        pipeline.set_stop_word(enabled);
+    }
+    Ok(())
+}
+
+/// How often `audio-metrics` is emitted while the pipeline runs.
+const AUDIO_METRICS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Buffer levels, drops and rates of the running pipeline (`None` when stopped).
+#[tauri::command]
+fn get_audio_metrics(pipeline_state: State<ManagedAudioPipeline>) -> Option<audio::AudioMetrics> {
+    pipeline_state.0.lock().unwrap().as_ref().and_then(|p| p.metrics())
+}
+
+// ── Settings commands ────────────────────────────────────────────────────
+
+/// Linux: grab the Deck-8's input so sound-only keys don't reach other apps.
+/// No-op on other platforms.
+#[tauri::command]
+fn set_input_grab(state: State<SharedState>, enabled: bool) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.settings.grab_device_input = enabled;
+    persist_state(&st);
+    drop(st);
+    keyboard_hook::set_grab(enabled);
+    Ok(())
+}
+
+/// Fade duration for slot changes (0 = instant, at most 2000 ms).
+#[tauri::command]
+fn set_fade_duration(state: State<SharedState>, ms: u64) -> Result<(), HubError> {
+    if ms > 2000 {
+        return Err(i18n::t("error-fade-too-long").into());
+    }
+    let mut st = state.lock().unwrap();
+    st.settings.fade_ms = ms;
+    persist_state(&st);
+    Ok(())
+}
+
+/// Set the daily window in which key sounds are muted or capped.
+#[tauri::command]
+fn set_quiet_hours(state: State<SharedState>, quiet_hours: QuietHours) -> Result<StateSnapshot, HubError> {
+    if quiet_hours.start_min >= 24 * 60 || quiet_hours.end_min >= 24 * 60 {
+        return Err(i18n::t("error-quiet-hours-time").into());
+    }
+    if !(0.0..=1.0).contains(&quiet_hours.max_volume) {
+        return Err(i18n::t("error-quiet-hours-volume").into());
+    }
+    let mut st = state.lock().unwrap();
+    st.settings.quiet_hours = quiet_hours;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Let key sounds play normally for `minutes` despite quiet hours (0 ends
+/// the override).
+#[tauri::command]
+fn override_quiet_hours(state: State<SharedState>, minutes: u64) -> Result<StateSnapshot, HubError> {
+    if minutes > 24 * 60 {
+        return Err(i18n::t("error-override-too-long").into());
+    }
+    let mut st = state.lock().unwrap();
+    st.quiet_override_until = (minutes > 0).then(|| now_ms() + minutes * 60_000);
+    info!("[quiet] override for {} min", minutes);
+    Ok(st.snapshot())
+}
+
+/// Play an earcon and announce the new slot whenever a key changes slot.
+#[tauri::command]
+fn set_accessibility_cues(state: State<SharedState>, enabled: bool) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.settings.accessibility_cues = enabled;
+    persist_state(&st);
+    Ok(())
+}
+
+/// Serve the local WebSocket API (api.rs) on 127.0.0.1.
+#[tauri::command]
+fn set_local_api(state: State<SharedState>, enabled: bool) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.settings.local_api = enabled;
+    api::set_enabled(enabled);
+    persist_state(&st);
+    Ok(())
+}
+
+/// Show a progress bar across the keys in matrix order (percent 0–100) in
+/// `color`. Key colors are held back until `clear_progress`.
+#[tauri::command]
+fn set_progress(state: State<SharedState>, percent: f32, color: protocol::HsvColor) -> Result<(), HubError> {
+    progress::show(&state.lock().unwrap(), percent, color)
+}
+
+/// Take the progress bar down and restore every key's color.
+#[tauri::command]
+fn clear_progress(state: State<SharedState>) {
+    progress::clear(&state.lock().unwrap());
+}
+
+/// Flash a key for `duration_ms` (see notify.rs); returns the id for
+/// `dismiss_notification`. Higher `priority` shows over lower.
+#[tauri::command]
+fn notify_key(
+    app: AppHandle,
+    key_index: usize,
+    color: protocol::HsvColor,
+    pattern: Option<notify::Pattern>,
+    duration_ms: u64,
+    priority: Option<u8>,
+) -> Result<u64, HubError> {
+    notify::notify(&app, key_index, color, pattern.unwrap_or_default(), duration_ms, priority.unwrap_or(0))
+}
+
+/// End a notification early. False if it had already ended.
+#[tauri::command]
+fn dismiss_notification(id: u64) -> bool {
+    notify::dismiss(id)
+}
+
+/// Discord Rich Presence and the app voice state bindings use (discord.rs
+/// picks the change up on the next watcher tick).
+#[tauri::command]
+fn set_discord_settings(state: State<SharedState>, discord: DiscordSettings) -> Result<(), HubError> {
+    if !discord.client_id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(i18n::t("error-discord-client-id").into());
+    }
+    let mut st = state.lock().unwrap();
+    st.settings.discord = discord;
+    persist_state(&st);
+    Ok(())
+}
+
+/// Lights that mirror the pad and take key scenes (see lighting.rs).
+#[tauri::command]
+fn set_lighting(state: State<SharedState>, lighting: state::LightingSettings) -> Result<StateSnapshot, HubError> {
+    if let Some(state::LightSource::Key { key_index }) = lighting.mirror {
+        if key_index >= 8 {
+            return Err(i18n::t("error-key-index").into());
         }
     }
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
+    for (i, target) in lighting.targets.iter().enumerate() {
+        let name = target.name().trim();
+        if name.is_empty() {
+            return Err(i18n::t("error-light-name-empty").into());
+        }
+        if lighting.targets[..i].iter().any(|t| t.name().trim() == name) {
+            return Err(i18n::t_with("error-light-name-taken", &[("name", &name)]).into());
+        }
+        match target {
+            state::LightTarget::Hue { bridge, user, .. } if bridge.trim().is_empty() || user.is_empty() => {
+                return Err(i18n::t_with("error-hue-unpaired", &[("name", &name)]).into());
+            }
+            state::LightTarget::Wled { host, .. } if host.trim().is_empty() => {
+                return Err(i18n::t_with("error-wled-address", &[("name", &name)]).into());
+            }
+            _ => {}
+        }
+    }
+    let mut st = state.lock().unwrap();
+    st.settings.lighting = lighting;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Serve this setup to another PC on the LAN; returns the address and
+/// pairing code to enter there.
+#[tauri::command]
+fn start_config_share(state: State<SharedState>) -> Result<pairing::Share, HubError> {
+    let bundle = profile::export_bundle(&state.lock().unwrap()).map_err(HubError::platform)?;
+    pairing::share(bundle).map_err(HubError::platform)
+}
+
+#[tauri::command]
+fn stop_config_share() {
+    pairing::stop();
+}
+
+/// Replace this setup with the one another PC is sharing, then restart.
+#[tauri::command]
+fn import_shared_config(app: AppHandle, state: State<SharedState>, address: String, code: String) -> Result<(), HubError> {
+    let bundle = pairing::fetch(&address, &code).map_err(HubError::platform)?;
+    let restored = profile::import_bundle(&bundle).map_err(HubError::platform)?;
+    {
+        let mut st = state.lock().unwrap();
+        // The echo check was for the other PC's device, not this one
+        let echo_checked = st.echo_checked_firmware;
+        apply_restored(&mut st, restored);
+        st.echo_checked_firmware = echo_checked;
+        persist_state(&st);
+    }
+    info!("[pairing] Imported the setup from {}, restarting", address.trim());
+    app.restart()
+}
+
+/// Get an app key from a Hue bridge (after its link button was pressed).
+#[tauri::command]
+fn pair_hue_bridge(bridge: String) -> Result<String, HubError> {
+    Ok(lighting::pair_hue(bridge.trim())?)
+}
+
+/// Language of tray items, announcements and errors; `None` follows the OS.
+#[tauri::command]
+fn set_language(app: AppHandle, state: State<SharedState>, language: Option<String>) -> Result<StateSnapshot, HubError> {
+    i18n::set(language.as_deref())?;
+    let snapshot = {
+        let mut st = state.lock().unwrap();
+        st.settings.language = language;
+        persist_state(&st);
+        st.snapshot()
+    };
+    refresh_tray_menu(&app);
+    Ok(snapshot)
+}
+
+/// Languages `set_language` accepts.
+#[tauri::command]
+fn list_languages() -> Vec<i18n::Language> {
+    i18n::languages()
+}
+
+/// Go on to the next setup step without finishing this one.
+#[tauri::command]
+fn skip_onboarding_step(state: State<SharedState>) -> StateSnapshot {
+    let mut st = state.lock().unwrap();
+    onboarding::skip(&mut st);
+    persist_state(&st);
+    st.snapshot()
+}
+
+/// Close the setup guide for good (Settings can bring it back).
+#[tauri::command]
+fn finish_onboarding(state: State<SharedState>) -> StateSnapshot {
+    let mut st = state.lock().unwrap();
+    st.settings.onboarding = onboarding::OnboardingStep::Done;
+    persist_state(&st);
+    st.snapshot()
+}
+
+/// Show the setup guide again, from the first step not yet done.
+#[tauri::command]
+fn reset_onboarding(state: State<SharedState>) -> StateSnapshot {
+    let mut st = state.lock().unwrap();
+    onboarding::reset(&mut st);
+    persist_state(&st);
+    st.snapshot()
+}
+
+/// What closing and minimizing the window do, and whether to ask before
+/// quitting mid-sound.
+#[tauri::command]
+fn set_window_settings(state: State<SharedState>, window: state::WindowSettings) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    st.settings.window = window;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// HID read timeout and report pacing; applies to the connected device now.
+#[tauri::command]
+fn set_hid_timing(state: State<SharedState>, timing: hid::HidTiming) -> Result<StateSnapshot, HubError> {
+    timing.validate()?;
+    let mut st = state.lock().unwrap();
+    st.settings.hid = timing;
+    if let Some(ref mut dev) = st.device {
+        dev.set_timing(timing);
+    }
+    info!("[HID] timeout {}ms, pacing {}ms", timing.timeout_ms, timing.pacing_ms);
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Keep retrying while another app holds the device (see coexist.rs).
+#[tauri::command]
+fn set_cooperative(state: State<SharedState>, enabled: bool) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    st.settings.cooperative = enabled;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
+
+/// Refuse configuration changes until `unlock_config` gets the same PIN.
+#[tauri::command]
+fn lock_config(state: State<SharedState>, pin: String) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    st.settings.lock_pin = Some(lock::hash_pin(&pin)?);
+    lock::set_locked(true);
+    persist_state(&st);
+    info!("[lock] Locked");
+    Ok(st.snapshot())
+}
+
+#[tauri::command]
+fn unlock_config(state: State<SharedState>, pin: String) -> Result<StateSnapshot, HubError> {
+    let mut st = state.lock().unwrap();
+    if let Some(ref stored) = st.settings.lock_pin {
+        lock::try_unlock(&pin, stored, now_ms())?;
+        st.settings.lock_pin = None;
+        lock::set_locked(false);
+        persist_state(&st);
+        info!("[lock] Unlocked");
+    }
+    Ok(st.snapshot())
+}
+
+/// Give keys on the default color the OS accent color (and follow it).
+#[tauri::command]
+fn set_accent_key_colors(app: AppHandle, state: State<SharedState>, enabled: bool) -> Result<StateSnapshot, HubError> {
+    {
+        let mut st = state.lock().unwrap();
+        st.settings.accent_key_colors = enabled;
+        persist_state(&st);
+    }
+    appearance::follow_accent(&app);
+    Ok(state.lock().unwrap().snapshot())
+}
+
+/// The desktop's dark/light mode and accent color.
+#[tauri::command]
+fn get_system_theme() -> appearance::SystemTheme {
+    appearance::current()
+}
+
+/// Track the OS media session for the now-playing card.
+#[tauri::command]
+fn set_now_playing(state: State<SharedState>, enabled: bool) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.settings.now_playing = enabled;
+    persist_state(&st);
+    Ok(())
+}
+
+/// The current media session, or `None` when nothing is playing or paused
+/// (or nothing has enabled tracking).
+#[tauri::command]
+fn get_now_playing() -> Option<media::NowPlaying> {
+    media::current()
+}
+
+/// Calibrate how slider values map to LED brightness, then re-push every key
+/// and the RGB matrix brightness through the new curve.
+#[tauri::command]
+fn set_brightness_curve(state: State<SharedState>, curve: BrightnessCurve) -> Result<(), HubError> {
+    if !(0.2..=5.0).contains(&curve.gamma) {
+        return Err(i18n::t("error-gamma").into());
+    }
+    if curve.min >= curve.max {
+        return Err(i18n::t("error-brightness-range").into());
+    }
+    let mut st = state.lock().unwrap();
+    st.settings.brightness_curve = curve;
+    brightness::set(curve);
+    if let Some(ref dev) = st.device {
+        apply_all_to_device(dev, &st.keys);
+        if let (Some(rgb), None) = (st.rgb_matrix, st.dnd) {
+            dev.rgb_set_brightness(brightness::apply(rgb.brightness)).map_err(HubError::from)?;
+        }
+    }
+    persist_state(&st);
+    Ok(())
+}
+
+/// Windows: set the LL hook / Raw Input dedup window (0–2000 ms).
+#[tauri::command]
+fn set_dedup_window(state: State<SharedState>, ms: u64) -> Result<(), HubError> {
+    if ms > 2000 {
+        return Err(i18n::t("error-dedup-too-long").into());
+    }
+    let mut st = state.lock().unwrap();
+    st.settings.dedup_ms = ms;
+    persist_state(&st);
+    drop(st);
+    keyboard_hook::set_dedup_ms(ms);
+    Ok(())
+}
+
+/// Choose how the plugin shortcut path replays keystrokes.
+#[tauri::command]
+fn set_replay_method(state: State<SharedState>, method: ReplayMethod) -> Result<(), HubError> {
+    let mut st = state.lock().unwrap();
+    st.settings.replay_method = method;
+    persist_state(&st);
+    Ok(())
+}
+
+// ── Keycode commands ─────────────────────────────────────────────────────
+
+/// The QMK keycode catalog for the keymap editor's picker.
+#[tauri::command]
+fn list_keycodes() -> Vec<keycodes::KeycodeInfo> {
+    keycodes::catalog()
+}
+
+// ── Folder commands ──────────────────────────────────────────────────────
+
+/// Show a directory in the OS file manager.
+fn reveal_dir(dir: &std::path::Path) -> anyhow::Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(opener).arg(dir).spawn()?;
     Ok(())
 }
 
+/// Reveal the config folder (state.json, debug.log).
+#[tauri::command]
+fn open_config_dir() -> Result<(), HubError> {
+    let dir = profile::config_dir().map_err(HubError::platform)?;
+    reveal_dir(&dir).map_err(HubError::platform)
+}
+
+/// Reveal the folder imported sounds are copied to.
+#[tauri::command]
+fn open_sounds_dir() -> Result<(), HubError> {
+    let dir = audio::sounds_dir().map_err(HubError::platform)?;
+    reveal_dir(&dir).map_err(HubError::platform)
+}
+
+/// Errors returned by commands since launch, oldest first.
+#[tauri::command]
+fn get_recent_errors() -> Vec<error::ErrorReport> {
+    error::recent()
+}
+
+// ── Permission commands ──────────────────────────────────────────────────
+
+/// Version, platform, paths and active backends (About section, bug reports).
 #[tauri::command]
-fn rename_sound(
-    state: State<SharedState>,
-    sound_id: String,
-    new_name: String,
-) -> Result<(), String> {
-    let mut st = state.lock().unwrap();
-    if let Some(entry) = st.audio_config.sound_library.iter_mut().find(|e| e.id == sound_id) {
-        entry.display_name = new_name;
+fn get_app_info(state: State<SharedState>, pipeline_state: State<ManagedAudioPipeline>) -> AppInfo {
+    let mut shortcut_backends = keyboard_hook::backends();
+    #[cfg(target_os = "linux")]
+    if shortcut_backends.is_empty() && wayland_shortcuts::is_active() {
+        shortcut_backends.push("portal");
+    }
+    if shortcut_backends.is_empty() {
+        shortcut_backends.push("global-shortcut");
+    }
+    let path = |p: anyhow::Result<std::path::PathBuf>| p.ok().map(|p| p.display().to_string());
+    AppInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        build_hash: option_env!("DECK8_BUILD_HASH"),
+        platform: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        config_dir: path(profile::config_dir()),
+        sounds_dir: path(audio::sounds_dir()),
+        shortcut_backends,
+        keyboard_layout: layout::current(),
+        audio_backend: audio::host_name(),
+        soundboard_running: pipeline_state.0.lock().unwrap().is_some(),
+        safe_mode: state.lock().unwrap().safe_mode,
     }
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
-    Ok(())
 }
 
 #[tauri::command]
-fn set_key_sound(
-    app: AppHandle,
-    state: State<SharedState>,
-    key_index: usize,
-    sound_id: Option<String>,
-) -> Result<(), String> {
-    if key_index >= 8 {
-        return Err("key_index out of range".into());
-    }
-    let keymaps_copy;
-    {
-        let mut st = state.lock().unwrap();
-        st.audio_config.key_sounds[key_index] = sound_id.clone();
-
-        let keymap_idx = led_to_keymap_index(key_index);
-        let current_keycode = st.keymaps[keymap_idx];
-
-        if sound_id.is_some() && current_keycode == 0x0000 {
-            // Auto-assign internal keycode so the shortcut handler can detect key presses
-            let internal_kc = internal_keycode_for_key(key_index);
-            if let Some(ref dev) = st.device {
-                let (row, col) = protocol::key_index_to_matrix(keymap_idx as u8);
-                if let Err(e) = dev.set_keycode(0, row, col, internal_kc) {
-                    error!("[sound] Failed to auto-assign keycode: {}", e);
-                }
-            }
-            st.keymaps[keymap_idx] = internal_kc;
-            info!("[sound] Auto-assigned internal keycode 0x{:04X} to LED {} (keymap {})",
-                  internal_kc, key_index, keymap_idx);
-        } else if sound_id.is_none() && is_internal_keycode(current_keycode) {
-            // Clear internal keycode when sound is removed
-            if let Some(ref dev) = st.device {
-                let (row, col) = protocol::key_index_to_matrix(keymap_idx as u8);
-                if let Err(e) = dev.set_keycode(0, row, col, 0x0000) {
-                    error!("[sound] Failed to clear internal keycode: {}", e);
-                }
-            }
-            st.keymaps[keymap_idx] = 0x0000;
-            info!("[sound] Cleared internal keycode from LED {} (keymap {})", key_index, keymap_idx);
-        }
-
-        keymaps_copy = st.keymaps;
-        persist_state(&st.keys, &st.audio_config, &st.keymaps);
-    }
-    // Re-register shortcuts with updated keymaps
-    register_key_shortcuts(&app, &keymaps_copy);
-    Ok(())
+fn get_usage_stats() -> usage::UsageStats {
+    usage::stats()
 }
 
 #[tauri::command]
-fn preview_library_sound(
-    state: State<SharedState>,
-    pipeline_state: State<ManagedAudioPipeline>,
-    sound_id: String,
-) -> Result<(), String> {
-    let st = state.lock().unwrap();
-    let entry = st.audio_config.sound_library.iter()
-        .find(|e| e.id == sound_id)
-        .ok_or("Sound not found in library")?;
-    let filename = entry.filename.clone();
-    drop(st);
+fn reset_usage_stats() {
+    usage::reset();
+}
 
-    let path = audio::resolve_sound_path(&filename).map_err(|e| e.to_string())?;
-    let pl = pipeline_state.0.lock().unwrap();
-    if let Some(ref pipeline) = *pl {
-        pipeline.play_sound(&path).map_err(|e| e.to_string())
-    } else {
-        // Fallback: play through default output when soundboard is not running
-        audio::preview_trim(
-            path.to_str().unwrap_or(""),
-            0,
-            audio::get_audio_duration(path.to_str().unwrap_or(""))
-                .unwrap_or(60000),
-        ).map_err(|e| e.to_string())
+// ── Focus timer commands ─────────────────────────────────────────────────
+
+/// Start a focus session (`minutes`, else the configured length).
+#[tauri::command]
+fn start_focus(app: AppHandle, minutes: Option<u16>) -> Result<StateSnapshot, HubError> {
+    let state = app.state::<SharedState>();
+    let minutes = minutes.unwrap_or(state.lock().unwrap().settings.focus.focus_min);
+    if !(1..=180).contains(&minutes) {
+        return Err(i18n::t("error-focus-length").into());
     }
+    pomodoro::start(&app, minutes);
+    let snapshot = state.lock().unwrap().snapshot();
+    Ok(snapshot)
 }
 
+/// Stop the running focus session; it isn't counted.
 #[tauri::command]
-fn set_sound_volume(
-    state: State<SharedState>,
-    pipeline_state: State<ManagedAudioPipeline>,
-    volume: f32,
-) -> Result<(), String> {
+fn stop_focus(state: State<SharedState>) -> StateSnapshot {
+    pomodoro::stop();
+    state.lock().unwrap().snapshot()
+}
+
+#[tauri::command]
+fn set_focus_settings(state: State<SharedState>, focus: pomodoro::FocusSettings) -> Result<StateSnapshot, HubError> {
+    focus.validate()?;
     let mut st = state.lock().unwrap();
-    st.audio_config.sound_volume = volume;
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
-    drop(st);
+    st.settings.focus = focus;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
 
-    let pl = pipeline_state.0.lock().unwrap();
-    if let Some(ref pipeline) = *pl {
-        pipeline.set_sound_volume(volume);
-    }
-    Ok(())
+/// Completed focus sessions per day for the last week, this week and the streak.
+#[tauri::command]
+fn get_focus_summary(state: State<SharedState>) -> pomodoro::FocusSummary {
+    let goal = state.lock().unwrap().settings.focus.daily_goal;
+    pomodoro::summary(goal)
 }
 
+// ── Game integration commands ────────────────────────────────────────────
+
+/// Game state endpoint (game.rs): on/off, port, token and rules.
 #[tauri::command]
-fn set_mic_volume(
-    state: State<SharedState>,
-    pipeline_state: State<ManagedAudioPipeline>,
-    volume: f32,
-) -> Result<(), String> {
+fn set_game_settings(state: State<SharedState>, game: game::GameSettings) -> Result<StateSnapshot, HubError> {
+    game.validate()?;
     let mut st = state.lock().unwrap();
-    st.audio_config.mic_volume = volume;
-    persist_state(&st.keys, &st.audio_config, &st.keymaps);
-    drop(st);
+    game::configure(&game);
+    st.settings.game = game;
+    persist_state(&st);
+    Ok(st.snapshot())
+}
 
-    let pl = pipeline_state.0.lock().unwrap();
-    if let Some(ref pipeline) = *pl {
-        pipeline.set_mic_volume(volume);
-    }
-    Ok(())
+/// Write the usage counts as CSV (one row per key, sound and action).
+#[tauri::command]
+fn export_usage_csv(state: State<SharedState>, path: String) -> Result<(), HubError> {
+    let st = state.lock().unwrap();
+    usage::export_csv(std::path::Path::new(&path), &st).map_err(|e| HubError::file(&path, e))
+}
+
+/// Preflight check of the OS permissions per-key detection and replay need.
+#[tauri::command]
+fn check_permissions() -> Vec<permissions::PermissionCheck> {
+    permissions::check_all()
+}
+
+/// Open a settings pane deep link returned by `check_permissions`.
+#[tauri::command]
+fn open_settings_url(url: String) -> Result<(), HubError> {
+    permissions::open_settings_url(&url).map_err(HubError::platform)
 }
 
 // ── Audio trim commands ──────────────────────────────────────────────────
 
 #[tauri::command]
-fn get_audio_duration(file_path: String) -> Result<u64, String> {
-    audio::get_audio_duration(&file_path).map_err(|e| e.to_string())
+fn get_audio_duration(file_path: String) -> Result<u64, HubError> {
+    audio::get_audio_duration(&file_path).map_err(|e| HubError::file(&file_path, e))
+}
+
+/// Play part of a file before importing it, with the fades and gain the
+/// import will apply, routed like library previews, replacing the preview
+/// playing. Returns before it's decoded (see sound_preview.rs). Without the
+/// pipeline it plays through the default output.
+#[tauri::command]
+fn preview_trim(state: State<SharedState>, source_path: String, trim: audio::Trim) -> Result<(), HubError> {
+    let path = std::path::PathBuf::from(&source_path);
+    if !path.is_file() {
+        return Err(HubError::file(&path, anyhow::anyhow!("Cannot open: {source_path}")));
+    }
+    if trim.end_ms <= trim.start_ms {
+        return Err(i18n::t("error-trim-empty").into());
+    }
+    let route = state.lock().unwrap().audio_config.preview_route;
+    sound_preview::play(sound_preview::Request { path, trim, route });
+    Ok(())
 }
 
+/// Cut the trim or library preview playing.
 #[tauri::command]
-fn preview_trim(source_path: String, start_ms: u64, end_ms: u64) -> Result<(), String> {
-    audio::preview_trim(&source_path, start_ms, end_ms).map_err(|e| e.to_string())
+fn stop_preview() {
+    sound_preview::stop();
+}
+
+// ── Per-key events (press/release from every shortcut backend) ──────────
+
+/// When each key went down, for hold durations on release.
+static PRESSED_AT: std::sync::Mutex<[Option<std::time::Instant>; 8]> = std::sync::Mutex::new([None; 8]);
+
+/// When each key last ran its action, for `KeyConfig::cooldown_ms`.
+static LAST_ACTION: std::sync::Mutex<[Option<std::time::Instant>; 8]> = std::sync::Mutex::new([None; 8]);
+
+/// Entry point for physical key events from every shortcut backend. A press
+/// toggles the key; both edges go to the frontend as `key-event`, the base
+/// for hold-style actions (push-to-talk, play-while-held, long-press).
+/// False if the event was dropped (a press inside the key's cooldown).
+fn on_key_event(app: &AppHandle, key_index: usize, pressed: bool) -> bool {
+    if key_index >= 8 { return false; }
+
+    // Presses inside the key's cooldown are dropped along with their release
+    if pressed {
+        let cooldown_ms = app.state::<SharedState>().lock().unwrap().keys[key_index].cooldown_ms;
+        let mut last_action = LAST_ACTION.lock().unwrap();
+        let now = std::time::Instant::now();
+        if last_action[key_index].is_some_and(|t| now.duration_since(t).as_millis() < cooldown_ms as u128) {
+            info!("[KEY-SHORTCUT] key={} in cooldown ({}ms), ignored", key_index, cooldown_ms);
+            return false;
+        }
+        last_action[key_index] = Some(now);
+        drop(last_action);
+        usage::record_key(key_index);
+    }
+
+    let held_ms = {
+        let mut pressed_at = PRESSED_AT.lock().unwrap();
+        if pressed {
+            pressed_at[key_index] = Some(std::time::Instant::now());
+            None
+        } else {
+            let Some(t) = pressed_at[key_index].take() else { return false };
+            Some(t.elapsed().as_millis() as u64)
+        }
+    };
+
+    if pressed {
+        do_toggle_key(app, key_index);
+        onboarding::sync_key_pressed(app);
+    } else {
+        info!("[KEY-SHORTCUT] key={} released after {:?}ms", key_index, held_ms);
+        trigger::release(app, key_index);
+    }
+    let _ = app.emit("key-event", KeyEvent { key_index, pressed, held_ms });
+    true
 }
 
 // ── Per-key toggle (triggered by physical keypress via global shortcut) ──
 
+/// Toggle the app a binding condition follows, if it follows one. Returns the
+/// usage action name.
+fn toggle_followed_app(app: &AppHandle, condition: &state::Condition, settings: &state::AppSettings) -> Option<String> {
+    let method = settings.replay_method;
+    if let Some(meeting) = meeting::Meeting::of(condition) {
+        meeting::toggle_mute(meeting, method);
+        return Some(format!("meeting:{}:toggle-mute", meeting.id()));
+    }
+    if let Some(action) = obs::toggle(condition) {
+        return Some(action);
+    }
+    match condition {
+        state::Condition::DiscordMuted => {
+            discord::toggle_mute();
+            Some("discord:toggle-mute".into())
+        }
+        state::Condition::MediaPlaying => {
+            media::play_pause(method);
+            Some("media:play-pause".into())
+        }
+        state::Condition::MixerMuted { mixer, channel } => {
+            mixer::toggle_mute(*mixer, channel.clone());
+            Some(format!("mixer:{}:{}:toggle-mute", mixer.id(), channel))
+        }
+        state::Condition::FocusRunning => {
+            if !pomodoro::stop() {
+                pomodoro::start(app, settings.focus.focus_min);
+            }
+            Some("focus:toggle".into())
+        }
+        state::Condition::SoundboardRunning => {
+            // Starting the pipeline takes the state lock the caller holds
+            let app = app.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = toggle_soundboard(&app) {
+                    warn!("[audio] {}", e);
+                }
+            });
+            Some("soundboard:toggle".into())
+        }
+        state::Condition::CalendarMeeting { url, lead_min, open_link: true } => {
+            calendar::open_link(url, *lead_min);
+            Some("calendar:open-link".into())
+        }
+        state::Condition::ModeActive { .. } => {
+            // Switching takes the state lock the caller holds
+            let app = app.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = cycle_mode(app) {
+                    warn!("[mode] {}", e);
+                }
+            });
+            Some("mode:cycle".into())
+        }
+        _ => None,
+    }
+}
+
 fn do_toggle_key(app: &AppHandle, key_index: usize) {
+    let pressed = std::time::Instant::now();
     let state = app.state::<SharedState>();
-    let (snapshot, sound_filename) = {
+    let (snapshot, playlist, gain) = {
         let mut st = state.lock().unwrap();
         if key_index >= 8 { return; }
 
-        let old = st.keys[key_index].active_slot;
-        st.keys[key_index].active_slot = match old {
-            ActiveSlot::A => ActiveSlot::B,
-            ActiveSlot::B => ActiveSlot::A,
+        // Sound first, so it starts while the LEDs are written (see trigger.rs)
+        let press = st.keys[key_index].press_mode;
+        let sound = st.audio_config.key_sounds[key_index]
+            .as_ref()
+            .and_then(|sound_id| st.audio_config.sound_library.iter().find(|e| &e.id == sound_id));
+        let playlist = key_playlist(&st, key_index);
+        let gain = key_sound_gain(&st, key_index);
+        let audible = (sound.is_some() || playlist.is_some()) && gain > 0.0 && press.runs_action() && {
+            let key = &st.keys[key_index];
+            let ready = cooldown::try_play(key_index, key.sound_cooldown_ms, pressed);
+            if !ready {
+                info!("[KEY-SHORTCUT] key={} sound cooling down ({}ms), skipped", key_index, key.sound_cooldown_ms);
+            }
+            ready
         };
-        let new_slot = st.keys[key_index].active_slot;
+        if let Some(entry) = sound.filter(|_| audible) {
+            info!("[KEY-SHORTCUT] key={} sound={}", key_index, entry.filename);
+            usage::record_sound(&entry.id);
+            trigger::play(app, trigger::Trigger {
+                key_index,
+                sound_id: entry.id.clone(),
+                filename: entry.filename.clone(),
+                route: entry.route(state::SoundRoute::Both),
+                gain,
+                mode: st.keys[key_index].sound_mode,
+                pressed,
+            });
+        }
 
-        info!("[KEY-SHORTCUT] key={} {:?}→{:?} override={}",
-              key_index, old, new_slot, st.keys[key_index].override_enabled);
+        // Keys following an app toggle it instead; the binding switches the
+        // slot once the app reports the new state
+        let followed = st.keys[key_index].binding.as_ref()
+            .filter(|_| press.runs_action())
+            .and_then(|b| toggle_followed_app(app, &b.condition, &st.settings));
+        let fading = followed.is_none() && press.changes_led() && st.settings.fade_ms > 0;
+        if let Some(action) = followed {
+            info!("[KEY-SHORTCUT] key={} {}", key_index, action);
+            announce_action(app, &st, key_index, &action_words(&action));
+            usage::record_action(&action);
+        } else if press.changes_led() {
+            let old = advance_key_slot(app, &mut st, key_index);
+            let new_slot = &st.keys[key_index].active().name;
 
-        if let Some(ref dev) = st.device {
-            apply_key_to_device(dev, key_index as u8, &st.keys[key_index]);
-        }
-        persist_state(&st.keys, &st.audio_config, &st.keymaps);
-        // Resolve sound filename from key_sounds → sound_library lookup
-        let filename = st.audio_config.key_sounds[key_index]
-            .as_ref()
-            .and_then(|sound_id| {
-                st.audio_config.sound_library.iter()
-                    .find(|e| &e.id == sound_id)
-                    .map(|e| e.filename.clone())
-            });
-        (st.snapshot(), filename)
-    };
+            info!("[KEY-SHORTCUT] key={} {:?}→{:?} override={}",
+                  key_index, old.name, new_slot, st.keys[key_index].override_enabled);
+            announce_slot(app, &st, key_index);
 
-    // Play sound if assigned
-    if let Some(ref filename) = sound_filename {
-        info!("[KEY-SHORTCUT] key={} sound={}", key_index, filename);
-        if let Ok(path) = audio::resolve_sound_path(filename) {
-            let pipeline_state = app.state::<ManagedAudioPipeline>();
-            let pl = pipeline_state.0.lock().unwrap();
-            if let Some(ref pipeline) = *pl {
-                if let Err(e) = pipeline.play_sound(&path) {
-                    warn!("[audio] Failed to play sound for key {}: {}", key_index, e);
-                }
-            } else {
-                // Fallback: play through default output when soundboard is not running
-                drop(pl);
-                let path_str = path.to_str().unwrap_or("");
-                let dur = audio::get_audio_duration(path_str).unwrap_or(60000);
-                if let Err(e) = audio::preview_trim(path_str, 0, dur) {
-                    warn!("[audio] Fallback play failed for key {}: {}", key_index, e);
+            persist_state(&st);
+            usage::record_action(&format!("slot:{}:{}", key_index, new_slot));
+        }
+        if let Some(scene) = st.keys[key_index].light_scene.as_ref().filter(|_| press.runs_action()) {
+            match lighting::scene_target(&st, scene) {
+                Some(target) => {
+                    lighting::trigger_scene(target, scene.scene.clone());
+                    announce_action(app, &st, key_index, &i18n::t_with("action-light-scene", &[("scene", &scene.scene)]));
+                    usage::record_action(&format!("light-scene:{}:{}", scene.target, scene.scene));
                 }
+                None => warn!("[lighting] key={} scene target {:?} is gone", key_index, scene.target),
             }
         }
+        if audible {
+            cooldown::dim(app, &st, key_index, fading);
+        }
+        (st.snapshot(), playlist.filter(|_| audible), gain)
+    };
+
+    if let Some((playlist, sounds)) = playlist {
+        play_key_playlist(app, key_index, &playlist, sounds, gain);
     }
 
     // Emit event so frontend updates its state
     let _ = app.emit("state-updated", &snapshot);
 }
 
-// ── Global toggle helper (used by tray menu) ────────────────────────────
+/// A playlist's sounds as (id, filename, route).
+type PlaylistSounds = Vec<(String, String, state::SoundRoute)>;
+
+/// The key's playlist and its sounds.
+fn key_playlist(st: &AppState, key_index: usize) -> Option<(state::Playlist, PlaylistSounds)> {
+    let id = st.audio_config.key_sounds[key_index].as_ref()?;
+    let playlist = st.audio_config.playlists.iter().find(|p| &p.id == id)?;
+    let sounds = playlist.sounds.iter()
+        .filter_map(|id| st.audio_config.sound_library.iter().find(|e| &e.id == id))
+        .map(|e| (e.id.clone(), e.filename.clone(), e.route(state::SoundRoute::Both)))
+        .collect();
+    Some((playlist.clone(), sounds))
+}
+
+/// Start the key's playlist, or skip to its next sound if it's playing.
+/// Sounds whose file is gone are left out. Needs the pipeline running.
+fn play_key_playlist(
+    app: &AppHandle,
+    key_index: usize,
+    playlist: &state::Playlist,
+    sounds: PlaylistSounds,
+    gain: f32,
+) {
+    let pipeline_state = app.state::<ManagedAudioPipeline>();
+    let pl = pipeline_state.0.lock().unwrap();
+    let Some(ref pipeline) = *pl else {
+        warn!("[audio] Playlist {:?} on key {} needs the soundboard running", playlist.name, key_index);
+        return;
+    };
+    if pipeline.playing_playlist().as_deref() == Some(playlist.id.as_str()) {
+        info!("[KEY-SHORTCUT] key={} playlist {:?} skipped ahead", key_index, playlist.name);
+        pipeline.skip_playlist();
+        return;
+    }
+    let mut missing = Vec::new();
+    let mut paths = Vec::new();
+    for (sound_id, filename, route) in sounds {
+        match audio::resolve_sound_path(&filename) {
+            Ok(path) => paths.push((path, route)),
+            Err(_) => missing.push(sound_id),
+        }
+    }
+    info!("[KEY-SHORTCUT] key={} playlist {:?}", key_index, playlist.name);
+    usage::record_action(&format!("playlist:{}", playlist.id));
+    if let Err(e) = pipeline.play_playlist(&playlist.id, &paths, playlist.loop_last, gain) {
+        warn!("[audio] Failed to play playlist for key {}: {}", key_index, e);
+    }
+    drop(pl);
+    for sound_id in missing {
+        mark_sound_missing(app, &sound_id);
+    }
+}
+
+/// Gain for a key's sound: the key's offset, capped by quiet hours (0 mutes,
+/// as does muting key sounds).
+fn key_sound_gain(st: &AppState, key_index: usize) -> f32 {
+    if st.audio_config.sounds_muted {
+        return 0.0;
+    }
+    quiet::cap(st, dsp::db_to_lin(st.audio_config.key_sound_gain_db[key_index]))
+}
 
-fn do_toggle(app: &AppHandle) -> Result<String, String> {
-    info!("⚠️ [GLOBAL TOGGLE] do_toggle() called — this toggles ALL keys!");
+/// A sound's file is gone: flag the entry and emit `sound-missing` so the UI
+/// can offer `relink_sound`.
+fn mark_sound_missing(app: &AppHandle, sound_id: &str) {
     let state = app.state::<SharedState>();
-    let result = {
+    let (snapshot, entry) = {
         let mut st = state.lock().unwrap();
-        st.active_slot = match st.active_slot {
-            ActiveSlot::A => ActiveSlot::B,
-            ActiveSlot::B => ActiveSlot::A,
-        };
-        let new_slot = st.active_slot;
-        // Toggle each key's individual slot
-        for key in st.keys.iter_mut() {
-            key.active_slot = match key.active_slot {
-                ActiveSlot::A => ActiveSlot::B,
-                ActiveSlot::B => ActiveSlot::A,
-            };
+        let Some(entry) = st.audio_config.sound_library.iter_mut().find(|e| e.id == sound_id) else { return };
+        entry.missing = true;
+        let entry = entry.clone();
+        persist_state(&st);
+        (st.snapshot(), entry)
+    };
+    let _ = app.emit("state-updated", &snapshot);
+    let _ = app.emit("sound-missing", &entry);
+}
+
+/// Stop sounds, or flip the key sounds mute (app hotkeys and the tray).
+fn run_app_hotkey(app: &AppHandle, action: state::AppHotkey) {
+    match action {
+        state::AppHotkey::StopSounds => stop_all_sounds(app.clone()),
+        state::AppHotkey::MuteSounds => {
+            let muted = app.state::<SharedState>().lock().unwrap().audio_config.sounds_muted;
+            set_sounds_muted_on(app, !muted);
+            refresh_tray_menu(app);
         }
-        if let Some(ref dev) = st.device {
-            apply_all_to_device(dev, &st.keys);
+    }
+}
+
+// ── Tray menu ───────────────────────────────────────────────────────────
+
+const TRAY_ID: &str = "main";
+
+/// What the tray menu shows, read under the state lock.
+struct TrayState {
+    keys: [KeyConfig; 8],
+    modes: Vec<String>,
+    active_mode: Option<String>,
+    sounds_muted: bool,
+}
+
+impl TrayState {
+    fn of(st: &AppState) -> Self {
+        Self {
+            keys: st.keys.clone(),
+            modes: st.modes.iter().map(|m| m.name.clone()).collect(),
+            active_mode: st.active_mode.clone(),
+            sounds_muted: st.audio_config.sounds_muted,
+        }
+    }
+}
+
+/// Tray menu, with a Keys submenu that toggles each key by its label/icon and
+/// a Modes submenu (when any are saved) that switches between them.
+fn build_tray_menu(app: &AppHandle, tray: &TrayState) -> tauri::Result<Menu<tauri::Wry>> {
+    let mut key_menu = SubmenuBuilder::new(app, i18n::t("tray-keys"));
+    for (i, key) in tray.keys.iter().enumerate() {
+        let item = MenuItemBuilder::with_id(format!("key-{i}"), key.display_name(i)).build(app)?;
+        key_menu = key_menu.item(&item);
+    }
+    let mut mode_menu = SubmenuBuilder::new(app, i18n::t("tray-modes"));
+    for (i, name) in tray.modes.iter().enumerate() {
+        let item = CheckMenuItemBuilder::with_id(format!("mode-{i}"), name)
+            .checked(tray.active_mode.as_ref() == Some(name))
+            .build(app)?;
+        mode_menu = mode_menu.item(&item);
+    }
+    let show = MenuItemBuilder::with_id("show", i18n::t("tray-show")).build(app)?;
+    let dnd = MenuItemBuilder::with_id("dnd", i18n::t("tray-dnd")).build(app)?;
+    let stop_sounds = MenuItemBuilder::with_id("stop-sounds", i18n::t("tray-stop-sounds")).build(app)?;
+    let mute_sounds = CheckMenuItemBuilder::with_id("mute-sounds", i18n::t("tray-mute-soundboard"))
+        .checked(tray.sounds_muted)
+        .build(app)?;
+    let quit = MenuItemBuilder::with_id("quit", i18n::t("tray-quit")).build(app)?;
+    let mut menu = MenuBuilder::new(app)
+        .item(&show)
+        .item(&key_menu.build()?);
+    if !tray.modes.is_empty() {
+        menu = menu.item(&mode_menu.build()?);
+    }
+    menu.item(&dnd)
+        .separator()
+        .item(&stop_sounds)
+        .item(&mute_sounds)
+        .separator()
+        .item(&quit)
+        .build()
+}
+
+/// Bring the window back from the tray (or from the taskbar, if minimized).
+fn show_main_window(app: &AppHandle) {
+    if let Some(w) = app.get_webview_window("main") {
+        let _ = w.unminimize();
+        let _ = w.show();
+        let _ = w.set_focus();
+    }
+}
+
+/// Whether quitting should ask first: a sound or playlist is still playing.
+fn quit_needs_confirm<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.state::<SharedState>().lock().unwrap().settings.window.confirm_quit
+        && app.state::<ManagedAudioPipeline>().0.lock().unwrap().as_ref().is_some_and(|p| p.is_playing())
+}
+
+/// Quit from the tray or the close button, asking first if set to.
+fn request_quit(app: &AppHandle) {
+    if !quit_needs_confirm(app) {
+        quit(app);
+        return;
+    }
+    show_main_window(app);
+    let handle = app.clone();
+    app.dialog()
+        .message(i18n::t("quit-confirm-message"))
+        .title(i18n::t("quit-confirm-title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(i18n::t("quit-confirm-quit"), i18n::t("quit-confirm-cancel")))
+        .show(move |confirmed| {
+            if confirmed {
+                quit(&handle);
+            }
+        });
+}
+
+fn quit(app: &AppHandle) {
+    usage::flush();
+    profile::clear_startup();
+    app.exit(0);
+}
+
+/// Rebuild the tray menu after key labels/icons change. Locks the state.
+fn refresh_tray_menu(app: &AppHandle) {
+    let state = app.state::<SharedState>();
+    let tray_state = TrayState::of(&state.lock().unwrap());
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+    match build_tray_menu(app, &tray_state) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                warn!("[tray] Failed to set menu: {e}");
+            }
+        }
+        Err(e) => warn!("[tray] Failed to build menu: {e}"),
+    }
+}
+
+// ── IPC Commands ────────────────────────────────────────────────────────
+
+/// Declare the IPC commands once: `invoke_handler` registers them and
+/// `COMMANDS` names them, so lock.rs's `ALLOWED` can be checked against it.
+macro_rules! commands {
+    ($($command:ident),* $(,)?) => {
+        /// Every command the frontend can invoke.
+        #[cfg(test)]
+        pub(crate) const COMMANDS: &[&str] = &[$(stringify!($command)),*];
+
+        fn invoke_handler() -> impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static {
+            tauri::generate_handler![$($command),*]
         }
-        persist_state(&st.keys, &st.audio_config, &st.keymaps);
-        new_slot.to_string()
     };
-    info!("⚠️ [GLOBAL TOGGLE] emitting slot-toggled={}", result);
-    let _ = app.emit("slot-toggled", &result);
-    Ok(result)
 }
 
+commands![
+    connect_device,
+    reload_definitions,
+    disconnect_device,
+    get_state,
+    set_key_color,
+    set_all_key_colors,
+    toggle_key_slot,
+    add_key_slot,
+    remove_key_slot,
+    rename_key_slot,
+    select_key_slot,
+    copy_key_config,
+    paste_key_config,
+    apply_color_to_all,
+    set_key_binding,
+    apply_colors,
+    disable_all_overrides,
+    get_keymap,
+    set_keycode,
+    restore_keymaps,
+    keep_device_keymaps,
+    start_keycode_capture,
+    cancel_keycode_capture,
+    set_key_override,
+    set_key_source,
+    set_key_cooldown,
+    set_key_sound_cooldown,
+    set_key_sound_mode,
+    set_key_press_mode,
+    set_key_light_scene,
+    set_key_sound_gain,
+    set_key_label,
+    set_key_icon,
+    restore_defaults,
+    // Palette & themes
+    add_swatch,
+    remove_swatch,
+    save_theme,
+    apply_theme,
+    preview_theme,
+    end_theme_preview,
+    delete_theme,
+    // Key groups
+    save_key_group,
+    delete_key_group,
+    save_mode,
+    delete_mode,
+    set_mode,
+    cycle_mode,
+    save_rule,
+    delete_rule,
+    get_device_info,
+    get_device_health,
+    get_connection_history,
+    device_indication,
+    bootloader_jump,
+    preview_reset,
+    eeprom_reset,
+    dynamic_keymap_reset,
+    macro_reset,
+    save_custom,
+    get_rgb_matrix,
+    set_rgb_brightness,
+    set_rgb_effect,
+    set_rgb_speed,
+    set_rgb_color,
+    save_rgb_matrix,
+    restore_rgb_matrix,
+    keep_device_rgb_matrix,
+    toggle_dnd,
+    // Soundboard
+    list_audio_devices,
+    set_soundboard,
+    set_audio_input_device,
+    choose_audio_device,
+    stop_all_sounds,
+    set_sounds_muted,
+    set_app_hotkey,
+    set_second_input_device,
+    set_second_input_volume,
+    set_audio_output_device,
+    set_sound_volume,
+    set_mic_volume,
+    set_input_gain,
+    set_processing_stage,
+    set_processing_order,
+    set_agc,
+    set_stop_word,
+    get_audio_metrics,
+    // Sound library
+    add_to_sound_library,
+    add_to_sound_library_trimmed,
+    set_sound_format,
+    set_preview_route,
+    compress_sound_library,
+    relink_sound,
+    get_library_stats,
+    get_library_audio_info,
+    clean_sound_library,
+    set_library_quota,
+    remove_from_sound_library,
+    rename_sound,
+    set_sound_monitor_only,
+    save_playlist,
+    delete_playlist,
+    skip_playlist,
+    stop_playlist,
+    set_key_sound,
+    preview_library_sound,
+    // Audio trim
+    get_audio_duration,
+    preview_trim,
+    stop_preview,
+    // Settings
+    set_input_grab,
+    set_replay_method,
+    set_dedup_window,
+    set_brightness_curve,
+    set_fade_duration,
+    set_accessibility_cues,
+    set_now_playing,
+    set_discord_settings,
+    set_lighting,
+    pair_hue_bridge,
+    start_config_share,
+    stop_config_share,
+    import_shared_config,
+    set_accent_key_colors,
+    get_system_theme,
+    set_language,
+    list_languages,
+    set_window_settings,
+    set_hid_timing,
+    set_cooperative,
+    lock_config,
+    unlock_config,
+    skip_onboarding_step,
+    finish_onboarding,
+    reset_onboarding,
+    set_local_api,
+    set_progress,
+    clear_progress,
+    notify_key,
+    dismiss_notification,
+    get_now_playing,
+    set_quiet_hours,
+    override_quiet_hours,
+    get_usage_stats,
+    start_focus,
+    stop_focus,
+    set_focus_settings,
+    get_focus_summary,
+    set_game_settings,
+    reset_usage_stats,
+    export_usage_csv,
+    // Keycodes
+    list_keycodes,
+    // Permissions
+    check_permissions,
+    get_app_info,
+    get_recent_errors,
+    open_config_dir,
+    open_sounds_dir,
+    open_settings_url,
+];
+
 // ── App Entry ───────────────────────────────────────────────────────────
 
 pub fn run() {
+    // Safe mode: explicit flag, or automatically after repeated startup crashes
+    let crashed_startups = profile::record_startup();
+    let safe_mode = std::env::args().any(|a| a == "--safe-mode")
+        || crashed_startups >= profile::CRASH_THRESHOLD;
+    if safe_mode {
+        warn!("[setup] Safe mode ({} unfinished startups before this one)", crashed_startups);
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_window_state::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .manage(std::sync::Mutex::new({
-            let mut state = AppState::default();
+            let mut state = AppState {
+                safe_mode,
+                ..AppState::default()
+            };
             // Restore key colors + audio config from last session
-            if let Some((keys, audio_cfg, keymaps)) = profile::load_state() {
-                state.keys = keys;
-                if let Some(cfg) = audio_cfg {
-                    state.audio_config = cfg;
-                }
-                if let Some(km) = keymaps {
-                    state.keymaps = km;
-                }
+            if let Some(restored) = profile::load_state() {
+                apply_restored(&mut state, restored);
             }
             // Migrate legacy sound_files → sound_library + key_sounds
             if state.audio_config.sound_library.is_empty() {
@@ -1136,6 +4104,8 @@ pub fn run() {
                                 id: id.clone(),
                                 filename: filename.clone(),
                                 display_name,
+                                missing: false,
+                                monitor_only: false,
                             }
                         );
                         state.audio_config.key_sounds[i] = Some(id);
@@ -1151,31 +4121,114 @@ pub fn run() {
         }))
         .manage(ManagedAudioPipeline(std::sync::Mutex::new(None)))
         .setup(|app| {
+            // Count this startup as successful once the app has stayed up a while
+            std::thread::spawn(|| {
+                std::thread::sleep(profile::STARTUP_GRACE);
+                profile::clear_startup();
+            });
+
+            let safe_mode = app.state::<SharedState>().lock().unwrap().safe_mode;
+            if let Err(e) = definition::reload() {
+                error!("[definition] {e:#}, using the built-in definition");
+            }
+            brightness::set(app.state::<SharedState>().lock().unwrap().settings.brightness_curve);
+            let language = app.state::<SharedState>().lock().unwrap().settings.language.clone();
+            if let Err(e) = i18n::set(language.as_deref()) {
+                warn!("[i18n] {e}, following the system language");
+                let _ = i18n::set(None);
+            }
+
             // Install keyboard hook early so it's ready before device connects
-            keyboard_hook::init();
+            if !safe_mode {
+                let state = app.state::<SharedState>();
+                let (grab, dedup_ms) = {
+                    let st = state.lock().unwrap();
+                    (st.settings.grab_device_input, st.settings.dedup_ms)
+                };
+                keyboard_hook::set_grab(grab);
+                keyboard_hook::set_dedup_ms(dedup_ms);
+                keyboard_hook::init();
+                #[cfg(target_os = "linux")]
+                wayland_shortcuts::init(app.handle());
+            }
+
+            if !safe_mode {
+                api::set_enabled(app.state::<SharedState>().lock().unwrap().settings.local_api);
+                api::start(app.handle().clone());
+                game::configure(&app.state::<SharedState>().lock().unwrap().settings.game);
+                game::start(app.handle().clone());
+                layout::start(app.handle().clone());
+            }
 
             // Pre-register shortcuts from persisted keymaps (instant response on startup)
-            {
-                let state = app.state::<SharedState>();
-                let st = state.lock().unwrap();
-                if st.keymaps.iter().any(|&k| k != 0) {
+            if !safe_mode {
+                let keymaps = app.state::<SharedState>().lock().unwrap().keymaps;
+                if keymaps.iter().any(|&k| k != 0) {
                     info!("[setup] Pre-registering shortcuts from persisted keymaps");
-                    keyboard_hook::register_shortcuts(app.handle(), &st.keymaps);
+                    keyboard_hook::register_shortcuts(app.handle(), &keymaps);
                 }
             }
 
+            // Slot bindings (mic muted, OBS recording, …)
+            if !safe_mode {
+                watchers::start(app.handle().clone());
+            }
+
+            // Desk lights mirroring a key or the RGB matrix
+            if !safe_mode {
+                lighting::start(app.handle().clone());
+            }
+
+            // OS dark mode and accent color
+            appearance::start(app.handle().clone());
+
+            // Reconnect when the device stops acking mid-sequence
+            watchdog::start(app.handle().clone());
+
+            // Connect once another app lets go of the device
+            coexist::start(app.handle().clone());
+
+            // Uptime history, to spot device reboots
+            heartbeat::start(app.handle().clone());
+            trigger::start(app.handle().clone());
+            sound_preview::start(app.handle().clone());
+
+            // Scheduled and battery LED rules
+            rules::start(app.handle().clone());
+
+            // Sounds dropped into (or deleted from) the sounds folder by hand
+            library_watch::start(app.handle().clone());
+
             // Persist initial state to disk (ensures state.json exists)
             {
                 let state = app.state::<SharedState>();
                 let st = state.lock().unwrap();
-                persist_state(&st.keys, &st.audio_config, &st.keymaps);
+                persist_state(&st);
             }
 
             // Auto-start audio pipeline if both devices are configured
-            {
+            if !safe_mode {
                 let state = app.state::<SharedState>();
                 let pipeline_state = app.state::<ManagedAudioPipeline>();
-                try_auto_start_pipeline(&state, &pipeline_state);
+                try_auto_start_pipeline(app.handle(), &state, &pipeline_state);
+            }
+
+            // Pipeline health for the sound view; nothing is sent while it's stopped.
+            // The first stream error of a pipeline raises a toast to restart it.
+            {
+                let app = app.handle().clone();
+                let mut stream_errors = 0;
+                std::thread::spawn(move || loop {
+                    std::thread::sleep(AUDIO_METRICS_INTERVAL);
+                    let metrics = app.state::<ManagedAudioPipeline>().0.lock().unwrap().as_ref().and_then(|p| p.metrics());
+                    if let Some(metrics) = metrics {
+                        if metrics.stream_errors > 0 && stream_errors == 0 {
+                            toast::pipeline_stopped(&app, metrics.last_stream_error.as_deref());
+                        }
+                        stream_errors = metrics.stream_errors;
+                        let _ = app.emit("audio-metrics", metrics);
+                    }
+                });
             }
 
             // Register plugins
@@ -1196,35 +4249,61 @@ pub fn run() {
                 app.handle().plugin(
                     tauri_plugin_global_shortcut::Builder::new()
                         .with_handler(move |app, shortcut, event| {
-                            if event.state() != ShortcutState::Pressed { return; }
+                            if REPLAYING.load(std::sync::atomic::Ordering::SeqCst) { return; }
                             let shortcut_str = format!("{}", shortcut);
                             let state = app.state::<SharedState>();
+                            let app_hotkey = state.lock().unwrap().app_hotkeys.get(&shortcut_str).map(|(action, _)| *action);
+                            if let Some(action) = app_hotkey {
+                                if event.state() == ShortcutState::Pressed {
+                                    run_app_hotkey(app, action);
+                                }
+                                return;
+                            }
                             let entry = {
                                 let st = state.lock().unwrap();
                                 st.shortcut_map.get(&shortcut_str).cloned()
                             };
+                            // Releases of keys that get replayed can be lost: the grab is
+                            // dropped around the replay, so the key-up may go to the app.
+                            if event.state() == ShortcutState::Released {
+                                if let Some((led_idx, _, _)) = entry {
+                                    on_key_event(app, led_idx, false);
+                                }
+                                return;
+                            }
                             if let Some((led_idx, keycode, register_str)) = entry {
+                                // Its release is still forwarded above, and on_key_event
+                                // ignores it: no press was recorded for it
+                                if replay::is_echo(keycode, std::time::Instant::now()) {
+                                    return;
+                                }
                                 info!("[SHORTCUT] \"{}\" → led={} replay=0x{:04X}",
                                       shortcut_str, led_idx, keycode);
-                                do_toggle_key(app, led_idx);
-
                                 // Skip keystroke replay for internal (sound-only) keycodes
-                                if is_internal_keycode(keycode) {
+                                // and for presses dropped by the key's cooldown
+                                if !on_key_event(app, led_idx, true) || is_internal_keycode(keycode) {
                                     return;
                                 }
 
-                                // Replay: unregister → simulate keystroke → re-register
-                                // Done on a thread to avoid blocking the UI.
+                                // Replay: the OS hotkey grab would swallow our synthetic
+                                // keystroke before the focused app sees it, so the shortcut
+                                // is released around the replay. Both calls are synchronous,
+                                // and REPLAYING makes the handler ignore anything that slips
+                                // through. Done on a thread to avoid blocking the UI.
+                                let method = state.lock().unwrap().settings.replay_method;
+                                usage::record_action(&format!("replay:{register_str}"));
                                 let app_clone = app.clone();
                                 std::thread::spawn(move || {
+                                    use std::sync::atomic::Ordering;
                                     use tauri_plugin_global_shortcut::GlobalShortcutExt;
+                                    let _guard = REPLAY_LOCK.lock().unwrap();
+                                    REPLAYING.store(true, Ordering::SeqCst);
                                     let _ = app_clone.global_shortcut()
                                         .unregister(register_str.as_str());
-                                    std::thread::sleep(std::time::Duration::from_millis(5));
-                                    simulate_qmk_keystroke(keycode);
-                                    std::thread::sleep(std::time::Duration::from_millis(30));
+                                    replay::replay_keystroke(keycode, method);
                                     let _ = app_clone.global_shortcut()
                                         .register(register_str.as_str());
+                                    REPLAYING.store(false, Ordering::SeqCst);
                                 });
                             } else {
                                 warn!("[SHORTCUT] Unmatched: \"{}\"", shortcut_str);
@@ -1233,47 +4312,46 @@ pub fn run() {
                         .build(),
                 )?;
                 // Shortcuts are registered dynamically in connect_device
-                // after reading the actual keymaps from the device.
+                // after reading the actual keymaps from the device; the app's
+                // own hotkeys don't wait for it.
+                register_app_hotkeys(app.handle());
             }
 
             // System tray
-            let show = MenuItemBuilder::with_id("show", "Show").build(app)?;
-            let toggle_leds = MenuItemBuilder::with_id("toggle", "Toggle LEDs").build(app)?;
-            let quit = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
-            let menu = MenuBuilder::new(app)
-                .item(&show)
-                .item(&toggle_leds)
-                .separator()
-                .item(&quit)
-                .build()?;
-
-            let _tray = TrayIconBuilder::new()
+            let state = app.state::<SharedState>();
+            let tray_state = TrayState::of(&state.lock().unwrap());
+            let menu = build_tray_menu(app.handle(), &tray_state)?;
+
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(Image::from_bytes(include_bytes!("../icons/icon.png"))?)
                 .tooltip("Deck-8 Hub")
                 .menu(&menu)
                 .on_menu_event(|app, event| match event.id().as_ref() {
-                    "show" => {
-                        if let Some(w) = app.get_webview_window("main") {
-                            let _ = w.show();
-                            let _ = w.set_focus();
+                    "show" => show_main_window(app),
+                    "dnd" => match toggle_dnd(app.state::<SharedState>()) {
+                        Ok(snapshot) => {
+                            let _ = app.emit("state-updated", &snapshot);
+                        }
+                        Err(e) => warn!("[dnd] {e}"),
+                    },
+                    "stop-sounds" => run_app_hotkey(app, state::AppHotkey::StopSounds),
+                    "mute-sounds" => run_app_hotkey(app, state::AppHotkey::MuteSounds),
+                    "quit" => request_quit(app),
+                    id => {
+                        if let Some(i) = id.strip_prefix("key-").and_then(|i| i.parse::<usize>().ok()) {
+                            do_toggle_key(app, i);
+                        } else if let Some(i) = id.strip_prefix("mode-").and_then(|i| i.parse::<usize>().ok()) {
+                            let name = app.state::<SharedState>().lock().unwrap().modes.get(i).map(|m| m.name.clone());
+                            if let Some(Err(e)) = name.map(|name| switch_mode(app, &name)) {
+                                warn!("[mode] {e}");
+                            }
                         }
                     }
-                    "toggle" => {
-                        let _ = do_toggle(app);
-                    }
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    _ => {}
                 })
                 .on_tray_icon_event(|tray, event| {
                     if let tauri::tray::TrayIconEvent::Click { button, .. } = event {
                         if button == tauri::tray::MouseButton::Left {
-                            let app = tray.app_handle();
-                            if let Some(w) = app.get_webview_window("main") {
-                                let _ = w.show();
-                                let _ = w.set_focus();
-                            }
+                            show_main_window(tray.app_handle());
                         }
                     }
                 })
@@ -1282,54 +4360,37 @@ pub fn run() {
             Ok(())
         })
         .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                // Hide to tray instead of closing
-                let _ = window.hide();
-                api.prevent_close();
+            let app = window.app_handle();
+            let settings = app.state::<SharedState>().lock().unwrap().settings.window;
+            match event {
+                tauri::WindowEvent::CloseRequested { api, .. } => {
+                    api.prevent_close();
+                    match settings.close {
+                        state::CloseAction::HideToTray => {
+                            let _ = window.hide();
+                        }
+                        state::CloseAction::Exit => request_quit(app),
+                    }
+                }
+                // There is no minimize event; a minimized window reports a resize
+                tauri::WindowEvent::Resized(_) if settings.minimize_to_tray && window.is_minimized().unwrap_or(false) => {
+                    let _ = window.hide();
+                }
+                _ => {}
             }
         })
-        .invoke_handler(tauri::generate_handler![
-            connect_device,
-            get_state,
-            set_key_color,
-            toggle_slot,
-            toggle_key_slot,
-            apply_colors,
-            disable_all_overrides,
-            get_keymap,
-            set_keycode,
-            set_key_override,
-            restore_defaults,
-            get_device_info,
-            device_indication,
-            bootloader_jump,
-            eeprom_reset,
-            dynamic_keymap_reset,
-            macro_reset,
-            save_custom,
-            get_rgb_matrix,
-            set_rgb_brightness,
-            set_rgb_effect,
-            set_rgb_speed,
-            set_rgb_color,
-            save_rgb_matrix,
-            // Soundboard
-            list_audio_devices,
-            set_audio_input_device,
-            set_audio_output_device,
-            set_sound_volume,
-            set_mic_volume,
-            // Sound library
-            add_to_sound_library,
-            add_to_sound_library_trimmed,
-            remove_from_sound_library,
-            rename_sound,
-            set_key_sound,
-            preview_library_sound,
-            // Audio trim
-            get_audio_duration,
-            preview_trim,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(lock::gate(invoke_handler()))
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // A clean exit isn't a crash, however soon after startup
+                profile::clear_startup();
+                // Counts since the last periodic write
+                usage::flush();
+                // Streams first, then the Linux soundboard sink they play into
+                app.state::<ManagedAudioPipeline>().0.lock().unwrap().take();
+                virtual_sink::remove();
+            }
+        });
 }
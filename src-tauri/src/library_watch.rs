@@ -0,0 +1,127 @@
+// Keeps the sound library in step with the sounds folder: audio files dropped
+// in by hand get library entries, and entries whose file was deleted outside
+// the app are flagged `missing` (cleared if the file comes back).
+//
+// The folder is polled rather than watched through OS notifications. A new
+// file is adopted only once two scans in a row see it at the same size, so a
+// copy still in progress isn't picked up half-written and the app's own
+// imports (file first, entry right after) aren't adopted twice.
+
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+
+use log::{info, warn};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::audio;
+use crate::state::{SharedState, SoundEntry};
+
+const SCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Age past which a file the scan hasn't adopted or the app hasn't claimed
+/// is a leftover; anything younger may be a copy or import still underway.
+pub const SETTLED_AFTER: Duration = Duration::from_secs(SCAN_INTERVAL.as_secs() * 5);
+
+/// Extensions rodio can decode.
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "flac", "ogg", "m4a", "aac", "mp4"];
+
+/// Unreferenced files seen on the previous scan, with their size.
+#[derive(Default)]
+pub struct Reconciler {
+    pending: HashMap<String, u64>,
+}
+
+impl Reconciler {
+    /// Bring `library` in line with `files` (name, size) in the sounds folder.
+    /// True if anything changed.
+    pub fn reconcile(&mut self, library: &mut Vec<SoundEntry>, files: &[(String, u64)]) -> bool {
+        let mut changed = false;
+        for entry in library.iter_mut() {
+            let missing = !files.iter().any(|(name, _)| *name == entry.filename);
+            if entry.missing != missing {
+                info!("[library] {} {}", entry.filename, if missing { "is missing" } else { "is back" });
+                entry.missing = missing;
+                changed = true;
+            }
+        }
+
+        let mut pending = HashMap::new();
+        for (name, size) in files {
+            if !is_audio(name) || library.iter().any(|e| e.filename == *name) {
+                continue;
+            }
+            if self.pending.get(name) != Some(size) {
+                pending.insert(name.clone(), *size);
+                continue;
+            }
+            let stem = name.rsplit_once('.').map_or(name.as_str(), |(stem, _)| stem);
+            info!("[library] Adopting {}", name);
+            library.push(SoundEntry {
+                id: format!("{}{}", audio::uuid_simple(), library.len()),
+                filename: name.clone(),
+                display_name: stem.to_string(),
+                missing: false,
+                monitor_only: false,
+            });
+            changed = true;
+        }
+        self.pending = pending;
+        changed
+    }
+}
+
+pub fn is_audio(name: &str) -> bool {
+    name.rsplit_once('.')
+        .is_some_and(|(_, ext)| AUDIO_EXTENSIONS.iter().any(|a| ext.eq_ignore_ascii_case(a)))
+}
+
+/// Files (name, size) in the sounds folder.
+fn list() -> anyhow::Result<Vec<(String, u64)>> {
+    let mut files = Vec::new();
+    for file in fs::read_dir(audio::sounds_dir()?)? {
+        let file = file?;
+        let meta = file.metadata()?;
+        if meta.is_file() {
+            files.push((file.file_name().to_string_lossy().into_owned(), meta.len()));
+        }
+    }
+    Ok(files)
+}
+
+/// Start the folder scan thread (once, at startup).
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut reconciler = Reconciler::default();
+        let mut last_error = None;
+        loop {
+            match list() {
+                Ok(files) => {
+                    last_error = None;
+                    let state = app.state::<SharedState>();
+                    let snapshot = {
+                        let mut st = state.lock().unwrap();
+                        if !reconciler.reconcile(&mut st.audio_config.sound_library, &files) {
+                            None
+                        } else {
+                            crate::onboarding::advance(&mut st);
+                            crate::persist_state(&st);
+                            Some(st.snapshot())
+                        }
+                    };
+                    if let Some(snapshot) = snapshot {
+                        let _ = app.emit("state-updated", &snapshot);
+                    }
+                }
+                Err(e) => {
+                    let message = format!("{e:#}");
+                    if last_error.as_ref() != Some(&message) {
+                        warn!("[library] Can't scan the sounds folder: {}", message);
+                        last_error = Some(message);
+                    }
+                }
+            }
+            std::thread::sleep(SCAN_INTERVAL);
+        }
+    });
+}
@@ -0,0 +1,224 @@
+// Desk lighting: mirror a key's color (or the RGB matrix color) to Philips Hue
+// lights or a WLED strip, and trigger lighting scenes from keys.
+//
+// A background thread compares the mirrored color with the last one each
+// target took every `TICK` and sends it the changes, so slot changes, themes,
+// bindings and fades all reach the lights without each path knowing about
+// them. A target only counts as up to date once a send succeeds; a failing
+// one is retried every `RETRY`, and a Hue bridge gets at most one change per
+// `HUE_INTERVAL` (it drops commands past about ten a second), so a fade
+// reaches it as the colors it's at when the next change is due, ending on the
+// final one. Both APIs are plain HTTP on the LAN:
+//
+// - Hue: the bridge's v1 REST API with an app key from `pair_hue` (press the
+//   bridge's link button first). Scenes are recalled on group 0 (all lights).
+// - WLED: the JSON API (`/json/state`); scenes are presets by number.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::protocol::HsvColor;
+use crate::state::{AppState, LightScene, LightSource, LightTarget, SharedState};
+
+const TICK: Duration = Duration::from_millis(250);
+const NET_TIMEOUT: Duration = Duration::from_secs(2);
+const HUE_INTERVAL: Duration = Duration::from_secs(1);
+const RETRY: Duration = Duration::from_secs(5);
+
+/// What a target was last sent.
+#[derive(Default)]
+struct Pushed {
+    /// The color it took.
+    color: Option<HsvColor>,
+    sent: Option<Instant>,
+    /// Why the last send failed, logged once.
+    error: Option<String>,
+}
+
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    std::thread::spawn(move || {
+        let mut pushed: Vec<(LightTarget, Pushed)> = Vec::new();
+        loop {
+            std::thread::sleep(TICK);
+            let (targets, color) = {
+                let state = app.state::<SharedState>();
+                let st = state.lock().unwrap();
+                (st.settings.lighting.targets.clone(), mirrored_color(&st))
+            };
+            // Mirroring off, or a target removed or edited: sent again from scratch
+            let Some(color) = color else {
+                pushed.clear();
+                continue;
+            };
+            pushed.retain(|(target, _)| targets.contains(target));
+            for target in targets {
+                let at = match pushed.iter().position(|(t, _)| *t == target) {
+                    Some(at) => at,
+                    None => {
+                        pushed.push((target.clone(), Pushed::default()));
+                        pushed.len() - 1
+                    }
+                };
+                let last = &mut pushed[at].1;
+                let wait = match target {
+                    _ if last.error.is_some() => RETRY,
+                    LightTarget::Hue { .. } => HUE_INTERVAL,
+                    LightTarget::Wled { .. } => TICK,
+                };
+                if last.color == Some(color) || last.sent.is_some_and(|sent| sent.elapsed() < wait) {
+                    continue;
+                }
+                last.sent = Some(Instant::now());
+                match set_color(&target, color) {
+                    Ok(()) => (last.color, last.error) = (Some(color), None),
+                    Err(e) => {
+                        let message = format!("{e:#}");
+                        if last.error.as_ref() != Some(&message) {
+                            warn!("[lighting] {}: {}", target.name(), message);
+                        }
+                        last.error = Some(message);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// The color the lights should show, if mirroring is on.
+pub fn mirrored_color(st: &AppState) -> Option<HsvColor> {
+    let lighting = &st.settings.lighting;
+    if lighting.targets.is_empty() {
+        return None;
+    }
+    match lighting.mirror? {
+        LightSource::Key { key_index } => st.keys.get(key_index).map(|k| k.active().color),
+        LightSource::Matrix => st.rgb_matrix.map(|m| HsvColor { h: m.color_h, s: m.color_s, v: m.brightness }),
+    }
+}
+
+/// Recall a key's scene on its target. Doesn't block.
+pub fn trigger_scene(target: LightTarget, scene: String) {
+    std::thread::spawn(move || {
+        let result = match &target {
+            LightTarget::Hue { bridge, user, .. } => hue_put(bridge, user, "groups/0/action", json!({ "scene": scene })),
+            LightTarget::Wled { host, .. } => match scene.trim().parse::<u8>() {
+                Ok(preset) => wled_post(host, json!({ "ps": preset })),
+                Err(_) => Err(anyhow!("WLED presets are numbers, not \"{scene}\"")),
+            },
+        };
+        match result {
+            Ok(()) => info!("[lighting] Scene {scene:?} on {}", target.name()),
+            Err(e) => warn!("[lighting] Scene {scene:?} on {} failed: {e:#}", target.name()),
+        }
+    });
+}
+
+/// The target a key's scene names, if it still exists.
+pub fn scene_target(st: &AppState, scene: &LightScene) -> Option<LightTarget> {
+    st.settings.lighting.targets.iter().find(|t| t.name() == scene.target).cloned()
+}
+
+/// Ask the bridge for an app key; the link button must have been pressed
+/// within the last 30 s.
+pub fn pair_hue(bridge: &str) -> Result<String> {
+    let agent = ureq::AgentBuilder::new().timeout(NET_TIMEOUT).build();
+    let body = agent.post(&format!("http://{bridge}/api"))
+        .send_string(&json!({ "devicetype": "deck8_hub#desktop" }).to_string())
+        .map_err(|e| anyhow!("Hue bridge {bridge} is not reachable: {e}"))?
+        .into_string()?;
+    let reply: Value = serde_json::from_str(&body).context("Unexpected answer from the Hue bridge")?;
+    if let Some(user) = reply[0]["success"]["username"].as_str() {
+        return Ok(user.to_string());
+    }
+    match reply[0]["error"]["description"].as_str() {
+        Some(e) if e.contains("link button") => bail!("Press the link button on the Hue bridge, then pair again"),
+        Some(e) => bail!("Hue bridge: {e}"),
+        None => bail!("Unexpected answer from the Hue bridge"),
+    }
+}
+
+fn set_color(target: &LightTarget, color: HsvColor) -> Result<()> {
+    match target {
+        LightTarget::Hue { bridge, user, lights, .. } => {
+            let state = hue_state(color);
+            for light in lights {
+                hue_put(bridge, user, &format!("lights/{light}/state"), state.clone())?;
+            }
+            Ok(())
+        }
+        LightTarget::Wled { host, .. } => wled_post(host, wled_state(color)),
+    }
+}
+
+/// Hue light state for a key color: hue 0–65535, sat and bri 0–254 (bri 1 is
+/// the dimmest "on").
+pub fn hue_state(color: HsvColor) -> Value {
+    if color.v == 0 {
+        return json!({ "on": false });
+    }
+    json!({
+        "on": true,
+        "hue": color.h as u32 * 65535 / 255,
+        "sat": color.s.min(254),
+        "bri": color.v.clamp(1, 254),
+    })
+}
+
+/// WLED state for a key color: the color at full value, dimmed with `bri`.
+pub fn wled_state(color: HsvColor) -> Value {
+    if color.v == 0 {
+        return json!({ "on": false });
+    }
+    let (r, g, b) = hsv_to_rgb(HsvColor { v: 255, ..color });
+    json!({ "on": true, "bri": color.v, "seg": [{ "col": [[r, g, b]] }] })
+}
+
+/// QMK-scale HSV (all 0–255) to RGB.
+pub fn hsv_to_rgb(color: HsvColor) -> (u8, u8, u8) {
+    let (h, s, v) = (color.h as u32, color.s as u32, color.v as u32);
+    if s == 0 {
+        return (v as u8, v as u8, v as u8);
+    }
+    // Six sectors of 43 (the last one a bit short)
+    let region = h * 6 / 256;
+    let rem = (h * 6) % 256;
+    let p = v * (255 - s) / 255;
+    let q = v * (255 - s * rem / 255) / 255;
+    let t = v * (255 - s * (255 - rem) / 255) / 255;
+    let (r, g, b) = match region {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    };
+    (r as u8, g as u8, b as u8)
+}
+
+fn hue_put(bridge: &str, user: &str, path: &str, body: Value) -> Result<()> {
+    let agent = ureq::AgentBuilder::new().timeout(NET_TIMEOUT).build();
+    let reply = agent.put(&format!("http://{bridge}/api/{user}/{path}"))
+        .send_string(&body.to_string())
+        .map_err(|e| anyhow!("Hue bridge {bridge}: {e}"))?
+        .into_string()?;
+    // The bridge answers 200 with a list of successes and errors
+    let reply: Value = serde_json::from_str(&reply).unwrap_or_default();
+    if let Some(e) = reply.as_array().into_iter().flatten().find_map(|r| r["error"]["description"].as_str()) {
+        bail!("Hue bridge: {e}");
+    }
+    Ok(())
+}
+
+fn wled_post(host: &str, body: Value) -> Result<()> {
+    let agent = ureq::AgentBuilder::new().timeout(NET_TIMEOUT).build();
+    agent.post(&format!("http://{host}/json/state"))
+        .set("Content-Type", "application/json")
+        .send_string(&body.to_string())
+        .map_err(|e| anyhow!("WLED {host}: {e}"))?;
+    Ok(())
+}
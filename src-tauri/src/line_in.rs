@@ -0,0 +1,101 @@
+// Second input: a second mic, or an instrument on a line-in, mixed into the
+// pipeline next to the mic with its own volume.
+//
+// It gets its own cpal stream, converted to the pipeline's format in the
+// callback (see convert.rs) and fed through a short ring buffer. `MicSource`
+// adds it after the processing chain, which is tuned for speech. The device
+// can change while the pipeline runs: each new stream comes with a new ring,
+// whose consumer is handed to the audio thread through `swaps`.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use log::{error, info};
+use ringbuf::traits::{Consumer, Observer, Producer, Split};
+use ringbuf::{HeapCons, HeapProd, HeapRb};
+use rodio::cpal::traits::StreamTrait;
+
+use crate::convert::Converter;
+
+/// Ring length; more than this backlog and the device's samples are dropped,
+/// which keeps a drifting clock from adding latency.
+const BUFFER_MS: u32 = 200;
+
+/// The second input on the audio thread.
+pub struct LineIn {
+    swaps: HeapCons<HeapCons<f32>>,
+    current: Option<HeapCons<f32>>,
+    channels: u64,
+    /// Samples read so far, so a new ring starts on a frame boundary.
+    read: u64,
+    volume: Arc<AtomicU32>,
+}
+
+impl LineIn {
+    /// Next sample at the input's volume, 0.0 without a device.
+    pub fn next_sample(&mut self) -> f32 {
+        if self.read.is_multiple_of(self.channels) {
+            while let Some(consumer) = self.swaps.try_pop() {
+                self.current = Some(consumer);
+            }
+        }
+        self.read += 1;
+        let sample = self.current.as_mut().and_then(|c| c.try_pop()).unwrap_or(0.0);
+        sample * f32::from_bits(self.volume.load(Ordering::Relaxed))
+    }
+}
+
+/// Opens and closes the second input's stream, owned by the pipeline.
+pub struct LineInControl {
+    swaps: HeapProd<HeapCons<f32>>,
+    stream: Option<rodio::cpal::Stream>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+/// The two sides of the second input, in the pipeline's format.
+pub fn new(channels: u16, sample_rate: u32, volume: Arc<AtomicU32>) -> (LineInControl, LineIn) {
+    let (swaps, swap_consumer) = HeapRb::new(4).split();
+    let control = LineInControl { swaps, stream: None, channels, sample_rate };
+    let voice = LineIn { swaps: swap_consumer, current: None, channels: channels.max(1) as u64, read: 0, volume };
+    (control, voice)
+}
+
+impl LineInControl {
+    /// Switch to `device`, or to none. The old stream closes first.
+    pub fn open(&mut self, device: Option<&str>) -> Result<()> {
+        self.stream = None;
+        let len = (self.sample_rate * BUFFER_MS / 1000) as usize * self.channels as usize;
+        let (mut producer, consumer) = HeapRb::<f32>::new(len.max(1)).split();
+        let stream = match device {
+            Some(name) => {
+                let dev = crate::audio::find_input_device(name)
+                    .context(format!("Input device not found: {}", name))?;
+                let config = crate::audio::input_config(&dev)?;
+                info!("[audio] Second input: {} ({}ch @ {}Hz, {:?})", name, config.channels(), config.sample_rate().0, config.sample_format());
+                let mut converter = Converter::new(config.channels(), config.sample_rate().0, self.channels, self.sample_rate);
+                let stream = crate::audio::build_input_stream(
+                    &dev,
+                    &config,
+                    move |data: &[f32]| {
+                        // Whole buffers or nothing, so channels stay aligned
+                        if producer.vacant_len() >= converter.max_output(data.len()) {
+                            converter.push(data, |s| {
+                                let _ = producer.try_push(s);
+                            });
+                        }
+                    },
+                    |err| error!("[audio] Second input stream error: {}", err),
+                )
+                .context("Failed to build second input stream")?;
+                stream.play().context("Failed to start second input stream")?;
+                Some(stream)
+            }
+            None => None,
+        };
+        self.swaps.try_push(consumer).ok().context("Second input switched too often")?;
+        self.stream = stream;
+        Ok(())
+    }
+}
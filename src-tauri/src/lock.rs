@@ -0,0 +1,139 @@
+// Configuration lock for shared streaming PCs and kiosks: while locked,
+// commands that change the setup (colors, keymaps, sounds, settings) or
+// write files are refused with `LOCKED`, and keys, modes, playback, volume
+// and the local API keep working.
+//
+// The check sits in front of the IPC handler (`gate`, see `run`), so a new
+// command is refused while locked unless it's added to `ALLOWED`. Locking
+// takes a PIN, kept salted and hashed in `settings.lock_pin` until the lock
+// is lifted with it. After `MAX_FAILURES` wrong PINs in a row, unlocking is
+// refused for `BACKOFF_MS`.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+use crate::error::HubError;
+
+const MAX_FAILURES: u32 = 5;
+const BACKOFF_MS: u64 = 60_000;
+
+/// Commands that still run while locked: reads, and what an operator does.
+pub const ALLOWED: &[&str] = &[
+    "get_state",
+    "connect_device",
+    "disconnect_device",
+    "toggle_key_slot",
+    "preview_theme",
+    "end_theme_preview",
+    "cancel_keycode_capture",
+    "get_keymap",
+    "get_device_info",
+    "get_device_health",
+    "get_connection_history",
+    "device_indication",
+    "get_rgb_matrix",
+    "toggle_dnd",
+    "set_mode",
+    "cycle_mode",
+    "list_audio_devices",
+    "stop_all_sounds",
+    "stop_preview",
+    "set_sounds_muted",
+    "set_sound_volume",
+    "set_mic_volume",
+    "get_audio_metrics",
+    "get_library_stats",
+    "get_library_audio_info",
+    "skip_playlist",
+    "stop_playlist",
+    "preview_library_sound",
+    "get_audio_duration",
+    "get_system_theme",
+    "list_languages",
+    "set_progress",
+    "clear_progress",
+    "notify_key",
+    "dismiss_notification",
+    "get_now_playing",
+    "override_quiet_hours",
+    "get_usage_stats",
+    "start_focus",
+    "stop_focus",
+    "get_focus_summary",
+    "list_keycodes",
+    "check_permissions",
+    "get_app_info",
+    "get_recent_errors",
+    "open_settings_url",
+    "unlock_config",
+];
+
+/// Mirrors `settings.lock_pin.is_some()` for the IPC gate.
+static LOCKED: AtomicBool = AtomicBool::new(false);
+/// (wrong PINs in a row, unix ms until which unlocking is refused)
+static FAILURES: Mutex<(u32, u64)> = Mutex::new((0, 0));
+
+pub fn set_locked(locked: bool) {
+    LOCKED.store(locked, Ordering::SeqCst);
+}
+
+pub fn is_locked() -> bool {
+    LOCKED.load(Ordering::SeqCst)
+}
+
+pub fn allows(command: &str) -> bool {
+    !is_locked() || ALLOWED.contains(&command)
+}
+
+/// Refuse locked commands before they run.
+pub fn gate<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        if !allows(invoke.message.command()) {
+            invoke.resolver.invoke_error(HubError::Locked.into());
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+/// "salt$sha256(salt + pin)" for a PIN of 4 to 8 digits.
+pub fn hash_pin(pin: &str) -> Result<String, String> {
+    if !(4..=8).contains(&pin.len()) || !pin.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(crate::i18n::t("error-pin-format"));
+    }
+    let salt = format!("{:016x}", RandomState::new().build_hasher().finish());
+    Ok(format!("{salt}${}", digest(&salt, pin)))
+}
+
+pub fn verify_pin(pin: &str, stored: &str) -> bool {
+    stored.split_once('$').is_some_and(|(salt, hash)| digest(salt, pin) == hash)
+}
+
+fn digest(salt: &str, pin: &str) -> String {
+    format!("{:x}", Sha256::digest(format!("{salt}{pin}").as_bytes()))
+}
+
+/// Check `pin` against `stored`, holding off guessing after repeated misses.
+pub fn try_unlock(pin: &str, stored: &str, now_ms: u64) -> Result<(), String> {
+    let mut failures = FAILURES.lock().unwrap();
+    if now_ms < failures.1 {
+        return Err(crate::i18n::t_with("error-pin-backoff", &[("seconds", &(failures.1 - now_ms).div_ceil(1000))]));
+    }
+    if verify_pin(pin, stored) {
+        *failures = (0, 0);
+        return Ok(());
+    }
+    failures.0 += 1;
+    if failures.0 >= MAX_FAILURES {
+        *failures = (0, now_ms + BACKOFF_MS);
+    }
+    Err(crate::i18n::t("error-pin-wrong"))
+}
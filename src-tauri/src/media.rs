@@ -0,0 +1,321 @@
+// Now playing: the OS media session's track and play/pause state.
+//
+// A monitor thread polls the session while something wants it (the "Now
+// playing" setting or a `MediaPlaying` slot binding), keeps the latest result
+// for `get_now_playing` and the binding, and emits `now-playing` when it
+// changes. A key bound to `MediaPlaying` toggles play/pause instead of its slot.
+//
+// - Linux: MPRIS through `playerctl`.
+// - Windows: the SMTC session manager through a long-running PowerShell that
+//   prints one line per poll (WinRT needs no extra crate that way); play/pause
+//   is the media key, which SMTC routes to the same session.
+// - macOS: MediaRemote is private, so Spotify and Music are asked directly
+//   through AppleScript (only when already running, so neither gets launched).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use log::warn;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::state::ReplayMethod;
+
+const POLL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NowPlaying {
+    pub playing: bool,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    /// The app playing it (MPRIS player name, SMTC app id, "Spotify"/"Music").
+    pub player: String,
+}
+
+#[derive(Default)]
+struct Status {
+    now: Option<NowPlaying>,
+    error: Option<String>,
+}
+
+static STATUS: Mutex<Status> = Mutex::new(Status { now: None, error: None });
+/// Stop flag of the running monitor.
+static MONITOR: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+
+/// Start or stop the monitor (called every watcher tick).
+pub fn set_active<R: Runtime>(app: &AppHandle<R>, active: bool) {
+    let mut monitor = MONITOR.lock().unwrap();
+    match (active, monitor.is_some()) {
+        (true, false) => *monitor = Some(start(app.clone())),
+        (false, true) => {
+            if let Some(stop) = monitor.take() {
+                stop.store(true, Ordering::Relaxed);
+            }
+            *STATUS.lock().unwrap() = Status::default();
+            let _ = app.emit("now-playing", None::<NowPlaying>);
+        }
+        _ => {}
+    }
+}
+
+fn start<R: Runtime>(app: AppHandle<R>) -> Arc<AtomicBool> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    std::thread::spawn(move || {
+        let mut session = platform::Session::new();
+        while !thread_stop.load(Ordering::Relaxed) {
+            let result = session.query();
+            if thread_stop.load(Ordering::Relaxed) {
+                break;
+            }
+            let mut status = STATUS.lock().unwrap();
+            match result {
+                Ok(now) => {
+                    status.error = None;
+                    if status.now != now {
+                        status.now = now;
+                        let _ = app.emit("now-playing", &status.now);
+                    }
+                }
+                Err(e) => {
+                    let msg = format!("{e:#}");
+                    if status.error.as_ref() != Some(&msg) {
+                        warn!("[media] {msg}");
+                    }
+                    status.error = Some(msg);
+                }
+            }
+        }
+    });
+    stop
+}
+
+/// The latest session, or `None` when nothing is playing or paused.
+pub fn current() -> Option<NowPlaying> {
+    STATUS.lock().unwrap().now.clone()
+}
+
+/// For the `MediaPlaying` binding.
+pub fn playing() -> Result<bool> {
+    let status = STATUS.lock().unwrap();
+    match (&status.now, &status.error) {
+        (_, Some(e)) => Err(anyhow!("Now playing: {e}")),
+        (now, None) => Ok(now.as_ref().is_some_and(|n| n.playing)),
+    }
+}
+
+/// Toggle play/pause of the current session. Doesn't block.
+pub fn play_pause(method: ReplayMethod) {
+    let player = current().map(|n| n.player);
+    std::thread::spawn(move || {
+        if let Err(e) = platform::play_pause(player.as_deref(), method) {
+            warn!("[media] Play/pause failed: {e:#}");
+        }
+    });
+}
+
+/// One `status\tplayer\ttitle\tartist\talbum` line, as every backend prints it.
+/// Empty or "none" means no session.
+pub fn parse_line(line: &str) -> Option<NowPlaying> {
+    let mut fields = line.trim_end_matches(['\r', '\n']).split('\t');
+    let status = fields.next().filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("none"))?;
+    if status.eq_ignore_ascii_case("stopped") {
+        return None;
+    }
+    let mut next = || fields.next().unwrap_or_default().to_owned();
+    Some(NowPlaying {
+        playing: status.eq_ignore_ascii_case("playing"),
+        player: next(),
+        title: next(),
+        artist: next(),
+        album: next(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use anyhow::{bail, Context, Result};
+
+    use super::{parse_line, NowPlaying, POLL};
+    use crate::state::ReplayMethod;
+
+    pub struct Session;
+
+    impl Session {
+        pub fn new() -> Self {
+            Self
+        }
+
+        pub fn query(&mut self) -> Result<Option<NowPlaying>> {
+            std::thread::sleep(POLL);
+            let out = std::process::Command::new("playerctl")
+                .args(["metadata", "--format", "{{status}}\t{{playerName}}\t{{title}}\t{{artist}}\t{{album}}"])
+                .output()
+                .context("playerctl not found (install playerctl)")?;
+            if !out.status.success() {
+                let stderr = String::from_utf8_lossy(&out.stderr);
+                if stderr.contains("No player") {
+                    return Ok(None);
+                }
+                bail!("playerctl failed: {}", stderr.trim());
+            }
+            Ok(parse_line(&String::from_utf8_lossy(&out.stdout)))
+        }
+    }
+
+    pub fn play_pause(_player: Option<&str>, _method: ReplayMethod) -> Result<()> {
+        let status = std::process::Command::new("playerctl")
+            .arg("play-pause")
+            .status()
+            .context("playerctl not found (install playerctl)")?;
+        if !status.success() {
+            bail!("playerctl play-pause failed");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::io::{BufRead, BufReader};
+    use std::os::windows::process::CommandExt;
+    use std::process::{Child, ChildStdout, Command, Stdio};
+
+    use anyhow::{bail, Context, Result};
+
+    use super::{parse_line, NowPlaying, POLL};
+    use crate::state::ReplayMethod;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    /// KC_MPLY
+    const MEDIA_PLAY_PAUSE: u16 = 0x00AE;
+
+    /// Prints a line per second for the current SMTC session ("none" without one).
+    const SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Runtime.WindowsRuntime
+$asTask = ([System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object {
+  $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1'
+})[0]
+function Await($op, $type) {
+  $task = $asTask.MakeGenericMethod($type).Invoke($null, @($op))
+  $task.Wait(-1) | Out-Null
+  $task.Result
+}
+$null = [Windows.Media.Control.GlobalSystemMediaTransportControlsSessionManager, Windows.Media.Control, ContentType = WindowsRuntime]
+$manager = Await ([Windows.Media.Control.GlobalSystemMediaTransportControlsSessionManager]::RequestAsync()) ([Windows.Media.Control.GlobalSystemMediaTransportControlsSessionManager])
+while ($true) {
+  $s = $manager.GetCurrentSession()
+  if ($s) {
+    $p = Await ($s.TryGetMediaPropertiesAsync()) ([Windows.Media.Control.GlobalSystemMediaTransportControlsSessionMediaProperties])
+    $fields = @($s.GetPlaybackInfo().PlaybackStatus, $s.SourceAppUserModelId, $p.Title, $p.Artist, $p.AlbumTitle) | ForEach-Object { "$_" -replace "`t", ' ' }
+    [Console]::Out.WriteLine($fields -join "`t")
+  } else {
+    [Console]::Out.WriteLine('none')
+  }
+  [Console]::Out.Flush()
+  Start-Sleep -Milliseconds POLL_MS
+}
+"#;
+
+    pub struct Session(Option<(Child, BufReader<ChildStdout>)>);
+
+    impl Session {
+        pub fn new() -> Self {
+            Self(None)
+        }
+
+        pub fn query(&mut self) -> Result<Option<NowPlaying>> {
+            if self.0.is_none() {
+                let mut child = Command::new("powershell")
+                    .args(["-NoProfile", "-NonInteractive", "-Command"])
+                    .arg(SCRIPT.replace("POLL_MS", &POLL.as_millis().to_string()))
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .creation_flags(CREATE_NO_WINDOW)
+                    .spawn()
+                    .context("Failed to start PowerShell")?;
+                let stdout = child.stdout.take().context("No PowerShell output")?;
+                self.0 = Some((child, BufReader::new(stdout)));
+            }
+            let (_, out) = self.0.as_mut().unwrap();
+            let mut line = String::new();
+            if out.read_line(&mut line)? == 0 {
+                self.0 = None;
+                std::thread::sleep(POLL);
+                bail!("Media session query stopped (SMTC unavailable?)");
+            }
+            Ok(parse_line(&line))
+        }
+    }
+
+    impl Drop for Session {
+        fn drop(&mut self) {
+            if let Some((mut child, _)) = self.0.take() {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        }
+    }
+
+    pub fn play_pause(_player: Option<&str>, method: ReplayMethod) -> Result<()> {
+        crate::replay::replay_keystroke(MEDIA_PLAY_PAUSE, method);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use anyhow::{bail, Context, Result};
+
+    use super::{parse_line, NowPlaying, POLL};
+    use crate::state::ReplayMethod;
+
+    /// Players' scripting terms only compile when the app is installed, so the
+    /// per-player part is compiled at run time (`run script`).
+    const SCRIPT: &str = r#"
+on playerInfo(appName)
+  if not (application appName is running) then return ""
+  return run script "tell application \"" & appName & "\"
+    if player state is stopped then return \"\"
+    return (player state as text) & tab & \"" & appName & "\" & tab & (name of current track) & tab & (artist of current track) & tab & (album of current track)
+  end tell"
+end playerInfo
+repeat with appName in {"Spotify", "Music"}
+  set info to playerInfo(appName as text)
+  if info is not "" then return info
+end repeat
+return ""
+"#;
+
+    fn osascript(script: &str) -> Result<String> {
+        let out = std::process::Command::new("osascript")
+            .args(["-e", script])
+            .output()
+            .context("osascript failed")?;
+        if !out.status.success() {
+            bail!("osascript failed: {}", String::from_utf8_lossy(&out.stderr).trim());
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+
+    pub struct Session;
+
+    impl Session {
+        pub fn new() -> Self {
+            Self
+        }
+
+        pub fn query(&mut self) -> Result<Option<NowPlaying>> {
+            std::thread::sleep(POLL);
+            Ok(parse_line(&osascript(SCRIPT)?))
+        }
+    }
+
+    pub fn play_pause(player: Option<&str>, _method: ReplayMethod) -> Result<()> {
+        let Some(player) = player else { bail!("Nothing is playing") };
+        osascript(&format!(r#"run script "tell application \"{player}\" to playpause""#)).map(drop)
+    }
+}
@@ -0,0 +1,264 @@
+// Meeting mute state for the `TeamsMuted` / `ZoomMuted` slot bindings.
+//
+// The key's LED follows what the meeting app reports rather than a local
+// guess, and pressing the key asks the app to toggle mute (the binding then
+// picks up the new state).
+//
+// - Teams: the local third-party device API (Settings → Privacy → Manage
+//   API). One background client (reconnect.rs) keeps a WebSocket to it open
+//   while a binding uses Teams; Teams pushes `meetingUpdate`s and answers `toggle-mute`. The
+//   pairing token Teams hands out after the user allows the app is kept in
+//   teams-token.txt so it doesn't ask again.
+// - Zoom has no local API. On macOS the Meeting menu says "Mute audio" or
+//   "Unmute audio" (read and clicked through System Events, which needs the
+//   Accessibility permission). Elsewhere the state can't be read: toggling
+//   sends Zoom's Alt+A, which reaches Zoom in the background only with its
+//   "global shortcut" option enabled, and the LED follows the key's own
+//   toggles (muting in Zoom itself isn't seen).
+
+use std::fs;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+
+use crate::reconnect::{self, Session};
+use crate::state::{Condition, ReplayMethod};
+
+const TEAMS_PORT: u16 = 8124;
+/// How long a Teams read blocks before queued actions are sent.
+const TEAMS_POLL: Duration = Duration::from_millis(200);
+const TEAMS_RETRY: Duration = Duration::from_secs(3);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Meeting {
+    Teams,
+    Zoom,
+}
+
+impl Meeting {
+    /// The meeting app a binding condition follows, if any.
+    pub fn of(condition: &Condition) -> Option<Self> {
+        match condition {
+            Condition::TeamsMuted => Some(Self::Teams),
+            Condition::ZoomMuted => Some(Self::Zoom),
+            _ => None,
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Teams => "teams",
+            Self::Zoom => "zoom",
+        }
+    }
+}
+
+/// Whether the app's microphone is muted in a meeting (false outside one).
+pub fn muted(meeting: Meeting) -> Result<bool> {
+    match meeting {
+        Meeting::Teams => teams_muted(),
+        Meeting::Zoom => zoom::muted(),
+    }
+}
+
+/// Ask the app to toggle mute. Doesn't block.
+pub fn toggle_mute(meeting: Meeting, method: ReplayMethod) {
+    match meeting {
+        Meeting::Teams => {
+            let client = TEAMS.lock().unwrap();
+            match client.as_ref().filter(|c| c.status.lock().unwrap().connected) {
+                Some(c) => c.send("toggle-mute"),
+                None => warn!("[meeting] Teams isn't connected, can't toggle mute"),
+            }
+        }
+        Meeting::Zoom => {
+            std::thread::spawn(move || {
+                if let Err(e) = zoom::toggle(method) {
+                    warn!("[meeting] Zoom toggle failed: {e:#}");
+                }
+            });
+        }
+    }
+}
+
+/// Stop the Teams client once no binding uses it.
+pub fn retain_teams(in_use: bool) {
+    if !in_use {
+        TEAMS.lock().unwrap().take();
+    }
+}
+
+// ── Teams ───────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct TeamsStatus {
+    connected: bool,
+    in_meeting: bool,
+    muted: bool,
+    error: Option<String>,
+}
+
+impl reconnect::Status for TeamsStatus {
+    fn failed(&mut self, error: String) {
+        self.connected = false;
+        self.error = Some(error);
+    }
+}
+
+/// Actions go to Teams as they are.
+type TeamsClient = reconnect::Client<TeamsStatus, &'static str>;
+
+static TEAMS: Mutex<Option<TeamsClient>> = Mutex::new(None);
+
+fn teams_muted() -> Result<bool> {
+    let mut client = TEAMS.lock().unwrap();
+    let client = client.get_or_insert_with(|| {
+        let mut request_id = 0;
+        TeamsClient::start(TEAMS_RETRY, move |session| teams_session(session, &mut request_id))
+    });
+    let status = client.status.lock().unwrap();
+    match (status.connected, &status.error) {
+        (true, _) => Ok(status.in_meeting && status.muted),
+        (false, Some(e)) => Err(anyhow!("Teams: {e}")),
+        // Still connecting
+        (false, None) => Ok(false),
+    }
+}
+
+fn token_file() -> Result<PathBuf> {
+    Ok(crate::profile::config_dir()?.join("teams-token.txt"))
+}
+
+/// One connection to Teams, until it closes or the client is dropped.
+fn teams_session(session: &Session<TeamsStatus, &'static str>, request_id: &mut u64) -> Result<()> {
+    use serde_json::{json, Value};
+    use tungstenite::Message;
+
+    let token = token_file().ok().and_then(|p| fs::read_to_string(p).ok()).unwrap_or_default();
+    let url = format!(
+        "ws://127.0.0.1:{TEAMS_PORT}?token={}&protocol-version=2.0.0&manufacturer=Deck8&device=Deck-8&app=Deck8%20Hub&app-version={}",
+        token.trim(),
+        env!("CARGO_PKG_VERSION"),
+    );
+    let addr = ("127.0.0.1", TEAMS_PORT).to_socket_addrs()?.next().context("No loopback address")?;
+    let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+        .context("Teams isn't running or its third-party app API is off")?;
+    stream.set_read_timeout(Some(TEAMS_POLL))?;
+    let (mut ws, _) = tungstenite::client(url, stream).map_err(|e| anyhow!("handshake failed: {e}"))?;
+    info!("[meeting] Connected to Teams");
+    session.connected();
+    *session.status.lock().unwrap() = TeamsStatus { connected: true, ..Default::default() };
+
+    while !session.stopped() {
+        for action in session.commands() {
+            *request_id += 1;
+            let msg = json!({ "action": action, "parameters": {}, "requestId": *request_id });
+            ws.send(Message::text(msg.to_string()))?;
+        }
+        let text = match ws.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => bail!("Teams closed the connection"),
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let Ok(msg) = serde_json::from_str::<Value>(&text) else { continue };
+        if let Some(token) = msg["tokenRefresh"].as_str() {
+            info!("[meeting] Paired with Teams");
+            if let Err(e) = token_file().and_then(|p| fs::write(p, token).context("Failed to save Teams token")) {
+                warn!("[meeting] {e:#}");
+            }
+        }
+        let state = &msg["meetingUpdate"]["meetingState"];
+        if state.is_object() {
+            let mut status = session.status.lock().unwrap();
+            status.in_meeting = state["isInMeeting"] == true;
+            status.muted = state["isMuted"] == true;
+        }
+    }
+    let _ = ws.close(None);
+    Ok(())
+}
+
+// ── Zoom ────────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+mod zoom {
+    use anyhow::{bail, Context, Result};
+
+    use crate::state::ReplayMethod;
+
+    /// Prints "muted", "unmuted" or "none" (not in a meeting), clicking the
+    /// menu item first when `click` is set.
+    fn script(click: bool) -> String {
+        let action = if click { "click" } else { "return" };
+        format!(
+            r#"tell application "System Events"
+  if not (exists process "zoom.us") then return "none"
+  tell process "zoom.us"
+    if not (exists menu bar item "Meeting" of menu bar 1) then return "none"
+    set m to menu 1 of menu bar item "Meeting" of menu bar 1
+    if exists menu item "Mute audio" of m then
+      {action} menu item "Mute audio" of m
+      return "unmuted"
+    end if
+    if exists menu item "Unmute audio" of m then
+      {action} menu item "Unmute audio" of m
+      return "muted"
+    end if
+  end tell
+end tell
+return "none""#
+        )
+    }
+
+    fn run(click: bool) -> Result<String> {
+        let out = std::process::Command::new("osascript")
+            .args(["-e", &script(click)])
+            .output()
+            .context("osascript failed")?;
+        if !out.status.success() {
+            bail!("Zoom menu unavailable (Accessibility permission?): {}", String::from_utf8_lossy(&out.stderr).trim());
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).trim().to_owned())
+    }
+
+    pub fn muted() -> Result<bool> {
+        Ok(run(false)? == "muted")
+    }
+
+    pub fn toggle(_method: ReplayMethod) -> Result<()> {
+        run(true).map(drop)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod zoom {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use anyhow::Result;
+
+    use crate::state::ReplayMethod;
+
+    /// LALT(KC_A)
+    const ALT_A: u16 = 0x0404;
+
+    /// Mute as toggled from the key.
+    static MUTED: AtomicBool = AtomicBool::new(false);
+
+    pub fn muted() -> Result<bool> {
+        Ok(MUTED.load(Ordering::Relaxed))
+    }
+
+    pub fn toggle(method: ReplayMethod) -> Result<()> {
+        crate::replay::replay_keystroke(ALT_A, method);
+        MUTED.fetch_xor(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
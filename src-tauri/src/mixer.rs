@@ -0,0 +1,312 @@
+// Virtual mixer channels for the `MixerMuted` slot binding.
+//
+// The key's LED follows the channel's mute state in the mixer and pressing
+// the key toggles it there (the binding then picks up the new state). The
+// soundboard can also output into either mixer's virtual inputs instead of a
+// plain cable (see `device_match::is_virtual_cable`).
+//
+// - Elgato Wave Link: its local JSON-RPC WebSocket (the one the Stream Deck
+//   plugin uses, first free port from 1824, Origin `streamdeck://`). The
+//   channel is an input's name; mute is the stream mix's, i.e. what viewers
+//   hear. One connection is kept open for the checks and toggles, and only
+//   made again after it fails (like the OBS and Discord clients).
+// - VoiceMeeter (Windows): the Remote API DLL from the VoiceMeeter install.
+//   The channel is a strip or bus, e.g. `Strip[0]` or `Bus[1]`.
+
+use std::net::TcpStream;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+
+use crate::state::Mixer;
+
+const WAVE_LINK_PORTS: std::ops::RangeInclusive<u16> = 1824..=1833;
+const WAVE_LINK_STREAM_MIX: &str = "com.elgato.mix.stream";
+const NET_TIMEOUT: Duration = Duration::from_secs(2);
+
+impl Mixer {
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::WaveLink => "wavelink",
+            Self::VoiceMeeter => "voicemeeter",
+        }
+    }
+}
+
+pub fn muted(mixer: Mixer, channel: &str) -> Result<bool> {
+    match mixer {
+        Mixer::WaveLink => wave_link_muted(channel),
+        Mixer::VoiceMeeter => voicemeeter::muted(channel),
+    }
+}
+
+/// Flip the channel's mute in the mixer. Doesn't block.
+pub fn toggle_mute(mixer: Mixer, channel: String) {
+    std::thread::spawn(move || {
+        let result = match mixer {
+            Mixer::WaveLink => wave_link_toggle(&channel),
+            Mixer::VoiceMeeter => voicemeeter::toggle(&channel),
+        };
+        match result {
+            Ok(()) => info!("[mixer] Toggled {} {channel}", mixer.id()),
+            Err(e) => warn!("[mixer] Toggling {} {channel} failed: {e:#}", mixer.id()),
+        }
+    });
+}
+
+// ── Wave Link ───────────────────────────────────────────────────────────
+
+struct WaveLink {
+    ws: tungstenite::WebSocket<TcpStream>,
+    next_id: u64,
+    /// The connection failed; the next request makes a new one.
+    broken: bool,
+}
+
+static WAVE_LINK: Mutex<Option<WaveLink>> = Mutex::new(None);
+
+/// Run `f` on the open Wave Link connection, connecting first if there's none.
+fn with_wave_link<T>(f: impl FnOnce(&mut WaveLink) -> Result<T>) -> Result<T> {
+    let mut open = WAVE_LINK.lock().unwrap();
+    let link = match open.take() {
+        Some(link) => open.insert(link),
+        None => open.insert(WaveLink::connect()?),
+    };
+    let result = f(link);
+    if link.broken {
+        *open = None;
+    }
+    result
+}
+
+impl WaveLink {
+    fn connect() -> Result<Self> {
+        use tungstenite::client::IntoClientRequest;
+
+        for port in WAVE_LINK_PORTS {
+            let addr = ([127, 0, 0, 1], port).into();
+            let Ok(stream) = TcpStream::connect_timeout(&addr, NET_TIMEOUT) else { continue };
+            stream.set_read_timeout(Some(NET_TIMEOUT))?;
+            stream.set_write_timeout(Some(NET_TIMEOUT))?;
+            let mut request = format!("ws://127.0.0.1:{port}").into_client_request()?;
+            request.headers_mut().insert("Origin", "streamdeck://".parse()?);
+            if let Ok((ws, _)) = tungstenite::client(request, stream) {
+                info!("[mixer] Connected to Wave Link on port {port}");
+                return Ok(Self { ws, next_id: 0, broken: false });
+            }
+        }
+        bail!("Wave Link isn't running")
+    }
+
+    /// Send a JSON-RPC request and wait for its result (skipping notifications).
+    fn call(&mut self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let result = self.exchange(method, params);
+        // Wave Link's own errors leave the connection usable
+        self.broken |= result.as_ref().is_err_and(|e| e.downcast_ref::<RpcError>().is_none());
+        result
+    }
+
+    fn exchange(&mut self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        use serde_json::{json, Value};
+        use tungstenite::Message;
+
+        self.next_id += 1;
+        let id = self.next_id;
+        let request = json!({ "jsonrpc": "2.0", "method": method, "id": id, "params": params });
+        self.ws.send(Message::text(request.to_string()))?;
+        loop {
+            let text = match self.ws.read()? {
+                Message::Text(text) => text,
+                Message::Close(_) => bail!("Wave Link closed the connection"),
+                _ => continue,
+            };
+            let msg: Value = serde_json::from_str(&text)?;
+            if msg["id"] != id {
+                continue;
+            }
+            if let Some(e) = msg.get("error") {
+                return Err(RpcError(format!("Wave Link {method}: {}", e["message"])).into());
+            }
+            return Ok(msg["result"].clone());
+        }
+    }
+
+    /// The input called `channel` (case-insensitive).
+    fn input(&mut self, channel: &str) -> Result<serde_json::Value> {
+        let inputs = self.call("getInputConfigs", serde_json::Value::Null)?;
+        inputs.as_array()
+            .and_then(|inputs| inputs.iter().find(|i| i["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(channel))))
+            .cloned()
+            .with_context(|| format!("Wave Link has no input named \"{channel}\""))
+    }
+}
+
+/// An error Wave Link answered with.
+#[derive(Debug)]
+struct RpcError(String);
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+/// Stream-mix mute of a Wave Link input. Newer versions report
+/// `streamMixer: [muted, volume]`, older ones `isStreamInMuted`.
+fn stream_muted(input: &serde_json::Value) -> Result<bool> {
+    input["streamMixer"][0].as_bool()
+        .or_else(|| input["isStreamInMuted"].as_bool())
+        .ok_or_else(|| anyhow!("Wave Link didn't report the input's mute state"))
+}
+
+fn wave_link_muted(channel: &str) -> Result<bool> {
+    with_wave_link(|link| stream_muted(&link.input(channel)?))
+}
+
+fn wave_link_toggle(channel: &str) -> Result<()> {
+    with_wave_link(|link| {
+        let input = link.input(channel)?;
+        let value = !stream_muted(&input)?;
+        link.call("setInputConfig", serde_json::json!({
+            "property": "Mute",
+            "identifier": input["identifier"],
+            "mixerID": WAVE_LINK_STREAM_MIX,
+            "value": value,
+            "forceLink": false,
+        }))?;
+        Ok(())
+    })
+}
+
+// ── VoiceMeeter ─────────────────────────────────────────────────────────
+
+/// `Strip[n]` / `Bus[n]`, the prefix of VoiceMeeter parameter names.
+pub fn valid_voicemeeter_channel(channel: &str) -> bool {
+    let Some(index) = channel.strip_prefix("Strip[").or_else(|| channel.strip_prefix("Bus[")) else {
+        return false;
+    };
+    index.strip_suffix(']').is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[cfg(target_os = "windows")]
+mod voicemeeter {
+    use std::ffi::{c_char, c_void, CString};
+    use std::sync::Mutex;
+
+    use anyhow::{bail, Context, Result};
+
+    type LoginFn = unsafe extern "system" fn() -> i32;
+    type DirtyFn = unsafe extern "system" fn() -> i32;
+    type GetFloatFn = unsafe extern "system" fn(*const c_char, *mut f32) -> i32;
+    type SetFloatFn = unsafe extern "system" fn(*const c_char, f32) -> i32;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn LoadLibraryW(name: *const u16) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+    }
+
+    const DLL_PATHS: [&str; 2] = [
+        r"C:\Program Files (x86)\VB\Voicemeeter\VoicemeeterRemote64.dll",
+        r"C:\Program Files\VB\Voicemeeter\VoicemeeterRemote64.dll",
+    ];
+
+    struct Api {
+        dirty: DirtyFn,
+        get: GetFloatFn,
+        set: SetFloatFn,
+    }
+
+    /// Loaded and logged in once; the client stays logged in until exit.
+    static API: Mutex<Option<Api>> = Mutex::new(None);
+
+    unsafe fn load() -> Result<Api> {
+        let module = DLL_PATHS.iter()
+            .map(|path| {
+                let wide: Vec<u16> = path.encode_utf16().chain([0]).collect();
+                LoadLibraryW(wide.as_ptr())
+            })
+            .find(|m| !m.is_null())
+            .context("VoiceMeeter isn't installed (VoicemeeterRemote64.dll not found)")?;
+        let proc = |name: &str| -> Result<*mut c_void> {
+            let name = CString::new(name)?;
+            let f = GetProcAddress(module, name.as_ptr());
+            if f.is_null() {
+                bail!("VoiceMeeter Remote API is missing {name:?}");
+            }
+            Ok(f)
+        };
+        let login = std::mem::transmute::<*mut c_void, LoginFn>(proc("VBVMR_Login")?);
+        let api = Api {
+            dirty: std::mem::transmute::<*mut c_void, DirtyFn>(proc("VBVMR_IsParametersDirty")?),
+            get: std::mem::transmute::<*mut c_void, GetFloatFn>(proc("VBVMR_GetParameterFloat")?),
+            set: std::mem::transmute::<*mut c_void, SetFloatFn>(proc("VBVMR_SetParameterFloat")?),
+        };
+        // 0: logged in, 1: logged in but VoiceMeeter isn't running (yet)
+        let hr = login();
+        if hr < 0 {
+            bail!("VoiceMeeter login failed ({hr})");
+        }
+        Ok(api)
+    }
+
+    fn with_api<T>(f: impl FnOnce(&Api) -> Result<T>) -> Result<T> {
+        let mut api = API.lock().unwrap();
+        if api.is_none() {
+            *api = Some(unsafe { load()? });
+        }
+        f(api.as_ref().unwrap())
+    }
+
+    fn parameter(channel: &str) -> Result<CString> {
+        if !super::valid_voicemeeter_channel(channel) {
+            bail!("VoiceMeeter channel must look like Strip[0] or Bus[0], not \"{channel}\"");
+        }
+        Ok(CString::new(format!("{channel}.Mute"))?)
+    }
+
+    fn get(api: &Api, name: &CString) -> Result<bool> {
+        let mut value = 0.0;
+        // Refreshes the parameter cache; a 1 just means something changed
+        unsafe { (api.dirty)() };
+        match unsafe { (api.get)(name.as_ptr(), &mut value) } {
+            0 => Ok(value != 0.0),
+            -2 => bail!("VoiceMeeter isn't running"),
+            -3 => bail!("VoiceMeeter has no {name:?}"),
+            code => bail!("VoiceMeeter read failed ({code})"),
+        }
+    }
+
+    pub fn muted(channel: &str) -> Result<bool> {
+        let name = parameter(channel)?;
+        with_api(|api| get(api, &name))
+    }
+
+    pub fn toggle(channel: &str) -> Result<()> {
+        let name = parameter(channel)?;
+        with_api(|api| {
+            let value = if get(api, &name)? { 0.0 } else { 1.0 };
+            match unsafe { (api.set)(name.as_ptr(), value) } {
+                0 => Ok(()),
+                code => bail!("VoiceMeeter write failed ({code})"),
+            }
+        })
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod voicemeeter {
+    use anyhow::{bail, Result};
+
+    pub fn muted(_channel: &str) -> Result<bool> {
+        bail!("VoiceMeeter is only available on Windows")
+    }
+
+    pub fn toggle(_channel: &str) -> Result<()> {
+        bail!("VoiceMeeter is only available on Windows")
+    }
+}
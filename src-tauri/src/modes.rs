@@ -0,0 +1,123 @@
+// Named modes ("Meeting", "Gaming", "Idle"): each puts every key it lists on
+// a given slot and can mute or unmute the key sounds, all at once.
+//
+// Modes replace the old "toggle every key" tray item. `set_mode` switches to
+// one by name, the tray lists them, and a key bound to `ModeActive` lights
+// while its mode is on and cycles to the next mode when pressed. Keys bound to
+// another condition keep following it. `AppState.active_mode` (persisted) is
+// the last mode switched to; editing a key's slot by hand doesn't clear it.
+//
+// A mode can build on a `base` ("Streaming" = "Default" + keys 1–4): it only
+// lists the slots that differ, and the rest come from the base (and its
+// base) when it's applied, so editing the base carries over. Deleting a base
+// folds its slots into the modes built on it.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::state::AppState;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Mode {
+    pub name: String,
+    /// Slot name per LED index; `None` leaves the key alone.
+    pub slots: [Option<String>; 8],
+    /// Mute (or unmute) the key sounds; `None` leaves them as they are.
+    #[serde(default)]
+    pub sounds_muted: Option<bool>,
+    /// Mode whose slots (and sounds setting) fill in where this one's are `None`.
+    #[serde(default)]
+    pub base: Option<String>,
+}
+
+fn find<'a>(modes: &'a [Mode], name: &str) -> Result<&'a Mode, String> {
+    modes.iter().find(|m| m.name == name).ok_or_else(|| crate::i18n::t_with("error-no-mode", &[("name", &name)]))
+}
+
+/// Take `base`'s slots and sounds setting where `mode` has none.
+fn inherit(mode: &mut Mode, base: &Mode) {
+    for (slot, inherited) in mode.slots.iter_mut().zip(&base.slots) {
+        if slot.is_none() {
+            slot.clone_from(inherited);
+        }
+    }
+    mode.sounds_muted = mode.sounds_muted.or(base.sounds_muted);
+}
+
+/// `name` with its bases folded in.
+pub fn resolve(modes: &[Mode], name: &str) -> Result<Mode, String> {
+    let mut mode = find(modes, name)?.clone();
+    let mut seen = vec![mode.name.clone()];
+    let mut next = mode.base.clone();
+    while let Some(base_name) = next {
+        if seen.contains(&base_name) {
+            return Err(crate::i18n::t_with("error-mode-loop", &[("name", &name), ("base", &base_name)]));
+        }
+        let base = find(modes, &base_name)?;
+        inherit(&mut mode, base);
+        next = base.base.clone();
+        seen.push(base_name);
+    }
+    Ok(mode)
+}
+
+/// Add or replace `mode`, refusing a base that's missing or leads back to it.
+pub fn save(modes: &mut Vec<Mode>, mode: Mode, max: usize) -> Result<(), String> {
+    let mut next = modes.clone();
+    match next.iter().position(|m| m.name == mode.name) {
+        Some(i) => next[i] = mode.clone(),
+        None if next.len() >= max => return Err(crate::i18n::t_with("error-too-many-modes", &[("max", &max)])),
+        None => next.push(mode.clone()),
+    }
+    resolve(&next, &mode.name)?;
+    *modes = next;
+    Ok(())
+}
+
+/// Slots of `current` that differ from what `base` would set; the rest are
+/// left to it.
+pub fn overrides(modes: &[Mode], base: &str, current: &[Option<String>; 8]) -> Result<[Option<String>; 8], String> {
+    let base = resolve(modes, base)?;
+    Ok(std::array::from_fn(|i| current[i].clone().filter(|slot| base.slots[i].as_ref() != Some(slot))))
+}
+
+/// Remove `name`; modes built on it take over its slots and its base.
+pub fn remove(modes: &mut Vec<Mode>, name: &str) -> bool {
+    let Some(index) = modes.iter().position(|m| m.name == name) else { return false };
+    let removed = modes.remove(index);
+    for mode in modes.iter_mut().filter(|m| m.base.as_deref() == Some(name)) {
+        inherit(mode, &removed);
+        mode.base.clone_from(&removed.base);
+    }
+    true
+}
+
+/// Put the keys on `name`'s slots. Returns the mode's `sounds_muted`, which
+/// the caller applies once the state lock is released.
+pub fn apply<R: Runtime>(app: &AppHandle<R>, st: &mut AppState, name: &str) -> Result<Option<bool>, String> {
+    let mode = resolve(&st.modes, name)?;
+    for (i, slot) in mode.slots.iter().enumerate() {
+        let Some(slot) = slot else { continue };
+        let key = &mut st.keys[i];
+        if key.binding.is_some() {
+            continue;
+        }
+        // A slot renamed or removed since the mode was saved
+        let Some(index) = key.slots.iter().position(|s| &s.name == slot) else { continue };
+        if key.active_slot == index {
+            continue;
+        }
+        let from = key.active().color;
+        key.active_slot = index;
+        crate::fade::transition(app, st, i, from);
+    }
+    st.active_mode = Some(mode.name);
+    Ok(mode.sounds_muted)
+}
+
+/// The mode after the active one (the first if none is), wrapping around.
+pub fn next(st: &AppState) -> Option<&str> {
+    let current = st.active_mode.as_deref().and_then(|name| st.modes.iter().position(|m| m.name == name));
+    let next = current.map_or(0, |i| (i + 1) % st.modes.len());
+    st.modes.get(next).map(|m| m.name.as_str())
+}
@@ -0,0 +1,235 @@
+// Key notifications from external tools (CI failed → a key blinks red).
+//
+// Each key has a stack of timed notifications; the highest priority shows
+// (newest on a tie) and the others keep counting down underneath, so a
+// low-priority "build running" pulse comes back after a high-priority alert
+// ends. Nothing is permanent: every notification has a duration and can be
+// dismissed early. When a key's stack empties its own color comes back (or
+// the progress bar or theme preview, if one is up).
+//
+// A frame thread runs while anything is on screen and only writes colors
+// that changed. Key color writes (`apply_key_to_device`, fades, the progress
+// bar) leave covered keys alone.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::HubError;
+use crate::protocol::HsvColor;
+use crate::state::SharedState;
+
+const FRAME: Duration = Duration::from_millis(50);
+const BLINK_PERIOD: Duration = Duration::from_millis(1000);
+const PULSE_PERIOD: Duration = Duration::from_millis(1500);
+/// Longest a notification may stay up.
+pub const MAX_DURATION_MS: u64 = 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum Pattern {
+    #[default]
+    Solid,
+    /// Half a second on, half a second off.
+    Blink,
+    /// Brightness breathing between dim and full.
+    Pulse,
+}
+
+impl Pattern {
+    /// The color `elapsed` into the notification.
+    pub fn frame(self, color: HsvColor, elapsed: Duration) -> HsvColor {
+        let phase = |period: Duration| (elapsed.as_millis() % period.as_millis()) as f32 / period.as_millis() as f32;
+        match self {
+            Self::Solid => color,
+            Self::Blink if phase(BLINK_PERIOD) < 0.5 => color,
+            Self::Blink => HsvColor { v: 0, ..color },
+            Self::Pulse => {
+                let level = 0.5 - 0.5 * (std::f32::consts::TAU * phase(PULSE_PERIOD)).cos();
+                // 16 steps keep the HID traffic down
+                let level = (0.15 + 0.85 * level) * 16.0;
+                HsvColor { v: (color.v as f32 * level.round() / 16.0) as u8, ..color }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Notification {
+    id: u64,
+    key: usize,
+    color: HsvColor,
+    pattern: Pattern,
+    priority: u8,
+    started: Instant,
+    until: Instant,
+}
+
+/// Every key's notifications.
+#[derive(Debug, Default)]
+pub struct Stack {
+    next_id: u64,
+    items: Vec<Notification>,
+}
+
+impl Stack {
+    pub const fn new() -> Self {
+        Self { next_id: 0, items: Vec::new() }
+    }
+
+    /// Add a notification; returns its id.
+    pub fn push(&mut self, key: usize, color: HsvColor, pattern: Pattern, priority: u8, duration: Duration, now: Instant) -> u64 {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.items.push(Notification { id, key, color, pattern, priority, started: now, until: now + duration });
+        id
+    }
+
+    pub fn dismiss(&mut self, id: u64) -> bool {
+        let before = self.items.len();
+        self.items.retain(|n| n.id != id);
+        self.items.len() != before
+    }
+
+    /// Push back when a notification ends. False if it isn't up anymore.
+    pub fn extend(&mut self, id: u64, duration: Duration, now: Instant) -> bool {
+        let Some(n) = self.items.iter_mut().find(|n| n.id == id && now < n.until) else { return false };
+        n.until = now + duration;
+        true
+    }
+
+    /// Drop what ran out by `now`.
+    pub fn expire(&mut self, now: Instant) {
+        self.items.retain(|n| now < n.until);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// What `key` shows at `now`, if a notification covers it.
+    pub fn color(&self, key: usize, now: Instant) -> Option<HsvColor> {
+        self.items.iter()
+            .filter(|n| n.key == key && now < n.until)
+            // Later pushes win ties: max_by_key keeps the last maximum
+            .max_by_key(|n| n.priority)
+            .map(|n| n.pattern.frame(n.color, now.saturating_duration_since(n.started)))
+    }
+}
+
+static STACK: Mutex<Stack> = Mutex::new(Stack::new());
+
+/// What the frame thread last did to each key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shown {
+    /// The key shows its own color.
+    Idle,
+    Color(HsvColor),
+    /// Someone else wrote the key; redraw the notification.
+    Stale,
+}
+
+static SHOWN: Mutex<[Shown; 8]> = Mutex::new([Shown::Idle; 8]);
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Whether a notification is on `key_index`.
+pub fn covers(key_index: usize) -> bool {
+    STACK.lock().unwrap().color(key_index, Instant::now()).is_some()
+}
+
+/// All keys were just rewritten: redraw notifications on the next frame.
+pub fn invalidate() {
+    for shown in SHOWN.lock().unwrap().iter_mut() {
+        if *shown != Shown::Idle {
+            *shown = Shown::Stale;
+        }
+    }
+}
+
+/// Show a notification on a key. Returns its id for `dismiss`.
+pub fn notify<R: Runtime>(
+    app: &AppHandle<R>,
+    key_index: usize,
+    color: HsvColor,
+    pattern: Pattern,
+    duration_ms: u64,
+    priority: u8,
+) -> Result<u64, HubError> {
+    if key_index >= 8 {
+        return Err(crate::i18n::t("error-key-index").into());
+    }
+    if duration_ms == 0 || duration_ms > MAX_DURATION_MS {
+        return Err(crate::i18n::t_with("error-notify-duration", &[("max", &MAX_DURATION_MS)]).into());
+    }
+    let id = STACK.lock().unwrap().push(key_index, color, pattern, priority, Duration::from_millis(duration_ms), Instant::now());
+    crate::fade::cancel(key_index);
+    if !RUNNING.swap(true, Ordering::SeqCst) {
+        let app = app.clone();
+        std::thread::spawn(move || run(&app));
+    }
+    Ok(id)
+}
+
+/// Take a notification down early. False if it already ended.
+pub fn dismiss(id: u64) -> bool {
+    STACK.lock().unwrap().dismiss(id)
+}
+
+/// Keep a notification up for another `duration_ms` from now. False if it
+/// already ended.
+pub fn extend(id: u64, duration_ms: u64) -> bool {
+    let duration = Duration::from_millis(duration_ms.min(MAX_DURATION_MS));
+    STACK.lock().unwrap().extend(id, duration, Instant::now())
+}
+
+fn run<R: Runtime>(app: &AppHandle<R>) {
+    loop {
+        std::thread::sleep(FRAME);
+        let now = Instant::now();
+        let (colors, empty) = {
+            let mut stack = STACK.lock().unwrap();
+            stack.expire(now);
+            (std::array::from_fn::<_, 8, _>(|k| stack.color(k, now)), stack.is_empty())
+        };
+        let state = app.state::<SharedState>();
+        let st = state.lock().unwrap();
+        let mut shown = SHOWN.lock().unwrap();
+        for (k, color) in colors.into_iter().enumerate() {
+            let Some(ref dev) = st.device else { break };
+            match color {
+                // Do-not-disturb keeps the key dark; it's redrawn on exit
+                Some(_) if crate::dnd_active() => shown[k] = Shown::Stale,
+                Some(c) if shown[k] != Shown::Color(c) => {
+                    let _ = dev.set_key_color(k as u8, &crate::brightness::key_color(&c));
+                    shown[k] = Shown::Color(c);
+                }
+                Some(_) => {}
+                None if shown[k] != Shown::Idle => {
+                    shown[k] = Shown::Idle;
+                    if crate::progress::active() {
+                        let _ = crate::progress::redraw(dev);
+                    } else if crate::preview::active() {
+                        // apply_key_to_device holds off under a preview
+                        let _ = crate::preview::redraw(dev);
+                    } else {
+                        crate::apply_key_to_device(dev, k as u8, &st.keys[k]);
+                    }
+                }
+                None => {}
+            }
+        }
+        if st.device.is_none() {
+            // Nothing to restore on the next device; it syncs every key
+            *shown = [Shown::Idle; 8];
+        }
+        if empty && shown.iter().all(|s| *s == Shown::Idle) {
+            RUNNING.store(false, Ordering::SeqCst);
+            // A notification pushed meanwhile found RUNNING still set
+            if STACK.lock().unwrap().is_empty() || RUNNING.swap(true, Ordering::SeqCst) {
+                return;
+            }
+        }
+    }
+}
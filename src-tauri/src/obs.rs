@@ -0,0 +1,217 @@
+// OBS state for the `ObsRecording` / `ObsStreaming` / `ObsScene` slot bindings.
+//
+// One client per OBS instance (host, port, password) keeps an obs-websocket v5
+// connection open while a binding uses it (see reconnect.rs). On connect it asks for the current
+// scene and the stream/record state, then follows OBS's events, so the keys
+// change as soon as OBS does. Pressing a bound key asks OBS to toggle the
+// recording or stream, or to switch to the key's scene (the binding then picks
+// up the new state).
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+use serde_json::{json, Value};
+
+use crate::reconnect::{self, Session};
+use crate::state::Condition;
+
+/// How long a read blocks before queued requests are sent.
+const POLL: Duration = Duration::from_millis(200);
+const RETRY: Duration = Duration::from_secs(3);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// obs-websocket event subscriptions: Scenes | Outputs.
+const EVENTS: u32 = (1 << 2) | (1 << 6);
+
+/// (host, port, password)
+pub type Instance = (String, u16, Option<String>);
+
+#[derive(Debug, Default)]
+pub struct ObsStatus {
+    pub connected: bool,
+    /// Program scene.
+    pub scene: Option<String>,
+    pub streaming: bool,
+    pub recording: bool,
+    pub error: Option<String>,
+}
+
+impl reconnect::Status for ObsStatus {
+    fn failed(&mut self, error: String) {
+        self.connected = false;
+        self.error = Some(error);
+    }
+}
+
+/// Requests go to OBS as they are, numbered.
+type Client = reconnect::Client<ObsStatus, Value>;
+
+static CLIENTS: Mutex<Vec<(Instance, Client)>> = Mutex::new(Vec::new());
+
+/// The OBS instance a binding condition follows, if any.
+pub fn instance(condition: &Condition) -> Option<Instance> {
+    match condition {
+        Condition::ObsRecording { host, port, password }
+        | Condition::ObsStreaming { host, port, password }
+        | Condition::ObsScene { host, port, password, .. } => Some((host.clone(), *port, password.clone())),
+        _ => None,
+    }
+}
+
+/// Whether the condition holds in OBS (false while still connecting).
+pub fn state(condition: &Condition) -> Result<bool> {
+    let instance = instance(condition).context("Not an OBS condition")?;
+    let mut clients = CLIENTS.lock().unwrap();
+    let client = match clients.iter().position(|(i, _)| *i == instance) {
+        Some(i) => &clients[i].1,
+        None => {
+            let session_instance = instance.clone();
+            clients.push((instance.clone(), Client::start(RETRY, move |s| session(&session_instance, s))));
+            &clients.last().unwrap().1
+        }
+    };
+    let status = client.status.lock().unwrap();
+    match (status.connected, &status.error) {
+        (true, _) => Ok(holds(&status, condition)),
+        (false, Some(e)) => Err(anyhow!("OBS {}:{}: {e}", instance.0, instance.1)),
+        (false, None) => Ok(false),
+    }
+}
+
+/// Whether `condition` holds for an OBS in `status`.
+pub fn holds(status: &ObsStatus, condition: &Condition) -> bool {
+    match condition {
+        Condition::ObsRecording { .. } => status.recording,
+        Condition::ObsStreaming { .. } => status.streaming,
+        Condition::ObsScene { scene, .. } => status.scene.as_deref() == Some(scene.as_str()),
+        _ => false,
+    }
+}
+
+/// Ask OBS to toggle what the condition follows, or switch to its scene.
+/// Doesn't block. Returns the action for usage stats.
+pub fn toggle(condition: &Condition) -> Option<String> {
+    let instance = instance(condition)?;
+    let (request, action) = match condition {
+        Condition::ObsRecording { .. } => (json!({ "requestType": "ToggleRecord" }), "obs:toggle-record".to_string()),
+        Condition::ObsStreaming { .. } => (json!({ "requestType": "ToggleStream" }), "obs:toggle-stream".to_string()),
+        Condition::ObsScene { scene, .. } => (
+            json!({ "requestType": "SetCurrentProgramScene", "requestData": { "sceneName": scene } }),
+            format!("obs:scene:{scene}"),
+        ),
+        _ => return None,
+    };
+    let clients = CLIENTS.lock().unwrap();
+    match clients.iter().find(|(i, c)| *i == instance && c.status.lock().unwrap().connected) {
+        Some((_, c)) => c.send(request),
+        None => warn!("[obs] {}:{} isn't connected, can't send {}", instance.0, instance.1, action),
+    }
+    Some(action)
+}
+
+/// Stop the clients no binding uses anymore.
+pub fn retain(in_use: &[Instance]) {
+    CLIENTS.lock().unwrap().retain(|(i, _)| in_use.contains(i));
+}
+
+/// One connection to OBS, until it closes or the client is dropped.
+fn session((host, port, password): &Instance, session: &Session<ObsStatus, Value>) -> Result<()> {
+    use tungstenite::Message;
+
+    let addr = (host.as_str(), *port).to_socket_addrs()?.next().context("Cannot resolve OBS host")?;
+    let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).context("OBS is not reachable")?;
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+    let (mut ws, _) = tungstenite::client(format!("ws://{host}:{port}"), stream)
+        .map_err(|e| anyhow!("OBS handshake failed: {e}"))?;
+
+    fn read(ws: &mut tungstenite::WebSocket<TcpStream>) -> Result<Value> {
+        loop {
+            match ws.read()? {
+                Message::Text(text) => return Ok(serde_json::from_str(&text)?),
+                Message::Close(_) => bail!("OBS closed the connection (wrong password?)"),
+                _ => {}
+            }
+        }
+    }
+
+    // Hello (op 0) → Identify (op 1) → Identified (op 2)
+    let hello = read(&mut ws)?;
+    let mut identify = json!({ "rpcVersion": 1, "eventSubscriptions": EVENTS });
+    if let Some(auth) = hello["d"].get("authentication") {
+        let password = password.as_deref().context("OBS requires a password")?;
+        let salt = auth["salt"].as_str().unwrap_or_default();
+        let challenge = auth["challenge"].as_str().unwrap_or_default();
+        let secret = sha256_base64(&format!("{password}{salt}"));
+        identify["authentication"] = sha256_base64(&format!("{secret}{challenge}")).into();
+    }
+    ws.send(Message::text(json!({ "op": 1, "d": identify }).to_string()))?;
+    let identified = read(&mut ws)?;
+    if identified["op"] != 2 {
+        bail!("OBS did not accept the connection");
+    }
+    info!("[obs] Connected to {host}:{port}");
+
+    session.connected();
+    *session.status.lock().unwrap() = ObsStatus { connected: true, ..Default::default() };
+    let mut request_id = 0u64;
+    let mut send = |ws: &mut tungstenite::WebSocket<TcpStream>, mut request: Value| -> Result<()> {
+        request_id += 1;
+        request["requestId"] = request_id.to_string().into();
+        ws.send(Message::text(json!({ "op": 6, "d": request }).to_string()))?;
+        Ok(())
+    };
+    for request_type in ["GetCurrentProgramScene", "GetStreamStatus", "GetRecordStatus"] {
+        send(&mut ws, json!({ "requestType": request_type }))?;
+    }
+
+    ws.get_ref().set_read_timeout(Some(POLL))?;
+    while !session.stopped() {
+        for request in session.commands() {
+            send(&mut ws, request)?;
+        }
+        let text = match ws.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => bail!("OBS closed the connection"),
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let Ok(msg) = serde_json::from_str::<Value>(&text) else { continue };
+        apply(&mut session.status.lock().unwrap(), &msg);
+    }
+    let _ = ws.close(None);
+    Ok(())
+}
+
+/// Update `status` from an OBS event (op 5) or request response (op 7).
+pub fn apply(status: &mut ObsStatus, msg: &Value) {
+    let d = &msg["d"];
+    let (kind, data) = match msg["op"].as_u64() {
+        Some(5) => (&d["eventType"], &d["eventData"]),
+        Some(7) if d["requestStatus"]["result"] == true => (&d["requestType"], &d["responseData"]),
+        Some(7) => {
+            warn!("[obs] {} failed: {}", d["requestType"], d["requestStatus"]["comment"]);
+            return;
+        }
+        _ => return,
+    };
+    match kind.as_str().unwrap_or_default() {
+        "CurrentProgramSceneChanged" => status.scene = data["sceneName"].as_str().map(str::to_string),
+        "GetCurrentProgramScene" => {
+            status.scene = data["currentProgramSceneName"].as_str().or(data["sceneName"].as_str()).map(str::to_string);
+        }
+        "StreamStateChanged" | "GetStreamStatus" => status.streaming = data["outputActive"] == true,
+        "RecordStateChanged" | "GetRecordStatus" => status.recording = data["outputActive"] == true,
+        _ => {}
+    }
+}
+
+fn sha256_base64(input: &str) -> String {
+    use base64::Engine;
+    use sha2::{Digest, Sha256};
+    base64::engine::general_purpose::STANDARD.encode(Sha256::digest(input.as_bytes()))
+}
@@ -0,0 +1,107 @@
+// First-run setup guide: connect the Deck-8, press a key, pick the audio
+// devices, import a first sound.
+//
+// The step lives in `AppSettings.onboarding` (persisted) and only moves
+// forward: `advance` passes every step whose goal is already met (a device is
+// connected, both audio devices are set, the library has a sound), so a step
+// done from anywhere in the app counts, not only from the guide. "Press a key"
+// is met by `key_pressed`. Users can skip a step (`skip`) or the whole guide
+// (`finish_onboarding`), and bring it back from Settings (`reset`).
+//
+// State files from before the guide existed deserialize as `Done`, so only
+// fresh installs see it.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::state::{AppState, SharedState};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OnboardingStep {
+    #[default]
+    Device,
+    TestKey,
+    Audio,
+    FirstSound,
+    Done,
+}
+
+impl OnboardingStep {
+    fn next(self) -> Self {
+        match self {
+            Self::Device => Self::TestKey,
+            Self::TestKey => Self::Audio,
+            Self::Audio => Self::FirstSound,
+            Self::FirstSound | Self::Done => Self::Done,
+        }
+    }
+
+    /// Whether the step's goal is met. A key press is an event, not state.
+    fn met(self, st: &AppState) -> bool {
+        let audio = &st.audio_config;
+        match self {
+            Self::Device => st.device.is_some(),
+            Self::TestKey => false,
+            Self::Audio => audio.audio_input_device.is_some() && audio.audio_output_device.is_some(),
+            Self::FirstSound => !audio.sound_library.is_empty(),
+            Self::Done => true,
+        }
+    }
+
+    /// The `serde(default)` for state files written before onboarding.
+    pub fn finished() -> Self {
+        Self::Done
+    }
+}
+
+/// Move past every step that is already met. True if the step changed.
+pub fn advance(st: &mut AppState) -> bool {
+    let before = st.settings.onboarding;
+    while st.settings.onboarding != OnboardingStep::Done && st.settings.onboarding.met(st) {
+        st.settings.onboarding = st.settings.onboarding.next();
+    }
+    st.settings.onboarding != before
+}
+
+/// Go on without meeting the current step.
+pub fn skip(st: &mut AppState) {
+    st.settings.onboarding = st.settings.onboarding.next();
+    advance(st);
+}
+
+/// Start the guide over (from the first step that isn't met).
+pub fn reset(st: &mut AppState) {
+    st.settings.onboarding = OnboardingStep::Device;
+    advance(st);
+}
+
+/// A Deck-8 key went down: completes "press a key".
+pub fn key_pressed(st: &mut AppState) -> bool {
+    if st.settings.onboarding != OnboardingStep::TestKey {
+        return false;
+    }
+    st.settings.onboarding = OnboardingStep::Audio;
+    advance(st);
+    true
+}
+
+/// Re-check the guide after a change elsewhere; persists and tells the UI if
+/// it moved. Locks the state.
+pub fn sync<R: Runtime>(app: &AppHandle<R>) {
+    update(app, advance);
+}
+
+/// `key_pressed`, from the key event path. Locks the state.
+pub fn sync_key_pressed<R: Runtime>(app: &AppHandle<R>) {
+    update(app, key_pressed);
+}
+
+fn update<R: Runtime>(app: &AppHandle<R>, f: fn(&mut AppState) -> bool) {
+    let state = app.state::<SharedState>();
+    let mut st = state.lock().unwrap();
+    if st.settings.onboarding == OnboardingStep::Done || !f(&mut st) {
+        return;
+    }
+    crate::persist_state(&st);
+    let _ = app.emit("state-updated", st.snapshot());
+}
@@ -0,0 +1,184 @@
+// LAN transfer: set up a second PC from this one without copying files.
+//
+// `start_config_share` bundles the setup (profile::export_bundle: state.json
+// and the sounds it uses) and serves it at http://<LAN address>:PORT/bundle
+// to a request carrying the 6-digit pairing code in an `X-Pair-Code` header.
+// The other PC's `import_shared_config` takes the address and code, fetches
+// the bundle, applies it and restarts the app. A share ends after one
+// download, `MAX_ATTEMPTS` wrong codes, `SHARE_FOR` or `stop_config_share`.
+//
+// The bundle travels unencrypted, so it carries no credentials (OBS
+// passwords, the Discord secret, Hue keys, calendar links, the lock PIN):
+// those are entered again on the other PC. The code keeps others on the LAN
+// from fetching the setup, not from watching.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+use serde::Serialize;
+
+pub const PORT: u16 = 18_810;
+const SHARE_FOR: Duration = Duration::from_secs(10 * 60);
+const MAX_ATTEMPTS: u32 = 5;
+/// How often the listener checks for connections and the share ending.
+const POLL: Duration = Duration::from_millis(200);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_BUNDLE: u64 = 1 << 30;
+
+/// What to enter on the other PC.
+#[derive(Debug, Clone, Serialize)]
+pub struct Share {
+    /// ip:port
+    pub address: String,
+    pub code: String,
+    pub expires_at_ms: u64,
+}
+
+struct Session {
+    code: String,
+    bundle: Arc<Vec<u8>>,
+    expires: Instant,
+    attempts: u32,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+/// The listener thread; held while starting or stopping a share.
+static LISTENER: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+/// Start serving `bundle`, replacing the one shared before (and its code).
+pub fn share(bundle: Vec<u8>) -> Result<Share> {
+    let ip = lan_ip().context("No network connection to share over")?;
+    let mut listener = LISTENER.lock().unwrap();
+    {
+        let mut session = SESSION.lock().unwrap();
+        // The running listener picks up the new session
+        if session.is_some() {
+            return Ok(open(&mut session, bundle, ip));
+        }
+    }
+    // One that's ending (expired, sent the bundle) lets go of the port first
+    if let Some(ended) = listener.take() {
+        let _ = ended.join();
+    }
+    let socket = TcpListener::bind(("0.0.0.0", PORT)).with_context(|| format!("Can't listen on port {PORT}"))?;
+    socket.set_nonblocking(true)?;
+    let share = open(&mut SESSION.lock().unwrap(), bundle, ip);
+    *listener = Some(std::thread::spawn(move || listen(socket)));
+    Ok(share)
+}
+
+fn open(session: &mut Option<Session>, bundle: Vec<u8>, ip: IpAddr) -> Share {
+    let code = new_code();
+    *session = Some(Session { code: code.clone(), bundle: Arc::new(bundle), expires: Instant::now() + SHARE_FOR, attempts: 0 });
+    info!("[pairing] Sharing the setup on {ip}:{PORT}");
+    Share { address: format!("{ip}:{PORT}"), code, expires_at_ms: crate::now_ms() + SHARE_FOR.as_millis() as u64 }
+}
+
+/// End the share; the port is free again once this returns.
+pub fn stop() {
+    let mut listener = LISTENER.lock().unwrap();
+    if SESSION.lock().unwrap().take().is_some() {
+        info!("[pairing] Stopped sharing");
+    }
+    if let Some(listener) = listener.take() {
+        let _ = listener.join();
+    }
+}
+
+/// Download the bundle `address` (ip, or ip:port) is sharing.
+pub fn fetch(address: &str, code: &str) -> Result<Vec<u8>> {
+    let address = address.trim();
+    let host = if address.contains(':') { address.to_string() } else { format!("{address}:{PORT}") };
+    let agent = ureq::AgentBuilder::new().timeout_connect(CONNECT_TIMEOUT).timeout_read(READ_TIMEOUT).build();
+    let response = match agent.get(&format!("http://{host}/bundle")).set("X-Pair-Code", code.trim()).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(403, _)) => bail!("Wrong pairing code"),
+        Err(ureq::Error::Status(status, _)) => bail!("{host} answered {status}"),
+        Err(e) => return Err(anyhow!("Can't reach {host}: {e}")),
+    };
+    let mut bundle = Vec::new();
+    response.into_reader().take(MAX_BUNDLE).read_to_end(&mut bundle).context("The transfer was interrupted")?;
+    Ok(bundle)
+}
+
+/// Six random digits.
+fn new_code() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(crate::now_ms());
+    format!("{:06}", hasher.finish() % 1_000_000)
+}
+
+/// This PC's address on the network the default route goes through.
+fn lan_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    // Nothing is sent; this only picks the interface
+    socket.connect(("192.0.2.1", 9)).ok()?;
+    Some(socket.local_addr().ok()?.ip()).filter(|ip| !ip.is_loopback() && !ip.is_unspecified())
+}
+
+fn listen(listener: TcpListener) {
+    loop {
+        std::thread::sleep(POLL);
+        {
+            let mut session = SESSION.lock().unwrap();
+            if session.as_ref().is_some_and(|s| Instant::now() >= s.expires) {
+                info!("[pairing] Share expired");
+                *session = None;
+            }
+            if session.is_none() {
+                return;
+            }
+        }
+        while let Ok((stream, peer)) = listener.accept() {
+            std::thread::spawn(move || serve(stream, peer.ip()));
+        }
+    }
+}
+
+fn serve(stream: TcpStream, peer: IpAddr) {
+    if stream.set_nonblocking(false).is_err() || stream.set_read_timeout(Some(Duration::from_secs(5))).is_err() {
+        return;
+    }
+    let (status, body) = match crate::http::read_request(&stream, 0) {
+        Ok(request) if request.method != "GET" || request.path != "/bundle" => ("404 Not Found", None),
+        Ok(request) => match request.header("x-pair-code") {
+            Some(code) => answer(code, peer),
+            None => ("403 Forbidden", None),
+        },
+        Err(status) => (status, None),
+    };
+    let length = body.as_ref().map_or(0, |b| b.len());
+    let head = format!("HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {length}\r\nConnection: close\r\n\r\n");
+    let mut stream = &stream;
+    if stream.write_all(head.as_bytes()).is_ok() {
+        if let Some(body) = body {
+            let _ = stream.write_all(&body);
+        }
+    }
+}
+
+fn answer(code: &str, peer: IpAddr) -> (&'static str, Option<Arc<Vec<u8>>>) {
+    let mut session = SESSION.lock().unwrap();
+    let Some(ref mut s) = *session else { return ("410 Gone", None) };
+    if s.code == code {
+        info!("[pairing] Sent the setup to {peer}");
+        let bundle = s.bundle.clone();
+        *session = None;
+        return ("200 OK", Some(bundle));
+    }
+    s.attempts += 1;
+    warn!("[pairing] Wrong pairing code from {peer}");
+    if s.attempts >= MAX_ATTEMPTS {
+        warn!("[pairing] Too many wrong codes, stopped sharing");
+        *session = None;
+    }
+    ("403 Forbidden", None)
+}
@@ -0,0 +1,283 @@
+// Permission preflight: checks what each platform needs for per-key detection,
+// HID access and keystroke replay. Each result carries a fix hint and, where
+// the OS has one, a deep link to the right settings pane.
+//
+// macOS:   Accessibility (enigo replay) + Input Monitoring (HID access)
+// Linux:   hidraw / evdev / uinput node access (udev rules or group membership)
+// Windows: no permissions to grant, but the hook can't see keys while an
+//          elevated window has focus unless the app is elevated too (UIPI).
+
+use serde::Serialize;
+
+#[allow(dead_code)] // each platform only reports some of these
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionStatus {
+    Granted,
+    Missing,
+    /// Works, with a caveat explained in `detail`.
+    Limited,
+    /// Can't be determined right now (e.g. device not connected).
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionCheck {
+    pub id: String,
+    pub label: String,
+    pub status: PermissionStatus,
+    pub detail: String,
+    /// What the user should do when the status isn't `Granted`.
+    pub fix: Option<String>,
+    /// Settings pane deep link, opened with `open_settings_url`.
+    pub settings_url: Option<String>,
+}
+
+impl PermissionCheck {
+    fn new(id: &str, label: &str, status: PermissionStatus, detail: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            status,
+            detail: detail.into(),
+            fix: None,
+            settings_url: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        if self.status != PermissionStatus::Granted {
+            self.fix = Some(fix.into());
+        }
+        self
+    }
+
+    #[allow(dead_code)] // only macOS checks carry a deep link
+    fn with_settings_url(mut self, url: &str) -> Self {
+        self.settings_url = Some(url.into());
+        self
+    }
+}
+
+/// URL schemes `open_settings_url` is allowed to open.
+const SETTINGS_URL_SCHEMES: &[&str] = &["x-apple.systempreferences:", "ms-settings:"];
+
+pub fn is_settings_url(url: &str) -> bool {
+    SETTINGS_URL_SCHEMES.iter().any(|s| url.starts_with(s))
+}
+
+/// Open a settings pane deep link with the platform's URL handler.
+pub fn open_settings_url(url: &str) -> anyhow::Result<()> {
+    if !is_settings_url(url) {
+        anyhow::bail!("Not a settings URL: {}", url);
+    }
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        anyhow::bail!("No settings panes on this platform");
+    };
+    std::process::Command::new(opener).arg(url).spawn()?;
+    Ok(())
+}
+
+// ── macOS ───────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+pub fn check_all() -> Vec<PermissionCheck> {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOHIDCheckAccess(request_type: u32) -> u32;
+    }
+    const K_IOHID_REQUEST_TYPE_LISTEN_EVENT: u32 = 1;
+    const K_IOHID_ACCESS_TYPE_GRANTED: u32 = 0;
+    const K_IOHID_ACCESS_TYPE_DENIED: u32 = 1;
+
+    let accessibility = if unsafe { AXIsProcessTrusted() } {
+        PermissionStatus::Granted
+    } else {
+        PermissionStatus::Missing
+    };
+    let input_monitoring = match unsafe { IOHIDCheckAccess(K_IOHID_REQUEST_TYPE_LISTEN_EVENT) } {
+        K_IOHID_ACCESS_TYPE_GRANTED => PermissionStatus::Granted,
+        K_IOHID_ACCESS_TYPE_DENIED => PermissionStatus::Missing,
+        _ => PermissionStatus::Unknown,
+    };
+
+    vec![
+        PermissionCheck::new(
+            "accessibility",
+            "Accessibility",
+            accessibility,
+            "Needed to replay keystrokes after a shortcut toggles a key.",
+        )
+        .with_fix("Enable Deck-8 Hub under Privacy & Security → Accessibility, then restart the app.")
+        .with_settings_url("x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"),
+        PermissionCheck::new(
+            "input_monitoring",
+            "Input Monitoring",
+            input_monitoring,
+            "Needed to talk to the Deck-8 over HID on recent macOS versions.",
+        )
+        .with_fix("Enable Deck-8 Hub under Privacy & Security → Input Monitoring, then restart the app.")
+        .with_settings_url("x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent"),
+    ]
+}
+
+// ── Linux ───────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "linux")]
+pub fn check_all() -> Vec<PermissionCheck> {
+    use std::fs::{self, OpenOptions};
+    use std::io::ErrorKind;
+    use std::path::{Path, PathBuf};
+
+    const UDEV_FIX: &str = "Install the udev rules from the README (/etc/udev/rules.d/70-deck8.rules) and replug the Deck-8.";
+
+    /// hidraw nodes whose uevent matches the Deck-8 (HID_ID=<bus>:0000CBBC:0000C101).
+    fn find_hidraw_nodes() -> Vec<PathBuf> {
+        let needle = format!(":{:08X}:{:08X}", crate::protocol::VID, crate::protocol::PID);
+        let Ok(entries) = fs::read_dir("/sys/class/hidraw") else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|e| {
+                fs::read_to_string(e.path().join("device").join("uevent"))
+                    .map(|u| u.lines().any(|l| l.starts_with("HID_ID=") && l.ends_with(&needle)))
+                    .unwrap_or(false)
+            })
+            .map(|e| Path::new("/dev").join(e.file_name()))
+            .collect()
+    }
+
+    /// Whether this process has the named supplementary group.
+    fn in_group(name: &str) -> bool {
+        let gid = fs::read_to_string("/etc/group").ok().and_then(|groups| {
+            groups.lines().find_map(|l| {
+                let mut fields = l.split(':');
+                if fields.next() != Some(name) {
+                    return None;
+                }
+                fields.nth(1).map(str::to_owned)
+            })
+        });
+        let Some(gid) = gid else { return false };
+        fs::read_to_string("/proc/self/status")
+            .map(|s| {
+                s.lines()
+                    .find(|l| l.starts_with("Groups:"))
+                    .is_some_and(|l| l.split_whitespace().skip(1).any(|g| g == gid))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Try to open every node; Missing if any is denied.
+    fn check_nodes(nodes: &[PathBuf], write: bool) -> (PermissionStatus, String) {
+        if nodes.is_empty() {
+            return (PermissionStatus::Unknown, "Deck-8 not connected.".into());
+        }
+        for node in nodes {
+            if let Err(e) = OpenOptions::new().read(true).write(write).open(node) {
+                let status = if e.kind() == ErrorKind::PermissionDenied {
+                    PermissionStatus::Missing
+                } else {
+                    PermissionStatus::Unknown
+                };
+                return (status, format!("{}: {}", node.display(), e));
+            }
+        }
+        let names: Vec<String> = nodes.iter().map(|n| n.display().to_string()).collect();
+        (PermissionStatus::Granted, names.join(", "))
+    }
+
+    let (hid_status, hid_detail) = check_nodes(&find_hidraw_nodes(), true);
+    let (input_status, mut input_detail) = check_nodes(&crate::keyboard_hook::find_deck8_nodes(), false);
+    if input_status == PermissionStatus::Missing && in_group("input") {
+        input_detail.push_str(" (you're in the input group — log out and back in)");
+    }
+
+    let (uinput_status, uinput_detail) = match OpenOptions::new().write(true).open("/dev/uinput") {
+        Ok(_) => (PermissionStatus::Granted, "/dev/uinput".to_string()),
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+            (PermissionStatus::Missing, format!("/dev/uinput: {}", e))
+        }
+        Err(e) => (PermissionStatus::Unknown, format!("/dev/uinput: {} (is the uinput module loaded?)", e)),
+    };
+
+    vec![
+        PermissionCheck::new("hidraw", "Deck-8 HID access", hid_status,
+            format!("Needed to read and write the key configuration. {}", hid_detail))
+            .with_fix(UDEV_FIX),
+        PermissionCheck::new("input", "Deck-8 key events", input_status,
+            format!("Needed for reliable per-key detection on X11 and Wayland. {}", input_detail))
+            .with_fix(format!("{} Alternatively add your user to the input group and log back in.", UDEV_FIX)),
+        PermissionCheck::new("uinput", "Input grab passthrough (optional)", uinput_status,
+            format!("Needed only for the \"grab device input\" setting. {}", uinput_detail))
+            .with_fix(UDEV_FIX),
+    ]
+}
+
+// ── Windows ─────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "windows")]
+pub fn check_all() -> Vec<PermissionCheck> {
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn OpenProcessToken(process: isize, access: u32, token: *mut isize) -> i32;
+        fn GetTokenInformation(
+            token: isize,
+            class: u32,
+            info: *mut u8,
+            info_len: u32,
+            return_len: *mut u32,
+        ) -> i32;
+    }
+    extern "system" {
+        fn GetCurrentProcess() -> isize;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+    const TOKEN_QUERY: u32 = 0x0008;
+    const TOKEN_ELEVATION: u32 = 20;
+
+    let elevated = unsafe {
+        let mut token = 0isize;
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            None
+        } else {
+            let mut elevation = 0u32;
+            let mut len = 0u32;
+            let ok = GetTokenInformation(
+                token,
+                TOKEN_ELEVATION,
+                &mut elevation as *mut u32 as *mut u8,
+                std::mem::size_of::<u32>() as u32,
+                &mut len,
+            );
+            CloseHandle(token);
+            (ok != 0).then_some(elevation != 0)
+        }
+    };
+
+    let (status, detail) = match elevated {
+        Some(true) => (PermissionStatus::Granted, "Running elevated; shortcuts work over admin windows too."),
+        Some(false) => (
+            PermissionStatus::Limited,
+            "Windows blocks the keyboard hook while an elevated (admin) window has focus, so Deck-8 keys aren't detected there.",
+        ),
+        None => (PermissionStatus::Unknown, "Couldn't read the process token."),
+    };
+
+    vec![PermissionCheck::new("elevated_windows", "Shortcuts over admin windows", status, detail)
+        .with_fix("Run Deck-8 Hub as administrator if you need the keys while admin apps are focused.")]
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub fn check_all() -> Vec<PermissionCheck> {
+    Vec::new()
+}
@@ -0,0 +1,203 @@
+// Playlists: a key plays several sounds back to back with no gap (an intro
+// sting into a looping bed), until skipped past the last one or stopped.
+//
+// Single sounds go through the injection ring, one after the other. A
+// playlist instead gets its own voice in the mixer (`MicSource` adds it to the
+// ring's output), so it can loop, skip and stop without touching whatever is
+// queued in the ring. Only one playlist plays at a time; starting another
+// fades out the sound playing, as a skip does, then replaces it. The local (headphone) copy is a rodio `Sink` queue, which is
+// gapless too, driven through a channel.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ringbuf::traits::Consumer;
+use rodio::buffer::SamplesBuffer;
+use rodio::{Sink, Source};
+
+/// One sound of a playlist, decoded in pipeline format.
+pub struct PlaylistItem {
+    pub samples: Arc<Vec<f32>>,
+    /// False for monitor-only sounds: the mic gets silence of the same length.
+    pub to_mic: bool,
+}
+
+pub enum Command {
+    /// Play `items`, after fading out a playlist playing.
+    Start { items: Vec<PlaylistItem>, loop_last: bool, gain: f32 },
+    /// Fade out the current sound and go on with the next.
+    Skip,
+    /// Fade out and end the playlist.
+    Stop,
+}
+
+/// What a fade-out ends in.
+#[derive(Clone, Copy)]
+enum Then {
+    Next,
+    /// Stop, or start the playlist waiting in `next`.
+    End,
+}
+
+/// The playlist's voice in the mixer, read one sample at a time on the audio
+/// thread.
+pub struct PlaylistVoice {
+    commands: ringbuf::HeapCons<Command>,
+    items: Vec<PlaylistItem>,
+    loop_last: bool,
+    gain: f32,
+    index: usize,
+    pos: usize,
+    /// Samples into a fade-out, and what follows it.
+    fading: Option<(u64, Then)>,
+    /// Playlist started during a fade-out: items, loop_last, gain.
+    next: Option<(Vec<PlaylistItem>, bool, f32)>,
+    fade_len: u64,
+    channels: u64,
+    /// Samples read so far, so a playlist starts on a frame boundary.
+    read: u64,
+    /// Set while a playlist is playing.
+    playing: Arc<AtomicBool>,
+}
+
+impl PlaylistVoice {
+    /// `fade_len` in samples (all channels).
+    pub fn new(commands: ringbuf::HeapCons<Command>, channels: u16, fade_len: u64, playing: Arc<AtomicBool>) -> Self {
+        Self {
+            commands,
+            items: Vec::new(),
+            loop_last: false,
+            gain: 1.0,
+            index: 0,
+            pos: 0,
+            fading: None,
+            next: None,
+            fade_len: fade_len.max(1),
+            channels: channels.max(1) as u64,
+            read: 0,
+            playing,
+        }
+    }
+
+    /// Next sample, 0.0 when nothing is playing.
+    pub fn next_sample(&mut self) -> f32 {
+        let frame_start = self.read.is_multiple_of(self.channels);
+        self.read += 1;
+        if frame_start {
+            while let Some(command) = self.commands.try_pop() {
+                self.apply(command);
+            }
+        }
+        loop {
+            let Some(item) = self.items.get(self.index) else { return 0.0 };
+            let mut gain = self.gain;
+            if let Some((done, then)) = self.fading {
+                if done >= self.fade_len && frame_start {
+                    self.fading = None;
+                    match then {
+                        Then::Next => self.advance(),
+                        Then::End => self.end(),
+                    }
+                    continue;
+                }
+                gain *= 1.0 - (done as f32 / self.fade_len as f32).min(1.0);
+                self.fading = Some((done + 1, then));
+            }
+            let Some(&sample) = item.samples.get(self.pos) else {
+                if self.index + 1 == self.items.len() && self.loop_last && self.pos > 0 {
+                    self.pos = 0;
+                } else {
+                    self.advance();
+                }
+                continue;
+            };
+            self.pos += 1;
+            return if item.to_mic { sample * gain } else { 0.0 };
+        }
+    }
+
+    fn apply(&mut self, command: Command) {
+        match command {
+            Command::Start { items, loop_last, gain } if !self.items.is_empty() => {
+                // Carry on from where a fade already got to
+                let done = self.fading.map_or(0, |(done, _)| done);
+                self.next = Some((items, loop_last, gain));
+                self.fading = Some((done, Then::End));
+            }
+            Command::Start { items, loop_last, gain } => self.start(items, loop_last, gain),
+            Command::Skip if !self.items.is_empty() && self.next.is_none() => self.fading = Some((0, Then::Next)),
+            Command::Stop if !self.items.is_empty() => {
+                self.next = None;
+                self.fading = Some((0, Then::End));
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, items: Vec<PlaylistItem>, loop_last: bool, gain: f32) {
+        (self.items, self.loop_last, self.gain) = (items, loop_last, gain);
+        (self.index, self.pos, self.fading) = (0, 0, None);
+        self.playing.store(!self.items.is_empty(), Ordering::Relaxed);
+    }
+
+    fn advance(&mut self) {
+        self.index += 1;
+        self.pos = 0;
+        if self.index >= self.items.len() {
+            self.end();
+        }
+    }
+
+    /// Stop, or go on with the playlist started during the fade-out.
+    fn end(&mut self) {
+        if let Some((items, loop_last, gain)) = self.next.take() {
+            self.start(items, loop_last, gain);
+            return;
+        }
+        self.items.clear();
+        (self.index, self.pos, self.fading) = (0, 0, None);
+        self.playing.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Control for the local copy.
+pub enum Local {
+    Skip,
+    Stop,
+}
+
+/// Play the sounds back to back through the default output. Dropping the
+/// sender stops it too.
+pub fn play_locally(
+    items: Vec<Arc<Vec<f32>>>,
+    loop_last: bool,
+    channels: u16,
+    sample_rate: u32,
+    volume: f32,
+) -> mpsc::Sender<Local> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let Ok((_stream, handle)) = crate::audio::open_default_output() else { return };
+        let Ok(sink) = Sink::try_new(&handle) else { return };
+        sink.set_volume(volume);
+        let last = items.len().saturating_sub(1);
+        for (i, samples) in items.into_iter().enumerate() {
+            let buffer = SamplesBuffer::new(channels, sample_rate, samples.to_vec());
+            if i == last && loop_last {
+                sink.append(buffer.repeat_infinite());
+            } else {
+                sink.append(buffer);
+            }
+        }
+        while !sink.empty() {
+            match rx.recv_timeout(Duration::from_millis(10)) {
+                Ok(Local::Skip) => sink.skip_one(),
+                Ok(Local::Stop) | Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+            }
+        }
+    });
+    tx
+}
@@ -0,0 +1,218 @@
+// Focus timer (Pomodoro) and its statistics.
+//
+// `start_focus` runs one focus session of `FocusSettings.focus_min`; a key
+// bound to `FocusRunning` is lit while one runs and starts or stops it when
+// pressed. Sessions that run to the end are appended to focus.json next to
+// state.json (stopped ones aren't counted) and emitted as `focus-completed`
+// with the new summary: sessions and minutes per day for the last week, this
+// week's totals (from Monday) and the streak of days that met the daily goal.
+// Reaching the goal flashes every key once, if `celebrate` is on.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::notify::Pattern;
+use crate::protocol::HsvColor;
+use crate::now_ms;
+use crate::state::SharedState;
+
+const CELEBRATION_MS: u64 = 4000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FocusSettings {
+    /// Length of a focus session (1–180 min).
+    pub focus_min: u16,
+    /// Sessions a day that count toward the streak; 0 for no goal.
+    pub daily_goal: u16,
+    /// Flash the keys when the day's goal is reached.
+    pub celebrate: bool,
+}
+
+impl Default for FocusSettings {
+    fn default() -> Self {
+        Self { focus_min: 25, daily_goal: 8, celebrate: true }
+    }
+}
+
+impl FocusSettings {
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=180).contains(&self.focus_min) {
+            bail!("A focus session must be 1–180 minutes");
+        }
+        if self.daily_goal > 48 {
+            bail!("The daily goal must be at most 48 sessions");
+        }
+        Ok(())
+    }
+}
+
+/// A completed focus session.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Session {
+    /// Unix time (ms).
+    pub started_ms: u64,
+    pub minutes: u16,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DaySummary {
+    /// Local date, `YYYY-MM-DD`.
+    pub date: String,
+    pub sessions: u32,
+    pub minutes: u32,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FocusSummary {
+    /// The last 7 days, oldest first; the last one is today.
+    pub days: Vec<DaySummary>,
+    /// Since Monday.
+    pub week_sessions: u32,
+    pub week_minutes: u32,
+    /// Days in a row that met the goal, up to today (or yesterday while today
+    /// still can). 0 without a goal.
+    pub streak: u32,
+    pub daily_goal: u16,
+}
+
+/// The session running now.
+#[derive(Debug, Clone, Copy)]
+struct Running {
+    started_ms: u64,
+    minutes: u16,
+}
+
+static RUNNING: Mutex<Option<Running>> = Mutex::new(None);
+static HISTORY: Mutex<Option<Vec<Session>>> = Mutex::new(None);
+
+fn history_file() -> Result<PathBuf> {
+    Ok(crate::profile::config_dir()?.join("focus.json"))
+}
+
+fn with_history<T>(f: impl FnOnce(&mut Vec<Session>) -> T) -> T {
+    let mut history = HISTORY.lock().unwrap();
+    let history = history.get_or_insert_with(|| {
+        history_file().ok()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    });
+    f(history)
+}
+
+/// Unix time (ms) the running session ends, if one is running.
+pub fn running_until() -> Option<u64> {
+    RUNNING.lock().unwrap().map(|r| r.started_ms + r.minutes as u64 * 60_000)
+}
+
+/// Start a session of `minutes`, replacing one that's running.
+pub fn start<R: Runtime>(app: &AppHandle<R>, minutes: u16) {
+    let session = Running { started_ms: now_ms(), minutes };
+    *RUNNING.lock().unwrap() = Some(session);
+    info!("[focus] {minutes} min started");
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(minutes as u64 * 60));
+        {
+            let mut running = RUNNING.lock().unwrap();
+            // Stopped, or replaced by a newer session
+            if running.is_none_or(|r| r.started_ms != session.started_ms) {
+                return;
+            }
+            *running = None;
+        }
+        complete(&app, Session { started_ms: session.started_ms, minutes });
+    });
+}
+
+/// Stop the running session without counting it. False if none was running.
+pub fn stop() -> bool {
+    let stopped = RUNNING.lock().unwrap().take().is_some();
+    if stopped {
+        info!("[focus] stopped");
+    }
+    stopped
+}
+
+fn complete<R: Runtime>(app: &AppHandle<R>, session: Session) {
+    info!("[focus] {} min completed", session.minutes);
+    let sessions = with_history(|history| {
+        history.push(session);
+        let result = history_file().and_then(|path| {
+            let json = serde_json::to_string(history).context("Failed to serialize focus sessions")?;
+            fs::write(path, json).context("Failed to write focus sessions")
+        });
+        if let Err(e) = result {
+            error!("[focus] {e:#}");
+        }
+        history.clone()
+    });
+    let state = app.state::<SharedState>();
+    let settings = state.lock().unwrap().settings.focus;
+    let summary = summarize(&sessions, today(), settings.daily_goal);
+    let today_sessions = summary.days.last().map_or(0, |d| d.sessions);
+    if settings.celebrate && settings.daily_goal > 0 && today_sessions == settings.daily_goal as u32 {
+        celebrate(app);
+    }
+    let _ = app.emit("focus-completed", &summary);
+    let _ = app.emit("state-updated", &state.lock().unwrap().snapshot());
+}
+
+/// Every key pulses a different hue for a few seconds.
+fn celebrate<R: Runtime>(app: &AppHandle<R>) {
+    for k in 0..8 {
+        let color = HsvColor { h: (k * 32) as u8, s: 0xFF, v: 0xFF };
+        if let Err(e) = crate::notify::notify(app, k, color, Pattern::Pulse, CELEBRATION_MS, 1) {
+            error!("[focus] celebration: {e}");
+        }
+    }
+}
+
+pub fn summary(daily_goal: u16) -> FocusSummary {
+    with_history(|history| summarize(history, today(), daily_goal))
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+fn day_of(session: &Session) -> Option<NaiveDate> {
+    Local.timestamp_millis_opt(session.started_ms as i64).single().map(|t| t.date_naive())
+}
+
+pub fn summarize(sessions: &[Session], today: NaiveDate, daily_goal: u16) -> FocusSummary {
+    let day = |date: NaiveDate| {
+        let on_day = sessions.iter().filter(|s| day_of(s) == Some(date));
+        DaySummary {
+            date: date.to_string(),
+            sessions: on_day.clone().count() as u32,
+            minutes: on_day.map(|s| s.minutes as u32).sum(),
+        }
+    };
+    let days: Vec<DaySummary> = (0..7).rev().map(|n| day(today - chrono::Days::new(n))).collect();
+    let monday = today - chrono::Days::new(today.weekday().num_days_from_monday() as u64);
+    let this_week = sessions.iter().filter(|s| day_of(s).is_some_and(|d| d >= monday && d <= today));
+    let met = |date: NaiveDate| daily_goal > 0 && day(date).sessions >= daily_goal as u32;
+    let mut streak = 0;
+    // Today only adds to the streak once it's met
+    let mut date = if met(today) { today } else { today - chrono::Days::new(1) };
+    while met(date) {
+        streak += 1;
+        date = date - chrono::Days::new(1);
+    }
+    FocusSummary {
+        days,
+        week_sessions: this_week.clone().count() as u32,
+        week_minutes: this_week.map(|s| s.minutes as u32).sum(),
+        streak,
+        daily_goal,
+    }
+}
@@ -0,0 +1,106 @@
+// Theme previews: show a saved theme on the pad for a few seconds without
+// applying it, so themes can be browsed on the device itself.
+//
+// Like the progress bar, a preview owns the LEDs while it's up: key color
+// writes (`apply_key_to_device`, fades) hold off and `apply_all_to_device`
+// redraws the preview. Nothing in `AppState` changes, so ending it (timeout,
+// `end_theme_preview` or a newer preview) is just `apply_all_to_device` with
+// the keys as they are, including slot changes made meanwhile. Key
+// notifications show on top. Emits `theme-preview` with the theme's name, then
+// `null` when it ends.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::info;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::error::HubError;
+use crate::hid::Deck8Device;
+use crate::protocol::HsvColor;
+use crate::state::{AppState, SharedState};
+
+/// How long a preview lasts unless asked otherwise, and at most.
+pub const DEFAULT_SECS: u64 = 5;
+pub const MAX_SECS: u64 = 60;
+
+struct Preview {
+    id: u64,
+    colors: [HsvColor; 8],
+}
+
+static PREVIEW: Mutex<Option<Preview>> = Mutex::new(None);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Whether a preview is covering the keys.
+pub fn active() -> bool {
+    PREVIEW.lock().unwrap().is_some()
+}
+
+/// Show `colors` (by LED) for `secs`, replacing any preview up.
+pub fn show<R: Runtime>(app: &AppHandle<R>, st: &AppState, name: &str, colors: [HsvColor; 8], secs: u64) -> Result<(), HubError> {
+    if !(1..=MAX_SECS).contains(&secs) {
+        return Err(crate::i18n::t_with("error-preview-length", &[("max", &MAX_SECS)]).into());
+    }
+    let dev = st.device.as_ref().ok_or(HubError::NotConnected)?;
+    if crate::dnd_active() {
+        return Err(crate::i18n::t("error-dnd-on").into());
+    }
+    if crate::progress::active() {
+        return Err(crate::i18n::t("error-progress-showing").into());
+    }
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    *PREVIEW.lock().unwrap() = Some(Preview { id, colors });
+    for i in 0..8 {
+        crate::fade::cancel(i);
+    }
+    if let Err(e) = redraw(dev) {
+        // No timer is running to end it, and it would hold off every key write
+        end(app, st);
+        return Err(e.into());
+    }
+    info!("[preview] {:?} for {}s", name, secs);
+    let _ = app.emit("theme-preview", Some(name));
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(secs));
+        // A newer preview (or an early end) owns the keys now
+        if PREVIEW.lock().unwrap().as_ref().is_some_and(|p| p.id == id) {
+            let state = app.state::<SharedState>();
+            let st = state.lock().unwrap();
+            end(&app, &st);
+        }
+    });
+    Ok(())
+}
+
+/// Draw the preview if one is up. Returns whether it is; the keys stay dark
+/// during do-not-disturb.
+pub fn redraw(dev: &Deck8Device) -> anyhow::Result<bool> {
+    let Some(colors) = PREVIEW.lock().unwrap().as_ref().map(|p| p.colors) else { return Ok(false) };
+    if !crate::dnd_active() {
+        let batch: Vec<(u8, HsvColor)> = colors
+            .iter()
+            .enumerate()
+            .filter(|&(led, _)| !crate::notify::covers(led))
+            .map(|(led, color)| (led as u8, crate::brightness::key_color(color)))
+            .collect();
+        dev.set_key_colors(&batch)?;
+    }
+    Ok(true)
+}
+
+/// Take the preview down and put the keys' own colors back. False if none
+/// was up.
+pub fn end<R: Runtime>(app: &AppHandle<R>, st: &AppState) -> bool {
+    if PREVIEW.lock().unwrap().take().is_none() {
+        return false;
+    }
+    if let Some(ref dev) = st.device {
+        crate::apply_all_to_device(dev, &st.keys);
+    }
+    let _ = app.emit("theme-preview", None::<&str>);
+    true
+}
@@ -1,9 +1,14 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
-use crate::state::{AudioConfig, KeyConfig};
+use crate::modes::Mode;
+use crate::protocol::{HsvColor, RgbMatrixState};
+use crate::rules::Rule;
+use crate::state::{AppSettings, AppState, AudioConfig, Condition, KeyConfig, KeyGroup, LightTarget, Theme};
 
 // ── Auto-persisted state ────────────────────────────────────────────────
 
@@ -15,38 +20,231 @@ struct PersistedState {
     pub audio_config: Option<AudioConfig>,
     #[serde(default)]
     pub keymaps: Option<Vec<u16>>,
+    #[serde(default)]
+    pub settings: Option<AppSettings>,
+    #[serde(default)]
+    pub palette: Vec<HsvColor>,
+    #[serde(default)]
+    pub themes: Vec<Theme>,
+    #[serde(default)]
+    pub groups: Vec<KeyGroup>,
+    #[serde(default)]
+    pub modes: Vec<Mode>,
+    #[serde(default)]
+    pub active_mode: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    #[serde(default)]
+    pub rgb_matrix: Option<RgbMatrixState>,
+    #[serde(default)]
+    pub echo_checked_firmware: Option<u32>,
 }
 
-/// Path: %APPDATA%/deck8-hub/state.json
-fn state_file() -> Result<PathBuf> {
+/// State restored from disk on startup.
+pub struct RestoredState {
+    pub keys: [KeyConfig; 8],
+    pub audio_config: Option<AudioConfig>,
+    pub keymaps: Option<[u16; 8]>,
+    pub settings: Option<AppSettings>,
+    pub palette: Vec<HsvColor>,
+    pub themes: Vec<Theme>,
+    pub groups: Vec<KeyGroup>,
+    pub modes: Vec<Mode>,
+    pub active_mode: Option<String>,
+    pub rules: Vec<Rule>,
+    pub rgb_matrix: Option<RgbMatrixState>,
+    pub echo_checked_firmware: Option<u32>,
+}
+
+/// Path: %APPDATA%/deck8-hub (state.json, debug.log, sounds/)
+pub fn config_dir() -> Result<PathBuf> {
+    #[cfg(not(test))]
     let base = dirs::config_dir().context("Cannot determine config directory")?;
+    // Tests never touch the real config
+    #[cfg(test)]
+    let base = std::env::temp_dir().join("deck8-hub-tests");
     let dir = base.join("deck8-hub");
     if !dir.exists() {
         fs::create_dir_all(&dir).context("Failed to create config directory")?;
     }
-    Ok(dir.join("state.json"))
+    Ok(dir)
+}
+
+/// Path: %APPDATA%/deck8-hub/state.json
+fn state_file() -> Result<PathBuf> {
+    Ok(config_dir()?.join("state.json"))
 }
 
-/// Save current key state, audio config, and keymaps to disk.
-pub fn save_state(keys: &[KeyConfig; 8], audio_config: &AudioConfig, keymaps: &[u16; 8]) -> Result<()> {
-    let persisted = PersistedState {
-        keys: keys.to_vec(),
-        audio_config: Some(audio_config.clone()),
-        keymaps: Some(keymaps.to_vec()),
-    };
-    let json = serde_json::to_string(&persisted).context("Failed to serialize state")?;
+/// Save current key state, audio config, keymaps, settings, palette, themes,
+/// key groups, modes, LED rules and RGB settings to disk.
+pub fn save_state(state: &AppState) -> Result<()> {
+    let json = serde_json::to_string(&persisted(state)).context("Failed to serialize state")?;
     fs::write(state_file()?, json).context("Failed to write state file")?;
     Ok(())
 }
 
-/// Load key state, audio config, and keymaps from disk.
-pub fn load_state() -> Option<([KeyConfig; 8], Option<AudioConfig>, Option<[u16; 8]>)> {
+fn persisted(state: &AppState) -> PersistedState {
+    PersistedState {
+        keys: state.keys.to_vec(),
+        audio_config: Some(state.audio_config.clone()),
+        // Keep the user's binds on disk while a restore is still pending
+        keymaps: Some(state.keymap_restore.unwrap_or(state.keymaps).to_vec()),
+        settings: Some(state.settings.clone()),
+        palette: state.palette.clone(),
+        themes: state.themes.clone(),
+        groups: state.groups.clone(),
+        modes: state.modes.clone(),
+        active_mode: state.active_mode.clone(),
+        rules: state.rules.clone(),
+        rgb_matrix: state.saved_rgb,
+        echo_checked_firmware: state.echo_checked_firmware,
+    }
+}
+
+/// Load key state, audio config, keymaps, and settings from disk.
+pub fn load_state() -> Option<RestoredState> {
     let path = state_file().ok()?;
     let json = fs::read_to_string(path).ok()?;
-    let persisted: PersistedState = serde_json::from_str(&json).ok()?;
+    restore(serde_json::from_str(&json).ok()?)
+}
+
+fn restore(persisted: PersistedState) -> Option<RestoredState> {
     let keys: [KeyConfig; 8] = persisted.keys.try_into().ok()?;
     let keymaps = persisted
         .keymaps
         .and_then(|v| <[u16; 8]>::try_from(v).ok());
-    Some((keys, persisted.audio_config, keymaps))
+    Some(RestoredState {
+        keys,
+        audio_config: persisted.audio_config,
+        keymaps,
+        settings: persisted.settings,
+        palette: persisted.palette,
+        themes: persisted.themes,
+        groups: persisted.groups,
+        modes: persisted.modes,
+        active_mode: persisted.active_mode,
+        rules: persisted.rules,
+        rgb_matrix: persisted.rgb_matrix,
+        echo_checked_firmware: persisted.echo_checked_firmware,
+    })
+}
+
+// ── Bundles (pairing.rs) ────────────────────────────────────────────────
+
+/// The whole setup in one file: state.json plus the sound files it uses.
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    state: PersistedState,
+    sounds: Vec<BundledSound>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundledSound {
+    filename: String,
+    /// Base64.
+    data: String,
+}
+
+/// Bundle the current setup for another PC (pairing.rs). Missing sound files
+/// are left out, and so are credentials (`strip_credentials`): they're
+/// entered again on the other PC.
+pub fn export_bundle(state: &AppState) -> Result<Vec<u8>> {
+    let dir = crate::audio::sounds_dir()?;
+    let sounds = state
+        .audio_config
+        .sound_library
+        .iter()
+        .filter_map(|s| Some(BundledSound { filename: s.filename.clone(), data: BASE64.encode(fs::read(dir.join(&s.filename)).ok()?) }))
+        .collect();
+    let mut state = persisted(state);
+    strip_credentials(&mut state);
+    serde_json::to_vec(&Bundle { state, sounds }).context("Failed to serialize the bundle")
+}
+
+/// Clear OBS passwords, calendar links (the feed's address is its secret),
+/// the Discord client secret, Hue app keys and the lock PIN.
+fn strip_credentials(state: &mut PersistedState) {
+    for binding in state.keys.iter_mut().filter_map(|k| k.binding.as_mut()) {
+        match &mut binding.condition {
+            Condition::ObsRecording { password, .. }
+            | Condition::ObsStreaming { password, .. }
+            | Condition::ObsScene { password, .. } => *password = None,
+            Condition::CalendarMeeting { url, .. } => url.clear(),
+            _ => {}
+        }
+    }
+    let Some(settings) = state.settings.as_mut() else { return };
+    settings.discord.client_secret = None;
+    settings.game.token = None;
+    settings.lock_pin = None;
+    for target in &mut settings.lighting.targets {
+        if let LightTarget::Hue { user, .. } = target {
+            user.clear();
+        }
+    }
+}
+
+/// Unpack a bundle: its sounds are written to the sounds folder (replacing
+/// files of the same name) and its state is returned to apply. Every file
+/// name, in the library and among the sounds, is checked and every sound
+/// decoded before anything is written: the library's names are later joined
+/// to the sounds folder to delete files.
+pub fn import_bundle(json: &[u8]) -> Result<RestoredState> {
+    let bundle: Bundle = serde_json::from_slice(json).context("Not a Deck-8 Hub bundle")?;
+    let state = restore(bundle.state).context("The bundle doesn't have 8 keys")?;
+    let library = state.audio_config.iter().flat_map(|c| &c.sound_library).map(|s| &s.filename);
+    if let Some(name) = library.chain(bundle.sounds.iter().map(|s| &s.filename)).find(|name| !is_plain_filename(name)) {
+        bail!("Unexpected sound file name: {name}");
+    }
+    let sounds = bundle
+        .sounds
+        .into_iter()
+        .map(|sound| {
+            let data = BASE64.decode(&sound.data).with_context(|| format!("{} is corrupt", sound.filename))?;
+            Ok((sound.filename, data))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let dir = crate::audio::sounds_dir()?;
+    for (filename, data) in sounds {
+        fs::write(dir.join(&filename), data).with_context(|| format!("Failed to write {filename}"))?;
+    }
+    Ok(state)
+}
+
+/// A file name that stays inside the folder it's joined to.
+pub fn is_plain_filename(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', ':'])
+}
+
+// ── Startup crash tracking ──────────────────────────────────────────────
+
+/// Consecutive startups that didn't survive STARTUP_GRACE before safe mode
+/// kicks in automatically.
+pub const CRASH_THRESHOLD: u32 = 3;
+
+/// How long the app must stay up for a startup to count as successful.
+pub const STARTUP_GRACE: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// Path: %APPDATA%/deck8-hub/startup.marker (holds the unfinished-startup count)
+fn startup_marker() -> Result<PathBuf> {
+    Ok(state_file()?.with_file_name("startup.marker"))
+}
+
+/// Record a startup attempt. Returns how many previous startups crashed
+/// (never reached `clear_startup`) in a row.
+pub fn record_startup() -> u32 {
+    let Ok(path) = startup_marker() else { return 0 };
+    let previous = fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .unwrap_or(0);
+    let _ = fs::write(&path, (previous + 1).to_string());
+    previous
+}
+
+/// Mark the current startup as successful.
+pub fn clear_startup() {
+    if let Ok(path) = startup_marker() {
+        let _ = fs::remove_file(path);
+    }
 }
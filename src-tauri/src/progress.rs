@@ -0,0 +1,84 @@
+// LED progress bar for scripts (builds, renders, downloads).
+//
+// `set_progress` fills the keys in matrix order (K1…K8) with one color: each
+// key is 12.5%, the partly filled one is dimmed to its share and the rest are
+// dark. While a bar is up it owns the LEDs: key color writes
+// (`apply_key_to_device`, fades) hold off, and `apply_all_to_device` redraws
+// the bar instead (reconnect, do-not-disturb exit). `clear_progress` puts
+// every key's own color back. Key notifications (notify.rs) show on top.
+
+use std::sync::Mutex;
+
+use crate::error::HubError;
+use crate::hid::Deck8Device;
+use crate::protocol::HsvColor;
+use crate::state::AppState;
+
+#[derive(Debug, Clone, Copy)]
+struct Bar {
+    percent: f32,
+    color: HsvColor,
+}
+
+static BAR: Mutex<Option<Bar>> = Mutex::new(None);
+
+/// Whether a bar is covering the keys.
+pub fn active() -> bool {
+    BAR.lock().unwrap().is_some()
+}
+
+/// The bar's color per LED index.
+pub fn leds(percent: f32, color: HsvColor) -> [HsvColor; 8] {
+    let led_order = crate::definition::current().led_order;
+    let filled = percent / 100.0 * 8.0;
+    let mut leds = [HsvColor { v: 0, ..color }; 8];
+    for (key, &led) in led_order.iter().enumerate() {
+        let share = (filled - key as f32).clamp(0.0, 1.0);
+        leds[led as usize].v = (color.v as f32 * share).round() as u8;
+    }
+    leds
+}
+
+/// Show (or move) the bar.
+pub fn show(st: &AppState, percent: f32, color: HsvColor) -> Result<(), HubError> {
+    if !(0.0..=100.0).contains(&percent) {
+        return Err(crate::i18n::t_with("error-progress-percent", &[("percent", &percent)]).into());
+    }
+    let dev = st.device.as_ref().ok_or(HubError::NotConnected)?;
+    let previous = BAR.lock().unwrap().replace(Bar { percent, color });
+    for i in 0..8 {
+        crate::fade::cancel(i);
+    }
+    if let Err(e) = redraw(dev) {
+        // The keys never showed this bar
+        *BAR.lock().unwrap() = previous;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Draw the bar if one is up. Returns whether it is; the keys stay dark
+/// during do-not-disturb.
+pub fn redraw(dev: &Deck8Device) -> anyhow::Result<bool> {
+    let Some(bar) = *BAR.lock().unwrap() else { return Ok(false) };
+    if !crate::dnd_active() {
+        let batch: Vec<(u8, HsvColor)> = leds(bar.percent, bar.color)
+            .iter()
+            .enumerate()
+            .filter(|&(led, _)| !crate::notify::covers(led))
+            .map(|(led, color)| (led as u8, crate::brightness::key_color(color)))
+            .collect();
+        dev.set_key_colors(&batch)?;
+    }
+    Ok(true)
+}
+
+/// Take the bar down and restore every key's color. No-op without a bar.
+pub fn clear(st: &AppState) {
+    if BAR.lock().unwrap().take().is_none() {
+        return;
+    }
+    if let Some(ref dev) = st.device {
+        crate::apply_all_to_device(dev, &st.keys);
+    }
+}
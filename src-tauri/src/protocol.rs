@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::definition;
+
 /// VID/PID for Churrosoft Deck-8
 pub const VID: u16 = 0xCBBC;
 pub const PID: u16 = 0xC101;
@@ -8,6 +10,9 @@ pub const PID: u16 = 0xC101;
 pub const USAGE_PAGE: u16 = 0xFF60;
 pub const USAGE_ID: u16 = 0x61;
 
+/// VIA protocol version this app speaks (custom channels, VIA v3).
+pub const VIA_PROTOCOL_VERSION: u16 = 0x000C;
+
 // ── VIA top-level command IDs ───────────────────────────────────────────
 
 pub const VIA_GET_PROTOCOL_VERSION: u8 = 0x01;
@@ -22,6 +27,7 @@ pub const VIA_EEPROM_RESET: u8 = 0x0A;
 pub const VIA_BOOTLOADER_JUMP: u8 = 0x0B;
 pub const VIA_MACRO_GET_COUNT: u8 = 0x0C;
 pub const VIA_MACRO_GET_BUFFER_SIZE: u8 = 0x0D;
+pub const VIA_MACRO_GET_BUFFER: u8 = 0x0E;
 pub const VIA_MACRO_RESET: u8 = 0x10;
 pub const VIA_GET_LAYER_COUNT: u8 = 0x11;
 
@@ -36,12 +42,15 @@ pub const KB_VALUE_DEVICE_INDICATION: u8 = 0x05;
 
 const CUSTOM_CHANNEL: u8 = 0x07;
 
-/// Per-key custom channel sub-command IDs
-const CMD_ENABLE_OVERRIDE: u8 = 0x01;
-const CMD_SET_BRIGHTNESS: u8 = 0x02;
-const CMD_SET_COLOR: u8 = 0x03;
+// Per-key channel, sub-command and value IDs come from the device definition
+// (definition.rs). Builds from before the features handshake answer 0xFF.
 
-/// RGB Matrix custom channel ID (used with VIA_CUSTOM_GET_VALUE / VIA_CUSTOM_SAVE)
+/// Feature bits in the features reply.
+const FEATURE_PER_KEY: u16 = 1 << 0;
+const FEATURE_RGB_MATRIX: u16 = 1 << 1;
+const FEATURE_SET_HSV: u16 = 1 << 2;
+
+/// Stock RGB Matrix custom channel ID (used with VIA_CUSTOM_GET_VALUE / VIA_CUSTOM_SAVE)
 pub const RGB_MATRIX_CHANNEL: u8 = 0x03;
 
 /// RGB Matrix value IDs within the RGB Matrix channel
@@ -76,6 +85,41 @@ pub struct DeviceInfo {
     pub layer_count: u8,
     pub macro_count: u8,
     pub macro_buffer_size: u16,
+    #[serde(default)]
+    pub capabilities: Capabilities,
+}
+
+/// What the connected firmware supports, from the features handshake.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Capabilities {
+    /// False when the firmware predates the handshake; the rest is then assumed.
+    pub reported: bool,
+    pub per_key_override: bool,
+    pub rgb_matrix: bool,
+    /// Sets H, S, V and the override in one report (`build_set_hsv`).
+    #[serde(default)]
+    pub set_hsv: bool,
+}
+
+impl Capabilities {
+    /// Every build before the handshake shipped both channels, and no
+    /// combined color report.
+    pub const LEGACY: Self = Self { reported: false, per_key_override: true, rgb_matrix: true, set_hsv: false };
+
+    pub fn from_bits(bits: u16) -> Self {
+        Self {
+            reported: true,
+            per_key_override: bits & FEATURE_PER_KEY != 0,
+            rgb_matrix: bits & FEATURE_RGB_MATRIX != 0,
+            set_hsv: bits & FEATURE_SET_HSV != 0,
+        }
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::LEGACY
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -92,9 +136,10 @@ pub struct RgbMatrixState {
 /// Build a 32-byte report to set H and S for a key.
 pub fn build_set_color(key_id: u8, color: &HsvColor) -> [u8; 32] {
     let mut buf = [0u8; 32];
+    let def = definition::current();
     buf[0] = CUSTOM_CHANNEL;
-    buf[1] = 0x00;
-    buf[2] = CMD_SET_COLOR;
+    buf[1] = def.per_key_channel;
+    buf[2] = def.cmd_set_color;
     buf[3] = LAYER;
     buf[4] = key_id;
     buf[5] = color.h;
@@ -105,21 +150,39 @@ pub fn build_set_color(key_id: u8, color: &HsvColor) -> [u8; 32] {
 /// Build a 32-byte report to set brightness (V) for a key.
 pub fn build_set_brightness(key_id: u8, brightness: u8) -> [u8; 32] {
     let mut buf = [0u8; 32];
+    let def = definition::current();
     buf[0] = CUSTOM_CHANNEL;
-    buf[1] = 0x00;
-    buf[2] = CMD_SET_BRIGHTNESS;
+    buf[1] = def.per_key_channel;
+    buf[2] = def.cmd_set_brightness;
     buf[3] = LAYER;
     buf[4] = key_id;
     buf[5] = brightness;
     buf
 }
 
+/// Build a 32-byte report that sets H, S and V for a key and enables its
+/// override, replacing the three reports above (firmware with `set_hsv`).
+pub fn build_set_hsv(key_id: u8, color: &HsvColor) -> [u8; 32] {
+    let mut buf = [0u8; 32];
+    let def = definition::current();
+    buf[0] = CUSTOM_CHANNEL;
+    buf[1] = def.per_key_channel;
+    buf[2] = def.cmd_set_hsv;
+    buf[3] = LAYER;
+    buf[4] = key_id;
+    buf[5] = color.h;
+    buf[6] = color.s;
+    buf[7] = color.v;
+    buf
+}
+
 /// Build a 32-byte report to enable per-key override for a key.
 pub fn build_enable_override(key_id: u8) -> [u8; 32] {
     let mut buf = [0u8; 32];
+    let def = definition::current();
     buf[0] = CUSTOM_CHANNEL;
-    buf[1] = 0x00;
-    buf[2] = CMD_ENABLE_OVERRIDE;
+    buf[1] = def.per_key_channel;
+    buf[2] = def.cmd_enable_override;
     buf[3] = LAYER;
     buf[4] = key_id;
     buf[5] = 0x01;
@@ -129,21 +192,32 @@ pub fn build_enable_override(key_id: u8) -> [u8; 32] {
 /// Build a 32-byte report to disable per-key override (restore original).
 pub fn build_disable_override(key_id: u8) -> [u8; 32] {
     let mut buf = [0u8; 32];
+    let def = definition::current();
     buf[0] = CUSTOM_CHANNEL;
-    buf[1] = 0x00;
-    buf[2] = CMD_ENABLE_OVERRIDE;
+    buf[1] = def.per_key_channel;
+    buf[2] = def.cmd_enable_override;
     buf[3] = LAYER;
     buf[4] = key_id;
     buf[5] = 0x00;
     buf
 }
 
+/// Build a 32-byte report reading the firmware's feature bits.
+pub fn build_get_features() -> [u8; 32] {
+    let mut buf = [0u8; 32];
+    let def = definition::current();
+    buf[0] = VIA_CUSTOM_GET_VALUE;
+    buf[1] = def.per_key_channel;
+    buf[2] = def.val_features;
+    buf
+}
+
 // ── Keymap builders ─────────────────────────────────────────────────────
 
 /// Convert key index (0-7) to matrix position (row, col).
-/// Row 0 = K1-K4 (cols 0-3), Row 1 = K5-K8 (cols 0-3).
+/// Stock: row 0 = K1-K4 (cols 0-3), row 1 = K5-K8 (cols 0-3).
 pub fn key_index_to_matrix(key_index: u8) -> (u8, u8) {
-    (key_index / 4, key_index % 4)
+    definition::current().matrix_position(key_index)
 }
 
 /// Build a 32-byte VIA top-level command to read a keycode.
@@ -224,6 +298,17 @@ pub fn build_macro_get_buffer_size() -> [u8; 32] {
     buf
 }
 
+/// Most macro buffer bytes one report carries.
+pub const MACRO_CHUNK: u8 = 28;
+
+pub fn build_macro_get_buffer(offset: u16, size: u8) -> [u8; 32] {
+    let mut buf = [0u8; 32];
+    buf[0] = VIA_MACRO_GET_BUFFER;
+    buf[1..3].copy_from_slice(&offset.to_be_bytes());
+    buf[3] = size.min(MACRO_CHUNK);
+    buf
+}
+
 pub fn build_macro_reset() -> [u8; 32] {
     let mut buf = [0u8; 32];
     buf[0] = VIA_MACRO_RESET;
@@ -241,7 +326,7 @@ pub fn build_get_layer_count() -> [u8; 32] {
 pub fn build_rgb_get_value(value_id: u8) -> [u8; 32] {
     let mut buf = [0u8; 32];
     buf[0] = VIA_CUSTOM_GET_VALUE;
-    buf[1] = RGB_MATRIX_CHANNEL;
+    buf[1] = definition::current().rgb_matrix_channel;
     buf[2] = value_id;
     buf
 }
@@ -249,7 +334,7 @@ pub fn build_rgb_get_value(value_id: u8) -> [u8; 32] {
 pub fn build_rgb_set_value_u8(value_id: u8, val: u8) -> [u8; 32] {
     let mut buf = [0u8; 32];
     buf[0] = CUSTOM_CHANNEL;
-    buf[1] = RGB_MATRIX_CHANNEL;
+    buf[1] = definition::current().rgb_matrix_channel;
     buf[2] = value_id;
     buf[3] = val;
     buf
@@ -258,7 +343,7 @@ pub fn build_rgb_set_value_u8(value_id: u8, val: u8) -> [u8; 32] {
 pub fn build_rgb_set_color(h: u8, s: u8) -> [u8; 32] {
     let mut buf = [0u8; 32];
     buf[0] = CUSTOM_CHANNEL;
-    buf[1] = RGB_MATRIX_CHANNEL;
+    buf[1] = definition::current().rgb_matrix_channel;
     buf[2] = RGB_VAL_COLOR;
     buf[3] = h;
     buf[4] = s;
@@ -268,7 +353,7 @@ pub fn build_rgb_set_color(h: u8, s: u8) -> [u8; 32] {
 pub fn build_rgb_save() -> [u8; 32] {
     let mut buf = [0u8; 32];
     buf[0] = VIA_CUSTOM_SAVE;
-    buf[1] = RGB_MATRIX_CHANNEL;
+    buf[1] = definition::current().rgb_matrix_channel;
     buf
 }
 
@@ -277,6 +362,6 @@ pub fn build_rgb_save() -> [u8; 32] {
 pub fn build_custom_save() -> [u8; 32] {
     let mut buf = [0u8; 32];
     buf[0] = VIA_CUSTOM_SAVE;
-    buf[1] = 0x00; // id_custom_channel
+    buf[1] = definition::current().per_key_channel; // id_custom_channel
     buf
 }
@@ -0,0 +1,37 @@
+// Quiet hours: a daily window in which key sounds are muted or capped.
+//
+// LEDs, keystrokes and slot changes are untouched; only the gain key sounds
+// play at changes. `override_quiet_hours` suspends the window for a while
+// (in memory only, so a restart brings quiet hours back).
+
+use chrono::Timelike;
+
+use crate::state::{AppState, QuietHours};
+
+/// Whether key sounds are muted or capped right now.
+pub fn active(st: &AppState) -> bool {
+    let q = &st.settings.quiet_hours;
+    if !q.enabled || st.quiet_override_until.is_some_and(|t| t > crate::now_ms()) {
+        return false;
+    }
+    let now = chrono::Local::now();
+    in_window(q, (now.hour() * 60 + now.minute()) as u16)
+}
+
+/// A key sound's `gain`, capped at `max_volume` inside quiet hours (a key
+/// already quieter than the cap keeps its own level).
+pub fn cap(st: &AppState, gain: f32) -> f32 {
+    if active(st) { gain.min(st.settings.quiet_hours.max_volume) } else { gain }
+}
+
+/// Whether `minute` (after local midnight) falls in the window. A window that
+/// starts where it ends covers the whole day.
+pub fn in_window(q: &QuietHours, minute: u16) -> bool {
+    if q.start_min == q.end_min {
+        true
+    } else if q.start_min < q.end_min {
+        (q.start_min..q.end_min).contains(&minute)
+    } else {
+        minute >= q.start_min || minute < q.end_min
+    }
+}
@@ -0,0 +1,83 @@
+// Background clients that keep one connection to a local app open while a
+// binding uses it (Teams in meeting.rs, OBS, Discord).
+//
+// The client's thread runs a session (connect, then follow the app until the
+// connection fails), records the error in the status and tries again after
+// `retry`, until the client is dropped. Commands from key presses go through
+// a channel the session drains between reads; those sent while disconnected
+// are dropped once a session connects, since a press from before the
+// connection is stale.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+
+/// What a client reports to the watcher.
+pub trait Status: Default + Send + 'static {
+    /// The session ended with `error`; reconnecting next.
+    fn failed(&mut self, error: String);
+}
+
+pub struct Client<S, C> {
+    pub status: Arc<Mutex<S>>,
+    commands: Sender<C>,
+    stop: Arc<AtomicBool>,
+}
+
+/// What a session gets from its client.
+pub struct Session<'a, S, C> {
+    pub status: &'a Mutex<S>,
+    commands: &'a Receiver<C>,
+    stop: &'a AtomicBool,
+}
+
+impl<S: Status, C: Send + 'static> Client<S, C> {
+    /// Run `session` on a thread of its own, again `retry` after each failure.
+    pub fn start(retry: Duration, mut session: impl FnMut(&Session<S, C>) -> Result<()> + Send + 'static) -> Self {
+        let status = Arc::new(Mutex::new(S::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (commands, queue) = mpsc::channel();
+        let (thread_status, thread_stop) = (status.clone(), stop.clone());
+        std::thread::spawn(move || {
+            let handle = Session { status: &thread_status, commands: &queue, stop: &thread_stop };
+            while !thread_stop.load(Ordering::Relaxed) {
+                if let Err(e) = session(&handle) {
+                    thread_status.lock().unwrap().failed(format!("{e:#}"));
+                }
+                std::thread::sleep(retry);
+            }
+        });
+        Self { status, commands, stop }
+    }
+
+    /// Queue a command for the session.
+    pub fn send(&self, command: C) {
+        let _ = self.commands.send(command);
+    }
+}
+
+impl<S, C> Drop for Client<S, C> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl<S, C> Session<'_, S, C> {
+    /// Call once connected: drops the commands sent before.
+    pub fn connected(&self) {
+        while self.commands.try_recv().is_ok() {}
+    }
+
+    /// Commands sent since the last call.
+    pub fn commands(&self) -> mpsc::TryIter<'_, C> {
+        self.commands.try_iter()
+    }
+
+    /// The client was dropped; the session should end.
+    pub fn stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,264 @@
+// Keystroke replay for the plugin shortcut path (macOS, Linux without evdev
+// access): the OS hotkey grab consumes the keystroke, so it is re-sent to the
+// focused application after the LED toggle. The hook backends let keystrokes
+// propagate naturally and never replay.
+//
+// Two mechanisms, chosen by AppSettings.replay_method:
+// - Native (default): what a physical keyboard produces, so games and apps
+//   that read scancodes see it too.
+//     Windows: one SendInput batch of virtual-key + scancode pairs (the
+//              key's own scancode for letters, digits, punctuation and the
+//              numpad, so the layout and NumLock apply as they do to the
+//              Deck-8).
+//     Linux:   key events through the uinput passthrough device (evdev codes).
+//     macOS:   enigo already posts CGEvents with kVK hardware keycodes, so
+//              Native and Enigo are the same.
+// - Enigo: enigo's replay (virtual keys / X keysyms through xdo). Also the
+//   fallback when the native path is unavailable (e.g. no /dev/uinput access).
+//
+// Loop protection: the plugin and portal shortcut paths can't tell our
+// keystrokes from real ones, so a replay (a key's own, or a meeting/media
+// toggle's) that matches a registered shortcut would fire that key again,
+// which may replay again, and so on. Every injected chord is remembered for
+// ECHO_WINDOW and `is_echo` lets those paths drop it when it comes back.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::{error, warn};
+
+use crate::keycodes;
+use crate::state::ReplayMethod;
+
+/// Tag carried by every keystroke we inject (Windows `dwExtraInfo`, macOS
+/// event source user data), so our own handlers can tell replays from real
+/// key presses.
+pub(crate) const REPLAY_MARKER: usize = 0xDEC8_0008;
+
+/// How long an injected chord counts as ours when a shortcut fires for it.
+/// Short, so a quick real double press still goes through.
+const ECHO_WINDOW: Duration = Duration::from_millis(100);
+
+/// Chords injected within ECHO_WINDOW, each excusing one shortcut press.
+static INJECTED: Mutex<Vec<(Chord, Instant)>> = Mutex::new(Vec::new());
+
+/// Base key + modifiers, left and right folded together.
+type Chord = (u8, [bool; 4]);
+
+fn chord(keycode: u16) -> Chord {
+    let mods = (keycode >> 8) as u8;
+    ((keycode & 0xFF) as u8, [0x11, 0x22, 0x44, 0x88].map(|bit| mods & bit != 0))
+}
+
+/// Remember a chord we're about to inject.
+pub fn note_injected(keycode: u16, now: Instant) {
+    let mut injected = INJECTED.lock().unwrap();
+    injected.retain(|(_, at)| now.duration_since(*at) < ECHO_WINDOW);
+    injected.push((chord(keycode), now));
+}
+
+/// Whether a shortcut press for `keycode` is one of our own replays coming
+/// back; if so it's used up.
+pub fn is_echo(keycode: u16, now: Instant) -> bool {
+    let mut injected = INJECTED.lock().unwrap();
+    injected.retain(|(_, at)| now.saturating_duration_since(*at) < ECHO_WINDOW);
+    let Some(i) = injected.iter().position(|(c, _)| *c == chord(keycode)) else { return false };
+    injected.remove(i);
+    warn!("[replay] 0x{:04X} came back as a shortcut, ignored (replay loop)", keycode);
+    true
+}
+
+/// Replay a QMK keycode (modifiers + key) to the focused application.
+pub fn replay_keystroke(keycode: u16, method: ReplayMethod) {
+    note_injected(keycode, Instant::now());
+    if method == ReplayMethod::Native && replay_native(keycode) {
+        return;
+    }
+    replay_enigo(keycode);
+}
+
+#[cfg(target_os = "windows")]
+#[allow(clippy::upper_case_acronyms)] // Win32 struct names
+fn replay_native(keycode: u16) -> bool {
+    #[repr(C)]
+    struct KEYBDINPUT {
+        vk: u16,
+        scan: u16,
+        flags: u32,
+        time: u32,
+        extra_info: usize,
+    }
+
+    /// INPUT with the keyboard arm of the union; the padding covers
+    /// MOUSEINPUT, the largest member.
+    #[repr(C)]
+    struct INPUT {
+        type_: u32,
+        ki: KEYBDINPUT,
+        _pad: [u8; 8],
+    }
+
+    extern "system" {
+        fn SendInput(count: u32, inputs: *const INPUT, size: i32) -> u32;
+        fn MapVirtualKeyW(code: u32, map_type: u32) -> u32;
+    }
+
+    const INPUT_KEYBOARD: u32 = 1;
+    const KEYEVENTF_EXTENDEDKEY: u32 = 0x0001;
+    const KEYEVENTF_KEYUP: u32 = 0x0002;
+    const KEYEVENTF_SCANCODE: u32 = 0x0008;
+    const MAPVK_VK_TO_VSC_EX: u32 = 4;
+    const MODIFIERS: [(u8, u16); 4] = [
+        (0x11, 0xA2), // VK_LCONTROL
+        (0x22, 0xA0), // VK_LSHIFT
+        (0x44, 0xA4), // VK_LMENU
+        (0x88, 0x5B), // VK_LWIN
+    ];
+
+    let mods = (keycode >> 8) as u8;
+    let basic = (keycode & 0xFF) as u8;
+    let Some(def) = keycodes::lookup(basic) else { return false };
+    let vk = crate::layout::vk(def);
+    let scan_of = |vk: u16| unsafe { MapVirtualKeyW(vk as u32, MAPVK_VK_TO_VSC_EX) };
+    // Letters, digits and punctuation: the physical key's own scancode, which
+    // the layout turns into whatever it types. Numpad keys too, so Num Enter
+    // isn't Enter and Num = (no VK) is sent at all. Media keys are consumed by
+    // the shell by virtual key; their scancodes don't map back reliably.
+    let scan = match def.scancode().or(def.numpad_scancode()) {
+        Some(scan) => scan as u32,
+        None if def.category != keycodes::KeyCategory::Media => vk.map_or(0, scan_of),
+        None => 0,
+    };
+    if vk.is_none() && scan == 0 {
+        return false;
+    }
+    let vk = vk.unwrap_or(0);
+
+    let input = |vk: u16, scan: u32, up: bool| {
+        let mut flags = if up { KEYEVENTF_KEYUP } else { 0 };
+        if scan != 0 {
+            flags |= KEYEVENTF_SCANCODE;
+            if scan & 0xFF00 == 0xE000 {
+                flags |= KEYEVENTF_EXTENDEDKEY;
+            }
+        }
+        INPUT {
+            type_: INPUT_KEYBOARD,
+            ki: KEYBDINPUT { vk, scan: (scan & 0xFF) as u16, flags, time: 0, extra_info: REPLAY_MARKER },
+            _pad: [0; 8],
+        }
+    };
+
+    let held: Vec<u16> = MODIFIERS.iter().filter(|(bit, _)| mods & bit != 0).map(|&(_, vk)| vk).collect();
+    let mut inputs: Vec<INPUT> = held.iter().map(|&m| input(m, scan_of(m), false)).collect();
+    inputs.push(input(vk, scan, false));
+    inputs.push(input(vk, scan, true));
+    inputs.extend(held.iter().rev().map(|&m| input(m, scan_of(m), true)));
+
+    // One batch, so nothing the user types can land between our events
+    let sent = unsafe { SendInput(inputs.len() as u32, inputs.as_ptr(), std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        warn!("[replay] SendInput sent {} of {} events", sent, inputs.len());
+    }
+    sent != 0
+}
+
+#[cfg(target_os = "linux")]
+fn replay_native(keycode: u16) -> bool {
+    const MODIFIERS: [(u8, u16); 4] = [
+        (0x11, 29),  // KEY_LEFTCTRL
+        (0x22, 42),  // KEY_LEFTSHIFT
+        (0x44, 56),  // KEY_LEFTALT
+        (0x88, 125), // KEY_LEFTMETA
+    ];
+
+    let mods = (keycode >> 8) as u8;
+    let basic = (keycode & 0xFF) as u8;
+    let Some(code) = keycodes::lookup(basic).and_then(|def| def.evdev()) else { return false };
+
+    let held: Vec<u16> = MODIFIERS.iter().filter(|(bit, _)| mods & bit != 0).map(|&(_, c)| c).collect();
+    let mut keys: Vec<(u16, bool)> = held.iter().map(|&m| (m, true)).collect();
+    keys.push((code, true));
+    keys.push((code, false));
+    keys.extend(held.iter().rev().map(|&m| (m, false)));
+    crate::keyboard_hook::emit_keys(&keys)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn replay_native(_keycode: u16) -> bool {
+    false
+}
+
+/// Replay through enigo: virtual keys on Windows, kVK codes on macOS, X
+/// keysyms (xdo) on Linux; the first and last for the active layout.
+fn replay_enigo(keycode: u16) {
+    use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+    let mods = (keycode >> 8) as u8;
+    let basic = (keycode & 0xFF) as u8;
+
+    let settings = Settings {
+        windows_dw_extra_info: Some(REPLAY_MARKER),
+        event_source_user_data: Some(REPLAY_MARKER as i64),
+        ..Settings::default()
+    };
+    let mut enigo = match Enigo::new(&settings) {
+        Ok(e) => e,
+        Err(e) => {
+            error!("[replay] Failed to create Enigo: {}", e);
+            return;
+        }
+    };
+
+    // Press modifiers
+    if mods & 0x11 != 0 { let _ = enigo.key(Key::Control, Direction::Press); }
+    if mods & 0x22 != 0 { let _ = enigo.key(Key::Shift, Direction::Press); }
+    if mods & 0x44 != 0 { let _ = enigo.key(Key::Alt, Direction::Press); }
+    if mods & 0x88 != 0 { let _ = enigo.key(Key::Meta, Direction::Press); }
+
+    // Press+release the base key. Media keys go through enigo's own variants
+    // (macOS sends them as system-defined events, not virtual keycodes, so
+    // those without a variant there are dropped); everything else uses the
+    // platform's native code from the keycode table.
+    let key = match basic {
+        0xA8 => Some(Key::VolumeMute),
+        0xA9 => Some(Key::VolumeUp),
+        0xAA => Some(Key::VolumeDown),
+        0xAB => Some(Key::MediaNextTrack),
+        0xAC => Some(Key::MediaPrevTrack),
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        0xAD => Some(Key::MediaStop),
+        0xAE => Some(Key::MediaPlayPause),
+        #[cfg(target_os = "macos")]
+        0xB0 => Some(Key::Eject),
+        #[cfg(target_os = "macos")]
+        0xBB => Some(Key::MediaFast),
+        #[cfg(target_os = "macos")]
+        0xBC => Some(Key::MediaRewind),
+        #[cfg(target_os = "macos")]
+        0xBD => Some(Key::BrightnessUp),
+        #[cfg(target_os = "macos")]
+        0xBE => Some(Key::BrightnessDown),
+        _ => keycodes::lookup(basic).and_then(|def| {
+            #[cfg(target_os = "windows")]
+            let native = crate::layout::vk(def).map(u32::from);
+            #[cfg(target_os = "macos")]
+            let native = def.mac().map(u32::from);
+            #[cfg(target_os = "linux")]
+            let native = crate::layout::keysym(def);
+            #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+            let native = def.keysym();
+            native.map(Key::Other)
+        }),
+    };
+    match key {
+        Some(k) => { let _ = enigo.key(k, Direction::Click); }
+        None => warn!("[replay] No key to replay for 0x{:04X} on this platform", keycode),
+    }
+
+    // Release modifiers (reverse order)
+    if mods & 0x88 != 0 { let _ = enigo.key(Key::Meta, Direction::Release); }
+    if mods & 0x44 != 0 { let _ = enigo.key(Key::Alt, Direction::Release); }
+    if mods & 0x22 != 0 { let _ = enigo.key(Key::Shift, Direction::Release); }
+    if mods & 0x11 != 0 { let _ = enigo.key(Key::Control, Direction::Release); }
+}
@@ -0,0 +1,125 @@
+// Destructive device commands (`eeprom_reset`, `dynamic_keymap_reset`,
+// `macro_reset`) in two steps, so nothing is wiped by a stray call.
+//
+// `preview_reset(kind)` reads what the reset would wipe (keymaps on every
+// layer, the macros, RGB settings, keys with a color override) and hands out
+// a one-time confirm token for that kind, good for `CONFIRM_FOR_MS`. The
+// reset command refuses to run without it. Right before wiping, the device
+// is read again and saved to backups/reset-<kind>-<time>.json in the config
+// dir; the command returns that path.
+
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::hid::Deck8Device;
+use crate::protocol::RgbMatrixState;
+
+const CONFIRM_FOR_MS: u64 = 2 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ResetKind {
+    /// Everything the firmware keeps: keymaps, macros, RGB, overrides.
+    Eeprom,
+    Keymaps,
+    Macros,
+}
+
+impl ResetKind {
+    fn slug(self) -> &'static str {
+        match self {
+            Self::Eeprom => "eeprom",
+            Self::Keymaps => "keymaps",
+            Self::Macros => "macros",
+        }
+    }
+}
+
+/// What a reset wipes, as read from the device.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceSnapshot {
+    /// Keycodes per layer in LED order, layer 0 first; empty when kept.
+    pub layers: Vec<[u16; 8]>,
+    /// The raw macro buffer, NUL-separated; empty when kept.
+    pub macros: Vec<u8>,
+    pub rgb: Option<RgbMatrixState>,
+    /// LED indices of keys showing their own color.
+    pub overrides: Vec<usize>,
+}
+
+impl DeviceSnapshot {
+    /// Read what `kind` wipes. `overrides` come from the app's key state.
+    pub fn read(dev: &Deck8Device, kind: ResetKind, overrides: Vec<usize>) -> Result<Self> {
+        let wipes_keymaps = kind != ResetKind::Macros;
+        let wipes_macros = kind != ResetKind::Keymaps;
+        let eeprom = kind == ResetKind::Eeprom;
+        Ok(Self {
+            layers: if wipes_keymaps { dev.read_all_layers()? } else { Vec::new() },
+            macros: if wipes_macros { dev.read_macro_buffer()? } else { Vec::new() },
+            rgb: if eeprom { dev.rgb_get_state().ok() } else { None },
+            overrides: if eeprom { overrides } else { Vec::new() },
+        })
+    }
+
+    /// Macros with something in them.
+    pub fn macro_count(&self) -> usize {
+        let used = self.macros.iter().rposition(|&b| b != 0).map_or(0, |last| last + 1);
+        self.macros[..used].split(|&b| b == 0).filter(|m| !m.is_empty()).count()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResetPreview {
+    pub kind: ResetKind,
+    /// Pass to the reset command to run it.
+    pub token: String,
+    pub expires_at_ms: u64,
+    pub snapshot: DeviceSnapshot,
+    /// `macro_count` of the snapshot.
+    pub macro_count: usize,
+}
+
+struct Pending {
+    kind: ResetKind,
+    token: String,
+    expires_at_ms: u64,
+}
+
+static PENDING: Mutex<Option<Pending>> = Mutex::new(None);
+
+/// Hand out a token for `kind`, replacing any earlier one.
+pub fn preview(kind: ResetKind, snapshot: DeviceSnapshot, now_ms: u64) -> ResetPreview {
+    let token = format!("{:016x}", RandomState::new().build_hasher().finish());
+    let expires_at_ms = now_ms + CONFIRM_FOR_MS;
+    *PENDING.lock().unwrap() = Some(Pending { kind, token: token.clone(), expires_at_ms });
+    ResetPreview { kind, token, expires_at_ms, macro_count: snapshot.macro_count(), snapshot }
+}
+
+/// Use up the token for `kind`.
+pub fn confirm(kind: ResetKind, token: &str, now_ms: u64) -> Result<(), String> {
+    let mut pending = PENDING.lock().unwrap();
+    match pending.as_ref() {
+        Some(p) if p.kind == kind && p.token == token && now_ms < p.expires_at_ms => {
+            *pending = None;
+            Ok(())
+        }
+        Some(p) if p.kind == kind && p.token == token => Err(crate::i18n::t("error-reset-expired")),
+        _ => Err(crate::i18n::t("error-reset-unconfirmed")),
+    }
+}
+
+/// Save `snapshot` before `kind` wipes it; returns the file.
+pub fn backup(kind: ResetKind, snapshot: &DeviceSnapshot) -> Result<PathBuf> {
+    let dir = crate::profile::config_dir()?.join("backups");
+    fs::create_dir_all(&dir).context("Failed to create the backups folder")?;
+    let path = dir.join(format!("reset-{}-{}.json", kind.slug(), Local::now().format("%Y%m%d-%H%M%S")));
+    let json = serde_json::to_string_pretty(snapshot).context("Failed to serialize the backup")?;
+    fs::write(&path, json).context("Failed to write the backup")?;
+    Ok(path)
+}
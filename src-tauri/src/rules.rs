@@ -0,0 +1,285 @@
+// LED rules: "between 9 and 17 on weekdays put key 1 on Blue", "when on
+// battery dim all LEDs to 30%".
+//
+// Rules are edge-triggered. A background thread evaluates them every 30 s
+// (and `save_rule`/`delete_rule` right away); a rule that starts holding
+// applies its action once, and when it stops holding the action is undone:
+// a key goes back to the slot it was on, unless it was moved since. Keys
+// bound to a condition are left alone, like with modes. Dimming scales every
+// per-key color sent to the device (`brightness::set_dim`), the lowest
+// active rule winning. Which rules are active isn't persisted; they're
+// evaluated again on startup.
+
+use std::time::Duration;
+
+use chrono::{Datelike, Timelike};
+use log::info;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::state::{AppState, SharedState, StateSnapshot};
+
+const POLL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Rule {
+    /// Empty when created from the UI; `save_rule` assigns one.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    pub when: RuleWhen,
+    pub then: RuleAction,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum RuleWhen {
+    /// Bit 0 is Monday. Minutes after local midnight; `start > end` wraps
+    /// past midnight (and counts as the day it started).
+    Schedule { days: u8, start_min: u16, end_min: u16 },
+    /// Running on battery (never true on desktops).
+    OnBattery,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum RuleAction {
+    /// Put a key on one of its slots.
+    KeySlot { key: usize, slot: String },
+    /// Scale every key's brightness to `percent`.
+    Dim { percent: u8 },
+}
+
+/// A rule that's holding, and what to restore when it stops.
+#[derive(Debug, Clone)]
+pub struct Active {
+    action: RuleAction,
+    /// Slot the key was on before a `KeySlot` rule moved it.
+    previous_slot: Option<usize>,
+}
+
+/// What rules are evaluated against.
+pub struct Now {
+    /// 0 is Monday.
+    pub weekday: u8,
+    pub minute: u16,
+    pub on_battery: bool,
+}
+
+/// Check a rule from the UI before it's saved.
+pub fn validate(st: &AppState, rule: &Rule) -> Result<(), String> {
+    if rule.name.is_empty() {
+        return Err(crate::i18n::t("error-rule-name-empty"));
+    }
+    if let RuleWhen::Schedule { days, start_min, end_min } = rule.when {
+        if days & 0x7f == 0 {
+            return Err(crate::i18n::t("error-rule-no-days"));
+        }
+        if start_min >= 24 * 60 || end_min >= 24 * 60 || start_min == end_min {
+            return Err(crate::i18n::t("error-rule-schedule"));
+        }
+    }
+    match rule.then {
+        RuleAction::KeySlot { key, ref slot } => {
+            let key = st.keys.get(key).ok_or_else(|| crate::i18n::t("error-key-index"))?;
+            if !key.slots.iter().any(|s| &s.name == slot) {
+                return Err(crate::i18n::t_with("error-no-slot", &[("slot", slot)]));
+            }
+        }
+        RuleAction::Dim { percent } if !(1..=100).contains(&percent) => return Err(crate::i18n::t("error-rule-dim")),
+        RuleAction::Dim { .. } => {}
+    }
+    Ok(())
+}
+
+pub fn holds(when: &RuleWhen, now: &Now) -> bool {
+    match *when {
+        RuleWhen::Schedule { days, start_min, end_min } => {
+            let on = |weekday: u8| days & (1 << weekday) != 0;
+            if start_min <= end_min {
+                on(now.weekday) && (start_min..end_min).contains(&now.minute)
+            } else if now.minute >= start_min {
+                on(now.weekday)
+            } else {
+                // Past midnight: the window started yesterday
+                now.minute < end_min && on((now.weekday + 6) % 7)
+            }
+        }
+        RuleWhen::OnBattery => now.on_battery,
+    }
+}
+
+/// Start the rules that began holding and undo the ones that stopped (or
+/// were disabled or deleted). True when anything changed.
+pub fn evaluate<R: Runtime>(app: &AppHandle<R>, st: &mut AppState, now: &Now) -> bool {
+    let holding: Vec<(String, RuleAction)> = st.rules.iter()
+        .filter(|r| r.enabled && holds(&r.when, now))
+        .map(|r| (r.id.clone(), r.then.clone()))
+        .collect();
+    let ended: Vec<String> = st.active_rules.keys()
+        .filter(|id| !holding.iter().any(|(h, _)| h == *id))
+        .cloned()
+        .collect();
+    let mut changed = false;
+    for id in ended {
+        end(app, st, &id);
+        changed = true;
+    }
+    for (id, action) in holding {
+        if st.active_rules.contains_key(&id) {
+            continue;
+        }
+        let previous_slot = match action {
+            RuleAction::KeySlot { key, ref slot } => move_key(app, st, key, slot),
+            RuleAction::Dim { .. } => None,
+        };
+        info!("[rules] {id} started");
+        st.active_rules.insert(id, Active { action, previous_slot });
+        changed = true;
+    }
+    changed
+}
+
+/// Undo an active rule's action; nothing if it isn't active.
+pub fn end<R: Runtime>(app: &AppHandle<R>, st: &mut AppState, id: &str) {
+    let Some(active) = st.active_rules.remove(id) else { return };
+    info!("[rules] {id} ended");
+    let (RuleAction::KeySlot { key, slot }, Some(previous)) = (active.action, active.previous_slot) else { return };
+    // Moved by hand (or by another rule) since: leave it
+    let Some(k) = st.keys.get(key) else { return };
+    if k.active().name != slot || previous >= k.slots.len() || k.binding.is_some() {
+        return;
+    }
+    let from = k.active().color;
+    st.keys[key].active_slot = previous;
+    crate::fade::transition(app, st, key, from);
+}
+
+/// Move an unbound key to `slot`; returns the slot it was on.
+fn move_key<R: Runtime>(app: &AppHandle<R>, st: &mut AppState, key_index: usize, slot: &str) -> Option<usize> {
+    let key = st.keys.get_mut(key_index)?;
+    if key.binding.is_some() {
+        return None;
+    }
+    let index = key.slots.iter().position(|s| s.name == slot)?;
+    if key.active_slot == index {
+        return None;
+    }
+    let previous = key.active_slot;
+    let from = key.active().color;
+    key.active_slot = index;
+    crate::fade::transition(app, st, key_index, from);
+    Some(previous)
+}
+
+/// Brightness the active `Dim` rules leave the keys at (100 with none).
+pub fn dim_percent(st: &AppState) -> u8 {
+    st.active_rules.values()
+        .filter_map(|a| match a.action {
+            RuleAction::Dim { percent } => Some(percent),
+            RuleAction::KeySlot { .. } => None,
+        })
+        .min()
+        .unwrap_or(100)
+}
+
+/// Evaluate the rules now, redraw if the dim level changed, and tell the UI
+/// and tray when anything did.
+pub fn refresh(app: &AppHandle) -> StateSnapshot {
+    let state = app.state::<SharedState>();
+    let needs_battery = state.lock().unwrap().rules.iter()
+        .any(|r| r.enabled && r.when == RuleWhen::OnBattery);
+    // Outside the lock: on macOS this runs pmset
+    let on_battery = needs_battery && power::on_battery();
+    let clock = chrono::Local::now();
+    let now = Now {
+        weekday: clock.weekday().num_days_from_monday() as u8,
+        minute: (clock.hour() * 60 + clock.minute()) as u16,
+        on_battery,
+    };
+    let (snapshot, changed) = {
+        let mut st = state.lock().unwrap();
+        let changed = evaluate(app, &mut st, &now);
+        if crate::brightness::set_dim(dim_percent(&st)) {
+            if let Some(ref dev) = st.device {
+                crate::apply_all_to_device(dev, &st.keys);
+            }
+        }
+        if changed {
+            crate::persist_state(&st);
+        }
+        (st.snapshot(), changed)
+    };
+    if changed {
+        crate::refresh_tray_menu(app);
+        let _ = app.emit("state-updated", &snapshot);
+    }
+    snapshot
+}
+
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        refresh(&app);
+        std::thread::sleep(POLL);
+    });
+}
+
+#[cfg(target_os = "windows")]
+mod power {
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    pub fn on_battery() -> bool {
+        let mut status = SystemPowerStatus::default();
+        // SAFETY: `status` is a valid SYSTEM_POWER_STATUS for the call to fill
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            log::warn!("[rules] GetSystemPowerStatus failed");
+            return false;
+        }
+        // 0 = offline, 1 = online, 255 = unknown
+        status.ac_line_status == 0
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod power {
+    pub fn on_battery() -> bool {
+        match std::process::Command::new("pmset").args(["-g", "batt"]).output() {
+            Ok(out) => String::from_utf8_lossy(&out.stdout).contains("'Battery Power'"),
+            Err(e) => {
+                log::warn!("[rules] pmset failed: {e}");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod power {
+    pub fn on_battery() -> bool {
+        let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else { return false };
+        supplies.flatten().any(|supply| {
+            let read = |name: &str| std::fs::read_to_string(supply.path().join(name)).unwrap_or_default();
+            read("type").trim() == "Battery" && read("status").trim() == "Discharging"
+        })
+    }
+}
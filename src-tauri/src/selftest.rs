@@ -0,0 +1,156 @@
+// Connect-time self-test: checks that the firmware understands what the app
+// is about to send, so a mismatch shows up as a report instead of commands
+// that are silently ignored.
+//
+// Runs from `sync_device` once the device info is read. The keycode check
+// writes a scratch position on the last layer (not the 8 keys on layer 0) and
+// the RGB check nudges the effect speed by one; both put the old value back,
+// even when reading it back failed. Those two write EEPROM, so once they pass
+// they only run again for another firmware version (`echo_checked_firmware`).
+
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::hid::Deck8Device;
+use crate::protocol::{DeviceInfo, VIA_PROTOCOL_VERSION};
+
+/// Transparent keycode, used as the scratch value (or KC_NO if it's there already).
+const KC_TRNS: u16 = 0x0001;
+const KEYCODE_WRITE: &str = "Keycode write";
+const RGB_SETTINGS: &str = "RGB settings";
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but something is assumed or may not.
+    Warn,
+    Fail,
+    /// Not applicable to this firmware.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// Result of the connect-time self-test, emitted as `sync-report`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncReport {
+    pub checks: Vec<Check>,
+}
+
+impl SyncReport {
+    /// Whether any check failed.
+    pub fn failed(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+
+    /// Whether the write checks passed (or didn't apply), so they needn't run
+    /// again on this firmware.
+    pub fn writes_passed(&self) -> bool {
+        self.checks
+            .iter()
+            .filter(|c| c.name == KEYCODE_WRITE || c.name == RGB_SETTINGS)
+            .all(|c| matches!(c.status, CheckStatus::Pass | CheckStatus::Skipped))
+    }
+}
+
+fn check(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Check {
+    Check { name, status, detail: detail.into() }
+}
+
+/// Run every check. `info` is `None` when reading the device info failed.
+/// The write checks are left out when they passed on this firmware version
+/// before (`echo_checked`).
+pub fn run(dev: &Deck8Device, info: Option<&DeviceInfo>, echo_checked: Option<u32>) -> SyncReport {
+    let mut checks = vec![protocol_version(info), per_key_channel(info)];
+    match info {
+        Some(info) if echo_checked == Some(info.firmware_version) => {
+            for name in [KEYCODE_WRITE, RGB_SETTINGS] {
+                checks.push(check(name, CheckStatus::Pass, "Passed on this firmware before"));
+            }
+        }
+        _ => checks.extend([keycode_echo(dev, info), rgb_echo(dev)]),
+    }
+    for c in &checks {
+        match c.status {
+            CheckStatus::Fail | CheckStatus::Warn => warn!("[selftest] {}: {:?} — {}", c.name, c.status, c.detail),
+            _ => info!("[selftest] {}: {:?} — {}", c.name, c.status, c.detail),
+        }
+    }
+    SyncReport { checks }
+}
+
+fn protocol_version(info: Option<&DeviceInfo>) -> Check {
+    const NAME: &str = "Protocol version";
+    let Some(info) = info else {
+        return check(NAME, CheckStatus::Fail, "Couldn't read the device info");
+    };
+    let v = info.protocol_version;
+    if v < VIA_PROTOCOL_VERSION {
+        check(NAME, CheckStatus::Fail, format!(
+            "VIA protocol {v} predates custom channels ({VIA_PROTOCOL_VERSION}); key colors and RGB settings will be ignored"))
+    } else if v > VIA_PROTOCOL_VERSION {
+        check(NAME, CheckStatus::Warn, format!("VIA protocol {v} is newer than this app knows ({VIA_PROTOCOL_VERSION})"))
+    } else {
+        check(NAME, CheckStatus::Pass, format!("VIA protocol {v}"))
+    }
+}
+
+fn per_key_channel(info: Option<&DeviceInfo>) -> Check {
+    const NAME: &str = "Per-key colors";
+    match info.map(|i| i.capabilities) {
+        None => check(NAME, CheckStatus::Skipped, "No device info"),
+        Some(c) if !c.per_key_override => {
+            check(NAME, CheckStatus::Warn, "The firmware has no per-key channel; key colors can't be set")
+        }
+        Some(c) if !c.reported => {
+            check(NAME, CheckStatus::Warn, "Assumed: the firmware predates the features handshake")
+        }
+        Some(_) => check(NAME, CheckStatus::Pass, "Reported by the firmware"),
+    }
+}
+
+fn keycode_echo(dev: &Deck8Device, info: Option<&DeviceInfo>) -> Check {
+    const NAME: &str = KEYCODE_WRITE;
+    let layer = match info.map(|i| i.layer_count) {
+        Some(n) if n > 1 => n - 1,
+        _ => return check(NAME, CheckStatus::Skipped, "No spare layer to test on"),
+    };
+    let original = match dev.get_keycode(layer, 0, 0) {
+        Ok(original) => original,
+        Err(e) => return check(NAME, CheckStatus::Fail, format!("{e:#}")),
+    };
+    let scratch = if original == KC_TRNS { 0 } else { KC_TRNS };
+    let echoed = dev.set_keycode(layer, 0, 0, scratch).and_then(|_| dev.get_keycode(layer, 0, 0));
+    let restored = dev.set_keycode(layer, 0, 0, original);
+    match (echoed, restored) {
+        (Err(e), _) | (Ok(_), Err(e)) => check(NAME, CheckStatus::Fail, format!("{e:#}")),
+        (Ok(echoed), Ok(())) if echoed == scratch => check(NAME, CheckStatus::Pass, format!("Layer {layer} read back")),
+        (Ok(echoed), Ok(())) => check(NAME, CheckStatus::Fail, format!(
+            "Wrote 0x{scratch:04X} to layer {layer}, read back 0x{echoed:04X}; key assignments won't stick")),
+    }
+}
+
+fn rgb_echo(dev: &Deck8Device) -> Check {
+    const NAME: &str = RGB_SETTINGS;
+    if !dev.capabilities().rgb_matrix {
+        return check(NAME, CheckStatus::Skipped, "The firmware has no RGB Matrix channel");
+    }
+    let original = match dev.rgb_get_speed() {
+        Ok(original) => original,
+        Err(e) => return check(NAME, CheckStatus::Fail, format!("{e:#}")),
+    };
+    let scratch = original ^ 1;
+    let echoed = dev.rgb_set_speed(scratch).and_then(|_| dev.rgb_get_speed());
+    let restored = dev.rgb_set_speed(original);
+    match (echoed, restored) {
+        (Err(e), _) | (Ok(_), Err(e)) => check(NAME, CheckStatus::Fail, format!("{e:#}")),
+        (Ok(echoed), Ok(())) if echoed == scratch => check(NAME, CheckStatus::Pass, "Effect speed read back"),
+        (Ok(echoed), Ok(())) => check(NAME, CheckStatus::Fail, format!(
+            "Set effect speed {scratch}, read back {echoed}; RGB changes won't apply")),
+    }
+}
@@ -0,0 +1,137 @@
+// Sound previews (the trim editor, library previews without the pipeline) on
+// one long-lived thread, so scrubbing the trim slider doesn't stack them.
+//
+// `play` returns at once and the thread decodes and plays. A newer request
+// replaces the preview playing, and requests queued behind it are skipped:
+// only the latest is decoded, and one that arrives while decoding wins over
+// it. `stop` (`stop_preview`) cuts it. With the pipeline running a preview
+// is decoded in the pipeline's format without holding its lock (key sounds
+// don't wait behind a decode), then handed to `SoundOutput::play_trimmed`,
+// which replaces its own previous one; otherwise the thread plays it on a
+// sink of its own on the default output, opened on first use and kept on the
+// thread (cpal streams are !Send on Windows).
+
+use std::path::PathBuf;
+use std::sync::{mpsc, OnceLock};
+
+use log::{error, info, warn};
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, OutputStreamHandle, Sink};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::audio::{self, Trim};
+use crate::state::{ManagedAudioPipeline, SoundRoute};
+
+pub struct Request {
+    pub path: PathBuf,
+    pub trim: Trim,
+    /// Where it plays while the pipeline runs.
+    pub route: SoundRoute,
+}
+
+enum Command {
+    Play(Request),
+    Stop,
+}
+
+static SENDER: OnceLock<mpsc::Sender<Command>> = OnceLock::new();
+
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    let (tx, rx) = mpsc::channel();
+    if SENDER.set(tx).is_err() {
+        return;
+    }
+    std::thread::spawn(move || run(&app, rx));
+}
+
+/// Play `request`, replacing the preview playing.
+pub fn play(request: Request) {
+    send(Command::Play(request));
+}
+
+pub fn stop() {
+    send(Command::Stop);
+}
+
+fn send(command: Command) {
+    if SENDER.get().is_none_or(|tx| tx.send(command).is_err()) {
+        warn!("[preview] Preview thread isn't running");
+    }
+}
+
+/// The latest of `command` and whatever is queued behind it.
+fn latest(mut command: Command, rx: &mpsc::Receiver<Command>) -> Command {
+    while let Ok(newer) = rx.try_recv() {
+        command = newer;
+    }
+    command
+}
+
+fn run<R: Runtime>(app: &AppHandle<R>, rx: mpsc::Receiver<Command>) {
+    let mut output: Option<(OutputStream, OutputStreamHandle)> = None;
+    let mut sink: Option<Sink> = None;
+    let mut next = None;
+    loop {
+        let command = match next.take() {
+            Some(command) => command,
+            None => match rx.recv() {
+                Ok(command) => command,
+                Err(_) => return,
+            },
+        };
+        let command = latest(command, &rx);
+        if let Some(sink) = sink.take() {
+            sink.stop();
+        }
+        let pipeline = app.state::<ManagedAudioPipeline>();
+        let request = match command {
+            Command::Stop => {
+                if let Some(ref pipeline) = *pipeline.0.lock().unwrap() {
+                    pipeline.stop_preview();
+                }
+                continue;
+            }
+            Command::Play(request) => request,
+        };
+        let format = pipeline.0.lock().unwrap().as_ref().map(|p| p.format());
+        let decoded = match format {
+            Some((channels, sample_rate)) => audio::decode_trimmed_to(&request.path, &request.trim, channels, sample_rate)
+                .map(|samples| (channels, sample_rate, samples)),
+            None => audio::decode_trimmed(&request.path.to_string_lossy(), &request.trim),
+        };
+        // Asked for something else while decoding
+        if let Ok(newer) = rx.try_recv() {
+            next = Some(newer);
+            continue;
+        }
+        let (channels, sample_rate, samples) = match decoded {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                error!("[preview] {}: {e:#}", request.path.display());
+                continue;
+            }
+        };
+        if format.is_some() {
+            match *pipeline.0.lock().unwrap() {
+                Some(ref pipeline) if pipeline.format() == (channels, sample_rate) => {
+                    pipeline.play_trimmed(&request.path, &request.trim, samples, request.route);
+                }
+                // Stopped or restarted in another format while decoding
+                _ => next = Some(Command::Play(request)),
+            }
+            continue;
+        }
+        if output.is_none() {
+            output = audio::open_default_output().map_err(|e| error!("[preview] Failed to open default output: {e}")).ok();
+        }
+        let Some((_, ref handle)) = output else { continue };
+        match Sink::try_new(handle) {
+            Ok(new_sink) => {
+                new_sink.append(SamplesBuffer::new(channels, sample_rate, samples));
+                sink = Some(new_sink);
+                info!("[preview] Playing {}", request.path.display());
+            }
+            Err(e) => error!("[preview] Failed to create preview sink: {e}"),
+        }
+    }
+}
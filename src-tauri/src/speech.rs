@@ -0,0 +1,76 @@
+// Spoken announcements while the window is in the tray, where the frontend's
+// live region can't reach a screen reader: the OS speech synthesizer reads
+// them instead (SAPI through PowerShell on Windows, `say` on macOS,
+// speech-dispatcher's `spd-say` on Linux).
+//
+// One thread speaks at a time; announcements queued behind the one being
+// spoken are skipped but the latest, so a burst of presses reads out where
+// the keys ended up rather than every step.
+
+use std::process::Command;
+use std::sync::{mpsc, OnceLock};
+
+use log::warn;
+
+static SENDER: OnceLock<mpsc::Sender<String>> = OnceLock::new();
+
+/// Read `text` aloud. Doesn't block.
+pub fn say(text: String) {
+    let tx = SENDER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            let mut last_error = None;
+            while let Ok(mut text) = rx.recv() {
+                while let Ok(newer) = rx.try_recv() {
+                    text = newer;
+                }
+                if let Err(e) = speak(&text) {
+                    let message = e.to_string();
+                    if last_error.as_ref() != Some(&message) {
+                        warn!("[speech] {}", message);
+                        last_error = Some(message);
+                    }
+                }
+            }
+        });
+        tx
+    });
+    let _ = tx.send(text);
+}
+
+fn speak(text: &str) -> std::io::Result<()> {
+    command(text).status().map(drop)
+}
+
+#[cfg(target_os = "windows")]
+fn command(text: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let mut command = Command::new("powershell");
+    command
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak($env:DECK8_SPEAK)",
+        ])
+        .env("DECK8_SPEAK", text)
+        .creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn command(text: &str) -> Command {
+    let mut command = Command::new("say");
+    command.arg("--").arg(text);
+    command
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn command(text: &str) -> Command {
+    let mut command = Command::new("spd-say");
+    // Wait, so the next announcement doesn't talk over this one
+    command.args(["--wait", "--"]).arg(text);
+    command
+}
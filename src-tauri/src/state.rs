@@ -2,43 +2,434 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Mutex;
 
-use crate::audio::AudioPipeline;
-use crate::hid::Deck8Device;
+use crate::audio::SoundOutput;
+use crate::game::GameSettings;
+use crate::hid::{Deck8Device, HidTiming};
+use crate::modes::Mode;
+use crate::onboarding::OnboardingStep;
+use crate::pomodoro::FocusSettings;
 use crate::protocol::{DeviceInfo, HsvColor, RgbMatrixState};
+use crate::rules::{self, Rule};
+use crate::selftest::SyncReport;
 
+/// A named color a key can show. Keypresses cycle through a key's slots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorSlot {
+    pub name: String,
+    pub color: HsvColor,
+}
+
+/// How a key plays its sound.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
-pub enum ActiveSlot {
+pub enum SoundMode {
+    /// The whole sound, once per press.
     #[default]
-    A,
-    B,
+    OneShot,
+    /// Only while the key is held; fades out on release.
+    Momentary,
 }
 
-impl std::fmt::Display for ActiveSlot {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ActiveSlot::A => write!(f, "A"),
-            ActiveSlot::B => write!(f, "B"),
-        }
+/// What a press does besides the `key-event`: move to the next color slot,
+/// run the key's action (sound, playlist, lighting scene, followed app), or
+/// both.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum PressMode {
+    #[default]
+    Both,
+    /// The color stays put (e.g. a sound key that shouldn't flip colors).
+    ActionOnly,
+    /// Only the color changes; followed apps aren't toggled either.
+    LedOnly,
+}
+
+impl PressMode {
+    pub fn changes_led(self) -> bool {
+        self != Self::ActionOnly
     }
+
+    pub fn runs_action(self) -> bool {
+        self != Self::LedOnly
+    }
+}
+
+/// Which keyboards may trigger a key's shortcut.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum KeySource {
+    /// Only events attributed to the Deck-8 (Windows Raw Input device handle;
+    /// the Linux evdev backend never sees other keyboards).
+    #[default]
+    Deck,
+    /// Any keyboard producing the same chord.
+    Any,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "KeyConfigFile")]
 pub struct KeyConfig {
-    pub slot_a: HsvColor,
-    pub slot_b: HsvColor,
-    #[serde(default)]
+    /// Never empty.
+    pub slots: Vec<ColorSlot>,
     pub override_enabled: bool,
-    #[serde(default)]
-    pub active_slot: ActiveSlot,
+    /// Index into `slots`.
+    pub active_slot: usize,
+    pub source: KeySource,
+    /// Minimum time between two actions of this key (0 = no cooldown).
+    pub cooldown_ms: u64,
+    /// Minimum time between two plays of this key's sound (see cooldown.rs).
+    pub sound_cooldown_ms: u64,
+    /// Dim the key's LED until its sound can play again.
+    pub dim_on_cooldown: bool,
+    /// Whether the sound plays out or only while the key is held.
+    pub sound_mode: SoundMode,
+    pub press_mode: PressMode,
+    /// Drive the active slot from an external condition (see watchers.rs).
+    pub binding: Option<SlotBinding>,
+    /// User-facing name, e.g. "Mute mic".
+    pub label: Option<String>,
+    /// Short icon shown next to the label (an emoji or a symbol).
+    pub icon: Option<String>,
+    /// Lighting scene recalled on each press (see lighting.rs).
+    pub light_scene: Option<LightScene>,
+}
+
+/// Slot a key shows while a watched condition is true / false.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlotBinding {
+    pub condition: Condition,
+    pub when_true: String,
+    pub when_false: String,
+    /// How often the condition is evaluated.
+    #[serde(default = "default_poll_ms")]
+    pub poll_ms: u64,
+}
+
+fn default_poll_ms() -> u64 {
+    1000
+}
+
+/// A condition the watcher can evaluate.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum Condition {
+    /// The system default microphone is muted.
+    MicMuted,
+    /// OBS (obs-websocket v5) is recording (see obs.rs). Pressing the key
+    /// toggles recording.
+    ObsRecording {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        password: Option<String>,
+    },
+    /// OBS is streaming. Pressing the key toggles the stream.
+    ObsStreaming {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        password: Option<String>,
+    },
+    /// `scene` is OBS's program scene. Pressing the key switches to it.
+    ObsScene {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        password: Option<String>,
+        scene: String,
+    },
+    FileExists { path: String },
+    /// The last retained/published payload on `topic` equals `payload`.
+    Mqtt {
+        host: String,
+        port: u16,
+        topic: String,
+        payload: String,
+    },
+    /// GET `url` answers 2xx (and its body contains `contains`, if set).
+    HttpPoll {
+        url: String,
+        #[serde(default)]
+        contains: Option<String>,
+    },
+    /// Microsoft Teams reports the mic muted in a meeting (see meeting.rs).
+    /// Pressing the key toggles mute in Teams.
+    TeamsMuted,
+    /// Zoom shows the mic muted in a meeting. Pressing the key toggles mute.
+    ZoomMuted,
+    /// The OS media session is playing (see media.rs). Pressing the key
+    /// toggles play/pause.
+    MediaPlaying,
+    /// A virtual mixer channel is muted (see mixer.rs). Pressing the key
+    /// toggles it.
+    MixerMuted { mixer: Mixer, channel: String },
+    /// The soundboard pipeline is routing the mic. Pressing the key turns it
+    /// on or off.
+    SoundboardRunning,
+    /// Self-muted (or deafened) in Discord (see discord.rs). Pressing the key
+    /// toggles mute.
+    DiscordMuted,
+    /// Discord hears the user speaking in a voice channel.
+    DiscordSpeaking,
+    /// A focus session is running (see pomodoro.rs). Pressing the key starts
+    /// one, or stops the one running.
+    FocusRunning,
+    /// `mode` is the active mode (see modes.rs). Pressing the key cycles to
+    /// the next mode.
+    ModeActive { mode: String },
+    /// A meeting in the ICS calendar at `url` starts within `lead_min`
+    /// minutes or is on (see calendar.rs). With `open_link`, pressing the key
+    /// opens the meeting's link.
+    CalendarMeeting {
+        url: String,
+        #[serde(default = "default_lead_min")]
+        lead_min: u16,
+        #[serde(default)]
+        open_link: bool,
+    },
+}
+
+fn default_lead_min() -> u16 {
+    5
+}
+
+/// Virtual mixers with a control API.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Mixer {
+    WaveLink,
+    VoiceMeeter,
 }
 
 impl Default for KeyConfig {
     fn default() -> Self {
         Self {
-            slot_a: HsvColor { h: 0x55, s: 0xFF, v: 0x78 }, // green
-            slot_b: HsvColor { h: 0x00, s: 0xFF, v: 0x78 }, // red
+            slots: vec![
+                ColorSlot { name: "A".into(), color: HsvColor { h: 0x55, s: 0xFF, v: 0x78 } }, // green
+                ColorSlot { name: "B".into(), color: HsvColor { h: 0x00, s: 0xFF, v: 0x78 } }, // red
+            ],
             override_enabled: false,
-            active_slot: ActiveSlot::A,
+            active_slot: 0,
+            source: KeySource::Deck,
+            cooldown_ms: 0,
+            sound_cooldown_ms: 0,
+            dim_on_cooldown: false,
+            sound_mode: SoundMode::OneShot,
+            press_mode: PressMode::Both,
+            binding: None,
+            label: None,
+            icon: None,
+            light_scene: None,
+        }
+    }
+}
+
+impl KeyConfig {
+    pub fn active(&self) -> &ColorSlot {
+        &self.slots[self.active_slot]
+    }
+
+    pub fn slot_index(&self, name: &str) -> Option<usize> {
+        self.slots.iter().position(|s| s.name == name)
+    }
+
+    /// Advance to the next slot, wrapping around.
+    pub fn cycle_slot(&mut self) {
+        self.active_slot = (self.active_slot + 1) % self.slots.len();
+    }
+
+    /// Make the slot called `name` active (for actions and the external API).
+    pub fn select_slot(&mut self, name: &str) -> Result<(), String> {
+        self.active_slot = self.slot_index(name).ok_or_else(|| crate::i18n::t_with("error-no-slot", &[("slot", &name)]))?;
+        Ok(())
+    }
+
+    pub fn add_slot(&mut self, name: &str, color: HsvColor) -> Result<(), String> {
+        let name = validate_slot_name(name)?;
+        if self.slot_index(&name).is_some() {
+            return Err(crate::i18n::t_with("error-slot-exists", &[("slot", &name)]));
+        }
+        if self.slots.len() >= MAX_SLOTS {
+            return Err(crate::i18n::t_with("error-too-many-slots", &[("max", &MAX_SLOTS)]));
+        }
+        self.slots.push(ColorSlot { name, color });
+        Ok(())
+    }
+
+    pub fn remove_slot(&mut self, name: &str) -> Result<(), String> {
+        let idx = self.slot_index(name).ok_or_else(|| crate::i18n::t_with("error-no-slot", &[("slot", &name)]))?;
+        if self.slots.len() == 1 {
+            return Err(crate::i18n::t("error-last-slot"));
+        }
+        if self.binding.as_ref().is_some_and(|b| b.when_true == name || b.when_false == name) {
+            return Err(crate::i18n::t_with("error-slot-bound", &[("slot", &name)]));
+        }
+        self.slots.remove(idx);
+        if self.active_slot > idx || self.active_slot == self.slots.len() {
+            self.active_slot -= 1;
+        }
+        Ok(())
+    }
+
+    pub fn rename_slot(&mut self, name: &str, new_name: &str) -> Result<(), String> {
+        let idx = self.slot_index(name).ok_or_else(|| crate::i18n::t_with("error-no-slot", &[("slot", &name)]))?;
+        let new_name = validate_slot_name(new_name)?;
+        if self.slot_index(&new_name).is_some_and(|i| i != idx) {
+            return Err(crate::i18n::t_with("error-slot-exists", &[("slot", &new_name)]));
+        }
+        if let Some(b) = self.binding.as_mut() {
+            for slot in [&mut b.when_true, &mut b.when_false] {
+                if slot == name {
+                    slot.clone_from(&new_name);
+                }
+            }
+        }
+        self.slots[idx].name = new_name;
+        Ok(())
+    }
+
+    /// Set or clear (None / blank) the key's label.
+    pub fn set_label(&mut self, label: Option<String>) -> Result<(), String> {
+        self.label = validate_key_text(label, "error-label-too-long", 32)?;
+        Ok(())
+    }
+
+    /// Set or clear (None / blank) the key's icon.
+    pub fn set_icon(&mut self, icon: Option<String>) -> Result<(), String> {
+        self.icon = validate_key_text(icon, "error-icon-too-long", 8)?;
+        Ok(())
+    }
+
+    /// "🎤 Mute mic", falling back to "Key N" (1-based LED index) when unlabeled.
+    pub fn display_name(&self, key_index: usize) -> String {
+        let label = self.label.clone().unwrap_or_else(|| crate::i18n::t_with("key-name", &[("n", &(key_index + 1))]));
+        match &self.icon {
+            Some(icon) => format!("{icon} {label}"),
+            None => label,
+        }
+    }
+
+    pub fn set_binding(&mut self, binding: Option<SlotBinding>) -> Result<(), String> {
+        if let Some(b) = &binding {
+            for name in [&b.when_true, &b.when_false] {
+                if self.slot_index(name).is_none() {
+                    return Err(crate::i18n::t_with("error-no-slot", &[("slot", &name)]));
+                }
+            }
+            if b.poll_ms < 100 {
+                return Err(crate::i18n::t("error-poll-too-short"));
+            }
+            match &b.condition {
+                Condition::MixerMuted { channel, .. } if channel.trim().is_empty() => {
+                    return Err(crate::i18n::t("error-mixer-channel-empty"));
+                }
+                Condition::MixerMuted { mixer: Mixer::VoiceMeeter, channel }
+                    if !crate::mixer::valid_voicemeeter_channel(channel) => {
+                    return Err(crate::i18n::t_with("error-voicemeeter-channel", &[("channel", &channel)]));
+                }
+                Condition::CalendarMeeting { url, .. }
+                    if !["https://", "http://", "webcal://"].iter().any(|s| url.trim().starts_with(s)) => {
+                    return Err(crate::i18n::t("error-calendar-address"));
+                }
+                _ => {}
+            }
+        }
+        self.binding = binding;
+        Ok(())
+    }
+}
+
+/// Upper bound on slots per key, to keep the editor usable.
+pub const MAX_SLOTS: usize = 16;
+
+fn validate_slot_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(crate::i18n::t("error-slot-name-empty"));
+    }
+    if name.chars().count() > 32 {
+        return Err(crate::i18n::t("error-slot-name-too-long"));
+    }
+    Ok(name.to_string())
+}
+
+/// `too_long` is the message id for text over `max` characters.
+fn validate_key_text(text: Option<String>, too_long: &str, max: usize) -> Result<Option<String>, String> {
+    let Some(text) = text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) else {
+        return Ok(None);
+    };
+    if text.chars().count() > max {
+        return Err(crate::i18n::t_with(too_long, &[("max", &max)]));
+    }
+    Ok(Some(text))
+}
+
+/// On-disk KeyConfig. Older state files have fixed `slot_a`/`slot_b` colors
+/// and an `"A"`/`"B"` active slot instead of `slots` and an index.
+#[derive(Deserialize)]
+struct KeyConfigFile {
+    #[serde(default)]
+    slots: Vec<ColorSlot>,
+    slot_a: Option<HsvColor>,
+    slot_b: Option<HsvColor>,
+    #[serde(default)]
+    override_enabled: bool,
+    #[serde(default)]
+    active_slot: Option<SlotRef>,
+    #[serde(default)]
+    source: KeySource,
+    #[serde(default)]
+    cooldown_ms: u64,
+    #[serde(default)]
+    sound_cooldown_ms: u64,
+    #[serde(default)]
+    dim_on_cooldown: bool,
+    #[serde(default)]
+    sound_mode: SoundMode,
+    #[serde(default)]
+    press_mode: PressMode,
+    #[serde(default)]
+    binding: Option<SlotBinding>,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    icon: Option<String>,
+    #[serde(default)]
+    light_scene: Option<LightScene>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SlotRef {
+    Index(usize),
+    Legacy(String),
+}
+
+impl From<KeyConfigFile> for KeyConfig {
+    fn from(f: KeyConfigFile) -> Self {
+        let mut slots = f.slots;
+        if slots.is_empty() {
+            let defaults = KeyConfig::default().slots;
+            let legacy = [f.slot_a, f.slot_b];
+            slots = defaults.into_iter().zip(legacy)
+                .map(|(d, c)| ColorSlot { name: d.name, color: c.unwrap_or(d.color) })
+                .collect();
+        }
+        let active_slot = match f.active_slot {
+            Some(SlotRef::Index(i)) => i,
+            Some(SlotRef::Legacy(s)) if s == "B" => 1,
+            _ => 0,
+        };
+        Self {
+            active_slot: active_slot.min(slots.len() - 1),
+            slots,
+            override_enabled: f.override_enabled,
+            source: f.source,
+            cooldown_ms: f.cooldown_ms,
+            sound_cooldown_ms: f.sound_cooldown_ms,
+            dim_on_cooldown: f.dim_on_cooldown,
+            sound_mode: f.sound_mode,
+            press_mode: f.press_mode,
+            binding: f.binding,
+            label: f.label,
+            icon: f.icon,
+            light_scene: f.light_scene,
         }
     }
 }
@@ -48,6 +439,19 @@ pub struct SoundEntry {
     pub id: String,
     pub filename: String,
     pub display_name: String,
+    /// The file was deleted outside the app (see library_watch.rs).
+    #[serde(default)]
+    pub missing: bool,
+    /// Never mixed into the mic, even from a key (private alert tones).
+    #[serde(default)]
+    pub monitor_only: bool,
+}
+
+impl SoundEntry {
+    /// `requested`, unless the sound must stay out of the mic.
+    pub fn route(&self, requested: SoundRoute) -> SoundRoute {
+        if self.monitor_only { SoundRoute::Monitor } else { requested }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,25 +462,182 @@ pub struct AudioConfig {
     /// Sound library: unlimited collection of sound entries.
     #[serde(default)]
     pub sound_library: Vec<SoundEntry>,
-    /// Per-key sound assignment: each key references a SoundEntry.id (or None).
+    /// Per-key sound assignment: each key references a SoundEntry.id or a
+    /// Playlist.id (or None).
     #[serde(default = "default_key_sounds")]
     pub key_sounds: [Option<String>; 8],
     #[serde(default)]
+    pub playlists: Vec<Playlist>,
+    /// Per-key volume offset in dB for its sound, on top of `sound_volume`.
+    #[serde(default)]
+    pub key_sound_gain_db: [f32; 8],
+    #[serde(default)]
     pub audio_input_device: Option<String>,
     #[serde(default)]
     pub audio_output_device: Option<String>,
+    /// Second mic or line-in mixed in next to the mic (see line_in.rs).
+    #[serde(default)]
+    pub second_input_device: Option<String>,
+    #[serde(default = "default_volume")]
+    pub second_input_volume: f32,
     #[serde(default = "default_volume")]
     pub sound_volume: f32,
     #[serde(default = "default_volume")]
     pub mic_volume: f32,
+    /// Mic gain in dB as it's captured, before processing (`mic_volume`
+    /// scales the mix after it).
+    #[serde(default)]
+    pub input_gain_db: f32,
+    /// The pipeline is running (kept up to date by the backend).
     #[serde(default)]
     pub soundboard_enabled: bool,
+    /// Turned off by the user: the pipeline isn't started until turned back on.
+    #[serde(default)]
+    pub soundboard_off: bool,
+    /// Key sounds don't play (the mic still passes through); for emergencies,
+    /// from the tray or a hotkey.
+    #[serde(default)]
+    pub sounds_muted: bool,
+    /// Mic processing stages in order (see dsp.rs); sounds are mixed in after.
+    #[serde(default = "default_processing", deserialize_with = "deserialize_processing")]
+    pub processing: Vec<ProcessingStage>,
+    #[serde(default)]
+    pub agc: AgcSettings,
+    /// Saying "stop stop stop" into the mic stops every sound (see stopword.rs).
+    #[serde(default)]
+    pub stop_word: bool,
+    /// Format trimmed imports are saved in.
+    #[serde(default)]
+    pub sound_format: SoundFormat,
+    /// Where library and trim previews play while the pipeline runs.
+    #[serde(default)]
+    pub preview_route: SoundRoute,
+    /// Imports are refused while the library's files take more than this.
+    #[serde(default)]
+    pub library_quota_mb: Option<u64>,
+}
+
+impl AudioConfig {
+    /// Ids of the sounds keys play, directly or through a playlist.
+    pub fn key_sound_ids(&self) -> Vec<&str> {
+        self.key_sounds.iter().flatten()
+            .flat_map(|id| match self.playlists.iter().find(|p| &p.id == id) {
+                Some(playlist) => playlist.sounds.iter().map(String::as_str).collect(),
+                None => vec![id.as_str()],
+            })
+            .collect()
+    }
+
+    /// The saved device for `role`.
+    pub fn device_mut(&mut self, role: DeviceRole) -> &mut Option<String> {
+        match role {
+            DeviceRole::Input => &mut self.audio_input_device,
+            DeviceRole::Output => &mut self.audio_output_device,
+            DeviceRole::SecondInput => &mut self.second_input_device,
+        }
+    }
+}
+
+/// Sounds a key plays back to back with no gap (see playlist.rs). Pressing
+/// the key again skips to the next one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Playlist {
+    pub id: String,
+    pub name: String,
+    /// SoundEntry ids, in order.
+    pub sounds: Vec<String>,
+    /// The last sound repeats until skipped or stopped.
+    #[serde(default)]
+    pub loop_last: bool,
+}
+
+/// File format for sounds the app writes into the library.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum SoundFormat {
+    /// 32-bit float WAV, readable by anything.
+    #[default]
+    Wav,
+    /// Lossless and much smaller (see flac.rs).
+    Flac,
+}
+
+/// Where a sound plays while the pipeline runs: the local monitor
+/// (headphones), the mic mix going to the virtual cable, or both.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SoundRoute {
+    /// Only the user hears it.
+    #[default]
+    Monitor,
+    Cable,
+    Both,
+}
+
+impl SoundRoute {
+    pub fn monitor(self) -> bool {
+        self != Self::Cable
+    }
+
+    pub fn cable(self) -> bool {
+        self != Self::Monitor
+    }
 }
 
 fn default_volume() -> f32 {
     1.0
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StageKind {
+    Gate,
+    Denoise,
+    Eq,
+    /// Automatic gain control (see `AgcSettings`).
+    Agc,
+    Compressor,
+}
+
+impl StageKind {
+    pub const ALL: [StageKind; 5] = [Self::Gate, Self::Denoise, Self::Eq, Self::Agc, Self::Compressor];
+}
+
+/// AGC keeps speech between the target levels, boosting by at most
+/// `max_gain_db`. Levels are dBFS RMS.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct AgcSettings {
+    pub target_low_db: f32,
+    pub target_high_db: f32,
+    pub max_gain_db: f32,
+}
+
+impl Default for AgcSettings {
+    fn default() -> Self {
+        Self { target_low_db: -24.0, target_high_db: -16.0, max_gain_db: 12.0 }
+    }
+}
+
+/// One stage of the mic chain; a disabled stage is bypassed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProcessingStage {
+    pub kind: StageKind,
+    pub enabled: bool,
+}
+
+/// Every stage, bypassed.
+pub fn default_processing() -> Vec<ProcessingStage> {
+    StageKind::ALL.iter().map(|&kind| ProcessingStage { kind, enabled: false }).collect()
+}
+
+/// Saved chain, with stages added since it was saved appended, bypassed.
+fn deserialize_processing<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<ProcessingStage>, D::Error> {
+    let mut stages = Vec::<ProcessingStage>::deserialize(d)?;
+    for kind in StageKind::ALL {
+        if !stages.iter().any(|s| s.kind == kind) {
+            stages.push(ProcessingStage { kind, enabled: false });
+        }
+    }
+    Ok(stages)
+}
+
 fn default_key_sounds() -> [Option<String>; 8] {
     Default::default()
 }
@@ -87,27 +648,375 @@ impl Default for AudioConfig {
             sound_files: Default::default(),
             sound_library: Vec::new(),
             key_sounds: Default::default(),
+            playlists: Vec::new(),
+            key_sound_gain_db: [0.0; 8],
             audio_input_device: None,
             audio_output_device: None,
+            second_input_device: None,
+            second_input_volume: 1.0,
             sound_volume: 1.0,
             mic_volume: 1.0,
+            input_gain_db: 0.0,
             soundboard_enabled: false,
+            soundboard_off: false,
+            sounds_muted: false,
+            processing: default_processing(),
+            agc: AgcSettings::default(),
+            stop_word: false,
+            sound_format: SoundFormat::Wav,
+            preview_route: SoundRoute::Monitor,
+            library_quota_mb: None,
+        }
+    }
+}
+
+/// App-level preferences that aren't tied to a key or the audio pipeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppSettings {
+    /// Linux: grab the Deck-8's input device so sound-only keys don't leak
+    /// to other apps (other keys are re-emitted through uinput).
+    #[serde(default)]
+    pub grab_device_input: bool,
+    /// How the plugin shortcut path replays keystrokes (see replay.rs).
+    #[serde(default)]
+    pub replay_method: ReplayMethod,
+    /// Windows: presses of the same key within this window count once (the
+    /// LL hook and Raw Input both see every keystroke; also absorbs bounce).
+    #[serde(default = "default_dedup_ms")]
+    pub dedup_ms: u64,
+    /// Fade between slot colors over this many ms (0 = instant).
+    #[serde(default)]
+    pub fade_ms: u64,
+    /// Perceptual curve applied to every V sent to the device (see brightness.rs).
+    #[serde(default)]
+    pub brightness_curve: BrightnessCurve,
+    /// Earcon + screen-reader announcement when a key changes slot.
+    #[serde(default)]
+    pub accessibility_cues: bool,
+    #[serde(default)]
+    pub quiet_hours: QuietHours,
+    /// Track the OS media session for the now-playing card (see media.rs).
+    #[serde(default)]
+    pub now_playing: bool,
+    /// Serve the local WebSocket API (see api.rs).
+    #[serde(default)]
+    pub local_api: bool,
+    /// Global hotkey that stops every sound, e.g. "Ctrl+Alt+Backspace".
+    #[serde(default)]
+    pub stop_sounds_hotkey: Option<String>,
+    /// Global hotkey that mutes or unmutes key sounds.
+    #[serde(default)]
+    pub mute_sounds_hotkey: Option<String>,
+    #[serde(default)]
+    pub discord: DiscordSettings,
+    #[serde(default)]
+    pub lighting: LightingSettings,
+    /// Keys still on the default first color take the OS accent color (see
+    /// appearance.rs).
+    #[serde(default)]
+    pub accent_key_colors: bool,
+    /// The accent color last given to those keys, so they can follow it.
+    #[serde(default)]
+    pub applied_accent: Option<HsvColor>,
+    /// Language of backend strings (see i18n.rs); `None` follows the OS.
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub window: WindowSettings,
+    #[serde(default)]
+    pub hid: HidTiming,
+    /// While another app holds the device, keep retrying and connect once it
+    /// lets go (see coexist.rs).
+    #[serde(default)]
+    pub cooperative: bool,
+    /// Salted hash of the PIN while the setup is locked (see lock.rs).
+    #[serde(default)]
+    pub lock_pin: Option<String>,
+    #[serde(default)]
+    pub focus: FocusSettings,
+    #[serde(default)]
+    pub game: GameSettings,
+    /// First-run guide step (see onboarding.rs).
+    #[serde(default = "OnboardingStep::finished")]
+    pub onboarding: OnboardingStep,
+}
+
+/// What closing and minimizing the main window do.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct WindowSettings {
+    #[serde(default)]
+    pub close: CloseAction,
+    /// Minimizing hides the window to the tray too.
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+    /// Ask before quitting while a sound or playlist is playing.
+    #[serde(default)]
+    pub confirm_quit: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum CloseAction {
+    /// Keep running in the tray (quit from the tray menu).
+    #[default]
+    HideToTray,
+    Exit,
+}
+
+/// The app's own global hotkeys (not a Deck-8 key's shortcut).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AppHotkey {
+    StopSounds,
+    MuteSounds,
+}
+
+impl AppSettings {
+    pub fn hotkey_mut(&mut self, action: AppHotkey) -> &mut Option<String> {
+        match action {
+            AppHotkey::StopSounds => &mut self.stop_sounds_hotkey,
+            AppHotkey::MuteSounds => &mut self.mute_sounds_hotkey,
         }
     }
 }
 
-pub struct ManagedAudioPipeline(pub Mutex<Option<AudioPipeline>>);
+/// Discord Rich Presence and voice state (see discord.rs), through the user's
+/// own Discord application.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DiscordSettings {
+    /// Show the deck's current profile as the Discord activity.
+    pub presence: bool,
+    pub client_id: String,
+    /// Only needed for voice state bindings (token exchange).
+    #[serde(default)]
+    pub client_secret: Option<String>,
+}
+
+/// Desk lights that follow the pad (see lighting.rs).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LightingSettings {
+    /// Color every target mirrors; `None` leaves them to scenes.
+    #[serde(default)]
+    pub mirror: Option<LightSource>,
+    #[serde(default)]
+    pub targets: Vec<LightTarget>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum LightSource {
+    /// A key's active slot color (LED index).
+    Key { key_index: usize },
+    /// The RGB matrix color and brightness.
+    Matrix,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum LightTarget {
+    /// Hue bridge; `user` is the app key from pairing, `lights` the light ids
+    /// that mirror the color.
+    Hue { name: String, bridge: String, user: String, lights: Vec<String> },
+    /// WLED controller (its main segment mirrors the color).
+    Wled { name: String, host: String },
+}
+
+impl LightTarget {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Hue { name, .. } | Self::Wled { name, .. } => name,
+        }
+    }
+}
+
+/// Scene a key recalls: a Hue scene id, or a WLED preset number.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LightScene {
+    /// `LightTarget` name.
+    pub target: String,
+    pub scene: String,
+}
+
+/// Daily window in which key sounds are muted or capped (see quiet.rs).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct QuietHours {
+    pub enabled: bool,
+    /// Minutes after local midnight. `start > end` wraps past midnight;
+    /// `start == end` is all day.
+    pub start_min: u16,
+    pub end_min: u16,
+    /// Loudest a key sound plays inside the window (0 = muted).
+    pub max_volume: f32,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self { enabled: false, start_min: 22 * 60, end_min: 8 * 60, max_volume: 0.0 }
+    }
+}
+
+/// `out = min + (max - min) * (v / 255)^gamma` for v > 0; 0 stays off.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BrightnessCurve {
+    pub gamma: f32,
+    /// Lowest V the LEDs get once on (lifts the invisible bottom of the range).
+    pub min: u8,
+    /// Highest V the LEDs get (caps the glaring top).
+    pub max: u8,
+}
+
+impl BrightnessCurve {
+    /// Identity mapping: what the device got before calibration existed.
+    pub const LINEAR: Self = Self { gamma: 1.0, min: 0, max: 255 };
+}
+
+impl Default for BrightnessCurve {
+    fn default() -> Self {
+        Self::LINEAR
+    }
+}
+
+pub const DEFAULT_DEDUP_MS: u64 = 150;
+
+fn default_dedup_ms() -> u64 {
+    DEFAULT_DEDUP_MS
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            grab_device_input: false,
+            replay_method: ReplayMethod::default(),
+            dedup_ms: DEFAULT_DEDUP_MS,
+            fade_ms: 0,
+            brightness_curve: BrightnessCurve::LINEAR,
+            accessibility_cues: false,
+            quiet_hours: QuietHours::default(),
+            now_playing: false,
+            local_api: false,
+            stop_sounds_hotkey: None,
+            mute_sounds_hotkey: None,
+            discord: DiscordSettings::default(),
+            lighting: LightingSettings::default(),
+            accent_key_colors: false,
+            applied_accent: None,
+            language: None,
+            window: WindowSettings::default(),
+            hid: HidTiming::default(),
+            cooperative: false,
+            lock_pin: None,
+            focus: FocusSettings::default(),
+            game: GameSettings::default(),
+            onboarding: OnboardingStep::default(),
+        }
+    }
+}
+
+/// Keystroke replay mechanism for the plugin shortcut path.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum ReplayMethod {
+    /// SendInput with scancodes (Windows), uinput (Linux).
+    #[default]
+    Native,
+    /// enigo's virtual-key / keysym replay.
+    Enigo,
+}
+
+/// A named set of 8 key colors (LED order), applied in one batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    pub colors: [HsvColor; 8],
+}
+
+/// Keys that act like radio buttons: when one leaves its first slot, the
+/// others go back to theirs (e.g. only one scene key lit).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeyGroup {
+    pub name: String,
+    /// LED indices, sorted, at least two.
+    pub keys: Vec<usize>,
+}
+
+/// What do-not-disturb turned off, so it can be put back exactly.
+#[derive(Debug, Clone, Copy)]
+pub struct DndState {
+    /// RGB matrix brightness before DND (updated by brightness changes made during DND).
+    pub brightness: u8,
+    /// `save_rgb_matrix` was requested during DND; run it on restore.
+    pub rgb_save_pending: bool,
+}
+
+pub struct ManagedAudioPipeline(pub Mutex<Option<Box<dyn SoundOutput>>>);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DeviceRole {
+    Input,
+    Output,
+    SecondInput,
+}
+
+/// A saved audio device that's gone, with several look-alikes listed in its
+/// place (see device_match.rs). The UI asks which one it is.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceChoice {
+    pub role: DeviceRole,
+    pub saved: String,
+    pub candidates: Vec<String>,
+}
 
 pub struct AppState {
     pub device: Option<Deck8Device>,
     pub keys: [KeyConfig; 8],
-    pub active_slot: ActiveSlot,
     pub keymaps: [u16; 8],
+    /// Last-known keymaps (matrix order) when the connected device came up with
+    /// different ones, e.g. after a keymap reset or a flash. Persisted instead
+    /// of `keymaps` until the user restores or dismisses them.
+    pub keymap_restore: Option<[u16; 8]>,
     pub device_info: Option<DeviceInfo>,
+    /// Last device info read, kept across reconnects so `get_device_info`
+    /// can skip the static fields. Cleared by `bootloader_jump` (a reflash).
+    pub device_info_cache: Option<DeviceInfo>,
     pub rgb_matrix: Option<RgbMatrixState>,
+    /// RGB matrix settings as the user last saved them (UI brightness, before
+    /// the curve). Persisted so they survive an EEPROM reset.
+    pub saved_rgb: Option<RgbMatrixState>,
+    /// The connected device's RGB settings differ from `saved_rgb`; the UI
+    /// asks whether to restore them.
+    pub rgb_mismatch: bool,
     /// Maps shortcut display string → (LED index, QMK keycode, register string)
     pub shortcut_map: HashMap<String, (usize, u16, String)>,
+    /// Registered app hotkeys: handler display string → (action, hotkey as set).
+    pub app_hotkeys: HashMap<String, (AppHotkey, String)>,
     pub audio_config: AudioConfig,
+    pub settings: AppSettings,
+    /// Saved swatches for the color picker.
+    pub palette: Vec<HsvColor>,
+    pub themes: Vec<Theme>,
+    pub groups: Vec<KeyGroup>,
+    pub modes: Vec<Mode>,
+    /// The mode last switched to.
+    pub active_mode: Option<String>,
+    pub rules: Vec<Rule>,
+    /// Rules holding right now, by id. Not persisted.
+    pub active_rules: HashMap<String, rules::Active>,
+    /// Key config copied with `copy_key_config` (source LED index, config). Not persisted.
+    pub key_clipboard: Option<(usize, KeyConfig)>,
+    /// Set while do-not-disturb has every LED off. Per-key overrides stay in
+    /// `keys`; only the device is darkened.
+    pub dnd: Option<DndState>,
+    /// Started with --safe-mode (or after repeated startup crashes): no device
+    /// auto-connect, no audio auto-start, no keyboard hooks/shortcuts.
+    pub safe_mode: bool,
+    /// Quiet hours are suspended until this Unix time (ms). Not persisted.
+    pub quiet_override_until: Option<u64>,
+    /// Self-test of the current connection.
+    pub sync_report: Option<SyncReport>,
+    /// Firmware version the self-test's write checks last passed on; they
+    /// write EEPROM, so they don't run again until the firmware changes.
+    pub echo_checked_firmware: Option<u32>,
+    /// The last connect found the Deck-8 held by another app. Not persisted.
+    pub device_busy: bool,
+    /// Saved device that couldn't be told apart from its look-alikes. Not persisted.
+    pub device_choice: Option<DeviceChoice>,
 }
 
 impl Default for AppState {
@@ -115,38 +1024,218 @@ impl Default for AppState {
         Self {
             device: None,
             keys: std::array::from_fn(|_| KeyConfig::default()),
-            active_slot: ActiveSlot::A,
             keymaps: [0u16; 8],
+            keymap_restore: None,
             device_info: None,
+            device_info_cache: None,
             rgb_matrix: None,
+            saved_rgb: None,
+            rgb_mismatch: false,
             shortcut_map: HashMap::new(),
+            app_hotkeys: HashMap::new(),
             audio_config: AudioConfig::default(),
+            settings: AppSettings::default(),
+            palette: Vec::new(),
+            themes: Vec::new(),
+            groups: Vec::new(),
+            modes: Vec::new(),
+            active_mode: None,
+            rules: Vec::new(),
+            active_rules: HashMap::new(),
+            key_clipboard: None,
+            dnd: None,
+            safe_mode: false,
+            quiet_override_until: None,
+            sync_report: None,
+            echo_checked_firmware: None,
+            device_busy: false,
+            device_choice: None,
         }
     }
 }
 
+/// Physical key press/release, emitted to the frontend as `key-event`.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyEvent {
+    /// LED index of the key.
+    pub key_index: usize,
+    pub pressed: bool,
+    /// On release: how long the key was held, in milliseconds.
+    pub held_ms: Option<u64>,
+}
+
+/// Returned by `get_app_info`, for the About section and bug reports.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppInfo {
+    pub version: &'static str,
+    /// Short commit hash, when built from a git checkout.
+    pub build_hash: Option<&'static str>,
+    /// e.g. "windows x86_64".
+    pub platform: String,
+    pub config_dir: Option<String>,
+    pub sounds_dir: Option<String>,
+    /// Per-key detection in use: "ll-hook", "raw-input", "evdev", "portal"
+    /// or "global-shortcut".
+    pub shortcut_backends: Vec<&'static str>,
+    /// Active keyboard layout keymaps are translated for (see layout.rs).
+    pub keyboard_layout: Option<String>,
+    /// cpal host used for the soundboard.
+    pub audio_backend: &'static str,
+    pub soundboard_running: bool,
+    pub safe_mode: bool,
+}
+
+/// Result of `start_keycode_capture`, emitted as `keycode-captured`.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeycodeCaptured {
+    /// Keymap (matrix) index of the key.
+    pub key_index: usize,
+    /// None when the capture was cancelled or timed out.
+    pub keycode: Option<u16>,
+    /// Set when writing the captured keycode failed.
+    pub error: Option<String>,
+}
+
+/// Whether the device shows what the app has, and keeps it.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub enum SyncStatus {
+    /// Shown and saved to EEPROM.
+    Saved,
+    /// Shown, but lost on unplug until "Save to device".
+    Unsaved,
+    /// The device shows something else: a write failed, or it was reset or
+    /// changed elsewhere.
+    OutOfSync,
+    /// No device, or its firmware lacks the feature.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct DeviceSync {
+    /// Per-key colors.
+    pub colors: SyncStatus,
+    pub keymaps: SyncStatus,
+    /// RGB Matrix settings.
+    pub rgb: SyncStatus,
+}
+
 /// Snapshot of state sent to the frontend (no device handle).
 #[derive(Debug, Clone, Serialize)]
 pub struct StateSnapshot {
     pub connected: bool,
     pub keys: Vec<KeyConfig>,
-    pub active_slot: ActiveSlot,
     pub keymaps: Vec<u16>,
+    /// Last-known keymaps offered for restore (matrix order).
+    pub keymap_restore: Option<Vec<u16>>,
     pub device_info: Option<DeviceInfo>,
     pub rgb_matrix: Option<RgbMatrixState>,
+    /// Saved RGB settings offered for restore (set when the device differs).
+    pub rgb_restore: Option<RgbMatrixState>,
     pub audio_config: AudioConfig,
+    pub settings: AppSettings,
+    pub palette: Vec<HsvColor>,
+    pub themes: Vec<Theme>,
+    pub groups: Vec<KeyGroup>,
+    pub modes: Vec<Mode>,
+    pub active_mode: Option<String>,
+    pub rules: Vec<Rule>,
+    /// Ids of the rules holding right now.
+    pub active_rules: Vec<String>,
+    /// LED index of the key last copied, if any.
+    pub key_clipboard: Option<usize>,
+    pub dnd: bool,
+    pub safe_mode: bool,
+    /// Key sounds are muted or capped right now.
+    pub quiet_active: bool,
+    /// Unix time (ms) the quiet hours override ends.
+    pub quiet_override_until: Option<u64>,
+    /// Unix time (ms) the running focus session ends.
+    pub focus_until: Option<u64>,
+    pub sync_report: Option<SyncReport>,
+    pub device_sync: DeviceSync,
+    /// Another app holds the device (the last connect couldn't open it).
+    pub device_busy: bool,
+    /// Configuration changes are refused until unlocked.
+    pub locked: bool,
+    pub device_choice: Option<DeviceChoice>,
 }
 
 impl AppState {
+    /// Per subsystem, whether "Save to device" (or a re-sync) is needed.
+    pub fn device_sync(&self) -> DeviceSync {
+        let Some(ref dev) = self.device else {
+            let unknown = SyncStatus::Unknown;
+            return DeviceSync { colors: unknown, keymaps: unknown, rgb: unknown };
+        };
+        let writes = dev.writes();
+        let caps = dev.capabilities();
+        let status = |supported: bool, stale: bool, unsaved: bool| match (supported, stale, unsaved) {
+            (false, _, _) => SyncStatus::Unknown,
+            (true, true, _) => SyncStatus::OutOfSync,
+            (true, false, true) => SyncStatus::Unsaved,
+            (true, false, false) => SyncStatus::Saved,
+        };
+        DeviceSync {
+            colors: status(caps.per_key_override, writes.colors_stale != 0, writes.colors_unsaved),
+            // Keymap writes go straight to EEPROM
+            keymaps: status(true, writes.keymaps_stale != 0 || self.keymap_restore.is_some(), false),
+            rgb: status(
+                caps.rgb_matrix && self.rgb_matrix.is_some(),
+                writes.rgb_stale || self.rgb_mismatch,
+                writes.rgb_unsaved,
+            ),
+        }
+    }
+
+    /// The saved theme the keys are showing, if any: the deck's profile.
+    pub fn active_theme(&self) -> Option<&str> {
+        self.themes.iter()
+            .find(|t| t.colors.iter().zip(&self.keys).all(|(c, k)| *c == k.active().color))
+            .map(|t| t.name.as_str())
+    }
+
+    /// Keys sharing a group with `key_index`, not counting itself.
+    pub fn group_peers(&self, key_index: usize) -> Vec<usize> {
+        let mut peers: Vec<usize> = self.groups.iter()
+            .filter(|g| g.keys.contains(&key_index))
+            .flat_map(|g| g.keys.iter().copied())
+            .filter(|&k| k != key_index)
+            .collect();
+        peers.sort_unstable();
+        peers.dedup();
+        peers
+    }
+
     pub fn snapshot(&self) -> StateSnapshot {
         StateSnapshot {
             connected: self.device.is_some(),
             keys: self.keys.to_vec(),
-            active_slot: self.active_slot,
             keymaps: self.keymaps.to_vec(),
+            keymap_restore: self.keymap_restore.map(|k| k.to_vec()),
             device_info: self.device_info.clone(),
             rgb_matrix: self.rgb_matrix,
+            rgb_restore: self.saved_rgb.filter(|_| self.rgb_mismatch),
             audio_config: self.audio_config.clone(),
+            // The webview only needs `locked`, not the PIN's hash
+            settings: AppSettings { lock_pin: None, ..self.settings.clone() },
+            palette: self.palette.clone(),
+            themes: self.themes.clone(),
+            groups: self.groups.clone(),
+            modes: self.modes.clone(),
+            active_mode: self.active_mode.clone(),
+            rules: self.rules.clone(),
+            active_rules: self.active_rules.keys().cloned().collect(),
+            key_clipboard: self.key_clipboard.as_ref().map(|(i, _)| *i),
+            dnd: self.dnd.is_some(),
+            safe_mode: self.safe_mode,
+            quiet_active: crate::quiet::active(self),
+            quiet_override_until: self.quiet_override_until.filter(|&t| t > crate::now_ms()),
+            focus_until: crate::pomodoro::running_until(),
+            sync_report: self.sync_report.clone(),
+            device_sync: self.device_sync(),
+            device_busy: self.device_busy,
+            locked: self.settings.lock_pin.is_some(),
+            device_choice: self.device_choice.clone(),
         }
     }
 }
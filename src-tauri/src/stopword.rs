@@ -0,0 +1,201 @@
+// Stop word: saying "stop stop stop" into the mic cuts every sound, for when
+// hands are off the pad.
+//
+// This is an acoustic heuristic, not speech recognition. Each 10ms frame of
+// the mic is sorted into quiet / hiss / voiced by its energy above an
+// adaptive noise floor and the balance between a high band (the /s/) and a
+// low band (the vowel). A "stop" is a hiss, a short gap (the /t/), a vowel
+// and a closure (the /p/); three in a row, each within a second of the last,
+// trigger. Other words with that shape can fool it once, rarely three times.
+//
+// It runs on its own thread, fed by a tap on the mic input that only fills
+// while `AudioConfig::stop_word` is on.
+
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Weak;
+use std::time::Duration;
+
+use log::info;
+use ringbuf::traits::{Consumer, Observer};
+
+use crate::dsp::Biquad;
+
+const FRAME_MS: f32 = 10.0;
+/// Frames of hiss, gap and vowel in one "stop".
+const HISS_FRAMES: RangeInclusive<u32> = 3..=25;
+const GAP_MAX_FRAMES: u32 = 8;
+const VOWEL_FRAMES: RangeInclusive<u32> = 6..=40;
+const REPEATS: usize = 3;
+/// Longest pause between two "stop"s, in frames.
+const BETWEEN_MAX_FRAMES: u64 = 100;
+/// Speech is this far above the noise floor, and above an absolute minimum
+/// (mean square, about -50 dBFS).
+const SPEECH_ABOVE_FLOOR_DB: f32 = 12.0;
+const MIN_LEVEL: f32 = 1e-5;
+/// How fast the noise floor may rise, and where it starts (so the first
+/// words count while it settles).
+const FLOOR_RISE_DB_PER_SEC: f32 = 3.0;
+const FLOOR_MIN: f32 = 1e-9;
+const HISS_HZ: f32 = 3500.0;
+const VOICE_HZ: f32 = 1000.0;
+/// How often the thread drains the mic tap.
+const POLL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frame {
+    Quiet,
+    Hiss,
+    Voiced,
+    /// Speech that's neither (plosive bursts, mixed frames).
+    Other,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Phase {
+    Idle,
+    Hiss(u32),
+    Gap(u32),
+    Vowel(u32),
+}
+
+/// Listens for "stop stop stop" in mono samples.
+pub struct Spotter {
+    frame_len: usize,
+    high: Biquad,
+    low: Biquad,
+    /// Sums of squares over the current frame: all, high band, low band.
+    sums: [f32; 3],
+    count: usize,
+    floor: f32,
+    floor_rise: f32,
+    speech_ratio: f32,
+    phase: Phase,
+    frames: u64,
+    /// Frame numbers where the latest "stop"s ended.
+    heard: Vec<u64>,
+}
+
+impl Spotter {
+    pub fn new(sample_rate: u32) -> Self {
+        let rate = sample_rate as f32;
+        let q = std::f32::consts::FRAC_1_SQRT_2;
+        Self {
+            frame_len: (rate * FRAME_MS / 1000.0) as usize,
+            high: Biquad::high_pass(1, rate, HISS_HZ, q),
+            low: Biquad::low_pass(1, rate, VOICE_HZ, q),
+            sums: [0.0; 3],
+            count: 0,
+            floor: FLOOR_MIN,
+            floor_rise: 10f32.powf(FLOOR_RISE_DB_PER_SEC * FRAME_MS / 1000.0 / 10.0),
+            speech_ratio: 10f32.powf(SPEECH_ABOVE_FLOOR_DB / 10.0),
+            phase: Phase::Idle,
+            frames: 0,
+            heard: Vec::new(),
+        }
+    }
+
+    /// Feed one sample; true when it completes the phrase.
+    pub fn feed(&mut self, x: f32) -> bool {
+        let (high, low) = (self.high.process(x, 0), self.low.process(x, 0));
+        self.sums[0] += x * x;
+        self.sums[1] += high * high;
+        self.sums[2] += low * low;
+        self.count += 1;
+        if self.count < self.frame_len {
+            return false;
+        }
+        let [all, high, low] = self.sums.map(|s| s / self.count as f32);
+        (self.sums, self.count) = ([0.0; 3], 0);
+        let frame = self.classify(all, high, low);
+        self.frames += 1;
+        self.step(frame)
+    }
+
+    fn classify(&mut self, all: f32, high: f32, low: f32) -> Frame {
+        self.floor = if all < self.floor { all.max(FLOOR_MIN) } else { self.floor * self.floor_rise };
+        if all < MIN_LEVEL || all < self.floor * self.speech_ratio {
+            Frame::Quiet
+        } else if high > 1.5 * low {
+            Frame::Hiss
+        } else if low > 2.0 * high {
+            Frame::Voiced
+        } else {
+            Frame::Other
+        }
+    }
+
+    fn step(&mut self, frame: Frame) -> bool {
+        self.phase = match (self.phase, frame) {
+            (Phase::Idle, Frame::Hiss) => Phase::Hiss(1),
+            (Phase::Idle, _) => Phase::Idle,
+            (Phase::Hiss(n), Frame::Hiss) if n < *HISS_FRAMES.end() => Phase::Hiss(n + 1),
+            (Phase::Hiss(n), Frame::Voiced) if HISS_FRAMES.contains(&n) => Phase::Vowel(1),
+            (Phase::Hiss(n), Frame::Quiet | Frame::Other) if HISS_FRAMES.contains(&n) => Phase::Gap(1),
+            (Phase::Hiss(_), _) => Phase::Idle,
+            (Phase::Gap(_), Frame::Voiced) => Phase::Vowel(1),
+            (Phase::Gap(n), _) if n < GAP_MAX_FRAMES => Phase::Gap(n + 1),
+            (Phase::Gap(_), _) => Phase::Idle,
+            (Phase::Vowel(n), Frame::Voiced | Frame::Other) if n < *VOWEL_FRAMES.end() => Phase::Vowel(n + 1),
+            (Phase::Vowel(n), Frame::Quiet | Frame::Hiss) if VOWEL_FRAMES.contains(&n) => {
+                // Said fast, the next "stop"'s hiss follows right away
+                let next = if frame == Frame::Hiss { Phase::Hiss(1) } else { Phase::Idle };
+                self.phase = next;
+                return self.heard_one();
+            }
+            (Phase::Vowel(_), _) => Phase::Idle,
+        };
+        false
+    }
+
+    fn heard_one(&mut self) -> bool {
+        if self.heard.last().is_some_and(|&last| self.frames - last > BETWEEN_MAX_FRAMES) {
+            self.heard.clear();
+        }
+        self.heard.push(self.frames);
+        if self.heard.len() < REPEATS {
+            return false;
+        }
+        self.heard.clear();
+        true
+    }
+}
+
+/// Spot the stop word in `tap` (interleaved mic samples) until the pipeline
+/// drops `enabled`, calling `on_stop` each time it's heard.
+pub fn spawn(
+    mut tap: ringbuf::HeapCons<f32>,
+    channels: u16,
+    sample_rate: u32,
+    enabled: Weak<AtomicBool>,
+    on_stop: Box<dyn Fn() + Send>,
+) {
+    std::thread::spawn(move || {
+        let mut spotter = Spotter::new(sample_rate);
+        let mut buf = vec![0.0; 4096];
+        // Channel of the next sample; the first channel is used
+        let mut channel = 0;
+        loop {
+            std::thread::sleep(POLL);
+            let Some(enabled) = enabled.upgrade() else { return };
+            if !enabled.load(Ordering::Relaxed) {
+                tap.skip(tap.occupied_len());
+                spotter = Spotter::new(sample_rate);
+                continue;
+            }
+            loop {
+                let n = tap.pop_slice(&mut buf);
+                if n == 0 {
+                    break;
+                }
+                for &x in &buf[..n] {
+                    if channel == 0 && spotter.feed(x) {
+                        info!("[stopword] Heard, stopping all sounds");
+                        on_stop();
+                    }
+                    channel = (channel + 1) % channels as usize;
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,284 @@
+// In-memory stand-ins for the Deck-8 and the audio pipeline.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+
+use crate::audio::{SoundOutput, Trim};
+use crate::hid::{Deck8Device, Transport};
+use crate::protocol::*;
+use crate::state::{AgcSettings, ProcessingStage, SoundRoute};
+
+/// What the fake firmware holds. Indexes follow the wire: keymaps in matrix
+/// order, overrides and colors by LED.
+#[derive(Debug)]
+pub struct Firmware {
+    pub keymaps: [u16; 8],
+    /// Layers 1–3.
+    pub upper_layers: [[u16; 8]; 3],
+    /// Acks keymap writes without storing them.
+    pub drops_keymap_writes: bool,
+    /// Matrix index whose keymap writes are acked but not stored.
+    pub stuck_key: Option<usize>,
+    pub overrides: [bool; 8],
+    pub colors: [HsvColor; 8],
+    pub rgb: RgbMatrixState,
+    /// Acks RGB Matrix writes without storing them.
+    pub drops_rgb_writes: bool,
+    pub firmware_version: u32,
+    pub uptime: u32,
+    /// Macro buffer (`VIA_MACRO_GET_BUFFER_SIZE` bytes once read).
+    pub macros: Vec<u8>,
+    /// Feature bits for the capability handshake; `None` is a pre-handshake build.
+    pub features: Option<u16>,
+    pub custom_saves: usize,
+    /// Reports the firmware didn't recognise (answered with 0xFF).
+    pub unhandled: usize,
+    /// Reports written to the device.
+    pub reports: usize,
+    /// Read timeout of the last response read.
+    pub last_timeout_ms: i32,
+    /// Receives reports but never acks them.
+    pub hung: bool,
+    responses: VecDeque<[u8; 32]>,
+}
+
+impl Default for Firmware {
+    fn default() -> Self {
+        Self {
+            keymaps: [0; 8],
+            upper_layers: [[0; 8]; 3],
+            drops_keymap_writes: false,
+            stuck_key: None,
+            overrides: [false; 8],
+            colors: [HsvColor::default(); 8],
+            rgb: RgbMatrixState { brightness: 200, effect: 1, speed: 128, color_h: 0, color_s: 255 },
+            drops_rgb_writes: false,
+            firmware_version: 0x0001_0200,
+            uptime: 0,
+            macros: Vec::new(),
+            features: Some(0b11),
+            custom_saves: 0,
+            unhandled: 0,
+            reports: 0,
+            last_timeout_ms: 0,
+            hung: false,
+            responses: VecDeque::new(),
+        }
+    }
+}
+
+impl Firmware {
+    /// Answer one report the way VIA does: echo the request, with any read
+    /// value filled in.
+    fn handle(&mut self, req: &[u8; 32]) -> [u8; 32] {
+        let mut resp = *req;
+        match req[0] {
+            VIA_GET_PROTOCOL_VERSION => resp[1..3].copy_from_slice(&12u16.to_be_bytes()),
+            VIA_GET_KEYBOARD_VALUE => match req[1] {
+                KB_VALUE_UPTIME => resp[2..6].copy_from_slice(&self.uptime.to_be_bytes()),
+                KB_VALUE_FIRMWARE_VERSION => resp[2..6].copy_from_slice(&self.firmware_version.to_be_bytes()),
+                _ => {}
+            },
+            VIA_DYNAMIC_KEYMAP_GET => {
+                let kc = self.layer(req[1])[matrix_index(req)];
+                resp[4..6].copy_from_slice(&kc.to_be_bytes());
+            }
+            VIA_DYNAMIC_KEYMAP_SET if self.drops_keymap_writes || self.stuck_key == Some(matrix_index(req)) => {}
+            VIA_DYNAMIC_KEYMAP_SET => {
+                self.layer(req[1])[matrix_index(req)] = u16::from_be_bytes([req[4], req[5]]);
+            }
+            VIA_DYNAMIC_KEYMAP_RESET => self.keymaps = [0; 8],
+            // Custom channel: per-key (0x00) or RGB Matrix
+            0x07 if req[1] == 0x00 => {
+                let led = req[4] as usize;
+                match req[2] {
+                    0x01 => self.overrides[led] = req[5] != 0,
+                    0x02 => self.colors[led].v = req[5],
+                    0x03 => (self.colors[led].h, self.colors[led].s) = (req[5], req[6]),
+                    0x04 if self.features.is_some_and(|bits| bits & 0b100 != 0) => {
+                        self.overrides[led] = true;
+                        self.colors[led] = HsvColor { h: req[5], s: req[6], v: req[7] };
+                    }
+                    _ => return self.unhandled(),
+                }
+            }
+            0x07 if req[1] == RGB_MATRIX_CHANNEL && self.drops_rgb_writes => {}
+            0x07 if req[1] == RGB_MATRIX_CHANNEL => match req[2] {
+                RGB_VAL_BRIGHTNESS => self.rgb.brightness = req[3],
+                RGB_VAL_EFFECT => self.rgb.effect = req[3],
+                RGB_VAL_EFFECT_SPEED => self.rgb.speed = req[3],
+                RGB_VAL_COLOR => (self.rgb.color_h, self.rgb.color_s) = (req[3], req[4]),
+                _ => return self.unhandled(),
+            },
+            VIA_CUSTOM_GET_VALUE if req[1] == 0x00 && req[2] == 0x10 => match self.features {
+                Some(bits) => resp[3..5].copy_from_slice(&bits.to_be_bytes()),
+                None => return self.unhandled(),
+            },
+            VIA_CUSTOM_GET_VALUE if req[1] == RGB_MATRIX_CHANNEL => match req[2] {
+                RGB_VAL_BRIGHTNESS => resp[3] = self.rgb.brightness,
+                RGB_VAL_EFFECT => resp[3] = self.rgb.effect,
+                RGB_VAL_EFFECT_SPEED => resp[3] = self.rgb.speed,
+                RGB_VAL_COLOR => (resp[3], resp[4]) = (self.rgb.color_h, self.rgb.color_s),
+                _ => return self.unhandled(),
+            },
+            VIA_CUSTOM_SAVE => self.custom_saves += 1,
+            VIA_SET_KEYBOARD_VALUE | VIA_EEPROM_RESET | VIA_BOOTLOADER_JUMP => {}
+            VIA_MACRO_RESET => self.macros.clear(),
+            VIA_MACRO_GET_BUFFER => {
+                let offset = u16::from_be_bytes([req[1], req[2]]) as usize;
+                for (i, byte) in resp[4..4 + req[3] as usize].iter_mut().enumerate() {
+                    *byte = self.macros.get(offset + i).copied().unwrap_or(0);
+                }
+            }
+            VIA_MACRO_GET_COUNT => resp[1] = 16,
+            VIA_MACRO_GET_BUFFER_SIZE => resp[1..3].copy_from_slice(&1024u16.to_be_bytes()),
+            VIA_GET_LAYER_COUNT => resp[1] = 4,
+            _ => return self.unhandled(),
+        }
+        resp
+    }
+
+    fn layer(&mut self, layer: u8) -> &mut [u16; 8] {
+        match layer {
+            0 => &mut self.keymaps,
+            n => &mut self.upper_layers[n as usize - 1],
+        }
+    }
+
+    fn unhandled(&mut self) -> [u8; 32] {
+        self.unhandled += 1;
+        let mut resp = [0u8; 32];
+        resp[0] = 0xFF;
+        resp
+    }
+}
+
+fn matrix_index(req: &[u8; 32]) -> usize {
+    req[2] as usize * 4 + req[3] as usize
+}
+
+/// A `Transport` backed by a shared `Firmware`, so a test can inspect it
+/// after handing the device to the app state.
+pub struct FakeLink(pub Arc<Mutex<Firmware>>);
+
+impl Transport for FakeLink {
+    fn write(&self, report: &[u8; 33]) -> Result<()> {
+        let req: [u8; 32] = report[1..].try_into().unwrap();
+        let mut fw = self.0.lock().unwrap();
+        fw.reports += 1;
+        if fw.hung {
+            return Ok(());
+        }
+        let resp = fw.handle(&req);
+        fw.responses.push_back(resp);
+        Ok(())
+    }
+
+    fn read_timeout(&self, buf: &mut [u8; 32], timeout_ms: i32) -> Result<usize> {
+        let mut fw = self.0.lock().unwrap();
+        fw.last_timeout_ms = timeout_ms;
+        match fw.responses.pop_front() {
+            Some(resp) => {
+                *buf = resp;
+                Ok(32)
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+/// A device talking to `fw`.
+pub fn device(fw: &Arc<Mutex<Firmware>>) -> Deck8Device {
+    Deck8Device::with_transport(Box::new(FakeLink(fw.clone())))
+}
+
+/// A `SoundOutput` that records what it was asked to do.
+#[derive(Default)]
+pub struct FakeSound {
+    pub played: Arc<Mutex<Vec<(PathBuf, SoundRoute)>>>,
+    pub preloaded: Arc<Mutex<Vec<PathBuf>>>,
+    /// What `play_held` is still holding, by key.
+    pub held: Arc<Mutex<[Option<PathBuf>; 8]>>,
+    /// The playlist playing.
+    pub playlist: Arc<Mutex<Option<String>>>,
+    /// The trim preview playing.
+    pub preview: Arc<Mutex<Option<(PathBuf, Trim)>>>,
+}
+
+impl SoundOutput for FakeSound {
+    fn play_sound(&self, path: &Path, _gain: f32, route: SoundRoute) -> Result<()> {
+        self.played.lock().unwrap().push((path.to_path_buf(), route));
+        Ok(())
+    }
+
+    fn format(&self) -> (u16, u32) {
+        (2, 48_000)
+    }
+
+    fn play_trimmed(&self, path: &Path, trim: &Trim, _samples: Vec<f32>, route: SoundRoute) {
+        *self.preview.lock().unwrap() = Some((path.to_path_buf(), *trim));
+        let _ = self.play_sound(path, 1.0, route);
+    }
+
+    fn stop_preview(&self) {
+        *self.preview.lock().unwrap() = None;
+    }
+
+    fn play_held(&self, key_index: usize, path: &Path, gain: f32, route: SoundRoute) -> Result<()> {
+        self.held.lock().unwrap()[key_index] = Some(path.to_path_buf());
+        self.play_sound(path, gain, route)
+    }
+
+    fn stop_held(&self, key_index: usize) {
+        self.held.lock().unwrap()[key_index] = None;
+    }
+
+    fn stop_all(&self) {
+        *self.held.lock().unwrap() = Default::default();
+    }
+
+    fn play_playlist(&self, id: &str, sounds: &[(PathBuf, SoundRoute)], _loop_last: bool, _gain: f32) -> Result<()> {
+        *self.playlist.lock().unwrap() = Some(id.to_string());
+        self.played.lock().unwrap().extend_from_slice(sounds);
+        Ok(())
+    }
+
+    fn skip_playlist(&self) {}
+
+    fn stop_playlist(&self) {
+        *self.playlist.lock().unwrap() = None;
+    }
+
+    fn playing_playlist(&self) -> Option<String> {
+        self.playlist.lock().unwrap().clone()
+    }
+
+    fn is_playing(&self) -> bool {
+        self.playlist.lock().unwrap().is_some() || self.held.lock().unwrap().iter().any(Option::is_some)
+    }
+
+    fn set_stop_word(&self, _enabled: bool) {}
+
+    fn preload(&self, paths: &[PathBuf]) {
+        *self.preloaded.lock().unwrap() = paths.to_vec();
+    }
+
+    fn set_mic_volume(&self, _vol: f32) {}
+
+    fn set_second_input(&self, _device: Option<&str>) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_second_input_volume(&self, _vol: f32) {}
+
+    fn set_input_gain(&self, _gain_db: f32) {}
+
+    fn set_sound_volume(&self, _vol: f32) {}
+
+    fn set_processing(&self, _stages: &[ProcessingStage]) {}
+
+    fn set_agc(&self, _agc: &AgcSettings) {}
+}
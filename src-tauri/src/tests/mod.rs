@@ -0,0 +1,2146 @@
+// Tests for the connect, toggle, shortcut and sound flows, run against the
+// in-memory firmware and sound output in `fakes`.
+
+mod fakes;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::HubError;
+use crate::hid::{Health, DEADLINE};
+use crate::protocol::{Capabilities, HsvColor, RgbMatrixState};
+use crate::meeting::Meeting;
+use crate::selftest::CheckStatus;
+use crate::state::{
+    AppState, ColorSlot, Condition, KeyGroup, ManagedAudioPipeline, Mixer, ProcessingStage, QuietHours, SlotBinding, SoundEntry, StageKind,
+};
+use fakes::{FakeSound, Firmware};
+
+const GREEN: HsvColor = HsvColor { h: 0x55, s: 0xFF, v: 0x78 };
+const RED: HsvColor = HsvColor { h: 0x00, s: 0xFF, v: 0x78 };
+/// Ctrl+Alt+M
+const CTRL_ALT_M: u16 = 0x0510;
+
+fn firmware() -> Arc<Mutex<Firmware>> {
+    Arc::new(Mutex::new(Firmware::default()))
+}
+
+/// App state with a fake device already synced.
+fn connected(fw: &Arc<Mutex<Firmware>>) -> AppState {
+    let mut st = AppState::default();
+    crate::sync_device(&mut st, fakes::device(fw));
+    st
+}
+
+#[test]
+fn connect_reads_device_and_pushes_keys() {
+    let fw = firmware();
+    fw.lock().unwrap().keymaps[2] = CTRL_ALT_M;
+    let mut st = AppState::default();
+    st.keys[0].override_enabled = true;
+
+    crate::sync_device(&mut st, fakes::device(&fw));
+
+    let fw = fw.lock().unwrap();
+    assert!(st.device.is_some());
+    assert_eq!(st.keymaps[2], CTRL_ALT_M);
+    assert_eq!(st.device_info.as_ref().unwrap().firmware_version, fw.firmware_version);
+    assert_eq!(st.rgb_matrix, Some(fw.rgb));
+    assert!(fw.overrides[0]);
+    assert_eq!(fw.colors[0], GREEN);
+    assert!(!fw.overrides[1]);
+    assert_eq!(fw.custom_saves, 1);
+    assert_eq!(fw.unhandled, 0);
+}
+
+#[test]
+fn connect_flags_reset_keymaps() {
+    let fw = firmware();
+    let mut st = AppState::default();
+    st.keymaps[0] = CTRL_ALT_M;
+
+    crate::sync_device(&mut st, fakes::device(&fw));
+
+    assert_eq!(st.keymaps, [0; 8]);
+    assert_eq!(st.keymap_restore.unwrap()[0], CTRL_ALT_M);
+}
+
+#[test]
+fn legacy_firmware_gets_assumed_capabilities() {
+    let fw = firmware();
+    fw.lock().unwrap().features = None;
+
+    let st = connected(&fw);
+
+    assert_eq!(st.device_info.unwrap().capabilities, Capabilities::LEGACY);
+    assert_eq!(fw.lock().unwrap().custom_saves, 1);
+}
+
+#[test]
+fn missing_per_key_feature_is_reported_not_written() {
+    let fw = firmware();
+    // RGB Matrix only
+    fw.lock().unwrap().features = Some(0b10);
+    let mut st = AppState::default();
+    st.keys[0].override_enabled = true;
+
+    crate::sync_device(&mut st, fakes::device(&fw));
+
+    let caps = st.device_info.as_ref().unwrap().capabilities;
+    assert!(caps.reported && !caps.per_key_override && caps.rgb_matrix);
+    assert!(st.rgb_matrix.is_some());
+    assert!(!fw.lock().unwrap().overrides[0]);
+    assert_eq!(fw.lock().unwrap().custom_saves, 0);
+    let err = st.device.as_ref().unwrap().set_key_color(0, &GREEN).unwrap_err();
+    assert_eq!(HubError::from(err).code(), "UNSUPPORTED");
+}
+
+#[test]
+fn connect_self_test_catches_ignored_writes() {
+    let fw = firmware();
+    let st = connected(&fw);
+    let report = st.sync_report.unwrap();
+    assert!(report.checks.iter().all(|c| c.status == CheckStatus::Pass), "{report:?}");
+    // Scratch values were put back
+    assert_eq!(fw.lock().unwrap().upper_layers[2][0], 0);
+    assert_eq!(fw.lock().unwrap().rgb.speed, 128);
+
+    let fw = firmware();
+    fw.lock().unwrap().drops_keymap_writes = true;
+    let report = connected(&fw).sync_report.unwrap();
+    assert!(report.failed());
+    let keycode = report.checks.iter().find(|c| c.name == "Keycode write").unwrap();
+    assert_eq!(keycode.status, CheckStatus::Fail);
+
+    // Once they pass, a reconnect on the same firmware doesn't write again
+    let fw = firmware();
+    let mut st = connected(&fw);
+    assert_eq!(st.echo_checked_firmware, Some(fw.lock().unwrap().firmware_version));
+    fw.lock().unwrap().drops_keymap_writes = true;
+    crate::sync_device(&mut st, fakes::device(&fw));
+    let report = st.sync_report.unwrap();
+    assert!(!report.failed(), "{report:?}");
+}
+
+#[test]
+fn keymap_writes_are_verified_and_rolled_back() {
+    let fw = firmware();
+    let mut st = connected(&fw);
+    fw.lock().unwrap().keymaps = [0x04, 0x05, 0x06, 0x07, 0, 0, 0, 0];
+    st.keymaps = fw.lock().unwrap().keymaps;
+
+    crate::write_keymaps(&mut st, &[(0, 0x04, 0x1E)]).unwrap();
+    assert_eq!(fw.lock().unwrap().keymaps[0], 0x1E);
+
+    // Key 3 doesn't take its write: keys 1 and 2 go back too
+    fw.lock().unwrap().stuck_key = Some(2);
+    let err = crate::write_keymaps(&mut st, &[(1, 0x05, 0x1F), (2, 0x06, 0x20), (3, 0x07, 0x21)]).unwrap_err();
+    assert_eq!(err.code(), "KEYMAP_WRITE");
+    assert!(matches!(err, HubError::KeymapWrite { key_index: 2, ref stranded, .. } if stranded.is_empty()));
+    assert_eq!(fw.lock().unwrap().keymaps, [0x1E, 0x05, 0x06, 0x07, 0, 0, 0, 0]);
+}
+
+#[test]
+fn transaction_rolls_back_before_later_stages_run() {
+    use crate::transaction::DeviceTransaction;
+
+    let fw = firmware();
+    let st = connected(&fw);
+    let dev = st.device.as_ref().unwrap();
+    let before = fw.lock().unwrap().rgb;
+    let after = RgbMatrixState { effect: 4, speed: 60, ..before };
+    let mut keys = st.keys.clone();
+    for key in &mut keys {
+        key.override_enabled = true;
+    }
+    let colors = fw.lock().unwrap().colors;
+
+    // RGB writes don't read back: the key stage never runs
+    fw.lock().unwrap().drops_rgb_writes = true;
+    let err = DeviceTransaction::new().rgb(before, after).keys(&st.keys, &keys).commit(dev).unwrap_err();
+    assert!(err.to_string().contains("nothing was changed"));
+    assert_eq!(fw.lock().unwrap().colors, colors);
+    assert!(!fw.lock().unwrap().overrides.iter().any(|&o| o));
+
+    fw.lock().unwrap().drops_rgb_writes = false;
+    DeviceTransaction::new().rgb(before, after).keys(&st.keys, &keys).commit(dev).unwrap();
+    assert_eq!(fw.lock().unwrap().rgb, after);
+    assert!(fw.lock().unwrap().overrides.iter().all(|&o| o));
+}
+
+#[test]
+fn heartbeat_spots_reboots_but_not_replugs() {
+    use crate::heartbeat::{DeviceHealth, HealthSample};
+
+    let sample = |at_ms, uptime: Option<u32>| HealthSample { at_ms, connected: uptime.is_some(), uptime, latency_ms: Some(2), error: None };
+    let mut health = DeviceHealth::default();
+    assert!(health.push(sample(0, Some(100))).is_none());
+    assert!(health.push(sample(1, Some(130))).is_none());
+    let reboot = health.push(sample(2, Some(4))).unwrap();
+    assert_eq!((reboot.previous_uptime, reboot.uptime), (130, 4));
+
+    // Unplugged in between
+    health.push(sample(3, None));
+    assert!(health.push(sample(4, Some(1))).is_none());
+    assert_eq!(health.samples.len(), 5);
+    assert_eq!(health.reboots.len(), 1);
+}
+
+#[test]
+fn watchdog_counts_back_to_back_timeouts() {
+    let ms = Duration::from_millis;
+    let t0 = Instant::now();
+    let health = Health::default();
+    health.begin(t0);
+    health.end(false, t0 + ms(500));
+    health.begin(t0 + ms(500));
+    assert!(!health.check_at(t0 + ms(1500)));
+    assert!(health.check_at(t0 + DEADLINE));
+
+    // A timeout long before doesn't count toward a later request
+    let health = Health::default();
+    health.begin(t0);
+    health.end(false, t0 + ms(500));
+    health.begin(t0 + ms(60_000));
+    assert!(!health.check_at(t0 + ms(61_000)));
+}
+
+#[test]
+fn hung_device_fails_fast_once_unhealthy() {
+    let fw = firmware();
+    let st = connected(&fw);
+    let dev = st.device.as_ref().unwrap();
+    fw.lock().unwrap().hung = true;
+
+    let err = dev.set_key_color(0, &GREEN).unwrap_err();
+    assert_eq!(HubError::from(err).code(), "DEVICE_TIMEOUT");
+    assert!(!dev.health().is_unhealthy());
+
+    let t0 = Instant::now();
+    dev.health().begin(t0);
+    assert!(dev.health().check_at(t0 + DEADLINE));
+    let sent = fw.lock().unwrap().reports;
+    let err = dev.set_key_color(0, &GREEN).unwrap_err();
+    assert_eq!(HubError::from(err).code(), "DEVICE_TIMEOUT");
+    assert_eq!(fw.lock().unwrap().reports, sent);
+}
+
+#[test]
+fn toggle_advances_slot_and_writes_color() {
+    let app = tauri::test::mock_app();
+    let fw = firmware();
+    let mut st = connected(&fw);
+    st.keys[3].override_enabled = true;
+
+    let old = crate::advance_key_slot(app.handle(), &mut st, 3);
+    assert_eq!(old.name, "A");
+    assert_eq!(st.keys[3].active().name, "B");
+    assert_eq!(fw.lock().unwrap().colors[3], RED);
+
+    crate::advance_key_slot(app.handle(), &mut st, 3);
+    assert_eq!(st.keys[3].active().name, "A");
+    assert_eq!(fw.lock().unwrap().colors[3], GREEN);
+}
+
+#[test]
+fn grouped_keys_act_like_radio_buttons() {
+    let app = tauri::test::mock_app();
+    let fw = firmware();
+    let mut st = connected(&fw);
+    for key in &mut st.keys {
+        key.override_enabled = true;
+    }
+    st.groups.push(KeyGroup { name: "Scenes".into(), keys: vec![0, 1, 2, 3] });
+
+    crate::advance_key_slot(app.handle(), &mut st, 0);
+    crate::advance_key_slot(app.handle(), &mut st, 5);
+    crate::advance_key_slot(app.handle(), &mut st, 1);
+    assert_eq!(st.keys[0].active().name, "A");
+    assert_eq!(fw.lock().unwrap().colors[0], GREEN);
+    assert_eq!(st.keys[1].active().name, "B");
+    // Not in the group
+    assert_eq!(st.keys[5].active().name, "B");
+
+    // Going back to the first slot leaves the others alone
+    crate::advance_key_slot(app.handle(), &mut st, 5);
+    crate::advance_key_slot(app.handle(), &mut st, 1);
+    assert!(st.keys.iter().all(|k| k.active_slot == 0));
+}
+
+#[test]
+fn shortcuts_map_keymaps_to_leds() {
+    let mut keymaps = [0u16; 8];
+    // Matrix index 4 is the bottom-left key, LED 7
+    keymaps[4] = CTRL_ALT_M;
+
+    let shortcuts = crate::plugin_shortcuts(&keymaps);
+
+    assert_eq!(shortcuts.len(), 1);
+    let (display, (led, keycode, shortcut)) = &shortcuts[0];
+    assert_eq!(display, "control+alt+KeyM");
+    assert_eq!((*led, *keycode), (7, CTRL_ALT_M));
+    assert_eq!(shortcut, "Ctrl+Alt+KeyM");
+}
+
+#[test]
+fn sound_assigns_and_clears_internal_keycode() {
+    let fw = firmware();
+    let mut st = connected(&fw);
+
+    crate::assign_key_sound(&mut st, 5, Some("snd".into()));
+    // LED 5 is matrix index 6
+    let internal = crate::internal_keycode_for_key(5);
+    assert_eq!(st.keymaps[6], internal);
+    assert_eq!(fw.lock().unwrap().keymaps[6], internal);
+
+    crate::assign_key_sound(&mut st, 5, None);
+    assert_eq!(st.keymaps[6], 0);
+    assert_eq!(fw.lock().unwrap().keymaps[6], 0);
+}
+
+#[test]
+fn sound_keeps_user_keycode() {
+    let fw = firmware();
+    fw.lock().unwrap().keymaps[1] = CTRL_ALT_M;
+    let mut st = connected(&fw);
+
+    crate::assign_key_sound(&mut st, 1, Some("snd".into()));
+    crate::assign_key_sound(&mut st, 1, None);
+
+    assert_eq!(st.keymaps[1], CTRL_ALT_M);
+    assert_eq!(fw.lock().unwrap().keymaps[1], CTRL_ALT_M);
+}
+
+#[test]
+fn key_sounds_are_preloaded() {
+    let path = crate::audio::sounds_dir().unwrap().join("preload-test.wav");
+    std::fs::write(&path, b"").unwrap();
+    let mut st = AppState::default();
+    st.audio_config.sound_library.push(SoundEntry {
+        id: "snd".into(),
+        filename: "preload-test.wav".into(),
+        display_name: "Test".into(),
+        missing: false,
+        monitor_only: false,
+    });
+    st.audio_config.key_sounds[2] = Some("snd".into());
+    let sound = FakeSound::default();
+    let preloaded = sound.preloaded.clone();
+
+    crate::preload_key_sounds(&Mutex::new(st), &ManagedAudioPipeline(Mutex::new(Some(Box::new(sound)))));
+
+    assert_eq!(*preloaded.lock().unwrap(), vec![path]);
+}
+
+#[test]
+fn quiet_hours_window_wraps_midnight() {
+    let night = QuietHours { enabled: true, start_min: 22 * 60, end_min: 8 * 60, max_volume: 0.0 };
+    assert!(crate::quiet::in_window(&night, 23 * 60));
+    assert!(crate::quiet::in_window(&night, 7 * 60 + 59));
+    assert!(!crate::quiet::in_window(&night, 8 * 60));
+    assert!(!crate::quiet::in_window(&night, 12 * 60));
+
+    let afternoon = QuietHours { start_min: 13 * 60, end_min: 14 * 60, ..night };
+    assert!(crate::quiet::in_window(&afternoon, 13 * 60 + 30));
+    assert!(!crate::quiet::in_window(&afternoon, 23 * 60));
+
+    let all_day = QuietHours { start_min: 9 * 60, end_min: 9 * 60, ..night };
+    assert!(crate::quiet::in_window(&all_day, 3 * 60));
+
+    let mut st = AppState::default();
+    st.settings.quiet_hours = QuietHours { start_min: 0, end_min: 0, max_volume: 0.5, ..night };
+    assert_eq!(crate::quiet::cap(&st, 1.5), 0.5);
+    assert_eq!(crate::quiet::cap(&st, 0.25), 0.25);
+}
+
+#[test]
+fn usage_csv_names_rows_and_quotes_fields() {
+    let mut st = AppState::default();
+    st.keys[0].label = Some("Mute, mic".into());
+    st.audio_config.sound_library.push(SoundEntry {
+        id: "snd".into(),
+        filename: "airhorn.wav".into(),
+        display_name: "Air \"horn\"".into(),
+        missing: false,
+        monitor_only: false,
+    });
+    let mut stats = crate::usage::UsageStats::default();
+    stats.keys[0].count = 3;
+    stats.sounds.entry("snd".into()).or_default().count = 2;
+
+    let csv = crate::usage::to_csv(&stats, &st);
+
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines[0], "kind,id,name,count,first_used_ms,last_used_ms");
+    assert_eq!(lines[1], "key,0,\"Mute, mic\",3,0,0");
+    assert_eq!(lines[9], "sound,snd,\"Air \"\"horn\"\"\",2,0,0");
+}
+
+#[test]
+fn meeting_conditions_name_their_app() {
+    let teams: Condition = serde_json::from_str(r#"{"kind":"TeamsMuted"}"#).unwrap();
+    assert_eq!(Meeting::of(&teams), Some(Meeting::Teams));
+    assert_eq!(Meeting::of(&Condition::ZoomMuted), Some(Meeting::Zoom));
+    assert_eq!(Meeting::of(&Condition::MicMuted), None);
+}
+
+#[test]
+fn now_playing_lines_parse() {
+    let now = crate::media::parse_line("Playing\tspotify\tSong\tArtist\tAlbum\n").unwrap();
+    assert!(now.playing);
+    assert_eq!((now.player.as_str(), now.title.as_str(), now.album.as_str()), ("spotify", "Song", "Album"));
+    assert!(!crate::media::parse_line("paused\tMusic\tSong\t\t").unwrap().playing);
+    assert_eq!(crate::media::parse_line("none"), None);
+    assert_eq!(crate::media::parse_line("Stopped\tvlc\t\t\t"), None);
+    assert_eq!(crate::media::parse_line(""), None);
+}
+
+#[test]
+fn mixer_binding_needs_a_valid_channel() {
+    let mut st = AppState::default();
+    let binding = |mixer, channel: &str| SlotBinding {
+        condition: Condition::MixerMuted { mixer, channel: channel.into() },
+        when_true: "B".into(),
+        when_false: "A".into(),
+        poll_ms: 1000,
+    };
+    let key = &mut st.keys[0];
+    assert!(key.set_binding(Some(binding(Mixer::VoiceMeeter, "Strip[0]"))).is_ok());
+    assert!(key.set_binding(Some(binding(Mixer::VoiceMeeter, "Bus[12]"))).is_ok());
+    assert!(key.set_binding(Some(binding(Mixer::VoiceMeeter, "Strip[]"))).is_err());
+    assert!(key.set_binding(Some(binding(Mixer::VoiceMeeter, "Mic"))).is_err());
+    assert!(key.set_binding(Some(binding(Mixer::WaveLink, "Mic"))).is_ok());
+    assert!(key.set_binding(Some(binding(Mixer::WaveLink, " "))).is_err());
+
+    let json = r#"{"kind":"MixerMuted","mixer":"VoiceMeeter","channel":"Strip[0]"}"#;
+    let condition: Condition = serde_json::from_str(json).unwrap();
+    assert_eq!(condition, Condition::MixerMuted { mixer: Mixer::VoiceMeeter, channel: "Strip[0]".into() });
+}
+
+#[test]
+fn virtual_sink_finds_its_modules_and_streams() {
+    let modules = "21\tmodule-null-sink\tsink_name=deck8_soundboard sink_properties=device.description=\"Deck8 Soundboard\"\n\
+                   22\tmodule-remap-source\tmaster=deck8_soundboard.monitor source_name=deck8_soundboard_mic\n\
+                   23\tmodule-null-sink\tsink_name=other\n";
+    assert_eq!(crate::virtual_sink::parse_modules(modules), vec![21, 22]);
+
+    let inputs = "Sink Input #40\n\tDriver: PipeWire\n\tProperties:\n\t\tapplication.process.id = \"7\"\n\
+                  Sink Input #41\n\tProperties:\n\t\tapplication.process.id = \"70\"\n";
+    assert_eq!(crate::virtual_sink::parse_sink_inputs(inputs, 7), vec![40]);
+}
+
+#[test]
+fn processing_stages_bypass_and_switch_live() {
+    let mut stages = crate::state::default_processing();
+    let control = Arc::new(crate::dsp::StageControl::new(&stages));
+    let mut graph = crate::dsp::Graph::new(control.clone(), 1, 48_000);
+    // Below the gate threshold
+    let hiss = 0.001;
+    assert_eq!(graph.process(hiss), hiss);
+
+    stages[0].enabled = true;
+    control.set(&stages);
+    let gated = (0..48_000).map(|_| graph.process(hiss)).last().unwrap();
+    assert!(gated.abs() < hiss / 100.0);
+
+    assert!(crate::dsp::is_complete(&stages));
+    stages.swap(0, 3);
+    assert!(crate::dsp::is_complete(&stages));
+    stages[1] = ProcessingStage { kind: StageKind::Gate, enabled: false };
+    assert!(!crate::dsp::is_complete(&stages));
+}
+
+#[test]
+fn sound_cooldown_blocks_retrigger_and_dims() {
+    let t0 = Instant::now();
+    // Key 7 isn't pressed by other tests
+    assert!(crate::cooldown::try_play(7, 2000, t0));
+    assert!(!crate::cooldown::try_play(7, 2000, t0 + Duration::from_millis(1999)));
+    assert!(crate::cooldown::try_play(7, 0, t0 + Duration::from_millis(2000)));
+    assert!(crate::cooldown::try_play(7, 0, t0 + Duration::from_millis(2001)));
+
+    let mut st = AppState::default();
+    st.keys[6].dim_on_cooldown = true;
+    assert!(crate::cooldown::try_play(6, 60_000, Instant::now()));
+    assert_eq!(crate::cooldown::shade(6, &st.keys[6], GREEN).v, GREEN.v / 4);
+    st.keys[6].dim_on_cooldown = false;
+    assert_eq!(crate::cooldown::shade(6, &st.keys[6], GREEN), GREEN);
+}
+
+#[test]
+fn released_sound_fades_then_skips_to_the_next() {
+    use crate::audio::{SoundStream, Span};
+    use ringbuf::traits::{Producer, Split};
+    use ringbuf::HeapRb;
+
+    let (mut sound_producer, sound_consumer) = HeapRb::<f32>::new(256).split();
+    let (mut cut_producer, cuts) = HeapRb::<Span>::new(4).split();
+    let mut sounds = SoundStream::new(sound_consumer, cuts, 4);
+    // A held sound, then a one-shot queued behind it
+    sound_producer.push_slice(&[1.0; 100]);
+    sound_producer.push_slice(&[0.5; 10]);
+
+    for _ in 0..5 {
+        assert_eq!(sounds.next_sample(), 1.0);
+    }
+    cut_producer.try_push(Span { start: 0, end: 100 }).unwrap();
+    let faded: Vec<f32> = (0..4).map(|_| sounds.next_sample()).collect();
+    assert_eq!(faded, [1.0, 0.75, 0.5, 0.25]);
+    assert_eq!(sounds.next_sample(), 0.5);
+    for _ in 0..9 {
+        sounds.next_sample();
+    }
+    assert_eq!(sounds.next_sample(), 0.0);
+}
+
+#[test]
+fn playlist_plays_gapless_loops_the_last_sound_and_skips_out() {
+    use crate::playlist::{Command, PlaylistItem, PlaylistVoice};
+    use ringbuf::traits::{Producer, Split};
+    use ringbuf::HeapRb;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let (mut commands, consumer) = HeapRb::<Command>::new(4).split();
+    let playing = Arc::new(AtomicBool::new(false));
+    let mut voice = PlaylistVoice::new(consumer, 1, 2, playing.clone());
+    let item = |value: f32, len: usize, to_mic: bool| PlaylistItem { samples: Arc::new(vec![value; len]), to_mic };
+    // An intro, a monitor-only sting, then a looping bed
+    let items = vec![item(1.0, 3, true), item(0.5, 2, false), item(0.25, 2, true)];
+    commands.try_push(Command::Start { items, loop_last: true, gain: 1.0 }).ok().unwrap();
+
+    let heard: Vec<f32> = (0..9).map(|_| voice.next_sample()).collect();
+    assert_eq!(heard, [1.0, 1.0, 1.0, 0.0, 0.0, 0.25, 0.25, 0.25, 0.25]);
+    assert!(playing.load(Ordering::Relaxed));
+
+    // Skipping past the last sound fades it out and ends the playlist
+    commands.try_push(Command::Skip).ok().unwrap();
+    let faded: Vec<f32> = (0..3).map(|_| voice.next_sample()).collect();
+    assert_eq!(faded, [0.25, 0.125, 0.0]);
+    assert!(!playing.load(Ordering::Relaxed));
+
+    // Starting another fades out the sound playing first
+    commands.try_push(Command::Start { items: vec![item(1.0, 4, true)], loop_last: false, gain: 1.0 }).ok().unwrap();
+    assert_eq!((voice.next_sample(), voice.next_sample()), (1.0, 1.0));
+    commands.try_push(Command::Start { items: vec![item(0.5, 4, true)], loop_last: false, gain: 1.0 }).ok().unwrap();
+    let replaced: Vec<f32> = (0..3).map(|_| voice.next_sample()).collect();
+    assert_eq!(replaced, [1.0, 0.5, 0.5]);
+    assert!(playing.load(Ordering::Relaxed));
+}
+
+#[test]
+fn second_input_converts_to_the_pipeline_format_across_buffers() {
+    use crate::convert::Converter;
+
+    let convert = |converter: &mut Converter, data: &[f32]| {
+        let mut out = Vec::new();
+        converter.push(data, |s| out.push(s));
+        out
+    };
+    // Stereo to mono averages (after one silent frame of lead-in)
+    let mut down = Converter::new(2, 48_000, 1, 48_000);
+    assert_eq!(convert(&mut down, &[1.0, 0.0, 0.5, 0.5]), [0.0, 0.5, 0.5]);
+    // Mono 24 kHz to stereo 48 kHz interpolates
+    let mut up = Converter::new(1, 24_000, 2, 48_000);
+    let out = convert(&mut up, &[1.0, 1.0]);
+    assert_eq!(out, [0.0, 0.0, 0.5, 0.5, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0]);
+    assert!(out.len() <= up.max_output(2));
+
+    // Splitting the input between callbacks changes nothing
+    let ramp: Vec<f32> = (0..90).map(|i| i as f32).collect();
+    let whole = convert(&mut Converter::new(1, 48_000, 1, 32_000), &ramp);
+    let mut split = Converter::new(1, 48_000, 1, 32_000);
+    let mut parts = convert(&mut split, &ramp[..37]);
+    parts.extend(convert(&mut split, &ramp[37..]));
+    assert_eq!(whole, parts);
+    assert_eq!(whole.len(), 61);
+}
+
+#[test]
+fn inputs_open_with_the_best_config_and_integer_samples_convert() {
+    use crate::audio::pick_input_config;
+    use rodio::cpal::{SampleFormat, SampleRate, SupportedBufferSize, SupportedStreamConfigRange};
+
+    let range = |channels, min, max, format| {
+        SupportedStreamConfigRange::new(channels, SampleRate(min), SampleRate(max), SupportedBufferSize::Unknown, format)
+    };
+    let summary = |config: rodio::cpal::SupportedStreamConfig| (config.channels(), config.sample_rate().0, config.sample_format());
+
+    // A usable default wins; an unusable one still lends its rate
+    let default = range(2, 44_100, 44_100, SampleFormat::I16).with_max_sample_rate();
+    assert_eq!(pick_input_config(Some(default), vec![]).map(summary), Some((2, 44_100, SampleFormat::I16)));
+    let odd = range(2, 96_000, 96_000, SampleFormat::F64).with_max_sample_rate();
+    let supported = vec![
+        range(8, 8_000, 192_000, SampleFormat::F32),
+        range(2, 8_000, 192_000, SampleFormat::U16),
+        range(2, 8_000, 192_000, SampleFormat::I16),
+        range(2, 22_050, 22_050, SampleFormat::F32),
+        range(2, 96_000, 96_000, SampleFormat::F64),
+    ];
+    assert_eq!(pick_input_config(Some(odd), supported.clone()).map(summary), Some((2, 96_000, SampleFormat::I16)));
+    assert_eq!(pick_input_config(None, supported[1..3].to_vec()).map(summary), Some((2, 48_000, SampleFormat::I16)));
+    assert_eq!(pick_input_config(None, supported[4..].to_vec()), None);
+
+    let mut buf = Vec::new();
+    assert_eq!(crate::convert::to_f32(&[i16::MIN, 0, 16_384], &mut buf), [-1.0, 0.0, 0.5]);
+    assert_eq!(crate::convert::to_f32(&[0u16, 32_768, 49_152], &mut buf), [-1.0, 0.0, 0.5]);
+}
+
+#[test]
+fn agc_brings_speech_into_range_up_to_its_max_gain() {
+    use crate::dsp::{Graph, StageControl};
+    use crate::state::AgcSettings;
+
+    let mut stages = crate::state::default_processing();
+    stages.iter_mut().filter(|s| s.kind == StageKind::Agc).for_each(|s| s.enabled = true);
+    // RMS in dB of the last second of 6 s of 1 kHz tone
+    let level = |amp: f32, max_gain_db: f32| {
+        let control = Arc::new(StageControl::new(&stages));
+        control.set_agc(&AgcSettings { max_gain_db, ..AgcSettings::default() });
+        let mut graph = Graph::new(control, 1, 48_000);
+        let out: Vec<f32> = (0..6 * 48_000)
+            .map(|i| graph.process(amp * (std::f32::consts::TAU * 1000.0 * i as f32 / 48_000.0).sin()))
+            .collect();
+        let tail = &out[5 * 48_000..];
+        10.0 * (tail.iter().map(|x| x * x).sum::<f32>() / tail.len() as f32).log10()
+    };
+    let in_range = |db: f32| (-24.5..=-15.5).contains(&db);
+
+    assert!(in_range(level(0.01, 30.0)), "quiet speech boosted");
+    assert!(in_range(level(0.5, 12.0)), "loud speech brought down");
+    // -43 dB needs 19 dB, only 12 allowed
+    assert!((level(0.01, 12.0) + 31.0).abs() < 1.0);
+    // Near silence isn't boosted
+    assert!((level(0.002, 30.0) + 57.0).abs() < 0.5);
+
+    // Chains saved before the stage existed get it, bypassed
+    let config: crate::state::AudioConfig =
+        serde_json::from_str(r#"{"processing": [{"kind": "Eq", "enabled": true}]}"#).unwrap();
+    assert!(crate::dsp::is_complete(&config.processing));
+    assert_eq!(config.processing[0], ProcessingStage { kind: StageKind::Eq, enabled: true });
+    assert!(config.processing.iter().all(|s| s.kind == StageKind::Eq || !s.enabled));
+}
+
+#[test]
+fn input_gain_clipping_counts_hot_and_boosted_samples() {
+    use crate::audio::measure_input;
+
+    // Boosted past full scale
+    let (peak, clipped) = measure_input(&[0.5, -0.9, 0.2], 1.5);
+    assert!((peak - 1.35).abs() < 1e-6);
+    assert_eq!(clipped, 1);
+    // Clipped by the device; turning the gain down doesn't undo it
+    let (peak, clipped) = measure_input(&[1.0, -0.4], 0.5);
+    assert_eq!((peak, clipped), (0.5, 1));
+    assert_eq!(measure_input(&[0.3, -0.3], 1.0), (0.3, 0));
+}
+
+#[test]
+fn key_releases_are_handled_after_their_press() {
+    let (done_tx, done_rx) = std::sync::mpsc::channel();
+    let tx = crate::keyboard_hook::spawn_key_worker(move |_: (), led_idx, pressed| {
+        // A slow press must still finish before its release starts
+        if pressed {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        done_tx.send((led_idx, pressed)).unwrap();
+    });
+    for led_idx in [3, 5] {
+        tx.send(((), led_idx, true)).unwrap();
+        tx.send(((), led_idx, false)).unwrap();
+    }
+
+    let events: Vec<_> = (0..4).map(|_| done_rx.recv_timeout(Duration::from_secs(1)).unwrap()).collect();
+    assert_eq!(events, [(3, true), (3, false), (5, true), (5, false)]);
+}
+
+#[test]
+fn injected_chord_is_ignored_once_when_it_comes_back() {
+    use crate::replay::{is_echo, note_injected};
+
+    // Ctrl+Shift+F13, unused by other tests
+    let t0 = Instant::now();
+    note_injected(0x0368, t0);
+    assert!(!is_echo(0x0369, t0));
+    // Right Ctrl+Shift is the same chord
+    assert!(is_echo(0x3068, t0 + Duration::from_millis(20)));
+    assert!(!is_echo(0x0368, t0 + Duration::from_millis(30)));
+
+    note_injected(0x0368, t0);
+    assert!(!is_echo(0x0368, t0 + Duration::from_millis(100)));
+}
+
+#[test]
+fn device_definition_overrides_stock_fields() {
+    use crate::definition::{parse, DeviceDefinition};
+
+    let def = parse(r#"{ "rows": 1, "cols": 8, "led_order": [0, 1, 2, 3, 4, 5, 6, 7], "cmd_set_color": 9 }"#).unwrap();
+    assert_eq!(def.matrix_position(6), (0, 6));
+    assert_eq!(def.keymap_index(5), 5);
+    assert_eq!(def.cmd_set_color, 9);
+    assert_eq!(def.cmd_set_brightness, DeviceDefinition::DECK8.cmd_set_brightness);
+    assert_eq!(DeviceDefinition::DECK8.keymap_index(4), 7);
+
+    assert!(parse(r#"{ "rows": 1, "cols": 4 }"#).is_err());
+    assert!(parse(r#"{ "led_order": [0, 1, 2, 3, 4, 5, 6, 6] }"#).is_err());
+    assert!(parse(r#"{ "colz": 4 }"#).is_err());
+}
+
+#[test]
+fn progress_bar_fills_keys_in_matrix_order() {
+    use crate::progress::leds;
+
+    let half = leds(50.0, GREEN);
+    assert_eq!(&half[..4], &[GREEN; 4]);
+    assert!(half[4..].iter().all(|c| c.v == 0 && c.h == GREEN.h));
+
+    // Four and a half keys; K5 is LED 7 on the snake-wired stock pad
+    let more = leds(56.25, GREEN);
+    assert_eq!(more[7].v, GREEN.v / 2);
+    assert_eq!(more[4].v, 0);
+    assert_eq!(leds(100.0, GREEN), [GREEN; 8]);
+    assert!(leds(0.0, GREEN).iter().all(|c| c.v == 0));
+}
+
+#[test]
+fn theme_preview_needs_a_device_and_a_sane_duration() {
+    // Only the rejections: a preview that went up would hold off the key
+    // writes other tests check
+    let app = tauri::test::mock_app();
+    let fw = firmware();
+    let st = connected(&fw);
+    let sent = fw.lock().unwrap().reports;
+    let colors = [RED; 8];
+
+    let err = crate::preview::show(app.handle(), &AppState::default(), "Night", colors, 5).unwrap_err();
+    assert_eq!(err.code(), "NOT_CONNECTED");
+    assert!(crate::preview::show(app.handle(), &st, "Night", colors, 0).is_err());
+    assert!(crate::preview::show(app.handle(), &st, "Night", colors, crate::preview::MAX_SECS + 1).is_err());
+    assert!(!crate::preview::active());
+    assert!(!crate::preview::end(app.handle(), &st));
+    assert_eq!(fw.lock().unwrap().reports, sent);
+}
+
+#[test]
+fn notifications_stack_by_priority_and_expire() {
+    use crate::notify::{Pattern, Stack};
+
+    let red = HsvColor { h: 0, s: 255, v: 200 };
+    let t0 = Instant::now();
+    let secs = Duration::from_secs;
+    let mut stack = Stack::new();
+    stack.push(7, GREEN, Pattern::Solid, 1, secs(60), t0);
+    let alert = stack.push(7, red, Pattern::Solid, 5, secs(10), t0);
+    // Same priority as the green one: the newer wins
+    stack.push(7, HsvColor { h: 170, ..GREEN }, Pattern::Solid, 1, secs(60), t0);
+
+    assert_eq!(stack.color(7, t0), Some(red));
+    assert_eq!(stack.color(6, t0), None);
+    let later = t0 + secs(11);
+    assert_eq!(stack.color(7, later), Some(HsvColor { h: 170, ..GREEN }));
+    assert!(stack.dismiss(alert));
+    assert!(!stack.dismiss(alert));
+
+    stack.expire(t0 + secs(61));
+    assert!(stack.is_empty());
+    assert_eq!(Pattern::Blink.frame(red, Duration::from_millis(600)).v, 0);
+    assert_eq!(Pattern::Blink.frame(red, Duration::from_millis(1100)), red);
+}
+
+#[test]
+fn stop_word_needs_three_in_a_row() {
+    use crate::stopword::Spotter;
+
+    const RATE: u32 = 48_000;
+    let ms = |n: u32| (RATE / 1000 * n) as usize;
+    // "stop": a hiss burst, the /t/ gap, a low vowel, then a pause
+    let mut seed = 1u32;
+    let mut stop = Vec::new();
+    stop.extend((0..ms(150)).map(|_| {
+        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (seed >> 8) as f32 / (1 << 24) as f32 * 0.6 - 0.3
+    }));
+    stop.extend(std::iter::repeat_n(0.0, ms(30)));
+    stop.extend((0..ms(200)).map(|i| (std::f32::consts::TAU * 300.0 * i as f32 / RATE as f32).sin() * 0.3));
+    stop.extend(std::iter::repeat_n(0.0, ms(250)));
+
+    let heard = |times: usize| {
+        let mut spotter = Spotter::new(RATE);
+        (0..times).flat_map(|_| stop.iter()).filter(|&&x| spotter.feed(x)).count()
+    };
+    assert_eq!(heard(1), 0);
+    assert_eq!(heard(2), 0);
+    assert_eq!(heard(3), 1);
+}
+
+#[test]
+fn flac_sounds_decode_to_the_same_samples() {
+    use rodio::Source;
+
+    // Stereo, with a silent stretch and a short last block
+    let samples: Vec<i16> = (0..2 * (3 * 4096 + 5))
+        .map(|i| if (8192..16384).contains(&i) { 0 } else { ((i as f32 * 0.03).sin() * 12_000.0) as i16 ^ (i % 7) as i16 })
+        .collect();
+    let bytes = crate::flac::encode(2, 44_100, &samples);
+    assert!(bytes.len() < samples.len() * 2);
+    let decoded = rodio::Decoder::new(std::io::Cursor::new(bytes)).unwrap();
+    assert_eq!((decoded.channels(), decoded.sample_rate()), (2, 44_100));
+    assert_eq!(decoded.collect::<Vec<i16>>(), samples);
+}
+
+#[test]
+fn library_stats_find_unused_missing_and_orphaned_sounds() {
+    let dir = std::env::temp_dir().join(format!("deck8-library-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, len) in [("a.wav", 10), ("b.flac", 20), ("stray.wav", 5), ("importing.wav", 7), ("notes.txt", 3)] {
+        std::fs::write(dir.join(name), vec![0u8; len]).unwrap();
+    }
+    // Only audio left there past an import counts as orphaned
+    let long_ago = std::time::SystemTime::now() - crate::library_watch::SETTLED_AFTER * 2;
+    for name in ["stray.wav", "notes.txt"] {
+        std::fs::File::options().write(true).open(dir.join(name)).unwrap().set_modified(long_ago).unwrap();
+    }
+    let entry = |id: &str, filename: &str| SoundEntry { id: id.into(), filename: filename.into(), display_name: id.into(), missing: false, monitor_only: false };
+    let library = [entry("a", "a.wav"), entry("b", "b.flac"), entry("gone", "gone.wav")];
+    let stats = crate::audio::library_stats(&dir, &library, &["a"]).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(stats.library_bytes, 30);
+    assert_eq!((stats.unused, stats.unused_bytes), (vec!["b".to_string()], 20));
+    assert_eq!(stats.missing, ["gone"]);
+    assert_eq!((stats.orphans, stats.orphan_bytes), (vec!["stray.wav".to_string()], 5));
+}
+
+#[test]
+fn sounds_folder_changes_reach_the_library() {
+    use crate::library_watch::Reconciler;
+
+    let entry = |id: &str, filename: &str| SoundEntry { id: id.into(), filename: filename.into(), display_name: id.into(), missing: false, monitor_only: false };
+    let mut library = vec![entry("a", "a.wav"), entry("b", "b.flac")];
+    let mut reconciler = Reconciler::default();
+    let file = |name: &str, size: u64| (name.to_string(), size);
+
+    // b.flac deleted; a drop still copying and a stray text file
+    assert!(reconciler.reconcile(&mut library, &[file("a.wav", 10), file("Air Horn.mp3", 100), file("notes.txt", 1)]));
+    assert!(library[1].missing);
+    assert_eq!(library.len(), 2);
+    // Still growing, then stable
+    assert!(!reconciler.reconcile(&mut library, &[file("a.wav", 10), file("Air Horn.mp3", 200), file("notes.txt", 1)]));
+    assert!(reconciler.reconcile(&mut library, &[file("a.wav", 10), file("Air Horn.mp3", 200), file("b.flac", 5)]));
+    assert!(!library[1].missing);
+    assert_eq!(library.len(), 3);
+    assert_eq!((library[2].filename.as_str(), library[2].display_name.as_str()), ("Air Horn.mp3", "Air Horn"));
+    assert!(!reconciler.reconcile(&mut library, &[file("a.wav", 10), file("Air Horn.mp3", 200), file("b.flac", 5)]));
+}
+
+#[test]
+fn relinked_sounds_keep_their_keys() {
+    use tauri::Manager;
+
+    let source = std::env::temp_dir().join(format!("deck8-relink-{}.mp3", std::process::id()));
+    std::fs::write(&source, b"new").unwrap();
+    let mut st = AppState::default();
+    st.audio_config.sound_library.push(SoundEntry {
+        id: "relink".into(),
+        filename: "relink-gone.wav".into(),
+        display_name: "Horn".into(),
+        missing: true,
+        monitor_only: false,
+    });
+    st.audio_config.key_sounds[1] = Some("relink".into());
+    let sound = FakeSound::default();
+    let preloaded = sound.preloaded.clone();
+    let app = tauri::test::mock_app();
+    app.manage(Mutex::new(st));
+    app.manage(ManagedAudioPipeline(Mutex::new(Some(Box::new(sound)))));
+
+    let entry = crate::relink_sound(app.state(), app.state(), "relink".into(), source.display().to_string()).unwrap();
+    std::fs::remove_file(&source).unwrap();
+    let path = crate::audio::sounds_dir().unwrap().join("relink.mp3");
+    assert_eq!(std::fs::read(&path).unwrap(), b"new");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!((entry.filename.as_str(), entry.missing), ("relink.mp3", false));
+    assert_eq!(*preloaded.lock().unwrap(), vec![path]);
+}
+
+#[test]
+fn key_sound_offsets_scale_only_their_key() {
+    use tauri::Manager;
+
+    let app = tauri::test::mock_app();
+    app.manage(Mutex::new(AppState::default()));
+    crate::set_key_sound_gain(app.state(), 2, -6.0).unwrap();
+    // Clamped to +12 dB
+    let snapshot = crate::set_key_sound_gain(app.state(), 5, 40.0).unwrap();
+    assert_eq!(snapshot.audio_config.key_sound_gain_db[5], 12.0);
+
+    let state = app.state::<crate::state::SharedState>();
+    let st = state.lock().unwrap();
+    assert!((crate::key_sound_gain(&st, 2) - 0.501).abs() < 0.001);
+    assert!((crate::key_sound_gain(&st, 5) - 3.981).abs() < 0.001);
+    assert_eq!(crate::key_sound_gain(&st, 0), 1.0);
+}
+
+#[test]
+fn soundboard_turns_off_and_stays_off() {
+    use tauri::Manager;
+
+    let app = tauri::test::mock_app();
+    let mut st = AppState::default();
+    st.audio_config.soundboard_enabled = true;
+    app.manage(Mutex::new(st));
+    app.manage(ManagedAudioPipeline(Mutex::new(Some(Box::new(FakeSound::default())))));
+
+    let snapshot = crate::set_soundboard_on(app.handle(), false).unwrap();
+    assert!(!crate::soundboard_running(app.handle()));
+    assert!(snapshot.audio_config.soundboard_off);
+    assert!(!snapshot.audio_config.soundboard_enabled);
+
+    // No devices configured, so it can't come back on, but the choice sticks
+    assert!(crate::set_soundboard_on(app.handle(), true).is_err());
+    let state = app.state::<crate::state::SharedState>();
+    assert!(!state.lock().unwrap().audio_config.soundboard_off);
+}
+
+#[test]
+fn muting_key_sounds_stops_them_and_keeps_them_silent() {
+    use tauri::Manager;
+
+    let app = tauri::test::mock_app();
+    app.manage(Mutex::new(AppState::default()));
+    let sound = FakeSound::default();
+    let (held, playlist) = (Arc::clone(&sound.held), Arc::clone(&sound.playlist));
+    held.lock().unwrap()[3] = Some("airhorn.wav".into());
+    *playlist.lock().unwrap() = Some("intro".into());
+    app.manage(ManagedAudioPipeline(Mutex::new(Some(Box::new(sound)))));
+
+    let snapshot = crate::set_sounds_muted_on(app.handle(), true);
+    assert!(snapshot.audio_config.sounds_muted);
+    assert_eq!(held.lock().unwrap()[3], None);
+    assert_eq!(*playlist.lock().unwrap(), None);
+    let state = app.state::<crate::state::SharedState>();
+    assert_eq!(crate::key_sound_gain(&state.lock().unwrap(), 0), 0.0);
+
+    crate::set_sounds_muted_on(app.handle(), false);
+    assert_eq!(crate::key_sound_gain(&state.lock().unwrap(), 0), 1.0);
+}
+
+#[test]
+fn previews_follow_the_preview_route() {
+    use crate::state::SoundRoute;
+    use tauri::Manager;
+
+    let app = tauri::test::mock_app();
+    let mut st = AppState::default();
+    assert_eq!(st.audio_config.preview_route, SoundRoute::Monitor);
+    st.audio_config.preview_route = SoundRoute::Cable;
+    let sound = FakeSound::default();
+    let played = sound.played.clone();
+    app.manage(Mutex::new(st));
+    app.manage(ManagedAudioPipeline(Mutex::new(Some(Box::new(sound)))));
+
+    let clip = std::env::temp_dir().join("deck8-preview-route.wav");
+    let spec = hound::WavSpec { channels: 1, sample_rate: 22_050, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut writer = hound::WavWriter::create(&clip, spec).unwrap();
+    (0..22_050).for_each(|i| writer.write_sample((i % 200) as i16 * 100).unwrap());
+    writer.finalize().unwrap();
+    crate::sound_preview::start(app.handle().clone());
+    crate::preview_trim(app.state(), clip.display().to_string(), crate::audio::Trim::range(0, 500)).unwrap();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while played.lock().unwrap().is_empty() && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(*played.lock().unwrap(), vec![(clip.clone(), SoundRoute::Cable)]);
+    std::fs::remove_file(clip).unwrap();
+    assert!(!SoundRoute::Cable.monitor() && SoundRoute::Both.monitor() && SoundRoute::Both.cable());
+}
+
+#[test]
+fn monitor_only_sounds_stay_out_of_the_mic() {
+    use crate::state::SoundRoute;
+    use tauri::Manager;
+
+    let app = tauri::test::mock_app();
+    let mut st = AppState::default();
+    st.audio_config.sound_library.push(SoundEntry {
+        id: "ding".into(),
+        filename: "ding.wav".into(),
+        display_name: "Ding".into(),
+        missing: false,
+        monitor_only: false,
+    });
+    app.manage(Mutex::new(st));
+    let state = app.state::<crate::state::SharedState>();
+    assert_eq!(state.lock().unwrap().audio_config.sound_library[0].route(SoundRoute::Both), SoundRoute::Both);
+
+    crate::set_sound_monitor_only(app.state(), "ding".into(), true).unwrap();
+    let entry = state.lock().unwrap().audio_config.sound_library[0].clone();
+    assert_eq!(entry.route(SoundRoute::Both), SoundRoute::Monitor);
+    assert_eq!(entry.route(SoundRoute::Cable), SoundRoute::Monitor);
+    assert!(crate::set_sound_monitor_only(app.state(), "gone".into(), true).is_err());
+}
+
+#[test]
+fn renamed_audio_devices_are_found_again_or_asked_about() {
+    use crate::audio::{AudioDeviceInfo, AudioDeviceList};
+    use crate::device_match::{device_key, find, DeviceMatch};
+    use crate::state::DeviceRole;
+
+    let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    assert_eq!(device_key("Microphone (2- USB Audio)"), "microphone usb audio");
+    assert_eq!(device_key("Line 2 (Virtual Audio-Cable)"), "line 2 virtual audio cable");
+    let available = names(&["Microphone (USB Audio)", "Microphone (Realtek Audio)"]);
+    assert_eq!(find("Microphone (USB Audio)", &available), DeviceMatch::Exact);
+    assert_eq!(find("Microphone (2- USB Audio)", &available), DeviceMatch::Renamed("Microphone (USB Audio)".into()));
+    // A dropped word is only offered; another device with the same kind of name isn't
+    assert_eq!(find("Microphone (USB Audio Device)", &available), DeviceMatch::Ambiguous(names(&["Microphone (USB Audio)"])));
+    assert_eq!(find("Microphone (Blue Yeti)", &available), DeviceMatch::Missing);
+    let headsets = names(&["Jabra Evolve 75 Headset"]);
+    assert_eq!(find("Jabra Evolve 65 Headset", &headsets), DeviceMatch::Ambiguous(headsets.clone()));
+    // Cables are never switched on their own, even when only the numbering differs
+    let cables = names(&["CABLE-A Input (VB-Audio Cable A)", "CABLE Input (2- VB-Audio Virtual Cable)"]);
+    assert_eq!(
+        find("CABLE Input (VB-Audio Virtual Cable)", &cables),
+        DeviceMatch::Ambiguous(names(&["CABLE Input (2- VB-Audio Virtual Cable)"]))
+    );
+    let twins = names(&["Microphone (3- USB Audio)", "Microphone (4- USB Audio)"]);
+    assert_eq!(find("Microphone (2- USB Audio)", &twins), DeviceMatch::Ambiguous(twins.clone()));
+
+    let mut st = AppState::default();
+    st.audio_config.audio_input_device = Some("Microphone (2- USB Audio)".into());
+    st.audio_config.audio_output_device = Some("CABLE Input (VB-Audio Virtual Cable)".into());
+    st.audio_config.second_input_device = Some("Line In (Realtek Audio)".into());
+    let devices = AudioDeviceList {
+        input_devices: ["Microphone (3- USB Audio)", "Microphone (4- USB Audio)", "Line In (2- Realtek Audio)"]
+            .into_iter()
+            .map(|name| AudioDeviceInfo { name: name.into() })
+            .collect(),
+        output_devices: vec![AudioDeviceInfo { name: "CABLE Input (VB-Audio Virtual Cable)".into() }],
+    };
+    assert!(crate::rematch_saved_devices(&mut st, &devices));
+    assert_eq!(st.audio_config.second_input_device.as_deref(), Some("Line In (2- Realtek Audio)"));
+    assert_eq!(st.audio_config.audio_output_device.as_deref(), Some("CABLE Input (VB-Audio Virtual Cable)"));
+    // The mic has two look-alikes: it stays as saved and the user is asked
+    assert_eq!(st.audio_config.audio_input_device.as_deref(), Some("Microphone (2- USB Audio)"));
+    let choice = st.device_choice.clone().unwrap();
+    assert_eq!((choice.role, choice.candidates), (DeviceRole::Input, twins));
+    assert!(!crate::rematch_saved_devices(&mut st, &devices));
+}
+
+#[test]
+fn app_clients_retry_and_drop_presses_from_before_connecting() {
+    use crate::reconnect::{Client, Status};
+
+    #[derive(Default)]
+    struct Seen {
+        connected: bool,
+        errors: Vec<String>,
+        commands: Vec<u32>,
+    }
+    impl Status for Seen {
+        fn failed(&mut self, error: String) {
+            self.errors.push(error);
+        }
+    }
+    let (open, gate) = std::sync::mpsc::channel::<()>();
+    let mut attempts = 0;
+    let client = Client::<Seen, u32>::start(Duration::from_millis(10), move |session| {
+        attempts += 1;
+        if attempts == 1 {
+            anyhow::bail!("refused");
+        }
+        gate.recv()?;
+        session.connected();
+        session.status.lock().unwrap().connected = true;
+        while !session.stopped() {
+            session.status.lock().unwrap().commands.extend(session.commands());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        Ok(())
+    });
+    let wait = |done: &dyn Fn(&Seen) -> bool| {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done(&client.status.lock().unwrap()) {
+            assert!(Instant::now() < deadline);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    };
+    client.send(1);
+    open.send(()).unwrap();
+    wait(&|seen| seen.connected);
+    client.send(2);
+    wait(&|seen| !seen.commands.is_empty());
+    let seen = client.status.lock().unwrap();
+    assert_eq!((seen.errors.as_slice(), seen.commands.as_slice()), (&["refused".to_string()][..], &[2][..]));
+}
+
+#[test]
+fn obs_events_drive_scene_stream_and_record_bindings() {
+    use crate::obs::{apply, holds, instance, ObsStatus};
+    use serde_json::json;
+
+    let obs = |scene: Option<&str>| match scene {
+        Some(scene) => Condition::ObsScene { host: "localhost".into(), port: 4455, password: None, scene: scene.into() },
+        None => Condition::ObsStreaming { host: "localhost".into(), port: 4455, password: None },
+    };
+    // Bindings on the same OBS share one connection
+    assert_eq!(instance(&obs(Some("Intro"))), instance(&obs(None)));
+    assert_eq!(instance(&Condition::MicMuted), None);
+
+    let mut status = ObsStatus { connected: true, ..Default::default() };
+    apply(&mut status, &json!({ "op": 7, "d": {
+        "requestType": "GetCurrentProgramScene", "requestId": "1",
+        "requestStatus": { "result": true, "code": 100 },
+        "responseData": { "currentProgramSceneName": "Intro" } } }));
+    assert!(holds(&status, &obs(Some("Intro"))));
+    assert!(!holds(&status, &obs(None)));
+
+    apply(&mut status, &json!({ "op": 5, "d": {
+        "eventType": "CurrentProgramSceneChanged", "eventIntent": 4, "eventData": { "sceneName": "Game" } } }));
+    apply(&mut status, &json!({ "op": 5, "d": {
+        "eventType": "StreamStateChanged", "eventIntent": 64,
+        "eventData": { "outputActive": true, "outputState": "OBS_WEBSOCKET_OUTPUT_STARTED" } } }));
+    assert!(!holds(&status, &obs(Some("Intro"))));
+    assert!(holds(&status, &obs(Some("Game"))));
+    assert!(holds(&status, &obs(None)));
+    // A failed request leaves the state alone
+    apply(&mut status, &json!({ "op": 7, "d": {
+        "requestType": "GetRecordStatus", "requestId": "2",
+        "requestStatus": { "result": false, "code": 501, "comment": "Output not running" } } }));
+    assert!(!status.recording);
+}
+
+#[test]
+fn discord_frames_and_voice_events_drive_bindings_and_presence() {
+    use crate::discord::{activity, apply, encode_frame, holds, take_frame, DiscordStatus};
+    use crate::state::Theme;
+    use serde_json::json;
+
+    // Frames arrive in pieces; only whole ones come off the buffer
+    let ready = json!({ "cmd": "DISPATCH", "evt": "READY", "data": { "v": 1 } });
+    let mut wire = encode_frame(1, &ready);
+    wire.extend(encode_frame(3, &json!({})));
+    let mut buf = wire[..5].to_vec();
+    assert!(take_frame(&mut buf).unwrap().is_none());
+    buf.extend_from_slice(&wire[5..]);
+    assert_eq!(take_frame(&mut buf).unwrap(), Some((1, ready)));
+    assert_eq!(take_frame(&mut buf).unwrap(), Some((3, json!({}))));
+    assert!(buf.is_empty());
+
+    let mut status = DiscordStatus { voice: true, ..Default::default() };
+    apply(&mut status, &json!({ "cmd": "AUTHENTICATE", "data": { "user": { "id": "42" } } }));
+    apply(&mut status, &json!({ "cmd": "DISPATCH", "evt": "VOICE_SETTINGS_UPDATE", "data": { "mute": true, "deaf": false } }));
+    apply(&mut status, &json!({ "cmd": "DISPATCH", "evt": "VOICE_CHANNEL_SELECT", "data": { "channel_id": "7" } }));
+    // Someone else speaking doesn't light the key
+    apply(&mut status, &json!({ "cmd": "DISPATCH", "evt": "SPEAKING_START", "data": { "user_id": "99" } }));
+    assert!(holds(&status, &Condition::DiscordMuted));
+    assert!(!holds(&status, &Condition::DiscordSpeaking));
+    apply(&mut status, &json!({ "cmd": "DISPATCH", "evt": "SPEAKING_START", "data": { "user_id": "42" } }));
+    assert!(holds(&status, &Condition::DiscordSpeaking));
+    assert_eq!(status.channel.as_deref(), Some("7"));
+    // Leaving the channel ends speaking
+    apply(&mut status, &json!({ "cmd": "DISPATCH", "evt": "VOICE_CHANNEL_SELECT", "data": { "channel_id": null } }));
+    assert!(!holds(&status, &Condition::DiscordSpeaking));
+
+    // The presence shows the theme the keys are showing
+    let mut st = AppState::default();
+    assert_eq!(st.active_theme(), None);
+    st.themes.push(Theme { name: "Stream".into(), colors: std::array::from_fn(|i| st.keys[i].active().color) });
+    assert_eq!(st.active_theme(), Some("Stream"));
+    assert_eq!(activity(st.active_theme())["details"], "Profile: Stream");
+    st.keys[3].slots[st.keys[3].active_slot].color.h ^= 0x40;
+    assert_eq!(st.active_theme(), None);
+    assert_eq!(activity(None)["details"], "Custom colors");
+}
+
+#[test]
+fn lights_mirror_the_chosen_color_in_each_api_scale() {
+    use crate::lighting::{hsv_to_rgb, hue_state, mirrored_color, wled_state};
+    use crate::state::{LightSource, LightTarget};
+    use serde_json::json;
+
+    assert_eq!(hsv_to_rgb(HsvColor { h: 0, s: 255, v: 255 }), (255, 0, 0));
+    assert_eq!(hsv_to_rgb(HsvColor { h: 85, s: 255, v: 255 }).1, 255);
+    assert_eq!(hsv_to_rgb(HsvColor { h: 170, s: 0, v: 90 }), (90, 90, 90));
+
+    let red = HsvColor { h: 0, s: 255, v: 120 };
+    assert_eq!(hue_state(red), json!({ "on": true, "hue": 0, "sat": 254, "bri": 120 }));
+    assert_eq!(wled_state(red), json!({ "on": true, "bri": 120, "seg": [{ "col": [[255, 0, 0]] }] }));
+    assert_eq!(hue_state(HsvColor { v: 0, ..red }), json!({ "on": false }));
+
+    let mut st = AppState::default();
+    st.settings.lighting.mirror = Some(LightSource::Key { key_index: 2 });
+    // Nothing to mirror to yet
+    assert_eq!(mirrored_color(&st), None);
+    st.settings.lighting.targets.push(LightTarget::Wled { name: "Desk".into(), host: "wled.local".into() });
+    let active = st.keys[2].active_slot;
+    st.keys[2].slots[active].color = red;
+    assert_eq!(mirrored_color(&st), Some(red));
+    st.settings.lighting.mirror = Some(LightSource::Matrix);
+    assert_eq!(mirrored_color(&st), None);
+}
+
+#[test]
+fn accent_recolors_only_keys_left_on_the_default_color() {
+    use crate::appearance::{accent_key_color, named_accent, rgb_to_hsv, stock_color, sync_accent};
+
+    assert_eq!(rgb_to_hsv(255, 0, 0), HsvColor { h: 0, s: 255, v: 255 });
+    assert_eq!(rgb_to_hsv(0, 0, 255).h, 171);
+    assert_eq!(rgb_to_hsv(60, 60, 60), HsvColor { h: 0, s: 0, v: 60 });
+    assert_eq!(named_accent("nope"), None);
+
+    let blue = named_accent("blue").unwrap();
+    let orange = named_accent("orange").unwrap();
+    let custom = HsvColor { h: 0x20, s: 0x80, v: 0x40 };
+    let mut st = AppState::default();
+    st.keys[1].slots[0].color = custom;
+
+    // Off: the accent is ignored
+    assert!(!sync_accent(&mut st, Some(blue)));
+    st.settings.accent_key_colors = true;
+    assert!(sync_accent(&mut st, Some(blue)));
+    assert_eq!(st.keys[0].slots[0].color, accent_key_color(blue));
+    assert_eq!(st.keys[0].slots[0].color.v, stock_color().v);
+    assert_eq!(st.keys[1].slots[0].color, custom);
+    // Second slots keep their colors
+    assert_eq!(st.keys[0].slots[1].color, AppState::default().keys[0].slots[1].color);
+    assert!(!sync_accent(&mut st, Some(blue)));
+
+    // The keys follow a new accent, unless edited since
+    st.keys[2].slots[0].color = custom;
+    assert!(sync_accent(&mut st, Some(orange)));
+    assert_eq!(st.keys[0].slots[0].color, accent_key_color(orange));
+    assert_eq!(st.keys[2].slots[0].color, custom);
+
+    st.settings.accent_key_colors = false;
+    assert!(sync_accent(&mut st, Some(orange)));
+    assert_eq!(st.keys[0].slots[0].color, stock_color());
+    assert_eq!(st.settings.applied_accent, None);
+}
+
+#[test]
+fn translations_fill_placeholders_and_cover_english_ids() {
+    use crate::i18n::{format, language_code, languages, parse};
+
+    assert_eq!(format("Key { $n }", &[("n", &3)]), "Key 3");
+    assert_eq!(format("{ $key }: {$slot}", &[("key", &"Mic"), ("slot", &"Muted")]), "Mic: Muted");
+    assert_eq!(format("{ $missing } stays", &[]), "{ $missing } stays");
+    assert_eq!(format("unclosed { $n", &[("n", &1)]), "unclosed { $n");
+
+    assert_eq!(language_code("es_AR.UTF-8"), "es");
+    assert_eq!(language_code("pt-BR"), "pt");
+    assert_eq!(language_code("en"), "en");
+
+    let en = parse(include_str!("../../locales/en.ftl"));
+    let es = parse(include_str!("../../locales/es.ftl"));
+    assert_eq!(en["key-name"], "Key { $n }");
+    // English is the fallback, so every translated id must exist there
+    for id in es.keys() {
+        assert!(en.contains_key(id), "{id} is missing from en.ftl");
+    }
+    // Errors reach the UI as they are, so each one needs its translation
+    for id in en.keys().filter(|id| id.starts_with("error-")) {
+        assert!(es.contains_key(id), "{id} is missing from es.ftl");
+    }
+    assert!(languages().iter().any(|l| l.code == "es" && l.name == "Español"));
+
+    // Every action a key can run is read out in words, not as its id
+    for action in ["obs:toggle-record", "obs:scene:Intro", "mixer:wave-link:Mic: USB:toggle-mute", "mode:cycle"] {
+        assert_ne!(crate::action_words(action), action);
+    }
+}
+
+#[test]
+fn quitting_asks_first_only_while_sounds_play() {
+    use crate::state::{CloseAction, WindowSettings};
+    use tauri::Manager;
+
+    // Settings saved before the option existed keep hiding to the tray
+    let window: WindowSettings = serde_json::from_str("{}").unwrap();
+    assert_eq!(window.close, CloseAction::HideToTray);
+    assert!(!window.minimize_to_tray && !window.confirm_quit);
+
+    let app = tauri::test::mock_app();
+    let mut st = AppState::default();
+    st.settings.window.confirm_quit = true;
+    app.manage(Mutex::new(st));
+    let sound = FakeSound::default();
+    let playlist = Arc::clone(&sound.playlist);
+    app.manage(ManagedAudioPipeline(Mutex::new(Some(Box::new(sound)))));
+
+    assert!(!crate::quit_needs_confirm(app.handle()));
+    *playlist.lock().unwrap() = Some("intro".into());
+    assert!(crate::quit_needs_confirm(app.handle()));
+    let state = app.state::<crate::state::SharedState>();
+    state.lock().unwrap().settings.window.confirm_quit = false;
+    assert!(!crate::quit_needs_confirm(app.handle()));
+}
+
+#[test]
+fn onboarding_passes_steps_already_done_and_waits_for_a_key() {
+    use crate::onboarding::{advance, key_pressed, reset, skip, OnboardingStep};
+    use crate::state::AppSettings;
+
+    // Existing installs skip the guide; fresh ones start it
+    let settings: AppSettings = serde_json::from_str("{}").unwrap();
+    assert_eq!(settings.onboarding, OnboardingStep::Done);
+    let mut st = AppState::default();
+    assert_eq!(st.settings.onboarding, OnboardingStep::Device);
+
+    // No device: nothing moves until the step is skipped
+    assert!(!advance(&mut st));
+    assert!(!key_pressed(&mut st));
+    skip(&mut st);
+    assert_eq!(st.settings.onboarding, OnboardingStep::TestKey);
+
+    // Audio devices picked and a sound imported before the key was pressed
+    st.audio_config.audio_input_device = Some("Mic".into());
+    st.audio_config.audio_output_device = Some("Cable".into());
+    st.audio_config.sound_library.push(SoundEntry {
+        id: "ding".into(),
+        filename: "ding.wav".into(),
+        display_name: "Ding".into(),
+        missing: false,
+        monitor_only: false,
+    });
+    assert!(!advance(&mut st));
+    assert!(key_pressed(&mut st));
+    assert_eq!(st.settings.onboarding, OnboardingStep::Done);
+    assert!(!key_pressed(&mut st));
+
+    // Starting over stops at the first step still to do
+    reset(&mut st);
+    assert_eq!(st.settings.onboarding, OnboardingStep::Device);
+    st.audio_config.audio_output_device = None;
+    skip(&mut st);
+    skip(&mut st);
+    assert_eq!(st.settings.onboarding, OnboardingStep::Audio);
+}
+
+#[test]
+fn press_mode_splits_the_color_change_from_the_action() {
+    use crate::state::{KeyConfig, PressMode};
+
+    // Keys saved before the option existed do both
+    let key: KeyConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(key.press_mode, PressMode::Both);
+    assert!(PressMode::Both.changes_led() && PressMode::Both.runs_action());
+    assert!(!PressMode::ActionOnly.changes_led() && PressMode::ActionOnly.runs_action());
+    assert!(PressMode::LedOnly.changes_led() && !PressMode::LedOnly.runs_action());
+}
+
+#[test]
+fn modes_switch_unbound_keys_and_cycle() {
+    use crate::modes::{self, Mode};
+
+    let app = tauri::test::mock_app();
+    let fw = firmware();
+    let mut st = connected(&fw);
+    for key in st.keys.iter_mut() {
+        key.override_enabled = true;
+    }
+    st.keys[1].binding = Some(SlotBinding {
+        condition: Condition::ModeActive { mode: "Gaming".into() },
+        when_true: "B".into(),
+        when_false: "A".into(),
+        poll_ms: 500,
+    });
+    let mut slots: [Option<String>; 8] = std::array::from_fn(|_| Some("B".into()));
+    slots[2] = None;
+    slots[3] = Some("Renamed".into());
+    st.modes.push(Mode { name: "Gaming".into(), slots, sounds_muted: Some(true), base: None });
+    // Saved before sounds were part of a mode
+    st.modes.push(serde_json::from_str(r#"{"name":"Idle","slots":[null,null,null,null,null,null,null,null]}"#).unwrap());
+
+    assert!(modes::apply(app.handle(), &mut st, "Nope").is_err());
+    assert_eq!(modes::next(&st), Some("Gaming"));
+    assert_eq!(modes::apply(app.handle(), &mut st, "Gaming"), Ok(Some(true)));
+    assert_eq!(st.active_mode.as_deref(), Some("Gaming"));
+    // Bound keys, keys left out and slots that no longer exist stay put
+    let names: Vec<_> = st.keys.iter().map(|k| k.active().name.as_str()).collect();
+    assert_eq!(names, ["B", "A", "A", "A", "B", "B", "B", "B"]);
+    assert_eq!(fw.lock().unwrap().colors[0], RED);
+
+    assert_eq!(modes::next(&st), Some("Idle"));
+    assert_eq!(modes::apply(app.handle(), &mut st, "Idle"), Ok(None));
+    assert_eq!(modes::next(&st), Some("Gaming"));
+}
+
+#[test]
+fn key_sounds_trigger_from_the_cached_path() {
+    use crate::state::{SoundMode, SoundRoute};
+    use crate::trigger::{play_now, Trigger};
+    use tauri::Manager;
+
+    let path = crate::audio::sounds_dir().unwrap().join("trigger-test.wav");
+    std::fs::write(&path, b"").unwrap();
+    assert_eq!(crate::audio::sound_path("trigger-test.wav").unwrap(), path);
+    let trigger = |filename: &str| Trigger {
+        key_index: 4,
+        sound_id: "snd".into(),
+        filename: filename.into(),
+        route: SoundRoute::Cable,
+        gain: 1.0,
+        mode: SoundMode::Momentary,
+        pressed: Instant::now(),
+    };
+
+    let app = tauri::test::mock_app();
+    let sound = FakeSound::default();
+    let (played, held) = (sound.played.clone(), sound.held.clone());
+    app.manage(ManagedAudioPipeline(Mutex::new(Some(Box::new(sound)))));
+    assert!(play_now(app.handle(), &trigger("trigger-test.wav")));
+    assert_eq!(*played.lock().unwrap(), vec![(path.clone(), SoundRoute::Cable)]);
+    assert_eq!(held.lock().unwrap()[4], Some(path));
+
+    // Without the soundboard the file is looked for before playing it locally
+    let app = tauri::test::mock_app();
+    app.manage(ManagedAudioPipeline(Mutex::new(None)));
+    assert!(!play_now(app.handle(), &trigger("trigger-gone.wav")));
+}
+
+#[test]
+fn combined_color_report_is_used_when_the_firmware_has_it() {
+    let fw = firmware();
+    let st = connected(&fw);
+    let dev = st.device.as_ref().unwrap();
+    let before = fw.lock().unwrap().reports;
+    dev.set_key_colors(&[(0, GREEN), (1, RED)]).unwrap();
+    assert_eq!(fw.lock().unwrap().reports - before, 6);
+
+    let fw = firmware();
+    fw.lock().unwrap().features = Some(0b111);
+    let st = connected(&fw);
+    assert!(st.device_info.as_ref().unwrap().capabilities.set_hsv);
+    let dev = st.device.as_ref().unwrap();
+    let before = fw.lock().unwrap().reports;
+    dev.set_key_colors(&[(0, GREEN), (1, RED)]).unwrap();
+    let fw = fw.lock().unwrap();
+    assert_eq!(fw.reports - before, 2);
+    assert_eq!((fw.colors[0], fw.colors[1]), (GREEN, RED));
+    assert!(fw.overrides[0] && fw.overrides[1]);
+    assert_eq!(fw.unhandled, 0);
+}
+
+#[test]
+fn hid_timing_sets_the_read_timeout_and_spaces_reports() {
+    use crate::hid::HidTiming;
+
+    assert!(HidTiming { timeout_ms: 50, pacing_ms: 0 }.validate().is_err());
+    assert!(HidTiming { timeout_ms: 500, pacing_ms: 100 }.validate().is_err());
+    // Settings saved before the option existed keep the old 500 ms
+    let settings: crate::state::AppSettings = serde_json::from_str("{}").unwrap();
+    assert_eq!(settings.hid, HidTiming::default());
+
+    let fw = firmware();
+    let mut st = AppState::default();
+    st.settings.hid = HidTiming { timeout_ms: 800, pacing_ms: 20 };
+    crate::sync_device(&mut st, fakes::device(&fw));
+    assert_eq!(fw.lock().unwrap().last_timeout_ms, 800);
+
+    let t0 = Instant::now();
+    st.device.as_ref().unwrap().set_key_color(0, &GREEN).unwrap();
+    // Three reports, each at least 20 ms after the one before
+    assert!(t0.elapsed() >= Duration::from_millis(40));
+}
+
+#[test]
+fn rules_apply_while_they_hold_and_undo_when_they_stop() {
+    use crate::rules::{self, Now, Rule, RuleAction, RuleWhen};
+
+    let at = |weekday, hour: u16, on_battery| Now { weekday, minute: hour * 60, on_battery };
+    let weekdays_9_to_17 = RuleWhen::Schedule { days: 0b11111, start_min: 9 * 60, end_min: 17 * 60 };
+    assert!(rules::holds(&weekdays_9_to_17, &at(0, 9, false)));
+    assert!(!rules::holds(&weekdays_9_to_17, &at(0, 17, false)));
+    assert!(!rules::holds(&weekdays_9_to_17, &at(5, 12, false)));
+    // Friday night until Saturday 2:00
+    let friday_night = RuleWhen::Schedule { days: 1 << 4, start_min: 22 * 60, end_min: 2 * 60 };
+    assert!(rules::holds(&friday_night, &at(5, 1, false)));
+    assert!(!rules::holds(&friday_night, &at(4, 1, false)));
+
+    let app = tauri::test::mock_app();
+    let fw = firmware();
+    let mut st = connected(&fw);
+    for key in st.keys.iter_mut() {
+        key.override_enabled = true;
+    }
+    let rule = |id: &str, when: RuleWhen, then: RuleAction| Rule { id: id.into(), name: id.into(), enabled: true, when, then };
+    st.rules = vec![
+        rule("work", weekdays_9_to_17.clone(), RuleAction::KeySlot { key: 0, slot: "B".into() }),
+        rule("bound", weekdays_9_to_17, RuleAction::KeySlot { key: 1, slot: "B".into() }),
+        rule("battery", RuleWhen::OnBattery, RuleAction::Dim { percent: 30 }),
+    ];
+    assert!(rules::validate(&st, &rule("", RuleWhen::OnBattery, RuleAction::KeySlot { key: 0, slot: "Nope".into() })).is_err());
+    st.keys[1].binding = Some(SlotBinding {
+        condition: Condition::MicMuted,
+        when_true: "B".into(),
+        when_false: "A".into(),
+        poll_ms: 500,
+    });
+
+    assert!(rules::evaluate(app.handle(), &mut st, &at(0, 10, true)));
+    assert_eq!(st.keys[0].active().name, "B");
+    assert_eq!(st.keys[1].active().name, "A");
+    assert_eq!(fw.lock().unwrap().colors[0], RED);
+    assert_eq!(rules::dim_percent(&st), 30);
+    // Still holding: nothing to do
+    assert!(!rules::evaluate(app.handle(), &mut st, &at(0, 11, true)));
+
+    assert!(rules::evaluate(app.handle(), &mut st, &at(0, 18, false)));
+    assert_eq!(st.keys[0].active().name, "A");
+    assert_eq!(rules::dim_percent(&st), 100);
+
+    // Moved by hand while the rule held: left where it is
+    rules::evaluate(app.handle(), &mut st, &at(1, 10, false));
+    st.keys[0].slots.push(ColorSlot { name: "C".into(), color: GREEN });
+    st.keys[0].active_slot = 2;
+    rules::evaluate(app.handle(), &mut st, &at(1, 18, false));
+    assert_eq!(st.keys[0].active_slot, 2);
+}
+
+#[test]
+fn calendar_meetings_light_ahead_and_recur() {
+    use crate::calendar::{current, parse};
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    let at = |d: u32, h: u32, m: u32| NaiveDate::from_ymd_opt(2026, 10, d).unwrap().and_hms_opt(h, m, 0).unwrap();
+    // Mon 12 Oct: a weekday standup with Wednesday skipped and Thursday moved,
+    // plus an all-day event and a cancelled meeting
+    let ics = "BEGIN:VCALENDAR\r\n\
+        BEGIN:VEVENT\r\nUID:standup\r\nSUMMARY:Standup\r\nDTSTART:20261012T093000\r\nDURATION:PT15M\r\n\
+        RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR\r\nEXDATE:20261014T093000\r\n\
+        DESCRIPTION:Join: https://teams.microsoft.com/l/meetup-join/abc\\n\r\n  more text\r\n\
+        BEGIN:VALARM\r\nTRIGGER:-PT5M\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nUID:standup\r\nRECURRENCE-ID:20261015T093000\r\nSUMMARY:Standup (moved)\r\n\
+        DTSTART:20261015T110000\r\nDTEND:20261015T111500\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nUID:holiday\r\nDTSTART;VALUE=DATE:20261013\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nUID:gone\r\nSTATUS:CANCELLED\r\nDTSTART:20261013T140000\r\nDTEND:20261013T150000\r\nEND:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+    let week = parse(ics, at(12, 0, 0), at(17, 0, 0));
+    let starts: Vec<_> = week.iter().map(|o| o.start).collect();
+    assert_eq!(starts, [at(12, 9, 30), at(13, 9, 30), at(15, 11, 0), at(16, 9, 30)]);
+    assert_eq!(week[0].link.as_deref(), Some("https://teams.microsoft.com/l/meetup-join/abc"));
+
+    // Only https:// links are opened, whichever property they come in
+    let ics = "BEGIN:VCALENDAR\r\n\
+        BEGIN:VEVENT\r\nUID:trap\r\nDTSTART:20261012T100000\r\nDURATION:PT30M\r\n\
+        X-GOOGLE-CONFERENCE:file:///C:/Windows/System32/calc.exe\r\n\
+        LOCATION:https://meet.google.com/abc-defg-hij\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nUID:local\r\nDTSTART:20261012T110000\r\nDURATION:PT30M\r\n\
+        X-MICROSOFT-SKYPETEAMSMEETINGURL:file:///tmp/run.sh\r\nEND:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+    let links: Vec<_> = parse(ics, at(12, 0, 0), at(13, 0, 0)).into_iter().map(|o| o.link).collect();
+    assert_eq!(links, [Some("https://meet.google.com/abc-defg-hij".to_string()), None]);
+
+    assert!(current(&week, at(13, 9, 20), 5).is_none());
+    assert_eq!(current(&week, at(13, 9, 26), 5).map(|o| o.start), Some(at(13, 9, 30)));
+    assert!(current(&week, at(13, 9, 44), 5).is_some());
+    assert!(current(&week, at(13, 9, 45), 5).is_none());
+    assert!(current(&week, at(14, 9, 30), 5).is_none());
+
+    // Times with a TZID follow that zone's clock, DST included: US clocks go
+    // back on 1 Nov, Europe's a week earlier
+    let local = |m: u32, d: u32, h: u32| {
+        Local.from_utc_datetime(&NaiveDate::from_ymd_opt(2026, m, d).unwrap().and_hms_opt(h, 0, 0).unwrap()).naive_local()
+    };
+    let ics = "BEGIN:VCALENDAR\r\n\
+        BEGIN:VEVENT\r\nUID:sync\r\nDTSTART;TZID=Pacific Standard Time:20261026T090000\r\nDURATION:PT30M\r\n\
+        RRULE:FREQ=WEEKLY;COUNT=2\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nUID:review\r\nDTSTART;TZID=\"Europe/Berlin\":20261027T100000\r\nDURATION:PT1H\r\nEND:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+    let starts: Vec<_> = parse(ics, at(25, 0, 0), at(31, 0, 0) + chrono::Duration::days(10)).iter().map(|o| o.start).collect();
+    assert_eq!(starts, [local(10, 26, 16), local(10, 27, 9), local(11, 2, 17)]);
+}
+
+#[test]
+fn focus_summary_counts_days_the_week_and_the_streak() {
+    use crate::pomodoro::{summarize, FocusSettings, Session};
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    assert!(FocusSettings { focus_min: 0, ..Default::default() }.validate().is_err());
+    // Settings saved before the focus timer existed
+    let settings: crate::state::AppSettings = serde_json::from_str("{}").unwrap();
+    assert_eq!(settings.focus, FocusSettings::default());
+
+    let day = |d: u32| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+    let session = |d: u32, h: u32| Session {
+        started_ms: Local.from_local_datetime(&day(d).and_hms_opt(h, 0, 0).unwrap()).unwrap().timestamp_millis() as u64,
+        minutes: 25,
+    };
+    // Thursday 15 Oct: two a day since Sunday, one so far today
+    let sessions = [
+        session(11, 9), session(11, 10),
+        session(12, 9), session(12, 10),
+        session(13, 9), session(13, 10),
+        session(14, 9), session(14, 10),
+        session(15, 9),
+    ];
+    let summary = summarize(&sessions, day(15), 2);
+    assert_eq!(summary.days.len(), 7);
+    assert_eq!(summary.days[0].date, "2026-10-09");
+    assert_eq!((summary.days[6].date.as_str(), summary.days[6].sessions, summary.days[6].minutes), ("2026-10-15", 1, 25));
+    // Monday to today
+    assert_eq!((summary.week_sessions, summary.week_minutes), (7, 175));
+    // Today doesn't break the streak before it's over
+    assert_eq!(summary.streak, 4);
+    assert_eq!(summarize(&sessions, day(15), 1).streak, 5);
+    assert_eq!(summarize(&sessions, day(15), 0).streak, 0);
+}
+
+#[test]
+fn game_rules_match_the_game_state() {
+    use crate::game::{authorized, lookup, GameRule, GameSettings, GameTest};
+    use crate::notify::{Pattern, Stack};
+    use serde_json::json;
+
+    // What CS2's state integration posts mid-round
+    let payload = json!({
+        "auth": { "token": "s3cret" },
+        "round": { "phase": "live", "bomb": "planted" },
+        "player": { "state": { "health": 24, "flashed": 0 } },
+        "abilities": { "ability1": { "can_cast": false } },
+    });
+    let rule = |path: &str, test: GameTest| GameRule {
+        name: path.into(),
+        path: path.into(),
+        test,
+        key: 0,
+        color: GREEN,
+        pattern: Pattern::Blink,
+    };
+    let holds = |r: &GameRule| r.test.matches(lookup(&payload, &r.path));
+    assert!(holds(&rule("player.state.health", GameTest::Below { value: 30.0 })));
+    assert!(!holds(&rule("player.state.health", GameTest::Above { value: 30.0 })));
+    assert!(holds(&rule("round.bomb", GameTest::Equals { value: "planted".into() })));
+    assert!(holds(&rule("abilities.ability1.can_cast", GameTest::Equals { value: "false".into() })));
+    // Not in this payload (e.g. in the menu)
+    assert!(!holds(&rule("hero.health_percent", GameTest::Below { value: 30.0 })));
+    // Not a number
+    assert!(!holds(&rule("round.bomb", GameTest::Below { value: 30.0 })));
+
+    let mut settings = GameSettings::default();
+    assert!(settings.validate().is_ok());
+    assert!(authorized(&settings, &payload));
+    settings.token = Some("other".into());
+    assert!(!authorized(&settings, &payload));
+    settings.token = Some("s3cret".into());
+    assert!(authorized(&settings, &json!({ "auth": { "token": "s3cret" } })));
+    settings.rules.push(rule("player..health", GameTest::Below { value: 30.0 }));
+    assert!(settings.validate().is_err());
+    settings.rules[0].path = "player.state.health".into();
+    settings.rules[0].key = 8;
+    assert!(settings.validate().is_err());
+
+    // Each update keeps a holding rule's notification up a while longer
+    let t0 = Instant::now();
+    let secs = Duration::from_secs;
+    let mut stack = Stack::new();
+    let id = stack.push(0, GREEN, Pattern::Solid, 1, secs(60), t0);
+    assert!(stack.extend(id, secs(60), t0 + secs(50)));
+    assert_eq!(stack.color(0, t0 + secs(100)), Some(GREEN));
+    assert!(!stack.extend(id, secs(60), t0 + secs(111)));
+}
+
+#[test]
+fn keymaps_follow_the_keyboard_layout() {
+    use crate::keycodes::lookup;
+    use crate::layout::XKeymap;
+
+    // `xmodmap -pk` on a French AZERTY layout (excerpt)
+    let azerty = XKeymap::parse(
+        "There are 7 KeySyms per KeyCode; KeyCodes range from 8 to 255.\n\
+         \n\
+         \x20   KeyCode\tKeysym (Keysym)\t...\n\
+         \x20   Value  \tValue   (Name) \t...\n\
+         \n\
+         \x20     8    \n\
+         \x20    10    \t0x0026 (ampersand)\t0x0031 (1)\t0x0026 (ampersand)\t0x0031 (1)\n\
+         \x20    24    \t0x0061 (a)\t0x0041 (A)\t0x0061 (a)\t0x0041 (A)\n\
+         \x20    38    \t0x0071 (q)\t0x0051 (Q)\t0x0071 (q)\t0x0051 (Q)\n\
+         \x20    47    \t0x006d (m)\t0x004d (M)\t0x006d (m)\t0x004d (M)\n\
+         \x20    58    \t0x002c (comma)\t0x003f (question)\t0x002c (comma)\t0x003f (question)\n\
+         \x20    67    \t0xffbe (F1)\t0x0000 (NoSymbol)\t0xffbe (F1)\n",
+    );
+    let key = |basic: u8| lookup(basic).unwrap();
+    // The key QMK calls KC_A is where AZERTY has Q
+    assert_eq!(azerty.keysym(key(0x04)), Some((0x71, "q")));
+    assert_eq!(azerty.hotkey_code(key(0x04)), Some("KeyQ"));
+    assert_eq!(azerty.hotkey_code(key(0x14)), Some("KeyA"));
+    assert_eq!(azerty.hotkey_code(key(0x33)), Some("KeyM"));
+    assert_eq!(azerty.hotkey_code(key(0x10)), Some("Comma"));
+    // "&" has no key of its own on a US layout
+    assert_eq!(azerty.keysym(key(0x1E)), Some((0x26, "ampersand")));
+    assert_eq!(azerty.hotkey_code(key(0x1E)), None);
+    // F-keys are the same on every layout
+    assert!(!key(0x3A).positional());
+    assert_eq!(azerty.keysym(key(0x3A)), None);
+    assert_eq!(key(0x04).scancode(), Some(0x1E));
+}
+
+#[test]
+fn numpad_keys_are_told_apart_by_scancode() {
+    use crate::keycodes::{from_numpad_scancode, from_vk, lookup};
+
+    let scan = |basic: u8| lookup(basic).unwrap().numpad_scancode();
+    assert_eq!(scan(0x5F), Some(0x47)); // Num 7, Home with NumLock off
+    assert_eq!(scan(0x63), Some(0x53)); // Num .
+    // 0xE0-prefixed, unlike the keys they share a VK or set-1 code with
+    assert_eq!(scan(0x58), Some(0xE01C));
+    assert_eq!(scan(0x54), Some(0xE035));
+    assert_eq!(scan(0x67), Some(0x59));
+    assert_eq!(scan(0x53), None);
+    assert_eq!(scan(0x4A), None); // Home itself
+    assert_eq!(from_numpad_scancode(0x47).unwrap().qmk, "KC_P7");
+    assert_eq!(from_numpad_scancode(0xE01C).unwrap().qmk, "KC_PENT");
+    assert!(from_numpad_scancode(0x1C).is_none());
+    // By VK alone Num Enter would be Enter
+    assert_eq!(from_vk(0x0D).unwrap().qmk, "KC_ENT");
+}
+
+#[test]
+fn bare_keys_only_bind_where_they_cant_hijack_typing() {
+    use crate::keycodes::is_bindable;
+
+    const KC_F5: u16 = 0x3E;
+    const KC_VOLU: u16 = 0xA9;
+    // Backends that see every keyboard: only F13–F24 go bare
+    assert!(is_bindable(0x68, false));
+    assert!(is_bindable(0x73, false));
+    assert!(!is_bindable(KC_F5, false));
+    assert!(!is_bindable(KC_VOLU, false));
+    assert!(!is_bindable(0x04, false));
+    assert!(is_bindable(0x0100 | KC_F5, false));
+    // Deck-only backends can take any bare key
+    assert!(is_bindable(KC_F5, true));
+    assert!(is_bindable(0x04, true));
+    assert!(!is_bindable(0x0100, true));
+}
+
+#[test]
+fn a_device_held_by_another_app_is_busy() {
+    use crate::hid::{is_busy, Busy};
+
+    assert!(is_busy("The process cannot access the file because it is being used by another process."));
+    assert!(is_busy("hid_open_path: failed to open IOHIDDevice from mach entry: (0xE00002C5) (iokit/common) exclusive access and device already open"));
+    assert!(is_busy("Access is denied."));
+    // Missing udev rules, or an unplugged device, aren't another app
+    assert!(!is_busy("Permission denied (os error 13)"));
+    assert!(!is_busy("No such device"));
+    assert_eq!(HubError::from(anyhow::Error::new(Busy)).code(), "DEVICE_BUSY");
+}
+
+#[test]
+fn toast_buttons_come_back_as_actions() {
+    use crate::toast::{xml, ToastAction};
+
+    let toast = xml("Audio <stopped>", "Mic & line-in", &[ToastAction::RestartPipeline, ToastAction::OpenApp]);
+    assert!(toast.contains("<text>Audio &lt;stopped&gt;</text><text>Mic &amp; line-in</text>"));
+    assert!(toast.contains(r#"content="Restart pipeline" arguments="restart-pipeline""#));
+    assert!(toast.starts_with(r#"<toast launch="open-app">"#));
+    // PowerShell prints the argument with a line ending
+    assert_eq!(ToastAction::parse("restart-pipeline\r"), Some(ToastAction::RestartPipeline));
+    assert_eq!(ToastAction::parse("open-app"), Some(ToastAction::OpenApp));
+    assert_eq!(ToastAction::parse(""), None);
+}
+
+#[test]
+fn connection_history_counts_drops_and_time_to_drop() {
+    use crate::connections::{push, summarize, ConnectionEvent, EventKind};
+
+    const HOUR: u64 = 60 * 60 * 1000;
+    let event = |at_ms, kind| ConnectionEvent { at_ms, kind, detail: None };
+    let mut events = Vec::new();
+    for e in [
+        event(0, EventKind::Connected),
+        event(2 * HOUR, EventKind::Lost),
+        event(2 * HOUR, EventKind::NotFound),
+        event(3 * HOUR, EventKind::Connected),
+        event(3 * HOUR + 1, EventKind::Released),
+        event(30 * HOUR, EventKind::Connected),
+        event(34 * HOUR, EventKind::Unresponsive),
+        event(34 * HOUR, EventKind::Connected),
+    ] {
+        assert!(push(&mut events, e));
+    }
+    // Cooperative mode retrying doesn't flood the history
+    assert!(push(&mut events, event(35 * HOUR, EventKind::Busy)));
+    assert!(!push(&mut events, event(36 * HOUR, EventKind::Busy)));
+
+    let summary = summarize(&events, 40 * HOUR);
+    assert_eq!((summary.drops_day, summary.drops_week), (1, 2));
+    // 2 h and 4 h; the released connection isn't a drop
+    assert_eq!(summary.mean_time_to_drop_ms, Some(3 * HOUR));
+    assert_eq!(summary.connected_since_ms, Some(34 * HOUR));
+
+    for i in 0..600 {
+        push(&mut events, event(i, EventKind::Connected));
+    }
+    assert_eq!(events.len(), 500);
+    assert_eq!(events[0].at_ms, 100);
+}
+
+#[test]
+fn a_bundle_carries_the_setup_and_its_sounds() {
+    use crate::profile::{export_bundle, import_bundle, is_plain_filename};
+
+    let path = crate::audio::sounds_dir().unwrap().join("bundle-test.wav");
+    std::fs::write(&path, b"RIFF").unwrap();
+    let mut st = AppState::default();
+    st.keymaps[3] = 0x0104;
+    st.audio_config.sound_library.push(SoundEntry {
+        id: "snd".into(),
+        filename: "bundle-test.wav".into(),
+        display_name: "Test".into(),
+        missing: false,
+        monitor_only: false,
+    });
+    // Not on disk: left out rather than failing the export
+    st.audio_config.sound_library.push(SoundEntry {
+        id: "gone".into(),
+        filename: "bundle-missing.wav".into(),
+        display_name: "Gone".into(),
+        missing: true,
+        monitor_only: false,
+    });
+    st.settings.discord.client_secret = Some("discord-secret".into());
+    st.settings.game.token = Some("game-token".into());
+    st.settings.lock_pin = Some("pin-hash".into());
+    st.keys[0].set_binding(Some(SlotBinding {
+        condition: Condition::ObsStreaming { host: "localhost".into(), port: 4455, password: Some("obs-secret".into()) },
+        when_true: "B".into(),
+        when_false: "A".into(),
+        poll_ms: 1000,
+    })).unwrap();
+    let bundle = export_bundle(&st).unwrap();
+    let text = String::from_utf8_lossy(&bundle);
+    for secret in ["discord-secret", "game-token", "pin-hash", "obs-secret"] {
+        assert!(!text.contains(secret), "{secret}");
+    }
+    std::fs::remove_file(&path).unwrap();
+
+    let restored = import_bundle(&bundle).unwrap();
+    assert_eq!(restored.keymaps.unwrap()[3], 0x0104);
+    assert_eq!(restored.audio_config.unwrap().sound_library.len(), 2);
+    assert_eq!(std::fs::read(&path).unwrap(), b"RIFF");
+
+    assert!(import_bundle(b"{}").is_err());
+    // A library entry pointing outside the sounds folder refuses the whole
+    // bundle, before its sounds are written
+    std::fs::remove_file(&path).unwrap();
+    st.audio_config.sound_library[1].filename = "../state.json".into();
+    let tampered = serde_json::to_vec(&{
+        let mut bundle: serde_json::Value = serde_json::from_slice(&bundle).unwrap();
+        bundle["state"]["audio_config"] = serde_json::to_value(&st.audio_config).unwrap();
+        bundle
+    })
+    .unwrap();
+    assert!(import_bundle(&tampered).unwrap_err().to_string().contains("../state.json"));
+    assert!(!path.exists());
+
+    assert!(is_plain_filename("key1_airhorn.mp3"));
+    for name in ["", "..", "../state.json", r"..\state.json", "C:evil.wav"] {
+        assert!(!is_plain_filename(name), "{name}");
+    }
+}
+
+#[test]
+fn a_locked_setup_keeps_triggers_and_needs_the_pin() {
+    use crate::lock::{allows, hash_pin, set_locked, try_unlock, verify_pin};
+
+    assert!(hash_pin("12").is_err());
+    assert!(hash_pin("12ab").is_err());
+    let stored = hash_pin("2468").unwrap();
+    // Salted: the same PIN doesn't hash the same twice
+    assert_ne!(stored, hash_pin("2468").unwrap());
+    assert!(verify_pin("2468", &stored));
+    assert!(!verify_pin("1357", &stored));
+
+    set_locked(true);
+    assert!(!allows("set_key_color"));
+    assert!(!allows("set_keycode"));
+    assert!(!allows("add_to_sound_library"));
+    assert!(allows("toggle_key_slot"));
+    assert!(allows("stop_all_sounds"));
+    assert!(allows("unlock_config"));
+    set_locked(false);
+    assert!(allows("set_key_color"));
+
+    // Guessing is held off after five misses, even with the right PIN
+    for _ in 0..5 {
+        assert_eq!(try_unlock("0000", &stored, 1_000), Err("Wrong PIN".into()));
+    }
+    assert!(try_unlock("2468", &stored, 2_000).unwrap_err().starts_with("Too many"));
+    assert_eq!(try_unlock("2468", &stored, 62_000), Ok(()));
+    assert_eq!(HubError::Locked.code(), "LOCKED");
+
+    // The webview hears that the setup is locked, never the PIN's hash
+    let mut st = AppState::default();
+    st.settings.lock_pin = Some(stored);
+    let snapshot = st.snapshot();
+    assert!(snapshot.locked);
+    assert_eq!(snapshot.settings.lock_pin, None);
+}
+
+#[test]
+fn every_command_allowed_while_locked_is_registered() {
+    for command in crate::lock::ALLOWED {
+        assert!(crate::COMMANDS.contains(command), "{command} isn't a command");
+    }
+    // Onboarding and the usage export write to disk, so a lock stops them
+    for command in ["finish_onboarding", "skip_onboarding_step", "export_usage_csv", "set_key_color"] {
+        assert!(crate::COMMANDS.contains(&command), "{command} isn't a command");
+        assert!(!crate::lock::ALLOWED.contains(&command), "{command} runs while locked");
+    }
+}
+
+#[test]
+fn modes_build_on_a_base() {
+    use crate::modes::{overrides, remove, resolve, save, Mode};
+
+    let slots = |names: [&str; 8]| -> [Option<String>; 8] { names.map(|n| (!n.is_empty()).then(|| n.to_string())) };
+    let mut modes = Vec::new();
+    let default = Mode { name: "Default".into(), slots: slots(["A"; 8]), sounds_muted: Some(false), base: None };
+    save(&mut modes, default, 16).unwrap();
+    // Keys 1–4 changed from the default
+    let streaming = overrides(&modes, "Default", &slots(["B", "B", "B", "B", "A", "A", "A", "A"])).unwrap();
+    assert_eq!(streaming, slots(["B", "B", "B", "B", "", "", "", ""]));
+    save(&mut modes, Mode { name: "Streaming".into(), slots: streaming, sounds_muted: None, base: Some("Default".into()) }, 16).unwrap();
+    let late = Mode { name: "Late".into(), slots: slots(["", "", "", "", "", "", "", "C"]), sounds_muted: Some(true), base: Some("Streaming".into()) };
+    save(&mut modes, late, 16).unwrap();
+
+    // Resolved when applied: a change to the base carries over
+    modes[0].slots[5] = Some("D".into());
+    let resolved = resolve(&modes, "Late").unwrap();
+    assert_eq!(resolved.slots, slots(["B", "B", "B", "B", "A", "D", "A", "C"]));
+    assert_eq!(resolved.sounds_muted, Some(true));
+    assert_eq!(resolve(&modes, "Streaming").unwrap().sounds_muted, Some(false));
+
+    // No loops, no missing bases
+    let looped = Mode { name: "Default".into(), slots: slots(["A"; 8]), sounds_muted: None, base: Some("Late".into()) };
+    assert!(save(&mut modes, looped, 16).is_err());
+    assert_eq!(modes[0].base, None);
+    let orphan = Mode { name: "Orphan".into(), slots: slots(["A"; 8]), sounds_muted: None, base: Some("Nope".into()) };
+    assert!(save(&mut modes, orphan, 16).is_err());
+
+    // Deleting a base keeps what the modes on it showed
+    assert!(remove(&mut modes, "Streaming"));
+    assert_eq!(modes[1].base.as_deref(), Some("Default"));
+    assert_eq!(resolve(&modes, "Late").unwrap().slots, resolved.slots);
+}
+
+#[test]
+fn resets_need_a_fresh_token_and_back_up_first() {
+    use crate::reset::{backup, confirm, preview, DeviceSnapshot, ResetKind};
+
+    let fw = firmware();
+    {
+        let mut fw = fw.lock().unwrap();
+        fw.keymaps = [CTRL_ALT_M; 8];
+        fw.macros = b"hello\0\0world\0".to_vec();
+    }
+    let dev = fakes::device(&fw);
+
+    let snapshot = DeviceSnapshot::read(&dev, ResetKind::Eeprom, vec![2]).unwrap();
+    assert_eq!(snapshot.layers.len(), 4);
+    assert_eq!(snapshot.layers[0], [CTRL_ALT_M; 8]);
+    assert_eq!(snapshot.macros.len(), 1024);
+    assert_eq!(snapshot.macro_count(), 2);
+    assert_eq!(snapshot.rgb.map(|rgb| rgb.brightness), Some(200));
+    assert_eq!(snapshot.overrides, vec![2]);
+    // A macro reset only reads the macros
+    let macros_only = DeviceSnapshot::read(&dev, ResetKind::Macros, vec![2]).unwrap();
+    assert!(macros_only.layers.is_empty() && macros_only.rgb.is_none() && macros_only.overrides.is_empty());
+
+    // No preview, or a token for another kind: refused
+    assert!(confirm(ResetKind::Macros, "", 0).is_err());
+    let token = preview(ResetKind::Macros, macros_only.clone(), 1_000).token;
+    assert!(confirm(ResetKind::Keymaps, &token, 2_000).is_err());
+    assert!(confirm(ResetKind::Macros, "wrong", 2_000).is_err());
+    // Single use
+    assert_eq!(confirm(ResetKind::Macros, &token, 2_000), Ok(()));
+    assert!(confirm(ResetKind::Macros, &token, 2_000).is_err());
+    // Expired after two minutes
+    let token = preview(ResetKind::Macros, macros_only, 1_000).token;
+    assert!(confirm(ResetKind::Macros, &token, 1_000 + 120_000).unwrap_err().contains("expired"));
+
+    let path = backup(ResetKind::Eeprom, &snapshot).unwrap();
+    let saved: DeviceSnapshot = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved, snapshot);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn trims_fade_and_gain_the_same_in_preview_and_import() {
+    use crate::audio::Trim;
+
+    // 1 s of stereo at 1 kHz, full scale
+    let samples = vec![0.5f32; 2 * 1000];
+    assert_eq!(Trim::range(100, 300).apply(&samples, 2, 1000), vec![0.5; 2 * 200]);
+
+    let trim = Trim { start_ms: 0, end_ms: 1000, fade_in_ms: 100, fade_out_ms: 200, gain_db: 12.0 };
+    let out = trim.apply(&samples, 2, 1000);
+    assert_eq!(out.len(), 2000);
+    // Both channels of a frame get the same scale
+    assert_eq!(out[0], 0.0);
+    assert_eq!(out[100], out[101]);
+    assert!((out[100] - 0.5 * 0.5 * crate::dsp::db_to_lin(12.0)).abs() < 0.01);
+    // Past the fade in, +12 dB of 0.5 is clipped to full scale
+    assert_eq!(out[2 * 500], 1.0);
+    assert_eq!(*out.last().unwrap(), 0.0);
+    assert!(out[2 * 900] < out[2 * 800]);
+
+    // Out-of-range gain is clamped; backwards ranges are empty
+    let loud = Trim { gain_db: 60.0, ..Trim::range(0, 10) }.apply(&[0.01; 20], 2, 1000);
+    assert!((loud[0] - 0.01 * crate::dsp::db_to_lin(12.0)).abs() < 1e-6);
+    assert!(Trim::range(500, 100).apply(&samples, 2, 1000).is_empty());
+
+    // A file cut mid-frame loses the partial frame instead of underflowing
+    let fade = Trim { start_ms: 0, end_ms: 1000, fade_in_ms: 0, fade_out_ms: 1, gain_db: 0.0 };
+    assert_eq!(fade.apply(&samples[..5], 2, 1000).len(), 4);
+}
+
+#[test]
+fn trim_previews_replace_each_other_and_stop() {
+    use crate::audio::{SoundOutput, Trim};
+    use crate::state::SoundRoute;
+    use tauri::Manager;
+
+    let sound = FakeSound::default();
+    let preview = sound.preview.clone();
+    sound.play_trimmed("a.wav".as_ref(), &Trim::range(0, 500), vec![0.0; 2], SoundRoute::Monitor);
+    sound.play_trimmed("a.wav".as_ref(), &Trim::range(100, 600), vec![0.0; 2], SoundRoute::Monitor);
+    assert_eq!(*preview.lock().unwrap(), Some(("a.wav".into(), Trim::range(100, 600))));
+    sound.stop_preview();
+    assert_eq!(*preview.lock().unwrap(), None);
+
+    // Refused before anything is queued
+    let app = tauri::test::mock_app();
+    app.manage(Mutex::new(AppState::default()));
+    assert!(crate::preview_trim(app.state(), "missing.wav".into(), Trim::range(0, 500)).is_err());
+    let clip = std::env::temp_dir().join("deck8-preview-empty.wav");
+    std::fs::write(&clip, b"RIFF").unwrap();
+    assert!(crate::preview_trim(app.state(), clip.display().to_string(), Trim::range(500, 500)).is_err());
+    std::fs::remove_file(clip).unwrap();
+}
+
+#[test]
+fn audio_info_comes_from_the_headers() {
+    use crate::probe::probe;
+
+    let dir = std::env::temp_dir();
+    // 1.5 s of 48 kHz stereo
+    let samples = vec![0i16; 2 * 72_000];
+    let flac = dir.join("deck8-probe.flac");
+    std::fs::write(&flac, crate::flac::encode(2, 48_000, &samples)).unwrap();
+    let info = probe(&flac).unwrap();
+    assert_eq!((info.duration_ms, info.sample_rate, info.channels), (1500, 48_000, 2));
+    assert_eq!(info.codec, "FLAC 16-bit");
+    assert!(info.bitrate_kbps.is_some());
+
+    let wav = dir.join("deck8-probe.wav");
+    let spec = hound::WavSpec { channels: 1, sample_rate: 22_050, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+    let mut writer = hound::WavWriter::create(&wav, spec).unwrap();
+    (0..22_050).for_each(|_| writer.write_sample(0i16).unwrap());
+    writer.finalize().unwrap();
+    let info = probe(&wav).unwrap();
+    assert_eq!((info.duration_ms, info.channels, info.codec.as_str()), (1000, 1, "PCM 16-bit"));
+    // 16-bit mono at 22.05 kHz, plus the header
+    assert_eq!(info.bitrate_kbps, Some(352));
+
+    // Ogg Vorbis: the identification header, then a last page 2 s in
+    let page = |granule: i64, packet: &[u8]| {
+        let mut page = b"OggS\0\0".to_vec();
+        page.extend_from_slice(&granule.to_le_bytes());
+        page.extend_from_slice(&[0; 12]);
+        page.push(1);
+        page.push(packet.len() as u8);
+        page.extend_from_slice(packet);
+        page
+    };
+    let mut id = b"\x01vorbis\0\0\0\0\x02".to_vec();
+    id.extend_from_slice(&44_100u32.to_le_bytes());
+    id.extend_from_slice(&[0; 13]);
+    let ogg = dir.join("deck8-probe.ogg");
+    std::fs::write(&ogg, [page(0, &id), page(88_200, &[0; 8])].concat()).unwrap();
+    let info = probe(&ogg).unwrap();
+    assert_eq!((info.duration_ms, info.sample_rate, info.channels, info.codec.as_str()), (2000, 44_100, 2, "Vorbis"));
+
+    for file in [flac, wav, ogg] {
+        std::fs::remove_file(file).unwrap();
+    }
+}
+
+#[test]
+fn sync_status_follows_writes_and_saves() {
+    use crate::state::{DeviceSync, SyncStatus::*};
+
+    assert_eq!(AppState::default().device_sync(), DeviceSync { colors: Unknown, keymaps: Unknown, rgb: Unknown });
+
+    let fw = firmware();
+    let mut st = connected(&fw);
+    // Connecting pushes the colors and saves them
+    assert_eq!(st.device_sync(), DeviceSync { colors: Saved, keymaps: Saved, rgb: Saved });
+
+    let dev = st.device.as_ref().unwrap();
+    // Notifications, fades and previews write colors without changing the setup
+    dev.set_key_color(1, &RED).unwrap();
+    assert_eq!(st.device_sync().colors, Saved);
+    dev.set_key_color(1, &GREEN).unwrap();
+    dev.note_color_edit();
+    dev.rgb_set_brightness(90).unwrap();
+    assert_eq!(st.device_sync(), DeviceSync { colors: Unsaved, keymaps: Saved, rgb: Unsaved });
+    dev.custom_save().unwrap();
+    dev.rgb_save().unwrap();
+    assert_eq!(st.device_sync().colors, Saved);
+    assert_eq!(st.device_sync().rgb, Saved);
+
+    // A failed write: the device shows something else until it's rewritten
+    fw.lock().unwrap().hung = true;
+    assert!(dev.set_key_color(3, &GREEN).is_err());
+    assert!(dev.rgb_set_speed(10).is_err());
+    fw.lock().unwrap().hung = false;
+    assert_eq!(st.device_sync().colors, OutOfSync);
+    dev.set_key_color(3, &GREEN).unwrap();
+    assert_eq!(st.device_sync().colors, Saved);
+    // A later RGB write doesn't hide the failed one; reading them back does
+    dev.rgb_set_effect(2).unwrap();
+    assert_eq!(st.device_sync().rgb, OutOfSync);
+    dev.rgb_synced();
+    assert_eq!(st.device_sync().rgb, Unsaved);
+
+    // Keymaps differing from the last-known ones, and RGB from the saved ones
+    st.keymap_restore = Some([CTRL_ALT_M; 8]);
+    st.rgb_mismatch = true;
+    assert_eq!(st.device_sync(), DeviceSync { colors: Saved, keymaps: OutOfSync, rgb: OutOfSync });
+}
+
+#[test]
+fn local_http_requests_are_bounded() {
+    use crate::http::read_request;
+
+    let request = read_request(&b"GET /bundle HTTP/1.1\r\nX-Pair-Code: 123456\r\nContent-Length: 4\r\n\r\nbodyextra"[..], 16).unwrap();
+    assert_eq!((request.method.as_str(), request.path.as_str()), ("GET", "/bundle"));
+    assert_eq!((request.header("x-pair-code"), &request.body[..]), (Some("123456"), &b"body"[..]));
+    assert_eq!(read_request(&b"POST / HTTP/1.1\r\nContent-Length: 17\r\n\r\n"[..], 16).err(), Some("413 Payload Too Large"));
+    // A line that never ends stops at the head's limit
+    assert_eq!(read_request(std::io::repeat(b'a'), 16).err(), Some("431 Request Header Fields Too Large"));
+}
+
+#[test]
+fn the_local_api_refuses_web_pages() {
+    use crate::api::refuse_browsers;
+    use tungstenite::handshake::server::{Request, Response};
+
+    let script = Request::builder().uri("/").body(()).unwrap();
+    assert!(refuse_browsers(&script, Response::new(())).is_ok());
+    let page = Request::builder().uri("/").header("Origin", "https://example.com").body(()).unwrap();
+    assert_eq!(refuse_browsers(&page, Response::new(())).unwrap_err().status(), 403);
+}
+
+#[test]
+fn game_state_from_web_pages_is_refused() {
+    use crate::http::read_request;
+
+    let game = read_request(&b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}"[..], 16).unwrap();
+    assert_eq!(crate::game::payload(&game), Ok(serde_json::json!({})));
+    let page = read_request(&b"POST / HTTP/1.1\r\nOrigin: https://example.com\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\r\n{}"[..], 16).unwrap();
+    assert_eq!(crate::game::payload(&page), Err("403 Forbidden"));
+}
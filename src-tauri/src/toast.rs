@@ -0,0 +1,156 @@
+// Desktop toasts with action buttons, for failures that happen while the
+// window is in the tray (the mic behind the audio pipeline is unplugged).
+//
+// Windows only: each toast is shown by a short-lived PowerShell (WinRT needs
+// no extra crate that way, as in media.rs) that waits on the toast's events
+// and prints the argument of the button pressed. The backend handles it, so
+// "Restart pipeline" recovers without opening the window; clicking the toast
+// itself opens the app. Elsewhere the failure is only logged.
+
+use log::info;
+use tauri::AppHandle;
+
+/// A toast button (or the toast body, `OpenApp`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastAction {
+    RestartPipeline,
+    OpenApp,
+}
+
+impl ToastAction {
+    /// What the toast hands back when this is clicked.
+    pub fn argument(self) -> &'static str {
+        match self {
+            Self::RestartPipeline => "restart-pipeline",
+            Self::OpenApp => "open-app",
+        }
+    }
+
+    #[allow(dead_code)] // Windows only
+    pub fn parse(argument: &str) -> Option<Self> {
+        [Self::RestartPipeline, Self::OpenApp].into_iter().find(|a| a.argument() == argument.trim())
+    }
+
+    fn label(self) -> String {
+        crate::i18n::t(match self {
+            Self::RestartPipeline => "toast-restart-pipeline",
+            Self::OpenApp => "toast-open-app",
+        })
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Toast XML: a title, a line of text and one button per action.
+pub fn xml(title: &str, body: &str, actions: &[ToastAction]) -> String {
+    let buttons: String = actions
+        .iter()
+        .map(|a| format!(r#"<action content="{}" arguments="{}" activationType="foreground"/>"#, escape(&a.label()), a.argument()))
+        .collect();
+    format!(
+        r#"<toast launch="{}"><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual><actions>{}</actions></toast>"#,
+        ToastAction::OpenApp.argument(),
+        escape(title),
+        escape(body),
+        buttons,
+    )
+}
+
+/// The audio pipeline's input stream failed.
+pub fn pipeline_stopped(app: &AppHandle, error: Option<&str>) {
+    let body = match error {
+        Some(e) => crate::i18n::t_with("toast-pipeline-stopped-because", &[("error", &e)]),
+        None => crate::i18n::t("toast-pipeline-stopped-body"),
+    };
+    show(app, &crate::i18n::t("toast-pipeline-stopped"), &body, &[ToastAction::RestartPipeline, ToastAction::OpenApp]);
+}
+
+pub fn show(app: &AppHandle, title: &str, body: &str, actions: &[ToastAction]) {
+    info!("[toast] {title}: {body}");
+    platform::show(app, xml(title, body, actions));
+}
+
+#[allow(dead_code)] // Windows only
+fn handle(app: &AppHandle, action: ToastAction) {
+    use tauri::Manager;
+
+    info!("[toast] {:?}", action);
+    match action {
+        ToastAction::RestartPipeline => {
+            crate::try_auto_start_pipeline(app, &app.state(), &app.state());
+            let _ = tauri::Emitter::emit(app, "state-updated", app.state::<crate::state::SharedState>().lock().unwrap().snapshot());
+        }
+        ToastAction::OpenApp => crate::show_main_window(app),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::io::{BufRead, BufReader};
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    use log::warn;
+    use tauri::AppHandle;
+
+    use super::{handle, ToastAction};
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    /// Shows the toast in `DECK8_TOAST_XML` and prints the argument it's
+    /// activated with, if it is before being dismissed.
+    const SCRIPT: &str = r#"
+$null = [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime]
+$null = [Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime]
+$xml = New-Object Windows.Data.Xml.Dom.XmlDocument
+$xml.LoadXml($env:DECK8_TOAST_XML)
+$toast = New-Object Windows.UI.Notifications.ToastNotification $xml
+Register-ObjectEvent -InputObject $toast -EventName Activated -SourceIdentifier activated | Out-Null
+Register-ObjectEvent -InputObject $toast -EventName Dismissed -SourceIdentifier dismissed | Out-Null
+Register-ObjectEvent -InputObject $toast -EventName Failed -SourceIdentifier failed | Out-Null
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:DECK8_TOAST_APP).Show($toast)
+$e = Wait-Event -Timeout 600
+if ($e -and $e.SourceIdentifier -eq 'activated') {
+  [Console]::Out.WriteLine(([Windows.UI.Notifications.ToastActivatedEventArgs]$e.SourceArgs[1]).Arguments)
+}
+"#;
+    /// Unpackaged dev builds have no Start menu entry to show toasts under.
+    const POWERSHELL_APP_ID: &str = r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+    pub fn show(app: &AppHandle, xml: String) {
+        let app_id = if cfg!(debug_assertions) { POWERSHELL_APP_ID.to_string() } else { app.config().identifier.clone() };
+        let child = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+            .env("DECK8_TOAST_XML", xml)
+            .env("DECK8_TOAST_APP", app_id)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .creation_flags(CREATE_NO_WINDOW)
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                warn!("[toast] Failed to start PowerShell: {e}");
+                return;
+            }
+        };
+        let app = app.clone();
+        std::thread::spawn(move || {
+            if let Some(stdout) = child.stdout.take() {
+                let action = BufReader::new(stdout).lines().map_while(Result::ok).find_map(|l| ToastAction::parse(&l));
+                if let Some(action) = action {
+                    handle(&app, action);
+                }
+            }
+            let _ = child.wait();
+        });
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use tauri::AppHandle;
+
+    pub fn show(_app: &AppHandle, _xml: String) {}
+}
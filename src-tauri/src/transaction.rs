@@ -0,0 +1,114 @@
+// All-or-nothing device writes for applying a theme or saved RGB settings.
+//
+// Callers stage the before/after of what they're about to change, `commit`
+// it, and only touch `AppState` (and persist) once it returns Ok. RGB settings
+// are read back after writing (key colors can't be read from the firmware),
+// and a stage that fails is retried once. When it fails again,
+// every stage written so far (the failing one included) is put back to its
+// "before" values and the error says so, leaving the device as it was.
+//
+// Keymap batches have their own verify-and-roll-back in
+// `Deck8Device::write_keymaps`.
+
+use anyhow::{bail, Result};
+use log::{error, info, warn};
+
+use crate::hid::Deck8Device;
+use crate::protocol::RgbMatrixState;
+use crate::state::KeyConfig;
+
+#[derive(Default)]
+pub struct DeviceTransaction {
+    keys: Option<([KeyConfig; 8], [KeyConfig; 8])>,
+    /// Effect, speed and color; brightness follows DND and the global dimmer,
+    /// so callers write it themselves.
+    rgb: Option<(RgbMatrixState, RgbMatrixState)>,
+}
+
+#[derive(Clone, Copy)]
+enum Stage {
+    Rgb,
+    Keys,
+}
+
+impl DeviceTransaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every key's override and active color, from `before` to `after`.
+    pub fn keys(mut self, before: &[KeyConfig; 8], after: &[KeyConfig; 8]) -> Self {
+        self.keys = Some((before.clone(), after.clone()));
+        self
+    }
+
+    pub fn rgb(mut self, before: RgbMatrixState, after: RgbMatrixState) -> Self {
+        self.rgb = Some((before, after));
+        self
+    }
+
+    /// Write every stage; on failure, roll back and return the error.
+    pub fn commit(&self, dev: &Deck8Device) -> Result<()> {
+        let stages = [(Stage::Rgb, self.rgb.is_some()), (Stage::Keys, self.keys.is_some())];
+        for (n, &(stage, staged)) in stages.iter().enumerate() {
+            if !staged {
+                continue;
+            }
+            let Err(e) = self.write(dev, stage, false).or_else(|e| {
+                warn!("[transaction] {} failed ({:#}), retrying", stage.name(), e);
+                self.write(dev, stage, false)
+            }) else {
+                continue;
+            };
+            let mut stranded = Vec::new();
+            for &(done, staged) in stages[..=n].iter().rev() {
+                if staged {
+                    if let Err(e) = self.write(dev, done, true) {
+                        error!("[transaction] rolling back {} FAILED: {:#}", done.name(), e);
+                        stranded.push(done.name());
+                    }
+                }
+            }
+            if stranded.is_empty() {
+                bail!("Writing {} failed, nothing was changed: {e:#}", stage.name());
+            }
+            bail!("Writing {} failed: {e:#}; {} couldn't be put back", stage.name(), stranded.join(" and "));
+        }
+        info!("[transaction] committed");
+        Ok(())
+    }
+
+    fn write(&self, dev: &Deck8Device, stage: Stage, rollback: bool) -> Result<()> {
+        match stage {
+            Stage::Rgb => {
+                let Some((before, after)) = self.rgb else { return Ok(()) };
+                let rgb = if rollback { before } else { after };
+                dev.rgb_set_effect(rgb.effect)?;
+                dev.rgb_set_speed(rgb.speed)?;
+                dev.rgb_set_color(rgb.color_h, rgb.color_s)?;
+                let read = dev.rgb_get_state()?;
+                if (read.effect, read.speed, read.color_h, read.color_s) != (rgb.effect, rgb.speed, rgb.color_h, rgb.color_s) {
+                    bail!("RGB settings read back as {:?}", read);
+                }
+                dev.rgb_synced();
+            }
+            Stage::Keys => {
+                let Some((ref before, ref after)) = self.keys else { return Ok(()) };
+                let keys = if rollback { before } else { after };
+                for (i, key) in keys.iter().enumerate() {
+                    crate::write_key_to_device(dev, i as u8, key)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Stage {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rgb => "RGB settings",
+            Self::Keys => "key colors",
+        }
+    }
+}
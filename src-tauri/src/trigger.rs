@@ -0,0 +1,196 @@
+// Key sound trigger: the press → sound path, kept under ~50 ms.
+//
+// `do_toggle_key` picks the key's sound under the state lock and hands it to
+// one long-lived thread over a channel before any LED is written, so the
+// sound starts while the HID writes go out instead of after them. The path is
+// joined onto the cached sounds folder without touching the disk
+// (`audio::sound_path`); the file is only looked for when playing fails, to
+// tell a missing sound from a broken one. Key sounds come decoded from the
+// pipeline's cache (`preload_key_sounds`) and local copies go through its
+// monitor thread. Key releases take the same channel, so a momentary sound
+// can't be released before it started.
+//
+// Without the soundboard, sounds play on the default output from a second
+// long-lived thread that owns the output stream (cpal streams are !Send on
+// Windows) and keeps the last `LOCAL_CACHE` sounds decoded, so a press
+// neither decodes on the trigger thread nor spawns a thread of its own.
+//
+// Each sound logs how long it took from the press; over `TARGET` warns.
+
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use log::{error, info, warn};
+use rodio::buffer::SamplesBuffer;
+use rodio::source::Buffered;
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::audio;
+use crate::state::{ManagedAudioPipeline, SoundMode, SoundRoute};
+
+const TARGET: Duration = Duration::from_millis(50);
+
+/// Sounds kept decoded for playing without the soundboard.
+const LOCAL_CACHE: usize = 16;
+
+pub struct Trigger {
+    pub key_index: usize,
+    pub sound_id: String,
+    pub filename: String,
+    pub route: SoundRoute,
+    pub gain: f32,
+    pub mode: SoundMode,
+    pub pressed: Instant,
+}
+
+enum Command {
+    Play(Trigger),
+    Release(usize),
+}
+
+static SENDER: OnceLock<mpsc::Sender<Command>> = OnceLock::new();
+
+/// Sounds (path, gain) for the default-output thread, started on first use.
+static LOCAL: OnceLock<mpsc::Sender<(PathBuf, f32)>> = OnceLock::new();
+
+pub fn start(app: AppHandle) {
+    let (tx, rx) = mpsc::channel();
+    if SENDER.set(tx).is_err() {
+        return;
+    }
+    std::thread::spawn(move || {
+        for command in rx {
+            run(&app, command);
+        }
+    });
+}
+
+/// Play a key's sound on the trigger thread. Doesn't lock the state, so it
+/// can be called with it held.
+pub fn play(app: &AppHandle, trigger: Trigger) {
+    send(app, Command::Play(trigger));
+}
+
+/// Release a momentary key's sound, after any play still queued.
+pub fn release(app: &AppHandle, key_index: usize) {
+    send(app, Command::Release(key_index));
+}
+
+fn send(app: &AppHandle, command: Command) {
+    let Some(Err(mpsc::SendError(command))) = SENDER.get().map(|tx| tx.send(command)) else { return };
+    // The thread is gone: a thread of its own (the caller may hold the state lock)
+    let app = app.clone();
+    std::thread::spawn(move || run(&app, command));
+}
+
+fn run(app: &AppHandle, command: Command) {
+    match command {
+        Command::Play(trigger) => {
+            if !play_now(app, &trigger) {
+                crate::mark_sound_missing(app, &trigger.sound_id);
+            }
+        }
+        Command::Release(key_index) => {
+            if let Some(ref pipeline) = *app.state::<ManagedAudioPipeline>().0.lock().unwrap() {
+                pipeline.stop_held(key_index);
+            }
+        }
+    }
+}
+
+/// Play the sound now. False when its file is missing.
+pub fn play_now<R: Runtime>(app: &AppHandle<R>, trigger: &Trigger) -> bool {
+    let path = match audio::sound_path(&trigger.filename) {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("[trigger] key={} {e:#}", trigger.key_index);
+            return true;
+        }
+    };
+    let pipeline_state = app.state::<ManagedAudioPipeline>();
+    let pl = pipeline_state.0.lock().unwrap();
+    if let Some(ref pipeline) = *pl {
+        let played = match trigger.mode {
+            SoundMode::OneShot => pipeline.play_sound(&path, trigger.gain, trigger.route),
+            SoundMode::Momentary => pipeline.play_held(trigger.key_index, &path, trigger.gain, trigger.route),
+        };
+        if let Err(e) = played {
+            if !found(&path, trigger) {
+                return false;
+            }
+            warn!("[audio] Failed to play sound for key {}: {}", trigger.key_index, e);
+            return true;
+        }
+    } else {
+        // Fallback: play through default output when soundboard is not running
+        // (always the whole sound)
+        drop(pl);
+        if !found(&path, trigger) {
+            return false;
+        }
+        play_locally(path, trigger.gain);
+    }
+    let latency = trigger.pressed.elapsed();
+    if latency > TARGET {
+        warn!("[trigger] key={} sound={} took {:?} from the press", trigger.key_index, trigger.filename, latency);
+    } else {
+        info!("[trigger] key={} sound={} in {:?}", trigger.key_index, trigger.filename, latency);
+    }
+    true
+}
+
+fn found(path: &Path, trigger: &Trigger) -> bool {
+    let found = path.exists();
+    if !found {
+        warn!("[audio] Sound file for key {} is missing: {}", trigger.key_index, trigger.filename);
+    }
+    found
+}
+
+fn play_locally(path: PathBuf, gain: f32) {
+    let tx = LOCAL.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || local_player(rx));
+        tx
+    });
+    let _ = tx.send((path, gain));
+}
+
+/// Play each sound on a sink of its own, so they overlap like on the
+/// soundboard. Cached by path and modification time, most recent last.
+fn local_player(rx: mpsc::Receiver<(PathBuf, f32)>) {
+    let mut output: Option<(OutputStream, OutputStreamHandle)> = None;
+    let mut cache: Vec<(PathBuf, Option<SystemTime>, Buffered<SamplesBuffer<f32>>)> = Vec::new();
+    for (path, gain) in rx {
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let sound = match cache.iter().position(|(p, m, _)| *p == path && *m == modified) {
+            Some(at) => cache.remove(at).2,
+            None => match audio::decode_trimmed(&path.to_string_lossy(), &audio::Trim::whole()) {
+                Ok((channels, sample_rate, samples)) => SamplesBuffer::new(channels, sample_rate, samples).buffered(),
+                Err(e) => {
+                    warn!("[audio] Fallback play failed for {}: {e:#}", path.display());
+                    continue;
+                }
+            },
+        };
+        cache.retain(|(p, _, _)| *p != path);
+        if cache.len() >= LOCAL_CACHE {
+            cache.remove(0);
+        }
+        cache.push((path, modified, sound.clone()));
+        if output.is_none() {
+            output = audio::open_default_output().map_err(|e| error!("[audio] Failed to open default output: {e}")).ok();
+        }
+        let Some((_, ref handle)) = output else { continue };
+        match Sink::try_new(handle) {
+            Ok(sink) => {
+                sink.set_volume(gain);
+                sink.append(sound);
+                sink.detach();
+            }
+            Err(e) => error!("[audio] Failed to create sink: {e}"),
+        }
+    }
+}
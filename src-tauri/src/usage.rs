@@ -0,0 +1,165 @@
+// Usage statistics: how often each key, sound and action gets used.
+//
+// Counters live in a static (they're bumped from shortcut handlers that don't
+// hold the state lock) and are written to usage.json next to state.json at
+// most every FLUSH_INTERVAL, plus on quit, so a burst of presses doesn't hit
+// the disk each time.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::now_ms;
+use crate::state::AppState;
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Uses of one key, sound or action. Times are Unix ms.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Counter {
+    pub count: u64,
+    pub first_ms: u64,
+    pub last_ms: u64,
+}
+
+impl Counter {
+    fn bump(&mut self, now: u64) {
+        if self.count == 0 {
+            self.first_ms = now;
+        }
+        self.count += 1;
+        self.last_ms = now;
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageStats {
+    /// When counting started (first run or last reset).
+    pub since_ms: u64,
+    /// Presses per LED index.
+    pub keys: [Counter; 8],
+    /// Plays per sound library id.
+    pub sounds: BTreeMap<String, Counter>,
+    /// Runs per action, e.g. `replay:Ctrl+Alt+KeyM`, `slot:3:Muted`, `dnd`.
+    pub actions: BTreeMap<String, Counter>,
+}
+
+struct Store {
+    stats: UsageStats,
+    dirty: bool,
+    flushed_at: Instant,
+}
+
+static STORE: Mutex<Option<Store>> = Mutex::new(None);
+
+fn usage_file() -> Result<PathBuf> {
+    Ok(crate::profile::config_dir()?.join("usage.json"))
+}
+
+fn with_store<T>(f: impl FnOnce(&mut Store) -> T) -> T {
+    let mut store = STORE.lock().unwrap();
+    let store = store.get_or_insert_with(|| Store {
+        stats: usage_file().ok()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(|| UsageStats { since_ms: now_ms(), ..Default::default() }),
+        dirty: false,
+        flushed_at: Instant::now(),
+    });
+    f(store)
+}
+
+fn record(bump: impl FnOnce(&mut UsageStats, u64)) {
+    with_store(|store| {
+        bump(&mut store.stats, now_ms());
+        store.dirty = true;
+        if store.flushed_at.elapsed() >= FLUSH_INTERVAL {
+            flush_store(store);
+        }
+    });
+}
+
+fn flush_store(store: &mut Store) {
+    store.flushed_at = Instant::now();
+    if !store.dirty {
+        return;
+    }
+    let result = usage_file().and_then(|path| {
+        let json = serde_json::to_string(&store.stats).context("Failed to serialize usage stats")?;
+        fs::write(path, json).context("Failed to write usage stats")
+    });
+    match result {
+        Ok(()) => store.dirty = false,
+        Err(e) => error!("[usage] {e:#}"),
+    }
+}
+
+pub fn record_key(key_index: usize) {
+    record(|s, now| s.keys[key_index].bump(now));
+}
+
+pub fn record_sound(sound_id: &str) {
+    record(|s, now| s.sounds.entry(sound_id.to_owned()).or_default().bump(now));
+}
+
+pub fn record_action(action: &str) {
+    record(|s, now| s.actions.entry(action.to_owned()).or_default().bump(now));
+}
+
+/// Write pending counts to disk now (on quit).
+pub fn flush() {
+    with_store(flush_store);
+}
+
+pub fn stats() -> UsageStats {
+    with_store(|store| store.stats.clone())
+}
+
+/// Start counting from zero.
+pub fn reset() {
+    with_store(|store| {
+        store.stats = UsageStats { since_ms: now_ms(), ..Default::default() };
+        store.dirty = true;
+        flush_store(store);
+    });
+}
+
+/// One row per key, sound and action, named the way the UI shows them.
+pub fn to_csv(stats: &UsageStats, st: &AppState) -> String {
+    let mut out = String::from("kind,id,name,count,first_used_ms,last_used_ms\n");
+    let mut row = |kind: &str, id: &str, name: &str, c: &Counter| {
+        out.push_str(&format!("{kind},{},{},{},{},{}\n", csv_field(id), csv_field(name), c.count, c.first_ms, c.last_ms));
+    };
+    for (i, c) in stats.keys.iter().enumerate() {
+        row("key", &i.to_string(), &st.keys[i].display_name(i), c);
+    }
+    for (id, c) in &stats.sounds {
+        let name = st.audio_config.sound_library.iter()
+            .find(|e| &e.id == id)
+            .map_or("(removed)", |e| e.display_name.as_str());
+        row("sound", id, name, c);
+    }
+    for (id, c) in &stats.actions {
+        row("action", id, id, c);
+    }
+    out
+}
+
+/// Quote a CSV field when it holds a separator, quote or newline.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+pub fn export_csv(path: &Path, st: &AppState) -> Result<()> {
+    fs::write(path, to_csv(&stats(), st)).context("Failed to write CSV")
+}
@@ -0,0 +1,140 @@
+// Linux soundboard output: a PipeWire/PulseAudio null sink ("Deck8 Soundboard")
+// the pipeline plays into, plus a virtual mic reading its monitor, so voice
+// apps can pick up mic + sounds without the user setting up a loopback first.
+//
+// Everything goes through `pactl`, which PipeWire serves too (pipewire-pulse).
+// The sink is loaded when a pipeline first starts on it and stays loaded
+// through pipeline restarts (voice apps would lose the mic otherwise) until
+// a pipeline starts on another output or the app exits; modules already
+// there, from a crash or an earlier pipeline, are reused instead of loaded
+// twice.
+//
+// cpal only sees ALSA devices, so the pipeline opens the `pulse` ALSA device
+// and its new stream is moved onto the sink. Every stream the app opens goes
+// by the same name there, so the pipeline holds `OPENING` from before it
+// opens its stream until it's claimed, and the app's other playback (local
+// copies, previews, earcons) opens its streams under it too, leaving the
+// pipeline's as the only new one.
+
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use log::{info, warn};
+
+/// Shown as an output device; selecting it creates the sink.
+pub const NAME: &str = "Deck8 Soundboard";
+const SINK: &str = "deck8_soundboard";
+const SOURCE: &str = "deck8_soundboard_mic";
+/// ALSA devices that route through the sound server, in order of preference.
+pub const ALSA_DEVICES: [&str; 3] = ["pulse", "pipewire", "default"];
+/// How long to wait for the pipeline's stream to show up in the server.
+const CLAIM_ATTEMPTS: u32 = 10;
+const CLAIM_WAIT: Duration = Duration::from_millis(50);
+
+/// Held while a playback stream is opened (see above).
+pub static OPENING: Mutex<()> = Mutex::new(());
+
+/// Sink and mic modules in use, to unload.
+static MODULES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+fn pactl(args: &[&str]) -> Result<String> {
+    let out = Command::new("pactl").args(args).output().context("pactl not found")?;
+    if !out.status.success() {
+        bail!("pactl {} failed: {}", args[0], String::from_utf8_lossy(&out.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Whether the sink can be offered (Linux with a Pulse-compatible server).
+pub fn available() -> bool {
+    cfg!(target_os = "linux") && pactl(&["info"]).is_ok()
+}
+
+/// Ids of the modules `pactl list short modules` shows for our sink and mic.
+pub fn parse_modules(list: &str) -> Vec<u32> {
+    list.lines()
+        .filter(|line| {
+            line.contains(&format!("sink_name={SINK}")) || line.contains(&format!("source_name={SOURCE}"))
+        })
+        .filter_map(|line| line.split('\t').next()?.trim().parse().ok())
+        .collect()
+}
+
+/// Ids of the sink inputs owned by process `pid` in `pactl list sink-inputs`.
+pub fn parse_sink_inputs(list: &str, pid: u32) -> Vec<u32> {
+    let owner = format!("application.process.id = \"{pid}\"");
+    list.split("Sink Input #")
+        .skip(1)
+        .filter(|block| block.contains(&owner))
+        .filter_map(|block| block.lines().next()?.trim().parse().ok())
+        .collect()
+}
+
+/// This process' playback streams.
+pub fn own_streams() -> Vec<u32> {
+    pactl(&["list", "sink-inputs"])
+        .map(|list| parse_sink_inputs(&list, std::process::id()))
+        .unwrap_or_default()
+}
+
+/// Load the sink and mic unless they're there already.
+pub fn create() -> Result<()> {
+    if !available() {
+        bail!("{NAME} needs PipeWire or PulseAudio (pactl)");
+    }
+    let mut modules = parse_modules(&pactl(&["list", "short", "modules"])?);
+    if modules.is_empty() {
+        let sink = format!("sink_name={SINK}");
+        let sink_props = format!("sink_properties=device.description=\"{NAME}\"");
+        modules.push(load(&["module-null-sink", &sink, &sink_props])?);
+        let master = format!("master={SINK}.monitor");
+        let source = format!("source_name={SOURCE}");
+        let source_props = format!("source_properties=device.description=\"{NAME} Mic\"");
+        match load(&["module-remap-source", &master, &source, &source_props]) {
+            Ok(id) => modules.push(id),
+            // Apps can still record the sink's monitor
+            Err(e) => warn!("[audio] {NAME} Mic not created: {e:#}"),
+        }
+        info!("[audio] Created {NAME} sink");
+    }
+    *MODULES.lock().unwrap() = modules;
+    Ok(())
+}
+
+/// Move the streams this process opened since `before` onto the sink.
+pub fn claim_streams(before: &[u32]) -> Result<()> {
+    for _ in 0..CLAIM_ATTEMPTS {
+        let new: Vec<u32> = own_streams().into_iter().filter(|id| !before.contains(id)).collect();
+        if !new.is_empty() {
+            for id in new {
+                pactl(&["move-sink-input", &id.to_string(), SINK])?;
+            }
+            return Ok(());
+        }
+        std::thread::sleep(CLAIM_WAIT);
+    }
+    bail!("The soundboard stream didn't show up in the sound server")
+}
+
+fn load(args: &[&str]) -> Result<u32> {
+    let id = pactl(&[&["load-module"], args].concat())?;
+    id.trim().parse().with_context(|| format!("Unexpected module id from pactl: {id}"))
+}
+
+/// Unload the sink and mic, if loaded. Streams still on the sink fall back
+/// to the default output.
+pub fn remove() {
+    let modules = std::mem::take(&mut *MODULES.lock().unwrap());
+    if modules.is_empty() {
+        return;
+    }
+    // The mic reads the sink's monitor, so it goes first
+    for id in modules.iter().rev() {
+        if let Err(e) = pactl(&["unload-module", &id.to_string()]) {
+            warn!("[audio] {e:#}");
+        }
+    }
+    info!("[audio] Removed {NAME} sink");
+}
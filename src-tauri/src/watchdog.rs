@@ -0,0 +1,53 @@
+// Device watchdog.
+//
+// A request the device never acks blocks whoever holds the state lock, and
+// each further report in the sequence would wait out its own read timeout.
+// `hid::Health` tracks how long the device has gone without an ack; this
+// thread checks it without taking the state lock, marks the device unhealthy
+// past `hid::DEADLINE` (so the rest of the sequence fails fast and the lock is
+// released), then runs the normal reconnect path.
+
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::hid::Health;
+use crate::state::SharedState;
+
+const POLL: Duration = Duration::from_millis(250);
+
+static WATCHED: Mutex<Option<Weak<Health>>> = Mutex::new(None);
+
+/// Watch a newly opened device instead of the previous one.
+pub fn watch(health: &Arc<Health>) {
+    *WATCHED.lock().unwrap() = Some(Arc::downgrade(health));
+}
+
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL);
+        let health = WATCHED.lock().unwrap().as_ref().and_then(Weak::upgrade);
+        if health.is_some_and(|h| h.check_at(Instant::now())) {
+            WATCHED.lock().unwrap().take();
+            recover(&app);
+        }
+    });
+}
+
+fn recover(app: &AppHandle) {
+    warn!("[watchdog] Device hung, reconnecting");
+    let _ = app.emit("device-unhealthy", ());
+    // Blocks until the hung call gives up the state lock
+    if !crate::connect_device(app.clone(), app.state::<SharedState>()) {
+        return;
+    }
+    let state = app.state::<SharedState>();
+    let hung = state.lock().unwrap().device.as_ref().is_some_and(|d| d.health().is_unhealthy());
+    if hung {
+        // Hung again while syncing: release it instead of reconnecting in a loop
+        info!("[watchdog] Device still not responding, releasing it");
+        crate::disconnect_device(app.clone(), state);
+    }
+}
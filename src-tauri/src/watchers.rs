@@ -0,0 +1,455 @@
+// Slot bindings: a background thread evaluates each key's `SlotBinding`
+// condition and switches the key to `when_true` / `when_false` whenever the
+// result changes, so the LEDs act as live status indicators.
+//
+// Each binding's condition is evaluated every `poll_ms` on a worker thread of
+// its own, and the watcher thread applies the latest result, so a dead host
+// (DNS and connect can block for long) only holds up its own key; a check
+// running past `EVAL_TIMEOUT` is reported as an error. MQTT is push-based:
+// one subscription thread per (broker, topic) keeps the last payload, and
+// polling just compares against it (OBS, Teams and now playing work the same
+// way, see obs.rs, meeting.rs, discord.rs and media.rs). Calendars are fetched
+// every few minutes in the background (calendar.rs). Evaluation errors keep
+// the current slot and are logged once per distinct message.
+//
+// A manual toggle on a bound key sticks until the condition changes again.
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::meeting::Meeting;
+use crate::state::{Condition, SharedState, SlotBinding};
+
+const TICK: Duration = Duration::from_millis(100);
+
+/// Connect/read timeout for HTTP checks.
+const NET_TIMEOUT: Duration = Duration::from_secs(2);
+/// A check still running after this is reported (and keeps the slot).
+const EVAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+static MQTT: LazyLock<Mutex<MqttSubscriptions>> = LazyLock::new(Default::default);
+
+struct Watch {
+    binding: SlotBinding,
+    last: Option<bool>,
+    last_error: Option<String>,
+    worker: Worker,
+}
+
+/// Evaluates one binding's condition every `poll_ms` until dropped.
+struct Worker {
+    /// Latest result, until the watcher thread takes it.
+    result: Arc<Mutex<Option<Result<bool, String>>>>,
+    /// When the check running started.
+    started: Arc<Mutex<Option<Instant>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Worker {
+    fn start(app: &AppHandle, binding: &SlotBinding) -> Self {
+        let result = Arc::new(Mutex::new(None));
+        let started = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (app, condition, poll) = (app.clone(), binding.condition.clone(), Duration::from_millis(binding.poll_ms));
+        let (thread_result, thread_started, thread_stop) = (result.clone(), started.clone(), stop.clone());
+        std::thread::spawn(move || {
+            #[cfg(target_os = "windows")]
+            mic::init_com();
+
+            while !thread_stop.load(Ordering::Relaxed) {
+                *thread_started.lock().unwrap() = Some(Instant::now());
+                let value = evaluate(&app, &condition).map_err(|e| format!("{e:#}"));
+                *thread_started.lock().unwrap() = None;
+                *thread_result.lock().unwrap() = Some(value);
+                std::thread::sleep(poll);
+            }
+        });
+        Self { result, started, stop }
+    }
+
+    /// The result of the last check, once; a check stuck past `EVAL_TIMEOUT`
+    /// is an error until it ends.
+    fn take(&self) -> Option<Result<bool, String>> {
+        if let Some(result) = self.result.lock().unwrap().take() {
+            return Some(result);
+        }
+        let started = *self.started.lock().unwrap();
+        started
+            .filter(|t| t.elapsed() >= EVAL_TIMEOUT)
+            .map(|_| Err(crate::i18n::t_with("error-no-answer", &[("seconds", &EVAL_TIMEOUT.as_secs())])))
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Start the watcher thread (once, at startup).
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || run(app));
+}
+
+fn run(app: AppHandle) {
+    let mut watches: [Option<Watch>; 8] = Default::default();
+    loop {
+        let (bindings, now_playing, discord, profile) = {
+            let state = app.state::<SharedState>();
+            let st = state.lock().unwrap();
+            let bindings: Vec<Option<SlotBinding>> = st.keys.iter().map(|k| k.binding.clone()).collect();
+            (bindings, st.settings.now_playing, st.settings.discord.clone(), st.active_theme().map(str::to_string))
+        };
+
+        for (key_index, binding) in bindings.iter().enumerate() {
+            let Some(binding) = binding else {
+                watches[key_index] = None;
+                continue;
+            };
+            // A new or edited binding starts over (and applies on first result)
+            if watches[key_index].as_ref().is_none_or(|w| w.binding != *binding) {
+                watches[key_index] = Some(Watch {
+                    binding: binding.clone(),
+                    last: None,
+                    last_error: None,
+                    worker: Worker::start(&app, binding),
+                });
+            }
+            let w = watches[key_index].as_mut().unwrap();
+            match w.worker.take() {
+                Some(Ok(value)) => {
+                    w.last_error = None;
+                    if w.last != Some(value) {
+                        w.last = Some(value);
+                        let slot = if value { &w.binding.when_true } else { &w.binding.when_false };
+                        select_slot(&app, key_index, slot);
+                    }
+                }
+                Some(Err(msg)) => {
+                    if w.last_error.as_ref() != Some(&msg) {
+                        warn!("[watch] key={} {:?}: {}", key_index, w.binding.condition, msg);
+                        w.last_error = Some(msg);
+                    }
+                }
+                None => {}
+            }
+        }
+
+        // Drop MQTT subscriptions no binding uses anymore
+        MQTT.lock().unwrap().retain(|key| {
+            bindings.iter().flatten().any(|b| matches!(&b.condition,
+                Condition::Mqtt { host, port, topic, .. } if (host, port, topic) == (&key.0, &key.1, &key.2)))
+        });
+        crate::obs::retain(&bindings.iter().flatten().filter_map(|b| crate::obs::instance(&b.condition)).collect::<Vec<_>>());
+        crate::discord::update(&discord, bindings.iter().flatten().any(|b| crate::discord::follows(&b.condition)), profile);
+        crate::calendar::retain(&bindings.iter().flatten().filter_map(|b| match &b.condition {
+            Condition::CalendarMeeting { url, .. } => Some(url.as_str()),
+            _ => None,
+        }).collect::<Vec<_>>());
+        crate::meeting::retain_teams(bindings.iter().flatten().any(|b| b.condition == Condition::TeamsMuted));
+        crate::media::set_active(&app, now_playing || bindings.iter().flatten().any(|b| b.condition == Condition::MediaPlaying));
+
+        std::thread::sleep(TICK);
+    }
+}
+
+/// Switch a key to `slot`, push it to the device and tell the frontend.
+fn select_slot(app: &AppHandle, key_index: usize, slot: &str) {
+    let state = app.state::<SharedState>();
+    let snapshot = {
+        let mut st = state.lock().unwrap();
+        let before = st.keys[key_index].active_slot;
+        let from = st.keys[key_index].active().color;
+        if let Err(e) = st.keys[key_index].select_slot(slot) {
+            warn!("[watch] key={} {}", key_index, e);
+            return;
+        }
+        if st.keys[key_index].active_slot == before {
+            return;
+        }
+        info!("[watch] key={} → slot {:?}", key_index, slot);
+        crate::fade::transition(app, &st, key_index, from);
+        crate::announce_slot(app, &st, key_index);
+        crate::usage::record_action(&format!("binding:{key_index}:{slot}"));
+        crate::persist_state(&st);
+        st.snapshot()
+    };
+    let _ = app.emit("state-updated", &snapshot);
+}
+
+fn evaluate(app: &AppHandle, condition: &Condition) -> Result<bool> {
+    match condition {
+        Condition::MicMuted => mic::muted(),
+        Condition::ObsRecording { .. } | Condition::ObsStreaming { .. } | Condition::ObsScene { .. } => {
+            crate::obs::state(condition)
+        }
+        Condition::FileExists { path } => Ok(std::path::Path::new(path).exists()),
+        Condition::Mqtt { host, port, topic, payload } => MQTT.lock().unwrap().matches(host, *port, topic, payload),
+        Condition::HttpPoll { url, contains } => http_poll(url, contains.as_deref()),
+        Condition::TeamsMuted => crate::meeting::muted(Meeting::Teams),
+        Condition::ZoomMuted => crate::meeting::muted(Meeting::Zoom),
+        Condition::MediaPlaying => crate::media::playing(),
+        Condition::MixerMuted { mixer, channel } => crate::mixer::muted(*mixer, channel),
+        Condition::SoundboardRunning => Ok(crate::soundboard_running(app)),
+        Condition::FocusRunning => Ok(crate::pomodoro::running_until().is_some()),
+        Condition::DiscordMuted | Condition::DiscordSpeaking => crate::discord::state(condition),
+        Condition::ModeActive { mode } => {
+            Ok(app.state::<SharedState>().lock().unwrap().active_mode.as_ref() == Some(mode))
+        }
+        Condition::CalendarMeeting { url, lead_min, .. } => crate::calendar::in_meeting(url, *lead_min),
+    }
+}
+
+// ── HTTP ────────────────────────────────────────────────────────────────
+
+fn http_poll(url: &str, contains: Option<&str>) -> Result<bool> {
+    let agent = ureq::AgentBuilder::new().timeout(NET_TIMEOUT).build();
+    match agent.get(url).call() {
+        Ok(resp) => match contains {
+            Some(needle) => Ok(resp.into_string().context("Failed to read response body")?.contains(needle)),
+            None => Ok(true),
+        },
+        // A non-2xx answer is a valid "false"; only transport failures are errors
+        Err(ureq::Error::Status(_, _)) => Ok(false),
+        Err(e) => Err(anyhow!("GET {url} failed: {e}")),
+    }
+}
+
+// ── MQTT ────────────────────────────────────────────────────────────────
+
+/// (host, port, topic)
+type MqttKey = (String, u16, String);
+
+#[derive(Default)]
+struct MqttSubscriptions(HashMap<MqttKey, MqttSubscription>);
+
+#[derive(Default)]
+struct MqttStatus {
+    payload: Option<String>,
+    error: Option<String>,
+}
+
+struct MqttSubscription {
+    client: rumqttc::Client,
+    status: Arc<Mutex<MqttStatus>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl MqttSubscriptions {
+    fn matches(&mut self, host: &str, port: u16, topic: &str, payload: &str) -> Result<bool> {
+        let key = (host.to_string(), port, topic.to_string());
+        let sub = self.0.entry(key).or_insert_with(|| MqttSubscription::new(host, port, topic));
+        let status = sub.status.lock().unwrap();
+        match (&status.payload, &status.error) {
+            (Some(last), _) => Ok(last.trim() == payload),
+            (None, Some(e)) => Err(anyhow!("MQTT {host}:{port}: {e}")),
+            // Nothing published yet
+            (None, None) => Ok(false),
+        }
+    }
+
+    fn retain(&mut self, keep: impl Fn(&MqttKey) -> bool) {
+        self.0.retain(|key, _| keep(key));
+    }
+}
+
+impl MqttSubscription {
+    fn new(host: &str, port: u16, topic: &str) -> Self {
+        use rumqttc::{Client, Event, MqttOptions, Packet, QoS};
+
+        // Unique per subscription: brokers kick the older of two equal ids
+        static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+        let client_id = format!("deck8-hub-{}-{}", std::process::id(), NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        let mut options = MqttOptions::new(client_id, host, port);
+        options.set_keep_alive(Duration::from_secs(30));
+        let (client, mut connection) = Client::new(options, 10);
+
+        let status = Arc::new(Mutex::new(MqttStatus::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (thread_client, thread_status, thread_stop) = (client.clone(), status.clone(), stop.clone());
+        let topic = topic.to_string();
+        std::thread::spawn(move || {
+            // The iterator reconnects by itself after an error
+            for notification in connection.iter() {
+                if thread_stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                match notification {
+                    // (Re)subscribe on every connect: sessions are clean
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        thread_status.lock().unwrap().error = None;
+                        let _ = thread_client.try_subscribe(topic.as_str(), QoS::AtMostOnce);
+                    }
+                    Ok(Event::Incoming(Packet::Publish(p))) if p.topic == topic => {
+                        thread_status.lock().unwrap().payload = Some(String::from_utf8_lossy(&p.payload).into_owned());
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        thread_status.lock().unwrap().error = Some(e.to_string());
+                        std::thread::sleep(Duration::from_secs(2));
+                    }
+                }
+            }
+        });
+
+        Self { client, status, stop }
+    }
+}
+
+impl Drop for MqttSubscription {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.client.try_disconnect();
+    }
+}
+
+// ── Microphone mute ─────────────────────────────────────────────────────
+
+/// Default capture endpoint mute via IAudioEndpointVolume (raw COM vtables).
+#[cfg(target_os = "windows")]
+mod mic {
+    use anyhow::{bail, Result};
+    use std::ffi::c_void;
+    use std::ptr::null_mut;
+
+    #[repr(C)]
+    struct Guid {
+        data1: u32,
+        data2: u16,
+        data3: u16,
+        data4: [u8; 8],
+    }
+
+    const CLSID_MM_DEVICE_ENUMERATOR: Guid = Guid {
+        data1: 0xBCDE0395, data2: 0xE52F, data3: 0x467C,
+        data4: [0x8E, 0x3D, 0xC4, 0x57, 0x92, 0x91, 0x69, 0x2E],
+    };
+    const IID_IMM_DEVICE_ENUMERATOR: Guid = Guid {
+        data1: 0xA95664D2, data2: 0x9614, data3: 0x4F35,
+        data4: [0xA7, 0x46, 0xDE, 0x8D, 0xB6, 0x36, 0x17, 0xE6],
+    };
+    const IID_IAUDIO_ENDPOINT_VOLUME: Guid = Guid {
+        data1: 0x5CDF2C82, data2: 0x841E, data3: 0x4546,
+        data4: [0x97, 0x22, 0x0C, 0xF7, 0x40, 0x78, 0x22, 0x9A],
+    };
+    const CLSCTX_ALL: u32 = 0x17;
+    const COINIT_MULTITHREADED: u32 = 0;
+    const E_CAPTURE: i32 = 1;
+    const E_CONSOLE: i32 = 0;
+
+    type ReleaseFn = unsafe extern "system" fn(*mut c_void) -> u32;
+    type GetDefaultAudioEndpointFn = unsafe extern "system" fn(*mut c_void, i32, i32, *mut *mut c_void) -> i32;
+    type ActivateFn = unsafe extern "system" fn(*mut c_void, *const Guid, u32, *mut c_void, *mut *mut c_void) -> i32;
+    type GetMuteFn = unsafe extern "system" fn(*mut c_void, *mut i32) -> i32;
+
+    #[link(name = "ole32")]
+    extern "system" {
+        fn CoInitializeEx(reserved: *mut c_void, co_init: u32) -> i32;
+        fn CoCreateInstance(
+            clsid: *const Guid,
+            outer: *mut c_void,
+            cls_context: u32,
+            iid: *const Guid,
+            out: *mut *mut c_void,
+        ) -> i32;
+    }
+
+    /// Vtable slot `index` of a COM object (objects start with a vtable pointer).
+    unsafe fn method(obj: *mut c_void, index: usize) -> *const c_void {
+        *(*(obj as *const *const *const c_void)).add(index)
+    }
+
+    unsafe fn release(obj: *mut c_void) {
+        let f = std::mem::transmute::<*const c_void, ReleaseFn>(method(obj, 2));
+        f(obj);
+    }
+
+    /// Called once on each worker thread.
+    pub fn init_com() {
+        unsafe {
+            CoInitializeEx(null_mut(), COINIT_MULTITHREADED);
+        }
+    }
+
+    pub fn muted() -> Result<bool> {
+        unsafe {
+            let mut enumerator = null_mut();
+            let hr = CoCreateInstance(
+                &CLSID_MM_DEVICE_ENUMERATOR, null_mut(), CLSCTX_ALL,
+                &IID_IMM_DEVICE_ENUMERATOR, &mut enumerator,
+            );
+            if hr < 0 {
+                bail!("MMDeviceEnumerator unavailable (0x{:08X})", hr as u32);
+            }
+
+            // IMMDeviceEnumerator::GetDefaultAudioEndpoint
+            let get_default = std::mem::transmute::<*const c_void, GetDefaultAudioEndpointFn>(method(enumerator, 4));
+            let mut device = null_mut();
+            let hr = get_default(enumerator, E_CAPTURE, E_CONSOLE, &mut device);
+            release(enumerator);
+            if hr < 0 {
+                bail!("No default microphone (0x{:08X})", hr as u32);
+            }
+
+            // IMMDevice::Activate
+            let activate = std::mem::transmute::<*const c_void, ActivateFn>(method(device, 3));
+            let mut volume = null_mut();
+            let hr = activate(device, &IID_IAUDIO_ENDPOINT_VOLUME, CLSCTX_ALL, null_mut(), &mut volume);
+            release(device);
+            if hr < 0 {
+                bail!("IAudioEndpointVolume unavailable (0x{:08X})", hr as u32);
+            }
+
+            // IAudioEndpointVolume::GetMute
+            let get_mute = std::mem::transmute::<*const c_void, GetMuteFn>(method(volume, 15));
+            let mut muted = 0;
+            let hr = get_mute(volume, &mut muted);
+            release(volume);
+            if hr < 0 {
+                bail!("GetMute failed (0x{:08X})", hr as u32);
+            }
+            Ok(muted != 0)
+        }
+    }
+}
+
+/// Default PulseAudio/PipeWire source mute.
+#[cfg(target_os = "linux")]
+mod mic {
+    use anyhow::{bail, Context, Result};
+
+    pub fn muted() -> Result<bool> {
+        let out = std::process::Command::new("pactl")
+            .args(["get-source-mute", "@DEFAULT_SOURCE@"])
+            .output()
+            .context("pactl not found (install pulseaudio-utils)")?;
+        if !out.status.success() {
+            bail!("pactl failed: {}", String::from_utf8_lossy(&out.stderr).trim());
+        }
+        // "Mute: yes" / "Mute: no"
+        Ok(String::from_utf8_lossy(&out.stdout).contains("yes"))
+    }
+}
+
+/// macOS has no mute flag for inputs; an input volume of 0 is what the
+/// menu-bar mute utilities set.
+#[cfg(target_os = "macos")]
+mod mic {
+    use anyhow::{bail, Context, Result};
+
+    pub fn muted() -> Result<bool> {
+        let out = std::process::Command::new("osascript")
+            .args(["-e", "input volume of (get volume settings)"])
+            .output()
+            .context("osascript failed")?;
+        if !out.status.success() {
+            bail!("osascript failed: {}", String::from_utf8_lossy(&out.stderr).trim());
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).trim() == "0")
+    }
+}
@@ -0,0 +1,225 @@
+// Wayland per-key shortcuts through the XDG GlobalShortcuts portal
+// (org.freedesktop.portal.GlobalShortcuts).
+//
+// tauri_plugin_global_shortcut grabs keys through X11, so on a Wayland session
+// it only fires while an XWayland window has focus. The portal is the
+// compositor-sanctioned way to get global shortcuts: KDE Plasma, GNOME 48+ and
+// Hyprland implement it. The compositor owns the binding, so the keystroke is
+// consumed and never replayed (same as the plugin path minus the replay).
+//
+// Backend order on Linux (see register_key_shortcuts in lib.rs):
+//   1. evdev reader (keyboard_hook) — works on every compositor, needs udev rules
+//   2. this portal — Wayland sessions whose compositor implements it
+//   3. tauri_plugin_global_shortcut — X11, or XWayland-focused windows only
+// wlroots compositors (sway, river…) have no GlobalShortcuts portal, so they
+// need the evdev backend for reliable per-key detection.
+
+use ashpd::desktop::global_shortcuts::{BindShortcutsOptions, GlobalShortcuts, NewShortcut};
+use ashpd::desktop::{CreateSessionOptions, Session};
+use futures_util::StreamExt;
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::async_runtime::{self, Receiver, Sender};
+use tauri::{AppHandle, Manager};
+
+use crate::state::SharedState;
+
+/// Shortcut ids are "deck8-key-<led index>".
+const ID_PREFIX: &str = "deck8-key-";
+
+/// Set once the portal session is up; until then lib.rs keeps using the plugin.
+static READY: AtomicBool = AtomicBool::new(false);
+
+/// Keymap updates for the binder task.
+static BINDINGS: OnceLock<Sender<[u16; 8]>> = OnceLock::new();
+
+/// True when running inside a Wayland session.
+pub fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland")
+}
+
+/// Whether per-key shortcuts can be bound through the portal.
+pub fn is_active() -> bool {
+    READY.load(Ordering::SeqCst)
+}
+
+/// Connect to the portal in the background. The D-Bus round trips can take a
+/// while (portal activation), so startup keeps the plugin shortcuts and swaps
+/// them for portal ones once the session is ready.
+pub fn init(app: &AppHandle) {
+    if !is_wayland() {
+        return;
+    }
+
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        let proxy = match GlobalShortcuts::new().await {
+            Ok(p) => p,
+            Err(e) => {
+                info!("[portal] GlobalShortcuts portal unavailable ({}), using plugin shortcuts", e);
+                return;
+            }
+        };
+        let activated = match proxy.receive_activated().await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("[portal] Failed to subscribe to Activated: {}", e);
+                return;
+            }
+        };
+        let deactivated = match proxy.receive_deactivated().await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("[portal] Failed to subscribe to Deactivated: {}", e);
+                return;
+            }
+        };
+
+        let (tx, rx) = async_runtime::channel::<[u16; 8]>(4);
+        if BINDINGS.set(tx).is_err() {
+            return;
+        }
+        READY.store(true, Ordering::SeqCst);
+        info!("[portal] GlobalShortcuts portal ready (v{})", proxy.version());
+
+        // Re-register with the portal unless the evdev reader took over meanwhile
+        let keymaps = {
+            let state = app.state::<SharedState>();
+            let st = state.lock().unwrap();
+            st.keymaps
+        };
+        if keymaps.iter().any(|&k| k != 0) {
+            crate::register_key_shortcuts(&app, &keymaps);
+        }
+
+        let listener_app = app.clone();
+        async_runtime::spawn(async move {
+            let mut activated = activated;
+            while let Some(event) = activated.next().await {
+                if let Some(led_idx) = led_index(event.shortcut_id()) {
+                    let keycode = listener_app.state::<SharedState>().lock().unwrap().keymaps[led_idx];
+                    if crate::replay::is_echo(keycode, std::time::Instant::now()) {
+                        continue;
+                    }
+                    info!("[portal] Activated {}", event.shortcut_id());
+                    crate::keyboard_hook::send_key_event(&listener_app, led_idx, true);
+                }
+            }
+            warn!("[portal] Activated stream ended");
+        });
+
+        let listener_app = app.clone();
+        async_runtime::spawn(async move {
+            let mut deactivated = deactivated;
+            while let Some(event) = deactivated.next().await {
+                if let Some(led_idx) = led_index(event.shortcut_id()) {
+                    crate::keyboard_hook::send_key_event(&listener_app, led_idx, false);
+                }
+            }
+            warn!("[portal] Deactivated stream ended");
+        });
+
+        run_binder(proxy, rx).await;
+    });
+}
+
+/// LED index from a "deck8-key-<led index>" shortcut id.
+fn led_index(shortcut_id: &str) -> Option<usize> {
+    shortcut_id.strip_prefix(ID_PREFIX)?.parse().ok()
+}
+
+/// Bind the per-key shortcuts through the portal. Returns false when the
+/// portal isn't available, so the caller falls back to the plugin.
+/// An all-zero keymap releases the portal session (evdev took over).
+pub fn register_shortcuts(keymaps: &[u16; 8]) -> bool {
+    let Some(tx) = BINDINGS.get() else { return false };
+    match tx.try_send(*keymaps) {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("[portal] Failed to queue shortcut update: {}", e);
+            false
+        }
+    }
+}
+
+/// Owns the portal session. Portals don't reliably support re-binding inside a
+/// session, so every keymap change closes the session and binds a fresh one.
+async fn run_binder(proxy: GlobalShortcuts, mut rx: Receiver<[u16; 8]>) {
+    let mut session: Option<Session<GlobalShortcuts>> = None;
+    let mut bound: Option<[u16; 8]> = None;
+
+    while let Some(keymaps) = rx.recv().await {
+        if bound == Some(keymaps) {
+            continue;
+        }
+
+        if let Some(old) = session.take() {
+            if let Err(e) = old.close().await {
+                warn!("[portal] Failed to close session: {}", e);
+            }
+        }
+        bound = Some(keymaps);
+
+        let shortcuts: Vec<NewShortcut> = keymaps
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &keycode)| {
+                let trigger = qmk_keycode_to_trigger(keycode)?;
+                let led_idx = crate::keymap_to_led_index(i);
+                Some(
+                    NewShortcut::new(format!("{}{}", ID_PREFIX, led_idx), format!("Deck-8 key {}", led_idx + 1))
+                        .preferred_trigger(trigger.as_str()),
+                )
+            })
+            .collect();
+        if shortcuts.is_empty() {
+            info!("[portal] No shortcuts to bind, session released");
+            continue;
+        }
+
+        let new_session = match proxy.create_session(CreateSessionOptions::default()).await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("[portal] Failed to create session: {}", e);
+                continue;
+            }
+        };
+        let result = proxy
+            .bind_shortcuts(&new_session, &shortcuts, None, BindShortcutsOptions::default())
+            .await
+            .and_then(|request| request.response());
+        match result {
+            Ok(response) => {
+                for s in response.shortcuts() {
+                    info!("[portal] {} → {}", s.id(), s.trigger_description());
+                }
+                info!("[portal] Bound {} of {} shortcuts", response.shortcuts().len(), shortcuts.len());
+            }
+            Err(e) => warn!("[portal] BindShortcuts failed: {}", e),
+        }
+        session = Some(new_session);
+    }
+}
+
+/// Convert a QMK keycode to the XDG shortcuts trigger format
+/// (e.g. "CTRL+ALT+m", "CTRL+SHIFT+ALT+LOGO+F13", "F15"). The compositor may assign a
+/// different trigger; the preferred one is only a suggestion.
+fn qmk_keycode_to_trigger(keycode: u16) -> Option<String> {
+    let mods = (keycode >> 8) as u8;
+    let basic = (keycode & 0xFF) as u8;
+    if !crate::keycodes::is_bindable(keycode, false) {
+        return None;
+    }
+
+    let key_name = crate::layout::xkb(crate::keycodes::lookup(basic)?)?;
+
+    let mut parts = Vec::new();
+    if mods & 0x11 != 0 { parts.push("CTRL"); }
+    if mods & 0x22 != 0 { parts.push("SHIFT"); }
+    if mods & 0x44 != 0 { parts.push("ALT"); }
+    if mods & 0x88 != 0 { parts.push("LOGO"); }
+    parts.push(&key_name);
+    Some(parts.join("+"))
+}